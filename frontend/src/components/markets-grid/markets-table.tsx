@@ -74,7 +74,9 @@ export const TimeframeSelector = ({
   onChange,
 }: TimeframeSelectorProps) => {
   const timeframes: { value: Timeframe; label: string }[] = [
+    { value: "5m", label: "5M" },
     { value: "1h", label: "1H" },
+    { value: "4h", label: "4H" },
     { value: "24h", label: "24H" },
     { value: "7d", label: "7D" },
     { value: "30d", label: "30D" },
@@ -1,7 +1,12 @@
 "use client";
 
 import { useCallback, useEffect, useRef, useState } from "react";
-import type { NewsItem, NewsSource } from "@/lib/types";
+import type {
+  MarketStats,
+  NewsItem,
+  NewsSource,
+  PredictionMarket,
+} from "@/lib/types";
 
 const WS_URL = process.env.NEXT_PUBLIC_WS_URL || "ws://localhost:3001/ws";
 
@@ -12,15 +17,37 @@ const WS_URL = process.env.NEXT_PUBLIC_WS_URL || "ws://localhost:3001/ws";
 export type Platform = "kalshi" | "polymarket";
 
 export interface SubscriptionType {
-  type: "price" | "order_book" | "trades" | "global_news" | "market_news";
+  type:
+    | "price"
+    | "order_book"
+    | "trades"
+    | "candles"
+    | "stats"
+    | "global_news"
+    | "market_news"
+    | "market_listings"
+    | "prices"
+    | "event"
+    | "alerts"
+    | "global_trades";
   platform?: Platform;
   market_id?: string;
+  /** Event or condition id, required for "event" subscriptions */
+  event_id?: string;
+  /** Candle interval, required for "candles" subscriptions */
+  interval?: "1m" | "5m" | "15m" | "1h" | "4h" | "1d";
+  /** Smallest price x size sent, for "global_trades" subscriptions */
+  min_notional?: string;
 }
 
 export interface ClientMessage {
-  type: "subscribe" | "unsubscribe" | "ping";
+  type: "subscribe" | "unsubscribe" | "ping" | "auth";
   subscription?: SubscriptionType;
+  /** Ask for this subscription's snapshots as zstd "compressed" messages */
+  compress?: boolean;
   timestamp?: number;
+  /** Access token, for "auth" (or pass ?token= in NEXT_PUBLIC_WS_URL) */
+  token?: string;
 }
 
 export interface PriceUpdate {
@@ -32,6 +59,18 @@ export interface PriceUpdate {
   timestamp: string;
 }
 
+export interface PriceTick {
+  type: "price_tick";
+  platform: Platform;
+  market_id: string;
+  best_bid: string;
+  best_ask: string;
+  mid: string;
+  timestamp: string;
+  /** Recent history sent on subscribe rather than a live update */
+  replay?: boolean;
+}
+
 export interface OrderBookLevel {
   price: string;
   quantity: string;
@@ -48,6 +87,8 @@ export interface OrderBookUpdate {
   no_bids: OrderBookLevel[];
   no_asks: OrderBookLevel[];
   timestamp: string;
+  /** Recent history sent on subscribe rather than a live update */
+  replay?: boolean;
 }
 
 export interface Trade {
@@ -66,6 +107,75 @@ export interface TradeUpdate {
   platform: Platform;
   market_id: string;
   trade: Trade;
+  /** Recent history sent on subscribe rather than a live update */
+  replay?: boolean;
+}
+
+export interface SnapshotMessage {
+  type: "snapshot";
+  platform: Platform;
+  market_id: string;
+  market?: PredictionMarket;
+  orderbook?: Omit<OrderBookUpdate, "type" | "update_type" | "replay">;
+  /** Latest stored trades, oldest first */
+  trades: Trade[];
+}
+
+export interface LiveCandle {
+  timestamp: string;
+  open: string;
+  high: string;
+  low: string;
+  close: string;
+  volume: string;
+  buy_volume: string;
+  sell_volume: string;
+}
+
+export interface CandleUpdate {
+  type: "candle_update";
+  platform: Platform;
+  market_id: string;
+  interval: "1m" | "5m" | "15m" | "1h" | "4h" | "1d";
+  candle: LiveCandle;
+  /** True once the bucket has ended and the candle is final */
+  closed: boolean;
+}
+
+export interface StatsUpdate {
+  type: "stats_update";
+  platform: Platform;
+  market_id: string;
+  /** 24h stats, pushed when new trades change them */
+  stats: MarketStats;
+}
+
+export interface EventUpdate {
+  type: "event_update";
+  platform: Platform;
+  event_id: string;
+  /** The outcome's own market id */
+  market_id: string;
+  outcome: string;
+  update: PriceUpdate | OrderBookUpdate | TradeUpdate;
+}
+
+export interface MarketListedMessage {
+  type: "market_listed";
+  platform: Platform;
+  market: PredictionMarket;
+}
+
+export type AlertKind = "volume_spike" | "large_print" | "price_jump";
+
+export interface MarketAlertMessage {
+  type: "market_alert";
+  platform: Platform;
+  market_id: string;
+  kind: AlertKind;
+  /** Multiple of the baseline, or price points moved for "price_jump" */
+  magnitude: number;
+  timestamp: string;
 }
 
 export interface SubscribedMessage {
@@ -82,6 +192,9 @@ export interface ErrorMessage {
   type: "error";
   code: string;
   message: string;
+  /** Limit hit, for subscription limit errors */
+  limit?: number;
+  count?: number;
 }
 
 export interface PongMessage {
@@ -94,6 +207,8 @@ export interface ConnectionStatusMessage {
   type: "connection_status";
   platform: Platform;
   status: "connected" | "connecting" | "disconnected" | "failed";
+  /** Feed restored after a drop: refetch snapshots, updates may be missing */
+  resync: boolean;
 }
 
 export interface MarketNewsContext {
@@ -107,16 +222,70 @@ export interface NewsUpdate {
   market_context: MarketNewsContext | null;
 }
 
+/** Sent first when connecting with ?session_id=; after a resume, the
+ * `replayed` messages missed while disconnected follow */
+export interface SessionMessage {
+  type: "session";
+  session_id: string;
+  resumed: boolean;
+  replayed: number;
+  dropped: number;
+}
+
+/** A trade on any tracked market, from the "global_trades" tape */
+export interface GlobalTradeMessage {
+  type: "global_trade";
+  trade: Trade;
+}
+
+/** Another message as zstd-compressed JSON in base64 (only if we asked) */
+export interface CompressedMessage {
+  type: "compressed";
+  encoding: "zstd_base64";
+  data: string;
+}
+
+/** Sent when the server requires a token and accepted ours */
+export interface AuthenticatedMessage {
+  type: "authenticated";
+  label: string;
+}
+
+/** Order submitted through the trading API (authenticated connections only) */
+export interface OrderUpdateMessage {
+  type: "order_update";
+  update: {
+    orderId?: string;
+    tokenId: string;
+    side: string;
+    price: number;
+    size: number;
+    transactionHashes: string[];
+  };
+}
+
 export type ServerMessage =
+  | SnapshotMessage
   | PriceUpdate
+  | PriceTick
   | OrderBookUpdate
   | TradeUpdate
+  | CandleUpdate
+  | StatsUpdate
+  | EventUpdate
+  | MarketListedMessage
+  | MarketAlertMessage
   | SubscribedMessage
   | UnsubscribedMessage
   | ErrorMessage
   | PongMessage
   | ConnectionStatusMessage
-  | NewsUpdate;
+  | NewsUpdate
+  | AuthenticatedMessage
+  | OrderUpdateMessage
+  | GlobalTradeMessage
+  | SessionMessage
+  | CompressedMessage;
 
 // ============================================================================
 // Connection State
@@ -262,16 +431,20 @@ export const useWebSocket = (
 
       ws.onmessage = (event) => {
         try {
-          const message = JSON.parse(event.data) as ServerMessage;
-
-          // Handle pong for latency measurement
-          if (message.type === "pong") {
-            const clientTimestamp = (message as PongMessage).client_timestamp;
-            setLatency(Date.now() - clientTimestamp);
+          // Batched frames carry an array of messages, in order
+          const parsed = JSON.parse(event.data) as ServerMessage | ServerMessage[];
+          const messages = Array.isArray(parsed) ? parsed : [parsed];
+
+          for (const message of messages) {
+            // Handle pong for latency measurement
+            if (message.type === "pong") {
+              const clientTimestamp = (message as PongMessage).client_timestamp;
+              setLatency(Date.now() - clientTimestamp);
+            }
+
+            // Notify all handlers
+            messageHandlersRef.current.forEach((handler) => handler(message));
           }
-
-          // Notify all handlers
-          messageHandlersRef.current.forEach((handler) => handler(message));
         } catch (e) {
           console.error("Failed to parse WebSocket message:", e);
         }
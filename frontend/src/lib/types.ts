@@ -14,6 +14,7 @@ export interface PredictionMarket {
   volume: string;
   volume_24hr: string | null; // 24h volume directly from platform API
   liquidity: string | null;
+  open_interest?: string; // Kalshi only
   close_time: string | null; // ISO datetime
   created_at: string | null; // ISO datetime
   status: MarketStatus;
@@ -23,6 +24,7 @@ export interface PredictionMarket {
   outcome_count: number | null;
   leading_outcome: string | null;
   is_multi_outcome: boolean;
+  event_id?: string; // Parent event (Polymarket); outcome markets share it
   options_json: string | null; // JSON array of MarketOption
   resolution_source: string | null; // How the market will be resolved
   // Sports-specific fields
@@ -36,6 +38,9 @@ export interface PredictionMarket {
   away_odds: string | null;
   spread_line: string | null;
   total_line: string | null;
+  // YES price movement, computed by the cache on refresh (null without prior data)
+  change_1h: string | null;
+  change_24h: string | null;
   // Tags for categorization (e.g., "Politics", "Crypto", "AI")
   tags: string[];
   // Trading fields (Polymarket)
@@ -55,6 +60,76 @@ export interface MarketOption {
 export interface MarketsResponse {
   markets: PredictionMarket[];
   count: number;
+  total_count: number;
+  /** Pass as `cursor` for the next page; absent on the last page */
+  next_cursor?: string;
+  /** With `group=events`: markets grouped by parent event (`markets` is empty) */
+  groups?: MarketGroup[];
+}
+
+/** Body/response of PUT /api/markets/:platform/:id/override */
+export interface MarketOverride {
+  display_title?: string | null;
+  pinned?: boolean;
+  hidden?: boolean;
+  notes?: string | null;
+}
+
+/** A market on a watchlist */
+export interface WatchlistEntry {
+  platform: Platform;
+  market_id: string;
+  added_at: string;
+  market: PredictionMarket | null; // Archived copy when unavailable
+  unavailable: boolean; // Left the cache; show as closed
+}
+
+/** Response of GET /api/watchlists/:id */
+export interface WatchlistResponse {
+  id: string;
+  markets: WatchlistEntry[];
+  count: number;
+}
+
+/** Result of POST /api/markets/refresh/:platform */
+export interface PlatformRefreshResponse {
+  platform: Platform;
+  last_refresh_at: string | null;
+  last_error: string | null;
+  consecutive_failures: number;
+  backoff_until: string | null; // Unforced refreshes skipped until then
+}
+
+/** Outcome markets of one event */
+export interface MarketGroup {
+  group_id: string;
+  platform: Platform;
+  title: string;
+  volume: string; // Sum of child volumes
+  markets: PredictionMarket[];
+}
+
+/** A market matched by full-text search */
+export interface MarketSearchResult extends PredictionMarket {
+  /** Matching excerpt with hits wrapped in [ ] */
+  snippet: string | null;
+  /** bm25 rank; lower is a better match */
+  score: number;
+}
+
+export interface SearchMarketsResponse {
+  query: string;
+  results: MarketSearchResult[];
+  count: number;
+}
+
+export interface CategoryCount {
+  name: string;
+  count: number;
+}
+
+export interface CategoriesResponse {
+  categories: CategoryCount[];
 }
 
 export interface UnifiedMarket {
@@ -66,6 +141,16 @@ export interface UnifiedMarket {
   spread: string | null;
 }
 
+export interface UnifiedMatch extends UnifiedMarket {
+  confidence: number;
+  manual: boolean;
+}
+
+export interface UnifiedMarketsResponse {
+  markets: UnifiedMatch[];
+  count: number;
+}
+
 // Market filter options (matches backend MarketFilter enum)
 export type MarketFilter =
   | "all"
@@ -82,6 +167,9 @@ export interface ListMarketsParams {
   search?: string;
   filter?: MarketFilter;
   limit?: number;
+  cursor?: string;
+  all?: boolean;
+  group?: "events";
   sort?: "volume" | "expiring_soon" | "newest";
 }
 
@@ -104,6 +192,10 @@ export interface OrderBook {
   no_bids: OrderBookLevel[];
   no_asks: OrderBookLevel[];
   sequence: number | null;
+  /** Market order book endpoint only: the aggregator's streamed book or a REST fetch */
+  source?: "cache" | "rest";
+  /** Market order book endpoint only: when the book last changed */
+  updated_at?: string;
 }
 
 // ============================================================================
@@ -125,6 +217,9 @@ export interface Trade {
   side: string | null; // TradeSide - accepts any case
   outcome_name?: string; // For multi-outcome event trades - which sub-market this trade belongs to
   transaction_hash?: string; // For on-chain trades (Polymarket on Polygon)
+  outcome_id?: string; // Outcome/token ID for multi-outcome markets
+  maker_address?: string; // Maker wallet (Polymarket)
+  taker_address?: string; // Taker wallet (Polymarket)
 }
 
 export interface TradeHistory {
@@ -134,6 +229,43 @@ export interface TradeHistory {
   next_cursor: string | null;
 }
 
+/** Final outcome of a resolved market */
+export interface MarketResolution {
+  platform: Platform;
+  market_id: string;
+  resolved_at: string;
+  /** "yes"/"no", or the option name for multi-outcome markets */
+  winning_outcome: string | null;
+  final_price: number | null;
+}
+
+/** Large trade from the cross-market whale feed */
+export interface WhaleTrade extends Trade {
+  /** price * quantity */
+  notional: number;
+}
+
+export interface WhaleTradesResponse {
+  trades: WhaleTrade[];
+  count: number;
+}
+
+/** Unusual activity raised on the exchange feeds */
+export interface MarketAlert {
+  platform: Platform;
+  market_id: string;
+  kind: "volume_spike" | "large_print" | "price_jump";
+  /** Multiple of the baseline, or price points moved for "price_jump" */
+  magnitude: number;
+  timestamp: string;
+}
+
+export interface RecentAlertsResponse {
+  /** Newest first */
+  alerts: MarketAlert[];
+  count: number;
+}
+
 // ============================================================================
 // Price History Types (Candlestick data)
 // ============================================================================
@@ -160,6 +292,43 @@ export interface PriceHistory {
   candles: PriceCandle[];
 }
 
+/** Candle as persisted in trade storage (unix-second timestamp, float prices) */
+export interface StoredCandle {
+  timestamp: number;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  volume: number;
+  trade_count: number;
+}
+
+/** Several markets' stored candles aligned to one time axis */
+export interface CandleCompareResponse {
+  interval: PriceInterval;
+  /** Shared bucket timestamps (unix seconds, ascending) */
+  timestamps: number[];
+  /** market_id -> one entry per timestamp (null where the market has no candle) */
+  series: Record<string, (StoredCandle | null)[]>;
+}
+
+/** Period stats over a time range (GET /markets/:platform/:id/summary) */
+export interface RangeSummaryResponse {
+  market_id: string;
+  platform: Platform;
+  from: number;
+  to: number;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  volume: number;
+  trade_count: number;
+  /** Largest peak-to-trough decline in probability (0-1 price units) */
+  max_drawdown: number;
+  source: "trades" | "candles";
+}
+
 // ============================================================================
 // Multi-Outcome Price History Types (from Polymarket CLOB API)
 // ============================================================================
@@ -183,7 +352,7 @@ export interface OutcomePriceHistory {
 // ============================================================================
 
 /** Available timeframes for market stats */
-export type Timeframe = "1h" | "24h" | "7d" | "30d";
+export type Timeframe = "5m" | "1h" | "4h" | "24h" | "7d" | "30d";
 
 /** Market statistics for a specific timeframe */
 export interface MarketStats {
@@ -201,8 +370,85 @@ export interface MarketStats {
   yes_txn_count: number;
   /** Number of NO trades in the timeframe */
   no_txn_count: number;
+  /** Volume-weighted average price (null without trades) */
+  vwap: string | null;
+  /** Time-weighted average price, mean of per-minute closes (null without trades) */
+  twap: string | null;
+  /** Buy/sell order flow in the timeframe */
+  flow: TradeFlow;
+  /** Buy trades / sided trades (null when over half the trades lack a side) */
+  buy_ratio: string | null;
+  /** Buy notional / sided notional */
+  notional_buy_ratio: string | null;
+  /** Buy notional minus sell notional */
+  net_delta: string | null;
+  /** Fraction of trades reporting a side (null without trades) */
+  side_coverage: string | null;
+  /** Distinct wallets trading in the timeframe (null when unknown, e.g. Kalshi) */
+  unique_traders: number | null;
+  /** Realized volatility of hourly log-returns over the last 24h */
+  volatility_24h: string | null;
+  /** YES spread from the latest order book (null without a snapshot) */
+  spread: string | null;
+  best_bid: string | null;
+  best_ask: string | null;
+  /** Notional resting on each side of the YES book */
+  bid_depth_usd: string | null;
+  ask_depth_usd: string | null;
+  /** True when the latest order book snapshot is over 5 minutes old */
+  liquidity_stale: boolean;
+  /** Consecutive rising/falling 1h closes ending at the latest candle (null without candles) */
+  consecutive_up_candles: number | null;
+  consecutive_down_candles: number | null;
+  /** Latest hourly % change minus the one before it */
+  pct_change_acceleration: string | null;
+  /** All-time YES high/low from stored snapshots and candles (null before any) */
+  ath_price: string | null;
+  atl_price: string | null;
+  ath_timestamp: string | null;
+  atl_timestamp: string | null;
+  /** Percent from the all-time high to the current price (e.g. "-25") */
+  pct_from_ath: string | null;
   /** The timeframe these stats cover */
   timeframe: Timeframe;
+  /** Outcome these stats are scoped to (multi-outcome markets only) */
+  outcome_id?: string;
+  /** Windowed volume rank within the market's category (single-market stats only) */
+  volume_percentile: VolumePercentile | null;
+  /** Contracts open as of the latest snapshot (Kalshi only) */
+  open_interest: string | null;
+  /** Open interest change over the last 24 hours (Kalshi only) */
+  oi_change_24h: string | null;
+  /** Freshness of the data behind these stats (null from /api/stats/bulk) */
+  data_quality: DataQuality | null;
+}
+
+export interface VolumePercentile {
+  category: string;
+  /** 0 (lowest volume) to 100 (highest); ties share their middle rank */
+  percentile: string;
+  category_size: number;
+}
+
+/** How much of a stats window is backed by stored data */
+export type StatsCoverage = "full" | "partial" | "none";
+
+export interface DataQuality {
+  last_trade_at: string | null;
+  last_snapshot_at: string | null;
+  trade_count_in_window: number;
+  /** full: trades in the window and a fresh snapshot; none: nothing stored */
+  coverage: StatsCoverage;
+}
+
+/** Buy/sell order flow (notional volumes) */
+export interface TradeFlow {
+  buy_volume: number;
+  sell_volume: number;
+  buy_count: number;
+  sell_count: number;
+  /** buy_volume - sell_volume */
+  net_flow: number;
 }
 
 /** Response from /api/markets/stats endpoint */
@@ -216,6 +462,112 @@ export interface MarketStatsResponse {
   count: number;
 }
 
+/** Stats for one outcome of a multi-outcome market */
+export interface OutcomeStats extends MarketStats {
+  outcome_label: string | null;
+}
+
+/** Response of GET /api/markets/:platform/:id/stats/outcomes */
+export interface OutcomeStatsResponse {
+  market_id: string;
+  timeframe: string;
+  /** Every known outcome, highest priced first */
+  outcomes: OutcomeStats[];
+  count: number;
+}
+
+/** Response of GET /api/markets/unified/:id/stats */
+export interface UnifiedStats {
+  unified_id: string;
+  timeframe: Timeframe;
+  kalshi: MarketStats | null;
+  polymarket: MarketStats | null;
+  /** Kalshi YES minus Polymarket YES (null unless both legs exist) */
+  price_spread: string | null;
+  /** Absolute spread exceeds the server's arbitrage threshold */
+  arbitrage_candidate: boolean;
+  /** A leg is missing or has no trades in the timeframe */
+  partial: boolean;
+}
+
+/** One bucket of a market's stats history */
+export interface StatsHistoryEntry {
+  /** Bucket start (ISO timestamp) */
+  timestamp: string;
+  volume: string;
+  txn_count: number;
+  /** Last YES price in the bucket, carried forward through empty buckets */
+  close: string | null;
+}
+
+/** Response of GET /api/markets/:platform/:id/stats/history */
+export interface StatsHistoryResponse {
+  market_id: string;
+  platform: Platform;
+  bucket: string;
+  /** Every bucket in the range, oldest first */
+  history: StatsHistoryEntry[];
+}
+
+/** Estimated fill of buying a USD notional of YES against the resting asks */
+export interface MarketImpact {
+  notional: string;
+  /** Fill fields are null when the book snapshot is stale or too thin */
+  contracts: string | null;
+  avg_fill_price: string | null;
+  /** Highest ask level reached */
+  worst_price: string | null;
+  /** Average fill minus the best ask */
+  slippage: string | null;
+  slippage_percent: string | null;
+}
+
+/** Response of GET /api/markets/:platform/:id/impact */
+export interface MarketImpactResponse {
+  market_id: string;
+  platform: Platform;
+  /** One estimate per requested notional, in request order */
+  impacts: MarketImpact[];
+}
+
+/** Trades of one notional size bin */
+export interface TradeSizeBucket {
+  min_notional: string;
+  /** Null for the open-ended top bin */
+  max_notional: string | null;
+  count: number;
+  total_notional: string;
+}
+
+/** Response of GET /api/markets/:platform/:id/trade-sizes */
+export interface TradeSizesResponse {
+  market_id: string;
+  platform: Platform;
+  timeframe: Timeframe;
+  /** One entry per bin, smallest first; empty bins are zeroed */
+  buckets: TradeSizeBucket[];
+}
+
+/** Response of POST /api/stats/bulk */
+export interface BulkStatsResponse {
+  /** Stats by market id; markets without trades are zeroed */
+  stats: Record<string, MarketStats>;
+  timeframe: string;
+  count: number;
+}
+
+/** Leaderboard ranking metric */
+export type LeaderboardMetric = "volume" | "abs_price_change" | "txn_count";
+
+/** Response of GET /api/stats/leaderboard */
+export interface LeaderboardResponse {
+  metric: LeaderboardMetric;
+  timeframe: string;
+  /** Ranked highest first, ties by market id */
+  markets: MarketStats[];
+  count: number;
+}
+
 /** Query params for fetching market stats */
 export interface MarketStatsParams {
   timeframe?: Timeframe;
@@ -186,21 +186,25 @@ export const WebSocketProvider = ({
 
       ws.onmessage = (event) => {
         try {
-          const message = JSON.parse(event.data) as ServerMessage;
+          // Batched frames carry an array of messages, in order
+          const parsed = JSON.parse(event.data) as ServerMessage | ServerMessage[];
+          const messages = Array.isArray(parsed) ? parsed : [parsed];
+
+          for (const message of messages) {
+            // Handle pong for latency measurement
+            if (message.type === "pong" && "client_timestamp" in message) {
+              setLatency(Date.now() - message.client_timestamp);
+            }
 
-          // Handle pong for latency measurement
-          if (message.type === "pong" && "client_timestamp" in message) {
-            setLatency(Date.now() - message.client_timestamp);
+            // Notify all registered handlers
+            messageHandlersRef.current.forEach((handler) => {
+              try {
+                handler(message);
+              } catch (e) {
+                console.error("[WebSocket] Handler error:", e);
+              }
+            });
           }
-
-          // Notify all registered handlers
-          messageHandlersRef.current.forEach((handler) => {
-            try {
-              handler(message);
-            } catch (e) {
-              console.error("[WebSocket] Handler error:", e);
-            }
-          });
         } catch (e) {
           console.error("[WebSocket] Failed to parse message:", e);
         }
@@ -10,13 +10,13 @@ use axum::{
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
-use terminal_kalshi::KalshiClient;
-use terminal_polymarket::PolymarketClient;
+use terminal_kalshi::{KalshiClient, KalshiWebSocketConfig};
+use terminal_polymarket::{PolymarketClient, PolymarketWebSocketConfig};
 use terminal_services::{
-    AggregatorConfig, CandleService, DiscordAggregator, MarketCache, MarketDataAggregator,
+    AggregatorConfig, AnomalyConfig, CanaryConfig, CanaryService, CandleCacheConfig, ClientHeartbeat, MarketSnapshotter, OutboundBatching, SessionConfig, SubscriptionLimits, TickerTapeConfig, CandleFinalizer, CandleService, CandleUpdater, CoalesceConfig, DailyCandleConfig, DailyCandleRefresher, DiscordAggregator, EmbeddingStore, MarketCache, MarketDataAggregator,
     MarketService, MarketStatsService, NewsAggregator, NewsAggregatorConfig, NewsAnalyzer,
-    NewsCache, RateLimiter, ResearchService, TradeCollector, TradeCollectorConfig, TradeStorage,
-    WebSocketState,
+    NewsCache, PageRequest, RateLimiter, RefreshLoopConfig, ResearchService, SnapshotEncoding, TradeCollector, TradeCollectorConfig,
+    TradeStorage, TradeStorageConfig, WebSocketState, WsAuth,
 };
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
@@ -41,6 +41,12 @@ pub struct AppState {
     pub research_service: Option<Arc<ResearchService>>,
     /// Trading state (optional - requires TRADING_PRIVATE_KEY)
     pub trading_state: Option<routes::SharedTradingState>,
+    /// Rate limiter for public embed endpoints (separate from the main API)
+    pub embed_rate_limiter: Arc<RateLimiter>,
+    /// Pipeline self-test with a synthetic canary market
+    pub canary_service: Arc<CanaryService>,
+    /// Background job precomputing 1d candles from platform price history
+    pub daily_candle_refresher: Arc<DailyCandleRefresher>,
 }
 
 #[tokio::main]
@@ -96,19 +102,19 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    // Refresh markets in background on startup
-    let cache_for_refresh = Arc::clone(&market_cache);
-    tokio::spawn(async move {
-        info!("Starting initial market cache refresh...");
-        if let Err(e) = cache_for_refresh.refresh_all().await {
-            tracing::error!("Failed to refresh market cache on startup: {}", e);
-        } else {
-            let stats = cache_for_refresh.stats();
-            info!(
-                "Market cache refreshed: {} total ({} Kalshi, {} Polymarket)",
-                stats.total, stats.kalshi_count, stats.polymarket_count
-            );
-        }
+    // Refresh markets now and then periodically, per platform (0 disables)
+    let refresh_interval = |var: &str, default: Option<std::time::Duration>| match std::env::var(var) {
+        Ok(secs) => secs.parse::<u64>().ok().filter(|s| *s > 0).map(std::time::Duration::from_secs),
+        Err(_) => default,
+    };
+    if let Some(days) = std::env::var("MARKET_ARCHIVE_AFTER_DAYS").ok().and_then(|s| s.parse().ok()) {
+        market_cache.set_archive_after_days(days);
+    }
+    let refresh_defaults = RefreshLoopConfig::default();
+    market_cache.start_refresh_loop(RefreshLoopConfig {
+        polymarket_interval: refresh_interval("MARKET_REFRESH_POLYMARKET_SECS", refresh_defaults.polymarket_interval),
+        kalshi_interval: refresh_interval("MARKET_REFRESH_KALSHI_SECS", refresh_defaults.kalshi_interval),
+        ..refresh_defaults
     });
 
     // Create subscription event channel for aggregator integration
@@ -121,12 +127,78 @@ async fn main() -> anyhow::Result<()> {
     let mut ws_state = WebSocketState::new(market_service.clone());
     ws_state.set_subscription_event_sender(subscription_tx);
     ws_state.set_trade_subscription_sender(trade_subscription_tx);
+    ws_state.set_refresh_request_sender(market_cache.refresh_sender());
+    let heartbeat_secs = |var: &str, default: std::time::Duration| {
+        std::env::var(var)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(default)
+    };
+    let subscription_cap = |var: &str, default: Option<usize>| match std::env::var(var).ok().and_then(|s| s.parse::<usize>().ok()) {
+        Some(0) => None,
+        Some(max) => Some(max),
+        None => default,
+    };
+    ws_state.set_subscription_limits(SubscriptionLimits {
+        per_client: subscription_cap("WS_MAX_SUBSCRIPTIONS_PER_CLIENT", SubscriptionLimits::default().per_client),
+        total: subscription_cap("WS_MAX_SUBSCRIPTIONS_TOTAL", SubscriptionLimits::default().total),
+    });
+    ws_state.set_batching(OutboundBatching {
+        flush_interval: std::env::var("WS_FLUSH_INTERVAL_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(OutboundBatching::default().flush_interval),
+        max_queued: std::env::var("WS_MAX_QUEUED_MESSAGES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(OutboundBatching::default().max_queued),
+    });
+    if let Some(auth) = std::env::var("WS_AUTH_TOKEN").ok().and_then(|spec| WsAuth::from_spec(&spec)) {
+        let auth = match std::env::var("WS_AUTH_TIMEOUT_SECS").ok().and_then(|s| s.parse().ok()) {
+            Some(secs) => auth.with_timeout(std::time::Duration::from_secs(secs)),
+            None => auth,
+        };
+        info!("WebSocket token authentication enabled: {:?}", auth);
+        ws_state.set_auth(auth);
+    }
+    ws_state.set_ticker_tape(TickerTapeConfig {
+        max_per_sec: std::env::var("WS_TAPE_MAX_TRADES_PER_SEC")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(TickerTapeConfig::default().max_per_sec),
+        ..TickerTapeConfig::default()
+    });
+    ws_state.set_sessions(SessionConfig {
+        ttl: heartbeat_secs("WS_SESSION_TTL_SECS", SessionConfig::default().ttl),
+        max_buffered: std::env::var("WS_SESSION_MAX_BUFFERED")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(SessionConfig::default().max_buffered),
+        ..SessionConfig::default()
+    });
+    ws_state.set_heartbeat(ClientHeartbeat {
+        ping_interval: heartbeat_secs("WS_CLIENT_PING_INTERVAL_SECS", ClientHeartbeat::default().ping_interval),
+        idle_timeout: heartbeat_secs("WS_CLIENT_IDLE_TIMEOUT_SECS", ClientHeartbeat::default().idle_timeout),
+    });
     let ws_state = Arc::new(ws_state);
+    market_cache.set_websocket_state(ws_state.clone());
+    ws_state.forward_market_listings(market_cache.subscribe_events());
+    ws_state.start_ticker_tape();
+    ws_state.start_sessions();
 
     // Initialize trade storage (SQLite database)
     let db_path = std::env::var("TRADES_DB_PATH").unwrap_or_else(|_| "data/trades.db".to_string());
     info!("Initializing trade storage at: {}", db_path);
-    let trade_storage = match TradeStorage::new(&db_path) {
+    let storage_config = TradeStorageConfig {
+        snapshot_encoding: std::env::var("SNAPSHOT_ENCODING")
+            .ok()
+            .and_then(|s| SnapshotEncoding::from_str(&s))
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let trade_storage = match TradeStorage::with_config(&db_path, storage_config) {
         Ok(storage) => Arc::new(storage),
         Err(e) => {
             tracing::error!("Failed to initialize trade storage at '{}': {}", db_path, e);
@@ -138,11 +210,45 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // Record market resolutions observed by the cache refresh
+    market_cache.set_trade_storage(trade_storage.clone());
+
+    // Stored market embeddings sharpen cross-platform matching when present
+    match EmbeddingStore::new("data/embeddings.db") {
+        Ok(store) => market_cache.set_embedding_store(Arc::new(store)),
+        Err(e) => info!("Matching markets without embeddings: {}", e),
+    }
+
     // Initialize candle service
-    let candle_service = Arc::new(CandleService::new(trade_storage.clone()));
+    let candle_cache_config = CandleCacheConfig {
+        ttl: std::env::var("CANDLE_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(CandleCacheConfig::default().ttl),
+        ..Default::default()
+    };
+    let candle_service = Arc::new(CandleService::with_cache_config(
+        trade_storage.clone(),
+        candle_cache_config,
+    ));
 
     // Initialize market stats service
-    let market_stats_service = Arc::new(MarketStatsService::new(trade_storage.clone()));
+    let mut market_stats_service = MarketStatsService::new(trade_storage.clone(), Some(candle_service.clone()));
+    market_stats_service.set_market_cache(market_cache.clone());
+    if let Some(threshold) = std::env::var("ARBITRAGE_SPREAD_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse::<rust_decimal::Decimal>().ok())
+    {
+        market_stats_service.set_arbitrage_threshold(threshold);
+    }
+    if let Some(secs) = std::env::var("STATS_STALE_AFTER_SECS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+    {
+        market_stats_service.set_stale_after(chrono::Duration::seconds(secs));
+    }
+    let market_stats_service = Arc::new(market_stats_service);
 
     // Initialize trade collector
     // KALSHI_DISABLED: Disable Kalshi trade collection while focusing on Polymarket
@@ -151,12 +257,18 @@ async fn main() -> anyhow::Result<()> {
         collect_polymarket: true,
         ..TradeCollectorConfig::default()
     };
-    let trade_collector = Arc::new(TradeCollector::new(
+    let mut trade_collector = TradeCollector::new(
         market_service_arc.clone(),
         trade_storage.clone(),
         Some(ws_state.clone()),
         trade_collector_config,
-    ));
+    );
+    // Share the candle service so post-backfill rebuilds invalidate its read cache
+    trade_collector.set_candle_service(candle_service.clone());
+    // Newly stored trades drop the affected market's cached stats
+    trade_collector.set_stats_invalidator(market_stats_service.spawn_invalidation_listener());
+    let trade_collector = Arc::new(trade_collector);
+    market_cache.set_trade_collector(trade_collector.clone());
 
     // Start trade collector in background
     let collector_handle = trade_collector.clone();
@@ -164,6 +276,53 @@ async fn main() -> anyhow::Result<()> {
         collector_handle.start().await;
     });
 
+    // Trade retention (opt-in): prune trades older than TRADES_RETENTION_DAYS once a day,
+    // keeping markets still tracked by the collector. TRADES_VACUUM=true also shrinks the file.
+    if let Some(retention_days) = std::env::var("TRADES_RETENTION_DAYS")
+        .ok()
+        .and_then(|d| d.parse::<u64>().ok())
+    {
+        let vacuum = std::env::var("TRADES_VACUUM")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        info!(
+            "Trade retention enabled: {} days (vacuum: {})",
+            retention_days, vacuum
+        );
+
+        let retention_storage = trade_storage.clone();
+        let retention_collector = trade_collector.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(86400));
+            loop {
+                ticker.tick().await;
+                let keep = retention_collector.tracked_markets().await;
+                let storage = retention_storage.clone();
+                // Pruning and VACUUM hold the writer for minutes on a big file;
+                // keep them off the async workers
+                let pruned = tokio::task::spawn_blocking(move || {
+                    match storage.prune_trades(retention_days, Some(&keep)) {
+                        Ok(deleted) => {
+                            if deleted > 0 {
+                                info!("Pruned {} trades older than {} days", deleted, retention_days);
+                                if vacuum {
+                                    if let Err(e) = storage.vacuum() {
+                                        tracing::warn!("Failed to vacuum trade storage: {}", e);
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => tracing::warn!("Failed to prune trades: {}", e),
+                    }
+                })
+                .await;
+                if let Err(e) = pruned {
+                    tracing::warn!("Trade retention task failed: {}", e);
+                }
+            }
+        });
+    }
+
     // Auto-track top markets for trade collection (after cache populates)
     // This ensures we have transaction count data for the most popular markets
     let auto_track_collector = trade_collector.clone();
@@ -172,7 +331,9 @@ async fn main() -> anyhow::Result<()> {
         // Wait for cache to populate (check every 2 seconds, up to 30 seconds)
         for _ in 0..15 {
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-            let markets = auto_track_cache.get_markets(Some(terminal_core::Platform::Polymarket));
+            let markets = auto_track_cache
+                .get_markets(Some(terminal_core::Platform::Polymarket), PageRequest::all())
+                .markets;
             if !markets.is_empty() {
                 // Track top 50 markets by volume for trade collection
                 let top_n = 50;
@@ -188,10 +349,82 @@ async fn main() -> anyhow::Result<()> {
     });
 
     // Initialize and start market data aggregator
-    // KALSHI_DISABLED: Disable Kalshi WebSocket while focusing on Polymarket
+    // Kalshi's feed is opt-in: KALSHI_WS_ENABLED=true plus KALSHI_API_KEY and
+    // KALSHI_PRIVATE_KEY_FILE (or KALSHI_PRIVATE_KEY) for the signed handshake
     let aggregator_config = AggregatorConfig {
-        kalshi_enabled: false,
+        kalshi_enabled: std::env::var("KALSHI_WS_ENABLED")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false),
         polymarket_enabled: true,
+        kalshi_ws: KalshiWebSocketConfig::default(),
+        // Backup endpoints are tried in order after repeated connect failures
+        polymarket_ws: {
+            let mut config = PolymarketWebSocketConfig::default();
+            if let Ok(urls) = std::env::var("POLYMARKET_WS_BACKUP_URLS") {
+                config.endpoints.extend(
+                    urls.split(',')
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty())
+                        .map(String::from),
+                );
+            }
+            config
+        },
+        // Parse every exchange message type, for debugging the feeds
+        filter_exchange_messages: std::env::var("EXCHANGE_MESSAGE_FILTER")
+            .map(|v| v != "false" && v != "0")
+            .unwrap_or(true),
+        max_forced_reconnects: std::env::var("WATCHDOG_MAX_FORCED_RECONNECTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(AggregatorConfig::default().max_forced_reconnects),
+        coalesce: match std::env::var("BOOK_FLUSH_INTERVAL_MS").ok().and_then(|s| s.parse::<u64>().ok()) {
+            Some(0) => CoalesceConfig::passthrough(),
+            Some(ms) => CoalesceConfig {
+                orderbook_interval: Some(std::time::Duration::from_millis(ms)),
+                price_interval: Some(std::time::Duration::from_millis(ms)),
+                ..CoalesceConfig::default()
+            },
+            None => CoalesceConfig::default(),
+        },
+        // 0 lifts the cap
+        max_exchange_subscriptions: match std::env::var("MAX_EXCHANGE_SUBSCRIPTIONS").ok().and_then(|s| s.parse::<usize>().ok()) {
+            Some(0) => None,
+            Some(max) => Some(max),
+            None => AggregatorConfig::default().max_exchange_subscriptions,
+        },
+        persist_ws_trades: std::env::var("PERSIST_WS_TRADES")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false),
+        snapshot_interval: std::env::var("ORDERBOOK_SNAPSHOT_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(AggregatorConfig::default().snapshot_interval),
+        mid_tick_epsilon: std::env::var("MID_TICK_EPSILON")
+            .ok()
+            .and_then(|s| s.parse::<rust_decimal::Decimal>().ok())
+            .unwrap_or(AggregatorConfig::default().mid_tick_epsilon),
+        anomaly: {
+            let defaults = AnomalyConfig::default();
+            AnomalyConfig {
+                large_print_multiple: std::env::var("ALERT_LARGE_PRINT_MULTIPLE")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(defaults.large_print_multiple),
+                volume_spike_multiple: std::env::var("ALERT_VOLUME_SPIKE_MULTIPLE")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(defaults.volume_spike_multiple),
+                price_jump: std::env::var("ALERT_PRICE_JUMP")
+                    .ok()
+                    .and_then(|s| s.parse::<rust_decimal::Decimal>().ok())
+                    .unwrap_or(defaults.price_jump),
+                ..defaults
+            }
+        },
+        ..AggregatorConfig::default()
     };
     let mut aggregator = MarketDataAggregator::new(
         aggregator_config,
@@ -201,6 +434,10 @@ async fn main() -> anyhow::Result<()> {
 
     // Set trade storage for orderbook snapshot persistence
     aggregator.set_trade_storage(trade_storage.clone());
+    // Markets the collector tracks keep getting book snapshots without a viewer
+    aggregator.set_trade_collector(trade_collector.clone());
+    // Markets over the subscription cap are polled through the market cache
+    aggregator.set_refresh_sender(market_cache.refresh_sender());
 
     // Start aggregator (connects to exchange WebSockets)
     if let Err(e) = aggregator.start().await {
@@ -212,10 +449,78 @@ async fn main() -> anyhow::Result<()> {
     // Spawn a task to process subscription events from frontend clients
     let aggregator = Arc::new(aggregator);
     let aggregator_for_events = Arc::clone(&aggregator);
+
+    // New market subscribers get the cached market, current book and latest trades first
+    ws_state.set_snapshot_source(Arc::new(
+        MarketSnapshotter::new(market_cache.clone(), Arc::clone(&aggregator)).with_trade_storage(trade_storage.clone()),
+    ));
     tokio::spawn(async move {
         aggregator_for_events.process_subscription_events(subscription_rx).await;
     });
 
+    // Stream live in-progress candles to clients subscribed to the candles channel
+    let candle_updater = Arc::new(CandleUpdater::new(ws_state.clone()));
+    tokio::spawn(async move {
+        candle_updater.start().await;
+    });
+
+    // Push recomputed stats to clients subscribed to the stats channel
+    let stats_push_interval = std::time::Duration::from_secs(
+        std::env::var("STATS_PUSH_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5),
+    );
+    let stats_updates = market_stats_service.clone();
+    let stats_ws_state = ws_state.clone();
+    tokio::spawn(async move {
+        stats_updates.start_stats_updates(stats_ws_state, stats_push_interval).await;
+    });
+
+    // Persist 1m/5m/1h candles as their buckets close
+    let candle_finalizer = Arc::new(CandleFinalizer::new(candle_service.clone()));
+    let finalizer_ws_state = ws_state.clone();
+    tokio::spawn(async move {
+        candle_finalizer.start(finalizer_ws_state).await;
+    });
+
+    // Start the pipeline canary (synthetic market exercising storage -> candles -> stats -> WS)
+    let canary_service = Arc::new(
+        CanaryService::new(
+            trade_storage.clone(),
+            trade_collector.clone(),
+            candle_service.clone(),
+            market_stats_service.clone(),
+            Some(ws_state.clone()),
+            CanaryConfig::default(),
+        )
+        .with_aggregator(Arc::clone(&aggregator)),
+    );
+    let canary_handle = canary_service.clone();
+    tokio::spawn(async move {
+        canary_handle.start().await;
+    });
+
+    // Precompute 1d candles from platform price history so all-time charts are served locally
+    let daily_candle_config = DailyCandleConfig {
+        request_delay: std::env::var("DAILY_CANDLES_DELAY_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(DailyCandleConfig::default().request_delay),
+        ..Default::default()
+    };
+    let daily_candle_refresher = Arc::new(DailyCandleRefresher::new(
+        candle_service.clone(),
+        market_service_arc.clone(),
+        daily_candle_config,
+    ));
+    let daily_candle_handle = daily_candle_refresher.clone();
+    let daily_candle_cache = market_cache.clone();
+    tokio::spawn(async move {
+        daily_candle_handle.start(daily_candle_cache).await;
+    });
+
     // Spawn a task to process trade subscription events and notify trade collector
     let trade_collector_for_events = Arc::clone(&trade_collector);
     let market_cache_for_events = Arc::clone(&market_cache);
@@ -258,7 +563,9 @@ async fn main() -> anyhow::Result<()> {
                         && market_id.chars().all(|c| c.is_ascii_digit())
                     {
                         // This is a clob_token_id, try to find the parent event
-                        let markets = market_cache_for_events.get_markets(Some(platform));
+                        let markets = market_cache_for_events
+                            .get_markets(Some(platform), PageRequest::all())
+                            .markets;
 
                         if let Some(event_id) = find_event_for_clob_token(&markets, &market_id) {
                             info!(
@@ -290,7 +597,9 @@ async fn main() -> anyhow::Result<()> {
                         && market_id.len() > 20
                         && market_id.chars().all(|c| c.is_ascii_digit())
                     {
-                        let markets = market_cache_for_events.get_markets(Some(platform));
+                        let markets = market_cache_for_events
+                            .get_markets(Some(platform), PageRequest::all())
+                            .markets;
                         find_event_for_clob_token(&markets, &market_id).unwrap_or(market_id.clone())
                     } else {
                         market_id.clone()
@@ -533,7 +842,13 @@ async fn main() -> anyhow::Result<()> {
         None
     };
 
+    // Rate limiter for public embed widgets (20ms spacing = max 50 req/sec)
+    let embed_rate_limiter = Arc::new(RateLimiter::new(20, "Embed"));
+
     // Create app state
+    // Closes exchange sockets and writes final snapshots on Ctrl-C
+    let aggregator_for_shutdown = Arc::clone(&aggregator);
+
     let state = AppState {
         market_cache,
         market_service: market_service_arc,
@@ -548,12 +863,16 @@ async fn main() -> anyhow::Result<()> {
         news_aggregator,
         research_service,
         trading_state,
+        embed_rate_limiter,
+        canary_service,
+        daily_candle_refresher,
     };
 
     // Configure CORS for frontend
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        // PUT sets market overrides and adds watchlist entries
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
         .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
 
     // Build router
@@ -573,7 +892,18 @@ async fn main() -> anyhow::Result<()> {
     info!("Server listening on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                tracing::error!("Failed to listen for Ctrl-C: {}", e);
+                std::future::pending::<()>().await;
+            }
+            info!("Shutdown requested, draining connections");
+        })
+        .await?;
+
+    aggregator_for_shutdown.shutdown().await;
+    info!("Shutdown complete");
 
     Ok(())
 }
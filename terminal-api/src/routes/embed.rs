@@ -0,0 +1,483 @@
+//! Public embed widget endpoints
+//!
+//! Minimal, heavily cached payloads for embedding live market cards in
+//! third-party pages (blog posts, newsletters). No auth, CORS-open, and
+//! rate-limited separately from the main API.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use terminal_core::{Platform, PriceInterval, PredictionMarket};
+use terminal_services::Timeframe;
+use tracing::{debug, warn};
+
+use crate::AppState;
+
+/// Cache lifetime for embed responses (seconds)
+const EMBED_MAX_AGE_SECS: u32 = 60;
+
+/// Maximum number of points in an embed sparkline
+const MAX_SPARKLINE_POINTS: usize = 30;
+
+/// Number of days of daily closes shown in the sparkline
+const SPARKLINE_DAYS: i64 = 7;
+
+/// SVG sparkline dimensions
+const SVG_WIDTH: f64 = 120.0;
+const SVG_HEIGHT: f64 = 32.0;
+
+/// Minimal market card payload for embeds
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbedMarketCard {
+    pub platform: Platform,
+    pub market_id: String,
+    pub title: String,
+    /// Current YES price (0.0 - 1.0)
+    pub yes_price: f64,
+    /// 24h price change in percent
+    pub change_24h_percent: f64,
+    /// Total market volume
+    pub volume: f64,
+    /// Daily closes for the last 7 days (oldest first)
+    pub sparkline: Vec<EmbedSparklinePoint>,
+    /// When the market resolves / closes for trading
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution_date: Option<DateTime<Utc>>,
+    /// Link to the market on the platform's website
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// A single sparkline point
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbedSparklinePoint {
+    /// Unix timestamp in seconds
+    pub t: i64,
+    /// Close price (0.0 - 1.0)
+    pub p: f64,
+}
+
+/// Cacheable not-found payload
+#[derive(Debug, Serialize)]
+pub struct EmbedNotFound {
+    pub error: String,
+    pub found: bool,
+}
+
+/// Create embed routes
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/embed/market/{platform}/{id}", get(get_embed_market))
+        .route("/embed/market/{platform}/{id}/sparkline.svg", get(get_embed_sparkline_svg))
+}
+
+/// GET /api/embed/market/{platform}/{id} - Minimal market card for embeds
+async fn get_embed_market(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(limited) = check_rate_limit(&state).await {
+        return limited;
+    }
+
+    let card = match load_card(&state, &platform_str, &id) {
+        Some(card) => card,
+        None => return not_found_response(&platform_str, &id),
+    };
+
+    let body = match serde_json::to_vec(&card) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize embed card for {}: {}", id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    cached_response(StatusCode::OK, "application/json", body, &headers)
+}
+
+/// GET /api/embed/market/{platform}/{id}/sparkline.svg - Server-rendered sparkline
+async fn get_embed_sparkline_svg(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(limited) = check_rate_limit(&state).await {
+        return limited;
+    }
+
+    let card = match load_card(&state, &platform_str, &id) {
+        Some(card) => card,
+        None => return not_found_response(&platform_str, &id),
+    };
+
+    let svg = render_sparkline_svg(&card.sparkline);
+    cached_response(StatusCode::OK, "image/svg+xml", svg.into_bytes(), &headers)
+}
+
+/// Reject the request if the embed rate limiter has no free slot
+///
+/// Embeds must never queue behind each other, so we reject instead of waiting.
+async fn check_rate_limit(state: &AppState) -> Option<Response> {
+    if !state.embed_rate_limiter.try_acquire().await {
+        let mut response = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(EmbedNotFound {
+                error: "Rate limit exceeded".to_string(),
+                found: false,
+            }),
+        )
+            .into_response();
+        apply_embed_headers(response.headers_mut(), None);
+        return Some(response);
+    }
+    None
+}
+
+/// Build the card payload from cache, stats and stored candles
+///
+/// Only cached markets are served: the endpoint is public, so an unknown id
+/// gets the cacheable 404 rather than an upstream fetch.
+fn load_card(state: &AppState, platform_str: &str, id: &str) -> Option<EmbedMarketCard> {
+    let platform: Platform = platform_str.parse().ok()?;
+
+    let Some(market) = state.market_cache.get_cached_market(platform, id) else {
+        debug!("Embed market {:?}/{} not cached", platform, id);
+        return None;
+    };
+
+    let stats = state.market_stats_service.get_market_stats(
+        platform,
+        id,
+        market.yes_price,
+        market.no_price,
+        Timeframe::TwentyFourHours,
+    );
+
+    let sparkline = load_daily_closes(state, platform, id);
+
+    Some(build_card(&market, stats.price_change_percent.to_f64().unwrap_or(0.0), sparkline))
+}
+
+/// Load the last week of daily closes, preferring stored daily candles
+fn load_daily_closes(state: &AppState, platform: Platform, id: &str) -> Vec<EmbedSparklinePoint> {
+    let now = Utc::now();
+    let from = now - Duration::days(SPARKLINE_DAYS);
+
+    let stored = state
+        .trade_storage
+        .get_candles(platform, id, "1d", from, now)
+        .unwrap_or_default();
+
+    let points: Vec<EmbedSparklinePoint> = if !stored.is_empty() {
+        stored
+            .into_iter()
+            .map(|c| EmbedSparklinePoint { t: c.timestamp, p: c.close })
+            .collect()
+    } else {
+        // Fall back to building daily candles from raw trades
+        state
+            .candle_service
+            .build_candles(platform, id, PriceInterval::OneDay, from, now)
+            .map(|history| {
+                history
+                    .candles
+                    .into_iter()
+                    .map(|c| EmbedSparklinePoint {
+                        t: c.timestamp.timestamp(),
+                        p: c.close.to_f64().unwrap_or(0.0),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    downsample(points, MAX_SPARKLINE_POINTS)
+}
+
+/// Assemble the card from a market and precomputed values
+fn build_card(
+    market: &PredictionMarket,
+    change_24h_percent: f64,
+    sparkline: Vec<EmbedSparklinePoint>,
+) -> EmbedMarketCard {
+    EmbedMarketCard {
+        platform: market.platform,
+        market_id: market.id.clone(),
+        title: market.title.clone(),
+        yes_price: market.yes_price.to_f64().unwrap_or(0.0),
+        change_24h_percent,
+        volume: market.volume.to_f64().unwrap_or(0.0),
+        sparkline,
+        resolution_date: market.close_time,
+        url: market.url.clone(),
+    }
+}
+
+/// Reduce a series to at most `max_points`, always keeping the last point
+fn downsample(points: Vec<EmbedSparklinePoint>, max_points: usize) -> Vec<EmbedSparklinePoint> {
+    if points.len() <= max_points || max_points < 2 {
+        return points;
+    }
+
+    let last_index = points.len() - 1;
+    let step = last_index as f64 / (max_points - 1) as f64;
+
+    (0..max_points)
+        .map(|i| {
+            let idx = ((i as f64 * step).round() as usize).min(last_index);
+            points[idx].clone()
+        })
+        .collect()
+}
+
+/// Render a sparkline as a standalone SVG document
+fn render_sparkline_svg(points: &[EmbedSparklinePoint]) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT
+    );
+
+    if points.len() >= 2 {
+        let min = points.iter().map(|p| p.p).fold(f64::MAX, f64::min);
+        let max = points.iter().map(|p| p.p).fold(f64::MIN, f64::max);
+        let range = if max - min > f64::EPSILON { max - min } else { 1.0 };
+        let x_step = SVG_WIDTH / (points.len() - 1) as f64;
+
+        let coords: Vec<String> = points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let x = i as f64 * x_step;
+                let y = SVG_HEIGHT - ((point.p - min) / range) * SVG_HEIGHT;
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+
+        let rising = points.last().map(|p| p.p).unwrap_or(0.0) >= points[0].p;
+        let stroke = if rising { "#22c55e" } else { "#ef4444" };
+
+        svg.push_str(&format!(
+            r#"<polyline fill="none" stroke="{}" stroke-width="1.5" points="{}"/>"#,
+            stroke,
+            coords.join(" ")
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Cacheable 404 payload (embeds should never render an error page)
+fn not_found_response(platform_str: &str, id: &str) -> Response {
+    let body = serde_json::to_vec(&EmbedNotFound {
+        error: format!("Market not found: {}/{}", platform_str, id),
+        found: false,
+    })
+    .unwrap_or_default();
+
+    let mut response = (StatusCode::NOT_FOUND, body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    apply_embed_headers(headers, None);
+    response
+}
+
+/// Build a response with ETag + Cache-Control, honouring If-None-Match
+fn cached_response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: Vec<u8>,
+    request_headers: &HeaderMap,
+) -> Response {
+    let etag = compute_etag(&body);
+
+    let not_modified = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+        .unwrap_or(false);
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        (status, body).into_response()
+    };
+
+    let headers = response.headers_mut();
+    if !not_modified {
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+    apply_embed_headers(headers, Some(&etag));
+    response
+}
+
+/// Apply the shared embed caching and CORS headers
+fn apply_embed_headers(headers: &mut HeaderMap, etag: Option<&str>) {
+    if let Ok(value) = HeaderValue::from_str(&format!("public, max-age={}", EMBED_MAX_AGE_SECS)) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    if let Some(etag) = etag {
+        if let Ok(value) = HeaderValue::from_str(etag) {
+            headers.insert(header::ETAG, value);
+        }
+    }
+}
+
+/// Compute a strong ETag from the response body
+fn compute_etag(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use terminal_core::MarketStatus;
+
+    fn test_market() -> PredictionMarket {
+        PredictionMarket {
+            id: "12345".to_string(),
+            platform: Platform::Polymarket,
+            ticker: None,
+            title: "Will it rain tomorrow?".to_string(),
+            description: None,
+            category: None,
+            yes_price: Decimal::new(62, 2),
+            no_price: Decimal::new(38, 2),
+            volume: Decimal::from(150_000),
+            volume_24hr: None,
+            liquidity: None,
+            open_interest: None,
+            close_time: DateTime::from_timestamp(1_800_000_000, 0),
+            created_at: None,
+            status: MarketStatus::Open,
+            image_url: None,
+            url: Some("https://polymarket.com/event/rain".to_string()),
+            outcome_count: None,
+            leading_outcome: None,
+            is_multi_outcome: false,
+            event_id: None,
+            options_json: None,
+            resolution_source: None,
+            tags: vec![],
+            is_sports: false,
+            is_live: false,
+            score: None,
+            game_period: None,
+            home_team: None,
+            away_team: None,
+            home_odds: None,
+            away_odds: None,
+            spread_line: None,
+            total_line: None,
+            change_1h: None,
+            change_24h: None,
+        }
+    }
+
+    fn points(n: usize) -> Vec<EmbedSparklinePoint> {
+        (0..n)
+            .map(|i| EmbedSparklinePoint {
+                t: i as i64 * 86400,
+                p: 0.5 + i as f64 * 0.001,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_card_payload_shape() {
+        let card = build_card(&test_market(), 1.5, points(7));
+        let json = serde_json::to_value(&card).unwrap();
+
+        for field in [
+            "platform",
+            "market_id",
+            "title",
+            "yes_price",
+            "change_24h_percent",
+            "volume",
+            "sparkline",
+            "resolution_date",
+            "url",
+        ] {
+            assert!(json.get(field).is_some(), "missing field {}", field);
+        }
+        assert_eq!(json["platform"], "polymarket");
+        assert_eq!(json["sparkline"].as_array().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_downsample_caps_points_and_keeps_last() {
+        let series = points(100);
+        let last_t = series.last().unwrap().t;
+        let sampled = downsample(series, MAX_SPARKLINE_POINTS);
+
+        assert_eq!(sampled.len(), MAX_SPARKLINE_POINTS);
+        assert_eq!(sampled.last().unwrap().t, last_t);
+        assert_eq!(downsample(points(7), MAX_SPARKLINE_POINTS).len(), 7);
+    }
+
+    #[test]
+    fn test_cached_response_headers_and_etag() {
+        let response = cached_response(
+            StatusCode::OK,
+            "application/json",
+            b"{}".to_vec(),
+            &HeaderMap::new(),
+        );
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=60");
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        let etag = headers[header::ETAG].to_str().unwrap().to_string();
+
+        // A matching If-None-Match yields 304
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(&etag).unwrap());
+        let response = cached_response(
+            StatusCode::OK,
+            "application/json",
+            b"{}".to_vec(),
+            &request_headers,
+        );
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[test]
+    fn test_not_found_is_cacheable_json() {
+        let response = not_found_response("polymarket", "missing");
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let headers = response.headers();
+        assert_eq!(headers[header::CONTENT_TYPE], "application/json");
+        assert_eq!(headers[header::CACHE_CONTROL], "public, max-age=60");
+    }
+
+    #[test]
+    fn test_render_sparkline_svg() {
+        let svg = render_sparkline_svg(&points(7));
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("<polyline"));
+        assert!(svg.ends_with("</svg>"));
+
+        // Empty series still renders a valid (blank) document
+        let empty = render_sparkline_svg(&[]);
+        assert!(!empty.contains("<polyline"));
+    }
+}
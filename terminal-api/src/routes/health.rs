@@ -1,7 +1,14 @@
 //! Health check endpoints
 
-use axum::{extract::State, http::StatusCode, response::Json, routing::get, Router};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::AppState;
 
@@ -10,13 +17,36 @@ use crate::AppState;
 struct HealthResponse {
     status: String,
     aggregator: terminal_services::AggregatorHealth,
+    /// Latest pipeline canary run (absent until the first run completes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canary: Option<terminal_services::CanaryReport>,
+    /// Stored candle read cache counters
+    candle_cache: terminal_services::CandleCacheStats,
+    /// Computed market stats cache counters
+    stats_cache: terminal_services::StatsCacheStats,
+    /// Market cache counts, hit rate, sizes and per-platform refresh outcomes
+    market_cache: terminal_services::CacheStats,
+    /// Markets whose 1d candles were refreshed from platform history since startup
+    daily_candles_refreshed: u64,
+    /// Frontend WebSocket connections, and dead ones closed for missed pongs
+    websocket_clients: terminal_services::ClientStats,
+    /// Snapshot-class messages sent compressed to clients that asked, and
+    /// the bytes that saved
+    websocket_compression: terminal_services::CompressionStats,
+    /// Trades published on and dropped from the global trades tape
+    ticker_tape: terminal_services::TickerTapeStats,
+    /// Detached client sessions waiting for a reconnect, and how many
+    /// resumed or expired
+    websocket_sessions: terminal_services::SessionStats,
 }
 
 /// Health check handler
 async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
     let aggregator_health = state.aggregator.get_health().await;
+    let canary = state.canary_service.last_report();
+    let canary_passed = canary.as_ref().map(|r| r.passed).unwrap_or(true);
 
-    let status = if aggregator_health.healthy {
+    let status = if aggregator_health.healthy && canary_passed {
         "healthy"
     } else {
         "degraded"
@@ -25,6 +55,15 @@ async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Health
     let response = HealthResponse {
         status: status.to_string(),
         aggregator: aggregator_health,
+        canary,
+        candle_cache: state.candle_service.cache_stats(),
+        stats_cache: state.market_stats_service.cache_stats(),
+        market_cache: state.market_cache.stats(),
+        daily_candles_refreshed: state.daily_candle_refresher.markets_refreshed(),
+        websocket_clients: state.ws_state.subscriptions.client_stats(),
+        websocket_compression: state.ws_state.compression_stats(),
+        ticker_tape: state.ws_state.ticker_tape_stats(),
+        websocket_sessions: state.ws_state.session_stats(),
     };
 
     let code = if status == "healthy" {
@@ -36,6 +75,85 @@ async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Health
     (code, Json(response))
 }
 
+/// Number of markets listed in the storage health payload
+const TOP_MARKETS_LIMIT: usize = 20;
+
+/// Storage health response
+#[derive(Debug, Serialize)]
+struct StorageHealthResponse {
+    #[serde(flatten)]
+    stats: terminal_services::StorageStats,
+    /// Markets with the most stored trade rows
+    top_markets: Vec<terminal_services::MarketRowCount>,
+}
+
+/// Storage statistics handler (what is filling trades.db)
+async fn storage_health(
+    State(state): State<AppState>,
+) -> Result<Json<StorageHealthResponse>, (StatusCode, String)> {
+    let internal_error = |e: terminal_services::TradeStorageError| {
+        tracing::error!("Failed to collect storage stats: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    };
+
+    let stats = state.trade_storage.get_storage_stats().map_err(internal_error)?;
+    let top_markets = state
+        .trade_storage
+        .get_top_markets_by_row_count(TOP_MARKETS_LIMIT)
+        .map_err(internal_error)?;
+
+    Ok(Json(StorageHealthResponse { stats, top_markets }))
+}
+
+/// Exchange feed health handler (connections, rates, fan-out latency)
+async fn aggregator_health(State(state): State<AppState>) -> Json<terminal_services::AggregatorHealth> {
+    Json(state.aggregator.get_health().await)
+}
+
+/// Most gaps returned by the gap log
+const GAPS_LIMIT: usize = 1000;
+
+/// Query parameters for the gap log
+#[derive(Debug, Deserialize)]
+struct GapsQuery {
+    /// Start of window (unix seconds, default: 24 hours ago)
+    since: Option<i64>,
+}
+
+/// Gap log response
+#[derive(Debug, Serialize)]
+struct GapsResponse {
+    gaps: Vec<terminal_services::DataGap>,
+    count: usize,
+}
+
+/// Windows of lost feed data (reconnects, book resyncs) overlapping `since`
+/// onwards, oldest first, for backfill tooling
+async fn gaps(
+    State(state): State<AppState>,
+    Query(params): Query<GapsQuery>,
+) -> Result<Json<GapsResponse>, (StatusCode, String)> {
+    let since = params
+        .since
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(Utc::now() - Duration::hours(24));
+
+    let gaps = state.trade_storage.get_gaps_since(since, GAPS_LIMIT).map_err(|e| {
+        tracing::error!("Failed to load gap log: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+
+    Ok(Json(GapsResponse {
+        count: gaps.len(),
+        gaps,
+    }))
+}
+
+/// Market cache statistics handler
+async fn cache_health(State(state): State<AppState>) -> Json<terminal_services::CacheStats> {
+    Json(state.market_cache.stats())
+}
+
 /// Simple liveness check (always returns OK if server is running)
 async fn liveness() -> &'static str {
     "OK"
@@ -46,4 +164,8 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/health", get(health_check))
         .route("/health/live", get(liveness))
+        .route("/health/storage", get(storage_health))
+        .route("/health/cache", get(cache_health))
+        .route("/health/aggregator", get(aggregator_health))
+        .route("/health/gaps", get(gaps))
 }
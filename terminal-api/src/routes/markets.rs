@@ -1,19 +1,27 @@
 //! Market-related API endpoints
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::IntoResponse,
-    routing::get,
+    routing::{get, post, put},
     Json, Router,
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use futures_util::future::join_all;
-use rust_decimal::Decimal;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use terminal_core::{Platform, PredictionMarket};
-use terminal_services::{MarketFilter, MarketStats, Timeframe};
+use terminal_core::{Platform, PredictionMarket, Trade};
+use terminal_services::{
+    candle_service::CandleServiceError, group_markets_by_event, is_canary_market, market_categories,
+    normalize_category, CandleAnchor, CandleComparison, CandleReadOptions, CandleSourceMode, CandleTransform, Coverage,
+    LeaderboardMetric, MarketAlert, MarketCacheError, MarketCursor, MarketFilter, MarketGroup, MarketOverride, MarketImpact, MarketSearchResult, MarketStats, OutcomeStats, StatsHistoryEntry,
+    PageRequest,
+    PlatformRefreshStatus, RangeSummary, SortKey, SourcedCandle, Timeframe, TradeExportFormat, TradeSizeBucket, UnifiedMatch,
+    WatchlistEntry, DEFAULT_MARKET_PAGE_SIZE, DEFAULT_TRADE_SIZE_BUCKETS,
+};
 use tracing::{debug, error, info, warn};
 
 use crate::AppState;
@@ -27,10 +35,22 @@ pub struct ListMarketsQuery {
     pub search: Option<String>,
     /// Tab filter (all, trending, expiring, new, crypto, politics, sports)
     pub filter: Option<String>,
-    /// Maximum number of results
+    /// Maximum number of results (default 100)
     pub limit: Option<usize>,
-    /// Sort order: "volume" (default), "expiring_soon", "newest"
+    /// Continue after a previous response's `next_cursor`
+    pub cursor: Option<String>,
+    /// Return every market instead of one page
+    #[serde(default)]
+    pub all: bool,
+    /// Sort order: "volume" (default), "change", "closing", "trending", "expiring_soon", "newest"
     pub sort: Option<String>,
+    /// Filter by category or tag (case-insensitive)
+    pub category: Option<String>,
+    /// Also return archived (long-closed) markets
+    #[serde(default)]
+    pub include_archived: bool,
+    /// "events" returns markets grouped by their parent event in `groups`
+    pub group: Option<String>,
 }
 
 /// Response for listing markets
@@ -38,6 +58,163 @@ pub struct ListMarketsQuery {
 pub struct MarketsResponse {
     pub markets: Vec<PredictionMarket>,
     pub count: usize,
+    /// Markets matching the query across all pages
+    pub total_count: usize,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Event groups (with `group=events`; `markets` is then empty)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<MarketGroup>>,
+}
+
+/// Query parameters for the closing-soon and recently-resolved views
+#[derive(Debug, Deserialize)]
+pub struct MarketWindowQuery {
+    /// Filter by platform (kalshi, polymarket, or all)
+    pub platform: Option<String>,
+    /// Window size in hours (default 24)
+    pub hours: Option<i64>,
+    /// Maximum number of results (default 100)
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for full-text market search
+#[derive(Debug, Deserialize)]
+pub struct SearchMarketsQuery {
+    /// Search text matched against titles and descriptions
+    pub q: String,
+    /// Filter by platform (kalshi, polymarket, or all)
+    pub platform: Option<String>,
+    /// Maximum number of results (default 20, max 100)
+    pub limit: Option<usize>,
+}
+
+/// Response for full-text market search
+#[derive(Debug, Serialize)]
+pub struct SearchMarketsResponse {
+    pub query: String,
+    pub results: Vec<MarketSearchResult>,
+    pub count: usize,
+}
+
+/// A category and how many cached markets are filed under it
+#[derive(Debug, Serialize)]
+pub struct CategoryCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Response for the category listing
+#[derive(Debug, Serialize)]
+pub struct CategoriesResponse {
+    pub categories: Vec<CategoryCount>,
+}
+
+/// Response for listing cross-platform matches
+#[derive(Debug, Serialize)]
+pub struct UnifiedMarketsResponse {
+    pub markets: Vec<UnifiedMatch>,
+    pub count: usize,
+}
+
+/// Response for a forced platform refresh
+#[derive(Debug, Serialize)]
+pub struct PlatformRefreshResponse {
+    pub platform: Platform,
+    #[serde(flatten)]
+    pub status: PlatformRefreshStatus,
+}
+
+/// Maximum number of markets in one bulk lookup
+const MAX_BULK_MARKETS: usize = 200;
+
+/// Request body for looking up many markets at once
+#[derive(Debug, Deserialize)]
+pub struct BulkMarketsRequest {
+    /// Markets to look up (at most 200)
+    pub markets: Vec<CompareMarket>,
+}
+
+/// Maximum number of markets in one bulk stats request
+const MAX_BULK_STATS_MARKETS: usize = 300;
+
+/// Request body for stats on many markets at once
+#[derive(Debug, Deserialize)]
+pub struct BulkStatsRequest {
+    pub platform: String,
+    /// Markets to compute stats for (at most 300)
+    pub market_ids: Vec<String>,
+    /// Timeframe: "5m", "1h", "4h", "24h", "7d", "30d" (default 24h)
+    pub timeframe: Option<String>,
+}
+
+/// Response for bulk market stats
+#[derive(Debug, Serialize)]
+pub struct BulkStatsResponse {
+    /// Stats by market id; markets without trades are zeroed
+    pub stats: HashMap<String, MarketStats>,
+    pub timeframe: String,
+    pub count: usize,
+}
+
+/// Top cached markets by 24h volume considered for the leaderboard, on top of
+/// the markets the trade collector tracks
+const LEADERBOARD_TOP_CACHED: usize = 200;
+
+/// Query parameters for the stats leaderboard
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    /// Ranking metric: "volume", "abs_price_change", "txn_count" (default volume)
+    pub metric: Option<String>,
+    /// Timeframe: "5m", "1h", "4h", "24h", "7d", "30d" (default 24h)
+    pub timeframe: Option<String>,
+    /// Filter by platform (kalshi, polymarket, or all)
+    pub platform: Option<String>,
+    /// Maximum number of results (default 20, at most 100)
+    pub limit: Option<usize>,
+}
+
+/// Response for the stats leaderboard
+#[derive(Debug, Serialize)]
+pub struct LeaderboardResponse {
+    pub metric: LeaderboardMetric,
+    pub timeframe: String,
+    /// Ranked highest first
+    pub markets: Vec<MarketStats>,
+    pub count: usize,
+}
+
+/// Response for a bulk market lookup
+#[derive(Debug, Serialize)]
+pub struct BulkMarketsResponse {
+    /// One entry per requested market, in request order; `null` when not cached
+    pub markets: Vec<Option<PredictionMarket>>,
+    /// Requested markets that were found
+    pub found: usize,
+}
+
+/// Response for a watchlist
+#[derive(Debug, Serialize)]
+pub struct WatchlistResponse {
+    pub id: String,
+    /// Watched markets, oldest addition first; `unavailable` ones have left the cache
+    pub markets: Vec<WatchlistEntry>,
+    pub count: usize,
+}
+
+/// Request body for manually linking two platform markets
+#[derive(Debug, Deserialize)]
+pub struct LinkMarketsRequest {
+    pub kalshi_id: String,
+    pub polymarket_id: String,
+    /// `false` rejects the pair instead (default true)
+    #[serde(default = "default_linked")]
+    pub linked: bool,
+}
+
+fn default_linked() -> bool {
+    true
 }
 
 /// Error response
@@ -63,6 +240,240 @@ pub struct TradesQuery {
     pub cursor: Option<String>,
 }
 
+/// Query parameters for trade export
+#[derive(Debug, Deserialize)]
+pub struct TradeExportQuery {
+    /// Output format: "csv" (default) or "jsonl"
+    pub format: Option<String>,
+    /// Start of range (unix seconds, default: all history)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+}
+
+/// Query parameters for volume buckets
+#[derive(Debug, Deserialize)]
+pub struct VolumeQuery {
+    /// Bucket interval (1m, 5m, 15m, 1h, 4h, 1d) - defaults to 1h
+    pub interval: Option<String>,
+    /// Start of range (unix seconds, default: 7 days ago)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+}
+
+/// A single volume bar
+#[derive(Debug, Serialize)]
+pub struct VolumeBucket {
+    /// Bucket start (unix seconds, aligned to the interval)
+    pub t: i64,
+    /// Notional volume (price * quantity)
+    pub volume: f64,
+    /// Number of trades in the bucket
+    pub trade_count: u32,
+}
+
+/// Response for volume buckets
+#[derive(Debug, Serialize)]
+pub struct VolumeResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub interval: String,
+    /// Buckets with trades only; empty buckets are omitted
+    pub buckets: Vec<VolumeBucket>,
+}
+
+/// Query parameters for the realized-volatility series
+#[derive(Debug, Deserialize)]
+pub struct VolatilityQuery {
+    /// Candle interval (1m, 5m, 15m, 1h, 4h, 1d) - defaults to 1h
+    pub interval: Option<String>,
+    /// Number of returns per rolling window (default 24, max 1000)
+    pub window: Option<usize>,
+    /// Start of range (unix seconds, default: 7 days ago)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+}
+
+/// A single volatility point
+#[derive(Debug, Serialize)]
+pub struct VolatilityPoint {
+    /// Timestamp of the candle closing the window (unix seconds)
+    pub t: i64,
+    /// Standard deviation of log-returns over the window
+    pub volatility: f64,
+}
+
+/// Response for the realized-volatility series
+#[derive(Debug, Serialize)]
+pub struct VolatilityResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub interval: String,
+    pub window: usize,
+    /// Empty when the range holds fewer than `window + 1` candles
+    pub points: Vec<VolatilityPoint>,
+}
+
+/// Query parameters for range summary statistics
+#[derive(Debug, Deserialize)]
+pub struct RangeSummaryQuery {
+    /// Start of range (unix seconds, default: 7 days ago)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+}
+
+/// Response for range summary statistics
+#[derive(Debug, Serialize)]
+pub struct RangeSummaryResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub from: i64,
+    pub to: i64,
+    #[serde(flatten)]
+    pub summary: RangeSummary,
+}
+
+/// Maximum number of markets in one compare request
+const MAX_COMPARE_MARKETS: usize = 10;
+
+/// A market to include in a candle comparison
+#[derive(Debug, Deserialize)]
+pub struct CompareMarket {
+    pub platform: String,
+    pub market_id: String,
+}
+
+/// Request body for aligned multi-market candles
+#[derive(Debug, Deserialize)]
+pub struct CandleCompareRequest {
+    /// Markets to compare (1 to 10)
+    pub markets: Vec<CompareMarket>,
+    /// Candle interval (1m, 5m, 15m, 1h, 4h, 1d) - defaults to 1h
+    pub interval: Option<String>,
+    /// Start of range (unix seconds, default: 7 days ago)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+}
+
+/// Response for aligned multi-market candles
+#[derive(Debug, Serialize)]
+pub struct CandleCompareResponse {
+    pub interval: String,
+    #[serde(flatten)]
+    pub comparison: CandleComparison,
+}
+
+/// Query parameters for stored candles
+#[derive(Debug, Deserialize)]
+pub struct CandlesQuery {
+    /// Candle interval (1m, 5m, 15m, 1h, 4h, 1d) - defaults to 1h
+    pub interval: Option<String>,
+    /// Start of range (unix seconds, default: 7 days ago)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+    /// Fill empty buckets with flat zero-volume candles
+    #[serde(default)]
+    pub fill: bool,
+    /// Outcome/token ID for one series of a multi-outcome market (default: whole market)
+    pub outcome: Option<String>,
+    /// Candle source: trades (default), mid, or auto (mid fills trade-less buckets)
+    pub source: Option<String>,
+    /// Transform: none (default), heikin_ashi, sma:<n> or ema:<n>
+    pub transform: Option<String>,
+    /// Return anchor for pct_change: midnight_utc, range_start or timestamp:<ts>
+    pub anchor: Option<String>,
+    /// Point budget; coarser intervals are used past it (default 2000)
+    pub max_points: Option<usize>,
+}
+
+/// Response for stored candles
+#[derive(Debug, Serialize)]
+pub struct CandlesResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub interval: String,
+    /// Interval of the returned candles after fitting `max_points`
+    pub effective_interval: String,
+    pub candles: Vec<SourcedCandle>,
+    /// Timestamp of the candle pct_change is measured against (anchored reads only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchored_at: Option<i64>,
+}
+
+/// Default point budget for stored candle responses
+const DEFAULT_CANDLE_MAX_POINTS: usize = 2000;
+
+/// Query parameters for rebuilding stored candles
+#[derive(Debug, Deserialize)]
+pub struct CandleRebuildQuery {
+    /// Candle interval (1m, 5m, 15m, 1h, 4h, 1d) - defaults to 1h
+    pub interval: Option<String>,
+    /// Start of range (unix seconds, default: oldest stored trade)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+    /// Outcome/token ID to rebuild (default: whole market)
+    pub outcome: Option<String>,
+}
+
+/// Response for a candle rebuild
+#[derive(Debug, Serialize)]
+pub struct CandleRebuildResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub interval: String,
+    pub candles_written: usize,
+}
+
+/// Query parameters for the cross-market whale feed
+#[derive(Debug, Deserialize)]
+pub struct WhaleTradesQuery {
+    /// Minimum notional (price * quantity), default 1000
+    pub min: Option<f64>,
+    /// Start of window (unix seconds, default: 24 hours ago)
+    pub since: Option<i64>,
+    /// Maximum number of trades (default 50, max 500)
+    pub limit: Option<usize>,
+}
+
+/// A large trade with its notional value
+#[derive(Debug, Serialize)]
+pub struct WhaleTrade {
+    #[serde(flatten)]
+    pub trade: Trade,
+    /// price * quantity
+    pub notional: f64,
+}
+
+/// Response for the whale feed
+#[derive(Debug, Serialize)]
+pub struct WhaleTradesResponse {
+    pub trades: Vec<WhaleTrade>,
+    pub count: usize,
+}
+
+/// Query parameters for the recent alerts feed
+#[derive(Debug, Deserialize)]
+pub struct RecentAlertsQuery {
+    /// Filter by platform (kalshi, polymarket, or all)
+    pub platform: Option<String>,
+    /// Maximum number of alerts (default 50, max 500)
+    pub limit: Option<usize>,
+}
+
+/// Response for the recent alerts feed
+#[derive(Debug, Serialize)]
+pub struct RecentAlertsResponse {
+    /// Newest first
+    pub alerts: Vec<MarketAlert>,
+    pub count: usize,
+}
+
 /// Query parameters for related markets
 #[derive(Debug, Deserialize)]
 pub struct RelatedMarketsQuery {
@@ -106,12 +517,97 @@ pub struct OutcomePriceHistoryQuery {
 /// Query parameters for market stats
 #[derive(Debug, Deserialize)]
 pub struct MarketStatsQuery {
-    /// Timeframe: "1h", "24h", "7d", "30d"
+    /// Timeframe: "5m", "1h", "4h", "24h", "7d", "30d"
     pub timeframe: Option<String>,
     /// Filter by platform
     pub platform: Option<String>,
     /// Maximum number of results
     pub limit: Option<usize>,
+    /// Drop markets with no stored data (`coverage: none`); 204 when none are left
+    #[serde(default)]
+    pub skip_empty: bool,
+}
+
+/// Query parameters for per-market stats
+#[derive(Debug, Deserialize)]
+pub struct StatsTimeframeQuery {
+    /// Timeframe: "5m", "1h", "4h", "24h", "7d", "30d" (default 24h)
+    pub timeframe: Option<String>,
+}
+
+/// Most buckets one stats history request may span
+const MAX_STATS_HISTORY_BUCKETS: i64 = 1000;
+
+/// Query parameters for a market's stats history
+#[derive(Debug, Deserialize)]
+pub struct StatsHistoryQuery {
+    /// Bucket size (1m, 5m, 15m, 1h, 4h, 1d) - defaults to 1d
+    pub bucket: Option<String>,
+    /// Start of range (unix seconds, default: 30 days before `to`)
+    pub from: Option<i64>,
+    /// End of range (unix seconds, default: now)
+    pub to: Option<i64>,
+}
+
+/// Response for a market's stats history
+#[derive(Debug, Serialize)]
+pub struct StatsHistoryResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub bucket: String,
+    /// Every bucket in the range, oldest first
+    pub history: Vec<StatsHistoryEntry>,
+}
+
+/// Most notionals one market impact request may price
+const MAX_IMPACT_AMOUNTS: usize = 20;
+
+/// Query parameters for market impact
+#[derive(Debug, Deserialize)]
+pub struct MarketImpactQuery {
+    /// Comma-separated USD notionals to buy (default "500,5000")
+    pub amounts: Option<String>,
+}
+
+/// Response for market impact
+#[derive(Debug, Serialize)]
+pub struct MarketImpactResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    /// One estimate per requested notional, in request order
+    pub impacts: Vec<MarketImpact>,
+}
+
+/// Most bin edges one trade-size histogram request may set
+const MAX_TRADE_SIZE_BUCKETS: usize = 10;
+
+/// Query parameters for a market's trade-size histogram
+#[derive(Debug, Deserialize)]
+pub struct TradeSizesQuery {
+    /// Timeframe: "5m", "1h", "4h", "24h", "7d", "30d" (default 24h)
+    pub timeframe: Option<String>,
+    /// Comma-separated ascending USD bin edges (default "10,100,1000,10000")
+    pub buckets: Option<String>,
+}
+
+/// Response for a market's trade-size histogram
+#[derive(Debug, Serialize)]
+pub struct TradeSizesResponse {
+    pub market_id: String,
+    pub platform: Platform,
+    pub timeframe: String,
+    /// One entry per bin, smallest first; empty bins are zeroed
+    pub buckets: Vec<TradeSizeBucket>,
+}
+
+/// Response for per-outcome stats of a multi-outcome market
+#[derive(Debug, Serialize)]
+pub struct OutcomeStatsResponse {
+    pub market_id: String,
+    pub timeframe: String,
+    /// One entry per known outcome, highest priced first
+    pub outcomes: Vec<OutcomeStats>,
+    pub count: usize,
 }
 
 /// Response for market stats
@@ -141,11 +637,47 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/markets", get(list_markets))
         .route("/markets/stats", get(get_market_stats))
+        .route("/markets/search", get(search_markets))
+        .route("/markets/categories", get(list_categories))
+        .route("/markets/bulk", post(get_markets_bulk))
+        .route("/markets/closing-soon", get(list_closing_soon))
+        .route("/markets/resolved", get(list_recently_resolved))
+        .route("/markets/unified", get(list_unified_markets))
+        .route("/markets/unified/link", post(link_unified_markets))
+        .route("/markets/unified/{id}", get(get_unified_market))
+        .route("/markets/unified/{id}/stats", get(get_unified_market_stats))
+        .route("/markets/refresh/{platform}", post(force_refresh_platform))
         .route("/markets/{platform}/{id}", get(get_market))
+        .route("/markets/{platform}/{id}/refresh", post(refresh_market))
+        .route(
+            "/markets/{platform}/{id}/override",
+            put(set_market_override).delete(clear_market_override),
+        )
         .route("/markets/{platform}/{id}/orderbook", get(get_orderbook))
         .route("/markets/{platform}/{id}/trades", get(get_trades))
+        .route("/markets/{platform}/{id}/trades/export", get(export_trades))
+        .route("/markets/{platform}/{id}/volume", get(get_volume))
+        .route("/markets/{platform}/{id}/volatility", get(get_volatility))
+        .route("/markets/{platform}/{id}/stats/outcomes", get(get_market_outcome_stats))
+        .route("/markets/{platform}/{id}/stats/history", get(get_market_stats_history))
+        .route("/markets/{platform}/{id}/impact", get(get_market_impact))
+        .route("/markets/{platform}/{id}/trade-sizes", get(get_trade_sizes))
+        .route("/markets/{platform}/{id}/summary", get(get_range_summary))
+        .route("/markets/{platform}/{id}/candles", get(get_candles))
+        .route("/markets/{platform}/{id}/candles/rebuild", post(rebuild_candles))
         .route("/markets/{platform}/{id}/history", get(get_price_history))
         .route("/markets/{platform}/{id}/related", get(get_related_markets))
+        .route("/markets/{platform}/{id}/resolution", get(get_resolution))
+        .route("/watchlists/{id}", get(get_watchlist))
+        .route(
+            "/watchlists/{id}/{platform}/{market_id}",
+            put(add_to_watchlist).delete(remove_from_watchlist),
+        )
+        .route("/stats/bulk", post(get_bulk_stats))
+        .route("/stats/leaderboard", get(get_stats_leaderboard))
+        .route("/trades/whales", get(get_whale_trades))
+        .route("/alerts/recent", get(get_recent_alerts))
+        .route("/candles/compare", post(compare_candles))
         // Multi-outcome / outcome-specific routes
         .route("/markets/{platform}/{id}/prices-history", get(get_multi_outcome_prices))
         .route("/markets/{platform}/{id}/outcomes/{outcome_id}/orderbook", get(get_outcome_orderbook))
@@ -181,33 +713,119 @@ async fn list_markets(
         f.parse().ok()
     });
 
+    let cursor = match params.cursor.as_deref().map(MarketCursor::parse) {
+        None => None,
+        Some(Some(cursor)) => Some(cursor),
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Invalid cursor".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+    let limit = if params.all {
+        None
+    } else {
+        Some(params.limit.unwrap_or(DEFAULT_MARKET_PAGE_SIZE))
+    };
+
+    let category = params.category.as_deref().and_then(normalize_category);
+    let sort_key = params.sort.as_deref().and_then(SortKey::from_str);
+    let unfiltered = params.search.is_none() && market_filter.is_none() && category.is_none();
+
+    // Only the plain listing is cursor-paged; other paths return one page,
+    // so a cursor there would just repeat it
+    let cursor_paged = unfiltered && params.sort.is_none();
+    if cursor.is_some() && !cursor_paged {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "cursor can't be combined with sort, category, filter or search".to_string(),
+            }),
+        )
+            .into_response();
+    }
+    let mut total_count = None;
+    let mut next_cursor = None;
+
     // Fetch markets based on params
-    let markets = if let Some(query) = &params.search {
+    let markets = if let (Some(sort_key), true) = (sort_key, unfiltered) {
+        // Precomputed ordering, persisted across restarts
+        match state
+            .market_cache
+            .get_markets_sorted(sort_key, platform_filter, None)
+        {
+            Ok(markets) => markets,
+            Err(e) => {
+                error!("Failed to read {} sort index: {}", sort_key.as_str(), e);
+                state.market_cache.get_markets(platform_filter, PageRequest::all()).markets
+            }
+        }
+    } else if let (Some(category), None, None) = (&category, &params.search, market_filter) {
+        // Category index lookup
+        match state
+            .market_cache
+            .get_markets_by_category(category, platform_filter, None)
+        {
+            Ok(markets) => markets,
+            Err(e) => {
+                error!("Failed to fetch markets for category {}: {}", category, e);
+                Vec::new()
+            }
+        }
+    } else if let Some(query) = &params.search {
         // Search uses cache - instant
         state.market_cache.search_markets(query, platform_filter, params.limit)
     } else if let Some(filter) = market_filter {
         // Use filtered endpoint with caching (30s TTL)
-        match state.market_cache.get_filtered_markets(filter, params.limit).await {
+        match state.market_cache.get_filtered_markets(filter, None).await {
             Ok(markets) => markets,
             Err(e) => {
                 error!("Failed to fetch filtered markets: {}", e);
                 // Fallback to regular cache on error
-                let mut markets = state.market_cache.get_markets(platform_filter);
-                if let Some(limit) = params.limit {
-                    markets.truncate(limit);
-                }
-                markets
+                state.market_cache.get_markets(platform_filter, PageRequest::all()).markets
             }
         }
+    } else if !cursor_paged {
+        // Sorts narrowed below (expiring_soon, newest) count what's left
+        state.market_cache.get_markets(platform_filter, PageRequest::all()).markets
     } else {
-        // List uses cache - instant
-        state.market_cache.get_markets(platform_filter)
+        // List uses cache - instant, paged by cursor
+        let page = state.market_cache.get_markets(
+            platform_filter,
+            PageRequest {
+                cursor,
+                limit,
+                unlimited: params.all,
+            },
+        );
+        total_count = Some(page.total_count);
+        next_cursor = page.next_cursor;
+        page.markets
     };
 
+    let mut markets = markets;
+    if params.include_archived && params.search.is_none() && market_filter.is_none() {
+        match state
+            .market_cache
+            .get_archived_markets(platform_filter, params.limit.unwrap_or(100), 0)
+        {
+            Ok(archived) => markets.extend(archived),
+            Err(e) => error!("Failed to read archived markets: {}", e),
+        }
+    }
+
+    // Search and tab filters don't consult the category index; narrow here
+    if let Some(category) = &category {
+        markets.retain(|m| market_categories(m).contains(category));
+    }
+
     // Apply sorting based on sort parameter
     let now = Utc::now();
     let seven_days = Duration::days(7);
-    let mut markets = markets;
     match params.sort.as_deref() {
         Some("expiring_soon") => {
             // Filter to markets expiring within 7 days, sort by close_time ascending
@@ -232,60 +850,564 @@ async fn list_markets(
         }
     }
 
-    // Apply limit if specified (for non-search queries)
+    // Unpaged paths count every match, before the limit below
+    let total_count = total_count.unwrap_or(markets.len());
+
+    // Apply limit (for non-search queries)
     if params.search.is_none() {
-        if let Some(limit) = params.limit {
+        if let Some(limit) = limit {
             markets.truncate(limit);
         }
     }
 
-    let count = markets.len();
+    let groups = match params.group.as_deref() {
+        Some("events") => Some(group_markets_by_event(std::mem::take(&mut markets))),
+        _ => None,
+    };
+
+    let count = groups.as_ref().map_or(markets.len(), Vec::len);
     info!(
         "Returning {} markets (filter={:?})",
         count,
         params.filter
     );
 
+    // Seconds since the served data was last refreshed (stale after a restart)
+    let cache_age = state
+        .market_cache
+        .data_age(platform_filter)
+        .map_or(0, |age| age.num_seconds().max(0));
+
     (
         StatusCode::OK,
-        Json(MarketsResponse { markets, count }),
+        [("x-cache-age", cache_age.to_string())],
+        Json(MarketsResponse {
+            markets,
+            count,
+            total_count,
+            next_cursor,
+            groups,
+        }),
     )
         .into_response()
 }
 
-/// Get market stats (price change, volume, txn counts) for all markets
-///
-/// This endpoint provides aggregated statistics for the markets table view.
-async fn get_market_stats(
+/// Which time-window view to serve
+#[derive(Debug, Clone, Copy)]
+enum MarketWindow {
+    ClosingSoon,
+    RecentlyResolved,
+}
+
+/// Open markets closing within the window, soonest first
+async fn list_closing_soon(
     State(state): State<AppState>,
-    Query(params): Query<MarketStatsQuery>,
+    Query(params): Query<MarketWindowQuery>,
 ) -> impl IntoResponse {
-    debug!("Getting market stats with params: {:?}", params);
-
-    // Parse timeframe (default to 24h)
-    let timeframe = params
-        .timeframe
-        .as_deref()
-        .and_then(Timeframe::from_str)
-        .unwrap_or(Timeframe::TwentyFourHours);
+    market_window_response(&state, &params, MarketWindow::ClosingSoon)
+}
 
-    // Parse platform filter
-    let platform_filter: Option<Platform> = params.platform.as_ref().and_then(|p| {
-        match p.to_lowercase().as_str() {
-            "kalshi" | "k" => Some(Platform::Kalshi),
-            "polymarket" | "poly" | "p" => Some(Platform::Polymarket),
-            "all" | "" => None,
-            _ => None,
-        }
-    });
+/// Markets settled within the window, most recent first
+async fn list_recently_resolved(
+    State(state): State<AppState>,
+    Query(params): Query<MarketWindowQuery>,
+) -> impl IntoResponse {
+    market_window_response(&state, &params, MarketWindow::RecentlyResolved)
+}
 
-    // Get markets from cache
-    let mut markets = state.market_cache.get_markets(platform_filter);
+fn market_window_response(state: &AppState, params: &MarketWindowQuery, view: MarketWindow) -> axum::response::Response {
+    let platform = match params.platform.as_deref() {
+        None | Some("") | Some("all") => None,
+        Some(platform_str) => match parse_platform(platform_str) {
+            Some(p) => Some(p),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown platform: {}", platform_str),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
 
-    // Apply limit if specified
-    if let Some(limit) = params.limit {
-        markets.truncate(limit);
-    }
+    let window = Duration::hours(params.hours.unwrap_or(24).max(1));
+    let limit = Some(params.limit.unwrap_or(DEFAULT_MARKET_PAGE_SIZE));
+    let markets = match view {
+        MarketWindow::ClosingSoon => state.market_cache.get_closing_soon(window, platform, limit),
+        MarketWindow::RecentlyResolved => state.market_cache.get_recently_resolved(window, platform, limit),
+    };
+
+    let count = markets.len();
+    let response = MarketsResponse {
+        markets,
+        count,
+        total_count: count,
+        next_cursor: None,
+        groups: None,
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Look up many cached markets by id, in request order
+///
+/// Markets not in the cache come back as `null` and are refreshed in the
+/// background, so retrying shortly after usually finds them.
+async fn get_markets_bulk(
+    State(state): State<AppState>,
+    Json(request): Json<BulkMarketsRequest>,
+) -> impl IntoResponse {
+    if request.markets.len() > MAX_BULK_MARKETS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("At most {} markets per request", MAX_BULK_MARKETS),
+            }),
+        )
+            .into_response();
+    }
+
+    let mut ids = Vec::with_capacity(request.markets.len());
+    for market in request.markets {
+        match parse_platform(&market.platform) {
+            Some(platform) => ids.push((platform, market.market_id)),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown platform: {}", market.platform),
+                    }),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let markets = state.market_cache.get_markets_by_ids(&ids);
+    let found = markets.iter().filter(|m| m.is_some()).count();
+    debug!("Bulk lookup found {}/{} markets", found, markets.len());
+    (StatusCode::OK, Json(BulkMarketsResponse { markets, found })).into_response()
+}
+
+/// Stats for many markets of one platform, computed in a single batched pass
+async fn get_bulk_stats(
+    State(state): State<AppState>,
+    Json(request): Json<BulkStatsRequest>,
+) -> impl IntoResponse {
+    if request.market_ids.len() > MAX_BULK_STATS_MARKETS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("At most {} markets per request", MAX_BULK_STATS_MARKETS),
+            }),
+        )
+            .into_response();
+    }
+
+    let platform = match parse_platform(&request.platform) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", request.platform),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let timeframe = match request.timeframe.as_deref() {
+        None => Timeframe::TwentyFourHours,
+        Some(tf) => match Timeframe::from_str(tf) {
+            Some(timeframe) => timeframe,
+            None => return invalid_timeframe(tf).into_response(),
+        },
+    };
+
+    // Current prices come from the cache
+    let ids: Vec<(Platform, String)> = request
+        .market_ids
+        .iter()
+        .map(|id| (platform, id.clone()))
+        .collect();
+    let current_prices: HashMap<String, Decimal> = state
+        .market_cache
+        .get_markets_by_ids(&ids)
+        .into_iter()
+        .flatten()
+        .map(|m| (m.id, m.yes_price))
+        .collect();
+
+    let stats = state.market_stats_service.get_bulk_stats(
+        platform,
+        &request.market_ids,
+        &current_prices,
+        timeframe,
+    );
+    let count = stats.len();
+    debug!("Computed bulk stats for {} markets", count);
+
+    (
+        StatusCode::OK,
+        Json(BulkStatsResponse {
+            stats,
+            timeframe: timeframe.as_str().to_string(),
+            count,
+        }),
+    )
+        .into_response()
+}
+
+/// Top movers, top volume or most active markets over a timeframe
+///
+/// Ranks the collector's tracked markets plus the top cached markets by 24h
+/// volume through the batched stats path.
+async fn get_stats_leaderboard(
+    State(state): State<AppState>,
+    Query(params): Query<LeaderboardQuery>,
+) -> impl IntoResponse {
+    let metric = match params.metric.as_deref() {
+        None => LeaderboardMetric::Volume,
+        Some(m) => match LeaderboardMetric::from_str(m) {
+            Some(metric) => metric,
+            None => {
+                let valid: Vec<&str> = LeaderboardMetric::ALL.iter().map(|m| m.as_str()).collect();
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Invalid metric '{}'; expected one of: {}", m, valid.join(", ")),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let timeframe = match params.timeframe.as_deref() {
+        None => Timeframe::TwentyFourHours,
+        Some(tf) => match Timeframe::from_str(tf) {
+            Some(timeframe) => timeframe,
+            None => return invalid_timeframe(tf).into_response(),
+        },
+    };
+
+    let platform = match params.platform.as_deref() {
+        None | Some("all") | Some("") => None,
+        Some(p) => match parse_platform(p) {
+            Some(platform) => Some(platform),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown platform: {}", p),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    let top_cached = state
+        .market_cache
+        .get_markets_sorted(SortKey::Volume24h, platform, Some(LEADERBOARD_TOP_CACHED))
+        .unwrap_or_else(|e| {
+            warn!("Failed to read top markets for leaderboard: {}", e);
+            Vec::new()
+        });
+    let mut current_prices: HashMap<(Platform, String), Decimal> = top_cached
+        .iter()
+        .map(|m| ((m.platform, m.id.clone()), m.yes_price))
+        .collect();
+
+    let tracked: Vec<(Platform, String)> = state
+        .trade_collector
+        .tracked_markets()
+        .await
+        .into_iter()
+        .filter(|(p, _)| platform.is_none_or(|platform| *p == platform))
+        .collect();
+    let missing_prices: Vec<(Platform, String)> = tracked
+        .iter()
+        .filter(|key| !current_prices.contains_key(*key))
+        .cloned()
+        .collect();
+    for market in state
+        .market_cache
+        .get_markets_by_ids(&missing_prices)
+        .into_iter()
+        .flatten()
+    {
+        current_prices.insert((market.platform, market.id), market.yes_price);
+    }
+
+    let mut candidates = tracked;
+    candidates.extend(top_cached.into_iter().map(|m| (m.platform, m.id)));
+
+    let markets = state.market_stats_service.get_leaderboard(
+        &candidates,
+        &current_prices,
+        timeframe,
+        metric,
+        limit,
+    );
+    let count = markets.len();
+    debug!("Ranked {} of {} markets by {}", count, candidates.len(), metric.as_str());
+
+    (
+        StatusCode::OK,
+        Json(LeaderboardResponse {
+            metric,
+            timeframe: timeframe.as_str().to_string(),
+            markets,
+            count,
+        }),
+    )
+        .into_response()
+}
+
+/// List Kalshi/Polymarket pairs describing the same event
+async fn list_unified_markets(State(state): State<AppState>) -> impl IntoResponse {
+    match state.market_cache.get_unified_markets() {
+        Ok(markets) => {
+            let count = markets.len();
+            (StatusCode::OK, Json(UnifiedMarketsResponse { markets, count })).into_response()
+        }
+        Err(e) => {
+            error!("Failed to list unified markets: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get a unified market by unified id or either platform's market id
+async fn get_unified_market(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.market_cache.get_unified_market(&id) {
+        Ok(Some(market)) => (StatusCode::OK, Json(market)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No unified market for: {}", id),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get unified market {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Per-platform stats and the cross-platform spread for a unified market
+async fn get_unified_market_stats(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<StatsTimeframeQuery>,
+) -> impl IntoResponse {
+    let timeframe = match params.timeframe.as_deref() {
+        None => Timeframe::TwentyFourHours,
+        Some(tf) => match Timeframe::from_str(tf) {
+            Some(timeframe) => timeframe,
+            None => return invalid_timeframe(tf).into_response(),
+        },
+    };
+
+    match state.market_cache.get_unified_market(&id) {
+        Ok(Some(unified)) => {
+            let stats = state
+                .market_stats_service
+                .get_unified_stats(&unified.market, timeframe);
+            (StatusCode::OK, Json(stats)).into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No unified market for: {}", id),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get unified market {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Manually link or reject a Kalshi/Polymarket pair
+async fn link_unified_markets(
+    State(state): State<AppState>,
+    Json(request): Json<LinkMarketsRequest>,
+) -> impl IntoResponse {
+    info!(
+        "{} {} <-> {}",
+        if request.linked { "Linking" } else { "Rejecting" },
+        request.kalshi_id,
+        request.polymarket_id
+    );
+
+    match state
+        .market_cache
+        .link_markets(&request.kalshi_id, &request.polymarket_id, request.linked)
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            error!("Failed to link markets: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// List market categories with their market counts
+async fn list_categories(State(state): State<AppState>) -> impl IntoResponse {
+    match state.market_cache.get_categories() {
+        Ok(categories) => (
+            StatusCode::OK,
+            Json(CategoriesResponse {
+                categories: categories
+                    .into_iter()
+                    .map(|(name, count)| CategoryCount { name, count })
+                    .collect(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to list categories: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Full-text search over cached market titles and descriptions (bm25-ranked)
+async fn search_markets(
+    State(state): State<AppState>,
+    Query(params): Query<SearchMarketsQuery>,
+) -> impl IntoResponse {
+    debug!("Searching markets for {:?}", params.q);
+
+    let platform = match params.platform.as_deref() {
+        None | Some("all") | Some("") => None,
+        Some(p) => match parse_platform(p) {
+            Some(platform) => Some(platform),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown platform: {}", p),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    match state.market_cache.search(&params.q, platform, limit) {
+        Ok(results) => {
+            let count = results.len();
+            (
+                StatusCode::OK,
+                Json(SearchMarketsResponse {
+                    query: params.q,
+                    results,
+                    count,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("Failed to search markets: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// 400 response for an unknown `?timeframe=`, listing the accepted values
+fn invalid_timeframe(value: &str) -> (StatusCode, Json<ErrorResponse>) {
+    let valid: Vec<&str> = Timeframe::ALL.iter().map(|tf| tf.as_str()).collect();
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: format!("Invalid timeframe: {} (expected one of {})", value, valid.join(", ")),
+        }),
+    )
+}
+
+/// Get market stats (price change, volume, txn counts) for all markets
+///
+/// This endpoint provides aggregated statistics for the markets table view.
+async fn get_market_stats(
+    State(state): State<AppState>,
+    Query(params): Query<MarketStatsQuery>,
+) -> impl IntoResponse {
+    debug!("Getting market stats with params: {:?}", params);
+
+    // Parse timeframe (default to 24h)
+    let timeframe = match params.timeframe.as_deref() {
+        None => Timeframe::TwentyFourHours,
+        Some(tf) => match Timeframe::from_str(tf) {
+            Some(timeframe) => timeframe,
+            None => return invalid_timeframe(tf).into_response(),
+        },
+    };
+
+    // Parse platform filter
+    let platform_filter: Option<Platform> = params.platform.as_ref().and_then(|p| {
+        match p.to_lowercase().as_str() {
+            "kalshi" | "k" => Some(Platform::Kalshi),
+            "polymarket" | "poly" | "p" => Some(Platform::Polymarket),
+            "all" | "" => None,
+            _ => None,
+        }
+    });
+
+    // Get markets from cache
+    let mut markets = state.market_cache.get_markets(platform_filter, PageRequest::all()).markets;
+
+    // Apply limit if specified
+    if let Some(limit) = params.limit {
+        markets.truncate(limit);
+    }
 
     // Prepare market data for stats calculation
     let market_data: Vec<(Platform, String, Decimal, Decimal)> = markets
@@ -294,13 +1416,26 @@ async fn get_market_stats(
         .collect();
 
     // Calculate stats for all markets
-    let stats = state
+    let mut stats = state
         .market_stats_service
         .get_bulk_market_stats(&market_data, timeframe);
 
+    if params.skip_empty {
+        stats.retain(|s| {
+            s.data_quality
+                .as_ref()
+                .is_some_and(|q| q.coverage != Coverage::None)
+        });
+        if stats.is_empty() {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+        markets.retain(|m| stats.iter().any(|s| s.platform == m.platform && s.market_id == m.id));
+    }
+
     // Determine interval based on timeframe for sparklines
     let interval = match timeframe {
-        Timeframe::OneHour => "1h",
+        Timeframe::FiveMin | Timeframe::OneHour => "1h",
+        Timeframe::FourHours => "6h",
         Timeframe::TwentyFourHours => "1d",
         Timeframe::SevenDays => "1w",
         Timeframe::ThirtyDays => "max",
@@ -478,21 +1613,268 @@ async fn get_market(
     }
 }
 
-// ============================================================================
-// Order Book, Trades, and Related Markets Endpoints
-// ============================================================================
-
-/// Helper to parse platform string
-fn parse_platform(platform_str: &str) -> Option<Platform> {
-    match platform_str.to_lowercase().as_str() {
-        "kalshi" | "k" => Some(Platform::Kalshi),
-        "polymarket" | "poly" | "p" => Some(Platform::Polymarket),
-        _ => None,
-    }
-}
-
-/// Get order book for a market
-async fn get_orderbook(
+/// Re-fetch a single market from its platform and update the cache
+async fn refresh_market(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.market_cache.refresh_market(platform, &id).await {
+        Ok(market) => (StatusCode::OK, Json(market)).into_response(),
+        Err(MarketCacheError::Api(terminal_core::TerminalError::NotFound(_))) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Market not found: {}", id),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to refresh market {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Set (replace) a market's listing override
+async fn set_market_override(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Json(market_override): Json<MarketOverride>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.market_cache.set_override(platform, &id, market_override.clone()) {
+        Ok(()) => (StatusCode::OK, Json(market_override)).into_response(),
+        Err(e) => {
+            error!("Failed to set override for {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Remove a market's listing override
+async fn clear_market_override(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.market_cache.clear_override(platform, &id) {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No override for: {}", id),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to clear override for {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get a watchlist's markets, tracking them for live stats
+async fn get_watchlist(State(state): State<AppState>, Path(id): Path<String>) -> impl IntoResponse {
+    match state.market_cache.get_watchlist(&id).await {
+        Ok(markets) => {
+            let count = markets.len();
+            (StatusCode::OK, Json(WatchlistResponse { id, markets, count })).into_response()
+        }
+        Err(e) => {
+            error!("Failed to load watchlist {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Add a market to a watchlist (created on first use)
+async fn add_to_watchlist(
+    State(state): State<AppState>,
+    Path((id, platform_str, market_id)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.market_cache.add_to_watchlist(&id, platform, &market_id) {
+        Ok(true) => StatusCode::CREATED.into_response(),
+        Ok(false) => StatusCode::OK.into_response(),
+        Err(e) => {
+            error!("Failed to add {} to watchlist {}: {}", market_id, id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Remove a market from a watchlist
+async fn remove_from_watchlist(
+    State(state): State<AppState>,
+    Path((id, platform_str, market_id)): Path<(String, String, String)>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.market_cache.remove_from_watchlist(&id, platform, &market_id) {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("{} is not on watchlist {}", market_id, id),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to remove {} from watchlist {}: {}", market_id, id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Refresh every market of a platform now, overriding any failure backoff
+async fn force_refresh_platform(
+    State(state): State<AppState>,
+    Path(platform_str): Path<String>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.market_cache.force_refresh_platform(platform).await {
+        Ok(()) => {
+            let status = state.market_cache.refresh_status(platform);
+            (StatusCode::OK, Json(PlatformRefreshResponse { platform, status })).into_response()
+        }
+        Err(e) => {
+            error!("Forced {:?} refresh failed: {}", platform, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ============================================================================
+// Order Book, Trades, and Related Markets Endpoints
+// ============================================================================
+
+/// Helper to parse platform string
+fn parse_platform(platform_str: &str) -> Option<Platform> {
+    match platform_str.to_lowercase().as_str() {
+        "kalshi" | "k" => Some(Platform::Kalshi),
+        "polymarket" | "poly" | "p" => Some(Platform::Polymarket),
+        _ => None,
+    }
+}
+
+/// Get order book for a market
+///
+/// Serves the aggregator's streamed book when it has one, so clients can seed
+/// the WebSocket delta stream without a round trip to the exchange.
+async fn get_orderbook(
     State(state): State<AppState>,
     Path((platform_str, id)): Path<(String, String)>,
     Query(_params): Query<OrderBookQuery>,
@@ -512,7 +1894,7 @@ async fn get_orderbook(
         }
     };
 
-    match state.market_service.get_orderbook(platform, &id).await {
+    match state.aggregator.current_orderbook(platform, &id).await {
         Ok(orderbook) => (StatusCode::OK, Json(orderbook)).into_response(),
         Err(terminal_core::TerminalError::NotFound(_)) => (
             StatusCode::NOT_FOUND,
@@ -592,6 +1974,1041 @@ async fn get_trades(
     }
 }
 
+/// Size of each chunk sent to the client while exporting trades
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// `io::Write` adapter that forwards buffered chunks to a streaming response body
+///
+/// Runs on a blocking thread; fails with `BrokenPipe` once the client disconnects.
+struct ChunkWriter {
+    tx: tokio::sync::mpsc::Sender<Result<Bytes, std::io::Error>>,
+    buf: Vec<u8>,
+}
+
+impl std::io::Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= EXPORT_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::take(&mut self.buf));
+        self.tx.blocking_send(Ok(chunk)).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "client disconnected")
+        })
+    }
+}
+
+/// Export stored trades for a market as a CSV or JSON-lines download
+///
+/// Rows are read from SQLite in pages and streamed in chunks, so large markets never load fully into memory.
+async fn export_trades(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<TradeExportQuery>,
+) -> impl IntoResponse {
+    info!("Exporting trades for {} on {}", id, platform_str);
+
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let format = match params.format.as_deref() {
+        None => TradeExportFormat::Csv,
+        Some(f) => match TradeExportFormat::from_str(f) {
+            Some(format) => format,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown export format: {}", f),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Market not found: {}", id),
+            }),
+        )
+            .into_response()
+    };
+
+    if is_canary_market(&id) {
+        return not_found();
+    }
+
+    match state.market_cache.get_market(platform, &id).await {
+        Ok(_) => {}
+        Err(terminal_core::TerminalError::NotFound(_)) => return not_found(),
+        Err(e) => {
+            error!("Failed to look up market for export: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response();
+        }
+    }
+
+    let from = params
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(DateTime::UNIX_EPOCH);
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or_else(Utc::now);
+
+    let platform_slug = match platform {
+        Platform::Kalshi => "kalshi",
+        Platform::Polymarket => "polymarket",
+    };
+    let safe_id: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let filename = format!("{}-{}-trades.{}", platform_slug, safe_id, format.extension());
+
+    // Export on a blocking thread, forwarding chunks to the response body as they fill
+    let (tx, rx) = tokio::sync::mpsc::channel(8);
+    let storage = state.trade_storage.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut writer = ChunkWriter {
+            tx: tx.clone(),
+            buf: Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        };
+        match storage.export_trades(platform, &id, from, to, format, &mut writer) {
+            Ok(count) => debug!("Exported {} trades for {}", count, id),
+            Err(e) => {
+                warn!("Trade export for {} failed: {}", id, e);
+                let _ = tx.blocking_send(Err(std::io::Error::other(e.to_string())));
+            }
+        }
+    });
+
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+/// Get volume bars for a market from stored trades
+async fn get_volume(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<VolumeQuery>,
+) -> impl IntoResponse {
+    debug!("Getting volume buckets for {} on {}", id, platform_str);
+
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let interval_str = params.interval.unwrap_or_else(|| "1h".to_string());
+    let interval = match terminal_core::PriceInterval::from_str(&interval_str) {
+        Some(interval) => interval,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown interval: {}", interval_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let now = Utc::now();
+    let from = params
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now - Duration::days(7));
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now);
+
+    match state
+        .trade_storage
+        .get_volume_buckets(platform, &id, interval.to_seconds() as i64, from, to)
+    {
+        Ok(buckets) => (
+            StatusCode::OK,
+            Json(VolumeResponse {
+                market_id: id,
+                platform,
+                interval: interval_str,
+                buckets: buckets
+                    .into_iter()
+                    .map(|(t, volume, trade_count)| VolumeBucket {
+                        t,
+                        volume,
+                        trade_count,
+                    })
+                    .collect(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get volume buckets: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get per-bucket volume, txn count and close for a market's detail sparklines
+async fn get_market_stats_history(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<StatsHistoryQuery>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let bucket_str = params.bucket.unwrap_or_else(|| "1d".to_string());
+    let bucket = match terminal_core::PriceInterval::from_str(&bucket_str) {
+        Some(bucket) => bucket,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown bucket: {}", bucket_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+    let bucket_secs = bucket.to_seconds() as i64;
+
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or_else(Utc::now);
+    let from = params
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(to - Duration::days(30));
+    if from > to || (to - from).num_seconds() / bucket_secs >= MAX_STATS_HISTORY_BUCKETS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "Range must be ordered and span at most {} buckets",
+                    MAX_STATS_HISTORY_BUCKETS
+                ),
+            }),
+        )
+            .into_response();
+    }
+
+    let history = state.market_stats_service.get_stats_history(
+        platform,
+        &id,
+        Duration::seconds(bucket_secs),
+        from,
+        to,
+    );
+
+    (
+        StatusCode::OK,
+        Json(StatsHistoryResponse {
+            market_id: id,
+            platform,
+            bucket: bucket_str,
+            history,
+        }),
+    )
+        .into_response()
+}
+
+/// Get the average fill and slippage of buying USD notionals of YES right now
+async fn get_market_impact(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<MarketImpactQuery>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let amounts_str = params.amounts.unwrap_or_else(|| "500,5000".to_string());
+    let amounts: Option<Vec<f64>> = amounts_str
+        .split(',')
+        .map(|a| a.trim().parse::<f64>().ok().filter(|a| a.is_finite() && *a > 0.0))
+        .collect();
+    let amounts = match amounts {
+        Some(amounts) if amounts.len() <= MAX_IMPACT_AMOUNTS => amounts,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!(
+                        "amounts must be up to {} positive comma-separated numbers, got: {}",
+                        MAX_IMPACT_AMOUNTS, amounts_str
+                    ),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let impacts = state.market_stats_service.get_market_impact(platform, &id, &amounts);
+
+    (
+        StatusCode::OK,
+        Json(MarketImpactResponse {
+            market_id: id,
+            platform,
+            impacts,
+        }),
+    )
+        .into_response()
+}
+
+/// Get a market's trades binned by notional (retail vs whale breakdown)
+async fn get_trade_sizes(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<TradeSizesQuery>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let timeframe = match params.timeframe.as_deref() {
+        None => Timeframe::TwentyFourHours,
+        Some(tf) => match Timeframe::from_str(tf) {
+            Some(timeframe) => timeframe,
+            None => return invalid_timeframe(tf).into_response(),
+        },
+    };
+
+    let buckets: Option<Vec<f64>> = match params.buckets.as_deref() {
+        None => Some(DEFAULT_TRADE_SIZE_BUCKETS.to_vec()),
+        Some(buckets_str) => buckets_str
+            .split(',')
+            .map(|b| b.trim().parse::<f64>().ok().filter(|b| b.is_finite() && *b > 0.0))
+            .collect(),
+    };
+    let buckets = match buckets {
+        Some(buckets) if buckets.len() <= MAX_TRADE_SIZE_BUCKETS && buckets.windows(2).all(|w| w[0] < w[1]) => {
+            buckets
+        }
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!(
+                        "buckets must be up to {} ascending positive comma-separated numbers, got: {}",
+                        MAX_TRADE_SIZE_BUCKETS,
+                        params.buckets.unwrap_or_default()
+                    ),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let histogram = state
+        .market_stats_service
+        .get_trade_size_histogram(platform, &id, timeframe, &buckets);
+
+    (
+        StatusCode::OK,
+        Json(TradeSizesResponse {
+            market_id: id,
+            platform,
+            timeframe: timeframe.as_str().to_string(),
+            buckets: histogram,
+        }),
+    )
+        .into_response()
+}
+
+/// Get volume, txn counts and price change broken out by outcome
+async fn get_market_outcome_stats(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<StatsTimeframeQuery>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let timeframe = match params.timeframe.as_deref() {
+        None => Timeframe::TwentyFourHours,
+        Some(tf) => match Timeframe::from_str(tf) {
+            Some(timeframe) => timeframe,
+            None => return invalid_timeframe(tf).into_response(),
+        },
+    };
+
+    let outcomes = state.market_stats_service.get_outcome_stats(platform, &id, timeframe);
+    let count = outcomes.len();
+    (
+        StatusCode::OK,
+        Json(OutcomeStatsResponse {
+            market_id: id,
+            timeframe: timeframe.as_str().to_string(),
+            outcomes,
+            count,
+        }),
+    )
+        .into_response()
+}
+
+/// Get a rolling realized-volatility series from stored candles
+async fn get_volatility(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<VolatilityQuery>,
+) -> impl IntoResponse {
+    debug!("Getting volatility for {} on {}", id, platform_str);
+
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let interval_str = params.interval.unwrap_or_else(|| "1h".to_string());
+    let interval = match terminal_core::PriceInterval::from_str(&interval_str) {
+        Some(interval) => interval,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown interval: {}", interval_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let window = params.window.unwrap_or(24).clamp(1, 1000);
+    let now = Utc::now();
+    let from = params
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now - Duration::days(7));
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now);
+
+    match state
+        .candle_service
+        .get_volatility(platform, &id, interval, window, from, to)
+    {
+        Ok(points) => (
+            StatusCode::OK,
+            Json(VolatilityResponse {
+                market_id: id,
+                platform,
+                interval: interval_str,
+                window,
+                points: points
+                    .into_iter()
+                    .map(|(t, volatility)| VolatilityPoint { t, volatility })
+                    .collect(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get volatility: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get OHLC, volume and max drawdown over a time range ("period stats")
+async fn get_range_summary(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<RangeSummaryQuery>,
+) -> impl IntoResponse {
+    debug!("Getting range summary for {} on {}", id, platform_str);
+
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let now = Utc::now();
+    let from = params
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now - Duration::days(7));
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now);
+
+    match state.candle_service.get_range_summary(platform, &id, from, to) {
+        Ok(Some(summary)) => (
+            StatusCode::OK,
+            Json(RangeSummaryResponse {
+                market_id: id,
+                platform,
+                from: from.timestamp(),
+                to: to.timestamp(),
+                summary,
+            }),
+        )
+            .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No trades or candles in range for market: {}", id),
+            }),
+        )
+            .into_response(),
+        Err(CandleServiceError::InvalidTimeRange) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "`from` must not be after `to`".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get range summary: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get stored candles, optionally gap-filled for contiguous charting
+async fn get_candles(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<CandlesQuery>,
+) -> impl IntoResponse {
+    debug!("Getting stored candles for {} on {}", id, platform_str);
+
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let interval_str = params.interval.unwrap_or_else(|| "1h".to_string());
+    let interval = match terminal_core::PriceInterval::from_str(&interval_str) {
+        Some(interval) => interval,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown interval: {}", interval_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let now = Utc::now();
+    let from = params
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now - Duration::days(7));
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now);
+
+    let source = match params.source.as_deref() {
+        None => CandleSourceMode::default(),
+        Some(s) => match CandleSourceMode::from_str(s) {
+            Some(mode) => mode,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown candle source: {}", s),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let transform = match params.transform.as_deref() {
+        None => CandleTransform::default(),
+        Some(t) => match CandleTransform::from_str(t) {
+            Some(transform) => transform,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown candle transform: {}", t),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let anchor = match params.anchor.as_deref() {
+        None => None,
+        Some(a) => match CandleAnchor::from_str(a) {
+            Some(anchor) => Some(anchor),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown candle anchor: {}", a),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let options = CandleReadOptions {
+        outcome: params.outcome.as_deref(),
+        source,
+        fill: params.fill,
+        transform,
+        anchor,
+        max_points: Some(params.max_points.unwrap_or(DEFAULT_CANDLE_MAX_POINTS).max(1)),
+    };
+
+    match state
+        .candle_service
+        .get_candles_with_source(platform, &id, interval, from, to, &options)
+    {
+        Ok(series) => (
+            StatusCode::OK,
+            Json(CandlesResponse {
+                market_id: id,
+                platform,
+                interval: interval_str,
+                effective_interval: series.effective_interval,
+                candles: series.candles,
+                anchored_at: series.anchored_at,
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get candles: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get several markets' stored candles aligned to a shared time axis
+async fn compare_candles(
+    State(state): State<AppState>,
+    Json(request): Json<CandleCompareRequest>,
+) -> impl IntoResponse {
+    if request.markets.is_empty() || request.markets.len() > MAX_COMPARE_MARKETS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Expected 1 to {} markets", MAX_COMPARE_MARKETS),
+            }),
+        )
+            .into_response();
+    }
+
+    let mut markets = Vec::with_capacity(request.markets.len());
+    for market in request.markets {
+        match parse_platform(&market.platform) {
+            Some(platform) => markets.push((platform, market.market_id)),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown platform: {}", market.platform),
+                    }),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let interval_str = request.interval.unwrap_or_else(|| "1h".to_string());
+    let interval = match terminal_core::PriceInterval::from_str(&interval_str) {
+        Some(interval) => interval,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown interval: {}", interval_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let now = Utc::now();
+    let from = request
+        .from
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now - Duration::days(7));
+    let to = request
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(now);
+
+    match state
+        .candle_service
+        .get_candles_multi(markets, interval, from, to)
+        .await
+    {
+        Ok(comparison) => (
+            StatusCode::OK,
+            Json(CandleCompareResponse {
+                interval: interval_str,
+                comparison,
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to compare candles: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Recompute stored candles for a market from its raw trades
+async fn rebuild_candles(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+    Query(params): Query<CandleRebuildQuery>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let interval_str = params.interval.unwrap_or_else(|| "1h".to_string());
+    let interval = match terminal_core::PriceInterval::from_str(&interval_str) {
+        Some(interval) => interval,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown interval: {}", interval_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let from = match params.from.and_then(|t| DateTime::from_timestamp(t, 0)) {
+        Some(from) => Some(from),
+        None => state
+            .trade_storage
+            .get_earliest_trade_time(platform, &id)
+            .unwrap_or(None),
+    };
+    let Some(from) = from else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No stored trades for market: {}", id),
+            }),
+        )
+            .into_response();
+    };
+    let to = params
+        .to
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or_else(Utc::now);
+
+    info!("Rebuilding {} candles for {} on {}", interval_str, id, platform_str);
+
+    match state
+        .candle_service
+        .rebuild_candles(platform, &id, params.outcome.as_deref(), interval, from, to)
+    {
+        Ok(candles_written) => (
+            StatusCode::OK,
+            Json(CandleRebuildResponse {
+                market_id: id,
+                platform,
+                interval: interval_str,
+                candles_written,
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to rebuild candles: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get the recorded final outcome of a resolved market
+async fn get_resolution(
+    State(state): State<AppState>,
+    Path((platform_str, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let platform = match parse_platform(&platform_str) {
+        Some(p) => p,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown platform: {}", platform_str),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state.trade_storage.get_resolution(platform, &id) {
+        Ok(Some(resolution)) => (StatusCode::OK, Json(resolution)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No resolution recorded for market: {}", id),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to get resolution: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get the largest recent trades across all markets
+async fn get_whale_trades(
+    State(state): State<AppState>,
+    Query(params): Query<WhaleTradesQuery>,
+) -> impl IntoResponse {
+    let min_notional = params.min.unwrap_or(1000.0);
+    let since = params
+        .since
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .unwrap_or(Utc::now() - Duration::hours(24));
+    let limit = params.limit.unwrap_or(50).min(500);
+
+    debug!("Getting whale trades (min notional {})", min_notional);
+
+    match state
+        .trade_storage
+        .get_largest_trades_global(min_notional, since, limit)
+    {
+        Ok(trades) => {
+            let trades: Vec<WhaleTrade> = trades
+                .into_iter()
+                .filter(|t| !is_canary_market(&t.market_id))
+                .map(|trade| {
+                    let notional = (trade.price * trade.quantity).to_f64().unwrap_or(0.0);
+                    WhaleTrade { trade, notional }
+                })
+                .collect();
+            let count = trades.len();
+            (StatusCode::OK, Json(WhaleTradesResponse { trades, count })).into_response()
+        }
+        Err(e) => {
+            error!("Failed to get whale trades: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Unusual-activity alerts raised on the exchange feeds, newest first
+async fn get_recent_alerts(
+    State(state): State<AppState>,
+    Query(params): Query<RecentAlertsQuery>,
+) -> impl IntoResponse {
+    let platform = match params.platform.as_deref() {
+        None | Some("all") | Some("") => None,
+        Some(p) => match parse_platform(p) {
+            Some(platform) => Some(platform),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: format!("Unknown platform: {}", p),
+                    }),
+                )
+                    .into_response();
+            }
+        },
+    };
+    let limit = params.limit.unwrap_or(50).min(500);
+
+    match state.trade_storage.get_recent_alerts(platform, limit) {
+        Ok(alerts) => {
+            let count = alerts.len();
+            (StatusCode::OK, Json(RecentAlertsResponse { alerts, count })).into_response()
+        }
+        Err(e) => {
+            error!("Failed to get recent alerts: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
 /// Get related markets
 async fn get_related_markets(
     State(state): State<AppState>,
@@ -620,7 +3037,14 @@ async fn get_related_markets(
     {
         Ok(markets) => {
             let count = markets.len();
-            (StatusCode::OK, Json(MarketsResponse { markets, count })).into_response()
+            let response = MarketsResponse {
+                markets,
+                count,
+                total_count: count,
+                next_cursor: None,
+                groups: None,
+            };
+            (StatusCode::OK, Json(response)).into_response()
         }
         Err(terminal_core::TerminalError::NotFound(_)) => (
             StatusCode::NOT_FOUND,
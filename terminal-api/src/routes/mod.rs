@@ -1,5 +1,6 @@
 //! API route definitions
 
+mod embed;
 mod health;
 mod markets;
 mod news;
@@ -21,6 +22,7 @@ pub fn api_routes() -> Router<AppState> {
         .merge(health::routes())
         .merge(research::routes())
         .merge(trading::routes())
+        .merge(embed::routes())
 }
 
 /// Create WebSocket routes (separate from API)
@@ -204,6 +204,28 @@ async fn submit_order(
     State(state): State<AppState>,
     Json(req): Json<SubmitOrderRequest>,
 ) -> impl IntoResponse {
+    let ws_state = Arc::clone(&state.ws_state);
+    let (token_id, side, price, size) = (req.token_id.clone(), req.side.clone(), req.price, req.size);
+
+    let (status, Json(response)) = place_order(state, req).await;
+
+    // Tell the trader's authenticated WebSocket connections
+    if response.success {
+        ws_state.broadcast_order_update(serde_json::json!({
+            "orderId": response.order_id,
+            "tokenId": token_id,
+            "side": side,
+            "price": price,
+            "size": size,
+            "transactionHashes": response.transaction_hashes,
+        }));
+    }
+
+    (status, Json(response))
+}
+
+/// Sign and post an order to the CLOB
+async fn place_order(state: AppState, req: SubmitOrderRequest) -> (StatusCode, Json<SubmitOrderResponse>) {
     info!("Submitting order: {:?}", req);
 
     // Get trading state
@@ -6,13 +6,14 @@ use axum::{
     body::Bytes,
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Query, State,
     },
     response::IntoResponse,
     routing::get,
     Router,
 };
 use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
 use tracing::info;
 
 use crate::AppState;
@@ -22,20 +23,30 @@ pub fn routes() -> Router<AppState> {
     Router::new().route("/ws", get(ws_handler))
 }
 
+/// Query parameters for the WebSocket upgrade
+#[derive(Debug, Deserialize)]
+struct WsQuery {
+    /// Access token, instead of an `auth` message (when `WS_AUTH_TOKEN` is set)
+    token: Option<String>,
+    /// Client-chosen id to resume this connection's session after a reconnect
+    session_id: Option<String>,
+}
+
 /// WebSocket upgrade handler
 async fn ws_handler(
     ws: WebSocketUpgrade,
+    Query(query): Query<WsQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
     info!("=== WebSocket upgrade request received ===");
     ws.on_upgrade(move |socket| {
         info!("=== WebSocket upgrade successful, handling socket ===");
-        handle_socket(socket, state)
+        handle_socket(socket, state, query.token, query.session_id)
     })
 }
 
 /// Handle an established WebSocket connection
-async fn handle_socket(socket: WebSocket, state: AppState) {
+async fn handle_socket(socket: WebSocket, state: AppState, token: Option<String>, session_id: Option<String>) {
     // Convert axum WebSocket to tokio-tungstenite compatible stream
     let (mut sender, mut receiver) = socket.split();
 
@@ -103,7 +114,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     let bridge = BridgeStream { rx, tx: response_tx };
 
     // Handle the connection using our WebSocketState
-    state.ws_state.handle_connection(bridge).await;
+    state.ws_state.handle_connection(bridge, token, session_id).await;
 
     // Clean up tasks
     recv_task.abort();
@@ -0,0 +1,180 @@
+//! Ping scheduling for exchange WebSocket connections
+//!
+//! The exchange clients ping on a fixed interval and time the reply. A ping
+//! left unanswered past the timeout means the connection is dead even if the
+//! socket still looks open, so the client drops it and reconnects instead of
+//! waiting for the aggregator's stale watchdog.
+//!
+//! The scheduler is a plain state machine over caller-supplied instants; the
+//! clients own the socket and the timer.
+
+use std::time::{Duration, Instant};
+
+/// What the connection should do after polling the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PingAction {
+    /// Nothing due yet
+    Wait,
+    /// Send a ping now
+    Send,
+    /// The last ping went unanswered past the timeout; reconnect
+    TimedOut,
+}
+
+/// One outstanding ping at a time, sent every `interval`
+#[derive(Debug, Clone)]
+pub struct PingScheduler {
+    interval: Duration,
+    timeout: Duration,
+    last_sent: Option<Instant>,
+    /// Send time of the ping still waiting for its pong
+    awaiting: Option<Instant>,
+    last_rtt: Option<Duration>,
+}
+
+impl PingScheduler {
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Self {
+            interval,
+            timeout,
+            last_sent: None,
+            awaiting: None,
+            last_rtt: None,
+        }
+    }
+
+    /// When `poll` next has something to do
+    pub fn next_deadline(&self, now: Instant) -> Instant {
+        match (self.awaiting, self.last_sent) {
+            (Some(sent), _) => sent + self.timeout,
+            (None, Some(sent)) => sent + self.interval,
+            (None, None) => now,
+        }
+    }
+
+    /// Check the schedule at `now`; a `Send` is recorded as sent
+    pub fn poll(&mut self, now: Instant) -> PingAction {
+        if let Some(sent) = self.awaiting {
+            return if now.saturating_duration_since(sent) >= self.timeout {
+                PingAction::TimedOut
+            } else {
+                PingAction::Wait
+            };
+        }
+        if self.last_sent.is_some_and(|sent| now.saturating_duration_since(sent) < self.interval) {
+            return PingAction::Wait;
+        }
+        self.last_sent = Some(now);
+        self.awaiting = Some(now);
+        PingAction::Send
+    }
+
+    /// Record a pong, returning the round trip of the ping it answers
+    ///
+    /// Unsolicited pongs (nothing outstanding) return `None`.
+    pub fn on_pong(&mut self, now: Instant) -> Option<Duration> {
+        let rtt = now.saturating_duration_since(self.awaiting.take()?);
+        self.last_rtt = Some(rtt);
+        Some(rtt)
+    }
+
+    pub fn last_rtt(&self) -> Option<Duration> {
+        self.last_rtt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers each ping after `latency`, or never
+    struct MockTransport {
+        latency: Option<Duration>,
+        in_flight: Option<Instant>,
+        pings: usize,
+    }
+
+    impl MockTransport {
+        fn new(latency: Option<Duration>) -> Self {
+            Self {
+                latency,
+                in_flight: None,
+                pings: 0,
+            }
+        }
+
+        fn send_ping(&mut self, now: Instant) {
+            self.pings += 1;
+            self.in_flight = Some(now);
+        }
+
+        /// A pong arriving by `now`, if any
+        fn take_pong(&mut self, now: Instant) -> Option<Instant> {
+            let arrives = self.in_flight? + self.latency?;
+            if arrives > now {
+                return None;
+            }
+            self.in_flight = None;
+            Some(arrives)
+        }
+    }
+
+    /// Drive the scheduler in 100ms steps until `end`, returning the RTTs
+    /// seen and whether it timed out
+    fn run(scheduler: &mut PingScheduler, transport: &mut MockTransport, start: Instant, end: Duration) -> (Vec<Duration>, bool) {
+        let mut rtts = Vec::new();
+        let mut now = start;
+        while now < start + end {
+            if let Some(arrived) = transport.take_pong(now) {
+                rtts.extend(scheduler.on_pong(arrived));
+            }
+            match scheduler.poll(now) {
+                PingAction::Send => transport.send_ping(now),
+                PingAction::TimedOut => return (rtts, true),
+                PingAction::Wait => {}
+            }
+            now += Duration::from_millis(100);
+        }
+        (rtts, false)
+    }
+
+    #[test]
+    fn test_pings_on_interval_and_measures_rtt() {
+        let start = Instant::now();
+        let mut scheduler = PingScheduler::new(Duration::from_secs(10), Duration::from_secs(5));
+        let mut transport = MockTransport::new(Some(Duration::from_millis(300)));
+
+        assert_eq!(scheduler.next_deadline(start), start);
+        let (rtts, timed_out) = run(&mut scheduler, &mut transport, start, Duration::from_secs(35));
+        assert!(!timed_out);
+        // Pings at 0, 10, 20 and 30s
+        assert_eq!(transport.pings, 4);
+        assert_eq!(rtts, vec![Duration::from_millis(300); 4]);
+        assert_eq!(scheduler.last_rtt(), Some(Duration::from_millis(300)));
+        assert_eq!(scheduler.next_deadline(start), start + Duration::from_secs(40));
+
+        // A pong nobody asked for doesn't skew the measurement
+        assert_eq!(scheduler.on_pong(start + Duration::from_secs(36)), None);
+    }
+
+    #[test]
+    fn test_missed_pong_times_out() {
+        let start = Instant::now();
+        let mut scheduler = PingScheduler::new(Duration::from_secs(10), Duration::from_secs(5));
+        let mut transport = MockTransport::new(None);
+
+        assert_eq!(scheduler.poll(start), PingAction::Send);
+        transport.send_ping(start);
+        assert_eq!(scheduler.next_deadline(start), start + Duration::from_secs(5));
+        assert_eq!(scheduler.poll(start + Duration::from_secs(4)), PingAction::Wait);
+        assert_eq!(scheduler.poll(start + Duration::from_secs(5)), PingAction::TimedOut);
+
+        // No second ping is sent while the first is outstanding
+        let mut scheduler = PingScheduler::new(Duration::from_secs(2), Duration::from_secs(5));
+        let mut transport = MockTransport::new(None);
+        let (rtts, timed_out) = run(&mut scheduler, &mut transport, start, Duration::from_secs(60));
+        assert!(timed_out);
+        assert!(rtts.is_empty());
+        assert_eq!(transport.pings, 1);
+    }
+}
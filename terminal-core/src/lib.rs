@@ -3,7 +3,9 @@
 //! This crate defines the shared data structures used across the terminal,
 //! including market representations, positions, and platform abstractions.
 
+pub mod keepalive;
 pub mod market;
+pub mod message_filter;
 pub mod news;
 pub mod platform;
 pub mod position;
@@ -18,10 +20,12 @@ pub use news::{
     MarketNewsContext, MatchedMarket, NewsFeed, NewsItem, NewsSearchParams, NewsSource,
     PriceSignal, SuggestedAction,
 };
+pub use keepalive::{PingAction, PingScheduler};
+pub use message_filter::MessageFilter;
 pub use platform::Platform;
 pub use position::{Balance, Portfolio, Position};
 pub use error::TerminalError;
 pub use websocket::{
-    ClientMessage, ConnectionState, ErrorCode, OrderBookUpdateType, ServerMessage,
+    AlertKind, ClientMessage, ConnectionState, ErrorCode, OrderBookUpdateType, PayloadEncoding, ServerMessage,
     SubscriptionChannel, SubscriptionKey, SubscriptionType,
 };
@@ -64,6 +64,10 @@ pub struct PredictionMarket {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub liquidity: Option<Decimal>,
 
+    /// Contracts currently open (Kalshi only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_interest: Option<Decimal>,
+
     /// When the market closes for trading
     #[serde(skip_serializing_if = "Option::is_none")]
     pub close_time: Option<DateTime<Utc>>,
@@ -95,6 +99,10 @@ pub struct PredictionMarket {
     #[serde(default)]
     pub is_multi_outcome: bool,
 
+    /// Parent event id (Polymarket); outcome markets of one event share it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+
     /// For multi-outcome events: JSON array of all options
     /// Format: [{ "name": string, "yes_price": number, "market_id": string }, ...]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -150,6 +158,17 @@ pub struct PredictionMarket {
     /// Total/over-under line (e.g., "45.5")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_line: Option<String>,
+
+    // ========================================================================
+    // Price movement (filled in by the market cache on refresh)
+    // ========================================================================
+    /// YES price change over the last hour (null without prior data)
+    #[serde(default)]
+    pub change_1h: Option<Decimal>,
+
+    /// YES price change over the last 24 hours (null without prior data)
+    #[serde(default)]
+    pub change_24h: Option<Decimal>,
 }
 
 impl PredictionMarket {
@@ -415,6 +434,15 @@ pub struct Trade {
     /// Transaction hash (for on-chain trades like Polymarket on Polygon)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transaction_hash: Option<String>,
+    /// Outcome/token identifier for multi-outcome markets (e.g. Polymarket asset ID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome_id: Option<String>,
+    /// Maker wallet address (on-chain platforms only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maker_address: Option<String>,
+    /// Taker wallet address (on-chain platforms only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taker_address: Option<String>,
 }
 
 /// Trade history response with pagination
@@ -436,7 +464,7 @@ pub struct TradeHistory {
 // ============================================================================
 
 /// Time interval for price candles
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PriceInterval {
     /// 1 minute candles
     #[serde(rename = "1m")]
@@ -483,6 +511,18 @@ impl PriceInterval {
             _ => None,
         }
     }
+
+    /// String representation (inverse of `from_str`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceInterval::OneMinute => "1m",
+            PriceInterval::FiveMinutes => "5m",
+            PriceInterval::FifteenMinutes => "15m",
+            PriceInterval::OneHour => "1h",
+            PriceInterval::FourHours => "4h",
+            PriceInterval::OneDay => "1d",
+        }
+    }
 }
 
 impl Default for PriceInterval {
@@ -0,0 +1,123 @@
+//! Message-type filtering for exchange WebSocket feeds
+//!
+//! The exchange feeds carry message types the terminal never uses, and at
+//! high volume parsing them is wasted work. The clients sniff the type field
+//! with a plain string scan and drop unwanted frames before any JSON
+//! deserialization. Frames whose type can't be sniffed (errors, unexpected
+//! shapes) are let through for the full parser to deal with.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Which message types a feed client parses
+#[derive(Debug, Clone)]
+pub struct MessageFilter {
+    /// JSON field holding the message type
+    field: &'static str,
+    /// Types let through; `None` lets everything through
+    allowed: Option<HashSet<String>>,
+    /// Frames dropped so far; shared so the owner can read it
+    dropped: Arc<AtomicU64>,
+}
+
+impl MessageFilter {
+    /// Let through only `types` (as found in `field`)
+    pub fn only(field: &'static str, types: &[&str]) -> Self {
+        Self {
+            field,
+            allowed: Some(types.iter().map(|t| t.to_string()).collect()),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Let every frame through
+    pub fn all(field: &'static str) -> Self {
+        Self {
+            field,
+            allowed: None,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The same filter with filtering switched off, for debugging
+    pub fn allow_everything(mut self) -> Self {
+        self.allowed = None;
+        self
+    }
+
+    /// Count drops into `counter` instead of the filter's own
+    pub fn with_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.dropped = counter;
+        self
+    }
+
+    /// Whether a frame should be parsed; dropped frames are counted
+    pub fn admit(&self, text: &str) -> bool {
+        let Some(allowed) = &self.allowed else {
+            return true;
+        };
+        match sniff_field(text, self.field) {
+            Some(kind) if !allowed.contains(kind) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Frames dropped so far
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// String value of the first `"field": "..."` in a JSON frame, without
+/// parsing it
+///
+/// Values with escapes come back raw; type names never have any.
+pub fn sniff_field<'a>(text: &'a str, field: &str) -> Option<&'a str> {
+    for (start, _) in text.match_indices(field) {
+        let end = start + field.len();
+        if !text[..start].ends_with('"') || !text[end..].starts_with('"') {
+            continue;
+        }
+        // A quoted "field" not followed by a colon is a value; keep looking
+        let Some(rest) = text[end + 1..].trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let value = rest.trim_start().strip_prefix('"')?;
+        return value.find('"').map(|close| &value[..close]);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_field_finds_the_key_not_the_value() {
+        assert_eq!(sniff_field(r#"{"type":"trade","sid":1}"#, "type"), Some("trade"));
+        assert_eq!(sniff_field(r#"{ "event_type" : "book", "bids": [] }"#, "event_type"), Some("book"));
+        // "type" inside another key or a value doesn't count
+        assert_eq!(sniff_field(r#"{"msg_type":"x","kind":"type","type":"ticker"}"#, "type"), Some("ticker"));
+        assert_eq!(sniff_field(r#"{"error":"bad request"}"#, "type"), None);
+        assert_eq!(sniff_field(r#"{"type":5}"#, "type"), None);
+    }
+
+    #[test]
+    fn test_filter_counts_dropped_frames() {
+        let filter = MessageFilter::only("type", &["trade"]);
+        assert!(filter.admit(r#"{"type":"trade"}"#));
+        assert!(!filter.admit(r#"{"type":"fill"}"#));
+        // No sniffable type: up to the parser
+        assert!(filter.admit(r#"{"error":"nope"}"#));
+        assert_eq!(filter.dropped(), 1);
+
+        let counter = Arc::new(AtomicU64::new(0));
+        let debug = filter.allow_everything().with_counter(Arc::clone(&counter));
+        assert!(debug.admit(r#"{"type":"fill"}"#));
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+}
@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::{NewsFeed, OrderBookLevel, Platform, Trade};
+use crate::{NewsFeed, OrderBook, OrderBookLevel, Platform, PredictionMarket, PriceCandle, PriceInterval, Trade};
 
 // ============================================================================
 // Client -> Server Messages
@@ -21,6 +21,10 @@ pub enum ClientMessage {
     Subscribe {
         /// Subscription type
         subscription: SubscriptionType,
+        /// Send this subscription's snapshot-class messages (snapshots,
+        /// full books) as `compressed` messages
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        compress: bool,
     },
     /// Unsubscribe from market updates
     Unsubscribe {
@@ -32,6 +36,11 @@ pub enum ClientMessage {
         /// Client timestamp
         timestamp: i64,
     },
+    /// Present an access token; must be the first message when the server
+    /// requires one and it wasn't passed in the URL
+    Auth {
+        token: String,
+    },
 }
 
 /// Types of subscriptions available
@@ -53,26 +62,83 @@ pub enum SubscriptionType {
         platform: Platform,
         market_id: String,
     },
+    /// Subscribe to live in-progress candles for a market at an interval
+    Candles {
+        platform: Platform,
+        market_id: String,
+        interval: PriceInterval,
+    },
+    /// Subscribe to markets newly listed on a platform
+    MarketListings {
+        platform: Platform,
+    },
+    /// Subscribe to recomputed 24h stats for a market
+    Stats {
+        platform: Platform,
+        market_id: String,
+    },
+    /// Subscribe to best bid/ask/mid ticks for a market (lighter than full books)
+    Prices {
+        platform: Platform,
+        market_id: String,
+    },
+    /// Subscribe to every outcome of a multi-outcome event at once
+    Event {
+        platform: Platform,
+        event_id: String,
+    },
+    /// Subscribe to unusual-activity alerts across a platform
+    Alerts {
+        platform: Platform,
+    },
+    /// Subscribe to the tape of every trade on tracked markets, across
+    /// platforms
+    GlobalTrades {
+        /// Only trades worth at least this much (price times size)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_notional: Option<Decimal>,
+    },
 }
 
 impl SubscriptionType {
-    /// Get the platform for this subscription
+    /// Get the platform for this subscription (a placeholder for the
+    /// cross-platform trade tape)
     pub fn platform(&self) -> Platform {
         match self {
             Self::Price { platform, .. } => *platform,
             Self::OrderBook { platform, .. } => *platform,
             Self::Trades { platform, .. } => *platform,
+            Self::Candles { platform, .. } => *platform,
+            Self::MarketListings { platform } => *platform,
+            Self::Stats { platform, .. } => *platform,
+            Self::Prices { platform, .. } => *platform,
+            Self::Event { platform, .. } => *platform,
+            Self::Alerts { platform } => *platform,
+            Self::GlobalTrades { .. } => Platform::Kalshi,
         }
     }
 
-    /// Get the market ID for this subscription
+    /// Get the market ID for this subscription (the event ID for events,
+    /// empty for platform-wide ones)
     pub fn market_id(&self) -> &str {
         match self {
             Self::Price { market_id, .. } => market_id,
             Self::OrderBook { market_id, .. } => market_id,
             Self::Trades { market_id, .. } => market_id,
+            Self::Candles { market_id, .. } => market_id,
+            Self::MarketListings { .. } => "",
+            Self::Stats { market_id, .. } => market_id,
+            Self::Prices { market_id, .. } => market_id,
+            Self::Event { event_id, .. } => event_id,
+            Self::Alerts { .. } => "",
+            Self::GlobalTrades { .. } => "",
         }
     }
+
+    /// Whether this subscription follows a single market
+    pub fn is_market_scoped(&self) -> bool {
+        !matches!(self, Self::MarketListings { .. } | Self::Alerts { .. } | Self::GlobalTrades { .. })
+    }
 }
 
 // ============================================================================
@@ -91,6 +157,20 @@ pub enum ServerMessage {
     Unsubscribed {
         subscription: SubscriptionType,
     },
+    /// Current state of a market, sent once on subscribing to one of its
+    /// channels, before any live update
+    Snapshot {
+        platform: Platform,
+        market_id: String,
+        /// The market as cached, if it is
+        #[serde(skip_serializing_if = "Option::is_none")]
+        market: Option<PredictionMarket>,
+        /// Streamed book, or the last stored snapshot
+        #[serde(skip_serializing_if = "Option::is_none")]
+        orderbook: Option<OrderBook>,
+        /// Latest stored trades, oldest first
+        trades: Vec<Trade>,
+    },
     /// Price update for a market
     PriceUpdate {
         platform: Platform,
@@ -99,6 +179,18 @@ pub enum ServerMessage {
         no_price: Decimal,
         timestamp: DateTime<Utc>,
     },
+    /// Top of book and mid, sent when the mid moves past a threshold
+    PriceTick {
+        platform: Platform,
+        market_id: String,
+        best_bid: Decimal,
+        best_ask: Decimal,
+        mid: Decimal,
+        timestamp: DateTime<Utc>,
+        /// Recent history sent on subscribe rather than a live update
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        replay: bool,
+    },
     /// Order book snapshot or update
     OrderBookUpdate {
         platform: Platform,
@@ -110,17 +202,92 @@ pub enum ServerMessage {
         no_bids: Vec<OrderBookLevel>,
         no_asks: Vec<OrderBookLevel>,
         timestamp: DateTime<Utc>,
+        /// Recent history sent on subscribe rather than a live update
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        replay: bool,
     },
     /// New trade occurred
     TradeUpdate {
         platform: Platform,
         market_id: String,
         trade: Trade,
+        /// Recent history sent on subscribe rather than a live update
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        replay: bool,
+    },
+    /// Live candle update for the current bucket of a subscribed interval
+    CandleUpdate {
+        platform: Platform,
+        market_id: String,
+        interval: PriceInterval,
+        candle: PriceCandle,
+        /// True once the bucket has ended and the candle is final
+        closed: bool,
+    },
+    /// Recomputed stats for a market (the serialized `MarketStats`)
+    StatsUpdate {
+        platform: Platform,
+        market_id: String,
+        stats: serde_json::Value,
+    },
+    /// Price, order book or trade update for one outcome of a subscribed event
+    EventUpdate {
+        platform: Platform,
+        event_id: String,
+        /// The outcome's own market id
+        market_id: String,
+        /// Outcome name, e.g. the candidate or bracket
+        outcome: String,
+        /// The outcome's `price_update`, `order_book_update` or `trade_update`
+        update: Box<ServerMessage>,
+    },
+    /// Unusual activity detected on a market
+    MarketAlert {
+        platform: Platform,
+        market_id: String,
+        kind: AlertKind,
+        /// Multiple of the baseline for volume spikes and large prints,
+        /// price points moved for price jumps
+        magnitude: f64,
+        timestamp: DateTime<Utc>,
+    },
+    /// A market appeared on a platform for the first time
+    MarketListed {
+        platform: Platform,
+        market: PredictionMarket,
     },
     /// News update for a market
     NewsUpdate {
         feed: NewsFeed,
     },
+    /// A trade on the global tape
+    GlobalTrade {
+        trade: Trade,
+    },
+    /// Connection accepted; `label` names the token it presented
+    Authenticated {
+        label: String,
+    },
+    /// The connection's session, sent first when the client gave a
+    /// `session_id`; `resumed` when an earlier connection's session was
+    /// picked up, after which `replayed` missed messages follow (`dropped`
+    /// more didn't fit the buffer)
+    Session {
+        session_id: String,
+        resumed: bool,
+        replayed: usize,
+        dropped: usize,
+    },
+    /// Another server message, compressed for a client that asked for it
+    Compressed {
+        encoding: PayloadEncoding,
+        data: String,
+    },
+    /// Order submitted through the trading API, sent to authenticated
+    /// connections only
+    OrderUpdate {
+        update: serde_json::Value,
+    },
     /// Research update (progress, completion, or failure)
     ResearchUpdate {
         /// The research update payload (from terminal-research crate)
@@ -130,6 +297,12 @@ pub enum ServerMessage {
     Error {
         code: ErrorCode,
         message: String,
+        /// The limit that was hit, for limit errors
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<usize>,
+        /// Current usage against `limit`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        count: Option<usize>,
     },
     /// Pong response to client ping
     Pong {
@@ -142,9 +315,21 @@ pub enum ServerMessage {
     ConnectionStatus {
         platform: Platform,
         status: ConnectionState,
+        /// True when the feed came back after a drop: updates may have been
+        /// missed, so refetch order book and price snapshots
+        #[serde(default)]
+        resync: bool,
     },
 }
 
+/// How a `compressed` message's data is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadEncoding {
+    /// zstd-compressed JSON, base64 encoded
+    ZstdBase64,
+}
+
 /// Type of order book update
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -155,6 +340,39 @@ pub enum OrderBookUpdateType {
     Delta,
 }
 
+/// Kind of unusual activity behind a market alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// Trade rate well above its recent baseline
+    VolumeSpike,
+    /// A single trade far larger than the typical size
+    LargePrint,
+    /// Price moved sharply within a short window
+    PriceJump,
+}
+
+impl AlertKind {
+    /// String representation (inverse of `parse`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertKind::VolumeSpike => "volume_spike",
+            AlertKind::LargePrint => "large_print",
+            AlertKind::PriceJump => "price_jump",
+        }
+    }
+
+    /// Parse from string representation
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "volume_spike" => Some(AlertKind::VolumeSpike),
+            "large_print" => Some(AlertKind::LargePrint),
+            "price_jump" => Some(AlertKind::PriceJump),
+            _ => None,
+        }
+    }
+}
+
 /// Error codes for WebSocket errors
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -169,6 +387,14 @@ pub enum ErrorCode {
     PlatformError,
     /// Rate limit exceeded
     RateLimited,
+    /// This connection holds as many subscriptions as it may
+    SubscriptionLimitExceeded,
+    /// The server holds as many subscriptions across clients as it may
+    GlobalSubscriptionLimitExceeded,
+    /// The client fell too far behind on messages and is being disconnected
+    SlowConsumer,
+    /// No valid access token was presented
+    Unauthorized,
     /// Internal server error
     InternalError,
 }
@@ -205,7 +431,14 @@ pub enum SubscriptionChannel {
     Price,
     OrderBook,
     Trades,
+    Candles(PriceInterval),
     News,
+    MarketListings,
+    Stats,
+    Prices,
+    Event,
+    Alerts,
+    GlobalTrades,
 }
 
 impl From<&SubscriptionType> for SubscriptionKey {
@@ -226,6 +459,72 @@ impl From<&SubscriptionType> for SubscriptionKey {
                 market_id: market_id.clone(),
                 channel: SubscriptionChannel::Trades,
             },
+            SubscriptionType::Candles {
+                platform,
+                market_id,
+                interval,
+            } => Self {
+                platform: *platform,
+                market_id: market_id.clone(),
+                channel: SubscriptionChannel::Candles(*interval),
+            },
+            SubscriptionType::MarketListings { platform } => Self {
+                platform: *platform,
+                market_id: String::new(),
+                channel: SubscriptionChannel::MarketListings,
+            },
+            SubscriptionType::Stats { platform, market_id } => Self {
+                platform: *platform,
+                market_id: market_id.clone(),
+                channel: SubscriptionChannel::Stats,
+            },
+            SubscriptionType::Prices { platform, market_id } => Self {
+                platform: *platform,
+                market_id: market_id.clone(),
+                channel: SubscriptionChannel::Prices,
+            },
+            SubscriptionType::Event { platform, event_id } => Self {
+                platform: *platform,
+                market_id: event_id.clone(),
+                channel: SubscriptionChannel::Event,
+            },
+            SubscriptionType::Alerts { platform } => Self {
+                platform: *platform,
+                market_id: String::new(),
+                channel: SubscriptionChannel::Alerts,
+            },
+            // One key for every client, whatever its filter
+            SubscriptionType::GlobalTrades { .. } => Self {
+                platform: sub.platform(),
+                market_id: String::new(),
+                channel: SubscriptionChannel::GlobalTrades,
+            },
         }
     }
 }
+
+impl SubscriptionKey {
+    /// The subscription this key was made from; `None` for channels clients
+    /// can't subscribe to. A global trades filter isn't part of the key, so
+    /// it comes back unset.
+    pub fn subscription(&self) -> Option<SubscriptionType> {
+        let (platform, id) = (self.platform, self.market_id.clone());
+        Some(match self.channel {
+            SubscriptionChannel::Price => SubscriptionType::Price { platform, market_id: id },
+            SubscriptionChannel::OrderBook => SubscriptionType::OrderBook { platform, market_id: id },
+            SubscriptionChannel::Trades => SubscriptionType::Trades { platform, market_id: id },
+            SubscriptionChannel::Candles(interval) => SubscriptionType::Candles {
+                platform,
+                market_id: id,
+                interval,
+            },
+            SubscriptionChannel::MarketListings => SubscriptionType::MarketListings { platform },
+            SubscriptionChannel::Stats => SubscriptionType::Stats { platform, market_id: id },
+            SubscriptionChannel::Prices => SubscriptionType::Prices { platform, market_id: id },
+            SubscriptionChannel::Event => SubscriptionType::Event { platform, event_id: id },
+            SubscriptionChannel::Alerts => SubscriptionType::Alerts { platform },
+            SubscriptionChannel::GlobalTrades => SubscriptionType::GlobalTrades { min_notional: None },
+            SubscriptionChannel::News => return None,
+        })
+    }
+}
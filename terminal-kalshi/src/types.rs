@@ -295,6 +295,7 @@ impl KalshiMarket {
             volume: Decimal::from(self.volume.unwrap_or(0)),
             volume_24hr: self.volume_24h.map(Decimal::from),
             liquidity: self.open_interest.map(Decimal::from),
+            open_interest: self.open_interest.map(Decimal::from),
             close_time: self.close_time.or(self.expiration_time),
             created_at: self.created_time.or(self.open_time),
             status,
@@ -304,6 +305,7 @@ impl KalshiMarket {
             outcome_count: None,
             leading_outcome: None,
             is_multi_outcome: false,
+            event_id: None,
             options_json: None,
             resolution_source: self.rules_primary.clone(),
             // Sports fields
@@ -317,6 +319,8 @@ impl KalshiMarket {
             away_odds,
             spread_line: None,
             total_line: None,
+            change_1h: None,
+            change_24h: None,
             // Kalshi doesn't have tags like Polymarket
             tags: Vec::new(),
         }
@@ -562,6 +566,9 @@ impl KalshiTrade {
             outcome,
             side,
             transaction_hash: None, // Kalshi doesn't have on-chain transactions
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
         }
     }
 }
@@ -663,6 +670,13 @@ pub fn markets_to_multi_outcome(
         .map(|m| m.volume.unwrap_or(0))
         .sum();
 
+    // Sum open interest across the markets that report it
+    let open_interest = markets
+        .iter()
+        .filter_map(|m| m.open_interest)
+        .reduce(|a, b| a + b)
+        .map(Decimal::from);
+
     // Use earliest close time from all markets
     let close_time = markets
         .iter()
@@ -706,6 +720,7 @@ pub fn markets_to_multi_outcome(
         volume: Decimal::from(total_volume),
         volume_24hr: None, // Multi-outcome events don't aggregate 24h volume
         liquidity: None,
+        open_interest,
         close_time,
         created_at,
         status,
@@ -713,6 +728,7 @@ pub fn markets_to_multi_outcome(
         url,
         // Multi-outcome fields
         is_multi_outcome: true,
+        event_id: None,
         outcome_count: Some(markets.len()),
         leading_outcome: Some(leader.title.clone()),
         options_json: Some(serde_json::to_string(&outcomes).unwrap_or_default()),
@@ -728,6 +744,8 @@ pub fn markets_to_multi_outcome(
         away_odds: None,
         spread_line: None,
         total_line: None,
+        change_1h: None,
+        change_24h: None,
         // Kalshi doesn't have tags like Polymarket
         tags: Vec::new(),
     }
@@ -20,17 +20,21 @@ use rsa::RsaPrivateKey;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
-use tokio::sync::{broadcast, mpsc, RwLock};
-use tokio::time::interval;
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
-use terminal_core::{OrderBook, OrderBookLevel, Platform, Trade, TradeOutcome, TradeSide};
+use terminal_core::{MessageFilter, OrderBook, OrderBookLevel, PingAction, PingScheduler, Trade};
+
+use crate::types::{KalshiOrderbook, KalshiTrade};
 
 /// Kalshi WebSocket URL
 const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
 
+/// Path signed into the handshake (timestamp + method + path)
+const KALSHI_WS_SIGN_PATH: &str = "/trade-api/ws/v2";
+
 /// Reconnect delay base
 const RECONNECT_DELAY_BASE: Duration = Duration::from_secs(1);
 
@@ -40,6 +44,27 @@ const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
 /// Attempts before logging as "extended retry mode" (for visibility)
 const RECONNECT_WARNING_THRESHOLD: u32 = 5;
 
+/// How often we ping the server (its own pings only prove it can reach us)
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a ping may go unanswered before the connection is dropped
+const PONG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long to wait for the server to answer our close frame
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Message types `handle_message` understands; anything else (fills,
+/// lifecycle events) is dropped before parsing
+pub const KALSHI_MESSAGE_TYPES: &[&str] = &[
+    "subscribed",
+    "unsubscribed",
+    "orderbook_snapshot",
+    "orderbook_delta",
+    "ticker",
+    "trade",
+    "error",
+];
+
 // ============================================================================
 // WebSocket Message Types (matching Kalshi's protocol)
 // ============================================================================
@@ -74,12 +99,16 @@ pub enum KalshiResponse {
     OrderbookSnapshot {
         #[serde(default)]
         sid: Option<u64>,
+        #[serde(default)]
+        seq: Option<u64>,
         msg: OrderbookSnapshotMsg,
     },
     /// Orderbook delta (incremental update)
     OrderbookDelta {
         #[serde(default)]
         sid: Option<u64>,
+        #[serde(default)]
+        seq: Option<u64>,
         msg: OrderbookDeltaMsg,
     },
     /// Ticker update (price, volume)
@@ -115,11 +144,14 @@ pub struct ErrorMsg {
     pub message: String,
 }
 
+/// Resting bids per outcome as [price_cents, quantity] pairs, like the REST orderbook
 #[derive(Debug, Clone, Deserialize)]
 pub struct OrderbookSnapshotMsg {
     pub market_ticker: String,
-    pub yes: Vec<OrderbookLevel>,
-    pub no: Vec<OrderbookLevel>,
+    #[serde(default)]
+    pub yes: Option<Vec<Vec<i64>>>,
+    #[serde(default)]
+    pub no: Option<Vec<Vec<i64>>>,
     #[serde(default)]
     pub seq: Option<u64>,
 }
@@ -134,20 +166,23 @@ pub struct OrderbookDeltaMsg {
     pub seq: Option<u64>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct OrderbookLevel {
-    pub price: i64,    // Price in cents
-    pub quantity: i64, // Number of contracts
-}
-
 #[derive(Debug, Clone, Deserialize)]
 pub struct TickerMsg {
     pub market_ticker: String,
+    /// Last traded YES price in cents
+    #[serde(default)]
+    pub price: Option<i64>,
+    #[serde(default)]
     pub yes_price: Option<i64>,
+    #[serde(default)]
     pub no_price: Option<i64>,
+    #[serde(default)]
     pub yes_bid: Option<i64>,
+    #[serde(default)]
     pub yes_ask: Option<i64>,
+    #[serde(default)]
     pub volume: Option<i64>,
+    #[serde(default)]
     pub open_interest: Option<i64>,
 }
 
@@ -155,13 +190,13 @@ pub struct TickerMsg {
 pub struct TradeMsg {
     pub market_ticker: String,
     pub trade_id: String,
-    pub side: String, // "yes" or "no"
-    pub price: i64,   // Price in cents
-    pub count: i64,   // Number of contracts
+    pub yes_price: i64, // Price in cents
+    pub no_price: i64,  // Price in cents
+    pub count: i64,     // Number of contracts
     #[serde(default)]
-    pub taker_side: Option<String>, // "buy" or "sell"
+    pub taker_side: Option<String>, // "yes" or "no"
     #[serde(default)]
-    pub ts: Option<i64>, // Timestamp
+    pub ts: Option<i64>, // Unix seconds
 }
 
 // ============================================================================
@@ -193,6 +228,10 @@ pub enum KalshiUpdate {
     },
     /// Trade executed
     Trade { market_ticker: String, trade: Trade },
+    /// Server keepalive ping (answered by the client)
+    Heartbeat,
+    /// Reply to our keepalive ping, with its round trip
+    Pong { rtt: Duration },
     /// Connection state change
     ConnectionState {
         connected: bool,
@@ -210,6 +249,8 @@ pub struct KalshiWebSocketConfig {
     pub api_key: Option<String>,
     pub private_key_pem: Option<String>,
     pub auto_reconnect: bool,
+    /// Frames parsed, by their `type`
+    pub message_filter: MessageFilter,
 }
 
 impl std::fmt::Debug for KalshiWebSocketConfig {
@@ -221,6 +262,7 @@ impl std::fmt::Debug for KalshiWebSocketConfig {
                 &self.private_key_pem.as_ref().map(|_| "[REDACTED]"),
             )
             .field("auto_reconnect", &self.auto_reconnect)
+            .field("message_filter", &self.message_filter)
             .finish()
     }
 }
@@ -247,10 +289,28 @@ impl Default for KalshiWebSocketConfig {
             api_key: std::env::var("KALSHI_API_KEY").ok(),
             private_key_pem,
             auto_reconnect: true,
+            message_filter: MessageFilter::only("type", KALSHI_MESSAGE_TYPES),
         }
     }
 }
 
+impl KalshiWebSocketConfig {
+    /// Config with explicit credentials (API key id + PEM or raw base64 private key)
+    pub fn with_credentials(api_key: impl Into<String>, private_key_pem: impl Into<String>) -> Self {
+        Self {
+            api_key: Some(api_key.into()),
+            private_key_pem: Some(private_key_pem.into()),
+            auto_reconnect: true,
+            message_filter: MessageFilter::only("type", KALSHI_MESSAGE_TYPES),
+        }
+    }
+
+    /// Whether both halves of the signed handshake are configured
+    pub fn has_credentials(&self) -> bool {
+        self.api_key.is_some() && self.private_key_pem.is_some()
+    }
+}
+
 /// Sign a message using RSA-PSS with SHA256
 fn sign_rsa_pss(private_key_input: &str, message: &str) -> Result<String, anyhow::Error> {
     use rsa::pkcs1::DecodeRsaPrivateKey;
@@ -306,25 +366,75 @@ fn format_as_pem(b64_content: &str, key_type: &str) -> String {
     pem
 }
 
-/// Create authentication headers for Kalshi WebSocket
+/// Signed handshake headers for the Kalshi WebSocket
+#[derive(Debug, Clone)]
+struct KalshiAuthHeaders {
+    key: String,
+    signature: String,
+    timestamp: String,
+}
+
+impl KalshiAuthHeaders {
+    /// Add the `KALSHI-ACCESS-*` headers to a handshake request
+    fn apply(&self, headers: &mut tokio_tungstenite::tungstenite::http::HeaderMap) -> Result<(), anyhow::Error> {
+        headers.insert("KALSHI-ACCESS-KEY", self.key.parse()?);
+        headers.insert("KALSHI-ACCESS-SIGNATURE", self.signature.parse()?);
+        headers.insert("KALSHI-ACCESS-TIMESTAMP", self.timestamp.parse()?);
+        Ok(())
+    }
+}
+
+/// Create authentication headers for Kalshi WebSocket, signed at `timestamp_ms`
 fn create_auth_headers(
     api_key: &str,
     private_key_pem: &str,
-) -> Result<(String, String, String), anyhow::Error> {
-    // Timestamp in milliseconds
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis()
-        .to_string();
+    timestamp_ms: u128,
+) -> Result<KalshiAuthHeaders, anyhow::Error> {
+    let timestamp = timestamp_ms.to_string();
 
     // Message to sign: timestamp + method + path
-    let message = format!("{}GET/trade-api/ws/v2", timestamp);
+    let message = format!("{}GET{}", timestamp, KALSHI_WS_SIGN_PATH);
 
     // Sign the message
     let signature = sign_rsa_pss(private_key_pem, &message)?;
 
-    Ok((api_key.to_string(), signature, timestamp))
+    Ok(KalshiAuthHeaders {
+        key: api_key.to_string(),
+        signature,
+        timestamp,
+    })
+}
+
+/// Apply an orderbook delta to a cached Kalshi book
+///
+/// Kalshi only rests bids: a YES bid at p is also a NO ask at 1 - p, so a
+/// delta on one side moves both that side's bids and the other side's asks.
+/// Levels that drop to zero are removed; negative deltas on unknown levels
+/// are ignored.
+pub fn apply_orderbook_delta(book: &mut OrderBook, side: &str, price: Decimal, delta: Decimal) {
+    let (bids, opposite_asks) = if side == "yes" {
+        (&mut book.yes_bids, &mut book.no_asks)
+    } else {
+        (&mut book.no_bids, &mut book.yes_asks)
+    };
+
+    apply_level_delta(bids, price, delta);
+    bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+    apply_level_delta(opposite_asks, Decimal::ONE - price, delta);
+    opposite_asks.sort_by_key(|l| l.price);
+
+    book.timestamp = Utc::now();
+}
+
+fn apply_level_delta(levels: &mut Vec<OrderBookLevel>, price: Decimal, delta: Decimal) {
+    if let Some(level) = levels.iter_mut().find(|l| l.price == price) {
+        level.quantity += delta;
+        if level.quantity <= Decimal::ZERO {
+            levels.retain(|l| l.price != price);
+        }
+    } else if delta > Decimal::ZERO {
+        levels.push(OrderBookLevel::new(price, delta));
+    }
 }
 
 /// Kalshi WebSocket client
@@ -338,12 +448,15 @@ pub struct KalshiWebSocket {
     command_tx: Option<mpsc::Sender<KalshiCommand>>,
     /// Next command ID
     next_id: Arc<RwLock<u64>>,
+    /// Set to close the connection for good
+    shutdown: watch::Sender<bool>,
 }
 
 impl KalshiWebSocket {
     /// Create a new Kalshi WebSocket client
     pub fn new(config: KalshiWebSocketConfig) -> (Self, broadcast::Receiver<KalshiUpdate>) {
         let (update_tx, update_rx) = broadcast::channel(1024);
+        let (shutdown, _) = watch::channel(false);
 
         (
             Self {
@@ -352,6 +465,7 @@ impl KalshiWebSocket {
                 subscriptions: Arc::new(RwLock::new(HashSet::new())),
                 command_tx: None,
                 next_id: Arc::new(RwLock::new(1)),
+                shutdown,
             },
             update_rx,
         )
@@ -382,9 +496,10 @@ impl KalshiWebSocket {
         let config = self.config.clone();
         let update_tx = self.update_tx.clone();
         let subscriptions = Arc::clone(&self.subscriptions);
+        let shutdown = self.shutdown.subscribe();
 
         tokio::spawn(async move {
-            Self::connection_loop(config, update_tx, command_rx, subscriptions).await;
+            Self::connection_loop(config, update_tx, command_rx, subscriptions, shutdown).await;
         });
 
         Ok(())
@@ -396,6 +511,7 @@ impl KalshiWebSocket {
         update_tx: broadcast::Sender<KalshiUpdate>,
         mut command_rx: mpsc::Receiver<KalshiCommand>,
         subscriptions: Arc<RwLock<HashSet<String>>>,
+        mut shutdown: watch::Receiver<bool>,
     ) {
         let mut reconnect_attempts = 0u32;
 
@@ -419,8 +535,12 @@ impl KalshiWebSocket {
             info!("[Kalshi WS] Connecting to {}", KALSHI_WS_URL);
 
             // Create authentication headers using RSA-PSS signing
-            let auth_result = create_auth_headers(&api_key, &private_key_pem);
-            let (key, signature, timestamp) = match auth_result {
+            let timestamp_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let auth_result = create_auth_headers(&api_key, &private_key_pem, timestamp_ms);
+            let auth_headers = match auth_result {
                 Ok(headers) => headers,
                 Err(e) => {
                     error!("[Kalshi WS] Failed to create auth headers: {}", e);
@@ -443,12 +563,10 @@ impl KalshiWebSocket {
             };
 
             // Add Kalshi authentication headers
-            let headers = request.headers_mut();
-            headers.insert("KALSHI-ACCESS-KEY", key.parse().unwrap());
-            headers.insert("KALSHI-ACCESS-SIGNATURE", signature.parse().unwrap());
-            headers.insert("KALSHI-ACCESS-TIMESTAMP", timestamp.parse().unwrap());
-
-            // Auth headers created successfully
+            if let Err(e) = auth_headers.apply(request.headers_mut()) {
+                error!("[Kalshi WS] Invalid auth header value: {}", e);
+                return;
+            }
 
             match connect_async(request).await {
                 Ok((ws_stream, _)) => {
@@ -463,13 +581,14 @@ impl KalshiWebSocket {
 
                     let (mut write, mut read) = ws_stream.split();
 
-                    // Re-subscribe to any active subscriptions
+                    // Re-subscribe to any active subscriptions, one command per
+                    // ticker: orderbook `seq` counts per subscription, so this
+                    // keeps it contiguous per market like `subscribe` does
                     {
                         let subs = subscriptions.read().await;
-                        if !subs.is_empty() {
-                            let tickers: Vec<String> = subs.iter().cloned().collect();
+                        for (id, ticker) in subs.iter().enumerate() {
                             let cmd = KalshiCommand {
-                                id: 1,
+                                id: id as u64 + 1,
                                 cmd: "subscribe".to_string(),
                                 params: KalshiCommandParams {
                                     channels: vec![
@@ -477,21 +596,20 @@ impl KalshiWebSocket {
                                         "ticker".to_string(),
                                         "trade".to_string(),
                                     ],
-                                    market_ticker: None,
-                                    market_tickers: Some(tickers),
+                                    market_ticker: Some(ticker.clone()),
+                                    market_tickers: None,
                                 },
                             };
 
                             if let Ok(json) = serde_json::to_string(&cmd) {
                                 if let Err(e) = write.send(Message::Text(json.into())).await {
-                                    warn!("[Kalshi WS] Failed to re-subscribe: {}", e);
+                                    warn!("[Kalshi WS] Failed to re-subscribe {}: {}", ticker, e);
                                 }
                             }
                         }
                     }
 
-                    // Create heartbeat interval
-                    let mut heartbeat = interval(Duration::from_secs(25));
+                    let mut pinger = PingScheduler::new(PING_INTERVAL, PONG_TIMEOUT);
 
                     loop {
                         tokio::select! {
@@ -499,7 +617,7 @@ impl KalshiWebSocket {
                             msg = read.next() => {
                                 match msg {
                                     Some(Ok(Message::Text(text))) => {
-                                        Self::handle_message(&text, &update_tx);
+                                        Self::handle_frame(&text, &config.message_filter, &update_tx);
                                     }
                                     Some(Ok(Message::Ping(data))) => {
                                         // Respond to ping
@@ -507,6 +625,12 @@ impl KalshiWebSocket {
                                             warn!("[Kalshi WS] Failed to send pong: {}", e);
                                             break;
                                         }
+                                        let _ = update_tx.send(KalshiUpdate::Heartbeat);
+                                    }
+                                    Some(Ok(Message::Pong(_))) => {
+                                        if let Some(rtt) = pinger.on_pong(std::time::Instant::now()) {
+                                            let _ = update_tx.send(KalshiUpdate::Pong { rtt });
+                                        }
                                     }
                                     Some(Ok(Message::Close(_))) => {
                                         info!("[Kalshi WS] Connection closed by server");
@@ -537,10 +661,41 @@ impl KalshiWebSocket {
                                 }
                             }
 
-                            // Send periodic pings (Kalshi sends heartbeat every 10s, we respond)
-                            _ = heartbeat.tick() => {
-                                // Just keep the connection alive by checking read state
-                                debug!("[Kalshi WS] Heartbeat tick");
+                            // Ping the server too; a missed pong means the connection is dead
+                            _ = tokio::time::sleep_until(pinger.next_deadline(std::time::Instant::now()).into()) => {
+                                match pinger.poll(std::time::Instant::now()) {
+                                    PingAction::Send => {
+                                        if let Err(e) = write.send(Message::Ping(Vec::new().into())).await {
+                                            warn!("[Kalshi WS] Failed to send ping: {}", e);
+                                            break;
+                                        }
+                                    }
+                                    PingAction::TimedOut => {
+                                        warn!("[Kalshi WS] No pong within {}s, reconnecting", PONG_TIMEOUT.as_secs());
+                                        break;
+                                    }
+                                    PingAction::Wait => {}
+                                }
+                            }
+
+                            // Client closing: say goodbye instead of dropping the socket
+                            _ = shutdown.changed() => {
+                                info!("[Kalshi WS] Closing connection");
+                                let _ = write.send(Message::Close(None)).await;
+                                // Give the server a moment to answer the close frame
+                                let _ = tokio::time::timeout(CLOSE_TIMEOUT, async {
+                                    while let Some(Ok(msg)) = read.next().await {
+                                        if matches!(msg, Message::Close(_)) {
+                                            break;
+                                        }
+                                    }
+                                })
+                                .await;
+                                let _ = update_tx.send(KalshiUpdate::ConnectionState {
+                                    connected: false,
+                                    error: None,
+                                });
+                                return;
                             }
                         }
                     }
@@ -597,7 +752,17 @@ impl KalshiWebSocket {
                 );
             }
 
-            tokio::time::sleep(final_delay).await;
+            tokio::select! {
+                _ = tokio::time::sleep(final_delay) => {}
+                _ = shutdown.changed() => return,
+            }
+        }
+    }
+
+    /// Handle an incoming text frame, skipping types the filter drops
+    fn handle_frame(text: &str, filter: &MessageFilter, update_tx: &broadcast::Sender<KalshiUpdate>) {
+        if filter.admit(text) {
+            Self::handle_message(text, update_tx);
         }
     }
 
@@ -614,15 +779,16 @@ impl KalshiWebSocket {
                 KalshiResponse::Unsubscribed { id, msg } => {
                     info!("[Kalshi WS] Unsubscribed from {} (id: {})", msg.channel, id);
                 }
-                KalshiResponse::OrderbookSnapshot { msg, .. } => {
+                KalshiResponse::OrderbookSnapshot { seq, msg, .. } => {
                     debug!("[Kalshi WS] Orderbook snapshot for {}", msg.market_ticker);
-                    let orderbook = Self::convert_orderbook_snapshot(&msg);
+                    let mut orderbook = Self::convert_orderbook_snapshot(&msg);
+                    orderbook.sequence = seq.or(msg.seq);
                     let _ = update_tx.send(KalshiUpdate::OrderbookSnapshot {
                         market_ticker: msg.market_ticker,
                         orderbook,
                     });
                 }
-                KalshiResponse::OrderbookDelta { msg, .. } => {
+                KalshiResponse::OrderbookDelta { seq, msg, .. } => {
                     debug!(
                         "[Kalshi WS] Orderbook delta for {} @ {}",
                         msg.market_ticker, msg.price
@@ -632,20 +798,23 @@ impl KalshiWebSocket {
                         side: msg.side,
                         price: Decimal::from(msg.price) / Decimal::from(100),
                         delta: Decimal::from(msg.delta),
-                        seq: msg.seq,
+                        seq: seq.or(msg.seq),
                     });
                 }
                 KalshiResponse::Ticker { msg, .. } => {
                     debug!("[Kalshi WS] Ticker update for {}", msg.market_ticker);
+                    // The ticker channel carries the last YES price; NO is its complement
+                    let yes_cents = msg.yes_price.or(msg.price);
+                    let no_cents = msg.no_price.or(yes_cents.map(|p| 100 - p));
                     let _ = update_tx.send(KalshiUpdate::PriceUpdate {
                         market_ticker: msg.market_ticker,
-                        yes_price: msg.yes_price.map(|p| Decimal::from(p) / Decimal::from(100)),
-                        no_price: msg.no_price.map(|p| Decimal::from(p) / Decimal::from(100)),
+                        yes_price: yes_cents.map(|p| Decimal::from(p) / Decimal::from(100)),
+                        no_price: no_cents.map(|p| Decimal::from(p) / Decimal::from(100)),
                         volume: msg.volume.map(Decimal::from),
                     });
                 }
                 KalshiResponse::Trade { msg, .. } => {
-                    debug!("[Kalshi WS] Trade on {} @ {}", msg.market_ticker, msg.price);
+                    debug!("[Kalshi WS] Trade on {} @ {}", msg.market_ticker, msg.yes_price);
                     let trade = Self::convert_trade(&msg);
                     let _ = update_tx.send(KalshiUpdate::Trade {
                         market_ticker: msg.market_ticker,
@@ -664,68 +833,38 @@ impl KalshiWebSocket {
     }
 
     /// Convert Kalshi orderbook snapshot to internal OrderBook
+    ///
+    /// Same [price_cents, quantity] bid arrays as the REST orderbook, so asks
+    /// are derived the same way.
     fn convert_orderbook_snapshot(msg: &OrderbookSnapshotMsg) -> OrderBook {
-        let mut orderbook = OrderBook::new(msg.market_ticker.clone(), Platform::Kalshi);
-
-        // Convert YES levels (bids are buy orders, asks are sell orders)
-        // Kalshi represents orderbook differently - need to map correctly
-        for level in &msg.yes {
-            let price = Decimal::from(level.price) / Decimal::from(100);
-            let quantity = Decimal::from(level.quantity);
-            // Positive quantity = bid, negative = ask (or vice versa based on Kalshi's format)
-            if level.quantity > 0 {
-                orderbook
-                    .yes_bids
-                    .push(OrderBookLevel::new(price, quantity));
-            }
+        KalshiOrderbook {
+            yes: msg.yes.clone(),
+            no: msg.no.clone(),
         }
-
-        for level in &msg.no {
-            let price = Decimal::from(level.price) / Decimal::from(100);
-            let quantity = Decimal::from(level.quantity);
-            if level.quantity > 0 {
-                orderbook.no_bids.push(OrderBookLevel::new(price, quantity));
-            }
-        }
-
-        // Sort bids by price descending, asks by price ascending
-        orderbook.yes_bids.sort_by(|a, b| b.price.cmp(&a.price));
-        orderbook.no_bids.sort_by(|a, b| b.price.cmp(&a.price));
-
-        orderbook.timestamp = Utc::now();
-        orderbook
+        .to_order_book(&msg.market_ticker)
     }
 
     /// Convert Kalshi trade message to internal Trade
     fn convert_trade(msg: &TradeMsg) -> Trade {
-        let timestamp = msg
-            .ts
-            .and_then(|ts| {
-                chrono::DateTime::from_timestamp(ts / 1000, ((ts % 1000) * 1_000_000) as u32)
-            })
-            .unwrap_or_else(Utc::now);
-
-        Trade {
-            id: msg.trade_id.clone(),
-            market_id: msg.market_ticker.clone(),
-            platform: Platform::Kalshi,
-            timestamp,
-            price: Decimal::from(msg.price) / Decimal::from(100),
-            quantity: Decimal::from(msg.count),
-            outcome: if msg.side == "yes" {
-                TradeOutcome::Yes
-            } else {
-                TradeOutcome::No
-            },
-            side: msg.taker_side.as_ref().map(|s| {
-                if s == "buy" {
-                    TradeSide::Buy
-                } else {
-                    TradeSide::Sell
-                }
-            }),
-            transaction_hash: None, // Kalshi doesn't have on-chain transactions
+        KalshiTrade {
+            trade_id: Some(msg.trade_id.clone()),
+            ticker: Some(msg.market_ticker.clone()),
+            yes_price: Some(msg.yes_price),
+            no_price: Some(msg.no_price),
+            count: Some(msg.count),
+            created_time: msg.ts.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)),
+            taker_side: msg.taker_side.clone(),
         }
+        .to_trade(&msg.market_ticker)
+    }
+
+    /// Close the connection with a close frame and stop reconnecting
+    ///
+    /// Resolves once the connection task has exited (immediately if it
+    /// never started).
+    pub async fn close(&self) {
+        self.shutdown.send_replace(true);
+        self.shutdown.closed().await;
     }
 
     /// Subscribe to a market
@@ -815,3 +954,142 @@ impl std::fmt::Debug for KalshiWebSocket {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+    use rsa::pss::{Signature, VerifyingKey};
+    use rsa::signature::Verifier;
+    use terminal_core::Platform;
+
+    fn cents(c: i64) -> Decimal {
+        Decimal::from(c) / Decimal::from(100)
+    }
+
+    fn levels(book_side: &[OrderBookLevel]) -> Vec<(Decimal, Decimal)> {
+        book_side.iter().map(|l| (l.price, l.quantity)).collect()
+    }
+
+    #[test]
+    fn test_auth_headers_sign_timestamp_method_and_path() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let pem = private_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let verifying_key = VerifyingKey::<Sha256>::new(private_key.to_public_key());
+
+        let headers = create_auth_headers("key-id", &pem, 1_700_000_000_123).unwrap();
+        assert_eq!(headers.key, "key-id");
+        assert_eq!(headers.timestamp, "1700000000123");
+
+        let signature_bytes = BASE64.decode(&headers.signature).unwrap();
+        let signature = Signature::try_from(signature_bytes.as_slice()).unwrap();
+        verifying_key
+            .verify(b"1700000000123GET/trade-api/ws/v2", &signature)
+            .unwrap();
+        assert!(verifying_key.verify(b"1700000000124GET/trade-api/ws/v2", &signature).is_err());
+
+        // The bare base64 body (as pasted into KALSHI_PRIVATE_KEY) works too
+        let body: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+        assert!(create_auth_headers("key-id", &body, 1).is_ok());
+
+        let mut request = KALSHI_WS_URL.into_client_request().unwrap();
+        headers.apply(request.headers_mut()).unwrap();
+        assert_eq!(request.headers()["KALSHI-ACCESS-KEY"], "key-id");
+        assert_eq!(request.headers()["KALSHI-ACCESS-TIMESTAMP"], "1700000000123");
+    }
+
+    #[test]
+    fn test_auth_headers_reject_bad_key() {
+        assert!(create_auth_headers("key-id", "not a key", 1).is_err());
+        assert!(!KalshiWebSocketConfig {
+            api_key: Some("key-id".to_string()),
+            private_key_pem: None,
+            auto_reconnect: true,
+            message_filter: MessageFilter::all("type"),
+        }
+        .has_credentials());
+        assert!(KalshiWebSocketConfig::with_credentials("key-id", "pem").has_credentials());
+    }
+
+    #[test]
+    fn test_snapshot_and_deltas_update_bids_and_derived_asks() {
+        let frame = r#"{"type":"orderbook_snapshot","sid":2,"seq":1,"msg":{"market_ticker":"FED-23DEC-T3.00","yes":[[8,300],[22,333]],"no":[[54,20],[56,146]]}}"#;
+        let (tx, mut rx) = broadcast::channel(8);
+        KalshiWebSocket::handle_message(frame, &tx);
+        let KalshiUpdate::OrderbookSnapshot { mut orderbook, .. } = rx.try_recv().unwrap() else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(orderbook.platform, Platform::Kalshi);
+        assert_eq!(orderbook.sequence, Some(1));
+        assert_eq!(
+            levels(&orderbook.yes_bids),
+            vec![(cents(22), Decimal::from(333)), (cents(8), Decimal::from(300))]
+        );
+        // NO bid at 56 = YES ask at 44
+        assert_eq!(orderbook.yes_asks[0].price, cents(44));
+
+        // New YES level also shows up as a NO ask
+        apply_orderbook_delta(&mut orderbook, "yes", cents(30), Decimal::from(10));
+        assert_eq!(orderbook.yes_bids[0].price, cents(30));
+        assert_eq!(orderbook.no_asks[0].price, cents(70));
+
+        // Partial fill, then removal
+        apply_orderbook_delta(&mut orderbook, "no", cents(56), Decimal::from(-46));
+        assert_eq!(orderbook.no_bids[0].quantity, Decimal::from(100));
+        assert_eq!(orderbook.yes_asks[0].quantity, Decimal::from(100));
+        apply_orderbook_delta(&mut orderbook, "no", cents(56), Decimal::from(-100));
+        assert_eq!(levels(&orderbook.no_bids), vec![(cents(54), Decimal::from(20))]);
+        assert_eq!(levels(&orderbook.yes_asks), vec![(cents(46), Decimal::from(20))]);
+
+        // Removing a level that isn't there is a no-op
+        apply_orderbook_delta(&mut orderbook, "yes", cents(50), Decimal::from(-5));
+        assert_eq!(orderbook.yes_bids.len(), 3);
+    }
+
+    #[test]
+    fn test_ticker_and_trade_frames() {
+        let (tx, mut rx) = broadcast::channel(8);
+        KalshiWebSocket::handle_message(
+            r#"{"type":"ticker","sid":11,"msg":{"market_ticker":"FED-23DEC-T3.00","price":48,"yes_bid":45,"yes_ask":53,"volume":33896,"open_interest":20422}}"#,
+            &tx,
+        );
+        let KalshiUpdate::PriceUpdate { yes_price, no_price, .. } = rx.try_recv().unwrap() else {
+            panic!("expected a price update");
+        };
+        assert_eq!(yes_price, Some(cents(48)));
+        assert_eq!(no_price, Some(cents(52)));
+
+        KalshiWebSocket::handle_message(
+            r#"{"type":"trade","sid":11,"msg":{"trade_id":"d91bc706","market_ticker":"HIGHNY-22DEC23-B53.5","yes_price":36,"no_price":64,"count":136,"taker_side":"no","ts":1669149841}}"#,
+            &tx,
+        );
+        let KalshiUpdate::Trade { trade, .. } = rx.try_recv().unwrap() else {
+            panic!("expected a trade");
+        };
+        assert_eq!(trade.id, "d91bc706");
+        assert_eq!(trade.outcome, terminal_core::TradeOutcome::No);
+        assert_eq!(trade.quantity, Decimal::from(136));
+        assert_eq!(trade.timestamp.timestamp(), 1669149841);
+    }
+
+    #[test]
+    fn test_filter_drops_unused_types_before_parsing() {
+        let frames = [
+            r#"{"type":"fill","sid":3,"msg":{"trade_id":"x","order_id":"y"}}"#,
+            r#"{"type":"ticker","sid":11,"msg":{"market_ticker":"FED-23DEC-T3.00","price":48}}"#,
+            r#"{"type":"market_lifecycle_v2","sid":4,"msg":{"market_ticker":"FED-23DEC-T3.00"}}"#,
+            r#"{"type":"trade","sid":11,"msg":{"trade_id":"d91bc706","market_ticker":"FED-23DEC-T3.00","yes_price":36,"no_price":64,"count":1,"taker_side":"no","ts":1669149841}}"#,
+            r#"{"type":"subscribed","id":1,"msg":{"channel":"ticker","sid":11}}"#,
+        ];
+        let filter = KalshiWebSocketConfig::with_credentials("key-id", "pem").message_filter;
+        let (tx, mut rx) = broadcast::channel(8);
+        for frame in frames {
+            KalshiWebSocket::handle_frame(frame, &filter, &tx);
+        }
+
+        assert!(matches!(rx.try_recv().unwrap(), KalshiUpdate::PriceUpdate { .. }));
+        assert!(matches!(rx.try_recv().unwrap(), KalshiUpdate::Trade { .. }));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(filter.dropped(), 2);
+    }
+}
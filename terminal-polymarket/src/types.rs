@@ -106,6 +106,10 @@ pub struct PolymarketMarket {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketEvent {
+    /// Event ID
+    #[serde(default)]
+    pub id: Option<String>,
+
     /// Event slug
     #[serde(default)]
     pub slug: Option<String>,
@@ -409,6 +413,7 @@ impl PolymarketMarket {
             volume: self.parse_volume(),
             volume_24hr: None, // Individual markets don't have 24hr volume in API
             liquidity: self.parse_liquidity(),
+            open_interest: None,
             close_time: self.end_date,
             created_at: self.created_at,
             status,
@@ -418,6 +423,10 @@ impl PolymarketMarket {
             outcome_count: None,
             leading_outcome: None,
             is_multi_outcome: false,
+            event_id: self.events
+                .as_ref()
+                .and_then(|e| e.first())
+                .and_then(|e| e.id.clone()),
             options_json,
             // Sports fields
             is_sports,
@@ -430,6 +439,8 @@ impl PolymarketMarket {
             away_odds,
             spread_line: None,
             total_line: None,
+            change_1h: None,
+            change_24h: None,
             resolution_source: self.resolution_source.clone(),
             // Individual markets don't have tags - tags are on events
             tags: Vec::new(),
@@ -638,6 +649,9 @@ pub struct ClobTrade {
     /// Transaction hash
     #[serde(default)]
     pub transaction_hash: Option<String>,
+    /// Maker wallet address
+    #[serde(default)]
+    pub maker_address: Option<String>,
 }
 
 impl ClobTrade {
@@ -688,6 +702,9 @@ impl ClobTrade {
             outcome: TradeOutcome::Yes, // Default to YES for the token
             side,
             transaction_hash: self.transaction_hash.clone(),
+            outcome_id: self.asset_id.clone(),
+            maker_address: self.maker_address.clone(),
+            taker_address: None,
         }
     }
 }
@@ -721,6 +738,9 @@ pub struct DataApiTrade {
     /// Transaction hash
     #[serde(default)]
     pub transaction_hash: Option<String>,
+    /// Wallet (proxy) address of the trader who took liquidity
+    #[serde(default)]
+    pub proxy_wallet: Option<String>,
 }
 
 impl DataApiTrade {
@@ -758,6 +778,9 @@ impl DataApiTrade {
             outcome,
             side,
             transaction_hash: self.transaction_hash.clone(),
+            outcome_id: Some(self.asset.clone()),
+            maker_address: None,
+            taker_address: self.proxy_wallet.clone(),
         }
     }
 }
@@ -861,6 +884,7 @@ impl PolymarketEvent {
                 volume: self.parse_volume(),
                 volume_24hr: self.volume_24hr.map(|v| Decimal::from_str(&v.to_string()).unwrap_or(Decimal::ZERO)),
                 liquidity: self.parse_liquidity(),
+                open_interest: None,
                 close_time: self.end_date,
                 created_at: self.created_at.or(self.start_date),
                 status,
@@ -869,6 +893,7 @@ impl PolymarketEvent {
                 outcome_count: None,
                 leading_outcome: None,
                 is_multi_outcome: false,
+                event_id: Some(self.id.clone()),
                 options_json,
                 // For binary events, resolution rules may be in child market description
                 resolution_source: self.resolution_source.clone()
@@ -889,6 +914,8 @@ impl PolymarketEvent {
                 away_odds,
                 spread_line: None,
                 total_line: None,
+                change_1h: None,
+                change_24h: None,
                 tags: tags.clone(),
             }
         } else {
@@ -940,6 +967,7 @@ impl PolymarketEvent {
                 volume: self.parse_volume(),
                 volume_24hr: self.volume_24hr.map(|v| Decimal::from_str(&v.to_string()).unwrap_or(Decimal::ZERO)),
                 liquidity: self.parse_liquidity(),
+                open_interest: None,
                 close_time: self.end_date,
                 created_at: self.created_at.or(self.start_date),
                 status,
@@ -948,6 +976,7 @@ impl PolymarketEvent {
                 outcome_count: Some(self.markets.len()),
                 leading_outcome: Some(leading_name),
                 is_multi_outcome: true,
+                event_id: Some(self.id.clone()),
                 options_json,
                 // For multi-outcome events, use leading outcome's resolution rules
                 resolution_source: self.resolution_source.clone()
@@ -968,6 +997,8 @@ impl PolymarketEvent {
                 away_odds: None,
                 spread_line: None,
                 total_line: None,
+                change_1h: None,
+                change_24h: None,
                 tags,
             }
         }
@@ -11,12 +11,11 @@ use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use tokio::sync::{broadcast, mpsc, RwLock};
-use tokio::time::interval;
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
-use terminal_core::{OrderBook, OrderBookLevel, Platform, Trade, TradeOutcome, TradeSide};
+use terminal_core::{MessageFilter, OrderBook, OrderBookLevel, PingAction, PingScheduler, Platform, Trade, TradeOutcome, TradeSide};
 use crate::client::PolymarketCredentials;
 
 /// Polymarket WebSocket URL (market channel - no auth required)
@@ -35,6 +34,19 @@ const RECONNECT_WARNING_THRESHOLD: u32 = 5;
 /// Ping interval (Polymarket expects pings every 10s)
 const PING_INTERVAL: Duration = Duration::from_secs(10);
 
+/// How long a ping may go unanswered before the connection is dropped
+const PONG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long to wait for the server to answer our close frame
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a connection attempt may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Market channel `event_type`s `handle_message` understands; anything else
+/// (tick size changes and the like) is dropped before parsing
+pub const POLYMARKET_MESSAGE_TYPES: &[&str] = &["book", "price_change", "last_trade_price"];
+
 // ============================================================================
 // WebSocket Message Types (matching Polymarket's protocol)
 // ============================================================================
@@ -178,12 +190,19 @@ pub enum PolymarketUpdate {
         changes: Vec<(Decimal, Decimal, String)>, // (price, size, side)
         best_bid: Option<Decimal>,
         best_ask: Option<Decimal>,
+        /// Exchange timestamp in ms; Polymarket has no sequence numbers, so
+        /// this is what orders deltas against the book snapshot
+        timestamp: Option<u64>,
     },
     /// Last trade price
     Trade {
         asset_id: String,
         trade: Trade,
     },
+    /// Keepalive from the server (a ping, or an unmatched reply to ours)
+    Heartbeat,
+    /// Reply to our keepalive ping, with its round trip
+    Pong { rtt: Duration },
     /// Connection state change
     ConnectionState {
         connected: bool,
@@ -200,6 +219,10 @@ pub enum PolymarketUpdate {
 pub struct PolymarketWebSocketConfig {
     pub credentials: Option<PolymarketCredentials>,
     pub auto_reconnect: bool,
+    /// Market channel URLs, primary first; the rest are backups to fail over to
+    pub endpoints: Vec<String>,
+    /// Frames parsed, by their `event_type`
+    pub message_filter: MessageFilter,
 }
 
 impl Default for PolymarketWebSocketConfig {
@@ -207,6 +230,8 @@ impl Default for PolymarketWebSocketConfig {
         Self {
             credentials: PolymarketCredentials::from_env(),
             auto_reconnect: true,
+            endpoints: vec![POLYMARKET_WS_URL.to_string()],
+            message_filter: MessageFilter::only("event_type", POLYMARKET_MESSAGE_TYPES),
         }
     }
 }
@@ -220,6 +245,10 @@ pub struct PolymarketWebSocket {
     subscriptions: Arc<RwLock<HashSet<String>>>,
     /// Command sender to the WebSocket task
     command_tx: Option<mpsc::Sender<WebSocketCommand>>,
+    /// Set to close the connection for good
+    shutdown: watch::Sender<bool>,
+    /// Index into `config.endpoints` the next `start` connects to
+    endpoint: usize,
 }
 
 /// Commands sent to the WebSocket task
@@ -233,6 +262,7 @@ impl PolymarketWebSocket {
     /// Create a new Polymarket WebSocket client
     pub fn new(config: PolymarketWebSocketConfig) -> (Self, broadcast::Receiver<PolymarketUpdate>) {
         let (update_tx, update_rx) = broadcast::channel(1024);
+        let (shutdown, _) = watch::channel(false);
 
         (
             Self {
@@ -240,11 +270,43 @@ impl PolymarketWebSocket {
                 update_tx,
                 subscriptions: Arc::new(RwLock::new(HashSet::new())),
                 command_tx: None,
+                shutdown,
+                endpoint: 0,
             },
             update_rx,
         )
     }
 
+    /// URL the next `start` connects to
+    pub fn endpoint(&self) -> &str {
+        self.config
+            .endpoints
+            .get(self.endpoint)
+            .map_or(POLYMARKET_WS_URL, String::as_str)
+    }
+
+    /// Connect to `config.endpoints[index]` from the next `start` on
+    pub fn use_endpoint(&mut self, index: usize) {
+        self.endpoint = index;
+    }
+
+    /// Whether `url` accepts a WebSocket connection within `timeout`
+    ///
+    /// The probe connection is closed straight away.
+    pub async fn probe(url: &str, timeout: Duration) -> bool {
+        match tokio::time::timeout(timeout, connect_async(url)).await {
+            Ok(Ok((mut ws_stream, _))) => {
+                let _ = ws_stream.close(None).await;
+                true
+            }
+            Ok(Err(e)) => {
+                debug!("[Polymarket WS] Probe of {} failed: {}", url, e);
+                false
+            }
+            Err(_) => false,
+        }
+    }
+
     /// Start the WebSocket connection
     /// Note: Connection is lazy - it only connects when subscriptions are added
     pub async fn start(&mut self) -> Result<(), anyhow::Error> {
@@ -252,12 +314,19 @@ impl PolymarketWebSocket {
         self.command_tx = Some(command_tx);
 
         let config = self.config.clone();
+        let url = self.endpoint().to_string();
         let update_tx = self.update_tx.clone();
         let subscriptions = Arc::clone(&self.subscriptions);
+        let shutdown = self.shutdown.subscribe();
 
         tokio::spawn(async move {
-            Self::connection_loop(config, update_tx, command_rx, subscriptions).await;
-            error!("[Polymarket WS] Connection loop exited unexpectedly!");
+            let auto_reconnect = config.auto_reconnect;
+            Self::connection_loop(config, url, update_tx, command_rx, subscriptions, shutdown).await;
+            if auto_reconnect {
+                error!("[Polymarket WS] Connection loop exited unexpectedly!");
+            } else {
+                info!("[Polymarket WS] Connection loop ended");
+            }
         });
 
         Ok(())
@@ -267,9 +336,11 @@ impl PolymarketWebSocket {
     /// Note: This uses lazy connection - waits for first subscription before connecting
     async fn connection_loop(
         config: PolymarketWebSocketConfig,
+        url: String,
         update_tx: broadcast::Sender<PolymarketUpdate>,
         mut command_rx: mpsc::Receiver<WebSocketCommand>,
         subscriptions: Arc<RwLock<HashSet<String>>>,
+        mut shutdown: watch::Receiver<bool>,
     ) {
         let mut reconnect_attempts = 0u32;
         let mut pending_subscribe: Option<Vec<String>>;
@@ -278,7 +349,11 @@ impl PolymarketWebSocket {
         // This prevents the server from closing idle connections
         info!("[Polymarket WS] Waiting for subscriptions before connecting...");
         loop {
-            match command_rx.recv().await {
+            let cmd = tokio::select! {
+                cmd = command_rx.recv() => cmd,
+                _ = shutdown.changed() => return,
+            };
+            match cmd {
                 Some(WebSocketCommand::Subscribe { asset_ids }) => {
                     if !asset_ids.is_empty() {
                         info!("[Polymarket WS] Got subscription request, will connect");
@@ -298,12 +373,12 @@ impl PolymarketWebSocket {
 
         // Now we have a subscription, enter the main connection loop
         loop {
-            info!("[Polymarket WS] Connecting to {}", POLYMARKET_WS_URL);
+            info!("[Polymarket WS] Connecting to {}", url);
 
-            // Add timeout to connection attempt (10 seconds)
+            // Add timeout to connection attempt
             let connect_result = tokio::time::timeout(
-                Duration::from_secs(10),
-                connect_async(POLYMARKET_WS_URL)
+                CONNECT_TIMEOUT,
+                connect_async(url.as_str())
             ).await;
 
             match connect_result {
@@ -352,16 +427,23 @@ impl PolymarketWebSocket {
                         }
                     }
 
-                    // Create ping interval - start after first successful subscribe
-                    let mut ping_timer = interval(PING_INTERVAL);
+                    // Ping schedule - starts after first successful subscribe
+                    let mut pinger = PingScheduler::new(PING_INTERVAL, PONG_TIMEOUT);
 
                     loop {
                         tokio::select! {
                             // Handle incoming messages
                             msg = read.next() => {
                                 match msg {
+                                    Some(Ok(Message::Text(text))) if text.as_str() == "PONG" => {
+                                        let update = match pinger.on_pong(std::time::Instant::now()) {
+                                            Some(rtt) => PolymarketUpdate::Pong { rtt },
+                                            None => PolymarketUpdate::Heartbeat,
+                                        };
+                                        let _ = update_tx.send(update);
+                                    }
                                     Some(Ok(Message::Text(text))) => {
-                                        Self::handle_message(&text, &update_tx);
+                                        Self::handle_frame(&text, &config.message_filter, &update_tx);
                                     }
                                     Some(Ok(Message::Ping(data))) => {
                                         // Respond to ping
@@ -369,6 +451,7 @@ impl PolymarketWebSocket {
                                             warn!("[Polymarket WS] Failed to send pong: {}", e);
                                             break;
                                         }
+                                        let _ = update_tx.send(PolymarketUpdate::Heartbeat);
                                     }
                                     Some(Ok(Message::Close(_))) => {
                                         info!("[Polymarket WS] Connection closed by server");
@@ -388,38 +471,72 @@ impl PolymarketWebSocket {
 
                             // Handle outgoing commands
                             cmd = command_rx.recv() => {
-                                if let Some(cmd) = cmd {
-                                    match cmd {
-                                        WebSocketCommand::Subscribe { asset_ids } => {
-                                            let msg = MarketSubscribeMessage {
-                                                assets_ids: asset_ids,
-                                                msg_type: "market".to_string(),
-                                            };
-                                            if let Ok(json) = serde_json::to_string(&msg) {
-                                                // Subscribing to market
-                                                if let Err(e) = write.send(Message::Text(json.into())).await {
-                                                    warn!("[Polymarket WS] Failed to send subscribe: {}", e);
-                                                    break;
-                                                }
+                                let Some(cmd) = cmd else {
+                                    // Superseded by a restart: the newer connection owns the feed
+                                    info!("[Polymarket WS] Command channel closed, exiting");
+                                    return;
+                                };
+                                match cmd {
+                                    WebSocketCommand::Subscribe { asset_ids } => {
+                                        let msg = MarketSubscribeMessage {
+                                            assets_ids: asset_ids,
+                                            msg_type: "market".to_string(),
+                                        };
+                                        if let Ok(json) = serde_json::to_string(&msg) {
+                                            // Subscribing to market
+                                            if let Err(e) = write.send(Message::Text(json.into())).await {
+                                                warn!("[Polymarket WS] Failed to send subscribe: {}", e);
+                                                break;
                                             }
                                         }
-                                        WebSocketCommand::Unsubscribe { asset_ids: _ } => {
-                                            // Polymarket doesn't have explicit unsubscribe
-                                            // We just stop processing updates for those assets
-                                            // Unsubscribe (local only - Polymarket doesn't support server-side unsub)
-                                        }
+                                    }
+                                    WebSocketCommand::Unsubscribe { asset_ids: _ } => {
+                                        // Polymarket doesn't have explicit unsubscribe
+                                        // We just stop processing updates for those assets
+                                        // Unsubscribe (local only - Polymarket doesn't support server-side unsub)
                                     }
                                 }
                             }
 
-                            // Send periodic pings to keep connection alive
-                            _ = ping_timer.tick() => {
-                                // Send keepalive ping
-                                if let Err(e) = write.send(Message::Text("PING".to_string().into())).await {
-                                    warn!("[Polymarket WS] Failed to send ping: {}", e);
-                                    break;
+                            // Send periodic pings; a missed pong means the connection is dead
+                            _ = tokio::time::sleep_until(pinger.next_deadline(std::time::Instant::now()).into()) => {
+                                match pinger.poll(std::time::Instant::now()) {
+                                    PingAction::Send => {
+                                        if let Err(e) = write.send(Message::Text("PING".to_string().into())).await {
+                                            warn!("[Polymarket WS] Failed to send ping: {}", e);
+                                            break;
+                                        }
+                                    }
+                                    PingAction::TimedOut => {
+                                        warn!(
+                                            "[Polymarket WS] No pong within {}s, reconnecting",
+                                            PONG_TIMEOUT.as_secs()
+                                        );
+                                        break;
+                                    }
+                                    PingAction::Wait => {}
                                 }
                             }
+
+                            // Client closing: say goodbye instead of dropping the socket
+                            _ = shutdown.changed() => {
+                                info!("[Polymarket WS] Closing connection");
+                                let _ = write.send(Message::Close(None)).await;
+                                // Give the server a moment to answer the close frame
+                                let _ = tokio::time::timeout(CLOSE_TIMEOUT, async {
+                                    while let Some(Ok(msg)) = read.next().await {
+                                        if matches!(msg, Message::Close(_)) {
+                                            break;
+                                        }
+                                    }
+                                })
+                                .await;
+                                let _ = update_tx.send(PolymarketUpdate::ConnectionState {
+                                    connected: false,
+                                    error: None,
+                                });
+                                return;
+                            }
                         }
                     }
 
@@ -482,14 +599,28 @@ impl PolymarketWebSocket {
                 );
             }
 
-            tokio::time::sleep(final_delay).await;
+            tokio::select! {
+                _ = tokio::time::sleep(final_delay) => {}
+                _ = shutdown.changed() => return,
+            }
+        }
+    }
+
+    /// Handle an incoming text frame, skipping types the filter drops
+    fn handle_frame(text: &str, filter: &MessageFilter, update_tx: &broadcast::Sender<PolymarketUpdate>) {
+        if filter.admit(text) {
+            Self::handle_message(text, update_tx);
         }
     }
 
     /// Handle an incoming message from the WebSocket
     fn handle_message(text: &str, update_tx: &broadcast::Sender<PolymarketUpdate>) {
-        // Skip ping/pong responses
-        if text == "PONG" || text.is_empty() {
+        // Pong replies only count as liveness
+        if text == "PONG" {
+            let _ = update_tx.send(PolymarketUpdate::Heartbeat);
+            return;
+        }
+        if text.is_empty() {
             return;
         }
 
@@ -536,6 +667,7 @@ impl PolymarketWebSocket {
                             changes,
                             best_bid: price_change.best_bid.and_then(|b| b.parse().ok()),
                             best_ask: price_change.best_ask.and_then(|a| a.parse().ok()),
+                            timestamp: price_change.timestamp.and_then(|t| t.parse().ok()),
                         });
                     }
                 }
@@ -581,6 +713,7 @@ impl PolymarketWebSocket {
         orderbook.yes_asks.sort_by(|a, b| a.price.cmp(&b.price));
 
         orderbook.timestamp = Utc::now();
+        orderbook.sequence = msg.timestamp.as_ref().and_then(|t| t.parse().ok());
         orderbook
     }
 
@@ -619,9 +752,21 @@ impl PolymarketWebSocket {
             outcome: TradeOutcome::Yes, // Polymarket uses token for outcome
             side,
             transaction_hash: None, // WebSocket last_trade_price doesn't include tx hash
+            outcome_id: Some(msg.asset_id.clone()),
+            maker_address: None,
+            taker_address: None,
         }
     }
 
+    /// Close the connection with a close frame and stop reconnecting
+    ///
+    /// Resolves once the connection task has exited (immediately if it
+    /// never started).
+    pub async fn close(&self) {
+        self.shutdown.send_replace(true);
+        self.shutdown.closed().await;
+    }
+
     /// Subscribe to market updates for given asset IDs (token IDs)
     pub async fn subscribe(&self, asset_ids: Vec<String>) -> Result<(), anyhow::Error> {
         info!("[Polymarket WS] Subscribe called for {} asset(s)", asset_ids.len());
@@ -681,6 +826,32 @@ impl std::fmt::Debug for PolymarketWebSocket {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PolymarketWebSocket")
             .field("config", &self.config)
+            .field("endpoint", &self.endpoint())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_drops_unused_types_before_parsing() {
+        let frames = [
+            r#"{"event_type":"tick_size_change","asset_id":"t1","market":"0xabc","old_tick_size":"0.01","new_tick_size":"0.001"}"#,
+            r#"{"event_type":"book","asset_id":"t1","market":"0xabc","bids":[{"price":"0.48","size":"30"}],"asks":[{"price":"0.52","size":"25"}],"timestamp":"1700000000000"}"#,
+            r#"{"event_type":"comment_created","asset_id":"t1","body":"price looks wrong"}"#,
+            r#"{"event_type":"last_trade_price","asset_id":"t1","market":"0xabc","price":"0.50","side":"BUY","size":"10","timestamp":"1700000000500"}"#,
+        ];
+        let filter = PolymarketWebSocketConfig::default().message_filter;
+        let (tx, mut rx) = broadcast::channel(8);
+        for frame in frames {
+            PolymarketWebSocket::handle_frame(frame, &filter, &tx);
+        }
+
+        assert!(matches!(rx.try_recv().unwrap(), PolymarketUpdate::OrderbookSnapshot { .. }));
+        assert!(matches!(rx.try_recv().unwrap(), PolymarketUpdate::Trade { .. }));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(filter.dropped(), 2);
+    }
+}
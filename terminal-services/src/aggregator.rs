@@ -3,24 +3,35 @@
 //! Coordinates WebSocket connections to Kalshi and Polymarket exchanges,
 //! normalizes incoming data, and broadcasts updates to connected frontend clients.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::Serialize;
-use tokio::sync::{broadcast, RwLock};
+use parking_lot::Mutex;
+use tokio::sync::{broadcast, mpsc, Notify, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{info, warn};
 
-use terminal_core::{OrderBook, OrderBookLevel, Platform};
+use terminal_core::{ConnectionState, MessageFilter, OrderBook, OrderBookLevel, Platform, TerminalError, Trade};
+use terminal_kalshi::websocket::apply_orderbook_delta;
 use terminal_kalshi::{KalshiUpdate, KalshiWebSocket, KalshiWebSocketConfig};
-use terminal_polymarket::{PolymarketUpdate, PolymarketWebSocket, PolymarketWebSocketConfig};
-
+use terminal_polymarket::{MarketOption, PolymarketUpdate, PolymarketWebSocket, PolymarketWebSocketConfig};
+
+use crate::anomaly::{AnomalyConfig, AnomalyDetector};
+use crate::canary::is_canary_market;
+use crate::feed_metrics::{
+    LatencyHistogram, LatencySummary, MessageCounters, MessageKind, MessageTypeCounts, ThroughputWindow,
+};
+use crate::replay_buffer::{ReplayBuffer, DEFAULT_REPLAY_TRADES};
+use crate::update_coalescer::{CoalesceConfig, UpdateCoalescer};
 use crate::websocket::{SubscriptionEvent, WebSocketState};
-use crate::MarketService;
-use crate::TradeStorage;
+use crate::{MarketService, RefreshRequest};
+use crate::{DataGap, GapReason, OrderbookMetrics, TradeCollector, TradeStorage, TradeStorageError};
 
 /// Health status for a connection
 #[derive(Debug, Clone, Serialize)]
@@ -30,6 +41,23 @@ pub struct ConnectionHealth {
     pub last_message_time: Option<DateTime<Utc>>,
     pub message_count: u64,
     pub is_stale: bool,
+    /// Reconnects forced by the stale-connection watchdog
+    pub forced_reconnects: u64,
+    /// Snapshot resyncs per market after a sequence gap
+    pub book_resyncs: HashMap<String, u64>,
+    /// Feed messages per second over the last minute (heartbeats excluded)
+    pub messages_per_sec: f64,
+    /// Time from receiving a feed message to handing it to the client
+    /// fan-out (the coalescing window comes on top)
+    pub fanout_latency: LatencySummary,
+    pub message_types: MessageTypeCounts,
+    /// Round trip of the last answered keepalive ping, in µs
+    pub ping_rtt_us: Option<u64>,
+    /// Exchange URL the feed is connected (or connecting) to, for feeds with
+    /// failover endpoints
+    pub endpoint: Option<String>,
+    /// Frames of unused message types dropped before parsing
+    pub filtered_messages: u64,
 }
 
 /// Overall aggregator health
@@ -38,17 +66,794 @@ pub struct AggregatorHealth {
     pub kalshi: ConnectionHealth,
     pub polymarket: ConnectionHealth,
     pub active_subscriptions: usize,
+    /// Unwatched markets still streaming during their grace period
+    pub pending_unsubscribes: usize,
     pub healthy: bool,
+    /// Book and price updates merged away before reaching clients
+    pub coalesced_updates: u64,
+    /// Unwatched markets dropped early to stay under the subscription cap
+    pub subscription_evictions: u64,
+    /// Watched markets polled over REST because every slot has viewers
+    pub rest_fallback_markets: usize,
 }
 
 /// Stale threshold - if no message for this duration, consider connection stale
 const STALE_THRESHOLD_SECS: u64 = 60;
 
+/// First exchange reconnect delay; doubles with each failed attempt
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait between exchange reconnect attempts
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// How often the watchdog checks the exchange feeds for staleness
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default forced reconnects in a row before the watchdog holds off
+const DEFAULT_MAX_FORCED_RECONNECTS: u32 = 3;
+
+/// Checks the watchdog sits out once the limit is hit (5 minutes)
+const WATCHDOG_HOLDOFF_CHECKS: u32 = 10;
+
+/// Default wait before dropping an exchange subscription nobody watches
+const DEFAULT_UNSUBSCRIBE_GRACE: Duration = Duration::from_secs(60);
+
+/// Default cap on markets streamed per exchange connection
+const DEFAULT_MAX_EXCHANGE_SUBSCRIPTIONS: usize = 200;
+
+/// How often markets over the subscription cap are re-fetched over REST
+const REST_FALLBACK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Default mid move needed for a price tick, in units of 0.0001 (0.1 cent)
+const DEFAULT_MID_TICK_EPSILON_BPS: i64 = 10;
+
+/// Default period between orderbook snapshots
+const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Longest wait for exchange sockets to close cleanly on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Failed connects in a row on one endpoint before moving to the next
+const FAILOVER_AFTER_FAILURES: u32 = 3;
+
+/// How often the primary endpoint is probed while on a backup
+const PRIMARY_PROBE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Longest wait for a primary probe to connect
+const PRIMARY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Feed trades waiting for the storage writer before new ones are dropped
+const TRADE_WRITE_QUEUE: usize = 4096;
+
+/// Capped exponential backoff between reconnect attempts, with jitter
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    base: Duration,
+    max: Duration,
+    attempts: u32,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new(RECONNECT_BASE_DELAY, RECONNECT_MAX_DELAY)
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self { base, max, attempts: 0 }
+    }
+
+    /// Delay before the next attempt, counting it
+    ///
+    /// `jitter` in [0, 1) adds up to 25% on top of the capped delay so
+    /// reconnecting servers don't retry in lockstep.
+    pub fn next_delay(&mut self, jitter: f64) -> Duration {
+        self.attempts += 1;
+        let doublings = (self.attempts - 1).min(16);
+        let delay = self.base.saturating_mul(1 << doublings).min(self.max);
+        delay + delay.mul_f64(jitter.clamp(0.0, 1.0) * 0.25)
+    }
+
+    /// Attempts since the last successful connection
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Start over after a successful connection
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+    }
+}
+
+/// What the watchdog does about a feed on one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogAction {
+    /// Messages are flowing (or there is nothing to judge)
+    Healthy,
+    /// Stale: drop the connection and reconnect
+    Reconnect,
+    /// Stale, but the forced reconnect limit was hit; wait it out
+    HoldOff,
+}
+
+/// Stale-connection decisions for one feed
+///
+/// A feed that keeps going stale right after a forced reconnect is most
+/// likely quiet upstream, so after `max_consecutive` forced reconnects in a
+/// row it only retries once every `WATCHDOG_HOLDOFF_CHECKS` checks instead
+/// of flapping. Any healthy check starts over.
+#[derive(Debug, Clone)]
+pub struct StaleWatchdog {
+    max_consecutive: u32,
+    consecutive: u32,
+    held_checks: u32,
+}
+
+impl StaleWatchdog {
+    pub fn new(max_consecutive: u32) -> Self {
+        Self {
+            max_consecutive,
+            consecutive: 0,
+            held_checks: 0,
+        }
+    }
+
+    /// Decide on one check
+    ///
+    /// `silent_for` is how long a connected feed with active subscriptions
+    /// has gone without a message; `None` when there is nothing to judge
+    /// (disconnected, no subscriptions, or no message yet).
+    pub fn check(&mut self, silent_for: Option<Duration>) -> WatchdogAction {
+        let stale = silent_for.is_some_and(|d| d > Duration::from_secs(STALE_THRESHOLD_SECS));
+        if !stale {
+            self.consecutive = 0;
+            self.held_checks = 0;
+            return WatchdogAction::Healthy;
+        }
+
+        if self.consecutive < self.max_consecutive {
+            self.consecutive += 1;
+            return WatchdogAction::Reconnect;
+        }
+
+        self.held_checks += 1;
+        if self.held_checks >= WATCHDOG_HOLDOFF_CHECKS {
+            self.held_checks = 0;
+            self.consecutive += 1;
+            return WatchdogAction::Reconnect;
+        }
+        WatchdogAction::HoldOff
+    }
+
+    /// Forced reconnects since the feed was last healthy
+    pub fn consecutive(&self) -> u32 {
+        self.consecutive
+    }
+}
+
+/// Which of a feed's endpoints to connect to
+///
+/// Endpoint 0 is the primary. After `threshold` failed connects in a row the
+/// feed moves to the next endpoint, wrapping around; a successful connection
+/// keeps it there. While on a backup the primary is probed now and then and
+/// the feed fails back once it answers.
+#[derive(Debug, Clone)]
+pub struct EndpointFailover {
+    endpoints: usize,
+    threshold: u32,
+    active: usize,
+    failures: u32,
+}
+
+impl EndpointFailover {
+    pub fn new(endpoints: usize, threshold: u32) -> Self {
+        Self {
+            endpoints: endpoints.max(1),
+            threshold: threshold.max(1),
+            active: 0,
+            failures: 0,
+        }
+    }
+
+    /// Index of the endpoint to connect to
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Whether the feed is on a backup endpoint
+    pub fn is_failed_over(&self) -> bool {
+        self.active != 0
+    }
+
+    /// Record a failed connect (or a drop), returning the endpoint to move
+    /// to when this failure tips over the threshold
+    pub fn record_failure(&mut self) -> Option<usize> {
+        self.failures += 1;
+        if self.endpoints == 1 || self.failures < self.threshold {
+            return None;
+        }
+        self.failures = 0;
+        self.active = (self.active + 1) % self.endpoints;
+        Some(self.active)
+    }
+
+    /// The active endpoint connected; it is healthy until it fails again
+    pub fn record_connected(&mut self) {
+        self.failures = 0;
+    }
+
+    /// The primary answered a probe; returns whether to switch back to it
+    pub fn fail_back(&mut self) -> bool {
+        if !self.is_failed_over() {
+            return false;
+        }
+        self.active = 0;
+        self.failures = 0;
+        true
+    }
+}
+
+/// Exchange ids to replay after a reconnect, sorted
+///
+/// Every actively subscribed market contributes the tokens mapped to it
+/// (token_id -> market_id), or its own id when none is mapped (the same
+/// fallback `subscribe` uses when the token lookup fails).
+fn resubscription_ids(active: Option<&HashSet<String>>, token_map: &HashMap<String, String>) -> Vec<String> {
+    let Some(active) = active else {
+        return Vec::new();
+    };
+    let mut ids = BTreeSet::new();
+    for market_id in active {
+        let mut tokens = token_map
+            .iter()
+            .filter(|(_, mid)| *mid == market_id)
+            .map(|(token, _)| token.clone())
+            .peekable();
+        if tokens.peek().is_none() {
+            ids.insert(market_id.clone());
+        } else {
+            ids.extend(tokens);
+        }
+    }
+    ids.into_iter().collect()
+}
+
+/// How a feed's book sequence numbers relate between consecutive updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceRule {
+    /// Each update is exactly one past the previous (Kalshi `seq`)
+    Contiguous,
+    /// Updates never go backwards (Polymarket exchange timestamps in ms)
+    Monotonic,
+}
+
+/// What to do with one book delta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaCheck {
+    /// In order: apply it
+    Apply,
+    /// Gap, reordering, or no snapshot to apply onto: fetch a fresh snapshot
+    Resync,
+    /// A resync is already in flight; the snapshot will supersede this delta
+    Skip,
+}
+
+type BookKey = (Platform, String);
+
+/// Tracks the last applied sequence of each cached order book
+///
+/// A book is only trusted from its snapshot onwards: a delta that doesn't
+/// follow on from the last applied one marks the book for resync, and deltas
+/// are dropped until a replacement snapshot lands.
+#[derive(Debug, Default)]
+pub struct BookSequencer {
+    /// Last applied sequence, present once a snapshot was applied
+    last: HashMap<BookKey, Option<u64>>,
+    /// Books waiting on a resync snapshot
+    pending: HashSet<BookKey>,
+    /// Resyncs requested per book
+    resyncs: HashMap<BookKey, u64>,
+}
+
+impl BookSequencer {
+    /// Record a snapshot, which any in-flight resync is waiting for
+    pub fn snapshot(&mut self, platform: Platform, market_id: &str, seq: Option<u64>) {
+        let key = (platform, market_id.to_string());
+        self.pending.remove(&key);
+        self.last.insert(key, seq);
+    }
+
+    /// Check a delta against the last applied sequence, recording it if in order
+    pub fn delta(&mut self, platform: Platform, market_id: &str, seq: Option<u64>, rule: SequenceRule) -> DeltaCheck {
+        let key = (platform, market_id.to_string());
+        if self.pending.contains(&key) {
+            return DeltaCheck::Skip;
+        }
+
+        let in_order = match (self.last.get(&key), seq) {
+            (None, _) => false,
+            // Nothing to compare against; trust the feed
+            (Some(_), None) | (Some(None), Some(_)) => true,
+            (Some(Some(last)), Some(seq)) => match rule {
+                SequenceRule::Contiguous => seq == last + 1,
+                SequenceRule::Monotonic => seq >= *last,
+            },
+        };
+
+        if in_order {
+            if seq.is_some() {
+                self.last.insert(key, seq);
+            }
+            DeltaCheck::Apply
+        } else {
+            *self.resyncs.entry(key.clone()).or_default() += 1;
+            self.pending.insert(key);
+            DeltaCheck::Resync
+        }
+    }
+
+    /// Whether a resync snapshot is still wanted for the book
+    pub fn is_pending(&self, platform: Platform, market_id: &str) -> bool {
+        self.pending.contains(&(platform, market_id.to_string()))
+    }
+
+    /// Give up on a resync (the snapshot fetch failed) so the next delta retries
+    pub fn resync_failed(&mut self, platform: Platform, market_id: &str) {
+        let key = (platform, market_id.to_string());
+        self.pending.remove(&key);
+        self.last.remove(&key);
+    }
+
+    /// Stop tracking a book that is no longer streamed
+    pub fn forget(&mut self, platform: Platform, market_id: &str) {
+        let key = (platform, market_id.to_string());
+        self.last.remove(&key);
+        self.pending.remove(&key);
+    }
+
+    /// Distrust every book on a platform, e.g. after its update channel lagged
+    pub fn invalidate(&mut self, platform: Platform) {
+        self.last.retain(|(p, _), _| *p != platform);
+    }
+
+    /// Resync counts for a platform's books, by market id
+    pub fn resyncs(&self, platform: Platform) -> HashMap<String, u64> {
+        self.resyncs
+            .iter()
+            .filter(|((p, _), _)| *p == platform)
+            .map(|((_, market_id), count)| (market_id.clone(), *count))
+            .collect()
+    }
+}
+
+/// Client reference counts per exchange market, from `SubscriptionEvent`s
+///
+/// Each event is one market channel (price, book, trades, ...) gaining its
+/// first or losing its last subscriber, so a market stays referenced while
+/// any channel has clients. At zero it is released only after a grace
+/// period, so a quick resubscribe (page reload, tab switch) keeps the
+/// exchange stream instead of tearing it down and rebuilding it.
+///
+/// With a cap on streamed markets per platform, a new market takes the slot
+/// of the unwatched one released longest ago; watched markets are never
+/// evicted, so when every slot has viewers the newcomer waits in an
+/// overflow set (served by REST polling) until a slot frees up.
+#[derive(Debug)]
+pub struct SubscriptionRefs {
+    grace: Duration,
+    max_streaming: Option<usize>,
+    counts: HashMap<BookKey, usize>,
+    /// Markets subscribed on the exchange
+    streaming: HashSet<BookKey>,
+    /// Streaming markets at zero, with when they get released
+    releasing: HashMap<BookKey, tokio::time::Instant>,
+    /// Watched markets waiting for a slot
+    overflow: HashSet<BookKey>,
+    evictions: u64,
+}
+
+/// What opening a reference requires of the exchange feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Acquired {
+    /// Nothing: already streaming, or already waiting for a slot
+    Unchanged,
+    /// Subscribe on the exchange, first dropping `evict` to make room
+    Subscribe { evict: Option<BookKey> },
+    /// Every slot is watched; fall back to REST polling
+    OverCapacity,
+}
+
+impl SubscriptionRefs {
+    pub fn new(grace: Duration, max_streaming: Option<usize>) -> Self {
+        Self {
+            grace,
+            max_streaming,
+            counts: HashMap::new(),
+            streaming: HashSet::new(),
+            releasing: HashMap::new(),
+            overflow: HashSet::new(),
+            evictions: 0,
+        }
+    }
+
+    /// Add a reference
+    pub fn acquire(&mut self, platform: Platform, market_id: &str) -> Acquired {
+        let key = (platform, market_id.to_string());
+        let count = self.counts.entry(key.clone()).or_default();
+        *count += 1;
+        // Still streaming if a release was pending
+        if *count > 1 || self.releasing.remove(&key).is_some() {
+            return Acquired::Unchanged;
+        }
+
+        if self.has_free_slot(platform) {
+            self.streaming.insert(key);
+            return Acquired::Subscribe { evict: None };
+        }
+
+        // Least recently watched: released the longest ago
+        let victim = self
+            .releasing
+            .iter()
+            .filter(|((p, _), _)| *p == platform)
+            .min_by(|a, b| a.1.cmp(b.1).then_with(|| a.0 .1.cmp(&b.0 .1)))
+            .map(|(key, _)| key.clone());
+        match victim {
+            Some(victim) => {
+                self.releasing.remove(&victim);
+                self.streaming.remove(&victim);
+                self.streaming.insert(key);
+                self.evictions += 1;
+                Acquired::Subscribe { evict: Some(victim) }
+            }
+            None => {
+                self.overflow.insert(key);
+                Acquired::OverCapacity
+            }
+        }
+    }
+
+    /// Drop a reference, scheduling the release when it was the last one
+    pub fn release(&mut self, platform: Platform, market_id: &str, now: tokio::time::Instant) {
+        let key = (platform, market_id.to_string());
+        let Some(count) = self.counts.get_mut(&key) else {
+            return;
+        };
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            self.counts.remove(&key);
+            // Never streamed, so there is nothing to wind down
+            if !self.overflow.remove(&key) {
+                self.releasing.insert(key, now + self.grace);
+            }
+        }
+    }
+
+    /// When the next pending release falls due
+    pub fn next_release(&self) -> Option<tokio::time::Instant> {
+        self.releasing.values().min().copied()
+    }
+
+    /// Markets whose grace period has run out, no longer counted as streaming
+    pub fn take_expired(&mut self, now: tokio::time::Instant) -> Vec<BookKey> {
+        let expired: Vec<BookKey> = self
+            .releasing
+            .iter()
+            .filter(|(_, due)| **due <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.releasing.remove(key);
+            self.streaming.remove(key);
+        }
+        expired
+    }
+
+    /// Move waiting markets into free slots, returning the ones to subscribe
+    pub fn promote_overflow(&mut self) -> Vec<BookKey> {
+        let mut waiting: Vec<BookKey> = self.overflow.iter().cloned().collect();
+        waiting.sort_by(|a, b| a.1.cmp(&b.1));
+        let mut promoted = Vec::new();
+        for key in waiting {
+            if self.has_free_slot(key.0) {
+                self.overflow.remove(&key);
+                self.streaming.insert(key.clone());
+                promoted.push(key);
+            }
+        }
+        promoted
+    }
+
+    fn has_free_slot(&self, platform: Platform) -> bool {
+        self.max_streaming.is_none_or(|max| {
+            self.streaming.iter().filter(|(p, _)| *p == platform).count() < max
+        })
+    }
+
+    /// References currently held for a market
+    pub fn count(&self, platform: Platform, market_id: &str) -> usize {
+        self.counts.get(&(platform, market_id.to_string())).copied().unwrap_or(0)
+    }
+
+    /// Markets waiting out their grace period
+    pub fn pending_releases(&self) -> usize {
+        self.releasing.len()
+    }
+
+    /// Watched markets served by REST polling for lack of a slot
+    pub fn overflow(&self) -> Vec<BookKey> {
+        let mut keys: Vec<BookKey> = self.overflow.iter().cloned().collect();
+        keys.sort_by(|a, b| a.1.cmp(&b.1));
+        keys
+    }
+
+    /// Unwatched markets dropped early to make room
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+}
+
+/// Apply Polymarket price changes (absolute sizes per level) to a cached book
+fn apply_price_changes(book: &mut OrderBook, changes: &[(Decimal, Decimal, String)]) {
+    for (price, size, side) in changes {
+        let levels = if side == "BUY" {
+            &mut book.yes_bids
+        } else {
+            &mut book.yes_asks
+        };
+
+        if let Some(level) = levels.iter_mut().find(|l| l.price == *price) {
+            level.quantity = *size;
+            if level.quantity <= Decimal::ZERO {
+                levels.retain(|l| l.price != *price);
+            }
+        } else if *size > Decimal::ZERO {
+            levels.push(OrderBookLevel::new(*price, *size));
+        }
+    }
+
+    // Sort
+    book.yes_bids.sort_by(|a, b| b.price.cmp(&a.price));
+    book.yes_asks.sort_by(|a, b| a.price.cmp(&b.price));
+    book.timestamp = Utc::now();
+}
+
+/// Store a trade print from an exchange feed unless it is already stored,
+/// under its id or as the REST print of the same fill
+///
+/// Returns whether a row was written. Feeds replay recent prints after a
+/// reconnect and REST polling stores the same fills, so duplicates are
+/// expected and skipped; a REST print stored later replaces the feed's.
+fn persist_ws_trade(storage: &TradeStorage, trade: &Trade) -> Result<bool, TradeStorageError> {
+    if storage.fill_stored(trade)? {
+        return Ok(false);
+    }
+    storage.store_trade(trade)?;
+    Ok(true)
+}
+
+/// Where a served order book came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookSource {
+    /// The aggregator's streamed book
+    Cache,
+    /// Fetched from the exchange REST API
+    Rest,
+}
+
+/// An order book as served over REST
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentOrderBook {
+    #[serde(flatten)]
+    pub orderbook: OrderBook,
+    /// When the book last changed (last applied delta, or the REST fetch)
+    pub updated_at: DateTime<Utc>,
+    pub source: BookSource,
+}
+
+/// Serve the streamed book if there is one, otherwise fetch it
+async fn cached_or_fetched<F, Fut>(cached: Option<OrderBook>, fetch: F) -> Result<CurrentOrderBook, TerminalError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<OrderBook, TerminalError>>,
+{
+    let (orderbook, source) = match cached {
+        Some(orderbook) => (orderbook, BookSource::Cache),
+        None => (fetch().await?, BookSource::Rest),
+    };
+    Ok(CurrentOrderBook {
+        updated_at: orderbook.timestamp,
+        orderbook,
+        source,
+    })
+}
+
+/// What one snapshot pass wrote
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct SnapshotSummary {
+    /// Watched books snapshotted
+    markets: usize,
+    /// Snapshot and price rows written
+    rows: usize,
+    /// Cached books left out because nothing watches them
+    skipped: usize,
+}
+
+/// One outcome of a multi-outcome event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventOutcome {
+    /// The outcome's own market id, used for the book cache and broadcasts
+    pub market_id: String,
+    /// Token streamed from the exchange for the outcome (the YES token)
+    pub token_id: String,
+    /// Outcome name clients route on
+    pub outcome: String,
+}
+
+/// Resolves an event or condition id to the outcome markets under it
+#[async_trait]
+pub trait OutcomeResolver: Send + Sync {
+    async fn resolve_outcomes(&self, platform: Platform, event_id: &str) -> Result<Vec<EventOutcome>, TerminalError>;
+}
+
+#[async_trait]
+impl OutcomeResolver for MarketService {
+    async fn resolve_outcomes(&self, platform: Platform, event_id: &str) -> Result<Vec<EventOutcome>, TerminalError> {
+        if platform != Platform::Polymarket {
+            return Err(TerminalError::platform(
+                "kalshi",
+                "Event subscriptions are only supported for Polymarket",
+            ));
+        }
+
+        let market = self.get_market(platform, event_id).await?;
+        let json = market
+            .options_json
+            .ok_or_else(|| TerminalError::not_found(format!("No outcomes found for event {}", event_id)))?;
+        // Multi-outcome events carry full MarketOption entries
+        let options: Vec<MarketOption> = serde_json::from_str(&json)
+            .map_err(|e| TerminalError::parse(format!("{} is not a multi-outcome event: {}", event_id, e)))?;
+
+        Ok(options
+            .into_iter()
+            .filter_map(|option| {
+                Some(EventOutcome {
+                    token_id: option.clob_token_id?,
+                    market_id: option.market_id,
+                    outcome: option.name,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Top of book and mid sent on the `prices` channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MidTick {
+    pub best_bid: Decimal,
+    pub best_ask: Decimal,
+    pub mid: Decimal,
+}
+
+/// Last published mid per book; a new tick goes out only once the mid
+/// moves more than `epsilon` from it
+#[derive(Debug)]
+pub struct MidTicker {
+    epsilon: Decimal,
+    last: HashMap<BookKey, Decimal>,
+}
+
+impl MidTicker {
+    pub fn new(epsilon: Decimal) -> Self {
+        Self {
+            epsilon,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Record a changed book, returning a tick if its mid moved enough
+    ///
+    /// One-sided books have no mid and are skipped.
+    pub fn observe(&mut self, platform: Platform, market_id: &str, book: &OrderBook) -> Option<MidTick> {
+        let (best_bid, best_ask) = (book.best_yes_bid()?, book.best_yes_ask()?);
+        let mid = (best_bid + best_ask) / Decimal::TWO;
+        let key = (platform, market_id.to_string());
+        if let Some(last) = self.last.get(&key) {
+            if (mid - *last).abs() <= self.epsilon {
+                return None;
+            }
+        }
+        self.last.insert(key, mid);
+        Some(MidTick { best_bid, best_ask, mid })
+    }
+
+    /// Drop a book that stopped streaming
+    pub fn forget(&mut self, platform: Platform, market_id: &str) {
+        self.last.remove(&(platform, market_id.to_string()));
+    }
+}
+
+/// Open data-loss windows, per feed (`None` market) or per book
+#[derive(Debug, Default)]
+pub struct GapTracker {
+    open: HashMap<(Platform, Option<String>), (DateTime<Utc>, GapReason)>,
+}
+
+impl GapTracker {
+    /// Note data missing since `at`; an already open window keeps its
+    /// earlier start
+    pub fn open(&mut self, platform: Platform, market_id: Option<&str>, reason: GapReason, at: DateTime<Utc>) {
+        self.open
+            .entry((platform, market_id.map(String::from)))
+            .and_modify(|(started_at, _)| *started_at = (*started_at).min(at))
+            .or_insert((at, reason));
+    }
+
+    /// Data is flowing again; returns the window if one was open
+    pub fn close(&mut self, platform: Platform, market_id: Option<&str>, at: DateTime<Utc>) -> Option<DataGap> {
+        let (started_at, reason) = self.open.remove(&(platform, market_id.map(String::from)))?;
+        Some(DataGap {
+            platform,
+            market_id: market_id.map(String::from),
+            started_at,
+            ended_at: at.max(started_at),
+            reason,
+        })
+    }
+}
+
+/// Handles an exchange update processor works with
+#[derive(Clone)]
+struct FeedContext {
+    updates: Arc<UpdateCoalescer>,
+    orderbook_cache: Arc<RwLock<HashMap<BookKey, OrderBook>>>,
+    sequencer: Arc<RwLock<BookSequencer>>,
+    /// REST snapshots for books that resync
+    market_service: MarketService,
+    /// Queue to the feed trade writer, if persistence is enabled
+    trade_sink: Option<mpsc::Sender<Trade>>,
+    mid_ticker: Arc<Mutex<MidTicker>>,
+    anomaly_detector: Arc<Mutex<AnomalyDetector>>,
+    gap_tracker: Arc<Mutex<GapTracker>>,
+    replay: Arc<ReplayBuffer>,
+    /// Where significant mid moves, alerts and gaps are written through
+    storage: Option<Arc<TradeStorage>>,
+}
+
 /// Configuration for the MarketDataAggregator
 #[derive(Clone, Debug)]
 pub struct AggregatorConfig {
     pub kalshi_enabled: bool,
     pub polymarket_enabled: bool,
+    /// Kalshi WebSocket credentials; the feed needs both to start
+    pub kalshi_ws: KalshiWebSocketConfig,
+    /// Polymarket WebSocket endpoints, primary first
+    pub polymarket_ws: PolymarketWebSocketConfig,
+    /// Drop exchange message types the aggregator doesn't use before
+    /// parsing (each client's `message_filter`); off parses everything
+    pub filter_exchange_messages: bool,
+    /// Forced reconnects in a row before the stale watchdog backs off
+    pub max_forced_reconnects: u32,
+    /// Per-channel flush rates for client broadcasts
+    pub coalesce: CoalesceConfig,
+    /// How long a market nobody watches keeps streaming before it's dropped
+    pub unsubscribe_grace: Duration,
+    /// Markets streamed per platform before unwatched ones are evicted
+    /// (`None` for no cap)
+    pub max_exchange_subscriptions: Option<usize>,
+    /// Write trades seen on the exchange feeds to trade storage, alongside
+    /// the collector's REST polling
+    pub persist_ws_trades: bool,
+    /// How often watched books are snapshotted to trade storage
+    pub snapshot_interval: Duration,
+    /// Mid moves at or below this don't produce a price tick
+    pub mid_tick_epsilon: Decimal,
+    /// Thresholds for unusual-activity alerts on the trade feeds
+    pub anomaly: AnomalyConfig,
+    /// Recent trades per streamed market replayed to new subscribers
+    pub replay_trades: usize,
 }
 
 impl Default for AggregatorConfig {
@@ -56,6 +861,18 @@ impl Default for AggregatorConfig {
         Self {
             kalshi_enabled: true,
             polymarket_enabled: true,
+            kalshi_ws: KalshiWebSocketConfig::default(),
+            polymarket_ws: PolymarketWebSocketConfig::default(),
+            filter_exchange_messages: true,
+            max_forced_reconnects: DEFAULT_MAX_FORCED_RECONNECTS,
+            coalesce: CoalesceConfig::default(),
+            unsubscribe_grace: DEFAULT_UNSUBSCRIBE_GRACE,
+            max_exchange_subscriptions: Some(DEFAULT_MAX_EXCHANGE_SUBSCRIPTIONS),
+            persist_ws_trades: false,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            mid_tick_epsilon: Decimal::new(DEFAULT_MID_TICK_EPSILON_BPS, 4),
+            anomaly: AnomalyConfig::default(),
+            replay_trades: DEFAULT_REPLAY_TRADES,
         }
     }
 }
@@ -66,6 +883,14 @@ pub struct ConnectionMetrics {
     connected: AtomicBool,
     last_message_epoch_ms: AtomicU64,
     message_count: AtomicU64,
+    forced_reconnects: AtomicU64,
+    throughput: ThroughputWindow,
+    fanout_latency: LatencyHistogram,
+    message_types: MessageCounters,
+    /// Last keepalive round trip in µs (0 until the first pong)
+    ping_rtt_us: AtomicU64,
+    /// Frames the client's message filter dropped unparsed
+    filtered_messages: Arc<AtomicU64>,
 }
 
 impl ConnectionMetrics {
@@ -74,6 +899,12 @@ impl ConnectionMetrics {
             connected: AtomicBool::new(false),
             last_message_epoch_ms: AtomicU64::new(0),
             message_count: AtomicU64::new(0),
+            forced_reconnects: AtomicU64::new(0),
+            throughput: ThroughputWindow::default(),
+            fanout_latency: LatencyHistogram::default(),
+            message_types: MessageCounters::default(),
+            ping_rtt_us: AtomicU64::new(0),
+            filtered_messages: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -81,6 +912,10 @@ impl ConnectionMetrics {
         self.connected.store(connected, Ordering::SeqCst);
     }
 
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
     fn record_message(&self) {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -88,21 +923,73 @@ impl ConnectionMetrics {
             .as_millis() as u64;
         self.last_message_epoch_ms.store(now, Ordering::SeqCst);
         self.message_count.fetch_add(1, Ordering::SeqCst);
+        self.throughput.record(now / 1000);
+    }
+
+    /// Count a feed message by kind
+    ///
+    /// Heartbeats only prove the socket is open, so they don't count as
+    /// traffic for the stale watchdog or the message rate.
+    fn record_update(&self, kind: MessageKind) {
+        self.message_types.record(kind);
+        if kind != MessageKind::Heartbeat {
+            self.record_message();
+        }
+    }
+
+    fn record_fanout(&self, latency: Duration) {
+        self.fanout_latency.record(latency);
+    }
+
+    fn record_ping_rtt(&self, rtt: Duration) {
+        // Clamped to 1µs so a measured round trip is never read back as "none yet"
+        let us = rtt.as_micros().clamp(1, u64::MAX as u128) as u64;
+        self.ping_rtt_us.store(us, Ordering::Relaxed);
+    }
+
+    /// A client's message filter, counting its drops here
+    ///
+    /// With `enabled` off every frame gets parsed, for debugging.
+    fn counting_filter(&self, filter: &MessageFilter, enabled: bool) -> MessageFilter {
+        let filter = filter.clone().with_counter(Arc::clone(&self.filtered_messages));
+        if enabled {
+            filter
+        } else {
+            filter.allow_everything()
+        }
+    }
+
+    fn record_forced_reconnect(&self) {
+        self.forced_reconnects.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Time since the last message while connected
+    fn silent_for(&self) -> Option<Duration> {
+        let last_ms = self.last_message_epoch_ms.load(Ordering::SeqCst);
+        if !self.is_connected() || last_ms == 0 {
+            return None;
+        }
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        Some(Duration::from_millis(now_ms.saturating_sub(last_ms)))
+    }
+
+    /// When the last message (heartbeats aside) arrived
+    fn last_message_time(&self) -> Option<DateTime<Utc>> {
+        let last_ms = self.last_message_epoch_ms.load(Ordering::SeqCst);
+        if last_ms == 0 {
+            return None;
+        }
+        DateTime::from_timestamp((last_ms / 1000) as i64, ((last_ms % 1000) * 1_000_000) as u32)
     }
 
     fn get_health(&self, platform: &str) -> ConnectionHealth {
         let connected = self.connected.load(Ordering::SeqCst);
         let last_ms = self.last_message_epoch_ms.load(Ordering::SeqCst);
         let message_count = self.message_count.load(Ordering::SeqCst);
-
-        let last_message_time = if last_ms > 0 {
-            DateTime::from_timestamp(
-                (last_ms / 1000) as i64,
-                ((last_ms % 1000) * 1_000_000) as u32,
-            )
-        } else {
-            None
-        };
+        let last_message_time = self.last_message_time();
 
         let now_ms = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -121,6 +1008,14 @@ impl ConnectionMetrics {
             last_message_time,
             message_count,
             is_stale,
+            forced_reconnects: self.forced_reconnects.load(Ordering::SeqCst),
+            book_resyncs: HashMap::new(),
+            messages_per_sec: self.throughput.rate(now_ms / 1000),
+            fanout_latency: self.fanout_latency.summary(),
+            message_types: self.message_types.counts(),
+            ping_rtt_us: Some(self.ping_rtt_us.load(Ordering::Relaxed)).filter(|us| *us > 0),
+            endpoint: None,
+            filtered_messages: self.filtered_messages.load(Ordering::Relaxed),
         }
     }
 }
@@ -130,26 +1025,52 @@ pub struct MarketDataAggregator {
     config: AggregatorConfig,
     /// WebSocket state for broadcasting to frontend clients
     ws_state: Arc<WebSocketState>,
-    /// Market service for lookups (ticker <-> market_id mapping)
-    _market_service: MarketService,
+    /// Rate-limited fan-out of exchange updates to frontend clients
+    coalescer: Arc<UpdateCoalescer>,
+    /// Market service for lookups and REST snapshots when a book resyncs
+    market_service: MarketService,
     /// Kalshi WebSocket client
     kalshi_ws: Option<KalshiWebSocket>,
-    /// Polymarket WebSocket client
-    polymarket_ws: Option<PolymarketWebSocket>,
+    /// Polymarket WebSocket client (restarted in place on reconnect)
+    polymarket_ws: Arc<RwLock<Option<PolymarketWebSocket>>>,
     /// Mapping from market_id to Kalshi ticker
     kalshi_ticker_map: Arc<RwLock<HashMap<String, String>>>,
     /// Mapping from market_id to Polymarket token_id
     polymarket_token_map: Arc<RwLock<HashMap<String, String>>>,
     /// Active subscriptions per platform
     active_subscriptions: Arc<RwLock<HashMap<Platform, HashSet<String>>>>,
-    /// Local orderbook cache for applying deltas
-    orderbook_cache: Arc<RwLock<HashMap<String, OrderBook>>>,
+    /// Local orderbook cache for applying deltas, keyed by platform and market
+    orderbook_cache: Arc<RwLock<HashMap<BookKey, OrderBook>>>,
+    /// Sequence tracking for the cached books
+    book_sequencer: Arc<RwLock<BookSequencer>>,
+    /// Last mid sent per book on the prices channel
+    mid_ticker: Arc<Mutex<MidTicker>>,
+    /// Trade rate and size baselines for unusual-activity alerts
+    anomaly_detector: Arc<Mutex<AnomalyDetector>>,
+    /// Reconnects and resyncs still waiting for data to flow again
+    gap_tracker: Arc<Mutex<GapTracker>>,
+    /// Recent history per streamed market, sent to new client subscribers
+    replay: Arc<ReplayBuffer>,
+    /// Client references per market, driving exchange unsubscribes
+    subscription_refs: RwLock<SubscriptionRefs>,
     /// Health metrics for Kalshi connection
     kalshi_metrics: Arc<ConnectionMetrics>,
     /// Health metrics for Polymarket connection
     polymarket_metrics: Arc<ConnectionMetrics>,
     /// Trade storage for persisting prices and orderbook snapshots
     trade_storage: Option<Arc<TradeStorage>>,
+    /// Trade collector whose tracked markets also get book snapshots
+    trade_collector: Option<Arc<TradeCollector>>,
+    /// Queue to the blocking feed trade writer, once started
+    trade_writer: Option<mpsc::Sender<Trade>>,
+    /// Market cache refresh queue, polling markets over the subscription cap
+    refresh_tx: Option<mpsc::Sender<RefreshRequest>>,
+    /// Periodic orderbook snapshot task, stopped on shutdown
+    snapshot_task: Mutex<Option<JoinHandle<()>>>,
+    /// Looks up the outcomes of subscribed events
+    outcome_resolver: Arc<dyn OutcomeResolver>,
+    /// Outcomes streamed for each subscribed event
+    event_outcomes: RwLock<HashMap<BookKey, Vec<EventOutcome>>>,
 }
 
 impl MarketDataAggregator {
@@ -159,19 +1080,42 @@ impl MarketDataAggregator {
         ws_state: Arc<WebSocketState>,
         market_service: MarketService,
     ) -> Self {
+        let coalescer = Arc::new(UpdateCoalescer::new(Arc::clone(&ws_state), config.coalesce.clone()));
+        let subscription_refs = RwLock::new(SubscriptionRefs::new(
+            config.unsubscribe_grace,
+            config.max_exchange_subscriptions,
+        ));
+        let outcome_resolver: Arc<dyn OutcomeResolver> = Arc::new(market_service.clone());
+        let mid_ticker = Arc::new(Mutex::new(MidTicker::new(config.mid_tick_epsilon)));
+        let anomaly_detector = Arc::new(Mutex::new(AnomalyDetector::new(config.anomaly.clone())));
+        let replay = Arc::new(ReplayBuffer::new(config.replay_trades));
+        ws_state.set_replay_buffer(Arc::clone(&replay));
         Self {
             config,
             ws_state,
-            _market_service: market_service,
+            coalescer,
+            market_service,
             kalshi_ws: None,
-            polymarket_ws: None,
+            polymarket_ws: Arc::new(RwLock::new(None)),
             kalshi_ticker_map: Arc::new(RwLock::new(HashMap::new())),
             polymarket_token_map: Arc::new(RwLock::new(HashMap::new())),
             active_subscriptions: Arc::new(RwLock::new(HashMap::new())),
             orderbook_cache: Arc::new(RwLock::new(HashMap::new())),
+            book_sequencer: Arc::new(RwLock::new(BookSequencer::default())),
+            mid_ticker,
+            anomaly_detector,
+            gap_tracker: Arc::new(Mutex::new(GapTracker::default())),
+            replay,
+            subscription_refs,
             kalshi_metrics: Arc::new(ConnectionMetrics::new()),
             polymarket_metrics: Arc::new(ConnectionMetrics::new()),
             trade_storage: None,
+            trade_collector: None,
+            trade_writer: None,
+            refresh_tx: None,
+            snapshot_task: Mutex::new(None),
+            outcome_resolver,
+            event_outcomes: RwLock::new(HashMap::new()),
         }
     }
 
@@ -180,76 +1124,190 @@ impl MarketDataAggregator {
         self.trade_storage = Some(storage);
     }
 
+    /// Snapshot the books of markets this trade collector tracks, even
+    /// without a client watching
+    pub fn set_trade_collector(&mut self, collector: Arc<TradeCollector>) {
+        self.trade_collector = Some(collector);
+    }
+
+    /// Set the market cache refresh queue (see `MarketCache::refresh_sender`)
+    pub fn set_refresh_sender(&mut self, tx: mpsc::Sender<RefreshRequest>) {
+        self.refresh_tx = Some(tx);
+    }
+
+    /// Resolve event subscriptions with `resolver` instead of the market service
+    pub fn set_outcome_resolver(&mut self, resolver: Arc<dyn OutcomeResolver>) {
+        self.outcome_resolver = resolver;
+    }
+
+    /// Handles shared by the exchange update processors
+    fn feed_context(&self) -> FeedContext {
+        FeedContext {
+            updates: Arc::clone(&self.coalescer),
+            orderbook_cache: Arc::clone(&self.orderbook_cache),
+            sequencer: Arc::clone(&self.book_sequencer),
+            market_service: self.market_service.clone(),
+            trade_sink: self.trade_writer.clone(),
+            mid_ticker: Arc::clone(&self.mid_ticker),
+            anomaly_detector: Arc::clone(&self.anomaly_detector),
+            gap_tracker: Arc::clone(&self.gap_tracker),
+            replay: Arc::clone(&self.replay),
+            storage: self.trade_storage.clone(),
+        }
+    }
+
+    fn store_ws_trade(storage: &TradeStorage, trade: &Trade) {
+        if let Err(e) = persist_ws_trade(storage, trade) {
+            warn!(
+                "[Aggregator] Failed to store {:?} trade {}: {}",
+                trade.platform, trade.id, e
+            );
+        }
+    }
+
+    /// Start the feed trade writer when persistence is enabled
+    ///
+    /// SQLite inserts block, so feed trades are queued and written on a
+    /// blocking thread instead of in the feed loops. A full queue drops
+    /// trades (REST polling still stores them) rather than stall the feed.
+    fn start_trade_writer(&mut self) {
+        let Some(storage) = self.trade_storage.clone().filter(|_| self.config.persist_ws_trades) else {
+            return;
+        };
+        let (tx, mut rx) = mpsc::channel::<Trade>(TRADE_WRITE_QUEUE);
+        tokio::task::spawn_blocking(move || {
+            while let Some(trade) = rx.blocking_recv() {
+                Self::store_ws_trade(&storage, &trade);
+            }
+        });
+        self.trade_writer = Some(tx);
+    }
+
+    /// Publish a trade as if it had arrived on an exchange feed
+    pub fn publish_feed_trade(&self, trade: Trade) {
+        Self::publish_trade(&self.feed_context(), trade);
+    }
+
+    /// Queue a REST refresh for a market that isn't streaming
+    fn request_rest_refresh(&self, platform: Platform, market_id: &str) {
+        if let Some(tx) = &self.refresh_tx {
+            let _ = tx.try_send(RefreshRequest::Single {
+                platform,
+                market_id: market_id.to_string(),
+            });
+        }
+    }
+
+    /// Markets whose books are worth persisting: those streamed for
+    /// clients and those the trade collector tracks
+    async fn watched_markets(
+        active_subscriptions: &RwLock<HashMap<Platform, HashSet<String>>>,
+        trade_collector: Option<&TradeCollector>,
+    ) -> HashSet<BookKey> {
+        let mut watched: HashSet<BookKey> = {
+            let subs = active_subscriptions.read().await;
+            subs.iter()
+                .flat_map(|(platform, ids)| ids.iter().map(move |id| (*platform, id.clone())))
+                .collect()
+        };
+        if let Some(collector) = trade_collector {
+            watched.extend(collector.tracked_markets().await);
+        }
+        watched
+    }
+
+    /// Store a snapshot and best prices for every watched cached book
+    async fn snapshot_books(
+        orderbook_cache: &RwLock<HashMap<BookKey, OrderBook>>,
+        watched: &HashSet<BookKey>,
+        storage: &TradeStorage,
+    ) -> SnapshotSummary {
+        let mut summary = SnapshotSummary::default();
+
+        // Read the watched orderbooks
+        let orderbooks: Vec<(BookKey, OrderBook)> = {
+            let cache = orderbook_cache.read().await;
+            summary.skipped = cache.keys().filter(|key| !watched.contains(*key)).count();
+            cache
+                .iter()
+                .filter(|(key, _)| watched.contains(*key))
+                .map(|(key, book)| (key.clone(), book.clone()))
+                .collect()
+        };
+
+        // Snapshot each orderbook
+        for ((platform, market_id), book) in orderbooks {
+            summary.markets += 1;
+
+            // Serialize orderbook levels to JSON
+            let yes_bids = serde_json::to_string(&book.yes_bids).unwrap_or_default();
+            let yes_asks = serde_json::to_string(&book.yes_asks).unwrap_or_default();
+            let no_bids = serde_json::to_string(&book.no_bids).unwrap_or_default();
+            let no_asks = serde_json::to_string(&book.no_asks).unwrap_or_default();
+
+            // Depth metrics from the parsed book, so readers needn't parse JSON
+            let metrics = OrderbookMetrics::from_book(&book);
+
+            // Store snapshot
+            match storage.store_orderbook_snapshot(
+                platform,
+                &market_id,
+                &yes_bids,
+                &yes_asks,
+                &no_bids,
+                &no_asks,
+                &metrics,
+            ) {
+                Ok(_) => summary.rows += 1,
+                Err(e) => warn!("[Aggregator] Failed to store orderbook snapshot for {}: {}", market_id, e),
+            }
+
+            // Also store current price from best bid/ask
+            let yes_price = book.yes_bids.first().map(|l| {
+                l.price.try_into().unwrap_or_else(|_| l.price.to_string().parse().unwrap_or(0.0))
+            });
+            let no_price = book.no_bids.first().map(|l| {
+                l.price.try_into().unwrap_or_else(|_| l.price.to_string().parse().unwrap_or(0.0))
+            });
+
+            if yes_price.is_some() || no_price.is_some() {
+                match storage.store_price(platform, &market_id, yes_price, no_price) {
+                    Ok(_) => summary.rows += 1,
+                    Err(e) => warn!("[Aggregator] Failed to store price for {}: {}", market_id, e),
+                }
+            }
+        }
+
+        summary
+    }
+
     /// Start orderbook snapshot background task
     fn start_snapshot_task(
-        orderbook_cache: Arc<RwLock<HashMap<String, OrderBook>>>,
-        ticker_map: Arc<RwLock<HashMap<String, String>>>,
-        _token_map: Arc<RwLock<HashMap<String, String>>>,
+        orderbook_cache: Arc<RwLock<HashMap<BookKey, OrderBook>>>,
+        active_subscriptions: Arc<RwLock<HashMap<Platform, HashSet<String>>>>,
+        trade_collector: Option<Arc<TradeCollector>>,
         storage: Arc<TradeStorage>,
-    ) {
+        period: Duration,
+    ) -> JoinHandle<()> {
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
+            let mut interval = tokio::time::interval(period);
 
             loop {
                 interval.tick().await;
 
-                // Read current orderbooks
-                let orderbooks = {
-                    let cache = orderbook_cache.read().await;
-                    cache.clone()
-                };
-
-                if orderbooks.is_empty() {
+                let started = std::time::Instant::now();
+                let watched = Self::watched_markets(&active_subscriptions, trade_collector.as_deref()).await;
+                let summary = Self::snapshot_books(&orderbook_cache, &watched, &storage).await;
+                if summary.markets == 0 {
                     continue;
                 }
-
-                // Get ticker mappings for platform detection
-                let kalshi_tickers: HashSet<String> = {
-                    let map = ticker_map.read().await;
-                    map.values().cloned().collect()
-                };
-
-                // Snapshot each orderbook
-                for (market_id, book) in orderbooks {
-                    // Determine platform (if in kalshi ticker map, it's Kalshi)
-                    let platform = if kalshi_tickers.contains(&market_id) {
-                        Platform::Kalshi
-                    } else {
-                        Platform::Polymarket
-                    };
-
-                    // Serialize orderbook levels to JSON
-                    let yes_bids = serde_json::to_string(&book.yes_bids).unwrap_or_default();
-                    let yes_asks = serde_json::to_string(&book.yes_asks).unwrap_or_default();
-                    let no_bids = serde_json::to_string(&book.no_bids).unwrap_or_default();
-                    let no_asks = serde_json::to_string(&book.no_asks).unwrap_or_default();
-
-                    // Store snapshot
-                    if let Err(e) = storage.store_orderbook_snapshot(
-                        platform,
-                        &market_id,
-                        &yes_bids,
-                        &yes_asks,
-                        &no_bids,
-                        &no_asks,
-                    ) {
-                        warn!("[Aggregator] Failed to store orderbook snapshot for {}: {}", market_id, e);
-                    }
-
-                    // Also store current price from best bid/ask
-                    let yes_price = book.yes_bids.first().map(|l| {
-                        l.price.try_into().unwrap_or_else(|_| l.price.to_string().parse().unwrap_or(0.0))
-                    });
-                    let no_price = book.no_bids.first().map(|l| {
-                        l.price.try_into().unwrap_or_else(|_| l.price.to_string().parse().unwrap_or(0.0))
-                    });
-
-                    if yes_price.is_some() || no_price.is_some() {
-                        if let Err(e) = storage.store_price(platform, &market_id, yes_price, no_price) {
-                            warn!("[Aggregator] Failed to store price for {}: {}", market_id, e);
-                        }
-                    }
-                }
+                info!(
+                    "[Aggregator] Snapshotted {} orderbook(s), {} row(s) written in {}ms ({} unwatched skipped)",
+                    summary.markets,
+                    summary.rows,
+                    started.elapsed().as_millis(),
+                    summary.skipped
+                );
 
                 // Prune old snapshots once per day (check on each tick, but only act if needed)
                 // This is a lightweight check
@@ -272,16 +1330,363 @@ impl MarketDataAggregator {
                             warn!("[Aggregator] Failed to prune orderbook snapshots: {}", e);
                         }
                     }
+
+                    // Roll old price snapshots up to hourly/daily resolution
+                    match storage.downsample_price_snapshots() {
+                        Ok(deleted) => {
+                            if deleted > 0 {
+                                info!("[Aggregator] Downsampled {} old price snapshots", deleted);
+                            }
+                        }
+                        Err(e) => {
+                            warn!("[Aggregator] Failed to downsample price snapshots: {}", e);
+                        }
+                    }
                     LAST_PRUNE.store(now, std::sync::atomic::Ordering::SeqCst);
                 }
             }
-        });
+        });
+    }
+
+    /// Reconnect the Polymarket socket whenever it drops, replaying subscriptions
+    ///
+    /// The client runs with `auto_reconnect` off, so each connection ends
+    /// for good; this task starts a new one after a capped exponential
+    /// backoff with jitter and resubscribes every active market's tokens, so
+    /// books and trades resume without clients resubscribing. Clients get a
+    /// `connection_status` on the drop and one with `resync` once the feed is
+    /// back, to refetch the snapshots they missed. A notification on
+    /// `force_reconnect` (from the stale watchdog) counts as a drop.
+    ///
+    /// With more than one endpoint configured, repeated failures move the
+    /// feed to the next one and a primary that answers a probe gets it back;
+    /// either switch goes through the same restart and resubscribe.
+    fn start_polymarket_reconnect_task(
+        mut rx: broadcast::Receiver<PolymarketUpdate>,
+        endpoints: Vec<String>,
+        force_reconnect: Arc<Notify>,
+        polymarket_ws: Arc<RwLock<Option<PolymarketWebSocket>>>,
+        active_subscriptions: Arc<RwLock<HashMap<Platform, HashSet<String>>>>,
+        token_map: Arc<RwLock<HashMap<String, String>>>,
+        metrics: Arc<ConnectionMetrics>,
+        ws_state: Arc<WebSocketState>,
+    ) {
+        tokio::spawn(async move {
+            let mut backoff = ReconnectBackoff::default();
+            let mut failover = EndpointFailover::new(endpoints.len(), FAILOVER_AFTER_FAILURES);
+            let mut probe_timer = tokio::time::interval(PRIMARY_PROBE_INTERVAL);
+            probe_timer.tick().await;
+            loop {
+                let mut failing_back = false;
+                let dropped = tokio::select! {
+                    update = rx.recv() => match update {
+                        Ok(PolymarketUpdate::ConnectionState { connected: true, .. }) => {
+                            failover.record_connected();
+                            let resync = backoff.attempts() > 0;
+                            if resync {
+                                info!(
+                                    "[Aggregator] Polymarket WebSocket restored after {} reconnect attempt(s)",
+                                    backoff.attempts()
+                                );
+                            }
+                            backoff.reset();
+                            ws_state.broadcast_connection_status(Platform::Polymarket, ConnectionState::Connected, resync);
+                            false
+                        }
+                        Ok(PolymarketUpdate::ConnectionState { connected: false, .. }) => true,
+                        Ok(_) => false,
+                        // The skipped updates may have held the drop itself
+                        Err(broadcast::error::RecvError::Lagged(_)) => !metrics.is_connected(),
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    // Restarting supersedes the silent connection, which then
+                    // ends without reporting a drop of its own
+                    _ = force_reconnect.notified() => {
+                        metrics.set_connected(false);
+                        true
+                    }
+                    _ = probe_timer.tick(), if failover.is_failed_over() => {
+                        let primary = &endpoints[0];
+                        if !PolymarketWebSocket::probe(primary, PRIMARY_PROBE_TIMEOUT).await {
+                            continue;
+                        }
+                        failover.fail_back();
+                        info!("[Aggregator] Polymarket primary endpoint {} is back, failing back", primary);
+                        metrics.set_connected(false);
+                        backoff.reset();
+                        failing_back = true;
+                        true
+                    }
+                };
+                if !dropped {
+                    continue;
+                }
+
+                let before = failover.active();
+                if !failing_back && failover.record_failure().is_some() {
+                    warn!(
+                        "[Aggregator] Polymarket endpoint {} failed {} times in a row, failing over to {}",
+                        endpoints[before],
+                        FAILOVER_AFTER_FAILURES,
+                        endpoints[failover.active()]
+                    );
+                }
+
+                if backoff.attempts() == 0 {
+                    ws_state.broadcast_connection_status(Platform::Polymarket, ConnectionState::Disconnected, false);
+                }
+                let delay = backoff.next_delay(rand::random::<f64>());
+                warn!(
+                    "[Aggregator] Polymarket WebSocket dropped, reconnecting in {:.1}s (attempt {})",
+                    delay.as_secs_f64(),
+                    backoff.attempts()
+                );
+                tokio::time::sleep(delay).await;
+
+                let ids = {
+                    let subs = active_subscriptions.read().await;
+                    let map = token_map.read().await;
+                    resubscription_ids(subs.get(&Platform::Polymarket), &map)
+                };
+
+                let mut ws = polymarket_ws.write().await;
+                let Some(ws) = ws.as_mut() else {
+                    break;
+                };
+                ws.use_endpoint(failover.active());
+                if let Err(e) = ws.start().await {
+                    warn!("[Aggregator] Failed to restart Polymarket WebSocket: {}", e);
+                    continue;
+                }
+                ws_state.broadcast_connection_status(Platform::Polymarket, ConnectionState::Connecting, false);
+
+                // The connection opens lazily, on this first subscribe
+                if !ids.is_empty() {
+                    info!("[Aggregator] Resubscribing {} Polymarket token(s)", ids.len());
+                    if let Err(e) = ws.subscribe(ids).await {
+                        warn!("[Aggregator] Failed to resubscribe Polymarket tokens: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Force a Polymarket reconnect when the feed goes stale
+    ///
+    /// Every `WATCHDOG_INTERVAL` checks whether the connection claims to be
+    /// up with markets subscribed but has been silent past
+    /// `STALE_THRESHOLD_SECS`, and if so asks the reconnect task to cycle it.
+    fn start_polymarket_watchdog_task(
+        force_reconnect: Arc<Notify>,
+        active_subscriptions: Arc<RwLock<HashMap<Platform, HashSet<String>>>>,
+        metrics: Arc<ConnectionMetrics>,
+        max_forced_reconnects: u32,
+    ) {
+        tokio::spawn(async move {
+            let mut watchdog = StaleWatchdog::new(max_forced_reconnects);
+            let mut interval = tokio::time::interval(WATCHDOG_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let subscribed = active_subscriptions
+                    .read()
+                    .await
+                    .get(&Platform::Polymarket)
+                    .is_some_and(|markets| !markets.is_empty());
+                let silent_for = if subscribed { metrics.silent_for() } else { None };
+
+                match watchdog.check(silent_for) {
+                    WatchdogAction::Healthy => {}
+                    WatchdogAction::Reconnect => {
+                        metrics.record_forced_reconnect();
+                        warn!(
+                            "[Aggregator] Polymarket feed stale for {}s, forcing reconnect ({} in a row)",
+                            silent_for.unwrap_or_default().as_secs(),
+                            watchdog.consecutive()
+                        );
+                        force_reconnect.notify_one();
+                    }
+                    WatchdogAction::HoldOff => {
+                        warn!(
+                            "[Aggregator] Polymarket feed stale for {}s after {} forced reconnects, holding off",
+                            silent_for.unwrap_or_default().as_secs(),
+                            watchdog.consecutive()
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    /// Replace a book with a resync snapshot, unless one already superseded it
+    ///
+    /// Returns whether the snapshot was applied.
+    async fn apply_resync_snapshot(
+        platform: Platform,
+        market_id: &str,
+        book: &OrderBook,
+        orderbook_cache: &RwLock<HashMap<BookKey, OrderBook>>,
+        sequencer: &RwLock<BookSequencer>,
+    ) -> bool {
+        let mut sequencer = sequencer.write().await;
+        if !sequencer.is_pending(platform, market_id) {
+            return false;
+        }
+        orderbook_cache.write().await.insert((platform, market_id.to_string()), book.clone());
+        sequencer.snapshot(platform, market_id, book.sequence);
+        true
+    }
+
+    /// Fetch a fresh REST snapshot for a book whose deltas went out of sequence
+    fn spawn_book_resync(platform: Platform, market_id: String, feed: FeedContext) {
+        warn!("[Aggregator] {:?} book {} out of sequence, resyncing", platform, market_id);
+        feed.gap_tracker.lock().open(platform, Some(&market_id), GapReason::Resync, Utc::now());
+        tokio::spawn(async move {
+            match feed.market_service.get_orderbook(platform, &market_id).await {
+                Ok(book) => {
+                    // Whichever snapshot lands first, the book is whole again
+                    let gap = feed.gap_tracker.lock().close(platform, Some(&market_id), Utc::now());
+                    if let Some(gap) = gap {
+                        Self::store_gap(&feed, &gap);
+                    }
+                    if Self::apply_resync_snapshot(platform, &market_id, &book, &feed.orderbook_cache, &feed.sequencer).await {
+                        Self::publish_mid_tick(&feed, platform, &market_id, &book);
+                        feed.updates.orderbook(platform, market_id, book);
+                    }
+                }
+                Err(e) => {
+                    warn!("[Aggregator] Failed to resync {:?} book {}: {}", platform, market_id, e);
+                    feed.sequencer.write().await.resync_failed(platform, &market_id);
+                }
+            }
+        });
+    }
+
+    /// Keep a changed book for replay and send a mid tick if its mid moved
+    /// past the threshold, writing the move through to price snapshots
+    fn publish_mid_tick(feed: &FeedContext, platform: Platform, market_id: &str, book: &OrderBook) {
+        feed.replay.record_book(platform, market_id, book);
+        let Some(tick) = feed.mid_ticker.lock().observe(platform, market_id, book) else {
+            return;
+        };
+        feed.replay.record_tick(platform, market_id, &tick);
+        feed.updates.ws_state().broadcast_price_tick(
+            platform,
+            market_id.to_string(),
+            tick.best_bid,
+            tick.best_ask,
+            tick.mid,
+        );
+
+        let (Some(storage), Ok(mid)) = (&feed.storage, f64::try_from(tick.mid)) else {
+            return;
+        };
+        if let Err(e) = storage.store_price_snapshot(platform, market_id, mid, Some(1.0 - mid)) {
+            warn!("[Aggregator] Failed to store price snapshot for {}: {}", market_id, e);
+        }
+    }
+
+    /// Open or close the feed-wide gap for a connection state change
+    ///
+    /// A drop opens a window from the last message before it (later failed
+    /// attempts keep the earlier start). Coming back closes it; a connection
+    /// taken down without a drop event (a forced reconnect) counts from its
+    /// last message too. The very first connect has no messages before it
+    /// and records nothing.
+    fn track_connection_gap(
+        feed: &FeedContext,
+        platform: Platform,
+        connected: bool,
+        was_connected: bool,
+        last_message: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) {
+        let mut tracker = feed.gap_tracker.lock();
+        if !connected || !was_connected {
+            if let Some(at) = last_message {
+                tracker.open(platform, None, GapReason::Reconnect, at);
+            }
+        }
+        if !connected {
+            return;
+        }
+        let gap = tracker.close(platform, None, now);
+        drop(tracker);
+        if let Some(gap) = gap {
+            Self::store_gap(feed, &gap);
+        }
+    }
+
+    fn store_gap(feed: &FeedContext, gap: &DataGap) {
+        info!(
+            "[Aggregator] {:?} {} gap{} of {}s",
+            gap.platform,
+            gap.reason.as_str(),
+            gap.market_id.as_deref().map(|m| format!(" on {}", m)).unwrap_or_default(),
+            (gap.ended_at - gap.started_at).num_seconds()
+        );
+        if let Some(storage) = &feed.storage {
+            if let Err(e) = storage.store_gap(gap) {
+                warn!("[Aggregator] Failed to store {:?} gap: {}", gap.platform, e);
+            }
+        }
+    }
+
+    /// Persist, alert on, keep for replay and broadcast a feed trade
+    fn publish_trade(feed: &FeedContext, trade: Trade) {
+        // Canary trades exercise the broadcast path only; the canary
+        // stores its own and removes them after each run
+        if !is_canary_market(&trade.market_id) {
+            if let Some(sink) = &feed.trade_sink {
+                if let Err(mpsc::error::TrySendError::Full(dropped)) = sink.try_send(trade.clone()) {
+                    warn!(
+                        "[Aggregator] Trade writer queue full, dropped {:?} trade {}",
+                        dropped.platform, dropped.id
+                    );
+                }
+            }
+            Self::raise_alerts(feed, &trade);
+            feed.replay.record_trade(&trade);
+        }
+        feed.updates.trade(trade);
+    }
+
+    /// Run a feed trade through the anomaly detector, broadcasting and
+    /// storing any alerts it raises
+    fn raise_alerts(feed: &FeedContext, trade: &Trade) {
+        let alerts = feed.anomaly_detector.lock().observe(trade);
+        for alert in alerts {
+            info!(
+                "[Aggregator] {} alert on {:?} {}: {:.2}",
+                alert.kind.as_str(),
+                alert.platform,
+                alert.market_id,
+                alert.magnitude
+            );
+            if let Some(storage) = &feed.storage {
+                if let Err(e) = storage.store_alert(&alert) {
+                    warn!("[Aggregator] Failed to store alert for {}: {}", alert.market_id, e);
+                }
+            }
+            feed.updates.ws_state().broadcast_market_alert(alert);
+        }
     }
 
     /// Get health status for all connections
     pub async fn get_health(&self) -> AggregatorHealth {
-        let kalshi_health = self.kalshi_metrics.get_health("kalshi");
-        let polymarket_health = self.polymarket_metrics.get_health("polymarket");
+        let mut kalshi_health = self.kalshi_metrics.get_health("kalshi");
+        let mut polymarket_health = self.polymarket_metrics.get_health("polymarket");
+        {
+            let sequencer = self.book_sequencer.read().await;
+            kalshi_health.book_resyncs = sequencer.resyncs(Platform::Kalshi);
+            polymarket_health.book_resyncs = sequencer.resyncs(Platform::Polymarket);
+        }
+        polymarket_health.endpoint = self
+            .polymarket_ws
+            .read()
+            .await
+            .as_ref()
+            .map(|ws| ws.endpoint().to_string());
 
         let active_subs = {
             let subs = self.active_subscriptions.read().await;
@@ -290,12 +1695,17 @@ impl MarketDataAggregator {
 
         let healthy = (!self.config.kalshi_enabled || (!kalshi_health.is_stale))
             && (!self.config.polymarket_enabled || (!polymarket_health.is_stale));
+        let refs = self.subscription_refs.read().await;
 
         AggregatorHealth {
             kalshi: kalshi_health,
             polymarket: polymarket_health,
             active_subscriptions: active_subs,
+            pending_unsubscribes: refs.pending_releases(),
             healthy,
+            coalesced_updates: self.coalescer.merged_count(),
+            subscription_evictions: refs.evictions(),
+            rest_fallback_markets: refs.overflow().len(),
         }
     }
 
@@ -303,28 +1713,36 @@ impl MarketDataAggregator {
     pub async fn start(&mut self) -> Result<(), anyhow::Error> {
         info!("[Aggregator] Starting MarketDataAggregator");
 
+        // Book and price broadcasts are flushed at a bounded rate
+        self.coalescer.start();
+
+        // Before the feed loops take their handles to its queue
+        self.start_trade_writer();
+
+        // Kalshi's WebSocket only accepts signed connections
+        if self.config.kalshi_enabled && !self.config.kalshi_ws.has_credentials() {
+            warn!("[Aggregator] Kalshi WebSocket enabled without KALSHI_API_KEY and a private key, skipping");
+            self.config.kalshi_enabled = false;
+        }
+
         // Start Kalshi WebSocket
         if self.config.kalshi_enabled {
-            let kalshi_config = KalshiWebSocketConfig::default();
+            let kalshi_config = KalshiWebSocketConfig {
+                message_filter: self
+                    .kalshi_metrics
+                    .counting_filter(&self.config.kalshi_ws.message_filter, self.config.filter_exchange_messages),
+                ..self.config.kalshi_ws.clone()
+            };
             let (mut kalshi_ws, kalshi_rx) = KalshiWebSocket::new(kalshi_config);
 
             kalshi_ws.start().await?;
 
             // Spawn task to process Kalshi updates
-            let ws_state = Arc::clone(&self.ws_state);
-            let ticker_map = Arc::clone(&self.kalshi_ticker_map);
-            let orderbook_cache = Arc::clone(&self.orderbook_cache);
+            let feed = self.feed_context();
             let metrics = Arc::clone(&self.kalshi_metrics);
 
             tokio::spawn(async move {
-                Self::process_kalshi_updates(
-                    kalshi_rx,
-                    ws_state,
-                    ticker_map,
-                    orderbook_cache,
-                    metrics,
-                )
-                .await;
+                Self::process_kalshi_updates(kalshi_rx, feed, metrics).await;
             });
 
             self.kalshi_ws = Some(kalshi_ws);
@@ -333,40 +1751,59 @@ impl MarketDataAggregator {
 
         // Start Polymarket WebSocket
         if self.config.polymarket_enabled {
-            let polymarket_config = PolymarketWebSocketConfig::default();
+            // Reconnects are driven here so subscriptions can be replayed
+            let polymarket_config = PolymarketWebSocketConfig {
+                auto_reconnect: false,
+                message_filter: self
+                    .polymarket_metrics
+                    .counting_filter(&self.config.polymarket_ws.message_filter, self.config.filter_exchange_messages),
+                ..self.config.polymarket_ws.clone()
+            };
+            let endpoints = polymarket_config.endpoints.clone();
             let (mut polymarket_ws, polymarket_rx) = PolymarketWebSocket::new(polymarket_config);
 
             polymarket_ws.start().await?;
+            let force_reconnect = Arc::new(Notify::new());
+            Self::start_polymarket_reconnect_task(
+                polymarket_ws.subscribe_updates(),
+                endpoints,
+                Arc::clone(&force_reconnect),
+                Arc::clone(&self.polymarket_ws),
+                Arc::clone(&self.active_subscriptions),
+                Arc::clone(&self.polymarket_token_map),
+                Arc::clone(&self.polymarket_metrics),
+                Arc::clone(&self.ws_state),
+            );
+            Self::start_polymarket_watchdog_task(
+                force_reconnect,
+                Arc::clone(&self.active_subscriptions),
+                Arc::clone(&self.polymarket_metrics),
+                self.config.max_forced_reconnects,
+            );
 
             // Spawn task to process Polymarket updates
-            let ws_state = Arc::clone(&self.ws_state);
+            let feed = self.feed_context();
             let token_map = Arc::clone(&self.polymarket_token_map);
-            let orderbook_cache = Arc::clone(&self.orderbook_cache);
             let metrics = Arc::clone(&self.polymarket_metrics);
 
             tokio::spawn(async move {
-                Self::process_polymarket_updates(
-                    polymarket_rx,
-                    ws_state,
-                    token_map,
-                    orderbook_cache,
-                    metrics,
-                )
-                .await;
+                Self::process_polymarket_updates(polymarket_rx, feed, token_map, metrics).await;
             });
 
-            self.polymarket_ws = Some(polymarket_ws);
+            *self.polymarket_ws.write().await = Some(polymarket_ws);
             info!("[Aggregator] Polymarket WebSocket started");
         }
 
         // Start orderbook snapshot task if storage is configured
         if let Some(ref storage) = self.trade_storage {
-            Self::start_snapshot_task(
+            let task = Self::start_snapshot_task(
                 Arc::clone(&self.orderbook_cache),
-                Arc::clone(&self.kalshi_ticker_map),
-                Arc::clone(&self.polymarket_token_map),
+                Arc::clone(&self.active_subscriptions),
+                self.trade_collector.clone(),
                 Arc::clone(storage),
+                self.config.snapshot_interval,
             );
+            *self.snapshot_task.lock() = Some(task);
             info!("[Aggregator] Orderbook snapshot task started");
         }
 
@@ -454,7 +1891,7 @@ impl MarketDataAggregator {
                 }
             }
             Platform::Polymarket => {
-                if let Some(ref ws) = self.polymarket_ws {
+                if let Some(ref ws) = *self.polymarket_ws.read().await {
                     // For Polymarket, we need to look up the token_id
                     // The market_id might be the event_id, we need the CLOB token_id
                     let token_id = self.get_polymarket_token_id(market_id).await?;
@@ -518,7 +1955,7 @@ impl MarketDataAggregator {
                 }
             }
             Platform::Polymarket => {
-                if let Some(ref ws) = self.polymarket_ws {
+                if let Some(ref ws) = *self.polymarket_ws.read().await {
                     // Find and remove from token map
                     let token_id = {
                         let map = self.polymarket_token_map.read().await;
@@ -545,6 +1982,13 @@ impl MarketDataAggregator {
             }
         }
 
+        // Nothing streams into these any more
+        self.orderbook_cache.write().await.remove(&(platform, market_id.to_string()));
+        self.book_sequencer.write().await.forget(platform, market_id);
+        self.mid_ticker.lock().forget(platform, market_id);
+        self.anomaly_detector.lock().forget(platform, market_id);
+        self.replay.forget(platform, market_id);
+
         Ok(())
     }
 
@@ -579,18 +2023,29 @@ impl MarketDataAggregator {
     /// Process Kalshi WebSocket updates
     async fn process_kalshi_updates(
         mut rx: broadcast::Receiver<KalshiUpdate>,
-        ws_state: Arc<WebSocketState>,
-        _ticker_map: Arc<RwLock<HashMap<String, String>>>,
-        orderbook_cache: Arc<RwLock<HashMap<String, OrderBook>>>,
+        feed: FeedContext,
         metrics: Arc<ConnectionMetrics>,
     ) {
         info!("[Aggregator] Starting Kalshi update processor");
+        let FeedContext {
+            updates,
+            orderbook_cache,
+            sequencer,
+            ..
+        } = feed.clone();
+
+        // The client reconnects and resubscribes on its own; a fresh snapshot
+        // follows, but clients still missed whatever happened in between
+        let mut dropped = false;
 
         loop {
             match rx.recv().await {
                 Ok(update) => {
                     // Record message for health tracking
-                    metrics.record_message();
+                    let received = std::time::Instant::now();
+                    let kind = MessageKind::from(&update);
+                    let last_message = metrics.last_message_time();
+                    metrics.record_update(kind);
 
                     match update {
                         KalshiUpdate::OrderbookSnapshot {
@@ -602,11 +2057,13 @@ impl MarketDataAggregator {
                             // Cache the orderbook
                             {
                                 let mut cache = orderbook_cache.write().await;
-                                cache.insert(market_ticker.clone(), orderbook.clone());
+                                cache.insert((Platform::Kalshi, market_ticker.clone()), orderbook.clone());
                             }
+                            sequencer.write().await.snapshot(Platform::Kalshi, &market_ticker, orderbook.sequence);
+                            Self::publish_mid_tick(&feed, Platform::Kalshi, &market_ticker, &orderbook);
 
                             // Broadcast to clients
-                            ws_state.broadcast_orderbook_update(
+                            updates.orderbook(
                                 Platform::Kalshi,
                                 market_ticker,
                                 orderbook,
@@ -617,44 +2074,36 @@ impl MarketDataAggregator {
                             side,
                             price,
                             delta,
-                            seq: _,
+                            seq,
                         } => {
-                            // Kalshi orderbook delta received
+                            let check = sequencer.write().await.delta(
+                                Platform::Kalshi,
+                                &market_ticker,
+                                seq,
+                                SequenceRule::Contiguous,
+                            );
+                            match check {
+                                DeltaCheck::Apply => {}
+                                DeltaCheck::Skip => continue,
+                                DeltaCheck::Resync => {
+                                    Self::spawn_book_resync(Platform::Kalshi, market_ticker, feed.clone());
+                                    continue;
+                                }
+                            }
 
                             // Apply delta to cached orderbook
                             let updated_book = {
                                 let mut cache = orderbook_cache.write().await;
-                                if let Some(book) = cache.get_mut(&market_ticker) {
-                                    // Apply the delta
-                                    let levels = if side == "yes" {
-                                        &mut book.yes_bids
-                                    } else {
-                                        &mut book.no_bids
-                                    };
-
-                                    // Find and update or add the level
-                                    if let Some(level) =
-                                        levels.iter_mut().find(|l| l.price == price)
-                                    {
-                                        level.quantity += delta;
-                                        if level.quantity <= Decimal::ZERO {
-                                            levels.retain(|l| l.price != price);
-                                        }
-                                    } else if delta > Decimal::ZERO {
-                                        levels.push(OrderBookLevel::new(price, delta));
-                                        levels.sort_by(|a, b| b.price.cmp(&a.price));
-                                    }
-
-                                    book.timestamp = Utc::now();
-                                    Some(book.clone())
-                                } else {
-                                    None
-                                }
+                                cache.get_mut(&(Platform::Kalshi, market_ticker.clone())).map(|book| {
+                                    apply_orderbook_delta(book, &side, price, delta);
+                                    book.clone()
+                                })
                             };
 
                             // Broadcast updated orderbook
                             if let Some(book) = updated_book {
-                                ws_state.broadcast_orderbook_update(
+                                Self::publish_mid_tick(&feed, Platform::Kalshi, &market_ticker, &book);
+                                updates.orderbook(
                                     Platform::Kalshi,
                                     market_ticker,
                                     book,
@@ -670,7 +2119,7 @@ impl MarketDataAggregator {
                             // Kalshi price update received
 
                             if let (Some(yes), Some(no)) = (yes_price, no_price) {
-                                ws_state.broadcast_price_update(
+                                updates.price(
                                     Platform::Kalshi,
                                     market_ticker,
                                     yes,
@@ -683,20 +2132,36 @@ impl MarketDataAggregator {
                             trade,
                         } => {
                             // Kalshi trade received
-                            ws_state.broadcast_trade(trade);
+                            Self::publish_trade(&feed, trade);
                         }
+                        KalshiUpdate::Heartbeat => {}
+                        KalshiUpdate::Pong { rtt } => metrics.record_ping_rtt(rtt),
                         KalshiUpdate::ConnectionState { connected, error } => {
+                            let was_connected = metrics.is_connected();
+                            Self::track_connection_gap(&feed, Platform::Kalshi, connected, was_connected, last_message, Utc::now());
                             metrics.set_connected(connected);
                             if connected {
                                 info!("[Aggregator] Kalshi WebSocket connected");
+                                updates.ws_state().broadcast_connection_status(Platform::Kalshi, ConnectionState::Connected, dropped);
+                                dropped = false;
                             } else {
                                 warn!("[Aggregator] Kalshi WebSocket disconnected: {:?}", error);
+                                if !dropped {
+                                    updates.ws_state().broadcast_connection_status(Platform::Kalshi, ConnectionState::Disconnected, false);
+                                }
+                                dropped = true;
                             }
                         }
                     }
+
+                    if kind.is_broadcast() {
+                        metrics.record_fanout(received.elapsed());
+                    }
                 }
                 Err(broadcast::error::RecvError::Lagged(n)) => {
                     warn!("[Aggregator] Kalshi update receiver lagged {} messages", n);
+                    // Skipped deltas leave every book suspect
+                    sequencer.write().await.invalidate(Platform::Kalshi);
                 }
                 Err(broadcast::error::RecvError::Closed) => {
                     info!("[Aggregator] Kalshi update channel closed");
@@ -709,18 +2174,26 @@ impl MarketDataAggregator {
     /// Process Polymarket WebSocket updates
     async fn process_polymarket_updates(
         mut rx: broadcast::Receiver<PolymarketUpdate>,
-        ws_state: Arc<WebSocketState>,
+        feed: FeedContext,
         token_map: Arc<RwLock<HashMap<String, String>>>,
-        orderbook_cache: Arc<RwLock<HashMap<String, OrderBook>>>,
         metrics: Arc<ConnectionMetrics>,
     ) {
         info!("[Aggregator] Starting Polymarket update processor");
+        let FeedContext {
+            updates,
+            orderbook_cache,
+            sequencer,
+            ..
+        } = feed.clone();
 
         loop {
             match rx.recv().await {
                 Ok(update) => {
                     // Record message for health tracking
-                    metrics.record_message();
+                    let received = std::time::Instant::now();
+                    let kind = MessageKind::from(&update);
+                    let last_message = metrics.last_message_time();
+                    metrics.record_update(kind);
 
                     match update {
                         PolymarketUpdate::OrderbookSnapshot {
@@ -740,11 +2213,13 @@ impl MarketDataAggregator {
                             // Cache the orderbook
                             {
                                 let mut cache = orderbook_cache.write().await;
-                                cache.insert(market_id.clone(), orderbook.clone());
+                                cache.insert((Platform::Polymarket, market_id.clone()), orderbook.clone());
                             }
+                            sequencer.write().await.snapshot(Platform::Polymarket, &market_id, orderbook.sequence);
+                            Self::publish_mid_tick(&feed, Platform::Polymarket, &market_id, &orderbook);
 
                             // Broadcast to clients
-                            ws_state.broadcast_orderbook_update(
+                            updates.orderbook(
                                 Platform::Polymarket,
                                 market_id,
                                 orderbook,
@@ -755,6 +2230,7 @@ impl MarketDataAggregator {
                             changes,
                             best_bid,
                             best_ask,
+                            timestamp,
                         } => {
                             let market_id = {
                                 let map = token_map.read().await;
@@ -765,43 +2241,31 @@ impl MarketDataAggregator {
 
                             // Polymarket price change received
 
-                            // Apply changes to cached orderbook
-                            let updated_book = {
-                                let mut cache = orderbook_cache.write().await;
-                                if let Some(book) = cache.get_mut(&market_id) {
-                                    for (price, size, side) in changes {
-                                        let levels = if side == "BUY" {
-                                            &mut book.yes_bids
-                                        } else {
-                                            &mut book.yes_asks
-                                        };
-
-                                        if let Some(level) =
-                                            levels.iter_mut().find(|l| l.price == price)
-                                        {
-                                            level.quantity = size;
-                                            if level.quantity <= Decimal::ZERO {
-                                                levels.retain(|l| l.price != price);
-                                            }
-                                        } else if size > Decimal::ZERO {
-                                            levels.push(OrderBookLevel::new(price, size));
-                                        }
-                                    }
-
-                                    // Sort
-                                    book.yes_bids.sort_by(|a, b| b.price.cmp(&a.price));
-                                    book.yes_asks.sort_by(|a, b| a.price.cmp(&b.price));
-                                    book.timestamp = Utc::now();
-
-                                    Some(book.clone())
-                                } else {
+                            let check = sequencer.write().await.delta(
+                                Platform::Polymarket,
+                                &market_id,
+                                timestamp,
+                                SequenceRule::Monotonic,
+                            );
+                            let updated_book = match check {
+                                DeltaCheck::Apply => {
+                                    let mut cache = orderbook_cache.write().await;
+                                    cache.get_mut(&(Platform::Polymarket, market_id.clone())).map(|book| {
+                                        apply_price_changes(book, &changes);
+                                        book.clone()
+                                    })
+                                }
+                                DeltaCheck::Skip => None,
+                                DeltaCheck::Resync => {
+                                    Self::spawn_book_resync(Platform::Polymarket, market_id.clone(), feed.clone());
                                     None
                                 }
                             };
 
                             // Broadcast updated orderbook
                             if let Some(book) = updated_book {
-                                ws_state.broadcast_orderbook_update(
+                                Self::publish_mid_tick(&feed, Platform::Polymarket, &market_id, &book);
+                                updates.orderbook(
                                     Platform::Polymarket,
                                     market_id.clone(),
                                     book,
@@ -814,7 +2278,7 @@ impl MarketDataAggregator {
                                 let yes_price = bid;
                                 let no_price = Decimal::ONE - bid;
 
-                                ws_state.broadcast_price_update(
+                                updates.price(
                                     Platform::Polymarket,
                                     market_id,
                                     yes_price,
@@ -836,9 +2300,13 @@ impl MarketDataAggregator {
                             let mut trade = trade;
                             trade.market_id = market_id;
 
-                            ws_state.broadcast_trade(trade);
+                            Self::publish_trade(&feed, trade);
                         }
+                        PolymarketUpdate::Heartbeat => {}
+                        PolymarketUpdate::Pong { rtt } => metrics.record_ping_rtt(rtt),
                         PolymarketUpdate::ConnectionState { connected, error } => {
+                            let was_connected = metrics.is_connected();
+                            Self::track_connection_gap(&feed, Platform::Polymarket, connected, was_connected, last_message, Utc::now());
                             metrics.set_connected(connected);
                             if connected {
                                 info!("[Aggregator] Polymarket WebSocket connected");
@@ -850,12 +2318,18 @@ impl MarketDataAggregator {
                             }
                         }
                     }
+
+                    if kind.is_broadcast() {
+                        metrics.record_fanout(received.elapsed());
+                    }
                 }
                 Err(broadcast::error::RecvError::Lagged(n)) => {
                     warn!(
                         "[Aggregator] Polymarket update receiver lagged {} messages",
                         n
                     );
+                    // Skipped deltas leave every book suspect
+                    sequencer.write().await.invalidate(Platform::Polymarket);
                 }
                 Err(broadcast::error::RecvError::Closed) => {
                     info!("[Aggregator] Polymarket update channel closed");
@@ -865,6 +2339,73 @@ impl MarketDataAggregator {
         }
     }
 
+    /// Stop streaming and persist the final state of every cached book
+    ///
+    /// Stops the snapshot task, closes the exchange sockets with close
+    /// frames (giving up after `SHUTDOWN_TIMEOUT`), then writes one last
+    /// snapshot and price row per book so nothing since the previous tick
+    /// is lost.
+    pub async fn shutdown(&self) {
+        info!("[Aggregator] Shutting down");
+
+        let snapshot_task = self.snapshot_task.lock().take();
+        if let Some(task) = snapshot_task {
+            // Only aborts at an await, so never midway through a write
+            task.abort();
+            let _ = task.await;
+        }
+
+        // Taking the client also stops the reconnect task from restarting it
+        let polymarket_ws = self.polymarket_ws.write().await.take();
+        let close_sockets = async {
+            let kalshi = async {
+                if let Some(ws) = &self.kalshi_ws {
+                    ws.close().await;
+                }
+            };
+            let polymarket = async {
+                if let Some(ws) = &polymarket_ws {
+                    ws.close().await;
+                }
+            };
+            tokio::join!(kalshi, polymarket);
+        };
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, close_sockets).await.is_err() {
+            warn!(
+                "[Aggregator] Exchange sockets didn't close within {}s, dropping them",
+                SHUTDOWN_TIMEOUT.as_secs()
+            );
+        }
+
+        if let Some(storage) = &self.trade_storage {
+            let watched = Self::watched_markets(&self.active_subscriptions, self.trade_collector.as_deref()).await;
+            let summary = Self::snapshot_books(&self.orderbook_cache, &watched, storage).await;
+            info!("[Aggregator] Wrote final snapshots for {} orderbook(s)", summary.markets);
+        }
+
+        info!("[Aggregator] Shutdown complete");
+    }
+
+    /// The streamed book for a market, if it is cached and in sequence
+    pub async fn get_orderbook(&self, platform: Platform, market_id: &str) -> Option<OrderBook> {
+        // A book waiting on a resync has missed deltas
+        if self.book_sequencer.read().await.is_pending(platform, market_id) {
+            return None;
+        }
+        self.orderbook_cache
+            .read()
+            .await
+            .get(&(platform, market_id.to_string()))
+            .cloned()
+    }
+
+    /// The current book for a market: the streamed one when available,
+    /// otherwise a REST fetch through the market service
+    pub async fn current_orderbook(&self, platform: Platform, market_id: &str) -> Result<CurrentOrderBook, TerminalError> {
+        let cached = self.get_orderbook(platform, market_id).await;
+        cached_or_fetched(cached, || self.market_service.get_orderbook(platform, market_id)).await
+    }
+
     /// Check if a market is actively subscribed
     pub async fn is_subscribed(&self, platform: Platform, market_id: &str) -> bool {
         let subs = self.active_subscriptions.read().await;
@@ -880,7 +2421,58 @@ impl MarketDataAggregator {
     ) {
         info!("[Aggregator] Starting subscription event processor");
 
-        while let Some(event) = rx.recv().await {
+        let mut rest_poll = tokio::time::interval(REST_FALLBACK_INTERVAL);
+        rest_poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            let next_release = self.subscription_refs.read().await.next_release();
+            let event = tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(next_release.unwrap_or_else(tokio::time::Instant::now)), if next_release.is_some() => {
+                    let (expired, promoted) = {
+                        let mut refs = self.subscription_refs.write().await;
+                        let expired = refs.take_expired(tokio::time::Instant::now());
+                        (expired, refs.promote_overflow())
+                    };
+                    for (platform, market_id) in expired {
+                        info!(
+                            "[Aggregator] No clients left on {:?}:{}, unsubscribing",
+                            platform, market_id
+                        );
+                        if let Err(e) = self.unsubscribe(platform, &market_id).await {
+                            warn!(
+                                "[Aggregator] Failed to unsubscribe from {:?}:{}: {}",
+                                platform, market_id, e
+                            );
+                        }
+                    }
+                    // Freed slots go to markets that were polling over REST
+                    for (platform, market_id) in promoted {
+                        info!(
+                            "[Aggregator] Slot freed, streaming {:?}:{} instead of polling",
+                            platform, market_id
+                        );
+                        if let Err(e) = self.subscribe(platform, &market_id).await {
+                            warn!(
+                                "[Aggregator] Failed to subscribe to {:?}:{}: {}",
+                                platform, market_id, e
+                            );
+                        }
+                    }
+                    continue;
+                }
+                _ = rest_poll.tick() => {
+                    let overflow = self.subscription_refs.read().await.overflow();
+                    for (platform, market_id) in overflow {
+                        self.request_rest_refresh(platform, &market_id);
+                    }
+                    continue;
+                }
+            };
+
             match event {
                 SubscriptionEvent::Subscribe {
                     platform,
@@ -890,12 +2482,7 @@ impl MarketDataAggregator {
                         "[Aggregator] Received subscribe event for {:?}:{}",
                         platform, market_id
                     );
-                    if let Err(e) = self.subscribe(platform, &market_id).await {
-                        warn!(
-                            "[Aggregator] Failed to subscribe to {:?}:{}: {}",
-                            platform, market_id, e
-                        );
-                    }
+                    self.open_market(platform, &market_id).await;
                 }
                 SubscriptionEvent::Unsubscribe {
                     platform,
@@ -905,18 +2492,131 @@ impl MarketDataAggregator {
                         "[Aggregator] Received unsubscribe event for {:?}:{}",
                         platform, market_id
                     );
-                    if let Err(e) = self.unsubscribe(platform, &market_id).await {
-                        warn!(
-                            "[Aggregator] Failed to unsubscribe from {:?}:{}: {}",
-                            platform, market_id, e
-                        );
-                    }
+                    self.release_market(platform, &market_id).await;
+                }
+                SubscriptionEvent::SubscribeEvent { platform, event_id } => {
+                    info!(
+                        "[Aggregator] Received subscribe event for {:?} event {}",
+                        platform, event_id
+                    );
+                    self.open_event(platform, &event_id).await;
+                }
+                SubscriptionEvent::UnsubscribeEvent { platform, event_id } => {
+                    info!(
+                        "[Aggregator] Received unsubscribe event for {:?} event {}",
+                        platform, event_id
+                    );
+                    self.release_event(platform, &event_id).await;
                 }
             }
         }
 
         info!("[Aggregator] Subscription event processor stopped");
     }
+
+    /// Take a client reference on a market, subscribing on the exchange
+    /// (or polling over REST past the cap) when it is the first
+    async fn open_market(&self, platform: Platform, market_id: &str) {
+        let acquired = self.subscription_refs.write().await.acquire(platform, market_id);
+        match acquired {
+            Acquired::Unchanged => return,
+            Acquired::OverCapacity => {
+                warn!(
+                    "[Aggregator] {:?} subscription cap reached with every market watched, polling {} over REST",
+                    platform, market_id
+                );
+                self.request_rest_refresh(platform, market_id);
+                return;
+            }
+            Acquired::Subscribe { evict: Some((evicted_platform, evicted_id)) } => {
+                info!(
+                    "[Aggregator] {:?} subscription cap reached, evicting unwatched {} for {}",
+                    platform, evicted_id, market_id
+                );
+                if let Err(e) = self.unsubscribe(evicted_platform, &evicted_id).await {
+                    warn!(
+                        "[Aggregator] Failed to unsubscribe from {:?}:{}: {}",
+                        evicted_platform, evicted_id, e
+                    );
+                }
+                // Keeps the cached price current if a viewer comes back
+                self.request_rest_refresh(evicted_platform, &evicted_id);
+            }
+            Acquired::Subscribe { evict: None } => {}
+        }
+        if let Err(e) = self.subscribe(platform, market_id).await {
+            warn!(
+                "[Aggregator] Failed to subscribe to {:?}:{}: {}",
+                platform, market_id, e
+            );
+        }
+    }
+
+    /// Drop a client reference; the exchange unsubscribe waits out the grace period
+    async fn release_market(&self, platform: Platform, market_id: &str) {
+        self.subscription_refs
+            .write()
+            .await
+            .release(platform, market_id, tokio::time::Instant::now());
+    }
+
+    /// Stream every outcome of an event, tagging their broadcasts for the
+    /// event's subscribers
+    async fn open_event(&self, platform: Platform, event_id: &str) {
+        let outcomes = match self.outcome_resolver.resolve_outcomes(platform, event_id).await {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                warn!(
+                    "[Aggregator] Failed to resolve outcomes for {:?} event {}: {}",
+                    platform, event_id, e
+                );
+                return;
+            }
+        };
+        info!(
+            "[Aggregator] {:?} event {} has {} outcome(s)",
+            platform, event_id, outcomes.len()
+        );
+
+        // Seed the token mapping so subscribe() streams the known token
+        // instead of looking it up per outcome
+        if platform == Platform::Polymarket {
+            let mut map = self.polymarket_token_map.write().await;
+            for outcome in &outcomes {
+                map.insert(outcome.token_id.clone(), outcome.market_id.clone());
+            }
+        }
+        self.ws_state.route_event(
+            platform,
+            event_id,
+            outcomes
+                .iter()
+                .map(|outcome| (outcome.market_id.clone(), outcome.outcome.clone()))
+                .collect(),
+        );
+        for outcome in &outcomes {
+            self.open_market(platform, &outcome.market_id).await;
+        }
+
+        self.event_outcomes
+            .write()
+            .await
+            .insert((platform, event_id.to_string()), outcomes);
+    }
+
+    /// Release every outcome an event subscription opened
+    async fn release_event(&self, platform: Platform, event_id: &str) {
+        self.ws_state.unroute_event(platform, event_id);
+        let outcomes = self
+            .event_outcomes
+            .write()
+            .await
+            .remove(&(platform, event_id.to_string()))
+            .unwrap_or_default();
+        for outcome in outcomes {
+            self.release_market(platform, &outcome.market_id).await;
+        }
+    }
 }
 
 impl std::fmt::Debug for MarketDataAggregator {
@@ -926,3 +2626,929 @@ impl std::fmt::Debug for MarketDataAggregator {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_to_cap_and_resets() {
+        let mut backoff = ReconnectBackoff::default();
+        let delays: Vec<u64> = (0..9).map(|_| backoff.next_delay(0.0).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60, 60]);
+        assert_eq!(backoff.attempts(), 9);
+
+        backoff.reset();
+        assert_eq!(backoff.attempts(), 0);
+        assert_eq!(backoff.next_delay(0.0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_backoff_jitter_adds_up_to_a_quarter() {
+        let mut backoff = ReconnectBackoff::default();
+        backoff.next_delay(0.0);
+        backoff.next_delay(0.0);
+        // Third attempt: 4s plus half of the 25% jitter
+        assert_eq!(backoff.next_delay(0.5), Duration::from_millis(4_500));
+        // Out-of-range jitter is clamped
+        let mut capped = ReconnectBackoff::new(Duration::from_secs(60), Duration::from_secs(60));
+        assert_eq!(capped.next_delay(7.0), Duration::from_secs(75));
+    }
+
+    #[test]
+    fn test_resubscription_ids_from_active_markets() {
+        let active: HashSet<String> = ["m1", "m2", "m3"].iter().map(|s| s.to_string()).collect();
+        let token_map: HashMap<String, String> = [
+            ("tok-b", "m1"),
+            ("tok-a", "m1"),
+            ("tok-c", "m2"),
+            // Mapped but no longer subscribed
+            ("tok-d", "gone"),
+        ]
+        .iter()
+        .map(|(token, market)| (token.to_string(), market.to_string()))
+        .collect();
+
+        // m3 has no mapped token, so its own id is replayed
+        assert_eq!(
+            resubscription_ids(Some(&active), &token_map),
+            vec!["m3", "tok-a", "tok-b", "tok-c"]
+        );
+        assert!(resubscription_ids(None, &token_map).is_empty());
+    }
+
+    fn metrics_silent_for(connected: bool, silent_secs: Option<u64>) -> ConnectionMetrics {
+        let metrics = ConnectionMetrics::new();
+        metrics.set_connected(connected);
+        if let Some(secs) = silent_secs {
+            let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+            metrics.last_message_epoch_ms.store(now_ms - secs * 1000, Ordering::SeqCst);
+        }
+        metrics
+    }
+
+    #[test]
+    fn test_watchdog_only_acts_on_connected_silent_feeds() {
+        let mut watchdog = StaleWatchdog::new(3);
+        // Fresh, disconnected, or never heard from: nothing to force
+        for metrics in [
+            metrics_silent_for(true, Some(5)),
+            metrics_silent_for(false, Some(300)),
+            metrics_silent_for(true, None),
+        ] {
+            assert_eq!(watchdog.check(metrics.silent_for()), WatchdogAction::Healthy);
+        }
+
+        let stale = metrics_silent_for(true, Some(STALE_THRESHOLD_SECS + 30));
+        assert_eq!(watchdog.check(stale.silent_for()), WatchdogAction::Reconnect);
+        assert_eq!(watchdog.consecutive(), 1);
+    }
+
+    #[test]
+    fn test_watchdog_holds_off_after_max_consecutive() {
+        let stale = Some(Duration::from_secs(STALE_THRESHOLD_SECS + 1));
+        let mut watchdog = StaleWatchdog::new(2);
+        assert_eq!(watchdog.check(stale), WatchdogAction::Reconnect);
+        assert_eq!(watchdog.check(stale), WatchdogAction::Reconnect);
+
+        // Limit hit: one retry per hold-off window
+        for _ in 1..WATCHDOG_HOLDOFF_CHECKS {
+            assert_eq!(watchdog.check(stale), WatchdogAction::HoldOff);
+        }
+        assert_eq!(watchdog.check(stale), WatchdogAction::Reconnect);
+        assert_eq!(watchdog.check(stale), WatchdogAction::HoldOff);
+        assert_eq!(watchdog.consecutive(), 3);
+
+        // Recovering starts over
+        assert_eq!(watchdog.check(Some(Duration::from_secs(1))), WatchdogAction::Healthy);
+        assert_eq!(watchdog.check(stale), WatchdogAction::Reconnect);
+        assert_eq!(watchdog.consecutive(), 1);
+    }
+
+    /// Connect attempts until one succeeds, returning the endpoints tried
+    fn connect_until_up(failover: &mut EndpointFailover, up: &[bool], max_attempts: usize) -> Vec<usize> {
+        let mut tried = Vec::new();
+        for _ in 0..max_attempts {
+            tried.push(failover.active());
+            if up[failover.active()] {
+                failover.record_connected();
+                break;
+            }
+            failover.record_failure();
+        }
+        tried
+    }
+
+    #[test]
+    fn test_endpoint_failover_rotates_and_fails_back() {
+        let mut failover = EndpointFailover::new(3, 3);
+
+        // Primary and first backup down: three tries on each, then the second backup
+        let tried = connect_until_up(&mut failover, &[false, false, true], 20);
+        assert_eq!(tried, vec![0, 0, 0, 1, 1, 1, 2]);
+        assert!(failover.is_failed_over());
+
+        // A drop on the healthy backup doesn't move it straight away
+        assert_eq!(failover.record_failure(), None);
+        failover.record_connected();
+        assert_eq!(failover.active(), 2);
+
+        // Everything down: wraps around to the primary
+        assert_eq!(connect_until_up(&mut failover, &[false; 3], 3), vec![2, 2, 2]);
+        assert_eq!(failover.active(), 0);
+        assert!(!failover.fail_back());
+
+        // The primary answering a probe brings the feed back from a backup
+        connect_until_up(&mut failover, &[false, true, true], 20);
+        assert_eq!(failover.active(), 1);
+        assert!(failover.fail_back());
+        assert_eq!(failover.active(), 0);
+        assert_eq!(connect_until_up(&mut failover, &[true, true, true], 20), vec![0]);
+
+        // A single endpoint never rotates
+        let mut single = EndpointFailover::new(1, 3);
+        assert_eq!(connect_until_up(&mut single, &[false], 10), vec![0; 10]);
+    }
+
+    fn book(market_id: &str, bids: &[(&str, &str)], asks: &[(&str, &str)], seq: u64) -> OrderBook {
+        let level = |(p, q): &(&str, &str)| OrderBookLevel::new(p.parse().unwrap(), q.parse().unwrap());
+        let mut book = OrderBook::new(market_id.to_string(), Platform::Polymarket);
+        book.yes_bids = bids.iter().map(level).collect();
+        book.yes_asks = asks.iter().map(level).collect();
+        book.sequence = Some(seq);
+        book
+    }
+
+    fn change(price: &str, size: &str, side: &str) -> (Decimal, Decimal, String) {
+        (price.parse().unwrap(), size.parse().unwrap(), side.to_string())
+    }
+
+    #[test]
+    fn test_sequencer_contiguous_gap_triggers_one_resync() {
+        let mut sequencer = BookSequencer::default();
+        // Delta before any snapshot
+        assert_eq!(sequencer.delta(Platform::Kalshi, "K", Some(1), SequenceRule::Contiguous), DeltaCheck::Resync);
+        sequencer.snapshot(Platform::Kalshi, "K", Some(5));
+
+        assert_eq!(sequencer.delta(Platform::Kalshi, "K", Some(6), SequenceRule::Contiguous), DeltaCheck::Apply);
+        // 7 went missing
+        assert_eq!(sequencer.delta(Platform::Kalshi, "K", Some(8), SequenceRule::Contiguous), DeltaCheck::Resync);
+        assert_eq!(sequencer.delta(Platform::Kalshi, "K", Some(9), SequenceRule::Contiguous), DeltaCheck::Skip);
+        assert_eq!(sequencer.resyncs(Platform::Kalshi)["K"], 2);
+        assert!(sequencer.resyncs(Platform::Polymarket).is_empty());
+
+        // A failed fetch lets the next delta ask again
+        sequencer.resync_failed(Platform::Kalshi, "K");
+        assert_eq!(sequencer.delta(Platform::Kalshi, "K", Some(10), SequenceRule::Contiguous), DeltaCheck::Resync);
+
+        // Lagging distrusts every book on the platform
+        sequencer.snapshot(Platform::Kalshi, "K", Some(20));
+        sequencer.invalidate(Platform::Kalshi);
+        assert_eq!(sequencer.delta(Platform::Kalshi, "K", Some(21), SequenceRule::Contiguous), DeltaCheck::Resync);
+    }
+
+    #[test]
+    fn test_sequencer_monotonic_allows_equal_timestamps() {
+        let mut sequencer = BookSequencer::default();
+        sequencer.snapshot(Platform::Polymarket, "P", Some(1_000));
+        assert_eq!(sequencer.delta(Platform::Polymarket, "P", Some(1_000), SequenceRule::Monotonic), DeltaCheck::Apply);
+        assert_eq!(sequencer.delta(Platform::Polymarket, "P", Some(1_250), SequenceRule::Monotonic), DeltaCheck::Apply);
+        assert_eq!(sequencer.delta(Platform::Polymarket, "P", None, SequenceRule::Monotonic), DeltaCheck::Apply);
+        assert_eq!(sequencer.delta(Platform::Polymarket, "P", Some(1_100), SequenceRule::Monotonic), DeltaCheck::Resync);
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_deltas_resync_to_snapshot() {
+        let cache = RwLock::new(HashMap::new());
+        let sequencer = RwLock::new(BookSequencer::default());
+        let snapshot = book("m", &[("0.40", "100")], &[("0.45", "50")], 1_000);
+        let m = (Platform::Polymarket, "m".to_string());
+        cache.write().await.insert(m.clone(), snapshot.clone());
+        sequencer.write().await.snapshot(Platform::Polymarket, "m", snapshot.sequence);
+
+        let deltas = [
+            (1_100, vec![change("0.41", "10", "BUY")]),
+            // Arrives after a newer one was applied
+            (1_300, vec![change("0.45", "0", "SELL")]),
+            (1_200, vec![change("0.41", "0", "BUY")]),
+            (1_400, vec![change("0.39", "5", "BUY")]),
+        ];
+        let mut resyncs = 0;
+        for (timestamp, changes) in &deltas {
+            let check = sequencer.write().await.delta(Platform::Polymarket, "m", Some(*timestamp), SequenceRule::Monotonic);
+            match check {
+                DeltaCheck::Apply => apply_price_changes(cache.write().await.get_mut(&m).unwrap(), changes),
+                DeltaCheck::Resync => resyncs += 1,
+                DeltaCheck::Skip => {}
+            }
+        }
+        assert_eq!(resyncs, 1);
+        // The delta after the gap was held back
+        assert!(cache.read().await[&m].yes_bids.iter().all(|l| l.price != "0.39".parse::<Decimal>().unwrap()));
+
+        let fresh = book("m", &[("0.42", "30")], &[("0.47", "80")], 1_500);
+        assert!(MarketDataAggregator::apply_resync_snapshot(Platform::Polymarket, "m", &fresh, &cache, &sequencer).await);
+        let levels = |levels: &[OrderBookLevel]| levels.iter().map(|l| (l.price, l.quantity)).collect::<Vec<_>>();
+        let cached = cache.read().await[&m].clone();
+        assert_eq!(levels(&cached.yes_bids), levels(&fresh.yes_bids));
+        assert_eq!(levels(&cached.yes_asks), levels(&fresh.yes_asks));
+        // Only the first snapshot wins
+        assert!(!MarketDataAggregator::apply_resync_snapshot(Platform::Polymarket, "m", &snapshot, &cache, &sequencer).await);
+        assert_eq!(
+            sequencer.write().await.delta(Platform::Polymarket, "m", Some(1_600), SequenceRule::Monotonic),
+            DeltaCheck::Apply
+        );
+        assert_eq!(sequencer.read().await.resyncs(Platform::Polymarket)["m"], 1);
+    }
+
+    const SUBSCRIBE: Acquired = Acquired::Subscribe { evict: None };
+
+    #[test]
+    fn test_subscription_refs_release_after_grace() {
+        let grace = Duration::from_secs(60);
+        let mut refs = SubscriptionRefs::new(grace, None);
+        let t0 = tokio::time::Instant::now();
+
+        // Book and trades channels on the same market: one exchange subscription
+        assert_eq!(refs.acquire(Platform::Polymarket, "m"), SUBSCRIBE);
+        assert_eq!(refs.acquire(Platform::Polymarket, "m"), Acquired::Unchanged);
+        assert_eq!(refs.count(Platform::Polymarket, "m"), 2);
+
+        refs.release(Platform::Polymarket, "m", t0);
+        assert_eq!(refs.next_release(), None);
+        refs.release(Platform::Polymarket, "m", t0);
+        assert_eq!(refs.count(Platform::Polymarket, "m"), 0);
+        assert_eq!(refs.next_release(), Some(t0 + grace));
+        assert_eq!(refs.pending_releases(), 1);
+
+        assert!(refs.take_expired(t0 + Duration::from_secs(59)).is_empty());
+        assert_eq!(
+            refs.take_expired(t0 + grace),
+            vec![(Platform::Polymarket, "m".to_string())]
+        );
+        assert_eq!(refs.pending_releases(), 0);
+
+        // Released for good: the next client reopens the stream
+        assert_eq!(refs.acquire(Platform::Polymarket, "m"), SUBSCRIBE);
+    }
+
+    #[test]
+    fn test_subscription_refs_resubscribe_cancels_release() {
+        let grace = Duration::from_secs(60);
+        let mut refs = SubscriptionRefs::new(grace, None);
+        let t0 = tokio::time::Instant::now();
+
+        assert_eq!(refs.acquire(Platform::Kalshi, "K"), SUBSCRIBE);
+        refs.release(Platform::Kalshi, "K", t0);
+        // Page reload within the grace period: still streaming, nothing to open
+        assert_eq!(refs.acquire(Platform::Kalshi, "K"), Acquired::Unchanged);
+        assert_eq!(refs.pending_releases(), 0);
+        assert!(refs.take_expired(t0 + grace * 2).is_empty());
+
+        // Stray releases for unknown markets are ignored
+        refs.release(Platform::Kalshi, "other", t0);
+        assert_eq!(refs.pending_releases(), 0);
+
+        // A later release restarts the full grace period
+        let t1 = t0 + Duration::from_secs(30);
+        refs.release(Platform::Kalshi, "K", t1);
+        assert!(refs.take_expired(t0 + grace).is_empty());
+        assert_eq!(refs.take_expired(t1 + grace).len(), 1);
+    }
+
+    fn key(platform: Platform, market_id: &str) -> BookKey {
+        (platform, market_id.to_string())
+    }
+
+    #[test]
+    fn test_subscription_cap_evicts_least_recently_watched() {
+        let grace = Duration::from_secs(60);
+        let mut refs = SubscriptionRefs::new(grace, Some(3));
+        let t0 = tokio::time::Instant::now();
+
+        for id in ["a", "b", "c"] {
+            assert_eq!(refs.acquire(Platform::Polymarket, id), SUBSCRIBE);
+        }
+        // Viewers leave "b" first, then "a"; "c" stays watched
+        refs.release(Platform::Polymarket, "b", t0);
+        refs.release(Platform::Polymarket, "a", t0 + Duration::from_secs(5));
+
+        // The other platform has its own slots
+        assert_eq!(refs.acquire(Platform::Kalshi, "K"), SUBSCRIBE);
+
+        assert_eq!(
+            refs.acquire(Platform::Polymarket, "d"),
+            Acquired::Subscribe { evict: Some(key(Platform::Polymarket, "b")) }
+        );
+        assert_eq!(
+            refs.acquire(Platform::Polymarket, "e"),
+            Acquired::Subscribe { evict: Some(key(Platform::Polymarket, "a")) }
+        );
+        assert_eq!(refs.evictions(), 2);
+        assert_eq!(refs.pending_releases(), 0);
+        // Evicted markets are gone, so nothing is left to expire
+        assert!(refs.take_expired(t0 + grace * 2).is_empty());
+    }
+
+    #[test]
+    fn test_subscription_cap_never_evicts_watched() {
+        let grace = Duration::from_secs(60);
+        let mut refs = SubscriptionRefs::new(grace, Some(2));
+        let t0 = tokio::time::Instant::now();
+
+        assert_eq!(refs.acquire(Platform::Polymarket, "a"), SUBSCRIBE);
+        assert_eq!(refs.acquire(Platform::Polymarket, "b"), SUBSCRIBE);
+
+        // Every slot has viewers: the newcomer polls over REST instead
+        assert_eq!(refs.acquire(Platform::Polymarket, "c"), Acquired::OverCapacity);
+        assert_eq!(refs.acquire(Platform::Polymarket, "c"), Acquired::Unchanged);
+        assert_eq!(refs.acquire(Platform::Polymarket, "d"), Acquired::OverCapacity);
+        assert_eq!(refs.evictions(), 0);
+        assert_eq!(
+            refs.overflow(),
+            vec![key(Platform::Polymarket, "c"), key(Platform::Polymarket, "d")]
+        );
+
+        // "d" loses its viewer while waiting: nothing to tear down
+        refs.release(Platform::Polymarket, "d", t0);
+        assert_eq!(refs.pending_releases(), 0);
+
+        // Only after "a" is released and its grace runs out does "c" stream
+        refs.release(Platform::Polymarket, "a", t0);
+        assert!(refs.promote_overflow().is_empty());
+        assert_eq!(refs.take_expired(t0 + grace), vec![key(Platform::Polymarket, "a")]);
+        assert_eq!(refs.promote_overflow(), vec![key(Platform::Polymarket, "c")]);
+        assert!(refs.overflow().is_empty());
+        assert_eq!(refs.count(Platform::Polymarket, "c"), 2);
+    }
+
+    fn feed_trade(platform: Platform, id: &str, market_id: &str) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: market_id.to_string(),
+            platform,
+            timestamp: Utc::now(),
+            price: Decimal::new(42, 2),
+            quantity: Decimal::from(10),
+            outcome: terminal_core::TradeOutcome::Yes,
+            side: Some(terminal_core::TradeSide::Buy),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[test]
+    fn test_ws_trades_persisted_once() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        // A Kalshi print, and a Polymarket print remapped from its token id
+        let kalshi = feed_trade(Platform::Kalshi, "kalshi-trade-1", "KXTEST-25");
+        let mut poly = feed_trade(Platform::Polymarket, "token-1-1700000000000", "token-1");
+        poly.market_id = "0xcondition".to_string();
+        poly.outcome_id = Some("token-1".to_string());
+
+        assert!(persist_ws_trade(&storage, &kalshi).unwrap());
+        assert!(persist_ws_trade(&storage, &poly).unwrap());
+        assert_eq!(storage.get_trade_count(Platform::Kalshi, "KXTEST-25").unwrap(), 1);
+        assert_eq!(storage.get_trade_count(Platform::Polymarket, "0xcondition").unwrap(), 1);
+        assert_eq!(storage.get_trade_count(Platform::Polymarket, "token-1").unwrap(), 0);
+
+        // Replayed after a reconnect: skipped, the stored row untouched
+        let mut replay = kalshi.clone();
+        replay.quantity = Decimal::from(99);
+        assert!(!persist_ws_trade(&storage, &replay).unwrap());
+        assert_eq!(storage.get_trade_count(Platform::Kalshi, "KXTEST-25").unwrap(), 1);
+        let stored = storage
+            .get_latest_trade(Platform::Kalshi, "KXTEST-25")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.quantity, Decimal::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_feed_trades_queued_to_the_writer() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let config = AggregatorConfig {
+            persist_ws_trades: true,
+            ..AggregatorConfig::default()
+        };
+        let mut aggregator = MarketDataAggregator::new(config, ws_state, market_service);
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        aggregator.set_trade_storage(Arc::clone(&storage));
+        aggregator.start_trade_writer();
+
+        aggregator.publish_feed_trade(feed_trade(Platform::Kalshi, "kalshi-trade-1", "KXTEST-25"));
+        aggregator.publish_feed_trade(feed_trade(Platform::Kalshi, "kalshi-trade-2", "KXTEST-25"));
+
+        // Written by the writer thread, not inline
+        let mut stored = 0;
+        for _ in 0..100 {
+            stored = storage.get_trade_count(Platform::Kalshi, "KXTEST-25").unwrap();
+            if stored == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(stored, 2);
+    }
+
+    #[test]
+    fn test_fill_stored_once_from_rest_and_feed() {
+        // The same fill as the feed prints it (token id + ms, condition id)
+        // and as REST polling does (transaction hash, event id)
+        let mut feed = feed_trade(Platform::Polymarket, "token-1-1700000000000", "0xcondition");
+        feed.outcome_id = Some("token-1".to_string());
+        let mut rest = feed.clone();
+        rest.id = "0xhash".to_string();
+        rest.market_id = "event-1".to_string();
+        rest.transaction_hash = Some("0xhash".to_string());
+        let total = |storage: &TradeStorage| {
+            storage.get_trade_count(Platform::Polymarket, "0xcondition").unwrap()
+                + storage.get_trade_count(Platform::Polymarket, "event-1").unwrap()
+        };
+
+        // REST first: the feed print is skipped
+        let storage = TradeStorage::new_in_memory().unwrap();
+        assert_eq!(storage.store_trades(std::slice::from_ref(&rest)).unwrap(), 1);
+        assert!(!persist_ws_trade(&storage, &feed).unwrap());
+        assert_eq!(total(&storage), 1);
+
+        // Feed first: the REST print replaces it
+        let storage = TradeStorage::new_in_memory().unwrap();
+        assert!(persist_ws_trade(&storage, &feed).unwrap());
+        assert_eq!(storage.store_trades(std::slice::from_ref(&rest)).unwrap(), 1);
+        assert_eq!(total(&storage), 1);
+        assert_eq!(storage.get_trade_count(Platform::Polymarket, "event-1").unwrap(), 1);
+
+        // A different fill in the same second is kept
+        let mut other = feed.clone();
+        other.id = "token-1-1700000000500".to_string();
+        other.quantity = Decimal::from(3);
+        assert!(persist_ws_trade(&storage, &other).unwrap());
+        assert_eq!(total(&storage), 2);
+    }
+
+    #[test]
+    fn test_heartbeats_counted_but_not_as_traffic() {
+        let metrics = ConnectionMetrics::new();
+        metrics.set_connected(true);
+        for kind in [MessageKind::Heartbeat, MessageKind::Heartbeat, MessageKind::BookDelta, MessageKind::Trade] {
+            metrics.record_update(kind);
+        }
+        metrics.record_fanout(Duration::from_micros(300));
+        assert_eq!(metrics.get_health("polymarket").ping_rtt_us, None);
+        metrics.record_ping_rtt(Duration::from_millis(42));
+
+        let health = metrics.get_health("polymarket");
+        assert_eq!(health.ping_rtt_us, Some(42_000));
+        assert_eq!(health.message_count, 2);
+        assert_eq!(health.message_types.heartbeats, 2);
+        assert_eq!(health.message_types.book_deltas, 1);
+        assert_eq!(health.fanout_latency.samples, 1);
+        assert_eq!(health.fanout_latency.p50_us, 300);
+    }
+
+    #[test]
+    fn test_filtered_messages_counted_in_health() {
+        let metrics = ConnectionMetrics::new();
+        let configured = PolymarketWebSocketConfig::default().message_filter;
+        let frames = [
+            r#"{"event_type":"tick_size_change","asset_id":"t1"}"#,
+            r#"{"event_type":"book","asset_id":"t1","bids":[],"asks":[]}"#,
+        ];
+
+        let filter = metrics.counting_filter(&configured, true);
+        let admitted = frames.iter().filter(|frame| filter.admit(frame)).count();
+        assert_eq!(admitted, 1);
+        assert_eq!(metrics.get_health("polymarket").filtered_messages, 1);
+
+        // Switched off from the aggregator config, everything is parsed
+        let debug = metrics.counting_filter(&configured, false);
+        assert!(frames.iter().all(|frame| debug.admit(frame)));
+        assert_eq!(metrics.get_health("polymarket").filtered_messages, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_flushes_cached_books() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let mut aggregator = MarketDataAggregator::new(AggregatorConfig::default(), ws_state, market_service);
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        aggregator.set_trade_storage(Arc::clone(&storage));
+
+        // Books streamed since the last periodic snapshot
+        let mut kalshi_book = OrderBook::new("KXTEST-25".to_string(), Platform::Kalshi);
+        kalshi_book.yes_bids = vec![OrderBookLevel::new(Decimal::new(42, 2), Decimal::from(100))];
+        kalshi_book.no_bids = vec![OrderBookLevel::new(Decimal::new(56, 2), Decimal::from(80))];
+        let mut poly_book = OrderBook::new("0xcondition".to_string(), Platform::Polymarket);
+        poly_book.yes_bids = vec![OrderBookLevel::new(Decimal::new(61, 2), Decimal::from(250))];
+        {
+            let mut cache = aggregator.orderbook_cache.write().await;
+            cache.insert((Platform::Kalshi, "KXTEST-25".to_string()), kalshi_book);
+            cache.insert((Platform::Polymarket, "0xcondition".to_string()), poly_book);
+        }
+        {
+            let mut subs = aggregator.active_subscriptions.write().await;
+            subs.entry(Platform::Kalshi).or_default().insert("KXTEST-25".to_string());
+            subs.entry(Platform::Polymarket).or_default().insert("0xcondition".to_string());
+        }
+
+        assert!(storage.get_latest_orderbook_snapshot(Platform::Kalshi, "KXTEST-25").unwrap().is_none());
+        aggregator.shutdown().await;
+
+        let snapshot = storage
+            .get_latest_orderbook_snapshot(Platform::Kalshi, "KXTEST-25")
+            .unwrap()
+            .expect("final Kalshi snapshot");
+        let bids: Vec<(Decimal, Decimal)> = snapshot.yes_bid_levels().iter().map(|l| (l.price, l.quantity)).collect();
+        assert_eq!(bids, vec![(Decimal::new(42, 2), Decimal::from(100))]);
+        assert!(storage
+            .get_latest_orderbook_snapshot(Platform::Polymarket, "0xcondition")
+            .unwrap()
+            .is_some());
+        let price = storage.get_price(Platform::Kalshi, "KXTEST-25").unwrap().expect("final price");
+        assert_eq!(price.yes_price, Some(0.42));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_skips_unwatched_books_and_keeps_platform() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let cache = RwLock::new(HashMap::new());
+        // The same id cached for both platforms must land under each one
+        let kalshi = book("SHARED", &[("0.30", "10")], &[], 1);
+        let polymarket = book("SHARED", &[("0.70", "20")], &[], 1);
+        let idle = book("IDLE", &[("0.50", "5")], &[], 1);
+        {
+            let mut cache = cache.write().await;
+            cache.insert((Platform::Kalshi, "SHARED".to_string()), kalshi);
+            cache.insert((Platform::Polymarket, "SHARED".to_string()), polymarket);
+            cache.insert((Platform::Polymarket, "IDLE".to_string()), idle);
+        }
+
+        // A streamed Kalshi market plus a collector-tracked Polymarket one
+        let active = RwLock::new(HashMap::from([(Platform::Kalshi, HashSet::from(["SHARED".to_string()]))]));
+        let collector = TradeCollector::new(
+            Arc::new(MarketService::new(
+                terminal_kalshi::KalshiClient::new(true),
+                terminal_polymarket::PolymarketClient::new(),
+            )),
+            Arc::new(TradeStorage::new_in_memory().unwrap()),
+            None,
+            crate::TradeCollectorConfig::default(),
+        );
+        collector.track_market(Platform::Polymarket, "SHARED".to_string()).await;
+
+        let watched = MarketDataAggregator::watched_markets(&active, Some(&collector)).await;
+        let summary = MarketDataAggregator::snapshot_books(&cache, &watched, &storage).await;
+        assert_eq!(summary, SnapshotSummary { markets: 2, rows: 4, skipped: 1 });
+
+        let best_bid = |platform: Platform| {
+            storage
+                .get_latest_orderbook_snapshot(platform, "SHARED")
+                .unwrap()
+                .expect("snapshot")
+                .yes_bid_levels()[0]
+                .price
+        };
+        assert_eq!(best_bid(Platform::Kalshi), Decimal::new(30, 2));
+        assert_eq!(best_bid(Platform::Polymarket), Decimal::new(70, 2));
+        assert!(storage.get_latest_orderbook_snapshot(Platform::Polymarket, "IDLE").unwrap().is_none());
+        assert!(storage.get_price(Platform::Polymarket, "IDLE").unwrap().is_none());
+
+        // Without interest nothing is written
+        let summary = MarketDataAggregator::snapshot_books(&cache, &HashSet::new(), &storage).await;
+        assert_eq!(summary, SnapshotSummary { markets: 0, rows: 0, skipped: 3 });
+    }
+
+    /// Stands in for the Gamma lookup of a 4-outcome event
+    struct FourOutcomeEvent;
+
+    #[async_trait]
+    impl OutcomeResolver for FourOutcomeEvent {
+        async fn resolve_outcomes(&self, _platform: Platform, event_id: &str) -> Result<Vec<EventOutcome>, TerminalError> {
+            if event_id != "fed-decision" {
+                return Err(TerminalError::not_found(event_id));
+            }
+            Ok(["Cut 50", "Cut 25", "Hold", "Hike"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| EventOutcome {
+                    market_id: format!("fed-{}", i),
+                    token_id: format!("token-{}", i),
+                    outcome: name.to_string(),
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_subscription_fans_out_outcomes() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let mut aggregator = MarketDataAggregator::new(AggregatorConfig::default(), Arc::clone(&ws_state), market_service);
+        aggregator.set_outcome_resolver(Arc::new(FourOutcomeEvent));
+        let mut broadcasts = ws_state.subscriptions.subscribe_broadcast();
+
+        aggregator.open_event(Platform::Polymarket, "fed-decision").await;
+
+        {
+            let refs = aggregator.subscription_refs.read().await;
+            for i in 0..4 {
+                assert_eq!(refs.count(Platform::Polymarket, &format!("fed-{}", i)), 1);
+            }
+        }
+        let token_map = aggregator.polymarket_token_map.read().await.clone();
+        assert_eq!(token_map.len(), 4);
+        assert_eq!(token_map.get("token-2").map(String::as_str), Some("fed-2"));
+
+        // An outcome's update goes out tagged for the event and untagged for the market
+        ws_state.broadcast_price_update(Platform::Polymarket, "fed-2".to_string(), Decimal::new(62, 2), Decimal::new(38, 2));
+        let tagged = broadcasts.recv().await.unwrap();
+        assert_eq!(tagged.key.channel, terminal_core::SubscriptionChannel::Event);
+        assert_eq!(tagged.key.market_id, "fed-decision");
+        match tagged.message {
+            terminal_core::ServerMessage::EventUpdate { event_id, market_id, outcome, update, .. } => {
+                assert_eq!(event_id, "fed-decision");
+                assert_eq!(market_id, "fed-2");
+                assert_eq!(outcome, "Hold");
+                assert!(matches!(*update, terminal_core::ServerMessage::PriceUpdate { .. }));
+            }
+            other => panic!("expected an event update, got {:?}", other),
+        }
+        let plain = broadcasts.recv().await.unwrap();
+        assert_eq!(plain.key.channel, terminal_core::SubscriptionChannel::Price);
+
+        // Tearing down the event releases every outcome and stops the tagging
+        aggregator.release_event(Platform::Polymarket, "fed-decision").await;
+        {
+            let refs = aggregator.subscription_refs.read().await;
+            for i in 0..4 {
+                assert_eq!(refs.count(Platform::Polymarket, &format!("fed-{}", i)), 0);
+            }
+            assert_eq!(refs.pending_releases(), 4);
+        }
+        assert!(ws_state.event_routes(Platform::Polymarket, "fed-2").is_empty());
+        ws_state.broadcast_price_update(Platform::Polymarket, "fed-2".to_string(), Decimal::new(63, 2), Decimal::new(37, 2));
+        assert_eq!(
+            broadcasts.recv().await.unwrap().key.channel,
+            terminal_core::SubscriptionChannel::Price
+        );
+
+        // An event that doesn't resolve opens nothing
+        aggregator.open_event(Platform::Polymarket, "unknown").await;
+        assert!(aggregator.event_outcomes.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_current_orderbook_prefers_streamed_book() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let aggregator = MarketDataAggregator::new(AggregatorConfig::default(), ws_state, market_service);
+        let streamed = book("m", &[("0.44", "120")], &[("0.46", "90")], 7);
+        aggregator
+            .orderbook_cache
+            .write()
+            .await
+            .insert((Platform::Polymarket, "m".to_string()), streamed.clone());
+        let rest = book("m", &[("0.40", "10")], &[], 1);
+
+        // Cached: served from memory, REST never called
+        let cached = aggregator.get_orderbook(Platform::Polymarket, "m").await;
+        let served = cached_or_fetched(cached, || async { Err(TerminalError::internal("cached book should be served")) })
+            .await
+            .unwrap();
+        assert_eq!(served.source, BookSource::Cache);
+        assert_eq!(served.updated_at, streamed.timestamp);
+        assert_eq!(served.orderbook.yes_bids[0].price, Decimal::new(44, 2));
+        let json = serde_json::to_value(&served).unwrap();
+        assert_eq!(json["source"], "cache");
+        assert_eq!(json["market_id"], "m");
+
+        // Same id on the other platform isn't cached, so it goes to REST
+        let cached = aggregator.get_orderbook(Platform::Kalshi, "m").await;
+        let served = cached_or_fetched(cached, || async { Ok(rest.clone()) }).await.unwrap();
+        assert_eq!(served.source, BookSource::Rest);
+        assert_eq!(served.orderbook.yes_bids[0].price, Decimal::new(40, 2));
+
+        // A book waiting on a resync isn't trusted either
+        aggregator
+            .book_sequencer
+            .write()
+            .await
+            .delta(Platform::Polymarket, "m", Some(9), SequenceRule::Contiguous);
+        assert!(aggregator.get_orderbook(Platform::Polymarket, "m").await.is_none());
+
+        let failed = cached_or_fetched(None, || async { Err(TerminalError::not_found("m")) }).await;
+        assert!(matches!(failed, Err(TerminalError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mid_tick_only_when_mid_moves() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let mut aggregator = MarketDataAggregator::new(AggregatorConfig::default(), Arc::clone(&ws_state), market_service);
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        aggregator.set_trade_storage(Arc::clone(&storage));
+        let feed = aggregator.feed_context();
+        let mut broadcasts = ws_state.subscriptions.subscribe_broadcast();
+        let mut ticks = || {
+            let mut ticks = Vec::new();
+            while let Ok(msg) = broadcasts.try_recv() {
+                assert_eq!(msg.key.channel, terminal_core::SubscriptionChannel::Prices);
+                if let terminal_core::ServerMessage::PriceTick { best_bid, best_ask, mid, .. } = msg.message {
+                    ticks.push((best_bid, best_ask, mid));
+                }
+            }
+            ticks
+        };
+
+        // The first book always ticks
+        let mut orderbook = book("m", &[("0.40", "100")], &[("0.44", "50")], 1);
+        MarketDataAggregator::publish_mid_tick(&feed, Platform::Polymarket, "m", &orderbook);
+        assert_eq!(ticks(), vec![(Decimal::new(40, 2), Decimal::new(44, 2), Decimal::new(42, 2))]);
+
+        // Size changes and levels behind the top leave the mid alone
+        apply_price_changes(&mut orderbook, &[change("0.40", "30", "BUY"), change("0.38", "500", "BUY")]);
+        MarketDataAggregator::publish_mid_tick(&feed, Platform::Polymarket, "m", &orderbook);
+        assert!(ticks().is_empty());
+
+        // A new best bid moves it
+        apply_price_changes(&mut orderbook, &[change("0.42", "10", "BUY")]);
+        MarketDataAggregator::publish_mid_tick(&feed, Platform::Polymarket, "m", &orderbook);
+        assert_eq!(ticks(), vec![(Decimal::new(42, 2), Decimal::new(44, 2), Decimal::new(43, 2))]);
+
+        // Each tick was written through to price snapshots
+        let snapshots = storage
+            .get_price_snapshots(Platform::Polymarket, "m", Utc::now() - chrono::Duration::minutes(1), Utc::now() + chrono::Duration::minutes(1))
+            .unwrap();
+        assert_eq!(snapshots.last().map(|s| s.yes_price), Some(0.43));
+
+        // Below the epsilon is noise
+        let mut ticker = MidTicker::new(Decimal::new(1, 2));
+        let quiet = book("q", &[("0.500", "1")], &[("0.510", "1")], 1);
+        assert!(ticker.observe(Platform::Kalshi, "q", &quiet).is_some());
+        let nudged = book("q", &[("0.505", "1")], &[("0.510", "1")], 2);
+        assert!(ticker.observe(Platform::Kalshi, "q", &nudged).is_none());
+        let moved = book("q", &[("0.530", "1")], &[("0.540", "1")], 3);
+        assert_eq!(ticker.observe(Platform::Kalshi, "q", &moved).map(|t| t.mid), Some(Decimal::new(535, 3)));
+    }
+
+    #[tokio::test]
+    async fn test_feed_alerts_broadcast_and_stored() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let config = AggregatorConfig {
+            anomaly: AnomalyConfig {
+                warmup_trades: 3,
+                ..AnomalyConfig::default()
+            },
+            ..AggregatorConfig::default()
+        };
+        let mut aggregator = MarketDataAggregator::new(config, Arc::clone(&ws_state), market_service);
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        aggregator.set_trade_storage(Arc::clone(&storage));
+        let feed = aggregator.feed_context();
+        let mut broadcasts = ws_state.subscriptions.subscribe_broadcast();
+
+        for i in 0..3 {
+            MarketDataAggregator::raise_alerts(&feed, &feed_trade(Platform::Kalshi, &format!("t{}", i), "KXTEST-25"));
+        }
+        assert!(broadcasts.try_recv().is_err());
+
+        let mut block = feed_trade(Platform::Kalshi, "block", "KXTEST-25");
+        block.quantity = Decimal::from(80);
+        MarketDataAggregator::raise_alerts(&feed, &block);
+
+        let msg = broadcasts.try_recv().unwrap();
+        assert_eq!(msg.key.channel, terminal_core::SubscriptionChannel::Alerts);
+        assert!(matches!(
+            msg.message,
+            terminal_core::ServerMessage::MarketAlert { kind: terminal_core::AlertKind::LargePrint, .. }
+        ));
+        assert!(broadcasts.try_recv().is_err());
+
+        let stored = storage.get_recent_alerts(Some(Platform::Kalshi), 10).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].market_id, "KXTEST-25");
+        assert_eq!(stored[0].magnitude, 8.0);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_writes_one_gap() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let mut aggregator = MarketDataAggregator::new(AggregatorConfig::default(), ws_state, market_service);
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        aggregator.set_trade_storage(Arc::clone(&storage));
+        let feed = aggregator.feed_context();
+        let at = |seconds| DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap();
+        let track = |connected, was_connected, last_message, now| {
+            MarketDataAggregator::track_connection_gap(&feed, Platform::Polymarket, connected, was_connected, last_message, now);
+        };
+
+        // First connect: nothing was missed
+        track(true, false, None, at(0));
+        // Last message at 40s, drop at 45s, a failed attempt at 50s, back at 58s
+        track(false, true, Some(at(40)), at(45));
+        track(false, false, Some(at(45)), at(50));
+        track(true, false, Some(at(50)), at(58));
+        // A duplicate connected event doesn't open another window
+        track(true, true, Some(at(58)), at(59));
+
+        let gaps = storage.get_gaps_since(at(0), 10).unwrap();
+        assert_eq!(gaps, vec![DataGap {
+            platform: Platform::Polymarket,
+            market_id: None,
+            started_at: at(40),
+            ended_at: at(58),
+            reason: GapReason::Reconnect,
+        }]);
+
+        // A forced reconnect has no drop event; the window starts at the last message
+        track(true, false, Some(at(100)), at(130));
+        let gaps = storage.get_gaps_since(at(60), 10).unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!((gaps[0].ended_at - gaps[0].started_at).num_seconds(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_late_subscriber_gets_replay_then_live_trades() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let ws_state = Arc::new(WebSocketState::new(market_service.clone()));
+        let aggregator = MarketDataAggregator::new(AggregatorConfig::default(), Arc::clone(&ws_state), market_service);
+        let feed = aggregator.feed_context();
+        let mut broadcasts = ws_state.subscriptions.subscribe_broadcast();
+
+        for i in 0..3 {
+            MarketDataAggregator::publish_trade(&feed, feed_trade(Platform::Kalshi, &format!("t{}", i), "KXTEST-25"));
+        }
+        while broadcasts.try_recv().is_ok() {}
+
+        let client_id = ws_state.subscriptions.new_client_id();
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel(16);
+        let subscribe = r#"{"type":"subscribe","subscription":{"type":"trades","platform":"kalshi","market_id":"KXTEST-25"}}"#;
+        WebSocketState::handle_message(
+            client_id,
+            tokio_tungstenite::tungstenite::Message::Text(subscribe.into()),
+            &ws_state.subscriptions,
+            &outgoing_tx,
+            &None,
+            &None,
+            &None,
+            &Some(Arc::clone(&aggregator.replay)),
+            &None,
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(outgoing_rx.try_recv(), Ok(terminal_core::ServerMessage::Subscribed { .. })));
+        let mut replayed = Vec::new();
+        while let Ok(msg) = outgoing_rx.try_recv() {
+            match msg {
+                terminal_core::ServerMessage::TradeUpdate { trade, replay: true, .. } => replayed.push(trade.id),
+                other => panic!("expected a replayed trade, got {:?}", other),
+            }
+        }
+        assert_eq!(replayed, vec!["t0", "t1", "t2"]);
+
+        // The next trade is live, and reaches the now-subscribed client
+        MarketDataAggregator::publish_trade(&feed, feed_trade(Platform::Kalshi, "t3", "KXTEST-25"));
+        let msg = broadcasts.try_recv().unwrap();
+        assert!(ws_state.subscriptions.is_subscribed(client_id, &msg.key));
+        assert!(matches!(
+            msg.message,
+            terminal_core::ServerMessage::TradeUpdate { ref trade, replay: false, .. } if trade.id == "t3"
+        ));
+
+        // The buffer goes with the exchange subscription
+        aggregator.unsubscribe(Platform::Kalshi, "KXTEST-25").await.unwrap();
+        assert!(aggregator.replay.is_empty());
+    }
+}
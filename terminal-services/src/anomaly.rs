@@ -0,0 +1,310 @@
+//! Unusual activity detection on the exchange trade feeds
+//!
+//! Each streamed market keeps exponentially weighted baselines of its trade
+//! rate (trades per minute) and typical trade size. A print far above the
+//! typical size, a minute with far more trades than usual, or a price move of
+//! more than a few points within the window raises a [`MarketAlert`].
+//! Baselines are only trusted after a warm-up, and each alert kind has a
+//! cooldown so one burst produces one alert rather than a stream of them.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use terminal_core::{AlertKind, Platform, Trade, TradeOutcome};
+
+/// Empty minutes folded into the rate baseline after a quiet spell; past
+/// this the baseline is effectively zero anyway
+const MAX_IDLE_MINUTES: i64 = 60;
+
+/// Thresholds for raising alerts
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    /// A print at least this many times the typical size is a large print
+    pub large_print_multiple: f64,
+    /// A minute with at least this many times the usual trade count is a
+    /// volume spike
+    pub volume_spike_multiple: f64,
+    /// YES price move (in price units, 0.10 = 10 points) within
+    /// `price_window` that counts as a jump
+    pub price_jump: Decimal,
+    pub price_window: Duration,
+    /// Weight of the newest observation in the baselines
+    pub ewma_alpha: f64,
+    /// Trades seen before the size baseline is trusted
+    pub warmup_trades: u32,
+    /// Minutes seen before the rate baseline is trusted
+    pub warmup_minutes: u32,
+    /// Minimum gap between two alerts of the same kind on one market
+    pub cooldown: Duration,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            large_print_multiple: 5.0,
+            volume_spike_multiple: 4.0,
+            price_jump: Decimal::new(10, 2),
+            price_window: Duration::from_secs(60),
+            ewma_alpha: 0.1,
+            warmup_trades: 20,
+            warmup_minutes: 5,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+/// An unusual-activity event on one market
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarketAlert {
+    pub platform: Platform,
+    pub market_id: String,
+    pub kind: AlertKind,
+    /// Multiple of the baseline for volume spikes and large prints, price
+    /// units moved for price jumps
+    pub magnitude: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Baselines and recent history for one market
+#[derive(Debug, Default)]
+struct MarketActivity {
+    trades_seen: u32,
+    typical_size: f64,
+    /// Epoch minute currently being counted
+    minute: i64,
+    minute_trades: u32,
+    minutes_seen: u32,
+    /// Trades per minute
+    trade_rate: f64,
+    /// YES prices within the jump window, oldest first
+    prices: VecDeque<(DateTime<Utc>, Decimal)>,
+    last_alert: HashMap<AlertKind, DateTime<Utc>>,
+}
+
+impl MarketActivity {
+    /// Fold finished minutes into the rate baseline once `minute` starts
+    fn roll_minute(&mut self, minute: i64, alpha: f64) {
+        if self.minute_trades == 0 && self.minutes_seen == 0 {
+            // First trade ever; nothing to fold yet
+            self.minute = minute;
+            return;
+        }
+        // Late trades count towards the current minute
+        if minute <= self.minute {
+            return;
+        }
+
+        let idle = (minute - self.minute - 1).min(MAX_IDLE_MINUTES) as usize;
+        let finished = std::iter::once(self.minute_trades as f64).chain(std::iter::repeat_n(0.0, idle));
+        for count in finished {
+            self.trade_rate = if self.minutes_seen == 0 {
+                count
+            } else {
+                ewma(self.trade_rate, count, alpha)
+            };
+            self.minutes_seen = self.minutes_seen.saturating_add(1);
+        }
+        self.minute = minute;
+        self.minute_trades = 0;
+    }
+
+    /// Whether an alert of `kind` at `now` is outside its cooldown; records it if so
+    fn take_alert(&mut self, kind: AlertKind, now: DateTime<Utc>, cooldown: Duration) -> bool {
+        let cooldown = chrono::Duration::from_std(cooldown).unwrap_or(chrono::Duration::zero());
+        if let Some(last) = self.last_alert.get(&kind) {
+            if now - *last < cooldown {
+                return false;
+            }
+        }
+        self.last_alert.insert(kind, now);
+        true
+    }
+}
+
+fn ewma(baseline: f64, sample: f64, alpha: f64) -> f64 {
+    alpha * sample + (1.0 - alpha) * baseline
+}
+
+/// Watches the trade stream for volume spikes, large prints and price jumps
+#[derive(Debug)]
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    markets: HashMap<(Platform, String), MarketActivity>,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Record a trade, returning the alerts it raises
+    ///
+    /// Time is the trade's own timestamp, so replayed or synthetic streams
+    /// behave the same as live ones.
+    pub fn observe(&mut self, trade: &Trade) -> Vec<MarketAlert> {
+        let config = &self.config;
+        let now = trade.timestamp;
+        let activity = self
+            .markets
+            .entry((trade.platform, trade.market_id.clone()))
+            .or_default();
+        let mut raised = Vec::new();
+
+        // Large print, checked against the baseline before this trade joins it
+        let size = trade.quantity.to_f64().unwrap_or(0.0);
+        if activity.trades_seen >= config.warmup_trades && activity.typical_size > 0.0 {
+            let multiple = size / activity.typical_size;
+            if multiple >= config.large_print_multiple {
+                raised.push((AlertKind::LargePrint, multiple));
+            }
+        }
+        activity.typical_size = if activity.trades_seen == 0 {
+            size
+        } else {
+            ewma(activity.typical_size, size, config.ewma_alpha)
+        };
+        activity.trades_seen = activity.trades_seen.saturating_add(1);
+
+        // Volume spike: this minute's count against the usual rate
+        activity.roll_minute(now.timestamp().div_euclid(60), config.ewma_alpha);
+        activity.minute_trades = activity.minute_trades.saturating_add(1);
+        if activity.minutes_seen >= config.warmup_minutes {
+            // Floor the baseline so a single trade in a quiet market isn't a spike
+            let baseline = activity.trade_rate.max(1.0);
+            let multiple = activity.minute_trades as f64 / baseline;
+            if multiple >= config.volume_spike_multiple {
+                raised.push((AlertKind::VolumeSpike, multiple));
+            }
+        }
+
+        // Price jump: the largest move from any price still in the window
+        let yes_price = match trade.outcome {
+            TradeOutcome::Yes => trade.price,
+            TradeOutcome::No => Decimal::ONE - trade.price,
+        };
+        let window = chrono::Duration::from_std(config.price_window).unwrap_or(chrono::Duration::zero());
+        while activity.prices.front().is_some_and(|(at, _)| *at < now - window) {
+            activity.prices.pop_front();
+        }
+        let jump = activity
+            .prices
+            .iter()
+            .map(|(_, price)| (yes_price - *price).abs())
+            .max()
+            .unwrap_or_default();
+        if jump >= config.price_jump {
+            raised.push((AlertKind::PriceJump, jump.to_f64().unwrap_or(0.0)));
+            // The new level is the reference from here on
+            activity.prices.clear();
+        }
+        activity.prices.push_back((now, yes_price));
+
+        raised
+            .into_iter()
+            .filter(|(kind, _)| activity.take_alert(*kind, now, config.cooldown))
+            .map(|(kind, magnitude)| MarketAlert {
+                platform: trade.platform,
+                market_id: trade.market_id.clone(),
+                kind,
+                magnitude,
+                timestamp: now,
+            })
+            .collect()
+    }
+
+    /// Drop a market that stopped streaming
+    pub fn forget(&mut self, platform: Platform, market_id: &str) {
+        self.markets.remove(&(platform, market_id.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn trade(seconds: i64, price: &str, quantity: u32) -> Trade {
+        Trade {
+            id: seconds.to_string(),
+            market_id: "m".to_string(),
+            platform: Platform::Polymarket,
+            timestamp: Utc.timestamp_opt(1_700_000_040 + seconds, 0).unwrap(),
+            price: price.parse().unwrap(),
+            quantity: Decimal::from(quantity),
+            outcome: TradeOutcome::Yes,
+            side: None,
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    /// Ten quiet minutes: six trades a minute of size 10, price wobbling a cent
+    fn warmed_up() -> (AnomalyDetector, i64) {
+        let mut detector = AnomalyDetector::new(AnomalyConfig::default());
+        for minute in 0..10 {
+            for i in 0..6 {
+                let price = if i % 2 == 0 { "0.50" } else { "0.51" };
+                let alerts = detector.observe(&trade(minute * 60 + i * 10, price, 10));
+                assert!(alerts.is_empty(), "baseline raised {:?}", alerts);
+            }
+        }
+        (detector, 600)
+    }
+
+    fn kinds(alerts: &[MarketAlert]) -> Vec<AlertKind> {
+        alerts.iter().map(|a| a.kind).collect()
+    }
+
+    #[test]
+    fn test_large_print_raises_one_alert() {
+        let (mut detector, start) = warmed_up();
+
+        let alerts = detector.observe(&trade(start + 5, "0.50", 120));
+        assert_eq!(kinds(&alerts), vec![AlertKind::LargePrint]);
+        assert!((alerts[0].magnitude - 12.0).abs() < 0.01);
+
+        // A second big print straight after is inside the cooldown
+        assert!(detector.observe(&trade(start + 15, "0.51", 120)).is_empty());
+    }
+
+    #[test]
+    fn test_burst_of_trades_is_a_volume_spike() {
+        let (mut detector, start) = warmed_up();
+
+        let mut alerts = Vec::new();
+        for i in 0..40 {
+            alerts.extend(detector.observe(&trade(start + i, "0.50", 10)));
+        }
+        assert_eq!(kinds(&alerts), vec![AlertKind::VolumeSpike]);
+        assert!((alerts[0].magnitude - 4.0).abs() < 0.01);
+        assert_eq!(alerts[0].market_id, "m");
+    }
+
+    #[test]
+    fn test_price_move_within_window_is_a_jump() {
+        let (mut detector, start) = warmed_up();
+
+        // Walks up from 0.50 to 0.62 over 30 seconds at normal size and pace
+        let mut alerts = Vec::new();
+        for (i, price) in ["0.53", "0.56", "0.59", "0.62"].iter().enumerate() {
+            alerts.extend(detector.observe(&trade(start + i as i64 * 10, price, 10)));
+        }
+        assert_eq!(kinds(&alerts), vec![AlertKind::PriceJump]);
+        assert!((alerts[0].magnitude - 0.12).abs() < 1e-9);
+
+        // The same move spread over several minutes never jumps
+        let (mut detector, start) = warmed_up();
+        for (i, price) in ["0.53", "0.56", "0.59", "0.62"].iter().enumerate() {
+            assert!(detector.observe(&trade(start + i as i64 * 90, price, 10)).is_empty());
+        }
+    }
+}
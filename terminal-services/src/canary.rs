@@ -0,0 +1,507 @@
+//! Canary Service
+//!
+//! Periodic self-test of the full data pipeline. A synthetic internal market
+//! receives synthetic trades through the same entry points real data uses:
+//! the trade collector's ingest path and, when wired up, the aggregator's
+//! feed publish path. Each downstream stage (storage, candles, stats,
+//! WebSocket delivery) is then verified within a deadline by reading what a
+//! client would: stored candles through the cached read path, and the
+//! updates forwarded to an internal subscribed client.
+//!
+//! The canary market is never part of the market cache, and every public
+//! aggregate (lists, stats, exports) filters it out via [`is_canary_market`].
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::Serialize;
+use terminal_core::{
+    Platform, PriceInterval, ServerMessage, SubscriptionType, Trade, TradeOutcome, TradeSide,
+};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::aggregator::MarketDataAggregator;
+use crate::candle_service::CandleService;
+use crate::market_stats::{MarketStatsService, Timeframe};
+use crate::trade_collector::TradeCollector;
+use crate::trade_storage::TradeStorage;
+use crate::websocket::{ClientId, WebSocketState};
+
+/// Market ID of the synthetic canary market
+pub const CANARY_MARKET_ID: &str = "__canary__";
+
+/// Platform the canary market is filed under
+pub const CANARY_PLATFORM: Platform = Platform::Polymarket;
+
+/// Check if a market ID belongs to the synthetic canary market
+pub fn is_canary_market(market_id: &str) -> bool {
+    market_id == CANARY_MARKET_ID
+}
+
+/// Configuration for the canary
+#[derive(Debug, Clone)]
+pub struct CanaryConfig {
+    /// How often to run the pipeline self-test (in seconds)
+    pub interval_secs: u64,
+    /// How long each stage may take before it is considered failed
+    pub stage_deadline: Duration,
+}
+
+impl Default for CanaryConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300,
+            stage_deadline: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Pipeline stages verified by the canary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanaryStage {
+    /// Trade and price persisted to TradeStorage
+    Storage,
+    /// 1m candle reflects the synthetic trade
+    Candles,
+    /// Market stats reflect the synthetic trade
+    Stats,
+    /// Internal WebSocket client received the broadcast
+    Broadcast,
+}
+
+/// Result of a single stage
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStageResult {
+    pub stage: CanaryStage,
+    pub passed: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of a full canary run
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryReport {
+    pub run_at: DateTime<Utc>,
+    pub passed: bool,
+    pub stages: Vec<CanaryStageResult>,
+}
+
+impl CanaryReport {
+    /// Get the stages that failed in this run
+    pub fn failed_stages(&self) -> Vec<CanaryStage> {
+        self.stages
+            .iter()
+            .filter(|s| !s.passed)
+            .map(|s| s.stage)
+            .collect()
+    }
+}
+
+/// Service that periodically exercises the data pipeline end to end
+pub struct CanaryService {
+    storage: Arc<TradeStorage>,
+    /// REST entry point: synthetic trades are ingested like collected ones
+    collector: Arc<TradeCollector>,
+    /// Feed entry point: synthetic trades are published like streamed ones
+    aggregator: Option<Arc<MarketDataAggregator>>,
+    candle_service: Arc<CandleService>,
+    stats_service: Arc<MarketStatsService>,
+    ws_state: Option<Arc<WebSocketState>>,
+    config: CanaryConfig,
+    /// Most recent run result
+    last_report: RwLock<Option<CanaryReport>>,
+}
+
+/// Internal WebSocket client subscribed to the canary market's trades
+struct CanaryClient {
+    ws_state: Arc<WebSocketState>,
+    client_id: ClientId,
+    rx: mpsc::Receiver<ServerMessage>,
+    forwarder: JoinHandle<()>,
+}
+
+impl CanaryClient {
+    /// Subscribe to canary trades through the same forwarder real clients use
+    fn connect(ws_state: &Arc<WebSocketState>) -> Self {
+        let client_id = ws_state.subscriptions.new_client_id();
+        ws_state.subscriptions.subscribe(
+            client_id,
+            &SubscriptionType::Trades {
+                platform: CANARY_PLATFORM,
+                market_id: CANARY_MARKET_ID.to_string(),
+            },
+        );
+        let (tx, rx) = mpsc::channel(16);
+        let forwarder = ws_state.forward_to_client(client_id, false, tx);
+        Self {
+            ws_state: Arc::clone(ws_state),
+            client_id,
+            rx,
+            forwarder,
+        }
+    }
+
+    fn disconnect(self) {
+        self.forwarder.abort();
+        self.ws_state.subscriptions.remove_client(self.client_id);
+    }
+}
+
+impl CanaryService {
+    /// Create a new CanaryService
+    pub fn new(
+        storage: Arc<TradeStorage>,
+        collector: Arc<TradeCollector>,
+        candle_service: Arc<CandleService>,
+        stats_service: Arc<MarketStatsService>,
+        ws_state: Option<Arc<WebSocketState>>,
+        config: CanaryConfig,
+    ) -> Self {
+        Self {
+            storage,
+            collector,
+            aggregator: None,
+            candle_service,
+            stats_service,
+            ws_state,
+            config,
+            last_report: RwLock::new(None),
+        }
+    }
+
+    /// Also publish a synthetic trade through the aggregator's feed path
+    pub fn with_aggregator(mut self, aggregator: Arc<MarketDataAggregator>) -> Self {
+        self.aggregator = Some(aggregator);
+        self
+    }
+
+    /// Get the most recent canary report
+    pub fn last_report(&self) -> Option<CanaryReport> {
+        self.last_report.read().clone()
+    }
+
+    /// Start the periodic canary loop
+    pub async fn start(self: Arc<Self>) {
+        info!(
+            "[Canary] Starting pipeline self-test every {}s",
+            self.config.interval_secs
+        );
+
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+        loop {
+            ticker.tick().await;
+            self.run_once().await;
+        }
+    }
+
+    /// Run the canary once and record the report
+    pub async fn run_once(&self) -> CanaryReport {
+        let rest_trade = Self::synthetic_trade("rest");
+        let feed_trade = self.aggregator.as_ref().map(|_| Self::synthetic_trade("feed"));
+        // Subscribed before anything is published so no update is missed
+        let mut client = self.ws_state.as_ref().map(CanaryClient::connect);
+        let mut stages = Vec::new();
+
+        stages.push(
+            self.timed(
+                CanaryStage::Storage,
+                self.check_storage(&rest_trade, feed_trade.as_ref()),
+            )
+            .await,
+        );
+        stages.push(
+            self.timed(CanaryStage::Candles, self.check_candles(&rest_trade))
+                .await,
+        );
+        stages.push(self.timed(CanaryStage::Stats, self.check_stats()).await);
+        let expected: Vec<&str> = std::iter::once(&rest_trade)
+            .chain(feed_trade.as_ref())
+            .map(|t| t.id.as_str())
+            .collect();
+        stages.push(
+            self.timed(
+                CanaryStage::Broadcast,
+                self.check_broadcast(client.as_mut(), &expected),
+            )
+            .await,
+        );
+
+        // Never leave synthetic data behind: the internal client, trades and
+        // everything derived from them in storage, and the cached reads
+        if let Some(client) = client {
+            client.disconnect();
+        }
+        if let Err(e) = self
+            .storage
+            .delete_market_data(CANARY_PLATFORM, CANARY_MARKET_ID)
+        {
+            warn!("[Canary] Failed to clean up canary data: {}", e);
+        }
+        self.candle_service
+            .invalidate_market(CANARY_PLATFORM, CANARY_MARKET_ID);
+        self.stats_service
+            .invalidate_market(CANARY_PLATFORM, CANARY_MARKET_ID);
+
+        let report = CanaryReport {
+            run_at: Utc::now(),
+            passed: stages.iter().all(|s| s.passed),
+            stages,
+        };
+
+        if report.passed {
+            info!("[Canary] Pipeline self-test passed");
+        } else {
+            error!(
+                "[Canary] ALERT: pipeline self-test failed at stages {:?}",
+                report.failed_stages()
+            );
+        }
+
+        *self.last_report.write() = Some(report.clone());
+        report
+    }
+
+    /// Run a stage under the configured deadline, recording its latency
+    async fn timed<F>(&self, stage: CanaryStage, check: F) -> CanaryStageResult
+    where
+        F: std::future::Future<Output = Result<(), String>>,
+    {
+        let started = Instant::now();
+        let outcome = match tokio::time::timeout(self.config.stage_deadline, check).await {
+            Ok(result) => result,
+            Err(_) => Err(format!(
+                "deadline of {}ms exceeded",
+                self.config.stage_deadline.as_millis()
+            )),
+        };
+
+        CanaryStageResult {
+            stage,
+            passed: outcome.is_ok(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: outcome.err(),
+        }
+    }
+
+    /// Build a synthetic trade for the canary market, tagged with the
+    /// entry point it goes through
+    fn synthetic_trade(source: &str) -> Trade {
+        let now = Utc::now();
+        Trade {
+            id: format!("canary-{}-{}", source, now.timestamp_millis()),
+            market_id: CANARY_MARKET_ID.to_string(),
+            platform: CANARY_PLATFORM,
+            timestamp: now,
+            price: Decimal::new(50, 2),
+            quantity: Decimal::ONE,
+            outcome: TradeOutcome::Yes,
+            side: Some(TradeSide::Buy),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    /// Stage 1: ingest through the trade collector (and publish through the
+    /// aggregator) and read the stored trade back
+    async fn check_storage(&self, rest_trade: &Trade, feed_trade: Option<&Trade>) -> Result<(), String> {
+        self.collector
+            .ingest_trades(CANARY_PLATFORM, CANARY_MARKET_ID, std::slice::from_ref(rest_trade))
+            .map_err(|e| format!("ingest_trades failed: {}", e))?;
+        if let (Some(aggregator), Some(trade)) = (&self.aggregator, feed_trade) {
+            aggregator.publish_feed_trade(trade.clone());
+        }
+        self.storage
+            .store_price(CANARY_PLATFORM, CANARY_MARKET_ID, Some(0.5), Some(0.5))
+            .map_err(|e| format!("store_price failed: {}", e))?;
+
+        if !self
+            .storage
+            .trade_exists(&rest_trade.id)
+            .map_err(|e| format!("trade_exists failed: {}", e))?
+        {
+            return Err("synthetic trade not found after insert".to_string());
+        }
+        Ok(())
+    }
+
+    /// Stage 2: the stored 1m candle covering the trade, as chart reads
+    /// see it, closes at its price
+    async fn check_candles(&self, trade: &Trade) -> Result<(), String> {
+        self.candle_service
+            .rebuild_candles(
+                CANARY_PLATFORM,
+                CANARY_MARKET_ID,
+                None,
+                PriceInterval::OneMinute,
+                trade.timestamp,
+                trade.timestamp,
+            )
+            .map_err(|e| format!("rebuild_candles failed: {}", e))?;
+
+        let candles = self
+            .candle_service
+            .get_stored_candles(
+                CANARY_PLATFORM,
+                CANARY_MARKET_ID,
+                None,
+                PriceInterval::OneMinute,
+                trade.timestamp - chrono::Duration::minutes(2),
+                Utc::now() + chrono::Duration::minutes(1),
+            )
+            .map_err(|e| format!("get_stored_candles failed: {}", e))?;
+
+        let price = trade.price.to_f64().unwrap_or(0.0);
+        match candles.last() {
+            Some(candle) if candle.close == price => Ok(()),
+            Some(candle) => Err(format!(
+                "latest stored 1m candle closed at {} instead of {}",
+                candle.close, trade.price
+            )),
+            None => Err("no stored 1m candle for synthetic trade".to_string()),
+        }
+    }
+
+    /// Stage 3: hourly stats count the synthetic trade
+    async fn check_stats(&self) -> Result<(), String> {
+        let stats = self.stats_service.get_market_stats(
+            CANARY_PLATFORM,
+            CANARY_MARKET_ID,
+            Decimal::new(50, 2),
+            Decimal::new(50, 2),
+            Timeframe::OneHour,
+        );
+
+        if stats.yes_txn_count == 0 || stats.volume <= Decimal::ZERO {
+            return Err(format!(
+                "stats did not reflect synthetic trade (txns={}, volume={})",
+                stats.yes_txn_count, stats.volume
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stage 4: the internal subscribed client was forwarded every
+    /// synthetic trade
+    async fn check_broadcast(&self, client: Option<&mut CanaryClient>, expected: &[&str]) -> Result<(), String> {
+        let client = client.ok_or_else(|| "WebSocket state not configured".to_string())?;
+
+        let mut missing: Vec<&str> = expected.to_vec();
+        while !missing.is_empty() {
+            match client.rx.recv().await {
+                Some(ServerMessage::TradeUpdate { trade, .. }) => {
+                    missing.retain(|id| *id != trade.id);
+                }
+                Some(_) => continue,
+                None => return Err("client forwarder stopped".to_string()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregator::AggregatorConfig;
+    use crate::trade_collector::TradeCollectorConfig;
+    use crate::MarketService;
+    use terminal_kalshi::KalshiClient;
+    use terminal_polymarket::PolymarketClient;
+
+    fn market_service() -> MarketService {
+        MarketService::new(KalshiClient::new(true), PolymarketClient::new())
+    }
+
+    fn create_canary(ws_state: Option<Arc<WebSocketState>>) -> (Arc<TradeStorage>, CanaryService) {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let collector = Arc::new(TradeCollector::new(
+            Arc::new(market_service()),
+            storage.clone(),
+            ws_state.clone(),
+            TradeCollectorConfig::default(),
+        ));
+        let canary = CanaryService::new(
+            storage.clone(),
+            collector,
+            Arc::new(CandleService::new(storage.clone())),
+            Arc::new(MarketStatsService::new(storage.clone(), None)),
+            ws_state,
+            CanaryConfig {
+                interval_secs: 60,
+                stage_deadline: Duration::from_millis(500),
+            },
+        );
+        (storage, canary)
+    }
+
+    #[tokio::test]
+    async fn test_canary_happy_path() {
+        let ws_state = Arc::new(WebSocketState::new(market_service()));
+        let aggregator = Arc::new(MarketDataAggregator::new(
+            AggregatorConfig::default(),
+            Arc::clone(&ws_state),
+            market_service(),
+        ));
+        let (storage, canary) = create_canary(Some(Arc::clone(&ws_state)));
+        let canary = canary.with_aggregator(aggregator);
+
+        // Twice, so a run can't pass on what the previous one left cached
+        for _ in 0..2 {
+            let report = canary.run_once().await;
+            assert!(report.passed, "stages failed: {:?}", report.stages);
+            assert_eq!(report.stages.len(), 4);
+        }
+        assert!(canary.last_report().is_some());
+
+        // Synthetic data must not be left behind
+        assert_eq!(
+            storage
+                .get_trade_count(CANARY_PLATFORM, CANARY_MARKET_ID)
+                .unwrap(),
+            0
+        );
+        assert_eq!(canary.candle_service.cache_stats().entries, 0);
+        assert!(!ws_state.subscriptions.has_subscriptions());
+    }
+
+    #[tokio::test]
+    async fn test_canary_detects_broken_stage() {
+        // No WebSocket state wired up - nothing reaches a client
+        let (_storage, canary) = create_canary(None);
+
+        let report = canary.run_once().await;
+
+        assert!(!report.passed);
+        assert_eq!(report.failed_stages(), vec![CanaryStage::Broadcast]);
+    }
+
+    #[tokio::test]
+    async fn test_canary_leaves_no_rows_behind() {
+        let (storage, canary) = create_canary(None);
+
+        canary.run_once().await;
+        canary.run_once().await;
+
+        assert_eq!(
+            storage
+                .count_market_data(CANARY_PLATFORM, CANARY_MARKET_ID)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_is_canary_market() {
+        assert!(is_canary_market(CANARY_MARKET_ID));
+        assert!(!is_canary_market("12345"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Candle Cache
+//!
+//! Short-lived in-memory cache of stored candle reads so repeated chart loads
+//! don't hit SQLite. Built on [`TtlCache`]: entries expire after a TTL, the
+//! least recently used entry is evicted past capacity, concurrent misses load
+//! once, and writes invalidate any overlapping range.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use terminal_core::Platform;
+
+use crate::trade_storage::StoredCandle;
+use crate::ttl_cache::TtlCache;
+
+/// Configuration for the candle cache
+#[derive(Debug, Clone)]
+pub struct CandleCacheConfig {
+    /// How long a cached read stays valid
+    pub ttl: Duration,
+    /// Maximum number of cached ranges before LRU eviction
+    pub max_entries: usize,
+}
+
+impl Default for CandleCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(10),
+            max_entries: 256,
+        }
+    }
+}
+
+/// Candle cache counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CandleCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// Cache key: one stored candle range read
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CandleCacheKey {
+    pub platform: Platform,
+    pub market_id: String,
+    pub outcome_id: String,
+    pub interval: String,
+    pub from: i64,
+    pub to: i64,
+}
+
+/// In-memory TTL/LRU cache for stored candle reads
+#[derive(Debug)]
+pub(crate) struct CandleCache {
+    inner: TtlCache<CandleCacheKey, Vec<StoredCandle>>,
+}
+
+impl CandleCache {
+    pub(crate) fn new(config: CandleCacheConfig) -> Self {
+        Self {
+            inner: TtlCache::new(config.ttl, config.max_entries),
+        }
+    }
+
+    /// Return the cached candles for `key`, computing them on a miss
+    pub(crate) fn get_or_load<E>(
+        &self,
+        key: CandleCacheKey,
+        load: impl FnOnce() -> Result<Vec<StoredCandle>, E>,
+    ) -> Result<Vec<StoredCandle>, E> {
+        self.inner.get_or_load(key, load)
+    }
+
+    /// Drop cached ranges of a series that overlap `[from, to]`
+    pub(crate) fn invalidate(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        interval: &str,
+        from: i64,
+        to: i64,
+    ) {
+        self.inner.retain(|key| {
+            !(key.platform == platform
+                && key.market_id == market_id
+                && key.outcome_id == outcome_id
+                && key.interval == interval
+                && key.from <= to
+                && key.to >= from)
+        });
+    }
+
+    /// Drop every cached range of a market
+    pub(crate) fn invalidate_market(&self, platform: Platform, market_id: &str) {
+        self.inner
+            .retain(|key| !(key.platform == platform && key.market_id == market_id));
+    }
+
+    pub(crate) fn stats(&self) -> CandleCacheStats {
+        CandleCacheStats {
+            hits: self.inner.hits(),
+            misses: self.inner.misses(),
+            entries: self.inner.entries(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn key(market_id: &str, from: i64, to: i64) -> CandleCacheKey {
+        CandleCacheKey {
+            platform: Platform::Kalshi,
+            market_id: market_id.to_string(),
+            outcome_id: String::new(),
+            interval: "1h".to_string(),
+            from,
+            to,
+        }
+    }
+
+    fn candle(timestamp: i64) -> StoredCandle {
+        StoredCandle {
+            timestamp,
+            open: 0.5,
+            high: 0.5,
+            low: 0.5,
+            close: 0.5,
+            volume: 1.0,
+            trade_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_hit_miss_and_invalidate() {
+        let cache = CandleCache::new(CandleCacheConfig::default());
+        let loads = AtomicUsize::new(0);
+        let load = || {
+            loads.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(vec![candle(3600)])
+        };
+
+        cache.get_or_load(key("m", 0, 7200), load).unwrap();
+        cache.get_or_load(key("m", 0, 7200), load).unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.stats(), CandleCacheStats { hits: 1, misses: 1, entries: 1 });
+
+        // A write outside the cached range leaves it alone
+        cache.invalidate(Platform::Kalshi, "m", "", "1h", 10_000, 20_000);
+        cache.get_or_load(key("m", 0, 7200), load).unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // An overlapping write forces a reload
+        cache.invalidate(Platform::Kalshi, "m", "", "1h", 3600, 3600);
+        assert_eq!(cache.stats().entries, 0);
+        cache.get_or_load(key("m", 0, 7200), load).unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+    }
+}
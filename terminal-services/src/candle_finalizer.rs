@@ -0,0 +1,400 @@
+//! Candle Finalizer
+//!
+//! Persists candles as their buckets close, so the candles table keeps up with
+//! live trading instead of waiting for a query or rebuild.
+//!
+//! Trades from the WebSocket trade broadcast are folded into one open bucket
+//! per (platform, market, interval) for the standard intervals. A bucket is
+//! written through [`CandleService::store_candle`] as soon as its window closes,
+//! either when a later trade rolls it over or on the periodic tick. Buckets
+//! without trades are never written.
+//!
+//! The first trade seen for a series loads its latest stored candle: a stored
+//! candle for the still-open bucket seeds it (no duplicate row, no lost
+//! volume), and trades for buckets at or before the latest stored one are
+//! ignored (rebuilds pick those up).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rust_decimal::prelude::ToPrimitive;
+use terminal_core::{Platform, PriceInterval, ServerMessage, Trade};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::canary::is_canary_market;
+use crate::candle_service::CandleService;
+use crate::trade_storage::StoredCandle;
+use crate::websocket::WebSocketState;
+
+/// Intervals persisted as their buckets close
+pub const FINALIZED_INTERVALS: [PriceInterval; 3] = [
+    PriceInterval::OneMinute,
+    PriceInterval::FiveMinutes,
+    PriceInterval::OneHour,
+];
+
+/// How often expired buckets are checked for finalization
+const FINALIZE_TICK: Duration = Duration::from_secs(1);
+
+type SeriesKey = (Platform, String, PriceInterval);
+
+/// A bucket accumulating trades until its window closes
+#[derive(Debug, Clone)]
+struct OpenBucket {
+    candle: StoredCandle,
+    first_trade_at: i64,
+    last_trade_at: i64,
+}
+
+impl OpenBucket {
+    fn from_trade(trade: &Trade, bucket: i64) -> Self {
+        let price = trade.price.to_f64().unwrap_or(0.0);
+        Self {
+            candle: StoredCandle {
+                timestamp: bucket,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: trade.quantity.to_f64().unwrap_or(0.0),
+                trade_count: 1,
+            },
+            first_trade_at: trade.timestamp.timestamp(),
+            last_trade_at: trade.timestamp.timestamp(),
+        }
+    }
+
+    /// Resume a bucket already stored before a restart
+    fn from_stored(candle: StoredCandle) -> Self {
+        let bucket = candle.timestamp;
+        Self {
+            candle,
+            first_trade_at: bucket,
+            last_trade_at: bucket,
+        }
+    }
+
+    fn add(&mut self, trade: &Trade) {
+        let price = trade.price.to_f64().unwrap_or(0.0);
+        let at = trade.timestamp.timestamp();
+        let candle = &mut self.candle;
+        candle.high = candle.high.max(price);
+        candle.low = candle.low.min(price);
+        candle.volume += trade.quantity.to_f64().unwrap_or(0.0);
+        candle.trade_count += 1;
+
+        if at < self.first_trade_at {
+            candle.open = price;
+            self.first_trade_at = at;
+        }
+        if at >= self.last_trade_at {
+            candle.close = price;
+            self.last_trade_at = at;
+        }
+    }
+}
+
+/// Per-series finalization state
+#[derive(Debug, Default)]
+struct SeriesState {
+    open: Option<OpenBucket>,
+    /// Start of the latest bucket already stored
+    stored_through: Option<i64>,
+}
+
+/// Writes candles to storage the moment their buckets close
+pub struct CandleFinalizer {
+    candle_service: Arc<CandleService>,
+    series: Mutex<HashMap<SeriesKey, SeriesState>>,
+}
+
+impl CandleFinalizer {
+    /// Create a new CandleFinalizer
+    pub fn new(candle_service: Arc<CandleService>) -> Self {
+        Self {
+            candle_service,
+            series: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run the finalizer, consuming trade broadcasts until the channel closes
+    pub async fn start(self: Arc<Self>, ws_state: Arc<WebSocketState>) {
+        info!("Starting candle finalizer for {:?}", FINALIZED_INTERVALS);
+        let mut rx = ws_state.subscriptions.subscribe_broadcast();
+        let mut ticker = tokio::time::interval(FINALIZE_TICK);
+
+        loop {
+            tokio::select! {
+                msg = rx.recv() => match msg {
+                    Ok(msg) => {
+                        if let ServerMessage::TradeUpdate { platform, market_id, trade, .. } = msg.message {
+                            self.handle_trade(platform, &market_id, &trade, Utc::now());
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Candle finalizer lagged, skipped {} messages", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = ticker.tick() => {
+                    self.finalize_expired(Utc::now());
+                }
+            }
+        }
+    }
+
+    /// Fold a trade into each interval's open bucket, writing any bucket it rolls over
+    pub fn handle_trade(&self, platform: Platform, market_id: &str, trade: &Trade, now: DateTime<Utc>) {
+        // The canary writes and removes its own candles
+        if is_canary_market(market_id) {
+            return;
+        }
+        let mut closed = Vec::new();
+
+        for interval in FINALIZED_INTERVALS {
+            let key = (platform, market_id.to_string(), interval);
+            if !self.series.lock().contains_key(&key) {
+                let state = self.load_state(platform, market_id, interval, now);
+                self.series.lock().entry(key.clone()).or_insert(state);
+            }
+
+            let interval_secs = interval.to_seconds() as i64;
+            let bucket = (trade.timestamp.timestamp() / interval_secs) * interval_secs;
+
+            let mut series = self.series.lock();
+            let Some(state) = series.get_mut(&key) else {
+                continue;
+            };
+            if state.stored_through.is_some_and(|ts| bucket <= ts) {
+                continue;
+            }
+
+            match state.open.as_mut() {
+                Some(open) if bucket == open.candle.timestamp => open.add(trade),
+                Some(open) if bucket > open.candle.timestamp => {
+                    let previous = std::mem::replace(open, OpenBucket::from_trade(trade, bucket));
+                    state.stored_through = Some(previous.candle.timestamp);
+                    closed.push((interval, previous.candle));
+                }
+                // Older bucket that had no trades of its own and has since closed
+                Some(_) => {}
+                None => {
+                    if bucket + interval_secs > now.timestamp() {
+                        state.open = Some(OpenBucket::from_trade(trade, bucket));
+                    }
+                }
+            }
+        }
+
+        for (interval, candle) in closed {
+            self.write(platform, market_id, interval, &candle);
+        }
+    }
+
+    /// Write every open bucket whose window has closed as of `now`
+    ///
+    /// Returns the number of candles written.
+    pub fn finalize_expired(&self, now: DateTime<Utc>) -> usize {
+        let mut closed = Vec::new();
+        {
+            let mut series = self.series.lock();
+            for ((platform, market_id, interval), state) in series.iter_mut() {
+                let expired = state.open.as_ref().is_some_and(|open| {
+                    open.candle.timestamp + interval.to_seconds() as i64 <= now.timestamp()
+                });
+                if expired {
+                    if let Some(open) = state.open.take() {
+                        state.stored_through = Some(open.candle.timestamp);
+                        closed.push((*platform, market_id.clone(), *interval, open.candle));
+                    }
+                }
+            }
+        }
+
+        for (platform, market_id, interval, candle) in &closed {
+            self.write(*platform, market_id, *interval, candle);
+        }
+        closed.len()
+    }
+
+    /// Number of buckets currently accumulating trades
+    pub fn open_count(&self) -> usize {
+        self.series.lock().values().filter(|s| s.open.is_some()).count()
+    }
+
+    /// Initial state for a series from its latest stored candle
+    fn load_state(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: PriceInterval,
+        now: DateTime<Utc>,
+    ) -> SeriesState {
+        let interval_secs = interval.to_seconds() as i64;
+        let latest = match self.candle_service.latest_stored_candle(platform, market_id, interval) {
+            Ok(latest) => latest,
+            Err(e) => {
+                warn!("Failed to load latest {} candle for {}: {}", interval.as_str(), market_id, e);
+                None
+            }
+        };
+
+        match latest {
+            // The stored candle's bucket is still open: keep accumulating into it
+            Some(candle) if candle.timestamp + interval_secs > now.timestamp() => SeriesState {
+                stored_through: Some(candle.timestamp - interval_secs),
+                open: Some(OpenBucket::from_stored(candle)),
+            },
+            Some(candle) => SeriesState {
+                open: None,
+                stored_through: Some(candle.timestamp),
+            },
+            None => SeriesState::default(),
+        }
+    }
+
+    fn write(&self, platform: Platform, market_id: &str, interval: PriceInterval, candle: &StoredCandle) {
+        if let Err(e) = self
+            .candle_service
+            .store_candle(platform, market_id, None, interval, candle)
+        {
+            warn!("Failed to store finalized {} candle for {}: {}", interval.as_str(), market_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canary::{CANARY_MARKET_ID, CANARY_PLATFORM};
+    use crate::trade_storage::TradeStorage;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use terminal_core::{TradeOutcome, TradeSide};
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    fn trade_at(secs: i64, price: Decimal) -> Trade {
+        Trade {
+            id: format!("t-{}-{}", secs, price),
+            market_id: "TEST-MARKET".to_string(),
+            platform: Platform::Kalshi,
+            timestamp: at(secs),
+            price,
+            quantity: dec!(10),
+            outcome: TradeOutcome::Yes,
+            side: Some(TradeSide::Buy),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    fn stored(storage: &TradeStorage, interval: &str) -> Vec<StoredCandle> {
+        storage
+            .get_candles(Platform::Kalshi, "TEST-MARKET", interval, at(0), at(100_000))
+            .unwrap()
+    }
+
+    fn setup() -> (Arc<TradeStorage>, CandleFinalizer) {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let finalizer = CandleFinalizer::new(Arc::new(CandleService::new(storage.clone())));
+        (storage, finalizer)
+    }
+
+    #[test]
+    fn test_bucket_written_when_window_closes() {
+        let (storage, finalizer) = setup();
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(65, dec!(0.50)), at(65));
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(90, dec!(0.55)), at(90));
+        assert_eq!(finalizer.open_count(), 3);
+
+        // One second before the 1m window closes nothing is written
+        assert_eq!(finalizer.finalize_expired(at(119)), 0);
+        assert!(stored(&storage, "1m").is_empty());
+
+        assert_eq!(finalizer.finalize_expired(at(120)), 1);
+        let minute = stored(&storage, "1m");
+        assert_eq!(minute.len(), 1);
+        assert_eq!(
+            (minute[0].timestamp, minute[0].open, minute[0].close, minute[0].volume, minute[0].trade_count),
+            (60, 0.50, 0.55, 20.0, 2)
+        );
+        // 5m and 1h buckets are still open
+        assert!(stored(&storage, "5m").is_empty());
+        assert_eq!(finalizer.finalize_expired(at(300)), 1);
+        assert_eq!(stored(&storage, "5m").len(), 1);
+        assert_eq!(finalizer.finalize_expired(at(3600)), 1);
+        assert_eq!(stored(&storage, "1h").len(), 1);
+    }
+
+    #[test]
+    fn test_rollover_writes_before_tick_and_skips_empty_buckets() {
+        let (storage, finalizer) = setup();
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(65, dec!(0.50)), at(65));
+
+        // Next trade lands two buckets later: 60 is written, 120 never had trades
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(185, dec!(0.60)), at(185));
+        let minute = stored(&storage, "1m");
+        assert_eq!(minute.iter().map(|c| c.timestamp).collect::<Vec<_>>(), vec![60]);
+
+        assert_eq!(finalizer.finalize_expired(at(600)), 2);
+        let minute = stored(&storage, "1m");
+        assert_eq!(minute.iter().map(|c| c.timestamp).collect::<Vec<_>>(), vec![60, 180]);
+
+        // A late trade for a finalized bucket does not rewrite it
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(70, dec!(0.90)), at(601));
+        assert_eq!(finalizer.finalize_expired(at(700)), 0);
+        assert_eq!(stored(&storage, "1m")[0].high, 0.50);
+    }
+
+    #[test]
+    fn test_canary_trades_are_never_finalized() {
+        let (storage, finalizer) = setup();
+        let mut trade = trade_at(65, dec!(0.50));
+        trade.platform = CANARY_PLATFORM;
+        trade.market_id = CANARY_MARKET_ID.to_string();
+        finalizer.handle_trade(CANARY_PLATFORM, CANARY_MARKET_ID, &trade, at(65));
+
+        assert_eq!(finalizer.open_count(), 0);
+        assert_eq!(finalizer.finalize_expired(at(3600)), 0);
+        let minute = storage
+            .get_candles(CANARY_PLATFORM, CANARY_MARKET_ID, "1m", at(0), at(100_000))
+            .unwrap();
+        assert!(minute.is_empty());
+    }
+
+    #[test]
+    fn test_resumes_from_stored_candle_after_restart() {
+        let (storage, finalizer) = setup();
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(65, dec!(0.50)), at(65));
+        finalizer.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(125, dec!(0.40)), at(125));
+        assert_eq!(stored(&storage, "1m").len(), 1);
+
+        // Simulate a restart mid-bucket with the open 1m bucket already stored
+        storage
+            .store_candle(Platform::Kalshi, "TEST-MARKET", "1m", 120, 0.40, 0.40, 0.40, 0.40, 10.0, 1)
+            .unwrap();
+        let restarted = CandleFinalizer::new(Arc::new(CandleService::new(storage.clone())));
+
+        // Trades for buckets already stored are ignored, the open one is resumed
+        restarted.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(100, dec!(0.90)), at(130));
+        restarted.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(150, dec!(0.45)), at(150));
+        restarted.finalize_expired(at(180));
+
+        let minute = stored(&storage, "1m");
+        assert_eq!(minute.len(), 2);
+        assert_eq!(minute[0].high, 0.50);
+        assert_eq!(
+            (minute[1].open, minute[1].close, minute[1].volume, minute[1].trade_count),
+            (0.40, 0.45, 20.0, 2)
+        );
+    }
+}
@@ -7,23 +7,185 @@
 //! 2. **Hybrid**: Combine native price API data with trade volume data (complete coverage)
 
 use chrono::{DateTime, Duration, Utc};
-use rust_decimal::Decimal;
-use std::collections::BTreeMap;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 use terminal_core::{Platform, PriceCandle, PriceHistory, PriceInterval, Trade, TradeSide};
 use terminal_polymarket::PriceHistoryPoint;
 
-use crate::trade_storage::{TradeStorage, TradeStorageError};
+use crate::candle_cache::{CandleCache, CandleCacheConfig, CandleCacheKey, CandleCacheStats};
+use crate::trade_storage::{StoredCandle, TradeStorage, TradeStorageError};
 
 /// Service for building price candles from stored trades
 pub struct CandleService {
     storage: Arc<TradeStorage>,
+    /// Short-lived cache of stored candle reads
+    cache: CandleCache,
 }
 
 impl CandleService {
     /// Create a new CandleService
     pub fn new(storage: Arc<TradeStorage>) -> Self {
-        Self { storage }
+        Self::with_cache_config(storage, CandleCacheConfig::default())
+    }
+
+    /// Create a new CandleService with custom read cache settings
+    pub fn with_cache_config(storage: Arc<TradeStorage>, cache_config: CandleCacheConfig) -> Self {
+        Self {
+            storage,
+            cache: CandleCache::new(cache_config),
+        }
+    }
+
+    /// Get read cache counters
+    pub fn cache_stats(&self) -> CandleCacheStats {
+        self.cache.stats()
+    }
+
+    /// Drop every cached read of a market (after deleting its candles)
+    pub fn invalidate_market(&self, platform: Platform, market_id: &str) {
+        self.cache.invalidate_market(platform, market_id);
+    }
+
+    /// Read stored candles through the cache
+    ///
+    /// The range is widened to whole buckets, so requests a few seconds
+    /// apart (charts ending at "now") share one cached read.
+    pub fn get_stored_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome: Option<&str>,
+        interval: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredCandle>, CandleServiceError> {
+        let outcome_id = outcome.unwrap_or("");
+        let interval_secs = interval.to_seconds() as i64;
+        let from_ts = from.timestamp().div_euclid(interval_secs) * interval_secs;
+        let to_ts = to.timestamp().div_euclid(interval_secs) * interval_secs + interval_secs - 1;
+        let key = CandleCacheKey {
+            platform,
+            market_id: market_id.to_string(),
+            outcome_id: outcome_id.to_string(),
+            interval: interval.as_str().to_string(),
+            from: from_ts,
+            to: to_ts,
+        };
+
+        let range_from = DateTime::from_timestamp(from_ts, 0).unwrap_or(from);
+        let range_to = DateTime::from_timestamp(to_ts, 0).unwrap_or(to);
+        Ok(self.cache.get_or_load(key, || {
+            self.storage.get_candles_for_outcome(
+                platform,
+                market_id,
+                outcome_id,
+                interval.as_str(),
+                range_from,
+                range_to,
+            )
+        })?)
+    }
+
+    /// Store a single candle and invalidate cached reads covering it
+    pub fn store_candle(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome: Option<&str>,
+        interval: PriceInterval,
+        candle: &StoredCandle,
+    ) -> Result<(), CandleServiceError> {
+        let outcome_id = outcome.unwrap_or("");
+        self.storage
+            .store_candle_for_outcome(platform, market_id, outcome_id, interval.as_str(), candle)?;
+        self.cache.invalidate(
+            platform,
+            market_id,
+            outcome_id,
+            interval.as_str(),
+            candle.timestamp,
+            candle.timestamp,
+        );
+        Ok(())
+    }
+
+    /// Replace stored candles in `[from, to]` and invalidate cached reads covering it
+    #[allow(clippy::too_many_arguments)]
+    fn replace_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome: Option<&str>,
+        interval: PriceInterval,
+        from: i64,
+        to: i64,
+        candles: &[StoredCandle],
+    ) -> Result<usize, CandleServiceError> {
+        let written = self
+            .storage
+            .replace_candles(platform, market_id, outcome, interval.as_str(), from, to, candles)?;
+        self.cache
+            .invalidate(platform, market_id, outcome.unwrap_or(""), interval.as_str(), from, to);
+        Ok(written)
+    }
+
+    /// Timestamp of the latest stored whole-market candle for an interval
+    pub fn latest_candle_timestamp(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: PriceInterval,
+    ) -> Result<Option<i64>, CandleServiceError> {
+        Ok(self
+            .storage
+            .get_latest_candle_timestamp(platform, market_id, "", interval.as_str())?)
+    }
+
+    /// Latest stored whole-market candle for an interval, read past the cache
+    pub fn latest_stored_candle(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: PriceInterval,
+    ) -> Result<Option<StoredCandle>, CandleServiceError> {
+        let Some(ts) = self.latest_candle_timestamp(platform, market_id, interval)? else {
+            return Ok(None);
+        };
+        let at = DateTime::from_timestamp(ts, 0).ok_or(CandleServiceError::InvalidTimeRange)?;
+        Ok(self
+            .storage
+            .get_candles(platform, market_id, interval.as_str(), at, at)?
+            .into_iter()
+            .next())
+    }
+
+    /// Bucket native platform price history into candles and upsert them
+    ///
+    /// Stored volume and trade counts are kept; only prices are refreshed.
+    /// Returns the number of candles written.
+    pub fn store_native_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: PriceInterval,
+        prices: &[PriceHistoryPoint],
+    ) -> Result<usize, CandleServiceError> {
+        let mut points: Vec<(i64, f64)> = prices.iter().map(|p| (p.t, p.p)).collect();
+        points.sort_by_key(|(ts, _)| *ts);
+
+        let candles = candles_from_mid_points(&points, interval.to_seconds() as i64);
+        let (Some(first), Some(last)) = (candles.first(), candles.last()) else {
+            return Ok(0);
+        };
+        let (from, to) = (first.timestamp, last.timestamp);
+
+        let written = self
+            .storage
+            .upsert_price_candles(platform, market_id, "", interval.as_str(), &candles)?;
+        self.cache
+            .invalidate(platform, market_id, "", interval.as_str(), from, to);
+        Ok(written)
     }
 
     /// Build candles for a market over a time range
@@ -38,38 +200,157 @@ impl CandleService {
         // Fetch trades for the time range
         let trades = self.storage.get_trades(platform, market_id, from, to)?;
 
-        if trades.is_empty() {
-            return Ok(PriceHistory {
-                market_id: market_id.to_string(),
+        Ok(PriceHistory {
+            market_id: market_id.to_string(),
+            platform,
+            interval,
+            candles: self.candles_from_trades(&trades, interval),
+        })
+    }
+
+    /// Build candles for a single outcome of a multi-outcome market
+    pub fn build_outcome_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        interval: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<PriceHistory, CandleServiceError> {
+        let trades = self
+            .storage
+            .get_trades_for_outcome(platform, market_id, outcome_id, from, to)?;
+
+        Ok(PriceHistory {
+            market_id: market_id.to_string(),
+            platform,
+            interval,
+            candles: self.candles_from_trades(&trades, interval),
+        })
+    }
+
+    /// Recompute stored candles for a market, or one of its outcomes
+    ///
+    /// Intervals of 5m and up are rolled up from stored 1m candles when any exist
+    /// in the range; otherwise candles are built from raw trades. The range is
+    /// widened to whole buckets and existing candles in it are replaced.
+    /// Returns the number of candles written.
+    pub fn rebuild_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome: Option<&str>,
+        interval: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<usize, CandleServiceError> {
+        let interval_secs = interval.to_seconds() as i64;
+        let from_ts = (from.timestamp() / interval_secs) * interval_secs;
+        let to_ts = (to.timestamp() / interval_secs) * interval_secs + interval_secs - 1;
+
+        if interval != PriceInterval::OneMinute {
+            let minute_candles = self.storage.get_candles_for_outcome(
                 platform,
-                interval,
-                candles: vec![],
-            });
+                market_id,
+                outcome.unwrap_or(""),
+                PriceInterval::OneMinute.as_str(),
+                DateTime::from_timestamp(from_ts, 0).unwrap_or(from),
+                DateTime::from_timestamp(to_ts, 0).unwrap_or(to),
+            )?;
+            if !minute_candles.is_empty() {
+                let candles = rollup_candles(&minute_candles, interval_secs);
+                return self.replace_candles(platform, market_id, outcome, interval, from_ts, to_ts, &candles);
+            }
         }
 
-        // Group trades by candle bucket
+        let range_from = DateTime::from_timestamp(from_ts, 0).unwrap_or(from);
+        let range_to = DateTime::from_timestamp(to_ts, 0).unwrap_or(to);
+        let trades = match outcome {
+            Some(outcome_id) => self
+                .storage
+                .get_trades_for_outcome(platform, market_id, outcome_id, range_from, range_to)?,
+            None => self.storage.get_trades(platform, market_id, range_from, range_to)?,
+        };
+
+        let candles: Vec<StoredCandle> = Self::bucket_trades(&trades, interval)
+            .into_iter()
+            .map(|(bucket_ts, bucket_trades)| {
+                let candle = self.build_candle_from_trades(bucket_ts, &bucket_trades);
+                StoredCandle {
+                    timestamp: bucket_ts,
+                    open: candle.open.to_f64().unwrap_or(0.0),
+                    high: candle.high.to_f64().unwrap_or(0.0),
+                    low: candle.low.to_f64().unwrap_or(0.0),
+                    close: candle.close.to_f64().unwrap_or(0.0),
+                    volume: candle.volume.to_f64().unwrap_or(0.0),
+                    trade_count: bucket_trades.len() as i64,
+                }
+            })
+            .collect();
+
+        self.replace_candles(platform, market_id, outcome, interval, from_ts, to_ts, &candles)
+    }
+
+    /// Roll stored candles of one interval up into a larger interval and persist them
+    ///
+    /// `target` must be a whole multiple of `source`. Returns the number of
+    /// target candles written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn aggregate_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome: Option<&str>,
+        source: PriceInterval,
+        target: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<usize, CandleServiceError> {
+        let source_secs = source.to_seconds() as i64;
+        let target_secs = target.to_seconds() as i64;
+        if target_secs <= source_secs || target_secs % source_secs != 0 {
+            return Err(CandleServiceError::InvalidInterval);
+        }
+
+        let source_candles = self.storage.get_candles_for_outcome(
+            platform,
+            market_id,
+            outcome.unwrap_or(""),
+            source.as_str(),
+            from,
+            to,
+        )?;
+        let candles = rollup_candles(&source_candles, target_secs);
+
+        for candle in &candles {
+            self.store_candle(platform, market_id, outcome, target, candle)?;
+        }
+
+        Ok(candles.len())
+    }
+
+    /// Group trades by interval bucket start (unix seconds)
+    fn bucket_trades(trades: &[Trade], interval: PriceInterval) -> BTreeMap<i64, Vec<&Trade>> {
         let interval_secs = interval.to_seconds() as i64;
         let mut buckets: BTreeMap<i64, Vec<&Trade>> = BTreeMap::new();
 
-        for trade in &trades {
+        for trade in trades {
             let bucket = (trade.timestamp.timestamp() / interval_secs) * interval_secs;
             buckets.entry(bucket).or_default().push(trade);
         }
 
-        // Build candles from buckets
-        let candles: Vec<PriceCandle> = buckets
+        buckets
+    }
+
+    /// Group trades into interval buckets and build one candle per bucket
+    fn candles_from_trades(&self, trades: &[Trade], interval: PriceInterval) -> Vec<PriceCandle> {
+        Self::bucket_trades(trades, interval)
             .into_iter()
             .map(|(bucket_ts, bucket_trades)| {
                 self.build_candle_from_trades(bucket_ts, &bucket_trades)
             })
-            .collect();
-
-        Ok(PriceHistory {
-            market_id: market_id.to_string(),
-            platform,
-            interval,
-            candles,
-        })
+            .collect()
     }
 
     /// Build a single candle from a set of trades
@@ -185,6 +466,209 @@ impl CandleService {
         history.candles = filled_candles;
     }
 
+    /// Get stored candles with missing buckets filled in, for contiguous charting
+    ///
+    /// Gaps (including trailing ones up to `to`) become flat candles at the previous
+    /// close with zero volume. Buckets before the first stored candle are omitted.
+    pub fn get_candles_filled(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome: Option<&str>,
+        interval: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredCandle>, CandleServiceError> {
+        let candles = self.get_stored_candles(platform, market_id, outcome, interval, from, to)?;
+
+        Ok(fill_stored_candle_gaps(candles, interval.to_seconds() as i64, to.timestamp()))
+    }
+
+    /// Get stored candles, synthesizing trade-less buckets from mid-prices
+    ///
+    /// Mid-prices come from orderbook snapshot mids and price snapshots, keyed by
+    /// the outcome token when one is given. In `Auto` mode a trade candle always
+    /// wins over a mid candle for the same bucket. With a point budget the series
+    /// is then rolled up to the smallest interval that fits it. Gap filling, the
+    /// return anchor and the transform are applied last, in that order. Nothing
+    /// is persisted.
+    pub fn get_candles_with_source(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        options: &CandleReadOptions<'_>,
+    ) -> Result<CandleSeries, CandleServiceError> {
+        let interval_secs = interval.to_seconds() as i64;
+        let outcome = options.outcome;
+
+        let trade_candles = match options.source {
+            CandleSourceMode::Mid => Vec::new(),
+            _ => self.get_stored_candles(platform, market_id, outcome, interval, from, to)?,
+        };
+        let mid_candles = match options.source {
+            CandleSourceMode::Trades => Vec::new(),
+            _ => {
+                let points = self.mid_points(platform, outcome.unwrap_or(market_id), from, to)?;
+                candles_from_mid_points(&points, interval_secs)
+            }
+        };
+
+        let mut merged = merge_candle_sources(trade_candles, mid_candles);
+        let bucket_secs = match options.max_points {
+            Some(max_points) => {
+                downsample_bucket_secs(interval, from.timestamp(), to.timestamp(), max_points)
+            }
+            None => interval_secs,
+        };
+        if bucket_secs > interval_secs {
+            merged = rollup_sourced_candles(merged, bucket_secs);
+        }
+
+        let mut candles = if options.fill {
+            fill_sourced_candle_gaps(merged, bucket_secs, to.timestamp())
+        } else {
+            merged
+        };
+        // Anchor on raw closes, before a transform can rewrite them
+        let anchored_at = options.anchor.and_then(|anchor| {
+            apply_candle_anchor(&mut candles, anchor.resolve(from.timestamp(), to.timestamp()))
+        });
+        Ok(CandleSeries {
+            candles: apply_candle_transform(candles, options.transform),
+            effective_interval: bucket_label(bucket_secs),
+            anchored_at,
+        })
+    }
+
+    /// Fetch several markets' stored candles concurrently, aligned to one time axis
+    ///
+    /// The axis is the sorted union of every series' bucket timestamps; each
+    /// series has one entry per axis timestamp, `None` where that market has no
+    /// candle. Series are keyed by market ID.
+    pub async fn get_candles_multi(
+        self: &Arc<Self>,
+        requests: Vec<(Platform, String)>,
+        interval: PriceInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<CandleComparison, CandleServiceError> {
+        let handles: Vec<_> = requests
+            .into_iter()
+            .map(|(platform, market_id)| {
+                let service = Arc::clone(self);
+                tokio::task::spawn_blocking(move || {
+                    let candles =
+                        service.get_stored_candles(platform, &market_id, None, interval, from, to)?;
+                    Ok::<_, CandleServiceError>((market_id, candles))
+                })
+            })
+            .collect();
+
+        let mut fetched = Vec::with_capacity(handles.len());
+        for handle in handles {
+            fetched.push(handle.await.map_err(|e| CandleServiceError::Task(e.to_string()))??);
+        }
+
+        Ok(align_candle_series(fetched))
+    }
+
+    /// Rolling realized volatility of a market's stored candle closes
+    ///
+    /// Each point is the standard deviation of the last `window` log-returns,
+    /// stamped with the timestamp of the candle that closes the window. Empty
+    /// buckets are filled flat first so gaps count as no movement. Returns an
+    /// empty series when there are not enough candles for one window.
+    pub fn get_volatility(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: PriceInterval,
+        window: usize,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(i64, f64)>, CandleServiceError> {
+        let candles = self.get_candles_filled(platform, market_id, None, interval, from, to)?;
+        Ok(rolling_volatility(&candles, window))
+    }
+
+    /// OHLC summary statistics for an arbitrary range
+    ///
+    /// Computed from raw trades, or from stored 1m candles when those are denser
+    /// (e.g. after old trades were pruned). Returns `None` when the range holds
+    /// neither.
+    pub fn get_range_summary(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<RangeSummary>, CandleServiceError> {
+        if from > to {
+            return Err(CandleServiceError::InvalidTimeRange);
+        }
+
+        let trades = self.storage.get_trades(platform, market_id, from, to)?;
+        let candles =
+            self.get_stored_candles(platform, market_id, None, PriceInterval::OneMinute, from, to)?;
+
+        if !trades.is_empty() && trades.len() >= candles.len() {
+            let prices: Vec<f64> = trades.iter().map(|t| t.price.to_f64().unwrap_or(0.0)).collect();
+            return Ok(Some(RangeSummary {
+                open: prices[0],
+                high: prices.iter().copied().fold(f64::MIN, f64::max),
+                low: prices.iter().copied().fold(f64::MAX, f64::min),
+                close: prices[prices.len() - 1],
+                volume: trades.iter().map(|t| t.quantity.to_f64().unwrap_or(0.0)).sum(),
+                trade_count: trades.len() as i64,
+                max_drawdown: max_drawdown(prices.iter().copied()),
+                source: RangeSummarySource::Trades,
+            }));
+        }
+
+        let (Some(first), Some(last)) = (candles.first(), candles.last()) else {
+            return Ok(None);
+        };
+        // Order of highs and lows inside a candle is unknown: walk the closes
+        let path = std::iter::once(first.open).chain(candles.iter().map(|c| c.close));
+        Ok(Some(RangeSummary {
+            open: first.open,
+            high: candles.iter().map(|c| c.high).fold(f64::MIN, f64::max),
+            low: candles.iter().map(|c| c.low).fold(f64::MAX, f64::min),
+            close: last.close,
+            volume: candles.iter().map(|c| c.volume).sum(),
+            trade_count: candles.iter().map(|c| c.trade_count).sum(),
+            max_drawdown: max_drawdown(path),
+            source: RangeSummarySource::Candles,
+        }))
+    }
+
+    /// Time-ordered (timestamp, mid) points from orderbook and price snapshots
+    fn mid_points(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(i64, f64)>, CandleServiceError> {
+        let mut points: Vec<(i64, f64)> = self
+            .storage
+            .get_spread_history(platform, market_id, from, to)?
+            .into_iter()
+            .filter_map(|p| p.metrics.mid.map(|mid| (p.timestamp, mid)))
+            .collect();
+        points.extend(
+            self.storage
+                .get_price_snapshots(platform, market_id, from, to)?
+                .into_iter()
+                .map(|s| (s.timestamp, s.yes_price)),
+        );
+        points.sort_by_key(|(ts, _)| *ts);
+        Ok(points)
+    }
+
     // ========================================================================
     // Hybrid Candle Building (Native Prices + Trade Volumes)
     // ========================================================================
@@ -353,52 +837,558 @@ pub enum CandleServiceError {
 
     #[error("Invalid time range")]
     InvalidTimeRange,
+
+    #[error("Target interval must be a multiple of the source interval")]
+    InvalidInterval,
+
+    #[error("Background task failed: {0}")]
+    Task(String),
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rust_decimal_macros::dec;
-    use terminal_core::{TradeOutcome, TradeSide};
+/// Several markets' candles aligned to a shared time axis
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CandleComparison {
+    /// Bucket timestamps shared by every series (unix seconds, ascending)
+    pub timestamps: Vec<i64>,
+    /// market_id -> one entry per axis timestamp (`null` where missing)
+    pub series: HashMap<String, Vec<Option<StoredCandle>>>,
+}
 
-    fn create_test_trade(
-        id: &str,
-        market_id: &str,
-        price: Decimal,
-        timestamp: DateTime<Utc>,
-        side: TradeSide,
-    ) -> Trade {
-        Trade {
-            id: id.to_string(),
-            market_id: market_id.to_string(),
-            platform: Platform::Kalshi,
-            timestamp,
-            price,
-            quantity: dec!(100),
-            outcome: TradeOutcome::Yes,
-            side: Some(side),
-            transaction_hash: None,
-        }
-    }
+/// Align per-market candle series to the union of their bucket timestamps
+fn align_candle_series(fetched: Vec<(String, Vec<StoredCandle>)>) -> CandleComparison {
+    let timestamps: Vec<i64> = fetched
+        .iter()
+        .flat_map(|(_, candles)| candles.iter().map(|c| c.timestamp))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
 
-    #[test]
-    fn test_build_candles() {
-        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
-        let service = CandleService::new(storage.clone());
+    let series = fetched
+        .into_iter()
+        .map(|(market_id, candles)| {
+            let mut by_ts: HashMap<i64, StoredCandle> =
+                candles.into_iter().map(|c| (c.timestamp, c)).collect();
+            let aligned = timestamps.iter().map(|ts| by_ts.remove(ts)).collect();
+            (market_id, aligned)
+        })
+        .collect();
 
-        let base_time = Utc::now() - Duration::hours(1);
+    CandleComparison { timestamps, series }
+}
 
-        // Create trades with mixed buy/sell sides
-        let trades = vec![
-            create_test_trade("t1", "market1", dec!(0.50), base_time, TradeSide::Buy),
-            create_test_trade("t2", "market1", dec!(0.55), base_time + Duration::minutes(5), TradeSide::Buy),
-            create_test_trade("t3", "market1", dec!(0.45), base_time + Duration::minutes(10), TradeSide::Sell),
-            create_test_trade("t4", "market1", dec!(0.52), base_time + Duration::minutes(15), TradeSide::Buy),
-        ];
+/// Where a candle's prices came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CandleSource {
+    /// Built from executed trades
+    Trade,
+    /// Synthesized from orderbook/price snapshot mid-prices
+    Mid,
+}
 
-        for trade in &trades {
-            storage.store_trade(trade).unwrap();
-        }
+/// What a range summary was computed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RangeSummarySource {
+    /// Raw stored trades
+    Trades,
+    /// Stored 1m candles
+    Candles,
+}
+
+/// OHLC summary statistics for a time range ("period stats")
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RangeSummary {
+    /// First trade price in the range
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    /// Last trade price in the range
+    pub close: f64,
+    pub volume: f64,
+    pub trade_count: i64,
+    /// Largest peak-to-trough decline in probability (price units, not percent)
+    pub max_drawdown: f64,
+    pub source: RangeSummarySource,
+}
+
+/// Which candle sources to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandleSourceMode {
+    /// Trade candles only
+    #[default]
+    Trades,
+    /// Mid-price candles only
+    Mid,
+    /// Trade candles, with mid-price candles for buckets that have no trades
+    Auto,
+}
+
+impl CandleSourceMode {
+    /// Parse from string representation
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "trades" => Some(Self::Trades),
+            "mid" => Some(Self::Mid),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// Server-side transform applied to a candle series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandleTransform {
+    /// Candles as stored
+    #[default]
+    None,
+    /// Heikin-Ashi candles (OHLC replaced)
+    HeikinAshi,
+    /// Simple moving average of closes over N candles, attached as `ma`
+    Sma(usize),
+    /// Exponential moving average of closes over N candles, attached as `ma`
+    Ema(usize),
+}
+
+impl CandleTransform {
+    /// Parse `none`, `heikin_ashi`, `sma:<n>` or `ema:<n>` (n >= 1)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => return Some(Self::None),
+            "heikin_ashi" => return Some(Self::HeikinAshi),
+            _ => {}
+        }
+        let (kind, period) = s.split_once(':')?;
+        let period: usize = period.parse().ok().filter(|n| *n > 0)?;
+        match kind {
+            "sma" => Some(Self::Sma(period)),
+            "ema" => Some(Self::Ema(period)),
+            _ => None,
+        }
+    }
+}
+
+/// Reference point for `pct_change` return overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandleAnchor {
+    /// Midnight UTC of the day the range ends in
+    MidnightUtc,
+    /// Start of the requested range
+    RangeStart,
+    /// A specific unix timestamp (seconds)
+    Timestamp(i64),
+}
+
+impl CandleAnchor {
+    /// Parse `midnight_utc`, `range_start` or `timestamp:<unix seconds>`
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "midnight_utc" => Some(Self::MidnightUtc),
+            "range_start" => Some(Self::RangeStart),
+            _ => {
+                let ts = s.strip_prefix("timestamp:")?;
+                ts.parse().ok().map(Self::Timestamp)
+            }
+        }
+    }
+
+    /// Anchor time for a range `[from, to]`
+    fn resolve(&self, from: i64, to: i64) -> i64 {
+        match self {
+            Self::MidnightUtc => to.div_euclid(86400) * 86400,
+            Self::RangeStart => from,
+            Self::Timestamp(ts) => *ts,
+        }
+    }
+}
+
+/// Options for reading a stored candle series
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CandleReadOptions<'a> {
+    /// Outcome/token series of a multi-outcome market (`None` = whole market)
+    pub outcome: Option<&'a str>,
+    /// Which candle sources to read
+    pub source: CandleSourceMode,
+    /// Fill empty buckets with flat zero-volume candles
+    pub fill: bool,
+    /// Transform applied to the final series
+    pub transform: CandleTransform,
+    /// Attach each candle's percent change versus the close at this anchor
+    pub anchor: Option<CandleAnchor>,
+    /// Roll the series up to a coarser interval so it has at most this many buckets
+    pub max_points: Option<usize>,
+}
+
+/// A candle series read with [`CandleReadOptions`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleSeries {
+    pub candles: Vec<SourcedCandle>,
+    /// Interval of the returned candles: the requested one, or a coarser one
+    /// picked to fit `max_points` (`"<n>d"` past one day)
+    pub effective_interval: String,
+    /// Timestamp of the candle the return overlay is anchored on; later than
+    /// the requested anchor when it predates the available history
+    pub anchored_at: Option<i64>,
+}
+
+/// A stored candle tagged with its source
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SourcedCandle {
+    #[serde(flatten)]
+    pub candle: StoredCandle,
+    pub source: CandleSource,
+    /// Moving average of closes at this candle (SMA/EMA transforms only;
+    /// `null` until the window is full)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ma: Option<Option<f64>>,
+    /// Percent change of the close versus the anchor close (anchored reads only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pct_change: Option<f64>,
+}
+
+/// Attach each candle's percent change versus the close of the anchor candle
+///
+/// The anchor candle is the latest one starting at or before `anchor`, or the
+/// earliest candle when `anchor` predates the series. Returns its timestamp,
+/// or `None` for an empty series. A zero anchor close leaves `pct_change` unset.
+fn apply_candle_anchor(candles: &mut [SourcedCandle], anchor: i64) -> Option<i64> {
+    let anchor_candle = candles
+        .iter()
+        .rev()
+        .find(|c| c.candle.timestamp <= anchor)
+        .or(candles.first())?;
+    let (anchored_at, reference) = (anchor_candle.candle.timestamp, anchor_candle.candle.close);
+
+    for c in candles.iter_mut() {
+        c.pct_change = (reference != 0.0).then(|| (c.candle.close - reference) / reference * 100.0);
+    }
+    Some(anchored_at)
+}
+
+/// Apply a transform to a time-ordered candle series
+fn apply_candle_transform(mut candles: Vec<SourcedCandle>, transform: CandleTransform) -> Vec<SourcedCandle> {
+    match transform {
+        CandleTransform::None => {}
+        CandleTransform::HeikinAshi => {
+            // (ha_open, ha_close) of the previous bar
+            let mut prev: Option<(f64, f64)> = None;
+            for c in candles.iter_mut().map(|c| &mut c.candle) {
+                let ha_close = (c.open + c.high + c.low + c.close) / 4.0;
+                // First bar has no previous HA candle: use its own open/close midpoint
+                let ha_open = match prev {
+                    Some((prev_open, prev_close)) => (prev_open + prev_close) / 2.0,
+                    None => (c.open + c.close) / 2.0,
+                };
+                c.high = c.high.max(ha_open).max(ha_close);
+                c.low = c.low.min(ha_open).min(ha_close);
+                c.open = ha_open;
+                c.close = ha_close;
+                prev = Some((ha_open, ha_close));
+            }
+        }
+        CandleTransform::Sma(period) => {
+            let closes: Vec<f64> = candles.iter().map(|c| c.candle.close).collect();
+            for (i, candle) in candles.iter_mut().enumerate() {
+                candle.ma = Some((i + 1 >= period).then(|| {
+                    closes[i + 1 - period..=i].iter().sum::<f64>() / period as f64
+                }));
+            }
+        }
+        CandleTransform::Ema(period) => {
+            // Seeded with the SMA of the first `period` closes
+            let alpha = 2.0 / (period as f64 + 1.0);
+            let mut ema: Option<f64> = None;
+            let mut seed_sum = 0.0;
+            for (i, candle) in candles.iter_mut().enumerate() {
+                let close = candle.candle.close;
+                ema = match ema {
+                    Some(prev) => Some(alpha * close + (1.0 - alpha) * prev),
+                    None => {
+                        seed_sum += close;
+                        (i + 1 == period).then(|| seed_sum / period as f64)
+                    }
+                };
+                candle.ma = Some(ema);
+            }
+        }
+    }
+    candles
+}
+
+/// Bucket time-ordered mid-price points into zero-volume candles
+fn candles_from_mid_points(points: &[(i64, f64)], interval_secs: i64) -> Vec<StoredCandle> {
+    let point_candles: Vec<StoredCandle> = points
+        .iter()
+        .map(|&(timestamp, mid)| StoredCandle {
+            timestamp,
+            open: mid,
+            high: mid,
+            low: mid,
+            close: mid,
+            volume: 0.0,
+            trade_count: 0,
+        })
+        .collect();
+    rollup_candles(&point_candles, interval_secs)
+}
+
+/// Merge trade and mid candles by bucket; trade candles win
+fn merge_candle_sources(trades: Vec<StoredCandle>, mids: Vec<StoredCandle>) -> Vec<SourcedCandle> {
+    let mut merged: BTreeMap<i64, SourcedCandle> = BTreeMap::new();
+    for candle in mids {
+        merged.insert(
+            candle.timestamp,
+            SourcedCandle { candle, source: CandleSource::Mid, ma: None, pct_change: None },
+        );
+    }
+    for candle in trades {
+        merged.insert(
+            candle.timestamp,
+            SourcedCandle { candle, source: CandleSource::Trade, ma: None, pct_change: None },
+        );
+    }
+    merged.into_values().collect()
+}
+
+/// Gap-fill sourced candles; filled buckets carry the previous candle's source
+fn fill_sourced_candle_gaps(candles: Vec<SourcedCandle>, interval_secs: i64, to: i64) -> Vec<SourcedCandle> {
+    let sources: BTreeMap<i64, CandleSource> =
+        candles.iter().map(|c| (c.candle.timestamp, c.source)).collect();
+    let mut source = CandleSource::Trade;
+
+    fill_stored_candle_gaps(candles.into_iter().map(|c| c.candle).collect(), interval_secs, to)
+        .into_iter()
+        .map(|candle| {
+            if let Some(s) = sources.get(&candle.timestamp) {
+                source = *s;
+            }
+            SourcedCandle { candle, source, ma: None, pct_change: None }
+        })
+        .collect()
+}
+
+/// Standard intervals, finest first
+const STANDARD_INTERVALS: [PriceInterval; 6] = [
+    PriceInterval::OneMinute,
+    PriceInterval::FiveMinutes,
+    PriceInterval::FifteenMinutes,
+    PriceInterval::OneHour,
+    PriceInterval::FourHours,
+    PriceInterval::OneDay,
+];
+
+/// Smallest bucket size covering `[from, to]` in at most `max_points` buckets
+///
+/// Tries the standard intervals from `interval` upwards (each a multiple of the
+/// ones below it), then whole multiples of a day. Depends only on its inputs,
+/// so the same request always gets the same buckets.
+pub(crate) fn downsample_bucket_secs(interval: PriceInterval, from: i64, to: i64, max_points: usize) -> i64 {
+    let max_points = max_points.max(1) as i64;
+    let buckets = |secs: i64| to.div_euclid(secs) - from.div_euclid(secs) + 1;
+
+    let base = interval.to_seconds() as i64;
+    if let Some(secs) = STANDARD_INTERVALS
+        .iter()
+        .map(|i| i.to_seconds() as i64)
+        .filter(|secs| *secs >= base)
+        .find(|secs| buckets(*secs) <= max_points)
+    {
+        return secs;
+    }
+
+    let day = PriceInterval::OneDay.to_seconds() as i64;
+    let mut days = ((to - from) / day / max_points).max(1);
+    while buckets(days * day) > max_points {
+        days += 1;
+    }
+    days * day
+}
+
+/// Label for a bucket size: a standard interval name, or `"<n>d"`
+fn bucket_label(secs: i64) -> String {
+    STANDARD_INTERVALS
+        .iter()
+        .find(|i| i.to_seconds() as i64 == secs)
+        .map(|i| i.as_str().to_string())
+        .unwrap_or_else(|| format!("{}d", secs / PriceInterval::OneDay.to_seconds() as i64))
+}
+
+/// Roll sourced candles up into `target_secs` buckets; a bucket with any trade
+/// candle counts as trade-sourced
+fn rollup_sourced_candles(candles: Vec<SourcedCandle>, target_secs: i64) -> Vec<SourcedCandle> {
+    let mut traded: BTreeSet<i64> = BTreeSet::new();
+    for c in &candles {
+        if c.source == CandleSource::Trade {
+            traded.insert((c.candle.timestamp / target_secs) * target_secs);
+        }
+    }
+
+    let inner: Vec<StoredCandle> = candles.into_iter().map(|c| c.candle).collect();
+    rollup_candles(&inner, target_secs)
+        .into_iter()
+        .map(|candle| {
+            let source = if traded.contains(&candle.timestamp) {
+                CandleSource::Trade
+            } else {
+                CandleSource::Mid
+            };
+            SourcedCandle { candle, source, ma: None, pct_change: None }
+        })
+        .collect()
+}
+
+/// Largest peak-to-trough decline along a time-ordered price path
+fn max_drawdown(prices: impl IntoIterator<Item = f64>) -> f64 {
+    let mut peak = f64::MIN;
+    let mut drawdown: f64 = 0.0;
+    for price in prices {
+        peak = peak.max(price);
+        drawdown = drawdown.max(peak - price);
+    }
+    drawdown
+}
+
+/// Prices are clamped into `[VOL_PRICE_EPSILON, 1 - VOL_PRICE_EPSILON]` before
+/// taking logs so closes at exactly 0 or 1 don't produce infinite returns
+const VOL_PRICE_EPSILON: f64 = 1e-4;
+
+/// Rolling population standard deviation of log-returns over `window` returns
+pub(crate) fn rolling_volatility(candles: &[StoredCandle], window: usize) -> Vec<(i64, f64)> {
+    if window == 0 || candles.len() <= window {
+        return Vec::new();
+    }
+
+    let clamp = |p: f64| p.clamp(VOL_PRICE_EPSILON, 1.0 - VOL_PRICE_EPSILON);
+    let returns: Vec<f64> = candles
+        .windows(2)
+        .map(|pair| (clamp(pair[1].close) / clamp(pair[0].close)).ln())
+        .collect();
+
+    returns
+        .windows(window)
+        .enumerate()
+        .map(|(i, rets)| {
+            let mean = rets.iter().sum::<f64>() / window as f64;
+            let variance = rets.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / window as f64;
+            // returns[i + window - 1] ends at candle i + window
+            (candles[i + window].timestamp, variance.sqrt())
+        })
+        .collect()
+}
+
+/// Combine time-ordered candles into buckets of `target_secs`
+///
+/// First open, max high, min low, last close, summed volume and trade count.
+fn rollup_candles(source: &[StoredCandle], target_secs: i64) -> Vec<StoredCandle> {
+    let mut rolled: Vec<StoredCandle> = Vec::new();
+
+    for candle in source {
+        let bucket = (candle.timestamp / target_secs) * target_secs;
+        match rolled.last_mut() {
+            Some(current) if current.timestamp == bucket => {
+                current.high = current.high.max(candle.high);
+                current.low = current.low.min(candle.low);
+                current.close = candle.close;
+                current.volume += candle.volume;
+                current.trade_count += candle.trade_count;
+            }
+            _ => rolled.push(StoredCandle {
+                timestamp: bucket,
+                ..candle.clone()
+            }),
+        }
+    }
+
+    rolled
+}
+
+/// Insert flat zero-volume candles for every missing bucket through `to`
+pub(crate) fn fill_stored_candle_gaps(candles: Vec<StoredCandle>, interval_secs: i64, to: i64) -> Vec<StoredCandle> {
+    let last_bucket = (to / interval_secs) * interval_secs;
+    let mut filled = Vec::with_capacity(candles.len());
+
+    let flat = |timestamp: i64, price: f64| StoredCandle {
+        timestamp,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        volume: 0.0,
+        trade_count: 0,
+    };
+
+    // (timestamp, close) of the last candle pushed
+    let mut prev: Option<(i64, f64)> = None;
+    for candle in candles {
+        if let Some((prev_ts, prev_close)) = prev {
+            let mut gap_ts = prev_ts + interval_secs;
+            while gap_ts < candle.timestamp {
+                filled.push(flat(gap_ts, prev_close));
+                gap_ts += interval_secs;
+            }
+        }
+        prev = Some((candle.timestamp, candle.close));
+        filled.push(candle);
+    }
+
+    if let Some((prev_ts, prev_close)) = prev {
+        let mut gap_ts = prev_ts + interval_secs;
+        while gap_ts <= last_bucket {
+            filled.push(flat(gap_ts, prev_close));
+            gap_ts += interval_secs;
+        }
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use terminal_core::{TradeOutcome, TradeSide};
+
+    fn create_test_trade(
+        id: &str,
+        market_id: &str,
+        price: Decimal,
+        timestamp: DateTime<Utc>,
+        side: TradeSide,
+    ) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: market_id.to_string(),
+            platform: Platform::Kalshi,
+            timestamp,
+            price,
+            quantity: dec!(100),
+            outcome: TradeOutcome::Yes,
+            side: Some(side),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[test]
+    fn test_build_candles() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base_time = Utc::now() - Duration::hours(1);
+
+        // Create trades with mixed buy/sell sides
+        let trades = vec![
+            create_test_trade("t1", "market1", dec!(0.50), base_time, TradeSide::Buy),
+            create_test_trade("t2", "market1", dec!(0.55), base_time + Duration::minutes(5), TradeSide::Buy),
+            create_test_trade("t3", "market1", dec!(0.45), base_time + Duration::minutes(10), TradeSide::Sell),
+            create_test_trade("t4", "market1", dec!(0.52), base_time + Duration::minutes(15), TradeSide::Buy),
+        ];
+
+        for trade in &trades {
+            storage.store_trade(trade).unwrap();
+        }
 
         let history = service
             .build_candles(
@@ -440,4 +1430,674 @@ mod tests {
 
         assert!(history.candles.is_empty());
     }
+
+    #[test]
+    fn test_rebuild_candles() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base = DateTime::from_timestamp(1_700_000_000 / 3600 * 3600, 0).unwrap();
+        let trades = vec![
+            // First hour: 3 trades
+            create_test_trade("t1", "m", dec!(0.40), base + Duration::minutes(1), TradeSide::Buy),
+            create_test_trade("t2", "m", dec!(0.48), base + Duration::minutes(20), TradeSide::Buy),
+            create_test_trade("t3", "m", dec!(0.44), base + Duration::minutes(50), TradeSide::Sell),
+            // Second hour: 2 trades
+            create_test_trade("t4", "m", dec!(0.50), base + Duration::minutes(65), TradeSide::Buy),
+            create_test_trade("t5", "m", dec!(0.47), base + Duration::minutes(90), TradeSide::Sell),
+        ];
+        storage.store_trades(&trades).unwrap();
+
+        // A stale candle in range that has no trades behind it
+        storage
+            .store_candle(Platform::Kalshi, "m", "1h", base.timestamp() + 7200, 0.9, 0.9, 0.9, 0.9, 1.0, 1)
+            .unwrap();
+
+        let written = service
+            .rebuild_candles(
+                Platform::Kalshi,
+                "m",
+                None,
+                PriceInterval::OneHour,
+                base + Duration::minutes(30),
+                base + Duration::hours(2),
+            )
+            .unwrap();
+        assert_eq!(written, 2);
+
+        let candles = storage
+            .get_candles(Platform::Kalshi, "m", "1h", base, base + Duration::hours(3))
+            .unwrap();
+        assert_eq!(candles.len(), 2);
+
+        let first = &candles[0];
+        assert_eq!(first.timestamp, base.timestamp());
+        assert_eq!((first.open, first.high, first.low, first.close), (0.40, 0.48, 0.40, 0.44));
+        assert_eq!(first.volume, 300.0);
+        assert_eq!(first.trade_count, 3);
+
+        let second = &candles[1];
+        assert_eq!(second.timestamp, base.timestamp() + 3600);
+        assert_eq!((second.open, second.high, second.low, second.close), (0.50, 0.50, 0.47, 0.47));
+        assert_eq!(second.volume, 200.0);
+        assert_eq!(second.trade_count, 2);
+    }
+
+    #[test]
+    fn test_get_candles_filled() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base = 1_700_000_000 / 3600 * 3600;
+        // Stored buckets at hours 1, 2 and 5; hour 0 (leading) and 3-4 (interior) are empty
+        for (hour, close) in [(1, 0.40), (2, 0.45), (5, 0.50)] {
+            storage
+                .store_candle(Platform::Kalshi, "m", "1h", base + hour * 3600, close, close, close, close, 10.0, 2)
+                .unwrap();
+        }
+
+        let from = DateTime::from_timestamp(base, 0).unwrap();
+        // Trailing gap: `to` falls inside hour 7
+        let to = DateTime::from_timestamp(base + 7 * 3600 + 60, 0).unwrap();
+        let candles = service
+            .get_candles_filled(Platform::Kalshi, "m", None, PriceInterval::OneHour, from, to)
+            .unwrap();
+
+        let hours: Vec<i64> = candles.iter().map(|c| (c.timestamp - base) / 3600).collect();
+        assert_eq!(hours, vec![1, 2, 3, 4, 5, 6, 7]);
+
+        // Interior gap carries the previous close with no volume
+        assert_eq!((candles[2].open, candles[2].close), (0.45, 0.45));
+        assert_eq!((candles[3].volume, candles[3].trade_count), (0.0, 0));
+        // Trailing gap carries the last real close
+        assert_eq!(candles[6].close, 0.50);
+        assert_eq!(candles[6].trade_count, 0);
+        // Real candles are untouched
+        assert_eq!(candles[4].trade_count, 2);
+    }
+
+    #[test]
+    fn test_aggregate_minute_candles_into_hours() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        // One day of 1m candles with a deterministic zig-zag price
+        let base = 1_700_000_000 / 86400 * 86400;
+        for minute in 0..1440_i64 {
+            let open = 0.30 + (minute % 60) as f64 * 0.001;
+            let close = open + 0.0005;
+            let high = close + 0.002;
+            let low = open - 0.001;
+            storage
+                .store_candle(Platform::Kalshi, "m", "1m", base + minute * 60, open, high, low, close, 5.0, 1)
+                .unwrap();
+        }
+
+        let from = DateTime::from_timestamp(base, 0).unwrap();
+        let to = DateTime::from_timestamp(base + 86399, 0).unwrap();
+        let written = service
+            .aggregate_candles(Platform::Kalshi, "m", None, PriceInterval::OneMinute, PriceInterval::OneHour, from, to)
+            .unwrap();
+        assert_eq!(written, 24);
+
+        let minutes = storage.get_candles(Platform::Kalshi, "m", "1m", from, to).unwrap();
+        let hours = storage.get_candles(Platform::Kalshi, "m", "1h", from, to).unwrap();
+        assert_eq!(hours.len(), 24);
+
+        for (h, hour) in hours.iter().enumerate() {
+            let slice = &minutes[h * 60..(h + 1) * 60];
+            assert_eq!(hour.timestamp, base + h as i64 * 3600);
+            assert_eq!(hour.open, slice[0].open);
+            assert_eq!(hour.close, slice[59].close);
+            assert_eq!(hour.high, slice.iter().map(|c| c.high).fold(f64::MIN, f64::max));
+            assert_eq!(hour.low, slice.iter().map(|c| c.low).fold(f64::MAX, f64::min));
+            assert_eq!(hour.volume, 300.0);
+            assert_eq!(hour.trade_count, 60);
+        }
+
+        // Rebuild prefers the stored 1m candles and agrees with the rollup
+        service
+            .rebuild_candles(Platform::Kalshi, "m", None, PriceInterval::FourHours, from, to)
+            .unwrap();
+        let four_hours = storage.get_candles(Platform::Kalshi, "m", "4h", from, to).unwrap();
+        assert_eq!(four_hours.len(), 6);
+        assert_eq!(four_hours[0].open, hours[0].open);
+        assert_eq!(four_hours[0].close, hours[3].close);
+        assert_eq!(four_hours[0].trade_count, 240);
+
+        assert!(matches!(
+            service.aggregate_candles(Platform::Kalshi, "m", PriceInterval::OneHour, PriceInterval::FiveMinutes, from, to),
+            Err(CandleServiceError::InvalidInterval)
+        ));
+    }
+
+    fn sourced(ohlc: &[(f64, f64, f64, f64)]) -> Vec<SourcedCandle> {
+        ohlc.iter()
+            .enumerate()
+            .map(|(i, &(open, high, low, close))| SourcedCandle {
+                candle: StoredCandle {
+                    timestamp: i as i64 * 3600,
+                    open,
+                    high,
+                    low,
+                    close,
+                    volume: 1.0,
+                    trade_count: 1,
+                },
+                source: CandleSource::Trade,
+                ma: None,
+                pct_change: None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_candles_multi_aligns_partial_overlap() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = Arc::new(CandleService::new(storage.clone()));
+
+        let base = 1_700_000_000 / 3600 * 3600;
+        // Market "a" has hours 0-2, market "b" has hours 1-3
+        for (market_id, hours) in [("a", 0..3), ("b", 1..4)] {
+            for hour in hours {
+                let price = 0.1 * (hour + 1) as f64;
+                storage
+                    .store_candle(Platform::Kalshi, market_id, "1h", base + hour * 3600, price, price, price, price, 1.0, 1)
+                    .unwrap();
+            }
+        }
+
+        let comparison = service
+            .get_candles_multi(
+                vec![(Platform::Kalshi, "a".to_string()), (Platform::Kalshi, "b".to_string())],
+                PriceInterval::OneHour,
+                DateTime::from_timestamp(base, 0).unwrap(),
+                DateTime::from_timestamp(base + 4 * 3600, 0).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let hours: Vec<i64> = comparison.timestamps.iter().map(|t| (t - base) / 3600).collect();
+        assert_eq!(hours, vec![0, 1, 2, 3]);
+
+        let present = |id: &str| -> Vec<bool> {
+            comparison.series[id].iter().map(|c| c.is_some()).collect()
+        };
+        assert_eq!(present("a"), vec![true, true, true, false]);
+        assert_eq!(present("b"), vec![false, true, true, true]);
+        assert_eq!(comparison.series["b"][3].as_ref().unwrap().timestamp, base + 3 * 3600);
+    }
+
+    #[test]
+    fn test_rolling_volatility() {
+        let series = |closes: &[f64]| -> Vec<StoredCandle> {
+            closes
+                .iter()
+                .enumerate()
+                .map(|(i, &close)| StoredCandle {
+                    timestamp: i as i64 * 3600,
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 0.0,
+                    trade_count: 0,
+                })
+                .collect()
+        };
+
+        // Constant series has zero volatility
+        let flat = rolling_volatility(&series(&[0.5; 6]), 3);
+        assert_eq!(flat.len(), 3);
+        assert!(flat.iter().all(|(_, v)| *v == 0.0));
+        assert_eq!(flat[0].0, 3 * 3600);
+
+        // Alternating 0.4/0.6: returns are +/- ln(1.5), mean 0, so std = ln(1.5)
+        let alternating = rolling_volatility(&series(&[0.4, 0.6, 0.4, 0.6, 0.4]), 2);
+        assert_eq!(alternating.len(), 3);
+        for (_, v) in &alternating {
+            assert!((v - 1.5_f64.ln()).abs() < 1e-9);
+        }
+
+        // Closes at the bounds are clamped rather than producing infinities
+        assert!(rolling_volatility(&series(&[0.0, 1.0, 0.0]), 2)[0].1.is_finite());
+
+        // Window larger than the series
+        assert!(rolling_volatility(&series(&[0.4, 0.6]), 2).is_empty());
+    }
+
+    #[test]
+    fn test_heikin_ashi_transform() {
+        let candles = sourced(&[(1.0, 3.0, 0.0, 2.0), (2.0, 4.0, 1.0, 3.0), (3.0, 5.0, 2.0, 4.0)]);
+        let ha: Vec<_> = apply_candle_transform(candles, CandleTransform::HeikinAshi)
+            .into_iter()
+            .map(|c| (c.candle.open, c.candle.high, c.candle.low, c.candle.close))
+            .collect();
+
+        assert_eq!(
+            ha,
+            vec![(1.5, 3.0, 0.0, 1.5), (1.5, 4.0, 1.0, 2.5), (2.0, 5.0, 2.0, 3.5)]
+        );
+    }
+
+    #[test]
+    fn test_moving_average_transforms() {
+        let candles = sourced(&[(2.0, 2.0, 2.0, 2.0), (3.0, 3.0, 3.0, 3.0), (6.0, 6.0, 6.0, 6.0), (3.0, 3.0, 3.0, 3.0)]);
+
+        let sma: Vec<_> = apply_candle_transform(candles.clone(), CandleTransform::Sma(2))
+            .into_iter()
+            .map(|c| c.ma)
+            .collect();
+        assert_eq!(sma, vec![Some(None), Some(Some(2.5)), Some(Some(4.5)), Some(Some(4.5))]);
+
+        // alpha = 2/3, seeded with SMA(2) = 2.5; then 29/6 and 65/18
+        let ema: Vec<_> = apply_candle_transform(candles.clone(), CandleTransform::Ema(2))
+            .into_iter()
+            .map(|c| c.ma.unwrap())
+            .collect();
+        assert_eq!(ema[0], None);
+        for (got, want) in ema[1..].iter().zip([2.5, 29.0 / 6.0, 65.0 / 18.0]) {
+            assert!((got.unwrap() - want).abs() < 1e-9, "{:?} != {}", got, want);
+        }
+
+        // Candles themselves are untouched by MA transforms
+        let untouched = apply_candle_transform(candles.clone(), CandleTransform::Ema(2));
+        assert_eq!(untouched[2].candle, candles[2].candle);
+    }
+
+    #[test]
+    fn test_candle_anchor_modes() {
+        // 23:00, 00:00, 01:00, 02:00 across the first UTC midnight
+        let mut candles = sourced(&[(1.0, 1.0, 1.0, 1.0), (2.0, 2.0, 2.0, 2.0), (4.0, 4.0, 4.0, 4.0), (1.0, 1.0, 1.0, 1.0)]);
+        for c in candles.iter_mut() {
+            c.candle.timestamp += 82800;
+        }
+        let (from, to) = (82800, 93600);
+        let anchored = |anchor: CandleAnchor| {
+            let mut series = candles.clone();
+            let anchored_at = apply_candle_anchor(&mut series, anchor.resolve(from, to));
+            let pct: Vec<f64> = series.iter().map(|c| c.pct_change.unwrap()).collect();
+            (anchored_at, pct)
+        };
+
+        assert_eq!(
+            anchored(CandleAnchor::MidnightUtc),
+            (Some(86400), vec![-50.0, 0.0, 100.0, -50.0])
+        );
+        assert_eq!(
+            anchored(CandleAnchor::RangeStart),
+            (Some(82800), vec![0.0, 100.0, 300.0, 0.0])
+        );
+        // A timestamp inside a bucket anchors on that bucket's close
+        assert_eq!(
+            anchored(CandleAnchor::Timestamp(90100)),
+            (Some(90000), vec![-75.0, -50.0, 0.0, -75.0])
+        );
+        // Anchors before the history fall back to the earliest candle
+        assert_eq!(anchored(CandleAnchor::Timestamp(0)).0, Some(82800));
+    }
+
+    #[test]
+    fn test_candle_anchor_empty_series() {
+        let mut empty: Vec<SourcedCandle> = Vec::new();
+        assert_eq!(apply_candle_anchor(&mut empty, 0), None);
+
+        assert_eq!(CandleAnchor::from_str("midnight_utc"), Some(CandleAnchor::MidnightUtc));
+        assert_eq!(CandleAnchor::from_str("range_start"), Some(CandleAnchor::RangeStart));
+        assert_eq!(CandleAnchor::from_str("timestamp:1700000000"), Some(CandleAnchor::Timestamp(1_700_000_000)));
+        assert_eq!(CandleAnchor::from_str("timestamp:abc"), None);
+        assert_eq!(CandleAnchor::from_str("open"), None);
+    }
+
+    #[test]
+    fn test_range_summary_drawdown() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+        let base = Utc::now() - Duration::hours(1);
+
+        // Peak 0.70 then trough 0.30: drawdown 0.40, despite a later higher low
+        let path = [dec!(0.50), dec!(0.70), dec!(0.40), dec!(0.60), dec!(0.30), dec!(0.55)];
+        let trades: Vec<Trade> = path
+            .iter()
+            .enumerate()
+            .map(|(i, &price)| {
+                let at = base + Duration::minutes(i as i64);
+                create_test_trade(&format!("t{}", i), "m", price, at, TradeSide::Buy)
+            })
+            .collect();
+        storage.store_trades(&trades).unwrap();
+
+        let summary = service
+            .get_range_summary(Platform::Kalshi, "m", base - Duration::minutes(1), Utc::now())
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.source, RangeSummarySource::Trades);
+        assert_eq!((summary.open, summary.high, summary.low, summary.close), (0.50, 0.70, 0.30, 0.55));
+        assert_eq!((summary.volume, summary.trade_count), (600.0, 6));
+        assert!((summary.max_drawdown - 0.40).abs() < 1e-9);
+
+        // A monotonically rising path has no drawdown
+        assert_eq!(max_drawdown([0.1, 0.2, 0.2, 0.9]), 0.0);
+
+        // Empty range
+        assert!(service
+            .get_range_summary(Platform::Kalshi, "m", base - Duration::days(2), base - Duration::days(1))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_range_summary_from_candles_when_denser() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+        let base = (Utc::now().timestamp() / 60) * 60 - 3600;
+
+        // Trades were pruned; only the 1m candles remain
+        let ohlc = [(0.50, 0.80, 0.50, 0.80), (0.80, 0.80, 0.45, 0.50), (0.50, 0.60, 0.50, 0.60)];
+        for (i, (open, high, low, close)) in ohlc.into_iter().enumerate() {
+            let ts = base + i as i64 * 60;
+            storage
+                .store_candle(Platform::Kalshi, "m", "1m", ts, open, high, low, close, 10.0, 2)
+                .unwrap();
+        }
+
+        let from = DateTime::from_timestamp(base, 0).unwrap();
+        let summary = service
+            .get_range_summary(Platform::Kalshi, "m", from, Utc::now())
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.source, RangeSummarySource::Candles);
+        assert_eq!((summary.open, summary.high, summary.low, summary.close), (0.50, 0.80, 0.45, 0.60));
+        assert_eq!((summary.volume, summary.trade_count), (30.0, 6));
+        // Close path 0.50 -> 0.80 -> 0.50 -> 0.60
+        assert!((summary.max_drawdown - 0.30).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_downsample_never_exceeds_budget() {
+        let day = 86400;
+        for (interval, span) in [
+            (PriceInterval::OneMinute, 90 * day),
+            (PriceInterval::OneMinute, 3600),
+            (PriceInterval::OneHour, 365 * day),
+            (PriceInterval::OneDay, 20 * 365 * day),
+        ] {
+            for max_points in [1, 2, 7, 100, 2000] {
+                let (from, to) = (1_700_000_123, 1_700_000_123 + span);
+                let secs = downsample_bucket_secs(interval, from, to, max_points);
+                let buckets = to.div_euclid(secs) - from.div_euclid(secs) + 1;
+                assert!(buckets <= max_points as i64, "{:?} {} {}: {}", interval, span, max_points, buckets);
+                assert_eq!(secs % interval.to_seconds() as i64, 0);
+                // Deterministic
+                assert_eq!(secs, downsample_bucket_secs(interval, from, to, max_points));
+            }
+        }
+
+        // Smallest standard interval that fits is chosen
+        assert_eq!(downsample_bucket_secs(PriceInterval::OneMinute, 0, day - 1, 2000), 60);
+        assert_eq!(downsample_bucket_secs(PriceInterval::OneMinute, 0, day - 1, 100), 900);
+        assert_eq!(bucket_label(900), "15m");
+        assert_eq!(bucket_label(3 * day), "3d");
+    }
+
+    #[test]
+    fn test_max_points_coarsens_preserving_ohlc() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+        let base = 1_700_006_400; // hour-aligned
+
+        // One day of 1m candles with a rising sawtooth inside each hour
+        let minutes: Vec<StoredCandle> = (0..1440)
+            .map(|i| {
+                let price = 0.30 + (i % 60) as f64 / 200.0;
+                StoredCandle {
+                    timestamp: base + i * 60,
+                    open: price,
+                    high: price + 0.01,
+                    low: price - 0.01,
+                    close: price + 0.005,
+                    volume: 1.0,
+                    trade_count: 1,
+                }
+            })
+            .collect();
+        storage
+            .replace_candles(Platform::Kalshi, "m", None, "1m", base, base + 86399, &minutes)
+            .unwrap();
+
+        let from = DateTime::from_timestamp(base, 0).unwrap();
+        let to = DateTime::from_timestamp(base + 86399, 0).unwrap();
+        let options = CandleReadOptions {
+            max_points: Some(50),
+            ..Default::default()
+        };
+        let series = service
+            .get_candles_with_source(Platform::Kalshi, "m", PriceInterval::OneMinute, from, to, &options)
+            .unwrap();
+
+        assert_eq!(series.effective_interval, "1h");
+        assert_eq!(series.candles.len(), 24);
+        let hour = &series.candles[0].candle;
+        assert_eq!(hour.timestamp, base);
+        assert_eq!(hour.open, minutes[0].open);
+        assert_eq!(hour.close, minutes[59].close);
+        assert_eq!(hour.high, minutes[59].high);
+        assert_eq!(hour.low, minutes[0].low);
+        assert_eq!((hour.volume, hour.trade_count), (60.0, 60));
+
+        // A budget the requested interval already fits leaves it alone
+        let options = CandleReadOptions {
+            max_points: Some(2000),
+            ..Default::default()
+        };
+        let series = service
+            .get_candles_with_source(Platform::Kalshi, "m", PriceInterval::OneMinute, from, to, &options)
+            .unwrap();
+        assert_eq!((series.effective_interval.as_str(), series.candles.len()), ("1m", 1440));
+    }
+
+    #[test]
+    fn test_parse_candle_transform() {
+        assert_eq!(CandleTransform::from_str("none"), Some(CandleTransform::None));
+        assert_eq!(CandleTransform::from_str("heikin_ashi"), Some(CandleTransform::HeikinAshi));
+        assert_eq!(CandleTransform::from_str("sma:20"), Some(CandleTransform::Sma(20)));
+        assert_eq!(CandleTransform::from_str("ema:9"), Some(CandleTransform::Ema(9)));
+        assert_eq!(CandleTransform::from_str("sma:0"), None);
+        assert_eq!(CandleTransform::from_str("wma:3"), None);
+    }
+
+    #[test]
+    fn test_stored_candle_reads_are_cached_until_written() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base = 1_700_000_000 / 3600 * 3600;
+        let from = DateTime::from_timestamp(base, 0).unwrap();
+        let to = DateTime::from_timestamp(base + 7199, 0).unwrap();
+        let candle = |close: f64| StoredCandle {
+            timestamp: base,
+            open: 0.5,
+            high: 0.9,
+            low: 0.1,
+            close,
+            volume: 10.0,
+            trade_count: 1,
+        };
+
+        service
+            .store_candle(Platform::Kalshi, "m", None, PriceInterval::OneHour, &candle(0.5))
+            .unwrap();
+        let read = || {
+            service
+                .get_stored_candles(Platform::Kalshi, "m", None, PriceInterval::OneHour, from, to)
+                .unwrap()
+        };
+        assert_eq!(read()[0].close, 0.5);
+        assert_eq!(read()[0].close, 0.5);
+        assert_eq!((service.cache_stats().hits, service.cache_stats().misses), (1, 1));
+
+        // A write into the cached range is visible on the next read
+        service
+            .store_candle(Platform::Kalshi, "m", None, PriceInterval::OneHour, &candle(0.7))
+            .unwrap();
+        assert_eq!(read()[0].close, 0.7);
+        assert_eq!(service.cache_stats().misses, 2);
+    }
+
+    #[test]
+    fn test_stored_candle_reads_share_whole_buckets() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base = 1_700_000_000 / 60 * 60;
+        storage
+            .store_candle(Platform::Kalshi, "m", "1m", base, 0.50, 0.55, 0.45, 0.52, 100.0, 3)
+            .unwrap();
+        let read = |offset: i64| {
+            let from = DateTime::from_timestamp(base - 3600 + 10 + offset, 0).unwrap();
+            let to = DateTime::from_timestamp(base + 20 + offset, 0).unwrap();
+            service
+                .get_stored_candles(Platform::Kalshi, "m", None, PriceInterval::OneMinute, from, to)
+                .unwrap()
+        };
+
+        // One second later falls in the same buckets and is served from cache
+        assert_eq!(read(0).len(), 1);
+        assert_eq!(read(1).len(), 1);
+        assert_eq!((service.cache_stats().hits, service.cache_stats().misses), (1, 1));
+    }
+
+    #[test]
+    fn test_mid_candles_fill_tradeless_buckets() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base = 1_700_000_000 / 3600 * 3600;
+        // Hour 0 has a trade candle and a snapshot; hour 1 only has snapshots
+        storage
+            .store_candle(Platform::Kalshi, "m", "1h", base, 0.50, 0.55, 0.45, 0.52, 100.0, 3)
+            .unwrap();
+        for (offset, price) in [(600, 0.90), (3700, 0.60), (4000, 0.64), (5000, 0.58)] {
+            storage
+                .store_price_snapshot_at(Platform::Kalshi, "m", base + offset, price, None)
+                .unwrap();
+        }
+
+        let from = DateTime::from_timestamp(base, 0).unwrap();
+        let to = DateTime::from_timestamp(base + 7199, 0).unwrap();
+        let read = |source| {
+            let options = CandleReadOptions {
+                source,
+                ..Default::default()
+            };
+            service
+                .get_candles_with_source(Platform::Kalshi, "m", PriceInterval::OneHour, from, to, &options)
+                .unwrap()
+                .candles
+        };
+
+        let auto = read(CandleSourceMode::Auto);
+        assert_eq!(auto.len(), 2);
+        // Trade candle wins over the snapshot in the same bucket
+        assert_eq!(auto[0].source, CandleSource::Trade);
+        assert_eq!(auto[0].candle.close, 0.52);
+        assert_eq!(auto[1].source, CandleSource::Mid);
+        assert_eq!(
+            (auto[1].candle.open, auto[1].candle.high, auto[1].candle.low, auto[1].candle.close),
+            (0.60, 0.64, 0.58, 0.58)
+        );
+        assert_eq!(auto[1].candle.volume, 0.0);
+
+        let trades = read(CandleSourceMode::Trades);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].source, CandleSource::Trade);
+
+        let mid = read(CandleSourceMode::Mid);
+        assert_eq!(mid.len(), 2);
+        assert!(mid.iter().all(|c| c.source == CandleSource::Mid));
+        assert_eq!(mid[0].candle.close, 0.90);
+    }
+
+    #[test]
+    fn test_rebuild_candles_per_outcome() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base = 1_700_000_000 / 3600 * 3600;
+        let at = |secs: i64| DateTime::from_timestamp(base + secs, 0).unwrap();
+
+        // Interleaved trades on two outcome tokens across two hours
+        let trades = [
+            ("a1", "tok-a", dec!(0.60), 60),
+            ("b1", "tok-b", dec!(0.40), 120),
+            ("a2", "tok-a", dec!(0.65), 600),
+            ("b2", "tok-b", dec!(0.35), 900),
+            ("a3", "tok-a", dec!(0.70), 3700),
+            ("b3", "tok-b", dec!(0.30), 3800),
+        ];
+        for (id, outcome_id, price, offset) in trades {
+            let mut trade = create_test_trade(id, "event1", price, at(offset), TradeSide::Buy);
+            trade.outcome_id = Some(outcome_id.to_string());
+            storage.store_trade(&trade).unwrap();
+        }
+        assert_eq!(
+            storage.get_outcome_ids(Platform::Kalshi, "event1").unwrap(),
+            vec!["tok-a".to_string(), "tok-b".to_string()]
+        );
+
+        for outcome_id in ["tok-a", "tok-b"] {
+            let written = service
+                .rebuild_candles(Platform::Kalshi, "event1", Some(outcome_id), PriceInterval::OneHour, at(0), at(7199))
+                .unwrap();
+            assert_eq!(written, 2);
+        }
+
+        let series = |outcome: Option<&str>| {
+            service
+                .get_candles_filled(Platform::Kalshi, "event1", outcome, PriceInterval::OneHour, at(0), at(7199))
+                .unwrap()
+        };
+
+        let a = series(Some("tok-a"));
+        assert_eq!(a.len(), 2);
+        assert_eq!((a[0].open, a[0].close, a[0].high), (0.60, 0.65, 0.65));
+        assert_eq!(a[1].close, 0.70);
+
+        let b = series(Some("tok-b"));
+        assert_eq!(b.len(), 2);
+        assert_eq!((b[0].open, b[0].close, b[0].low), (0.40, 0.35, 0.35));
+        assert_eq!(b[1].close, 0.30);
+
+        // The whole-market series is untouched
+        assert!(series(None).is_empty());
+    }
+
+    #[test]
+    fn test_outcome_candles_are_separate() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = CandleService::new(storage.clone());
+
+        let base_time = Utc::now() - Duration::minutes(30);
+        for (outcome_id, price) in [("tok-a", dec!(0.60)), ("tok-b", dec!(0.30)), ("tok-c", dec!(0.10))] {
+            let mut trade = create_test_trade(outcome_id, "event1", price, base_time, TradeSide::Buy);
+            trade.outcome_id = Some(outcome_id.to_string());
+            storage.store_trade(&trade).unwrap();
+        }
+
+        for (outcome_id, price) in [("tok-a", dec!(0.60)), ("tok-b", dec!(0.30)), ("tok-c", dec!(0.10))] {
+            let history = service
+                .build_outcome_candles(
+                    Platform::Kalshi,
+                    "event1",
+                    outcome_id,
+                    PriceInterval::OneHour,
+                    base_time - Duration::minutes(1),
+                    Utc::now(),
+                )
+                .unwrap();
+            assert_eq!(history.candles.len(), 1);
+            assert_eq!(history.candles[0].close, price);
+            assert_eq!(history.candles[0].volume, dec!(100));
+        }
+    }
 }
@@ -0,0 +1,379 @@
+//! Live Candle Updater
+//!
+//! Maintains the in-progress candle for every subscribed
+//! (platform, market, interval) from the WebSocket trade broadcast, pushes a
+//! `CandleUpdate` whenever a trade lands in the open bucket, and a final
+//! `closed` update once the bucket ends.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use terminal_core::{
+    Platform, PriceCandle, PriceInterval, ServerMessage, SubscriptionChannel, SubscriptionKey,
+    Trade, TradeSide,
+};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::websocket::WebSocketState;
+
+/// How often expired buckets are checked for finalization
+const FINALIZE_TICK: Duration = Duration::from_secs(1);
+
+type LiveKey = (Platform, String, PriceInterval);
+
+/// Result of applying a trade to a live candle
+#[derive(Debug, Clone)]
+pub(crate) enum CandleEvent {
+    /// Trade landed in the open bucket
+    Updated(PriceCandle),
+    /// Trade opened a new bucket; the previous one is final
+    Rolled {
+        closed: PriceCandle,
+        current: PriceCandle,
+    },
+    /// Trade belongs to a bucket that has already closed
+    Ignored,
+}
+
+/// The open bucket for a single (platform, market, interval)
+#[derive(Debug, Clone)]
+pub(crate) struct LiveCandle {
+    candle: PriceCandle,
+    interval: PriceInterval,
+    first_trade_at: DateTime<Utc>,
+    last_trade_at: DateTime<Utc>,
+}
+
+impl LiveCandle {
+    /// Open a new bucket from its first trade
+    pub(crate) fn new(trade: &Trade, interval: PriceInterval) -> Self {
+        let start = bucket_start(trade.timestamp, interval);
+        let (buy_volume, sell_volume) = side_volumes(trade);
+        Self {
+            candle: PriceCandle {
+                timestamp: start,
+                open: trade.price,
+                high: trade.price,
+                low: trade.price,
+                close: trade.price,
+                volume: trade.quantity,
+                buy_volume,
+                sell_volume,
+            },
+            interval,
+            first_trade_at: trade.timestamp,
+            last_trade_at: trade.timestamp,
+        }
+    }
+
+    /// Apply a trade, rolling over to a new bucket if it starts one
+    ///
+    /// Trades arriving out of order within the open bucket only move open/close
+    /// when they are earlier/later than every trade seen so far. Trades for an
+    /// already-closed bucket are ignored; stored candles pick them up on rebuild.
+    pub(crate) fn apply_trade(&mut self, trade: &Trade) -> CandleEvent {
+        let start = bucket_start(trade.timestamp, self.interval);
+
+        if start < self.candle.timestamp {
+            return CandleEvent::Ignored;
+        }
+
+        if start > self.candle.timestamp {
+            let previous = std::mem::replace(self, Self::new(trade, self.interval));
+            return CandleEvent::Rolled {
+                closed: previous.candle,
+                current: self.candle.clone(),
+            };
+        }
+
+        let candle = &mut self.candle;
+        candle.high = candle.high.max(trade.price);
+        candle.low = candle.low.min(trade.price);
+        candle.volume += trade.quantity;
+        let (buy_volume, sell_volume) = side_volumes(trade);
+        candle.buy_volume += buy_volume;
+        candle.sell_volume += sell_volume;
+
+        if trade.timestamp < self.first_trade_at {
+            candle.open = trade.price;
+            self.first_trade_at = trade.timestamp;
+        }
+        if trade.timestamp >= self.last_trade_at {
+            candle.close = trade.price;
+            self.last_trade_at = trade.timestamp;
+        }
+
+        CandleEvent::Updated(candle.clone())
+    }
+
+    /// Whether the bucket has ended as of `now`
+    pub(crate) fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now.timestamp() >= self.candle.timestamp.timestamp() + self.interval.to_seconds() as i64
+    }
+}
+
+/// Streams live candles for subscribed markets over the WebSocket
+pub struct CandleUpdater {
+    ws_state: Arc<WebSocketState>,
+    live: Mutex<HashMap<LiveKey, LiveCandle>>,
+}
+
+impl CandleUpdater {
+    /// Create a new CandleUpdater
+    pub fn new(ws_state: Arc<WebSocketState>) -> Self {
+        Self {
+            ws_state,
+            live: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run the updater, consuming trade broadcasts until the channel closes
+    pub async fn start(self: Arc<Self>) {
+        info!("Starting live candle updater");
+        let mut rx = self.ws_state.subscriptions.subscribe_broadcast();
+        let mut ticker = tokio::time::interval(FINALIZE_TICK);
+
+        loop {
+            tokio::select! {
+                msg = rx.recv() => match msg {
+                    Ok(msg) => {
+                        if let ServerMessage::TradeUpdate { platform, market_id, trade, .. } = msg.message {
+                            self.handle_trade(platform, &market_id, &trade, Utc::now());
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Live candle updater lagged, skipped {} messages", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = ticker.tick() => self.finalize_expired(Utc::now()),
+            }
+        }
+    }
+
+    /// Apply a trade to every subscribed interval for its market
+    pub fn handle_trade(&self, platform: Platform, market_id: &str, trade: &Trade, now: DateTime<Utc>) {
+        let intervals: Vec<PriceInterval> = self
+            .ws_state
+            .subscriptions
+            .get_market_subscriptions(platform, market_id)
+            .into_iter()
+            .filter_map(|key| match key.channel {
+                SubscriptionChannel::Candles(interval) => Some(interval),
+                _ => None,
+            })
+            .collect();
+
+        let mut updates = Vec::new();
+        {
+            let mut live = self.live.lock();
+            for interval in intervals {
+                let key = (platform, market_id.to_string(), interval);
+                match live.get_mut(&key) {
+                    Some(candle) => match candle.apply_trade(trade) {
+                        CandleEvent::Updated(current) => updates.push((interval, current, false)),
+                        CandleEvent::Rolled { closed, current } => {
+                            updates.push((interval, closed, true));
+                            updates.push((interval, current, false));
+                        }
+                        CandleEvent::Ignored => {}
+                    },
+                    None => {
+                        let candle = LiveCandle::new(trade, interval);
+                        // A late trade for a bucket that already ended would
+                        // otherwise reopen it
+                        if candle.is_expired(now) {
+                            continue;
+                        }
+                        updates.push((interval, candle.candle.clone(), false));
+                        live.insert(key, candle);
+                    }
+                }
+            }
+        }
+
+        for (interval, candle, closed) in updates {
+            self.ws_state
+                .broadcast_candle_update(platform, market_id.to_string(), interval, candle, closed);
+        }
+    }
+
+    /// Send final updates for buckets that have ended and drop unsubscribed entries
+    pub fn finalize_expired(&self, now: DateTime<Utc>) {
+        let mut finalized = Vec::new();
+        {
+            let mut live = self.live.lock();
+            live.retain(|(platform, market_id, interval), candle| {
+                let key = SubscriptionKey {
+                    platform: *platform,
+                    market_id: market_id.clone(),
+                    channel: SubscriptionChannel::Candles(*interval),
+                };
+                if !self.ws_state.subscriptions.has_any_subscribers(&key) {
+                    return false;
+                }
+                if candle.is_expired(now) {
+                    finalized.push((*platform, market_id.clone(), *interval, candle.candle.clone()));
+                    return false;
+                }
+                true
+            });
+        }
+
+        for (platform, market_id, interval, candle) in finalized {
+            self.ws_state
+                .broadcast_candle_update(platform, market_id, interval, candle, true);
+        }
+    }
+
+    /// Number of open buckets being tracked
+    pub fn live_count(&self) -> usize {
+        self.live.lock().len()
+    }
+}
+
+fn bucket_start(timestamp: DateTime<Utc>, interval: PriceInterval) -> DateTime<Utc> {
+    let interval_secs = interval.to_seconds() as i64;
+    let start = (timestamp.timestamp() / interval_secs) * interval_secs;
+    DateTime::from_timestamp(start, 0).unwrap_or(timestamp)
+}
+
+fn side_volumes(trade: &Trade) -> (Decimal, Decimal) {
+    match trade.side {
+        Some(TradeSide::Buy) => (trade.quantity, Decimal::ZERO),
+        Some(TradeSide::Sell) => (Decimal::ZERO, trade.quantity),
+        None => (Decimal::ZERO, Decimal::ZERO),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MarketService;
+    use rust_decimal_macros::dec;
+    use terminal_core::{SubscriptionType, TradeOutcome};
+    use terminal_kalshi::KalshiClient;
+    use terminal_polymarket::PolymarketClient;
+
+    fn trade_at(secs: i64, price: Decimal, side: TradeSide) -> Trade {
+        Trade {
+            id: format!("t-{}-{}", secs, price),
+            market_id: "TEST-MARKET".to_string(),
+            platform: Platform::Kalshi,
+            timestamp: DateTime::from_timestamp(secs, 0).unwrap(),
+            price,
+            quantity: dec!(10),
+            outcome: TradeOutcome::Yes,
+            side: Some(side),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[test]
+    fn test_bucket_rollover() {
+        let mut live = LiveCandle::new(&trade_at(60, dec!(0.50), TradeSide::Buy), PriceInterval::OneMinute);
+        assert!(matches!(
+            live.apply_trade(&trade_at(90, dec!(0.55), TradeSide::Sell)),
+            CandleEvent::Updated(_)
+        ));
+
+        match live.apply_trade(&trade_at(125, dec!(0.60), TradeSide::Buy)) {
+            CandleEvent::Rolled { closed, current } => {
+                assert_eq!(closed.timestamp.timestamp(), 60);
+                assert_eq!(closed.open, dec!(0.50));
+                assert_eq!(closed.close, dec!(0.55));
+                assert_eq!(closed.volume, dec!(20));
+                assert_eq!(closed.buy_volume, dec!(10));
+                assert_eq!(closed.sell_volume, dec!(10));
+
+                assert_eq!(current.timestamp.timestamp(), 120);
+                assert_eq!(current.open, dec!(0.60));
+                assert_eq!(current.volume, dec!(10));
+            }
+            other => panic!("expected rollover, got {:?}", other),
+        }
+
+        assert!(!live.is_expired(DateTime::from_timestamp(179, 0).unwrap()));
+        assert!(live.is_expired(DateTime::from_timestamp(180, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_out_of_order_trades() {
+        let mut live = LiveCandle::new(&trade_at(130, dec!(0.50), TradeSide::Buy), PriceInterval::OneMinute);
+
+        // Older trade in the same bucket moves open and extremes, not close
+        match live.apply_trade(&trade_at(125, dec!(0.40), TradeSide::Buy)) {
+            CandleEvent::Updated(candle) => {
+                assert_eq!(candle.open, dec!(0.40));
+                assert_eq!(candle.low, dec!(0.40));
+                assert_eq!(candle.close, dec!(0.50));
+                assert_eq!(candle.volume, dec!(20));
+            }
+            other => panic!("expected update, got {:?}", other),
+        }
+
+        // Trade for an earlier, already-closed bucket is ignored
+        assert!(matches!(
+            live.apply_trade(&trade_at(100, dec!(0.90), TradeSide::Buy)),
+            CandleEvent::Ignored
+        ));
+        assert_eq!(live.candle.high, dec!(0.50));
+        assert_eq!(live.candle.volume, dec!(20));
+    }
+
+    #[tokio::test]
+    async fn test_updater_broadcasts_and_finalizes() {
+        let ws_state = Arc::new(WebSocketState::new(MarketService::new(
+            KalshiClient::new(true),
+            PolymarketClient::new(),
+        )));
+        let client = ws_state.subscriptions.new_client_id();
+        ws_state.subscriptions.subscribe(
+            client,
+            &SubscriptionType::Candles {
+                platform: Platform::Kalshi,
+                market_id: "TEST-MARKET".to_string(),
+                interval: PriceInterval::OneMinute,
+            },
+        );
+        let mut rx = ws_state.subscriptions.subscribe_broadcast();
+        let updater = CandleUpdater::new(ws_state.clone());
+
+        let now = DateTime::from_timestamp(70, 0).unwrap();
+        updater.handle_trade(Platform::Kalshi, "TEST-MARKET", &trade_at(65, dec!(0.50), TradeSide::Buy), now);
+        assert_eq!(updater.live_count(), 1);
+
+        let msg = rx.recv().await.unwrap();
+        assert!(matches!(msg.message, ServerMessage::CandleUpdate { closed: false, .. }));
+
+        updater.finalize_expired(DateTime::from_timestamp(120, 0).unwrap());
+        assert_eq!(updater.live_count(), 0);
+
+        let msg = rx.recv().await.unwrap();
+        match msg.message {
+            ServerMessage::CandleUpdate { closed, candle, interval, .. } => {
+                assert!(closed);
+                assert_eq!(interval, PriceInterval::OneMinute);
+                assert_eq!(candle.close, dec!(0.50));
+            }
+            other => panic!("expected candle update, got {:?}", other),
+        }
+
+        // A late trade for the finalized bucket does not reopen it
+        updater.handle_trade(
+            Platform::Kalshi,
+            "TEST-MARKET",
+            &trade_at(110, dec!(0.70), TradeSide::Buy),
+            DateTime::from_timestamp(121, 0).unwrap(),
+        );
+        assert_eq!(updater.live_count(), 0);
+    }
+}
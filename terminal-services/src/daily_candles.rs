@@ -0,0 +1,293 @@
+//! Daily Candle Refresher
+//!
+//! Background job that keeps stored 1d candles current for every cached market,
+//! so all-time charts are served from TradeStorage instead of the platform APIs.
+//!
+//! Once per refresh interval it walks the MarketCache, skips markets whose
+//! latest stored 1d candle already covers today, and for the rest pulls the
+//! platform's native price history and upserts it as daily candles. Platform
+//! calls are spaced by a configurable delay.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use terminal_core::{Platform, PriceInterval, TerminalError};
+use terminal_polymarket::PriceHistoryPoint;
+use tracing::{debug, info, warn};
+
+use crate::candle_service::CandleService;
+use crate::market_cache::{MarketCache, PageRequest};
+use crate::market_service::MarketService;
+
+/// Source of a market's full daily price history
+#[async_trait]
+pub trait DailyPriceSource: Send + Sync {
+    /// Fetch the market's price history at daily (or finer) resolution
+    async fn daily_price_history(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Vec<PriceHistoryPoint>, TerminalError>;
+}
+
+#[async_trait]
+impl DailyPriceSource for MarketService {
+    async fn daily_price_history(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Vec<PriceHistoryPoint>, TerminalError> {
+        self.get_native_price_history(platform, market_id, "max", Some(1440))
+            .await
+    }
+}
+
+/// Configuration for the daily candle refresher
+#[derive(Debug, Clone)]
+pub struct DailyCandleConfig {
+    /// How often to walk the market cache
+    pub refresh_interval: Duration,
+    /// Pause between platform price history calls
+    pub request_delay: Duration,
+}
+
+impl Default for DailyCandleConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(3600),
+            request_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Check if a market's stored 1d candles already cover the day containing `now`
+fn is_fresh(latest_daily: Option<i64>, now: DateTime<Utc>) -> bool {
+    let day_secs = PriceInterval::OneDay.to_seconds() as i64;
+    let today = (now.timestamp() / day_secs) * day_secs;
+    latest_daily.is_some_and(|ts| ts >= today)
+}
+
+/// Background job that precomputes 1d candles from platform price history
+pub struct DailyCandleRefresher<S: DailyPriceSource = MarketService> {
+    candle_service: Arc<CandleService>,
+    source: Arc<S>,
+    config: DailyCandleConfig,
+    /// Markets whose daily candles were refreshed since startup
+    markets_refreshed: AtomicU64,
+}
+
+impl<S: DailyPriceSource> DailyCandleRefresher<S> {
+    /// Create a new DailyCandleRefresher
+    pub fn new(candle_service: Arc<CandleService>, source: Arc<S>, config: DailyCandleConfig) -> Self {
+        Self {
+            candle_service,
+            source,
+            config,
+            markets_refreshed: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of markets refreshed since startup
+    pub fn markets_refreshed(&self) -> u64 {
+        self.markets_refreshed.load(Ordering::Relaxed)
+    }
+
+    /// Start the periodic refresh loop over the cached markets
+    pub async fn start(self: Arc<Self>, market_cache: Arc<MarketCache>) {
+        info!(
+            "[DailyCandles] Refreshing 1d candles every {}s",
+            self.config.refresh_interval.as_secs()
+        );
+
+        let mut ticker = tokio::time::interval(self.config.refresh_interval);
+        loop {
+            ticker.tick().await;
+            let markets: Vec<(Platform, String)> = market_cache
+                .get_markets(None, PageRequest::all())
+                .markets
+                .into_iter()
+                .map(|m| (m.platform, m.id))
+                .collect();
+            let refreshed = self.refresh_markets(&markets, Utc::now()).await;
+            info!(
+                "[DailyCandles] Refreshed {} of {} markets",
+                refreshed,
+                markets.len()
+            );
+        }
+    }
+
+    /// Refresh every stale market in `markets`, returning how many were refreshed
+    pub async fn refresh_markets(&self, markets: &[(Platform, String)], now: DateTime<Utc>) -> usize {
+        let mut refreshed = 0;
+        let mut fetched_any = false;
+
+        for (platform, market_id) in markets {
+            match self.candle_service.latest_candle_timestamp(*platform, market_id, PriceInterval::OneDay) {
+                Ok(latest) if is_fresh(latest, now) => {
+                    debug!("[DailyCandles] {} is up to date", market_id);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!("[DailyCandles] Failed to read latest candle for {}: {}", market_id, e);
+                    continue;
+                }
+            }
+
+            // Rate limit: space out platform calls, but don't sleep for skipped markets
+            if fetched_any {
+                tokio::time::sleep(self.config.request_delay).await;
+            }
+            fetched_any = true;
+
+            if self.refresh_market(*platform, market_id).await {
+                refreshed += 1;
+            }
+        }
+
+        refreshed
+    }
+
+    /// Fetch one market's price history and upsert its 1d candles
+    async fn refresh_market(&self, platform: Platform, market_id: &str) -> bool {
+        let prices = match self.source.daily_price_history(platform, market_id).await {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!("[DailyCandles] Failed to fetch price history for {}: {}", market_id, e);
+                return false;
+            }
+        };
+
+        match self
+            .candle_service
+            .store_native_candles(platform, market_id, PriceInterval::OneDay, &prices)
+        {
+            Ok(0) => false,
+            Ok(written) => {
+                debug!("[DailyCandles] Stored {} daily candles for {}", written, market_id);
+                self.markets_refreshed.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(e) => {
+                warn!("[DailyCandles] Failed to store daily candles for {}: {}", market_id, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trade_storage::{StoredCandle, TradeStorage};
+    use chrono::TimeZone;
+    use parking_lot::Mutex;
+
+    /// Price source that records which markets were fetched
+    #[derive(Default)]
+    struct MockPriceSource {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl DailyPriceSource for MockPriceSource {
+        async fn daily_price_history(
+            &self,
+            _platform: Platform,
+            market_id: &str,
+        ) -> Result<Vec<PriceHistoryPoint>, TerminalError> {
+            self.calls.lock().push(market_id.to_string());
+            let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap().timestamp();
+            Ok(vec![
+                PriceHistoryPoint { t: now - 2 * 86400, p: 0.40 },
+                PriceHistoryPoint { t: now - 86400, p: 0.45 },
+                PriceHistoryPoint { t: now, p: 0.50 },
+            ])
+        }
+    }
+
+    fn daily_candle(timestamp: i64, close: f64) -> StoredCandle {
+        StoredCandle {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 10.0,
+            trade_count: 2,
+        }
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let today = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap().timestamp();
+
+        assert!(is_fresh(Some(today), now));
+        assert!(!is_fresh(Some(today - 86400), now));
+        assert!(!is_fresh(None, now));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_skips_fresh_markets() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let candle_service = Arc::new(CandleService::new(storage.clone()));
+        let source = Arc::new(MockPriceSource::default());
+        let refresher = DailyCandleRefresher::new(
+            candle_service.clone(),
+            source.clone(),
+            DailyCandleConfig {
+                request_delay: Duration::ZERO,
+                ..Default::default()
+            },
+        );
+
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let today = Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap().timestamp();
+
+        // "fresh" already has today's candle, "stale" stops at yesterday
+        candle_service
+            .store_candle(Platform::Polymarket, "fresh", None, PriceInterval::OneDay, &daily_candle(today, 0.3))
+            .unwrap();
+        candle_service
+            .store_candle(
+                Platform::Polymarket,
+                "stale",
+                None,
+                PriceInterval::OneDay,
+                &daily_candle(today - 86400, 0.3),
+            )
+            .unwrap();
+
+        let markets = vec![
+            (Platform::Polymarket, "fresh".to_string()),
+            (Platform::Polymarket, "stale".to_string()),
+            (Platform::Polymarket, "new".to_string()),
+        ];
+        assert_eq!(refresher.refresh_markets(&markets, now).await, 2);
+        assert_eq!(*source.calls.lock(), vec!["stale".to_string(), "new".to_string()]);
+        assert_eq!(refresher.markets_refreshed(), 2);
+
+        // Upserted prices keep the stored bucket's volume
+        let stale = storage
+            .get_candles(
+                Platform::Polymarket,
+                "stale",
+                "1d",
+                Utc.timestamp_opt(today - 86400, 0).unwrap(),
+                now,
+            )
+            .unwrap();
+        assert_eq!(stale.len(), 2);
+        assert_eq!((stale[0].close, stale[0].volume), (0.45, 10.0));
+        assert_eq!(stale[1].close, 0.50);
+
+        // A second pass finds everything up to date
+        assert_eq!(refresher.refresh_markets(&markets, now).await, 0);
+        assert_eq!(source.calls.lock().len(), 2);
+    }
+}
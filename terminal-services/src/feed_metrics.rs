@@ -0,0 +1,327 @@
+//! Lightweight throughput and latency metrics for the exchange feeds
+//!
+//! Everything here is lock-free atomics on the hot path: a ring of per-second
+//! message counters for a rolling rate, fixed-bucket latency histograms, and
+//! per-type message counters. Concurrent writers can race on a bucket
+//! rollover and lose a count, which is fine for an operational gauge.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use terminal_kalshi::KalshiUpdate;
+use terminal_polymarket::PolymarketUpdate;
+
+/// Seconds covered by the rolling message rate
+const THROUGHPUT_WINDOW_SECS: usize = 60;
+
+/// Slots in the ring: the full window plus the second still filling
+const THROUGHPUT_SLOTS: usize = THROUGHPUT_WINDOW_SECS + 1;
+
+/// Upper bounds of the latency buckets in microseconds; anything slower
+/// lands in a final overflow bucket
+const LATENCY_BUCKETS_US: [u64; 12] = [
+    50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 1_000_000,
+];
+
+/// Messages per second over the last `THROUGHPUT_WINDOW_SECS` full seconds
+#[derive(Debug)]
+pub struct ThroughputWindow {
+    /// Epoch second each slot is counting
+    seconds: [AtomicU64; THROUGHPUT_SLOTS],
+    counts: [AtomicU64; THROUGHPUT_SLOTS],
+}
+
+impl Default for ThroughputWindow {
+    fn default() -> Self {
+        Self {
+            seconds: std::array::from_fn(|_| AtomicU64::new(0)),
+            counts: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+impl ThroughputWindow {
+    /// Count a message received during epoch second `now_secs`
+    pub fn record(&self, now_secs: u64) {
+        let slot = (now_secs % THROUGHPUT_SLOTS as u64) as usize;
+        let second = self.seconds[slot].load(Ordering::Relaxed);
+        if second != now_secs
+            && self.seconds[slot]
+                .compare_exchange(second, now_secs, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            // First message of a new second reuses the slot
+            self.counts[slot].store(0, Ordering::Relaxed);
+        }
+        self.counts[slot].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Average rate over the full seconds before `now_secs`
+    ///
+    /// The current second is still filling, so it is left out.
+    pub fn rate(&self, now_secs: u64) -> f64 {
+        let oldest = now_secs.saturating_sub(THROUGHPUT_WINDOW_SECS as u64);
+        let total: u64 = self
+            .seconds
+            .iter()
+            .zip(&self.counts)
+            .filter(|(second, _)| {
+                let second = second.load(Ordering::Relaxed);
+                second >= oldest && second < now_secs
+            })
+            .map(|(_, count)| count.load(Ordering::Relaxed))
+            .sum();
+        total as f64 / THROUGHPUT_WINDOW_SECS as f64
+    }
+}
+
+/// Fixed-bucket latency histogram
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS_US.len() + 1],
+    total_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            total_us: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Latency distribution; percentiles are bucket upper bounds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub samples: u64,
+    pub mean_us: u64,
+    pub p50_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&self, latency: Duration) {
+        let us = latency.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = LATENCY_BUCKETS_US
+            .iter()
+            .position(|bound| us <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_US.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(us, Ordering::Relaxed);
+        self.max_us.fetch_max(us, Ordering::Relaxed);
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        let counts: Vec<u64> = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).collect();
+        let samples: u64 = counts.iter().sum();
+        if samples == 0 {
+            return LatencySummary::default();
+        }
+        let max_us = self.max_us.load(Ordering::Relaxed);
+        let percentile = |p: f64| {
+            let rank = ((samples as f64) * p).ceil().max(1.0) as u64;
+            let mut seen = 0;
+            for (i, count) in counts.iter().enumerate() {
+                seen += count;
+                if seen >= rank {
+                    // The overflow bucket has no bound; the max stands in
+                    return LATENCY_BUCKETS_US.get(i).map_or(max_us, |bound| (*bound).min(max_us));
+                }
+            }
+            max_us
+        };
+        LatencySummary {
+            samples,
+            mean_us: self.total_us.load(Ordering::Relaxed) / samples,
+            p50_us: percentile(0.50),
+            p99_us: percentile(0.99),
+            max_us,
+        }
+    }
+}
+
+/// Kinds of exchange feed messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    BookSnapshot,
+    BookDelta,
+    Price,
+    Trade,
+    Heartbeat,
+    Connection,
+}
+
+impl MessageKind {
+    /// Whether the message is fanned out to frontend clients
+    pub fn is_broadcast(self) -> bool {
+        !matches!(self, Self::Heartbeat | Self::Connection)
+    }
+}
+
+impl From<&KalshiUpdate> for MessageKind {
+    fn from(update: &KalshiUpdate) -> Self {
+        match update {
+            KalshiUpdate::OrderbookSnapshot { .. } => Self::BookSnapshot,
+            KalshiUpdate::OrderbookDelta { .. } => Self::BookDelta,
+            KalshiUpdate::PriceUpdate { .. } => Self::Price,
+            KalshiUpdate::Trade { .. } => Self::Trade,
+            KalshiUpdate::Heartbeat | KalshiUpdate::Pong { .. } => Self::Heartbeat,
+            KalshiUpdate::ConnectionState { .. } => Self::Connection,
+        }
+    }
+}
+
+impl From<&PolymarketUpdate> for MessageKind {
+    fn from(update: &PolymarketUpdate) -> Self {
+        match update {
+            PolymarketUpdate::OrderbookSnapshot { .. } => Self::BookSnapshot,
+            // Price changes are book level updates; prices are derived from them
+            PolymarketUpdate::PriceChange { .. } => Self::BookDelta,
+            PolymarketUpdate::Trade { .. } => Self::Trade,
+            PolymarketUpdate::Heartbeat | PolymarketUpdate::Pong { .. } => Self::Heartbeat,
+            PolymarketUpdate::ConnectionState { .. } => Self::Connection,
+        }
+    }
+}
+
+/// Messages received per kind
+#[derive(Debug, Default)]
+pub struct MessageCounters {
+    book_snapshots: AtomicU64,
+    book_deltas: AtomicU64,
+    prices: AtomicU64,
+    trades: AtomicU64,
+    heartbeats: AtomicU64,
+    connection_events: AtomicU64,
+}
+
+/// Counts per message kind, as reported in the health payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MessageTypeCounts {
+    pub book_snapshots: u64,
+    pub book_deltas: u64,
+    pub prices: u64,
+    pub trades: u64,
+    pub heartbeats: u64,
+    pub connection_events: u64,
+}
+
+impl MessageCounters {
+    pub fn record(&self, kind: MessageKind) {
+        let counter = match kind {
+            MessageKind::BookSnapshot => &self.book_snapshots,
+            MessageKind::BookDelta => &self.book_deltas,
+            MessageKind::Price => &self.prices,
+            MessageKind::Trade => &self.trades,
+            MessageKind::Heartbeat => &self.heartbeats,
+            MessageKind::Connection => &self.connection_events,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn counts(&self) -> MessageTypeCounts {
+        MessageTypeCounts {
+            book_snapshots: self.book_snapshots.load(Ordering::Relaxed),
+            book_deltas: self.book_deltas.load(Ordering::Relaxed),
+            prices: self.prices.load(Ordering::Relaxed),
+            trades: self.trades.load(Ordering::Relaxed),
+            heartbeats: self.heartbeats.load(Ordering::Relaxed),
+            connection_events: self.connection_events.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throughput_rate_over_rolling_window() {
+        let window = ThroughputWindow::default();
+        let start = 1_700_000_000;
+
+        // 10 msg/s for 30s, then 40 msg/s for 30s
+        for second in 0..60 {
+            let per_second = if second < 30 { 10 } else { 40 };
+            for _ in 0..per_second {
+                window.record(start + second);
+            }
+        }
+        assert_eq!(window.rate(start + 60), 25.0);
+
+        // 30s later the quiet half has rolled out of the window
+        assert_eq!(window.rate(start + 90), 20.0);
+        assert_eq!(window.rate(start + 200), 0.0);
+
+        // The partial current second isn't counted yet
+        window.record(start + 60);
+        assert_eq!(window.rate(start + 60), 25.0);
+
+        // A reused slot starts from zero
+        window.record(start + 120);
+        assert_eq!(window.rate(start + 121), 1.0 / 60.0);
+    }
+
+    #[test]
+    fn test_latency_histogram_percentiles() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.summary(), LatencySummary::default());
+
+        // 98 fast fan-outs, one slow, one stalled past every bucket
+        for _ in 0..98 {
+            histogram.record(Duration::from_micros(80));
+        }
+        histogram.record(Duration::from_millis(40));
+        histogram.record(Duration::from_secs(3));
+
+        let summary = histogram.summary();
+        assert_eq!(summary.samples, 100);
+        assert_eq!(summary.p50_us, 100);
+        assert_eq!(summary.p99_us, 50_000);
+        assert_eq!(summary.max_us, 3_000_000);
+        assert_eq!(summary.mean_us, (98 * 80 + 40_000 + 3_000_000) / 100);
+    }
+
+    #[test]
+    fn test_message_kinds_counted() {
+        let counters = MessageCounters::default();
+        let updates = [
+            PolymarketUpdate::Heartbeat,
+            PolymarketUpdate::PriceChange {
+                asset_id: "t".to_string(),
+                changes: Vec::new(),
+                best_bid: None,
+                best_ask: None,
+                timestamp: None,
+            },
+            PolymarketUpdate::PriceChange {
+                asset_id: "t".to_string(),
+                changes: Vec::new(),
+                best_bid: None,
+                best_ask: None,
+                timestamp: None,
+            },
+            PolymarketUpdate::ConnectionState {
+                connected: true,
+                error: None,
+            },
+        ];
+        for update in &updates {
+            counters.record(MessageKind::from(update));
+        }
+        counters.record(MessageKind::from(&KalshiUpdate::Heartbeat));
+
+        let counts = counters.counts();
+        assert_eq!(counts.heartbeats, 2);
+        assert_eq!(counts.book_deltas, 2);
+        assert_eq!(counts.connection_events, 1);
+        assert_eq!(counts.trades, 0);
+        assert!(!MessageKind::Heartbeat.is_broadcast());
+        assert!(MessageKind::BookDelta.is_broadcast());
+    }
+}
@@ -4,9 +4,18 @@
 //! from multiple platform clients and provides unified market views.
 
 pub mod aggregator;
+pub mod anomaly;
+pub mod canary;
+pub mod candle_cache;
+pub mod candle_finalizer;
 pub mod candle_service;
+pub mod candle_updater;
+pub mod daily_candles;
 pub mod discord_aggregator;
+pub mod feed_metrics;
 pub mod market_cache;
+pub mod market_matching;
+pub mod market_snapshot;
 pub mod market_service;
 pub mod market_stats;
 pub mod news_aggregator;
@@ -14,27 +23,62 @@ pub mod news_analyzer;
 pub mod news_cache;
 pub mod news_service;
 pub mod rate_limiter;
+pub mod replay_buffer;
 pub mod research_service;
+pub mod stats_cache;
+pub mod ticker_tape;
 pub mod trade_collector;
 pub mod trade_storage;
+pub mod ttl_cache;
+pub mod update_coalescer;
 pub mod websocket;
 
-pub use aggregator::{AggregatorConfig, AggregatorHealth, ConnectionHealth, MarketDataAggregator};
-pub use candle_service::CandleService;
+pub use aggregator::{AggregatorConfig, AggregatorHealth, BookSource, ConnectionHealth, CurrentOrderBook, EventOutcome, MarketDataAggregator, MidTick, MidTicker, OutcomeResolver};
+pub use anomaly::{AnomalyConfig, AnomalyDetector, MarketAlert};
+pub use canary::{
+    is_canary_market, CanaryConfig, CanaryReport, CanaryService, CanaryStage, CanaryStageResult,
+    CANARY_MARKET_ID,
+};
+pub use candle_cache::{CandleCacheConfig, CandleCacheStats};
+pub use candle_finalizer::{CandleFinalizer, FINALIZED_INTERVALS};
+pub use candle_service::{
+    CandleAnchor, CandleComparison, CandleReadOptions, CandleSeries, CandleService, CandleSource,
+    CandleSourceMode, CandleTransform, RangeSummary, RangeSummarySource, SourcedCandle,
+};
+pub use candle_updater::CandleUpdater;
+pub use daily_candles::{DailyCandleConfig, DailyCandleRefresher, DailyPriceSource};
 pub use discord_aggregator::{DiscordAggregator, DiscordAggregatorError};
-pub use market_cache::{CacheStats, MarketCache, MarketCacheError, RefreshRequest};
+pub use feed_metrics::{LatencySummary, MessageTypeCounts};
+pub use market_cache::{
+    group_markets_by_event, market_categories, normalize_category, CacheStats, MarketCache, MarketCacheError, MarketCacheEvent,
+    MarketCursor, MarketGroup, MarketOverride, MarketPage, MarketSearchResult, MarketSource, PageRequest, PlatformRefreshStatus,
+    RefreshLoopConfig, RefreshRequest, SortKey, UnifiedMatch, WatchlistEntry, DEFAULT_MARKET_PAGE_SIZE,
+};
+pub use market_matching::{MarketMatch, MIN_MATCH_CONFIDENCE};
 pub use market_service::{MarketService, OutcomePriceHistory};
+pub use market_snapshot::{MarketSnapshot, MarketSnapshotter, SnapshotSource, DEFAULT_SNAPSHOT_TRADES};
 pub use terminal_polymarket::MarketFilter;
-pub use market_stats::{MarketStats, MarketStatsService, Timeframe};
+pub use terminal_embedding::EmbeddingStore;
+pub use market_stats::{BookLiquidity, Coverage, DataQuality, Extremes, FlowImbalance, LeaderboardMetric, MarketImpact, MarketStats, MarketStatsService, Momentum, OutcomeStats, StatsHistoryEntry, Timeframe, TradeSizeBucket, UnifiedStats, VolumePercentile, DEFAULT_TRADE_SIZE_BUCKETS};
 pub use news_aggregator::{NewsAggregator, NewsAggregatorConfig};
 pub use news_analyzer::{NewsAnalyzer, NewsAnalyzerConfig};
 pub use news_cache::{NewsCache, NewsCacheError};
 pub use news_service::{NewsService, NewsServiceError};
 pub use rate_limiter::{RateLimiter, RateLimiterStats};
+pub use replay_buffer::{ReplayBuffer, DEFAULT_REPLAY_TRADES};
 pub use research_service::ResearchService;
+pub use stats_cache::{StatsCacheConfig, StatsCacheStats};
+pub use ticker_tape::{TickerTape, TickerTapeConfig, TickerTapeStats};
 pub use trade_collector::{TradeCollector, TradeCollectorConfig};
 pub use trade_storage::{
-    MarketTradeStats, OrderbookSnapshot, PriceSnapshot, StoredCandle, StoredPrice, TradeStorage,
-    TxnCounts,
+    DataGap, GapReason, MarketResolution, MarketRowCount, MarketTradeStats, OrderbookMetrics, OrderbookSnapshot,
+    OutcomePrice, PlatformStorageStats, PriceExtremes, PriceSnapshot, SnapshotEncoding, SpreadPoint, StorageStats,
+    StoredCandle, StoredPrice, TradeBucket, TradeExportFormat, TradeFlow, TradeStorage, TradeStorageConfig,
+    TradeStorageError, TxnCounts,
+};
+pub use update_coalescer::{CoalesceConfig, UpdateCoalescer};
+pub use websocket::{
+    AuthError, ClientHeartbeat, ClientStats, CompressionStats, OutboundBatching, SessionConfig, SessionStats, SlowConsumer,
+    SubscriptionEvent, SubscriptionLimitError, SubscriptionLimits, SubscriptionManager, TradeSubscriptionEvent, WebSocketState,
+    WsAuth,
 };
-pub use websocket::{SubscriptionEvent, SubscriptionManager, TradeSubscriptionEvent, WebSocketState};
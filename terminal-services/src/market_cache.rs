@@ -3,19 +3,29 @@
 //! In-memory cache with SQLite persistence for instant market lookups.
 //! This is the key to fast search and market list operations.
 
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
-use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
 use rusqlite::{params, Connection};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
-use terminal_core::{Platform, PredictionMarket, TerminalError};
+use std::time::{Duration, Instant};
+use terminal_core::{MarketStatus, Platform, PredictionMarket, TerminalError, UnifiedMarket};
+use terminal_embedding::EmbeddingStore;
 use terminal_polymarket::MarketFilter;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
-use crate::MarketService;
+use crate::canary::is_canary_market;
+use crate::market_matching::{find_matches, MIN_MATCH_CONFIDENCE};
+use crate::websocket::WebSocketState;
+use crate::{MarketResolution, MarketService, TradeCollector, TradeStorage};
 
 /// Cache TTL in seconds (5 minutes)
 const CACHE_TTL_SECS: i64 = 300;
@@ -23,17 +33,77 @@ const CACHE_TTL_SECS: i64 = 300;
 /// Filter cache TTL in seconds (30 seconds - shorter for fresher filtered results)
 const FILTER_CACHE_TTL_SECS: i64 = 30;
 
+/// Default page size for [`MarketCache::get_markets`]
+pub const DEFAULT_MARKET_PAGE_SIZE: usize = 100;
+
+/// Repeats of a refresh request within this window are dropped
+const REFRESH_COALESCE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Default age after which closed/settled markets are archived (7 days)
+const DEFAULT_ARCHIVE_AFTER_DAYS: i64 = 7;
+
+/// First backoff after a failed platform refresh, doubled per further failure
+const REFRESH_BACKOFF_BASE_SECS: i64 = 30;
+
+/// Longest backoff between failing platform refreshes (15 minutes)
+const REFRESH_BACKOFF_MAX_SECS: i64 = 900;
+
+/// How long a market's reference price anchors [`SortKey::Change`] (24 hours)
+const PRICE_REFERENCE_SECS: i64 = 86400;
+
+/// Horizons for a market's `change_1h` / `change_24h` (seconds)
+const CHANGE_1H_SECS: i64 = 3600;
+const CHANGE_24H_SECS: i64 = 86400;
+
+/// Cache change events buffered per subscriber before it lags
+const CACHE_EVENT_CAPACITY: usize = 1024;
+
+/// Markets per batched price-snapshot lookup (bounded by SQLite's parameter limit)
+const SNAPSHOT_LOOKUP_CHUNK: usize = 500;
+
+/// Search terms expanded to each other (e.g. "bitcoin" also matches "BTC")
+const SEARCH_SYNONYMS: &[(&str, &str)] = &[
+    ("bitcoin", "btc"),
+    ("ethereum", "eth"),
+    ("solana", "sol"),
+];
+
+/// bm25 column weights for (platform, market_id, title, description)
+const SEARCH_WEIGHTS: &str = "0.0, 0.0, 10.0, 1.0";
+
 /// Cached market with metadata
 #[derive(Debug, Clone)]
 struct CachedMarket {
     market: PredictionMarket,
     updated_at: DateTime<Utc>,
+    /// Set when warmed from SQLite at startup: served as stale from this
+    /// time until a refresh replaces the entry
+    stale_at: Option<DateTime<Utc>>,
 }
 
 impl CachedMarket {
     fn is_fresh(&self) -> bool {
         let age = Utc::now().signed_duration_since(self.updated_at);
-        age.num_seconds() < CACHE_TTL_SECS
+        self.stale_at.is_none() && age.num_seconds() < CACHE_TTL_SECS
+    }
+
+    /// Rough heap + inline footprint, for [`CacheStats::memory_bytes`]
+    fn estimated_size(&self) -> usize {
+        let m = &self.market;
+        let opt = |s: &Option<String>| s.as_ref().map_or(0, String::len);
+        std::mem::size_of::<Self>()
+            + m.id.len()
+            + m.title.len()
+            + opt(&m.ticker)
+            + opt(&m.description)
+            + opt(&m.category)
+            + opt(&m.image_url)
+            + opt(&m.url)
+            + opt(&m.event_id)
+            + opt(&m.options_json)
+            + opt(&m.leading_outcome)
+            + opt(&m.resolution_source)
+            + m.tags.iter().map(|t| t.len() + std::mem::size_of::<String>()).sum::<usize>()
     }
 }
 
@@ -51,8 +121,182 @@ impl CachedFilterResult {
     }
 }
 
+/// Trade storage used to record resolutions (set after construction)
+type StorageSlot = Arc<RwLock<Option<Arc<TradeStorage>>>>;
+
+/// WebSocket state that single-market refreshes are broadcast through (set after construction)
+type WebSocketSlot = Arc<RwLock<Option<Arc<WebSocketState>>>>;
+
+/// Embedding store used to sharpen cross-platform matching (set after construction)
+type EmbeddingSlot = Arc<RwLock<Option<Arc<EmbeddingStore>>>>;
+
+/// Trade collector that watched markets are tracked with (set after construction)
+type CollectorSlot = Arc<RwLock<Option<Arc<TradeCollector>>>>;
+
+/// Metadata overrides by market, mirrored from the `market_overrides` table
+type OverrideMap = Arc<RwLock<HashMap<(Platform, String), MarketOverride>>>;
+
+/// Outcome of the latest platform refreshes
+type RefreshStatusSlot = Arc<RwLock<HashMap<Platform, PlatformRefreshStatus>>>;
+
+/// A change to the cached markets, emitted as refreshes land
+#[derive(Debug, Clone)]
+pub enum MarketCacheEvent {
+    /// A market not previously cached
+    Added { platform: Platform, market: PredictionMarket },
+    /// A cached market whose price, volume or status changed
+    Updated { platform: Platform, market: PredictionMarket },
+    /// A market moved out of the cache (archived)
+    Removed { platform: Platform, market_id: String },
+}
+
+/// The fields compared to decide whether a refreshed market changed
+fn market_fingerprint(market: &PredictionMarket) -> (Decimal, Decimal, MarketStatus) {
+    (market.yes_price, market.volume, market.status)
+}
+
+/// Manual corrections to how a market is listed
+///
+/// Kept apart from the market rows and merged when serving, so refreshes
+/// never overwrite them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarketOverride {
+    /// Title shown instead of the platform's
+    #[serde(default)]
+    pub display_title: Option<String>,
+    /// Listed ahead of unpinned markets
+    #[serde(default)]
+    pub pinned: bool,
+    /// Left out of market listings
+    #[serde(default)]
+    pub hidden: bool,
+    /// Internal notes, never shown in listings
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// A market on a watchlist, hydrated from the cache
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchlistEntry {
+    pub platform: Platform,
+    pub market_id: String,
+    /// When the market was added to the list
+    pub added_at: DateTime<Utc>,
+    /// The cached market, or its archived copy when no longer listed
+    pub market: Option<PredictionMarket>,
+    /// Set once the market has left the cache (archived or delisted)
+    pub unavailable: bool,
+}
+
+/// Outcome of a platform's most recent full refreshes
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlatformRefreshStatus {
+    /// When the platform last refreshed successfully
+    pub last_refresh_at: Option<DateTime<Utc>>,
+    /// Error from the latest attempt, cleared on success
+    pub last_error: Option<String>,
+    /// Failed attempts since the last success
+    pub consecutive_failures: u32,
+    /// Unforced refreshes are skipped until then
+    pub backoff_until: Option<DateTime<Utc>>,
+    /// How long the latest attempt took, successful or not
+    pub last_duration_ms: Option<u64>,
+    /// Markets first seen by the last successful refresh
+    pub last_added: usize,
+    /// Markets whose price, volume or status changed in the last successful refresh
+    pub last_updated: usize,
+    /// Markets archived after the last successful refresh
+    pub last_removed: usize,
+}
+
+/// Wait after `failures` consecutive failed refreshes (exponential, capped)
+fn refresh_backoff(failures: u32) -> chrono::Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    chrono::Duration::seconds((REFRESH_BACKOFF_BASE_SECS << doublings).min(REFRESH_BACKOFF_MAX_SECS))
+}
+
+/// Configuration for [`MarketCache::start_refresh_loop`]
+#[derive(Debug, Clone)]
+pub struct RefreshLoopConfig {
+    /// How often to refresh Polymarket (`None` disables)
+    pub polymarket_interval: Option<Duration>,
+    /// How often to refresh Kalshi (`None` disables)
+    pub kalshi_interval: Option<Duration>,
+    /// Extra random delay per cycle, as a fraction of the interval
+    pub jitter: f64,
+}
+
+impl Default for RefreshLoopConfig {
+    fn default() -> Self {
+        Self {
+            polymarket_interval: Some(Duration::from_secs(60)),
+            // KALSHI_DISABLED: Only refresh Polymarket while focusing on it
+            kalshi_interval: None,
+            jitter: 0.25,
+        }
+    }
+}
+
+/// Where the cache fetches markets from when refreshing
+#[async_trait]
+pub trait MarketSource: Send + Sync {
+    /// Fetch a single market
+    async fn fetch_market(&self, platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError>;
+
+    /// Fetch every listed market for a platform
+    async fn fetch_platform_markets(&self, platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError>;
+}
+
+#[async_trait]
+impl MarketSource for MarketService {
+    async fn fetch_market(&self, platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError> {
+        self.get_market(platform, market_id).await
+    }
+
+    async fn fetch_platform_markets(&self, platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+        self.get_markets_by_platform(platform, None).await
+    }
+}
+
+/// Precomputed market orderings served by [`MarketCache::get_markets_sorted`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortKey {
+    /// 24h volume, highest first
+    Volume24h,
+    /// Total volume, highest first
+    Volume,
+    /// Absolute move from the daily reference price, largest first
+    Change,
+    /// Close time, soonest first (markets without one are left out)
+    Closing,
+}
+
+impl SortKey {
+    /// Every maintained ordering
+    pub const ALL: [SortKey; 4] = [SortKey::Volume24h, SortKey::Volume, SortKey::Change, SortKey::Closing];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::Volume24h => "volume_24h",
+            SortKey::Volume => "volume",
+            SortKey::Change => "change",
+            SortKey::Closing => "closing",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "volume_24h" | "trending" => Some(SortKey::Volume24h),
+            "volume" => Some(SortKey::Volume),
+            "change" => Some(SortKey::Change),
+            "closing" => Some(SortKey::Closing),
+            _ => None,
+        }
+    }
+}
+
 /// Background refresh request
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RefreshRequest {
     /// Refresh a single market
     Single { platform: Platform, market_id: String },
@@ -72,8 +316,32 @@ pub struct MarketCache {
     db: Arc<parking_lot::Mutex<Connection>>,
     /// Underlying market service for API calls
     service: Arc<MarketService>,
+    /// Source for cache refreshes (the market service outside tests)
+    source: Arc<dyn MarketSource>,
     /// Channel to send refresh requests to background task
     refresh_tx: mpsc::Sender<RefreshRequest>,
+    /// Where observed market resolutions are recorded
+    trade_storage: StorageSlot,
+    /// Latest full refresh outcome per platform
+    refresh_status: RefreshStatusSlot,
+    /// Set to skip scheduled refreshes (e.g. during maintenance)
+    refresh_paused: Arc<AtomicBool>,
+    /// Market embeddings for cross-platform matching, when available
+    embedding_store: EmbeddingSlot,
+    /// Days after closing before a closed/settled market is archived
+    archive_after_days: Arc<AtomicI64>,
+    /// Where refreshed markets are broadcast to subscribed clients
+    ws_state: WebSocketSlot,
+    /// Per-market listing overrides (title, pin, hide, notes)
+    overrides: OverrideMap,
+    /// Added/updated/removed markets, for subscribers
+    events: broadcast::Sender<MarketCacheEvent>,
+    /// Lookups served from fresh cache entries since startup
+    hits: Arc<AtomicU64>,
+    /// Lookups that found a missing or stale entry since startup
+    misses: Arc<AtomicU64>,
+    /// Where markets on fetched watchlists are tracked for live stats
+    trade_collector: CollectorSlot,
 }
 
 impl MarketCache {
@@ -86,6 +354,16 @@ impl MarketCache {
     pub async fn new<P: AsRef<Path>>(
         db_path: P,
         service: MarketService,
+    ) -> Result<Self, MarketCacheError> {
+        let source: Arc<dyn MarketSource> = Arc::new(service.clone());
+        Self::with_source(db_path, service, source).await
+    }
+
+    /// Create a MarketCache that refreshes from `source` instead of `service`
+    pub async fn with_source<P: AsRef<Path>>(
+        db_path: P,
+        service: MarketService,
+        source: Arc<dyn MarketSource>,
     ) -> Result<Self, MarketCacheError> {
         // Open database
         let conn = Connection::open(db_path.as_ref()).map_err(MarketCacheError::Database)?;
@@ -108,50 +386,199 @@ impl MarketCache {
 
             CREATE INDEX IF NOT EXISTS idx_markets_title
             ON markets(title COLLATE NOCASE);
+
+            -- Normalized category and tags, one row per (market, category)
+            CREATE TABLE IF NOT EXISTS market_categories (
+                platform TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                category TEXT NOT NULL,
+                PRIMARY KEY (platform, market_id, category)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_market_categories_category
+            ON market_categories(category);
+
+            -- Precomputed orderings, rewritten after each platform refresh
+            CREATE TABLE IF NOT EXISTS market_sort_index (
+                sort_key TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                platform TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                PRIMARY KEY (sort_key, position)
+            );
+
+            -- Kalshi/Polymarket pairs describing the same event.
+            -- status: 'auto' (matcher), 'linked' or 'rejected' (manual overrides)
+            CREATE TABLE IF NOT EXISTS unified_matches (
+                kalshi_id TEXT NOT NULL,
+                polymarket_id TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                status TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (kalshi_id, polymarket_id)
+            );
+
+            -- Closed/settled markets moved out of the hot cache
+            CREATE TABLE IF NOT EXISTS archived_markets (
+                platform TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                data JSON NOT NULL,
+                closed_at INTEGER NOT NULL,
+                archived_at INTEGER NOT NULL,
+                PRIMARY KEY (platform, market_id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_archived_markets_closed
+            ON archived_markets(platform, closed_at);
+
+            -- Manual listing overrides, merged into markets when served
+            CREATE TABLE IF NOT EXISTS market_overrides (
+                platform TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                display_title TEXT,
+                pinned INTEGER NOT NULL DEFAULT 0,
+                hidden INTEGER NOT NULL DEFAULT 0,
+                notes TEXT,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (platform, market_id)
+            );
+
+            -- Markets saved to client-named watchlists
+            CREATE TABLE IF NOT EXISTS watchlists (
+                list_id TEXT NOT NULL,
+                platform TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                added_at INTEGER NOT NULL,
+                PRIMARY KEY (list_id, platform, market_id)
+            );
+
+            -- Reference prices for the price change ordering
+            CREATE TABLE IF NOT EXISTS market_price_refs (
+                platform TEXT NOT NULL,
+                market_id TEXT NOT NULL,
+                price REAL NOT NULL,
+                anchored_at INTEGER NOT NULL,
+                PRIMARY KEY (platform, market_id)
+            );
             "#,
         )
         .map_err(MarketCacheError::Database)?;
 
+        // Full-text index over titles and descriptions, rebuilt from the
+        // markets table on startup. Search falls back to LIKE without FTS5.
+        let fts = conn.execute_batch(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS markets_fts USING fts5(
+                platform UNINDEXED,
+                market_id UNINDEXED,
+                title,
+                description
+            );
+
+            DELETE FROM markets_fts;
+
+            INSERT INTO markets_fts (platform, market_id, title, description)
+            SELECT platform, market_id, title, COALESCE(json_extract(data, '$.description'), '')
+            FROM markets m
+            WHERE NOT EXISTS (
+                SELECT 1 FROM archived_markets a
+                WHERE a.platform = m.platform AND a.market_id = m.market_id
+            );
+            "#,
+        );
+        if let Err(e) = fts {
+            warn!("FTS5 unavailable, market search falls back to LIKE: {}", e);
+        }
+
         let db = Arc::new(parking_lot::Mutex::new(conn));
         let cache = Arc::new(RwLock::new(HashMap::new()));
         let filter_cache = Arc::new(RwLock::new(HashMap::new()));
         let service = Arc::new(service);
+        let trade_storage: StorageSlot = Arc::new(RwLock::new(None));
+        let refresh_status: RefreshStatusSlot = Arc::new(RwLock::new(HashMap::new()));
+        let ws_state: WebSocketSlot = Arc::new(RwLock::new(None));
 
-        // Load existing cached markets from DB
+        // Warm the memory cache from the DB before serving anything, so a
+        // restart serves the last persisted markets until the first refresh
         let loaded = Self::load_from_db(&db, &cache)?;
         info!("Loaded {} markets from cache database", loaded);
+        Self::rebuild_category_index(&db, &cache)?;
+
+        // Persisted sort indices serve the first requests; build them if missing
+        let indexed: i64 = db
+            .lock()
+            .query_row("SELECT COUNT(*) FROM market_sort_index", [], |row| row.get(0))
+            .map_err(MarketCacheError::Database)?;
+        if indexed == 0 && loaded > 0 {
+            Self::rebuild_sort_indices(&db, &cache, Utc::now())?;
+        }
+
+        let overrides: OverrideMap = Arc::new(RwLock::new(Self::load_overrides(&db)?));
 
         // Create refresh channel
         let (refresh_tx, refresh_rx) = mpsc::channel::<RefreshRequest>(100);
+        let (events, _) = broadcast::channel(CACHE_EVENT_CAPACITY);
 
         let market_cache = Self {
             cache: Arc::clone(&cache),
             filter_cache: Arc::clone(&filter_cache),
             db: Arc::clone(&db),
             service: Arc::clone(&service),
+            source: Arc::clone(&source),
             refresh_tx,
+            trade_storage: Arc::clone(&trade_storage),
+            refresh_status: Arc::clone(&refresh_status),
+            refresh_paused: Arc::new(AtomicBool::new(false)),
+            embedding_store: Arc::new(RwLock::new(None)),
+            archive_after_days: Arc::new(AtomicI64::new(DEFAULT_ARCHIVE_AFTER_DAYS)),
+            ws_state: Arc::clone(&ws_state),
+            overrides,
+            events: events.clone(),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+            trade_collector: Arc::new(RwLock::new(None)),
         };
 
         // Spawn background refresh task
         let cache_clone = Arc::clone(&cache);
         let db_clone = Arc::clone(&db);
-        let service_clone = Arc::clone(&service);
         tokio::spawn(async move {
-            Self::background_refresh_task(cache_clone, db_clone, service_clone, refresh_rx).await;
+            Self::background_refresh_task(
+                cache_clone,
+                db_clone,
+                source,
+                trade_storage,
+                refresh_status,
+                ws_state,
+                events,
+                refresh_rx,
+            )
+            .await;
         });
 
         Ok(market_cache)
     }
 
-    /// Load markets from SQLite into memory
+    /// Load markets from SQLite into memory, marked stale
+    ///
+    /// Markets that also appear in the archive (an archive interrupted before
+    /// its delete) stay archived.
     fn load_from_db(
         db: &Arc<parking_lot::Mutex<Connection>>,
         cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
     ) -> Result<usize, MarketCacheError> {
         let conn = db.lock();
+        let stale_at = Utc::now();
 
         let mut stmt = conn
-            .prepare("SELECT platform, market_id, data, updated_at FROM markets")
+            .prepare(
+                "SELECT platform, market_id, data, updated_at FROM markets m
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM archived_markets a
+                     WHERE a.platform = m.platform AND a.market_id = m.market_id
+                 )",
+            )
             .map_err(MarketCacheError::Database)?;
 
         let rows = stmt
@@ -165,6 +592,7 @@ impl MarketCache {
             .map_err(MarketCacheError::Database)?;
 
         let mut loaded = 0;
+        let mut skipped = 0;
         let mut write_cache = cache.write();
 
         for row in rows.flatten() {
@@ -173,59 +601,249 @@ impl MarketCache {
             let platform = match platform_str.as_str() {
                 "kalshi" => Platform::Kalshi,
                 "polymarket" => Platform::Polymarket,
-                _ => continue,
+                _ => {
+                    skipped += 1;
+                    continue;
+                }
             };
 
-            if let Ok(market) = serde_json::from_str::<PredictionMarket>(&data_json) {
-                let updated_at =
-                    DateTime::from_timestamp(updated_at, 0).unwrap_or_else(Utc::now);
+            match serde_json::from_str::<PredictionMarket>(&data_json) {
+                Ok(market) => {
+                    let updated_at =
+                        DateTime::from_timestamp(updated_at, 0).unwrap_or_else(Utc::now);
 
-                write_cache.insert(
-                    (platform, market_id),
-                    CachedMarket { market, updated_at },
-                );
-                loaded += 1;
+                    write_cache.insert(
+                        (platform, market_id),
+                        CachedMarket {
+                            market,
+                            updated_at,
+                            stale_at: Some(stale_at),
+                        },
+                    );
+                    loaded += 1;
+                }
+                Err(e) => {
+                    debug!("Skipping unreadable cached market {}: {}", market_id, e);
+                    skipped += 1;
+                }
             }
         }
 
+        if skipped > 0 {
+            warn!("Skipped {} unreadable markets in cache database", skipped);
+        }
+
         Ok(loaded)
     }
 
+    /// Read all stored market overrides
+    fn load_overrides(
+        db: &Arc<parking_lot::Mutex<Connection>>,
+    ) -> Result<HashMap<(Platform, String), MarketOverride>, MarketCacheError> {
+        let conn = db.lock();
+        let mut stmt = conn
+            .prepare("SELECT platform, market_id, display_title, pinned, hidden, notes FROM market_overrides")
+            .map_err(MarketCacheError::Database)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    MarketOverride {
+                        display_title: row.get(2)?,
+                        pinned: row.get(3)?,
+                        hidden: row.get(4)?,
+                        notes: row.get(5)?,
+                    },
+                ))
+            })
+            .map_err(MarketCacheError::Database)?;
+
+        Ok(rows
+            .filter_map(|r| r.ok())
+            .filter_map(|(platform_str, market_id, market_override)| {
+                let platform = match platform_str.as_str() {
+                    "kalshi" => Platform::Kalshi,
+                    "polymarket" => Platform::Polymarket,
+                    _ => return None,
+                };
+                Some(((platform, market_id), market_override))
+            })
+            .collect())
+    }
+
+    /// Re-index every cached market's categories (normalization may have changed)
+    fn rebuild_category_index(
+        db: &Arc<parking_lot::Mutex<Connection>>,
+        cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
+    ) -> Result<(), MarketCacheError> {
+        let mut conn = db.lock();
+        let tx = conn.transaction().map_err(MarketCacheError::Database)?;
+        tx.execute("DELETE FROM market_categories", [])
+            .map_err(MarketCacheError::Database)?;
+        for ((platform, _), cached) in cache.read().iter() {
+            index_categories(&tx, platform_key(*platform), &cached.market)
+                .map_err(MarketCacheError::Database)?;
+        }
+        tx.commit().map_err(MarketCacheError::Database)
+    }
+
+    /// Recompute every [`SortKey`] ordering from the memory cache and persist it
+    ///
+    /// Ties break on (platform, market id) so orderings are stable across
+    /// rebuilds. Reference prices older than [`PRICE_REFERENCE_SECS`] are
+    /// re-anchored to the current price after being compared against.
+    fn rebuild_sort_indices(
+        db: &Arc<parking_lot::Mutex<Connection>>,
+        cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
+        now: DateTime<Utc>,
+    ) -> Result<(), MarketCacheError> {
+        let markets: Vec<(&'static str, PredictionMarket)> = cache
+            .read()
+            .iter()
+            .filter(|((_, id), _)| !is_canary_market(id))
+            .map(|((platform, _), cached)| (platform_key(*platform), cached.market.clone()))
+            .collect();
+
+        let mut conn = db.lock();
+        let tx = conn.transaction().map_err(MarketCacheError::Database)?;
+
+        let refs: HashMap<(String, String), (f64, i64)> = {
+            let mut stmt = tx
+                .prepare("SELECT platform, market_id, price, anchored_at FROM market_price_refs")
+                .map_err(MarketCacheError::Database)?;
+            let rows = stmt
+                .query_map([], |row| Ok(((row.get(0)?, row.get(1)?), (row.get(2)?, row.get(3)?))))
+                .map_err(MarketCacheError::Database)?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        let mut changes: HashMap<(&str, &str), f64> = HashMap::new();
+        for (platform_str, market) in &markets {
+            let price = market.yes_price.to_f64().unwrap_or(0.0);
+            let reference = refs.get(&(platform_str.to_string(), market.id.clone()));
+            changes.insert(
+                (*platform_str, market.id.as_str()),
+                reference.map_or(0.0, |(ref_price, _)| (price - ref_price).abs()),
+            );
+            let anchored = reference.is_some_and(|(_, at)| now.timestamp() - at < PRICE_REFERENCE_SECS);
+            if !anchored {
+                tx.execute(
+                    "INSERT OR REPLACE INTO market_price_refs (platform, market_id, price, anchored_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![platform_str, market.id, price, now.timestamp()],
+                )
+                .map_err(MarketCacheError::Database)?;
+            }
+        }
+
+        tx.execute("DELETE FROM market_sort_index", [])
+            .map_err(MarketCacheError::Database)?;
+        for key in SortKey::ALL {
+            let mut ordered: Vec<&(&'static str, PredictionMarket)> = markets
+                .iter()
+                .filter(|(_, m)| key != SortKey::Closing || m.close_time.is_some())
+                .collect();
+            ordered.sort_by(|(pa, a), (pb, b)| {
+                let primary = match key {
+                    SortKey::Volume24h => b.volume_24hr.unwrap_or_default().cmp(&a.volume_24hr.unwrap_or_default()),
+                    SortKey::Volume => b.volume.cmp(&a.volume),
+                    SortKey::Change => changes[&(*pb, b.id.as_str())].total_cmp(&changes[&(*pa, a.id.as_str())]),
+                    SortKey::Closing => a.close_time.cmp(&b.close_time),
+                };
+                primary.then_with(|| (pa, &a.id).cmp(&(pb, &b.id)))
+            });
+
+            for (position, (platform_str, market)) in ordered.into_iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO market_sort_index (sort_key, position, platform, market_id) VALUES (?1, ?2, ?3, ?4)",
+                    params![key.as_str(), position as i64, platform_str, market.id],
+                )
+                .map_err(MarketCacheError::Database)?;
+            }
+        }
+
+        tx.commit().map_err(MarketCacheError::Database)
+    }
+
     /// Background task that handles refresh requests
+    ///
+    /// Requests repeated within [`REFRESH_COALESCE_WINDOW`] of an identical
+    /// one are dropped. Refreshed single markets are broadcast as price updates.
     async fn background_refresh_task(
         cache: Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
         db: Arc<parking_lot::Mutex<Connection>>,
-        service: Arc<MarketService>,
+        source: Arc<dyn MarketSource>,
+        trade_storage: StorageSlot,
+        refresh_status: RefreshStatusSlot,
+        ws_state: WebSocketSlot,
+        events: broadcast::Sender<MarketCacheEvent>,
         mut rx: mpsc::Receiver<RefreshRequest>,
     ) {
         info!("Market cache background refresh task started");
 
+        let mut last_handled: HashMap<RefreshRequest, Instant> = HashMap::new();
         while let Some(request) = rx.recv().await {
+            let now = Instant::now();
+            if last_handled
+                .get(&request)
+                .is_some_and(|at| now.duration_since(*at) < REFRESH_COALESCE_WINDOW)
+            {
+                debug!("Coalesced duplicate refresh request: {:?}", request);
+                continue;
+            }
+            last_handled.retain(|_, at| now.duration_since(*at) < REFRESH_COALESCE_WINDOW);
+            last_handled.insert(request.clone(), now);
+
             match request {
                 RefreshRequest::Single { platform, market_id } => {
                     debug!("Refreshing single market: {:?}/{}", platform, market_id);
-                    if let Err(e) =
-                        Self::refresh_single(&cache, &db, &service, platform, &market_id).await
-                    {
-                        warn!("Failed to refresh market {}: {}", market_id, e);
+                    match Self::refresh_single(&cache, &db, &source, &trade_storage, platform, &market_id).await {
+                        Ok(market) => {
+                            if let Some(ws) = ws_state.read().clone() {
+                                ws.broadcast_price_update(platform, market.id, market.yes_price, market.no_price);
+                            }
+                        }
+                        Err(e) => warn!("Failed to refresh market {}: {}", market_id, e),
                     }
                 }
                 RefreshRequest::Platform(platform) => {
                     debug!("Refreshing all {:?} markets", platform);
-                    if let Err(e) =
-                        Self::refresh_platform(&cache, &db, &service, platform).await
+                    match Self::refresh_platform(
+                        &cache,
+                        &db,
+                        &source,
+                        &trade_storage,
+                        &refresh_status,
+                        &events,
+                        platform,
+                        false,
+                    )
+                    .await
                     {
-                        warn!("Failed to refresh {:?} markets: {}", platform, e);
+                        Ok(()) => {}
+                        Err(e @ MarketCacheError::BackingOff { .. }) => debug!("{}", e),
+                        Err(e) => warn!("Failed to refresh {:?} markets: {}", platform, e),
                     }
                 }
                 RefreshRequest::All => {
                     debug!("Refreshing all markets");
                     // KALSHI_DISABLED: Only refresh Polymarket while focusing on it
                     for platform in [Platform::Polymarket] {
-                        if let Err(e) =
-                            Self::refresh_platform(&cache, &db, &service, platform).await
+                        match Self::refresh_platform(
+                            &cache,
+                            &db,
+                            &source,
+                            &trade_storage,
+                            &refresh_status,
+                            &events,
+                            platform,
+                            false,
+                        )
+                        .await
                         {
-                            warn!("Failed to refresh {:?} markets: {}", platform, e);
+                            Ok(()) => {}
+                            Err(e @ MarketCacheError::BackingOff { .. }) => debug!("{}", e),
+                            Err(e) => warn!("Failed to refresh {:?} markets: {}", platform, e),
                         }
                     }
                 }
@@ -234,63 +852,138 @@ impl MarketCache {
     }
 
     /// Refresh a single market from API
+    ///
+    /// No lock is held across the fetch; the memory and SQLite writes each take
+    /// their lock briefly, so concurrent refreshes of the same market are
+    /// last-write-wins.
     async fn refresh_single(
         cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
         db: &Arc<parking_lot::Mutex<Connection>>,
-        service: &Arc<MarketService>,
+        source: &Arc<dyn MarketSource>,
+        trade_storage: &StorageSlot,
         platform: Platform,
         market_id: &str,
-    ) -> Result<(), MarketCacheError> {
-        let market = service
-            .get_market(platform, market_id)
+    ) -> Result<PredictionMarket, MarketCacheError> {
+        let mut market = source
+            .fetch_market(platform, market_id)
             .await
             .map_err(MarketCacheError::Api)?;
 
         let now = Utc::now();
-        let cached = CachedMarket {
-            market: market.clone(),
-            updated_at: now,
-        };
+        Self::annotate_price_changes(cache, trade_storage, platform, std::slice::from_mut(&mut market), now);
 
         // Update memory cache
-        cache.write().insert((platform, market_id.to_string()), cached);
+        Self::update_memory_cache(cache, trade_storage, platform, std::slice::from_ref(&market), now);
 
         // Update SQLite
         Self::store_market_to_db(db, platform, &market, now)?;
 
-        Ok(())
+        Ok(market)
     }
 
     /// Refresh all markets for a platform
+    ///
+    /// Each failure backs the platform off exponentially; until the backoff
+    /// expires, unforced refreshes return [`MarketCacheError::BackingOff`]
+    /// without fetching. A success clears the backoff.
     async fn refresh_platform(
         cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
         db: &Arc<parking_lot::Mutex<Connection>>,
-        service: &Arc<MarketService>,
+        source: &Arc<dyn MarketSource>,
+        trade_storage: &StorageSlot,
+        refresh_status: &RefreshStatusSlot,
+        events: &broadcast::Sender<MarketCacheEvent>,
         platform: Platform,
+        force: bool,
     ) -> Result<(), MarketCacheError> {
-        let markets = service
-            .get_markets_by_platform(platform, None)
+        if !force {
+            let backoff_until = refresh_status.read().get(&platform).and_then(|s| s.backoff_until);
+            if let Some(until) = backoff_until.filter(|until| *until > Utc::now()) {
+                return Err(MarketCacheError::BackingOff { platform, until });
+            }
+        }
+
+        let started = Instant::now();
+        let result = Self::fetch_and_store_platform(cache, db, source, trade_storage, events, platform).await;
+
+        let mut status = refresh_status.write();
+        let entry = status.entry(platform).or_default();
+        entry.last_duration_ms = Some(started.elapsed().as_millis() as u64);
+        match &result {
+            Ok((added, updated)) => {
+                entry.last_refresh_at = Some(Utc::now());
+                entry.last_error = None;
+                entry.consecutive_failures = 0;
+                entry.backoff_until = None;
+                entry.last_added = *added;
+                entry.last_updated = *updated;
+                entry.last_removed = 0;
+            }
+            Err(e) => {
+                entry.last_error = Some(e.to_string());
+                entry.consecutive_failures += 1;
+                let backoff = refresh_backoff(entry.consecutive_failures);
+                entry.backoff_until = Some(Utc::now() + backoff);
+                warn!(
+                    "{:?} refresh failed {} time(s) in a row, backing off {}s",
+                    platform,
+                    entry.consecutive_failures,
+                    backoff.num_seconds()
+                );
+            }
+        }
+
+        result.map(|_| ())
+    }
+
+    /// Fetch a platform's markets and write them to memory and SQLite
+    ///
+    /// Markets that are new, or whose fingerprint changed, are emitted as
+    /// [`MarketCacheEvent`]s once stored. Returns how many were added and
+    /// updated.
+    async fn fetch_and_store_platform(
+        cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
+        db: &Arc<parking_lot::Mutex<Connection>>,
+        source: &Arc<dyn MarketSource>,
+        trade_storage: &StorageSlot,
+        events: &broadcast::Sender<MarketCacheEvent>,
+        platform: Platform,
+    ) -> Result<(usize, usize), MarketCacheError> {
+        let mut markets = source
+            .fetch_platform_markets(platform)
             .await
             .map_err(MarketCacheError::Api)?;
 
         let now = Utc::now();
         let count = markets.len();
 
+        // Price movement against the previous cached prices and stored snapshots
+        Self::annotate_price_changes(cache, trade_storage, platform, &mut markets, now);
+
+        let changes = Self::diff_markets(cache, platform, &markets);
+        let added = changes
+            .iter()
+            .filter(|c| matches!(c, MarketCacheEvent::Added { .. }))
+            .count();
+        let updated = changes.len() - added;
+
         // Batch update memory cache
-        {
-            let mut write_cache = cache.write();
-            for market in &markets {
-                let cached = CachedMarket {
-                    market: market.clone(),
-                    updated_at: now,
-                };
-                write_cache.insert((platform, market.id.clone()), cached);
-            }
-        }
+        Self::update_memory_cache(cache, trade_storage, platform, &markets, now);
+
+        // Snapshot open interest (Kalshi) for its 24h change in market stats
+        Self::record_open_interest(trade_storage, platform, &markets, now);
 
         // Batch update SQLite
         Self::store_markets_to_db(db, platform, &markets, now)?;
 
+        for change in changes {
+            // No receivers is fine: nobody is listening for changes yet
+            let _ = events.send(change);
+        }
+
+        // Keep the precomputed orderings in step with the refreshed data
+        Self::rebuild_sort_indices(db, cache, now)?;
+
         // Log refresh with top markets by volume for visibility
         let mut sorted = markets.clone();
         sorted.sort_by(|a, b| b.volume.cmp(&a.volume));
@@ -309,47 +1002,212 @@ impl MarketCache {
         } else {
             info!("Refreshed {} {:?} markets", count, platform);
         }
-        Ok(())
+        Ok((added, updated))
     }
 
-    /// Store a single market to SQLite
-    fn store_market_to_db(
-        db: &Arc<parking_lot::Mutex<Connection>>,
+    /// Events for refreshed markets that are new or changed against the cache
+    fn diff_markets(
+        cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
         platform: Platform,
-        market: &PredictionMarket,
-        updated_at: DateTime<Utc>,
-    ) -> Result<(), MarketCacheError> {
-        let conn = db.lock();
+        markets: &[PredictionMarket],
+    ) -> Vec<MarketCacheEvent> {
+        let read_cache = cache.read();
+        markets
+            .iter()
+            .filter_map(|market| match read_cache.get(&(platform, market.id.clone())) {
+                None => Some(MarketCacheEvent::Added {
+                    platform,
+                    market: market.clone(),
+                }),
+                Some(cached) if market_fingerprint(&cached.market) != market_fingerprint(market) => {
+                    Some(MarketCacheEvent::Updated {
+                        platform,
+                        market: market.clone(),
+                    })
+                }
+                Some(_) => None,
+            })
+            .collect()
+    }
 
-        let platform_str = match platform {
-            Platform::Kalshi => "kalshi",
-            Platform::Polymarket => "polymarket",
+    /// Fill in `change_1h` / `change_24h` on freshly fetched markets
+    ///
+    /// Each horizon compares against the latest price snapshot at or before it
+    /// (ignoring snapshots more than one horizon older than that). The hour
+    /// falls back to the previous cached price when it's under an hour old.
+    /// Snapshot lookups are batched, a few queries per refresh rather than one
+    /// per market.
+    fn annotate_price_changes(
+        cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
+        trade_storage: &StorageSlot,
+        platform: Platform,
+        markets: &mut [PredictionMarket],
+        now: DateTime<Utc>,
+    ) {
+        let previous: HashMap<String, Decimal> = {
+            let read_cache = cache.read();
+            markets
+                .iter()
+                .filter_map(|m| {
+                    let cached = read_cache.get(&(platform, m.id.clone()))?;
+                    let age = (now - cached.updated_at).num_seconds();
+                    (age <= CHANGE_1H_SECS).then(|| (m.id.clone(), cached.market.yes_price))
+                })
+                .collect()
         };
 
-        let data_json =
-            serde_json::to_string(market).map_err(|e| MarketCacheError::Serialization(e.to_string()))?;
-
-        conn.execute(
-            r#"
-            INSERT OR REPLACE INTO markets (platform, market_id, ticker, title, data, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-            "#,
-            params![
-                platform_str,
-                market.id,
-                market.ticker,
-                market.title,
-                data_json,
-                updated_at.timestamp(),
-            ],
-        )
-        .map_err(MarketCacheError::Database)?;
+        let storage = trade_storage.read().clone();
+        let ids: Vec<String> = markets.iter().map(|m| m.id.clone()).collect();
+        let snapshot_prices = |horizon_secs: i64| -> HashMap<String, Decimal> {
+            let mut prices = HashMap::new();
+            let Some(storage) = &storage else {
+                return prices;
+            };
+            let target = now - chrono::Duration::seconds(horizon_secs);
+            let oldest = target.timestamp() - horizon_secs;
+            for chunk in ids.chunks(SNAPSHOT_LOOKUP_CHUNK) {
+                match storage.get_prices_at_time_batch(platform, chunk, target) {
+                    Ok(rows) => prices.extend(
+                        rows.into_iter()
+                            .filter(|(_, snapshot)| snapshot.timestamp >= oldest)
+                            .filter_map(|(id, snapshot)| Some((id, Decimal::from_f64(snapshot.yes_price)?))),
+                    ),
+                    Err(e) => {
+                        warn!("Failed to read {:?} price snapshots: {}", platform, e);
+                        break;
+                    }
+                }
+            }
+            prices
+        };
+        let hour_ago = snapshot_prices(CHANGE_1H_SECS);
+        let day_ago = snapshot_prices(CHANGE_24H_SECS);
 
-        Ok(())
+        for market in markets.iter_mut() {
+            let change = |reference: Option<&Decimal>| reference.map(|r| (market.yes_price - r).round_dp(4));
+            let change_1h = change(hour_ago.get(&market.id).or_else(|| previous.get(&market.id)));
+            let change_24h = change(day_ago.get(&market.id));
+            market.change_1h = change_1h;
+            market.change_24h = change_24h;
+        }
     }
 
-    /// Store multiple markets to SQLite
-    fn store_markets_to_db(
+    /// Insert refreshed markets into the memory cache
+    ///
+    /// Markets that transition to settled since their previous cached version
+    /// have their resolution recorded in trade storage (if configured).
+    fn update_memory_cache(
+        cache: &Arc<RwLock<HashMap<(Platform, String), CachedMarket>>>,
+        trade_storage: &StorageSlot,
+        platform: Platform,
+        markets: &[PredictionMarket],
+        now: DateTime<Utc>,
+    ) {
+        let mut resolved = Vec::new();
+        {
+            let mut write_cache = cache.write();
+            for market in markets {
+                let cached = CachedMarket {
+                    market: market.clone(),
+                    updated_at: now,
+                    stale_at: None,
+                };
+                let previous = write_cache.insert((platform, market.id.clone()), cached);
+                let was_open = previous.is_some_and(|p| p.market.status != MarketStatus::Settled);
+                if was_open && market.status == MarketStatus::Settled {
+                    resolved.push(resolution_for(market, now));
+                }
+            }
+        }
+
+        if resolved.is_empty() {
+            return;
+        }
+        let Some(storage) = trade_storage.read().clone() else {
+            return;
+        };
+        for resolution in &resolved {
+            match storage.store_resolution(resolution) {
+                Ok(true) => info!("Recorded resolution for {:?}/{}", platform, resolution.market_id),
+                Ok(false) => {}
+                Err(e) => warn!("Failed to store resolution for {}: {}", resolution.market_id, e),
+            }
+        }
+    }
+
+    /// Store a price snapshot carrying open interest for each market that reports it
+    fn record_open_interest(
+        trade_storage: &StorageSlot,
+        platform: Platform,
+        markets: &[PredictionMarket],
+        now: DateTime<Utc>,
+    ) {
+        let snapshots: Vec<(Platform, String, f64, Option<f64>, f64)> = markets
+            .iter()
+            .filter_map(|m| {
+                Some((
+                    platform,
+                    m.id.clone(),
+                    m.yes_price.to_f64()?,
+                    m.no_price.to_f64(),
+                    m.open_interest?.to_f64()?,
+                ))
+            })
+            .collect();
+        if snapshots.is_empty() {
+            return;
+        }
+        let Some(storage) = trade_storage.read().clone() else {
+            return;
+        };
+        if let Err(e) = storage.store_open_interest_snapshots_at(now.timestamp(), &snapshots) {
+            warn!("Failed to store {:?} open interest snapshots: {}", platform, e);
+        }
+    }
+
+    /// Store a single market to SQLite
+    fn store_market_to_db(
+        db: &Arc<parking_lot::Mutex<Connection>>,
+        platform: Platform,
+        market: &PredictionMarket,
+        updated_at: DateTime<Utc>,
+    ) -> Result<(), MarketCacheError> {
+        let conn = db.lock();
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let data_json =
+            serde_json::to_string(market).map_err(|e| MarketCacheError::Serialization(e.to_string()))?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO markets (platform, market_id, ticker, title, data, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                platform_str,
+                market.id,
+                market.ticker,
+                market.title,
+                data_json,
+                updated_at.timestamp(),
+            ],
+        )
+        .map_err(MarketCacheError::Database)?;
+
+        if has_fts_index(&conn) {
+            index_market(&conn, platform_str, market).map_err(MarketCacheError::Database)?;
+        }
+        index_categories(&conn, platform_str, market).map_err(MarketCacheError::Database)?;
+
+        Ok(())
+    }
+
+    /// Store multiple markets to SQLite
+    fn store_markets_to_db(
         db: &Arc<parking_lot::Mutex<Connection>>,
         platform: Platform,
         markets: &[PredictionMarket],
@@ -363,6 +1221,7 @@ impl MarketCache {
         };
 
         let timestamp = updated_at.timestamp();
+        let fts = has_fts_index(&conn);
 
         for market in markets {
             let data_json = match serde_json::to_string(market) {
@@ -388,6 +1247,16 @@ impl MarketCache {
                 ],
             ) {
                 warn!("Failed to store market {}: {}", market.id, e);
+                continue;
+            }
+
+            if fts {
+                if let Err(e) = index_market(&conn, platform_str, market) {
+                    warn!("Failed to index market {}: {}", market.id, e);
+                }
+            }
+            if let Err(e) = index_categories(&conn, platform_str, market) {
+                warn!("Failed to index categories for market {}: {}", market.id, e);
             }
         }
 
@@ -398,24 +1267,104 @@ impl MarketCache {
     // Public API
     // =========================================================================
 
-    /// Get all markets, optionally filtered by platform
+    /// Markets in a precomputed [`SortKey`] order, read from the persisted index
     ///
-    /// This returns instantly from the in-memory cache.
-    /// Triggers background refresh if data is stale.
-    pub fn get_markets(&self, platform: Option<Platform>) -> Vec<PredictionMarket> {
-        let read_cache = self.cache.read();
+    /// The closing order skips markets that have closed since the last rebuild.
+    pub fn get_markets_sorted(
+        &self,
+        sort: SortKey,
+        platform: Option<Platform>,
+        limit: Option<usize>,
+    ) -> Result<Vec<PredictionMarket>, MarketCacheError> {
+        let ids: Vec<(String, String)> = {
+            let conn = self.db.lock();
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT platform, market_id FROM market_sort_index
+                    WHERE sort_key = ?1 AND (?2 IS NULL OR platform = ?2)
+                    ORDER BY position
+                    "#,
+                )
+                .map_err(MarketCacheError::Database)?;
+            let rows = stmt
+                .query_map(params![sort.as_str(), platform.map(platform_key)], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .map_err(MarketCacheError::Database)?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
 
-        let mut markets: Vec<PredictionMarket> = read_cache
-            .iter()
-            .filter(|((p, _), _cached)| {
-                // Filter by platform if specified
-                platform.is_none() || platform == Some(*p)
+        let now = Utc::now();
+        let read_cache = self.cache.read();
+        let markets = ids
+            .into_iter()
+            .filter_map(|(platform_str, market_id)| {
+                let platform = match platform_str.as_str() {
+                    "kalshi" => Platform::Kalshi,
+                    "polymarket" => Platform::Polymarket,
+                    _ => return None,
+                };
+                read_cache.get(&(platform, market_id)).map(|c| c.market.clone())
             })
-            .map(|(_, cached)| cached.market.clone())
+            .filter(|m| sort != SortKey::Closing || m.close_time.is_some_and(|ct| ct > now))
+            .take(limit.unwrap_or(usize::MAX))
             .collect();
 
-        // Sort by volume descending (highest volume first)
-        markets.sort_by(|a, b| b.volume.cmp(&a.volume));
+        Ok(markets)
+    }
+
+    /// Get a page of markets, optionally filtered by platform
+    ///
+    /// This returns instantly from the in-memory cache, pinned markets first,
+    /// then by volume (highest first) with ties broken by platform and id.
+    /// Overrides apply: hidden markets are left out and display titles
+    /// replace platform titles. Pages continue after the cursor's position,
+    /// so markets added or dropped by a refresh between pages don't shift
+    /// later pages.
+    /// Triggers background refresh if data is stale.
+    pub fn get_markets(&self, platform: Option<Platform>, page: PageRequest) -> MarketPage {
+        let read_cache = self.cache.read();
+
+        let mut listed: Vec<(MarketCursor, PredictionMarket)> = {
+            let overrides = self.overrides.read();
+            read_cache
+                .iter()
+                .filter(|((p, id), _cached)| {
+                    // Filter by platform if specified; never list the canary market
+                    (platform.is_none() || platform == Some(*p)) && !is_canary_market(id)
+                })
+                .filter_map(|(key, cached)| {
+                    let market_override = overrides.get(key);
+                    if market_override.is_some_and(|o| o.hidden) {
+                        return None;
+                    }
+                    let mut market = cached.market.clone();
+                    if let Some(title) = market_override.and_then(|o| o.display_title.clone()) {
+                        market.title = title;
+                    }
+                    let pinned = market_override.is_some_and(|o| o.pinned);
+                    Some((MarketCursor::of(&market, pinned), market))
+                })
+                .collect()
+        };
+        let total_count = listed.len();
+
+        // Pinned first, then volume descending (highest volume first)
+        listed.sort_by(|a, b| listing_order(&a.0, &b.0));
+
+        if let Some(cursor) = &page.cursor {
+            listed.retain(|(position, _)| listing_order(position, cursor) == std::cmp::Ordering::Greater);
+        }
+        let mut next_cursor = None;
+        if !page.unlimited {
+            let limit = page.limit.unwrap_or(DEFAULT_MARKET_PAGE_SIZE);
+            if listed.len() > limit {
+                listed.truncate(limit);
+                next_cursor = listed.last().map(|(position, _)| position.encode());
+            }
+        }
+        let markets: Vec<PredictionMarket> = listed.into_iter().map(|(_, market)| market).collect();
 
         // Log top markets for debugging
         if markets.len() >= 3 {
@@ -435,6 +1384,8 @@ impl MarketCache {
         let needs_refresh = read_cache.values().any(|c| !c.is_fresh());
         drop(read_cache);
 
+        let counter = if needs_refresh { &self.misses } else { &self.hits };
+        counter.fetch_add(1, Ordering::Relaxed);
         if needs_refresh {
             let _ = self.refresh_tx.try_send(match platform {
                 Some(p) => RefreshRequest::Platform(p),
@@ -442,9 +1393,249 @@ impl MarketCache {
             });
         }
 
+        MarketPage {
+            markets,
+            total_count,
+            next_cursor,
+        }
+    }
+
+    /// Open markets closing within `window`, soonest first
+    ///
+    /// Markets without a close time are excluded.
+    pub fn get_closing_soon(
+        &self,
+        window: chrono::Duration,
+        platform: Option<Platform>,
+        limit: Option<usize>,
+    ) -> Vec<PredictionMarket> {
+        let now = Utc::now();
+        let mut markets = self.markets_closing_between(platform, now, now + window, |status| {
+            status == MarketStatus::Open
+        });
+        markets.sort_by(|a, b| a.close_time.cmp(&b.close_time).then_with(|| a.id.cmp(&b.id)));
+        if let Some(l) = limit {
+            markets.truncate(l);
+        }
+        markets
+    }
+
+    /// Settled markets resolved within the last `window`, most recent first
+    ///
+    /// A market's close time stands in for its resolution time; markets
+    /// without one are excluded.
+    pub fn get_recently_resolved(
+        &self,
+        window: chrono::Duration,
+        platform: Option<Platform>,
+        limit: Option<usize>,
+    ) -> Vec<PredictionMarket> {
+        let now = Utc::now();
+        let mut markets = self.markets_closing_between(platform, now - window, now, |status| {
+            status == MarketStatus::Settled
+        });
+        markets.sort_by(|a, b| b.close_time.cmp(&a.close_time).then_with(|| a.id.cmp(&b.id)));
+        if let Some(l) = limit {
+            markets.truncate(l);
+        }
         markets
     }
 
+    /// Cached markets with a matching status whose close time is in `[from, to]`
+    fn markets_closing_between(
+        &self,
+        platform: Option<Platform>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        status: impl Fn(MarketStatus) -> bool,
+    ) -> Vec<PredictionMarket> {
+        self.cache
+            .read()
+            .iter()
+            .filter(|((p, id), _)| (platform.is_none() || platform == Some(*p)) && !is_canary_market(id))
+            .map(|(_, cached)| &cached.market)
+            .filter(|m| status(m.status) && m.close_time.is_some_and(|t| t >= from && t <= to))
+            .cloned()
+            .collect()
+    }
+
+    /// Store (replacing) a market's listing override
+    pub fn set_override(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        market_override: MarketOverride,
+    ) -> Result<(), MarketCacheError> {
+        self.db
+            .lock()
+            .execute(
+                "INSERT OR REPLACE INTO market_overrides
+                 (platform, market_id, display_title, pinned, hidden, notes, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    platform_key(platform),
+                    market_id,
+                    market_override.display_title,
+                    market_override.pinned,
+                    market_override.hidden,
+                    market_override.notes,
+                    Utc::now().timestamp(),
+                ],
+            )
+            .map_err(MarketCacheError::Database)?;
+        self.overrides
+            .write()
+            .insert((platform, market_id.to_string()), market_override);
+        Ok(())
+    }
+
+    /// A market's listing override, if any
+    pub fn get_override(&self, platform: Platform, market_id: &str) -> Option<MarketOverride> {
+        self.overrides.read().get(&(platform, market_id.to_string())).cloned()
+    }
+
+    /// Remove a market's listing override; returns whether one existed
+    pub fn clear_override(&self, platform: Platform, market_id: &str) -> Result<bool, MarketCacheError> {
+        let removed = self
+            .db
+            .lock()
+            .execute(
+                "DELETE FROM market_overrides WHERE platform = ?1 AND market_id = ?2",
+                params![platform_key(platform), market_id],
+            )
+            .map_err(MarketCacheError::Database)?;
+        self.overrides.write().remove(&(platform, market_id.to_string()));
+        Ok(removed > 0)
+    }
+
+    /// Add a market to a watchlist; returns whether it was newly added
+    pub fn add_to_watchlist(
+        &self,
+        list_id: &str,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<bool, MarketCacheError> {
+        let added = self
+            .db
+            .lock()
+            .execute(
+                "INSERT OR IGNORE INTO watchlists (list_id, platform, market_id, added_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![list_id, platform_key(platform), market_id, Utc::now().timestamp()],
+            )
+            .map_err(MarketCacheError::Database)?;
+        Ok(added > 0)
+    }
+
+    /// Remove a market from a watchlist; returns whether it was on it
+    pub fn remove_from_watchlist(
+        &self,
+        list_id: &str,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<bool, MarketCacheError> {
+        let removed = self
+            .db
+            .lock()
+            .execute(
+                "DELETE FROM watchlists WHERE list_id = ?1 AND platform = ?2 AND market_id = ?3",
+                params![list_id, platform_key(platform), market_id],
+            )
+            .map_err(MarketCacheError::Database)?;
+        Ok(removed > 0)
+    }
+
+    /// A watchlist's markets, oldest addition first
+    ///
+    /// Markets that have left the cache come back flagged `unavailable`,
+    /// with their archived copy when there is one. Available markets are
+    /// tracked by the trade collector (when set) so they get live stats.
+    pub async fn get_watchlist(&self, list_id: &str) -> Result<Vec<WatchlistEntry>, MarketCacheError> {
+        let rows: Vec<(String, String, i64, Option<String>)> = {
+            let conn = self.db.lock();
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT w.platform, w.market_id, w.added_at, a.data
+                    FROM watchlists w
+                    LEFT JOIN archived_markets a
+                    ON a.platform = w.platform AND a.market_id = w.market_id
+                    WHERE w.list_id = ?1
+                    ORDER BY w.added_at, w.platform, w.market_id
+                    "#,
+                )
+                .map_err(MarketCacheError::Database)?;
+            let rows = stmt
+                .query_map(params![list_id], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })
+                .map_err(MarketCacheError::Database)?
+                .filter_map(|r| r.ok())
+                .collect();
+            rows
+        };
+
+        let entries: Vec<WatchlistEntry> = {
+            let read_cache = self.cache.read();
+            rows.into_iter()
+                .filter_map(|(platform, market_id, added_at, archived)| {
+                    let platform = match platform.as_str() {
+                        "kalshi" => Platform::Kalshi,
+                        "polymarket" => Platform::Polymarket,
+                        _ => return None,
+                    };
+                    let cached = read_cache
+                        .get(&(platform, market_id.clone()))
+                        .map(|cached| cached.market.clone());
+                    let unavailable = cached.is_none();
+                    let market = cached.or_else(|| {
+                        archived.and_then(|data| serde_json::from_str::<PredictionMarket>(&data).ok())
+                    });
+                    Some(WatchlistEntry {
+                        platform,
+                        market_id,
+                        added_at: DateTime::from_timestamp(added_at, 0).unwrap_or_else(Utc::now),
+                        market,
+                        unavailable,
+                    })
+                })
+                .collect()
+        };
+
+        let collector = self.trade_collector.read().clone();
+        if let Some(collector) = collector {
+            for entry in entries.iter().filter(|e| !e.unavailable) {
+                collector.track_market(entry.platform, entry.market_id.clone()).await;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Track markets on fetched watchlists with this trade collector
+    pub fn set_trade_collector(&self, collector: Arc<TradeCollector>) {
+        *self.trade_collector.write() = Some(collector);
+    }
+
+    /// Time since the newest cached market (of a platform) was refreshed
+    pub fn data_age(&self, platform: Option<Platform>) -> Option<chrono::Duration> {
+        let newest = self
+            .cache
+            .read()
+            .iter()
+            .filter(|((p, _), _)| platform.is_none() || platform == Some(*p))
+            .map(|(_, cached)| cached.updated_at)
+            .max()?;
+        Some(Utc::now() - newest)
+    }
+
+    /// Get markets grouped by event, highest volume first
+    pub fn get_market_groups(&self, platform: Option<Platform>) -> Vec<MarketGroup> {
+        let mut groups = group_markets_by_event(self.get_markets(platform, PageRequest::all()).markets);
+        groups.sort_by(|a, b| b.volume.cmp(&a.volume));
+        groups
+    }
+
     /// Search markets by title
     ///
     /// Instant search over in-memory cache.
@@ -487,6 +1678,157 @@ impl MarketCache {
         results
     }
 
+    /// Full-text search over market titles and descriptions
+    ///
+    /// Ranked by bm25 with title matches weighted above description matches,
+    /// each result carrying a highlighted snippet. Every query word must match
+    /// (as a prefix), and known aliases count as the same word ("bitcoin" also
+    /// finds "BTC"). Falls back to a case-insensitive LIKE, ranked by volume,
+    /// when SQLite lacks FTS5.
+    pub fn search(
+        &self,
+        query: &str,
+        platform: Option<Platform>,
+        limit: usize,
+    ) -> Result<Vec<MarketSearchResult>, MarketCacheError> {
+        let terms = search_terms(query);
+        if terms.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let platform_str = platform.map(|p| match p {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        });
+
+        let hits: Vec<(String, String, Option<String>, f64)> = {
+            let conn = self.db.lock();
+            if has_fts_index(&conn) {
+                let sql = format!(
+                    r#"
+                    SELECT platform, market_id,
+                           snippet(markets_fts, -1, '[', ']', '...', 12),
+                           bm25(markets_fts, {})
+                    FROM markets_fts
+                    WHERE markets_fts MATCH ?1 AND (?2 IS NULL OR platform = ?2)
+                    ORDER BY bm25(markets_fts, {})
+                    LIMIT ?3
+                    "#,
+                    SEARCH_WEIGHTS, SEARCH_WEIGHTS
+                );
+                let mut stmt = conn.prepare(&sql).map_err(MarketCacheError::Database)?;
+                let rows = stmt
+                    .query_map(
+                        params![fts_match_expression(&terms), platform_str, limit as i64],
+                        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                    )
+                    .map_err(MarketCacheError::Database)?;
+                rows.filter_map(|r| r.ok()).collect()
+            } else {
+                like_search(&conn, &terms, platform_str, limit)?
+            }
+        };
+
+        let read_cache = self.cache.read();
+        let mut results: Vec<MarketSearchResult> = hits
+            .into_iter()
+            .filter_map(|(platform_str, market_id, snippet, score)| {
+                let platform = match platform_str.as_str() {
+                    "kalshi" => Platform::Kalshi,
+                    "polymarket" => Platform::Polymarket,
+                    _ => return None,
+                };
+                if is_canary_market(&market_id) {
+                    return None;
+                }
+                read_cache.get(&(platform, market_id)).map(|cached| MarketSearchResult {
+                    market: cached.market.clone(),
+                    snippet,
+                    score,
+                })
+            })
+            .collect();
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
+    /// List every category with its number of markets, most populated first
+    ///
+    /// Categories are a market's category plus its tags, normalized with
+    /// [`normalize_category`]; a market counts once under each.
+    pub fn get_categories(&self) -> Result<Vec<(String, usize)>, MarketCacheError> {
+        let conn = self.db.lock();
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT category, COUNT(*) AS markets
+                FROM market_categories
+                GROUP BY category
+                ORDER BY markets DESC, category ASC
+                "#,
+            )
+            .map_err(MarketCacheError::Database)?;
+
+        let categories = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))
+            .map_err(MarketCacheError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(categories)
+    }
+
+    /// Markets filed under a category (case-insensitive), highest volume first
+    pub fn get_markets_by_category(
+        &self,
+        category: &str,
+        platform: Option<Platform>,
+        limit: Option<usize>,
+    ) -> Result<Vec<PredictionMarket>, MarketCacheError> {
+        let Some(category) = normalize_category(category) else {
+            return Ok(Vec::new());
+        };
+
+        let ids: Vec<(String, String)> = {
+            let conn = self.db.lock();
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT platform, market_id FROM market_categories
+                    WHERE category = ?1 AND (?2 IS NULL OR platform = ?2)
+                    "#,
+                )
+                .map_err(MarketCacheError::Database)?;
+            let rows = stmt
+                .query_map(params![category, platform.map(platform_key)], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .map_err(MarketCacheError::Database)?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        let read_cache = self.cache.read();
+        let mut markets: Vec<PredictionMarket> = ids
+            .into_iter()
+            .filter(|(_, market_id)| !is_canary_market(market_id))
+            .filter_map(|(platform_str, market_id)| {
+                let platform = match platform_str.as_str() {
+                    "kalshi" => Platform::Kalshi,
+                    "polymarket" => Platform::Polymarket,
+                    _ => return None,
+                };
+                read_cache.get(&(platform, market_id)).map(|c| c.market.clone())
+            })
+            .collect();
+        markets.sort_by(|a, b| b.volume.cmp(&a.volume));
+
+        if let Some(l) = limit {
+            markets.truncate(l);
+        }
+
+        Ok(markets)
+    }
+
     /// Get filtered markets with caching (30s TTL)
     ///
     /// This uses Polymarket's API-level filtering for accurate results,
@@ -500,11 +1842,11 @@ impl MarketCache {
     ) -> Result<Vec<PredictionMarket>, TerminalError> {
         // For "All", just use the existing cache (sorted by volume)
         if filter == MarketFilter::All {
-            let mut markets = self.get_markets(Some(Platform::Polymarket));
-            if let Some(l) = limit {
-                markets.truncate(l);
-            }
-            return Ok(markets);
+            let page = match limit {
+                Some(l) => PageRequest::first(l),
+                None => PageRequest::all(),
+            };
+            return Ok(self.get_markets(Some(Platform::Polymarket), page).markets);
         }
 
         // Check filter cache first
@@ -568,10 +1910,12 @@ impl MarketCache {
             let read_cache = self.cache.read();
             if let Some(cached) = read_cache.get(&(platform, market_id.to_string())) {
                 if cached.is_fresh() {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
                     return Ok(cached.market.clone());
                 }
             }
         }
+        self.misses.fetch_add(1, Ordering::Relaxed);
 
         // Cache miss or stale - fetch from API
         let market = self.service.get_market(platform, market_id).await?;
@@ -585,27 +1929,216 @@ impl MarketCache {
         Ok(market)
     }
 
-    /// Force refresh all markets (blocking)
+    /// Get a single market from the cache only, fresh or stale
+    ///
+    /// Never calls the platform APIs or queues a refresh, so ids from
+    /// untrusted callers can't turn into upstream requests.
+    pub fn get_cached_market(&self, platform: Platform, market_id: &str) -> Option<PredictionMarket> {
+        self.cache
+            .read()
+            .get(&(platform, market_id.to_string()))
+            .map(|cached| cached.market.clone())
+    }
+
+    /// Look up many markets at once, in request order
+    ///
+    /// Misses come back as `None` and are queued for a background refresh, so
+    /// a later lookup can find them. Cached entries are returned even when
+    /// stale.
+    pub fn get_markets_by_ids(&self, ids: &[(Platform, String)]) -> Vec<Option<PredictionMarket>> {
+        let markets: Vec<Option<PredictionMarket>> = {
+            let read_cache = self.cache.read();
+            ids.iter()
+                .map(|key| read_cache.get(key).map(|cached| cached.market.clone()))
+                .collect()
+        };
+
+        for ((platform, market_id), market) in ids.iter().zip(&markets) {
+            if market.is_none() {
+                let _ = self.refresh_tx.try_send(RefreshRequest::Single {
+                    platform: *platform,
+                    market_id: market_id.clone(),
+                });
+            }
+        }
+
+        markets
+    }
+
+    /// Refresh all markets (blocking)
+    ///
+    /// Platforms backing off after failures are skipped; the rest still
+    /// refresh. Returns the last failure, if any.
     pub async fn refresh_all(&self) -> Result<(), MarketCacheError> {
         // KALSHI_DISABLED: Only refresh Polymarket while focusing on it
-        for platform in [Platform::Polymarket] {
-            Self::refresh_platform(&self.cache, &self.db, &self.service, platform).await?;
+        self.refresh_platforms(&[Platform::Polymarket]).await
+    }
+
+    async fn refresh_platforms(&self, platforms: &[Platform]) -> Result<(), MarketCacheError> {
+        let mut result = Ok(());
+        for &platform in platforms {
+            match self.refresh_platform_now(platform).await {
+                Ok(()) => {}
+                Err(e @ MarketCacheError::BackingOff { .. }) => debug!("Skipping refresh: {}", e),
+                Err(e) => result = Err(e),
+            }
         }
-        Ok(())
+        self.run_matching_pass();
+        result
+    }
+
+    /// Re-fetch one market now and return its fresh state
+    ///
+    /// Safe to call while a full refresh is running; whichever write lands
+    /// last wins.
+    pub async fn refresh_market(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<PredictionMarket, MarketCacheError> {
+        Self::refresh_single(&self.cache, &self.db, &self.source, &self.trade_storage, platform, market_id).await
     }
 
-    /// Force refresh a platform (blocking)
+    /// Refresh a platform now (blocking), unless it's backing off after failures
     pub async fn refresh_platform_now(&self, platform: Platform) -> Result<(), MarketCacheError> {
-        Self::refresh_platform(&self.cache, &self.db, &self.service, platform).await
+        self.refresh_and_archive(platform, false).await
     }
 
-    /// Queue a background refresh
-    pub fn queue_refresh(&self, request: RefreshRequest) {
-        let _ = self.refresh_tx.try_send(request);
+    /// Refresh a platform now (blocking), ignoring any failure backoff
+    pub async fn force_refresh_platform(&self, platform: Platform) -> Result<(), MarketCacheError> {
+        self.refresh_and_archive(platform, true).await
     }
 
-    /// Get cache statistics
+    async fn refresh_and_archive(&self, platform: Platform, force: bool) -> Result<(), MarketCacheError> {
+        Self::refresh_platform(
+            &self.cache,
+            &self.db,
+            &self.source,
+            &self.trade_storage,
+            &self.refresh_status,
+            &self.events,
+            platform,
+            force,
+        )
+        .await?;
+
+        match self.archive_stale_by_platform(Utc::now()) {
+            Ok(archived) => {
+                let removed = archived.get(&platform).copied().unwrap_or(0);
+                if removed > 0 {
+                    info!("Archived {} closed {:?} markets", removed, platform);
+                }
+                if let Some(status) = self.refresh_status.write().get_mut(&platform) {
+                    status.last_removed = removed;
+                }
+            }
+            Err(e) => warn!("Failed to archive closed markets: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Spawn one refresh loop per enabled platform
+    ///
+    /// Each platform refreshes immediately, then every interval plus up to
+    /// `jitter` of it, independently of the others. Cycles are skipped while
+    /// [`pause_refresh`](Self::pause_refresh) is in effect.
+    pub fn start_refresh_loop(&self, config: RefreshLoopConfig) -> Vec<JoinHandle<()>> {
+        [
+            (Platform::Polymarket, config.polymarket_interval),
+            (Platform::Kalshi, config.kalshi_interval),
+        ]
+        .into_iter()
+        .filter_map(|(platform, interval)| interval.map(|interval| (platform, interval)))
+        .map(|(platform, interval)| {
+            info!("Refreshing {:?} markets every {}s", platform, interval.as_secs());
+            let market_cache = self.clone();
+            let jitter = config.jitter;
+            tokio::spawn(async move {
+                loop {
+                    if market_cache.is_refresh_paused() {
+                        debug!("Skipping {:?} refresh while paused", platform);
+                    } else {
+                        match market_cache.refresh_platform_now(platform).await {
+                            Ok(()) => market_cache.run_matching_pass(),
+                            Err(e @ MarketCacheError::BackingOff { .. }) => debug!("{}", e),
+                            Err(e) => warn!("Scheduled {:?} refresh failed: {}", platform, e),
+                        }
+                    }
+
+                    // Add jitter to prevent thundering herd
+                    let jitter_ms = (interval.as_millis() as f64 * rand::random::<f64>() * jitter) as u64;
+                    tokio::time::sleep(interval + Duration::from_millis(jitter_ms)).await;
+                }
+            })
+        })
+        .collect()
+    }
+
+    /// Skip scheduled refreshes until [`resume_refresh`](Self::resume_refresh)
+    pub fn pause_refresh(&self) {
+        self.refresh_paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Resume scheduled refreshes
+    pub fn resume_refresh(&self) {
+        self.refresh_paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether scheduled refreshes are paused
+    pub fn is_refresh_paused(&self) -> bool {
+        self.refresh_paused.load(Ordering::Relaxed)
+    }
+
+    /// Queue a priority background refresh
+    ///
+    /// Duplicate requests within a few seconds of each other are coalesced.
+    /// Dropped (with a warning) if the queue is full.
+    pub fn request_refresh(&self, request: RefreshRequest) {
+        if let Err(e) = self.refresh_tx.try_send(request) {
+            warn!("Failed to queue market refresh: {}", e);
+        }
+    }
+
+    /// Sender for queueing refreshes from other services
+    pub fn refresh_sender(&self) -> mpsc::Sender<RefreshRequest> {
+        self.refresh_tx.clone()
+    }
+
+    /// Latest refresh outcome and backoff state for a platform
+    pub fn refresh_status(&self, platform: Platform) -> PlatformRefreshStatus {
+        self.refresh_status.read().get(&platform).cloned().unwrap_or_default()
+    }
+
+    /// Receive markets added, updated or removed by refreshes and archiving
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MarketCacheEvent> {
+        self.events.subscribe()
+    }
+
+    /// Broadcast refreshed single markets through this WebSocket state
+    pub fn set_websocket_state(&self, ws_state: Arc<WebSocketState>) {
+        *self.ws_state.write() = Some(ws_state);
+    }
+
+    /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
+        // Read the DB before taking the cache lock (db is locked first elsewhere)
+        let (archived_count, db_bytes) = {
+            let conn = self.db.lock();
+            let archived_count = conn
+                .query_row("SELECT COUNT(*) FROM archived_markets", [], |row| row.get::<_, i64>(0))
+                .map(|n| n as usize)
+                .unwrap_or(0);
+            let db_bytes = conn
+                .query_row(
+                    "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|n| n as u64)
+                .unwrap_or(0);
+            (archived_count, db_bytes)
+        };
+
         let read_cache = self.cache.read();
 
         let total = read_cache.len();
@@ -625,6 +2158,14 @@ impl MarketCache {
             .values()
             .map(|c| c.updated_at)
             .min();
+        let newest = read_cache.values().map(|c| c.updated_at).max();
+        let warmed_from_disk = read_cache.values().filter(|c| c.stale_at.is_some()).count();
+        let memory_bytes = read_cache
+            .iter()
+            .map(|((_, id), cached)| id.len() + cached.estimated_size())
+            .sum();
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
 
         CacheStats {
             total,
@@ -633,7 +2174,311 @@ impl MarketCache {
             kalshi_count,
             polymarket_count: poly_count,
             oldest_entry: oldest,
+            newest_entry: newest,
+            data_age_secs: newest.map(|n| (Utc::now() - n).num_seconds()),
+            warmed_from_disk,
+            kalshi_refresh: self.refresh_status(Platform::Kalshi),
+            polymarket_refresh: self.refresh_status(Platform::Polymarket),
+            archived_count,
+            hits,
+            misses,
+            hit_rate: (hits + misses > 0).then(|| hits as f64 / (hits + misses) as f64),
+            memory_bytes,
+            db_bytes,
+        }
+    }
+
+    /// Archive closed/settled markets this many days after they close
+    pub fn set_archive_after_days(&self, days: i64) {
+        self.archive_after_days.store(days, Ordering::Relaxed);
+    }
+
+    /// Move closed/settled markets past the archive age out of the hot cache
+    ///
+    /// A market's age is taken from its close time, or its last update when it
+    /// has none. Archived markets leave the memory map and the markets table
+    /// (with their search and category rows) for `archived_markets`. Returns
+    /// how many were archived.
+    pub fn archive_stale_markets(&self, now: DateTime<Utc>) -> Result<usize, MarketCacheError> {
+        Ok(self.archive_stale_by_platform(now)?.values().sum())
+    }
+
+    /// [`archive_stale_markets`](Self::archive_stale_markets), counted per platform
+    fn archive_stale_by_platform(&self, now: DateTime<Utc>) -> Result<HashMap<Platform, usize>, MarketCacheError> {
+        let cutoff = now - chrono::Duration::days(self.archive_after_days.load(Ordering::Relaxed));
+        let stale: Vec<(Platform, PredictionMarket, DateTime<Utc>)> = self
+            .cache
+            .read()
+            .iter()
+            .filter_map(|((platform, _), cached)| {
+                let closed_at = cached.market.close_time.unwrap_or(cached.updated_at);
+                let closed = matches!(cached.market.status, MarketStatus::Closed | MarketStatus::Settled);
+                (closed && closed_at < cutoff).then(|| (*platform, cached.market.clone(), closed_at))
+            })
+            .collect();
+        if stale.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        {
+            let mut conn = self.db.lock();
+            let fts = has_fts_index(&conn);
+            let tx = conn.transaction().map_err(MarketCacheError::Database)?;
+            for (platform, market, closed_at) in &stale {
+                let platform_str = platform_key(*platform);
+                let data_json =
+                    serde_json::to_string(market).map_err(|e| MarketCacheError::Serialization(e.to_string()))?;
+                tx.execute(
+                    r#"
+                    INSERT OR REPLACE INTO archived_markets (platform, market_id, title, data, closed_at, archived_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                    "#,
+                    params![
+                        platform_str,
+                        market.id,
+                        market.title,
+                        data_json,
+                        closed_at.timestamp(),
+                        now.timestamp()
+                    ],
+                )
+                .map_err(MarketCacheError::Database)?;
+                for table in ["markets", "market_categories"] {
+                    tx.execute(
+                        &format!("DELETE FROM {} WHERE platform = ?1 AND market_id = ?2", table),
+                        params![platform_str, market.id],
+                    )
+                    .map_err(MarketCacheError::Database)?;
+                }
+                if fts {
+                    tx.execute(
+                        "DELETE FROM markets_fts WHERE platform = ?1 AND market_id = ?2",
+                        params![platform_str, market.id],
+                    )
+                    .map_err(MarketCacheError::Database)?;
+                }
+            }
+            tx.commit().map_err(MarketCacheError::Database)?;
+        }
+
+        {
+            let mut write_cache = self.cache.write();
+            for (platform, market, _) in &stale {
+                write_cache.remove(&(*platform, market.id.clone()));
+            }
         }
+        let mut archived: HashMap<Platform, usize> = HashMap::new();
+        for (platform, market, _) in &stale {
+            *archived.entry(*platform).or_default() += 1;
+            let _ = self.events.send(MarketCacheEvent::Removed {
+                platform: *platform,
+                market_id: market.id.clone(),
+            });
+        }
+
+        Ok(archived)
+    }
+
+    /// Archived markets, most recently closed first
+    pub fn get_archived_markets(
+        &self,
+        platform: Option<Platform>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<PredictionMarket>, MarketCacheError> {
+        let conn = self.db.lock();
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT data FROM archived_markets
+                WHERE ?1 IS NULL OR platform = ?1
+                ORDER BY closed_at DESC, market_id
+                LIMIT ?2 OFFSET ?3
+                "#,
+            )
+            .map_err(MarketCacheError::Database)?;
+
+        let markets = stmt
+            .query_map(params![platform.map(platform_key), limit as i64, offset as i64], |row| {
+                row.get::<_, String>(0)
+            })
+            .map_err(MarketCacheError::Database)?
+            .filter_map(|r| r.ok())
+            .filter_map(|data| serde_json::from_str::<PredictionMarket>(&data).ok())
+            .collect();
+
+        Ok(markets)
+    }
+
+    /// Use stored market embeddings when matching markets across platforms
+    pub fn set_embedding_store(&self, store: Arc<EmbeddingStore>) {
+        *self.embedding_store.write() = Some(store);
+    }
+
+    /// Re-match markets across platforms, logging the outcome
+    fn run_matching_pass(&self) {
+        match self.rebuild_unified_matches() {
+            Ok(count) => debug!("Matched {} markets across platforms", count),
+            Err(e) => warn!("Failed to match markets across platforms: {}", e),
+        }
+    }
+
+    /// Pair cached Kalshi and Polymarket markets describing the same event
+    ///
+    /// Automatic matches are replaced wholesale; manual links and rejections
+    /// are kept, and markets in a manual link are not matched again. Returns
+    /// the number of automatic matches.
+    pub fn rebuild_unified_matches(&self) -> Result<usize, MarketCacheError> {
+        let (kalshi, polymarket): (Vec<PredictionMarket>, Vec<PredictionMarket>) = {
+            let read_cache = self.cache.read();
+            read_cache
+                .iter()
+                .filter(|((_, id), _)| !is_canary_market(id))
+                .map(|(_, cached)| cached.market.clone())
+                .partition(|m| m.platform == Platform::Kalshi)
+        };
+
+        let embeddings: HashMap<String, Vec<f32>> = match self.embedding_store.read().clone() {
+            Some(store) => match store.load_all_market_embeddings() {
+                Ok(rows) => rows.into_iter().map(|(id, _, embedding)| (id, embedding)).collect(),
+                Err(e) => {
+                    warn!("Matching without embeddings: {}", e);
+                    HashMap::new()
+                }
+            },
+            None => HashMap::new(),
+        };
+
+        let mut conn = self.db.lock();
+        let tx = conn.transaction().map_err(MarketCacheError::Database)?;
+
+        let overrides: Vec<(String, String, String)> = {
+            let mut stmt = tx
+                .prepare("SELECT kalshi_id, polymarket_id, status FROM unified_matches WHERE status != 'auto'")
+                .map_err(MarketCacheError::Database)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(MarketCacheError::Database)?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+        let excluded: HashSet<(String, String)> =
+            overrides.iter().map(|(k, p, _)| (k.clone(), p.clone())).collect();
+        let linked_kalshi: HashSet<&str> = overrides
+            .iter()
+            .filter(|(_, _, status)| status == "linked")
+            .map(|(k, _, _)| k.as_str())
+            .collect();
+        let linked_poly: HashSet<&str> = overrides
+            .iter()
+            .filter(|(_, _, status)| status == "linked")
+            .map(|(_, p, _)| p.as_str())
+            .collect();
+
+        let kalshi: Vec<PredictionMarket> =
+            kalshi.into_iter().filter(|m| !linked_kalshi.contains(m.id.as_str())).collect();
+        let polymarket: Vec<PredictionMarket> =
+            polymarket.into_iter().filter(|m| !linked_poly.contains(m.id.as_str())).collect();
+        let matches = find_matches(&kalshi, &polymarket, &embeddings, &excluded, MIN_MATCH_CONFIDENCE);
+
+        tx.execute("DELETE FROM unified_matches WHERE status = 'auto'", [])
+            .map_err(MarketCacheError::Database)?;
+        let now = Utc::now().timestamp();
+        for m in &matches {
+            tx.execute(
+                r#"
+                INSERT INTO unified_matches (kalshi_id, polymarket_id, confidence, status, updated_at)
+                VALUES (?1, ?2, ?3, 'auto', ?4)
+                "#,
+                params![m.kalshi_id, m.polymarket_id, m.confidence, now],
+            )
+            .map_err(MarketCacheError::Database)?;
+        }
+        tx.commit().map_err(MarketCacheError::Database)?;
+
+        Ok(matches.len())
+    }
+
+    /// Manually link (or, with `linked = false`, reject) a Kalshi/Polymarket pair
+    ///
+    /// Linking replaces any automatic match involving either market.
+    pub fn link_markets(&self, kalshi_id: &str, polymarket_id: &str, linked: bool) -> Result<(), MarketCacheError> {
+        let mut conn = self.db.lock();
+        let tx = conn.transaction().map_err(MarketCacheError::Database)?;
+        if linked {
+            tx.execute(
+                "DELETE FROM unified_matches WHERE status = 'auto' AND (kalshi_id = ?1 OR polymarket_id = ?2)",
+                params![kalshi_id, polymarket_id],
+            )
+            .map_err(MarketCacheError::Database)?;
+        }
+        tx.execute(
+            r#"
+            INSERT OR REPLACE INTO unified_matches (kalshi_id, polymarket_id, confidence, status, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![
+                kalshi_id,
+                polymarket_id,
+                if linked { 1.0 } else { 0.0 },
+                if linked { "linked" } else { "rejected" },
+                Utc::now().timestamp(),
+            ],
+        )
+        .map_err(MarketCacheError::Database)?;
+        tx.commit().map_err(MarketCacheError::Database)
+    }
+
+    /// Matched cross-platform markets, most confident first
+    ///
+    /// Pairs whose markets are no longer cached are skipped.
+    pub fn get_unified_markets(&self) -> Result<Vec<UnifiedMatch>, MarketCacheError> {
+        let rows: Vec<(String, String, f64, String)> = {
+            let conn = self.db.lock();
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT kalshi_id, polymarket_id, confidence, status FROM unified_matches
+                    WHERE status != 'rejected'
+                    ORDER BY confidence DESC, kalshi_id, polymarket_id
+                    "#,
+                )
+                .map_err(MarketCacheError::Database)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+                .map_err(MarketCacheError::Database)?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        let read_cache = self.cache.read();
+        let unified = rows
+            .into_iter()
+            .filter_map(|(kalshi_id, polymarket_id, confidence, status)| {
+                let kalshi = read_cache.get(&(Platform::Kalshi, kalshi_id.clone()))?.market.clone();
+                let polymarket = read_cache.get(&(Platform::Polymarket, polymarket_id.clone()))?.market.clone();
+                let title = polymarket.title.clone();
+                Some(UnifiedMatch {
+                    market: UnifiedMarket::matched(unified_id(&kalshi_id, &polymarket_id), title, kalshi, polymarket),
+                    confidence,
+                    manual: status == "linked",
+                })
+            })
+            .collect();
+
+        Ok(unified)
+    }
+
+    /// Look up a unified market by its unified id or either platform's market id
+    pub fn get_unified_market(&self, id: &str) -> Result<Option<UnifiedMatch>, MarketCacheError> {
+        Ok(self.get_unified_markets()?.into_iter().find(|u| {
+            u.market.id == id
+                || u.market.kalshi.as_ref().is_some_and(|m| m.id == id)
+                || u.market.polymarket.as_ref().is_some_and(|m| m.id == id)
+        }))
+    }
+
+    /// Record resolutions in this storage when markets are observed settling
+    pub fn set_trade_storage(&self, storage: Arc<TradeStorage>) {
+        *self.trade_storage.write() = Some(storage);
     }
 
     /// Get underlying market service (for non-cached operations)
@@ -642,8 +2487,367 @@ impl MarketCache {
     }
 }
 
-/// Cache statistics
+/// Build a resolution record from a settled market
+fn resolution_for(market: &PredictionMarket, resolved_at: DateTime<Utc>) -> MarketResolution {
+    let winning_outcome = if market.is_multi_outcome {
+        market.leading_outcome.clone()
+    } else if market.yes_price > market.no_price {
+        Some("yes".to_string())
+    } else {
+        Some("no".to_string())
+    };
+
+    MarketResolution {
+        platform: market.platform,
+        market_id: market.id.clone(),
+        resolved_at,
+        winning_outcome,
+        final_price: market.yes_price.to_f64(),
+    }
+}
+
+/// Which slice of the market listing [`MarketCache::get_markets`] returns
+#[derive(Debug, Clone, Default)]
+pub struct PageRequest {
+    /// Continue after this position (from a previous page's `next_cursor`)
+    pub cursor: Option<MarketCursor>,
+    /// Page size (default [`DEFAULT_MARKET_PAGE_SIZE`])
+    pub limit: Option<usize>,
+    /// Return every remaining market, ignoring `limit`
+    pub unlimited: bool,
+}
+
+impl PageRequest {
+    /// The first `limit` markets
+    pub fn first(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    /// Every market, unpaginated
+    pub fn all() -> Self {
+        Self {
+            unlimited: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// One page of [`MarketCache::get_markets`]
 #[derive(Debug, Clone)]
+pub struct MarketPage {
+    pub markets: Vec<PredictionMarket>,
+    /// Markets matching the platform filter across all pages
+    pub total_count: usize,
+    /// Cursor for the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Markets belonging to one event (Polymarket lists each outcome separately)
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketGroup {
+    /// Event id, or the market id for a market outside any event
+    pub group_id: String,
+    pub platform: Platform,
+    pub title: String,
+    /// Sum of the child markets' volume
+    pub volume: Decimal,
+    /// Child outcome markets, highest volume first
+    pub markets: Vec<PredictionMarket>,
+}
+
+/// Collapse markets sharing an event into one group per event
+///
+/// Groups keep the order of their first market in `markets`. When a group
+/// holds both the event-level entry (id equal to the event id) and its outcome
+/// markets, the event entry only supplies the title so volume isn't counted
+/// twice.
+pub fn group_markets_by_event(markets: Vec<PredictionMarket>) -> Vec<MarketGroup> {
+    let mut order: Vec<(Platform, String)> = Vec::new();
+    let mut members: HashMap<(Platform, String), Vec<PredictionMarket>> = HashMap::new();
+    for market in markets {
+        let key = (market.platform, market.event_id.clone().unwrap_or_else(|| market.id.clone()));
+        let group = members.entry(key.clone()).or_default();
+        if group.is_empty() {
+            order.push(key);
+        }
+        group.push(market);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|key| {
+            let mut children = members.remove(&key)?;
+            let (platform, group_id) = key;
+            let event_entry = children.iter().position(|m| m.id == group_id);
+            let title = match event_entry {
+                Some(i) if children.len() > 1 => children.remove(i).title,
+                Some(i) => children[i].title.clone(),
+                // Outcome titles read "Event Title → Option"
+                None => children[0]
+                    .title
+                    .split(" → ")
+                    .next()
+                    .unwrap_or(&children[0].title)
+                    .to_string(),
+            };
+            children.sort_by(|a, b| b.volume.cmp(&a.volume));
+            Some(MarketGroup {
+                group_id,
+                platform,
+                title,
+                volume: children.iter().map(|m| m.volume).sum(),
+                markets: children,
+            })
+        })
+        .collect()
+}
+
+/// Position in the market listing: a market's (pinned, volume, platform, id)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketCursor {
+    pinned: bool,
+    volume: Decimal,
+    platform: String,
+    market_id: String,
+}
+
+impl MarketCursor {
+    fn of(market: &PredictionMarket, pinned: bool) -> Self {
+        Self {
+            pinned,
+            volume: market.volume,
+            platform: platform_key(market.platform).to_string(),
+            market_id: market.id.clone(),
+        }
+    }
+
+    /// Opaque (hex) form handed to clients
+    pub fn encode(&self) -> String {
+        let pinned = if self.pinned { "1" } else { "0" };
+        format!("{}\u{1f}{}\u{1f}{}\u{1f}{}", pinned, self.volume, self.platform, self.market_id)
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Parse a cursor produced by [`encode`](Self::encode)
+    pub fn parse(cursor: &str) -> Option<Self> {
+        if cursor.len() % 2 != 0 {
+            return None;
+        }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let decoded = String::from_utf8(bytes).ok()?;
+        let mut parts = decoded.splitn(4, '\u{1f}');
+        let pinned = match parts.next()? {
+            "1" => true,
+            "0" => false,
+            _ => return None,
+        };
+        Some(Self {
+            pinned,
+            volume: parts.next()?.parse().ok()?,
+            platform: parts.next()?.to_string(),
+            market_id: parts.next()?.to_string(),
+        })
+    }
+}
+
+/// Listing order: pinned first, volume descending, then platform and id ascending
+fn listing_order(a: &MarketCursor, b: &MarketCursor) -> std::cmp::Ordering {
+    b.pinned
+        .cmp(&a.pinned)
+        .then_with(|| b.volume.cmp(&a.volume))
+        .then_with(|| (&a.platform, &a.market_id).cmp(&(&b.platform, &b.market_id)))
+}
+
+/// Id of the unified market pairing two platform markets
+pub fn unified_id(kalshi_id: &str, polymarket_id: &str) -> String {
+    format!("{}+{}", kalshi_id, polymarket_id)
+}
+
+/// A cross-platform pair from [`MarketCache::get_unified_markets`]
+#[derive(Debug, Clone, Serialize)]
+pub struct UnifiedMatch {
+    #[serde(flatten)]
+    pub market: UnifiedMarket,
+    /// Match confidence (0.0 - 1.0; 1.0 for manual links)
+    pub confidence: f64,
+    /// Whether the pair was linked by hand
+    pub manual: bool,
+}
+
+/// A market matched by [`MarketCache::search`]
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketSearchResult {
+    #[serde(flatten)]
+    pub market: PredictionMarket,
+    /// Matching excerpt with hits wrapped in `[` `]` (FTS only)
+    pub snippet: Option<String>,
+    /// bm25 rank; lower is a better match (0 for LIKE fallback results)
+    pub score: f64,
+}
+
+/// Normalize a category or tag: trimmed and lowercased; `None` when blank
+pub fn normalize_category(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_lowercase())
+}
+
+/// A market's normalized, deduplicated categories (its category plus tags)
+pub fn market_categories(market: &PredictionMarket) -> Vec<String> {
+    let mut categories: Vec<String> = market
+        .category
+        .iter()
+        .chain(market.tags.iter())
+        .filter_map(|c| normalize_category(c))
+        .collect();
+    categories.sort();
+    categories.dedup();
+    categories
+}
+
+/// Storage key for a platform
+fn platform_key(platform: Platform) -> &'static str {
+    match platform {
+        Platform::Kalshi => "kalshi",
+        Platform::Polymarket => "polymarket",
+    }
+}
+
+/// Replace a market's rows in the category index
+fn index_categories(conn: &Connection, platform_str: &str, market: &PredictionMarket) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM market_categories WHERE platform = ?1 AND market_id = ?2",
+        params![platform_str, market.id],
+    )?;
+    for category in market_categories(market) {
+        conn.execute(
+            "INSERT OR IGNORE INTO market_categories (platform, market_id, category) VALUES (?1, ?2, ?3)",
+            params![platform_str, market.id, category],
+        )?;
+    }
+    Ok(())
+}
+
+/// Lowercased alphanumeric words of a search query
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// A term plus its known aliases
+fn with_synonyms(term: &str) -> Vec<String> {
+    let mut variants = vec![term.to_string()];
+    for (a, b) in SEARCH_SYNONYMS {
+        if term == *a {
+            variants.push(b.to_string());
+        } else if term == *b {
+            variants.push(a.to_string());
+        }
+    }
+    variants
+}
+
+/// FTS5 MATCH expression: every term as a quoted prefix, or one of its aliases
+fn fts_match_expression(terms: &[String]) -> String {
+    terms
+        .iter()
+        .map(|term| {
+            let mut variants = vec![format!("\"{}\"*", term)];
+            // Aliases match whole words only ("sol" shouldn't find "solar")
+            variants.extend(with_synonyms(term).iter().skip(1).map(|v| format!("\"{}\"", v)));
+            format!("({})", variants.join(" OR "))
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Whether the FTS5 index was created
+fn has_fts_index(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'markets_fts'",
+        [],
+        |_| Ok(()),
+    )
+    .is_ok()
+}
+
+/// Replace a market's row in the FTS5 index
+fn index_market(conn: &Connection, platform_str: &str, market: &PredictionMarket) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM markets_fts WHERE platform = ?1 AND market_id = ?2",
+        params![platform_str, market.id],
+    )?;
+    conn.execute(
+        "INSERT INTO markets_fts (platform, market_id, title, description) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            platform_str,
+            market.id,
+            market.title,
+            market.description.as_deref().unwrap_or("")
+        ],
+    )?;
+    Ok(())
+}
+
+/// Case-insensitive LIKE search used when FTS5 is unavailable, highest volume first
+fn like_search(
+    conn: &Connection,
+    terms: &[String],
+    platform_str: Option<&str>,
+    limit: usize,
+) -> Result<Vec<(String, String, Option<String>, f64)>, MarketCacheError> {
+    // ?1 is the platform; every term needs one of its variants in the title or description
+    let mut patterns: Vec<String> = Vec::new();
+    let mut clauses = Vec::new();
+    for term in terms {
+        let mut variants = Vec::new();
+        for variant in with_synonyms(term) {
+            patterns.push(format!("%{}%", variant));
+            let n = patterns.len() + 1;
+            variants.push(format!(
+                "title LIKE ?{n} OR json_extract(data, '$.description') LIKE ?{n}"
+            ));
+        }
+        clauses.push(format!("({})", variants.join(" OR ")));
+    }
+
+    let sql = format!(
+        r#"
+        SELECT platform, market_id
+        FROM markets
+        WHERE (?1 IS NULL OR platform = ?1) AND {}
+        ORDER BY CAST(COALESCE(json_extract(data, '$.volume'), 0) AS REAL) DESC
+        LIMIT {}
+        "#,
+        clauses.join(" AND "),
+        limit
+    );
+
+    let mut values: Vec<Option<String>> = vec![platform_str.map(str::to_string)];
+    values.extend(patterns.into_iter().map(Some));
+
+    let mut stmt = conn.prepare(&sql).map_err(MarketCacheError::Database)?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(values), |row| {
+            Ok((row.get(0)?, row.get(1)?, None, 0.0))
+        })
+        .map_err(MarketCacheError::Database)?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Cache statistics
+#[derive(Debug, Clone, Serialize)]
 pub struct CacheStats {
     pub total: usize,
     pub fresh: usize,
@@ -651,6 +2855,25 @@ pub struct CacheStats {
     pub kalshi_count: usize,
     pub polymarket_count: usize,
     pub oldest_entry: Option<DateTime<Utc>>,
+    pub newest_entry: Option<DateTime<Utc>>,
+    /// Seconds since the newest entry was refreshed
+    pub data_age_secs: Option<i64>,
+    /// Entries still served from the startup warm-up (no refresh yet)
+    pub warmed_from_disk: usize,
+    pub kalshi_refresh: PlatformRefreshStatus,
+    pub polymarket_refresh: PlatformRefreshStatus,
+    /// Markets moved to the archive table
+    pub archived_count: usize,
+    /// `get_market`/`get_markets` lookups served fresh since startup
+    pub hits: u64,
+    /// Lookups that hit a missing or stale entry since startup
+    pub misses: u64,
+    /// `hits / (hits + misses)`, absent before the first lookup
+    pub hit_rate: Option<f64>,
+    /// Estimated size of the in-memory market map
+    pub memory_bytes: usize,
+    /// Size of the cache database (pages in use times page size)
+    pub db_bytes: u64,
 }
 
 /// Errors from market cache operations
@@ -667,6 +2890,12 @@ pub enum MarketCacheError {
 
     #[error("IO error: {0}")]
     Io(String),
+
+    #[error("{platform:?} refresh backing off until {until}")]
+    BackingOff {
+        platform: Platform,
+        until: DateTime<Utc>,
+    },
 }
 
 impl Clone for MarketCache {
@@ -676,7 +2905,19 @@ impl Clone for MarketCache {
             filter_cache: Arc::clone(&self.filter_cache),
             db: Arc::clone(&self.db),
             service: Arc::clone(&self.service),
+            source: Arc::clone(&self.source),
             refresh_tx: self.refresh_tx.clone(),
+            trade_storage: Arc::clone(&self.trade_storage),
+            refresh_status: Arc::clone(&self.refresh_status),
+            refresh_paused: Arc::clone(&self.refresh_paused),
+            embedding_store: Arc::clone(&self.embedding_store),
+            archive_after_days: Arc::clone(&self.archive_after_days),
+            ws_state: Arc::clone(&self.ws_state),
+            overrides: Arc::clone(&self.overrides),
+            events: self.events.clone(),
+            hits: Arc::clone(&self.hits),
+            misses: Arc::clone(&self.misses),
+            trade_collector: Arc::clone(&self.trade_collector),
         }
     }
 }
@@ -684,6 +2925,1173 @@ impl Clone for MarketCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal::Decimal;
+    use terminal_kalshi::KalshiClient;
+    use terminal_polymarket::PolymarketClient;
+
+    fn test_market(id: &str, status: MarketStatus, yes_price: Decimal) -> PredictionMarket {
+        PredictionMarket {
+            id: id.to_string(),
+            platform: Platform::Polymarket,
+            ticker: None,
+            title: "Will it rain tomorrow?".to_string(),
+            description: None,
+            category: None,
+            yes_price,
+            no_price: Decimal::ONE - yes_price,
+            volume: Decimal::from(1000),
+            volume_24hr: None,
+            liquidity: None,
+            open_interest: None,
+            close_time: None,
+            created_at: None,
+            status,
+            image_url: None,
+            url: None,
+            outcome_count: None,
+            leading_outcome: None,
+            is_multi_outcome: false,
+            event_id: None,
+            options_json: None,
+            resolution_source: None,
+            tags: vec![],
+            is_sports: false,
+            is_live: false,
+            score: None,
+            game_period: None,
+            home_team: None,
+            away_team: None,
+            home_odds: None,
+            away_odds: None,
+            spread_line: None,
+            total_line: None,
+            change_1h: None,
+            change_24h: None,
+        }
+    }
+
+    #[test]
+    fn test_settling_market_records_resolution() {
+        let cache = Arc::new(RwLock::new(HashMap::new()));
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let slot: StorageSlot = Arc::new(RwLock::new(Some(Arc::clone(&storage))));
+        let platform = Platform::Polymarket;
+
+        // First sighting of an already-settled market is not a transition
+        let settled_unseen = test_market("unseen", MarketStatus::Settled, Decimal::ONE);
+        MarketCache::update_memory_cache(&cache, &slot, platform, &[settled_unseen], Utc::now());
+        assert!(storage.get_resolution(platform, "unseen").unwrap().is_none());
+
+        let open = test_market("m1", MarketStatus::Open, Decimal::new(97, 2));
+        MarketCache::update_memory_cache(&cache, &slot, platform, &[open], Utc::now());
+        assert!(storage.get_resolution(platform, "m1").unwrap().is_none());
+
+        let settled = test_market("m1", MarketStatus::Settled, Decimal::ONE);
+        MarketCache::update_memory_cache(&cache, &slot, platform, &[settled], Utc::now());
+
+        let resolution = storage.get_resolution(platform, "m1").unwrap().unwrap();
+        assert_eq!(resolution.winning_outcome.as_deref(), Some("yes"));
+        assert_eq!(resolution.final_price, Some(1.0));
+        assert_eq!(cache.read()[&(platform, "m1".to_string())].market.status, MarketStatus::Settled);
+    }
+
+    async fn seeded_cache() -> MarketCache {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+
+        let mut described = test_market("btc-desc", MarketStatus::Open, Decimal::new(40, 2));
+        described.title = "Will the price cross $100k by June?".to_string();
+        described.description = Some("Resolves YES if BTC trades above 100,000 USD.".to_string());
+        let rain = test_market("rain", MarketStatus::Open, Decimal::new(50, 2));
+        let mut titled = test_market("btc-title", MarketStatus::Open, Decimal::new(60, 2));
+        titled.platform = Platform::Kalshi;
+        titled.title = "Bitcoin above 100k?".to_string();
+
+        let now = Utc::now();
+        for (platform, markets) in [
+            (Platform::Polymarket, vec![described, rain]),
+            (Platform::Kalshi, vec![titled]),
+        ] {
+            MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, platform, &markets, now);
+            MarketCache::store_markets_to_db(&cache.db, platform, &markets, now).unwrap();
+        }
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_descriptions_and_aliases() {
+        let cache = seeded_cache().await;
+
+        let results = cache.search("bitcoin", None, 10).unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.market.id.as_str()).collect();
+        // Title hits rank above description-only hits
+        assert_eq!(ids, vec!["btc-title", "btc-desc"]);
+        assert!(results[1].snippet.as_deref().unwrap().contains("[BTC]"));
+
+        // Platform filtering composes with search
+        let poly = cache.search("bitcoin", Some(Platform::Polymarket), 10).unwrap();
+        assert_eq!(poly.len(), 1);
+        assert_eq!(poly[0].market.id, "btc-desc");
+
+        assert!(cache.search("rain bitcoin", None, 10).unwrap().is_empty());
+        assert!(cache.search("  ", None, 10).unwrap().is_empty());
+    }
 
-    // Tests would go here
+    #[tokio::test]
+    async fn test_like_fallback_search() {
+        let cache = seeded_cache().await;
+        let conn = cache.db.lock();
+
+        let hits = like_search(&conn, &search_terms("Bitcoin"), None, 10).unwrap();
+        let mut ids: Vec<&str> = hits.iter().map(|(_, id, _, _)| id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["btc-desc", "btc-title"]);
+
+        let hits = like_search(&conn, &search_terms("bitcoin"), Some("kalshi"), 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].1, "btc-title");
+    }
+
+    /// Market source that counts fetches and serves a fixed yes price
+    struct CountingSource {
+        fetches: std::sync::atomic::AtomicUsize,
+        yes_price: Decimal,
+    }
+
+    #[async_trait]
+    impl MarketSource for CountingSource {
+        async fn fetch_market(&self, _platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(test_market(market_id, MarketStatus::Open, self.yes_price))
+        }
+
+        async fn fetch_platform_markets(&self, _platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+            Ok(vec![test_market("m1", MarketStatus::Open, self.yes_price)])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_market_updates_cache_and_db() {
+        let source = Arc::new(CountingSource {
+            fetches: Default::default(),
+            yes_price: Decimal::new(75, 2),
+        });
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source.clone()).await.unwrap();
+
+        let stale_at = Utc::now() - chrono::Duration::hours(1);
+        let markets = vec![test_market("m1", MarketStatus::Open, Decimal::new(50, 2))];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, stale_at);
+        MarketCache::store_markets_to_db(&cache.db, Platform::Polymarket, &markets, stale_at).unwrap();
+
+        let market = cache.refresh_market(Platform::Polymarket, "m1").await.unwrap();
+        assert_eq!(market.yes_price, Decimal::new(75, 2));
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 1);
+
+        // The refreshed entry is fresh, so reads are served without another fetch
+        let cached = cache.get_market(Platform::Polymarket, "m1").await.unwrap();
+        assert_eq!(cached.yes_price, Decimal::new(75, 2));
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 1);
+        assert!(cache.stats().newest_entry.unwrap() > stale_at);
+
+        let stored_at: i64 = cache
+            .db
+            .lock()
+            .query_row(
+                "SELECT updated_at FROM markets WHERE platform = 'polymarket' AND market_id = 'm1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(stored_at > stale_at.timestamp());
+
+        // Racing a full refresh completes without deadlocking
+        let (single, all) = tokio::join!(cache.refresh_market(Platform::Polymarket, "m1"), cache.refresh_all());
+        assert!(single.is_ok() && all.is_ok());
+    }
+
+    /// Market source whose Kalshi listing always fails
+    #[derive(Default)]
+    struct FlakySource {
+        polymarket_fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl MarketSource for FlakySource {
+        async fn fetch_market(&self, _platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError> {
+            Err(TerminalError::NotFound(market_id.to_string()))
+        }
+
+        async fn fetch_platform_markets(&self, platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+            match platform {
+                Platform::Polymarket => {
+                    self.polymarket_fetches.fetch_add(1, Ordering::SeqCst);
+                    Ok(vec![test_market("m1", MarketStatus::Open, Decimal::new(50, 2))])
+                }
+                Platform::Kalshi => Err(TerminalError::NotFound("kalshi listing".to_string())),
+            }
+        }
+    }
+
+    /// Source whose platform listings fail while `failing` is set
+    #[derive(Default)]
+    struct RateLimitedSource {
+        failing: AtomicBool,
+        fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl MarketSource for RateLimitedSource {
+        async fn fetch_market(&self, _platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError> {
+            Err(TerminalError::NotFound(market_id.to_string()))
+        }
+
+        async fn fetch_platform_markets(&self, platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            if platform == Platform::Kalshi || self.failing.load(Ordering::SeqCst) {
+                return Err(TerminalError::api("429 Too Many Requests"));
+            }
+            Ok(vec![test_market("m1", MarketStatus::Open, Decimal::new(50, 2))])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_refresh_backs_off() {
+        let source = Arc::new(RateLimitedSource::default());
+        source.failing.store(true, Ordering::SeqCst);
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source.clone()).await.unwrap();
+
+        assert!(matches!(
+            cache.refresh_platform_now(Platform::Polymarket).await,
+            Err(MarketCacheError::Api(_))
+        ));
+        let status = cache.stats().polymarket_refresh;
+        assert_eq!(status.consecutive_failures, 1);
+        assert!(status.backoff_until.unwrap() > Utc::now());
+
+        // Backing off: no fetch, and refresh_all skips the platform quietly
+        assert!(matches!(
+            cache.refresh_platform_now(Platform::Polymarket).await,
+            Err(MarketCacheError::BackingOff { platform: Platform::Polymarket, .. })
+        ));
+        assert!(cache.refresh_all().await.is_ok());
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 1);
+
+        // A forced refresh overrides the backoff; another failure doubles it
+        assert!(cache.force_refresh_platform(Platform::Polymarket).await.is_err());
+        let status = cache.stats().polymarket_refresh;
+        assert_eq!(status.consecutive_failures, 2);
+        let remaining = status.backoff_until.unwrap() - Utc::now();
+        assert!(remaining > chrono::Duration::seconds(REFRESH_BACKOFF_BASE_SECS));
+
+        source.failing.store(false, Ordering::SeqCst);
+        cache.force_refresh_platform(Platform::Polymarket).await.unwrap();
+        let status = cache.stats().polymarket_refresh;
+        assert_eq!(status.consecutive_failures, 0);
+        assert!(status.backoff_until.is_none());
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_skips_backed_off_platform_only() {
+        let source = Arc::new(RateLimitedSource::default());
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source.clone()).await.unwrap();
+
+        // Kalshi fails and backs off; Polymarket refreshes every time
+        assert!(cache.refresh_platforms(&[Platform::Kalshi, Platform::Polymarket]).await.is_err());
+        assert!(cache.refresh_platforms(&[Platform::Kalshi, Platform::Polymarket]).await.is_ok());
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 3);
+
+        let stats = cache.stats();
+        assert_eq!(stats.kalshi_refresh.consecutive_failures, 1);
+        assert!(stats.polymarket_refresh.backoff_until.is_none());
+        assert!(stats.polymarket_refresh.last_refresh_at.is_some());
+    }
+
+    #[test]
+    fn test_refresh_backoff_doubles_up_to_cap() {
+        assert_eq!(refresh_backoff(1).num_seconds(), REFRESH_BACKOFF_BASE_SECS);
+        assert_eq!(refresh_backoff(2).num_seconds(), REFRESH_BACKOFF_BASE_SECS * 2);
+        assert_eq!(refresh_backoff(3).num_seconds(), REFRESH_BACKOFF_BASE_SECS * 4);
+        assert_eq!(refresh_backoff(50).num_seconds(), REFRESH_BACKOFF_MAX_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_loop_repeats_and_records_errors() {
+        let source = Arc::new(FlakySource::default());
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source.clone()).await.unwrap();
+
+        let handles = cache.start_refresh_loop(RefreshLoopConfig {
+            polymarket_interval: Some(Duration::from_millis(20)),
+            kalshi_interval: Some(Duration::from_millis(20)),
+            jitter: 0.25,
+        });
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        assert!(source.polymarket_fetches.load(Ordering::SeqCst) >= 3);
+        let stats = cache.stats();
+        assert!(stats.polymarket_refresh.last_refresh_at.is_some());
+        assert!(stats.polymarket_refresh.last_error.is_none());
+        assert!(stats.kalshi_refresh.last_refresh_at.is_none());
+        assert!(stats.kalshi_refresh.last_error.unwrap().contains("kalshi listing"));
+
+        // Paused loops keep ticking but stop fetching
+        cache.pause_refresh();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let paused_at = source.polymarket_fetches.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(source.polymarket_fetches.load(Ordering::SeqCst), paused_at);
+
+        cache.resume_refresh();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(source.polymarket_fetches.load(Ordering::SeqCst) > paused_at);
+
+        for handle in handles {
+            handle.abort();
+        }
+    }
+
+    fn sorted_ids(cache: &MarketCache, sort: SortKey) -> Vec<String> {
+        cache
+            .get_markets_sorted(sort, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sort_indices_are_stable() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let now = Utc::now();
+
+        let mut markets: Vec<PredictionMarket> = ["c", "a", "b"]
+            .iter()
+            .map(|id| test_market(id, MarketStatus::Open, Decimal::new(50, 2)))
+            .collect();
+        markets[0].volume = Decimal::from(5000);
+        markets[0].volume_24hr = Some(Decimal::from(10));
+        markets[1].volume_24hr = Some(Decimal::from(300));
+        markets[2].volume_24hr = Some(Decimal::from(20));
+        markets[1].close_time = Some(now + chrono::Duration::days(3));
+        markets[2].close_time = Some(now + chrono::Duration::days(1));
+
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, now);
+        MarketCache::rebuild_sort_indices(&cache.db, &cache.cache, now).unwrap();
+
+        // "a" and "b" tie on volume and keep id order
+        assert_eq!(sorted_ids(&cache, SortKey::Volume), vec!["c", "a", "b"]);
+        assert_eq!(sorted_ids(&cache, SortKey::Volume24h), vec!["a", "b", "c"]);
+        assert_eq!(sorted_ids(&cache, SortKey::Closing), vec!["b", "a"]);
+        // No move from the reference prices yet, so change falls back to id order
+        assert_eq!(sorted_ids(&cache, SortKey::Change), vec!["a", "b", "c"]);
+
+        // Prices move within the reference window: largest absolute move first
+        markets[0].yes_price = Decimal::new(55, 2);
+        markets[1].yes_price = Decimal::new(40, 2);
+        let later = now + chrono::Duration::minutes(5);
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, later);
+        MarketCache::rebuild_sort_indices(&cache.db, &cache.cache, later).unwrap();
+        assert_eq!(sorted_ids(&cache, SortKey::Change), vec!["a", "c", "b"]);
+
+        // Rebuilding without changes yields the same orderings
+        MarketCache::rebuild_sort_indices(&cache.db, &cache.cache, later).unwrap();
+        assert_eq!(sorted_ids(&cache, SortKey::Change), vec!["a", "c", "b"]);
+        assert_eq!(sorted_ids(&cache, SortKey::Volume), vec!["c", "a", "b"]);
+
+        let limited = cache.get_markets_sorted(SortKey::Volume, Some(Platform::Polymarket), Some(1)).unwrap();
+        assert_eq!(limited.len(), 1);
+        assert!(cache.get_markets_sorted(SortKey::Volume, Some(Platform::Kalshi), None).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sort_indices_persist_across_restart() {
+        let db_path = std::env::temp_dir().join(format!("market_cache_sort_test_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let now = Utc::now();
+
+        {
+            let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+            let cache = MarketCache::new(&db_path, service).await.unwrap();
+            let mut markets = vec![
+                test_market("low", MarketStatus::Open, Decimal::new(50, 2)),
+                test_market("high", MarketStatus::Open, Decimal::new(50, 2)),
+            ];
+            markets[1].volume = Decimal::from(9000);
+            MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, now);
+            MarketCache::store_markets_to_db(&cache.db, Platform::Polymarket, &markets, now).unwrap();
+            MarketCache::rebuild_sort_indices(&cache.db, &cache.cache, now).unwrap();
+        }
+
+        // A new cache over the same file serves the ordering without refreshing
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(&db_path, service).await.unwrap();
+        assert_eq!(sorted_ids(&cache, SortKey::Volume), vec!["high", "low"]);
+
+        drop(cache);
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    /// Source for a machine with no network: counts calls, always fails
+    struct OfflineSource {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl MarketSource for OfflineSource {
+        async fn fetch_market(&self, _platform: Platform, _market_id: &str) -> Result<PredictionMarket, TerminalError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(TerminalError::network("offline"))
+        }
+
+        async fn fetch_platform_markets(&self, _platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(TerminalError::network("offline"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_startup_warms_cache_from_db() {
+        let db_path = std::env::temp_dir().join(format!("market_cache_warm_test_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let updated_at = Utc::now() - chrono::Duration::hours(12);
+
+        {
+            let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+            let cache = MarketCache::new(&db_path, service).await.unwrap();
+            let mut markets = vec![
+                test_market("m1", MarketStatus::Open, Decimal::new(50, 2)),
+                test_market("m2", MarketStatus::Open, Decimal::new(60, 2)),
+                test_market("gone", MarketStatus::Settled, Decimal::ONE),
+            ];
+            markets[0].tags = vec!["Crypto".to_string()];
+            MarketCache::store_markets_to_db(&cache.db, Platform::Polymarket, &markets, updated_at).unwrap();
+            // An archive that stopped before deleting the market row
+            cache
+                .db
+                .lock()
+                .execute(
+                    "INSERT INTO archived_markets (platform, market_id, title, data, closed_at, archived_at)
+                     VALUES ('polymarket', 'gone', 'gone', '{}', 0, 0)",
+                    [],
+                )
+                .unwrap();
+        }
+
+        let source = Arc::new(OfflineSource {
+            calls: Default::default(),
+        });
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(&db_path, service, source.clone()).await.unwrap();
+
+        // Served before any refresh, which can't run until this task yields
+        let page = cache.get_markets(None, PageRequest::all());
+        let mut ids: Vec<&str> = page.markets.iter().map(|m| m.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["m1", "m2"]);
+        assert_eq!(source.calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(sorted_ids(&cache, SortKey::Volume).len(), 2);
+        assert!(cache.get_categories().unwrap().iter().any(|(name, _)| name == "crypto"));
+
+        let stats = cache.stats();
+        assert_eq!(stats.warmed_from_disk, 2);
+        assert_eq!(stats.fresh, 0);
+        assert!(stats.data_age_secs.unwrap() >= 12 * 3600);
+        assert!(cache.data_age(Some(Platform::Polymarket)).unwrap() >= chrono::Duration::hours(12));
+
+        drop(cache);
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_unified_matches_and_overrides() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let now = Utc::now();
+
+        let platform_market = |platform: Platform, id: &str, title: &str, yes: i64| {
+            let mut m = test_market(id, MarketStatus::Open, Decimal::new(yes, 2));
+            m.platform = platform;
+            m.title = title.to_string();
+            m
+        };
+        let kalshi = vec![
+            platform_market(Platform::Kalshi, "KXBTC", "Bitcoin above $100k by end of 2025", 40),
+            platform_market(Platform::Kalshi, "KXRAIN", "Rain in NYC tomorrow?", 30),
+        ];
+        let polymarket = vec![
+            platform_market(Platform::Polymarket, "p-btc", "Will Bitcoin be above $100k by the end of 2025?", 46),
+            platform_market(Platform::Polymarket, "p-nyc", "New York precipitation on Friday", 35),
+        ];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Kalshi, &kalshi, now);
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &polymarket, now);
+
+        assert_eq!(cache.rebuild_unified_matches().unwrap(), 1);
+        let unified = cache.get_unified_markets().unwrap();
+        assert_eq!(unified.len(), 1);
+        assert_eq!(unified[0].market.id, unified_id("KXBTC", "p-btc"));
+        assert_eq!(unified[0].market.spread, Some(Decimal::new(6, 2)));
+        assert!(!unified[0].manual);
+        assert!(cache.get_unified_market("p-btc").unwrap().is_some());
+        assert!(cache.get_unified_market("KXRAIN").unwrap().is_none());
+
+        // Manual overrides: link the pair the matcher missed, reject the one it found
+        cache.link_markets("KXRAIN", "p-nyc", true).unwrap();
+        cache.link_markets("KXBTC", "p-btc", false).unwrap();
+        assert_eq!(cache.rebuild_unified_matches().unwrap(), 0);
+
+        let unified = cache.get_unified_markets().unwrap();
+        assert_eq!(unified.len(), 1);
+        assert_eq!(unified[0].market.id, unified_id("KXRAIN", "p-nyc"));
+        assert!(unified[0].manual);
+        assert_eq!(unified[0].confidence, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_archives_old_closed_markets() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let now = Utc::now();
+
+        let open = test_market("open", MarketStatus::Open, Decimal::new(50, 2));
+        let mut old_closed = test_market("old-closed", MarketStatus::Closed, Decimal::new(50, 2));
+        old_closed.close_time = Some(now - chrono::Duration::days(30));
+        let mut old_settled = test_market("old-settled", MarketStatus::Settled, Decimal::new(99, 2));
+        old_settled.close_time = Some(now - chrono::Duration::days(10));
+        let mut recent_settled = test_market("recent-settled", MarketStatus::Settled, Decimal::new(1, 2));
+        recent_settled.close_time = Some(now - chrono::Duration::days(1));
+        let markets = vec![open, old_closed, old_settled, recent_settled];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, now);
+        MarketCache::store_markets_to_db(&cache.db, Platform::Polymarket, &markets, now).unwrap();
+
+        assert_eq!(cache.archive_stale_markets(now).unwrap(), 2);
+
+        let mut hot: Vec<String> = cache
+            .get_markets(None, PageRequest::all())
+            .markets
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        hot.sort();
+        assert_eq!(hot, vec!["open", "recent-settled"]);
+
+        let archived: Vec<String> = cache
+            .get_archived_markets(None, 10, 0)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(archived, vec!["old-settled", "old-closed"]);
+        assert_eq!(cache.get_archived_markets(Some(Platform::Polymarket), 1, 1).unwrap()[0].id, "old-closed");
+        assert!(cache.get_archived_markets(Some(Platform::Kalshi), 10, 0).unwrap().is_empty());
+        assert_eq!(cache.stats().archived_count, 2);
+
+        // A shorter archive age picks up the recently settled market too
+        cache.set_archive_after_days(0);
+        assert_eq!(cache.archive_stale_markets(now).unwrap(), 1);
+        assert_eq!(cache.get_markets(None, PageRequest::all()).total_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_requests_coalesce_and_broadcast() {
+        let source = Arc::new(CountingSource {
+            fetches: Default::default(),
+            yes_price: Decimal::new(75, 2),
+        });
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service.clone(), source.clone()).await.unwrap();
+        let ws_state = Arc::new(WebSocketState::new(service));
+        let mut broadcast_rx = ws_state.subscriptions.subscribe_broadcast();
+        cache.set_websocket_state(ws_state);
+
+        let request = RefreshRequest::Single {
+            platform: Platform::Polymarket,
+            market_id: "m1".to_string(),
+        };
+        for _ in 0..3 {
+            cache.request_refresh(request.clone());
+        }
+
+        let update = tokio::time::timeout(Duration::from_secs(1), broadcast_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match update.message {
+            terminal_core::ServerMessage::PriceUpdate { market_id, yes_price, .. } => {
+                assert_eq!(market_id, "m1");
+                assert_eq!(yes_price, Decimal::new(75, 2));
+            }
+            other => panic!("unexpected broadcast: {:?}", other),
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 1);
+        assert!(broadcast_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_computes_price_changes() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let platform = Platform::Polymarket;
+        let source = |cents: i64| -> Arc<dyn MarketSource> {
+            Arc::new(CountingSource {
+                fetches: Default::default(),
+                yes_price: Decimal::new(cents, 2),
+            })
+        };
+
+        // No cached price and no snapshots: both changes are null
+        MarketCache::fetch_and_store_platform(&cache.cache, &cache.db, &source(50), &cache.trade_storage, &cache.events, platform)
+            .await
+            .unwrap();
+        let market = cache.get_market(platform, "m1").await.unwrap();
+        assert_eq!(market.change_1h, None);
+        assert_eq!(market.change_24h, None);
+        let json = serde_json::to_value(&market).unwrap();
+        assert!(json["change_24h"].is_null());
+
+        // A snapshot from a day ago anchors the 24h change; the previous
+        // refresh anchors the 1h change
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let day_ago = (Utc::now() - chrono::Duration::hours(25)).timestamp();
+        storage.store_price_snapshot_at(platform, "m1", day_ago, 0.40, Some(0.60)).unwrap();
+        cache.set_trade_storage(storage);
+
+        MarketCache::fetch_and_store_platform(&cache.cache, &cache.db, &source(65), &cache.trade_storage, &cache.events, platform)
+            .await
+            .unwrap();
+        let page = cache.get_markets(Some(platform), PageRequest::all());
+        let market = &page.markets[0];
+        assert_eq!(market.change_1h, Some(Decimal::new(15, 2)));
+        assert_eq!(market.change_24h, Some(Decimal::new(25, 2)));
+
+        // Changes are persisted with the market
+        let reloaded = Arc::new(RwLock::new(HashMap::new()));
+        MarketCache::load_from_db(&cache.db, &reloaded).unwrap();
+        let stored = reloaded.read()[&(platform, "m1".to_string())].market.clone();
+        assert_eq!(stored.change_24h, Some(Decimal::new(25, 2)));
+    }
+
+    /// Source serving a fixed platform listing
+    struct ListingSource {
+        markets: Vec<PredictionMarket>,
+    }
+
+    #[async_trait]
+    impl MarketSource for ListingSource {
+        async fn fetch_market(&self, _platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError> {
+            Err(TerminalError::NotFound(market_id.to_string()))
+        }
+
+        async fn fetch_platform_markets(&self, _platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+            Ok(self.markets.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_emits_added_and_updated_events() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let platform = Platform::Polymarket;
+
+        let cached = vec![
+            test_market("same", MarketStatus::Open, Decimal::new(50, 2)),
+            test_market("moved", MarketStatus::Open, Decimal::new(50, 2)),
+        ];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, platform, &cached, Utc::now());
+        let mut events = cache.subscribe_events();
+
+        let source: Arc<dyn MarketSource> = Arc::new(ListingSource {
+            markets: vec![
+                test_market("same", MarketStatus::Open, Decimal::new(50, 2)),
+                test_market("moved", MarketStatus::Open, Decimal::new(62, 2)),
+                test_market("breaking", MarketStatus::Open, Decimal::new(10, 2)),
+            ],
+        });
+        MarketCache::fetch_and_store_platform(&cache.cache, &cache.db, &source, &cache.trade_storage, &cache.events, platform)
+            .await
+            .unwrap();
+
+        let mut added = Vec::new();
+        let mut updated = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            match event {
+                MarketCacheEvent::Added { market, .. } => added.push(market.id),
+                MarketCacheEvent::Updated { market, .. } => updated.push(market.id),
+                MarketCacheEvent::Removed { market_id, .. } => panic!("unexpected removal of {}", market_id),
+            }
+        }
+        assert_eq!(added, vec!["breaking"]);
+        assert_eq!(updated, vec!["moved"]);
+
+        // An identical refresh changes nothing
+        MarketCache::fetch_and_store_platform(&cache.cache, &cache.db, &source, &cache.trade_storage, &cache.events, platform)
+            .await
+            .unwrap();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_lookup_keeps_order_and_refreshes_misses() {
+        let source = Arc::new(CountingSource {
+            fetches: Default::default(),
+            yes_price: Decimal::new(30, 2),
+        });
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source.clone()).await.unwrap();
+        let markets = vec![
+            test_market("a", MarketStatus::Open, Decimal::new(50, 2)),
+            test_market("b", MarketStatus::Open, Decimal::new(60, 2)),
+        ];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, Utc::now());
+
+        let ids = vec![
+            (Platform::Polymarket, "b".to_string()),
+            (Platform::Polymarket, "missing".to_string()),
+            (Platform::Kalshi, "a".to_string()),
+            (Platform::Polymarket, "a".to_string()),
+        ];
+        let found: Vec<Option<String>> = cache
+            .get_markets_by_ids(&ids)
+            .into_iter()
+            .map(|m| m.map(|m| m.id))
+            .collect();
+        assert_eq!(
+            found,
+            vec![Some("b".to_string()), None, None, Some("a".to_string())]
+        );
+
+        // Misses were queued for refresh and are found on the next lookup
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 2);
+        let found = cache.get_markets_by_ids(&ids[1..3]);
+        assert_eq!(found[0].as_ref().unwrap().yes_price, Decimal::new(30, 2));
+        assert!(found[1].is_some());
+        assert!(cache.get_markets_by_ids(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cached_market_lookup_never_fetches() {
+        let source = Arc::new(CountingSource {
+            fetches: Default::default(),
+            yes_price: Decimal::new(30, 2),
+        });
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source.clone()).await.unwrap();
+        let markets = vec![test_market("a", MarketStatus::Open, Decimal::new(50, 2))];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, Utc::now());
+
+        assert_eq!(cache.get_cached_market(Platform::Polymarket, "a").unwrap().id, "a");
+        assert!(cache.get_cached_market(Platform::Polymarket, "made-up").is_none());
+        assert!(cache.get_cached_market(Platform::Kalshi, "a").is_none());
+
+        // Misses are not queued for refresh either
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_watchlist_crud_and_auto_track() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service.clone()).await.unwrap();
+        let now = Utc::now();
+        let mut closed = test_market("closed", MarketStatus::Closed, Decimal::new(50, 2));
+        closed.close_time = Some(now - chrono::Duration::days(30));
+        let markets = vec![test_market("open", MarketStatus::Open, Decimal::new(40, 2)), closed];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, now);
+        MarketCache::store_markets_to_db(&cache.db, Platform::Polymarket, &markets, now).unwrap();
+
+        assert!(cache.add_to_watchlist("mine", Platform::Polymarket, "open").unwrap());
+        assert!(!cache.add_to_watchlist("mine", Platform::Polymarket, "open").unwrap());
+        assert!(cache.add_to_watchlist("mine", Platform::Polymarket, "closed").unwrap());
+        assert!(cache.add_to_watchlist("mine", Platform::Kalshi, "gone").unwrap());
+        assert!(cache.add_to_watchlist("other", Platform::Polymarket, "open").unwrap());
+
+        // Markets leaving the cache stay listed, flagged unavailable
+        assert_eq!(cache.archive_stale_markets(now).unwrap(), 1);
+        let collector = Arc::new(TradeCollector::new(
+            Arc::new(service),
+            Arc::new(TradeStorage::new_in_memory().unwrap()),
+            None,
+            crate::TradeCollectorConfig::default(),
+        ));
+        cache.set_trade_collector(collector.clone());
+
+        let list = cache.get_watchlist("mine").await.unwrap();
+        let mut entries: Vec<(&str, bool, bool)> = list
+            .iter()
+            .map(|e| (e.market_id.as_str(), e.unavailable, e.market.is_some()))
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![("closed", true, true), ("gone", true, false), ("open", false, true)]
+        );
+
+        // Only markets still cached are tracked
+        assert_eq!(
+            collector.tracked_markets().await,
+            vec![(Platform::Polymarket, "open".to_string())]
+        );
+
+        assert!(cache.remove_from_watchlist("mine", Platform::Polymarket, "open").unwrap());
+        assert!(!cache.remove_from_watchlist("mine", Platform::Polymarket, "open").unwrap());
+        assert_eq!(cache.get_watchlist("mine").await.unwrap().len(), 2);
+        assert_eq!(cache.get_watchlist("other").await.unwrap().len(), 1);
+        assert!(cache.get_watchlist("unknown").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stats_track_lookups_and_refreshes() {
+        let source = Arc::new(CountingSource {
+            fetches: Default::default(),
+            yes_price: Decimal::new(75, 2),
+        });
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::with_source(":memory:", service, source).await.unwrap();
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (0, 0));
+        assert_eq!(stats.hit_rate, None);
+        assert_eq!(stats.memory_bytes, 0);
+        assert!(stats.db_bytes > 0);
+
+        let stale_at = Utc::now() - chrono::Duration::hours(1);
+        let markets = vec![test_market("m1", MarketStatus::Open, Decimal::new(50, 2))];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, stale_at);
+
+        cache.refresh_all().await.unwrap();
+        let stats = cache.stats();
+        assert!(stats.polymarket_refresh.last_duration_ms.is_some());
+        assert_eq!(stats.polymarket_refresh.last_added, 0);
+        assert_eq!(stats.polymarket_refresh.last_updated, 1);
+        assert_eq!(stats.polymarket_refresh.last_removed, 0);
+        assert!(stats.memory_bytes > std::mem::size_of::<CachedMarket>());
+
+        // Fresh after the refresh: hits
+        cache.get_market(Platform::Polymarket, "m1").await.unwrap();
+        cache.get_markets(None, PageRequest::all());
+        assert_eq!(cache.stats().hits, 2);
+
+        // A stale entry makes the listing a miss
+        let markets = vec![test_market("old", MarketStatus::Open, Decimal::new(50, 2))];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, stale_at);
+        cache.get_markets(None, PageRequest::all());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert_eq!(stats.hit_rate, Some(2.0 / 3.0));
+    }
+
+    #[tokio::test]
+    async fn test_event_outcomes_are_grouped() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+
+        let outcome = |id: &str, event: Option<&str>, title: &str, volume: i64| {
+            let mut m = test_market(id, MarketStatus::Open, Decimal::new(30, 2));
+            m.event_id = event.map(str::to_string);
+            m.title = title.to_string();
+            m.volume = Decimal::from(volume);
+            m
+        };
+        let markets = vec![
+            outcome("ev1", Some("ev1"), "Top grossing movie 2025", 9000),
+            outcome("m-a", Some("ev1"), "Top grossing movie 2025 → Avatar", 1000),
+            outcome("m-b", Some("ev1"), "Top grossing movie 2025 → Jurassic World", 2500),
+            outcome("m-c", Some("ev1"), "Top grossing movie 2025 → Zootopia", 500),
+            outcome("solo", None, "Rain in NYC tomorrow?", 300),
+        ];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, Utc::now());
+
+        let groups = cache.get_market_groups(Some(Platform::Polymarket));
+        assert_eq!(groups.len(), 2);
+
+        let event = &groups[0];
+        assert_eq!(event.group_id, "ev1");
+        assert_eq!(event.title, "Top grossing movie 2025");
+        let ids: Vec<&str> = event.markets.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m-b", "m-a", "m-c"]);
+        assert_eq!(event.volume, event.markets.iter().map(|m| m.volume).sum::<Decimal>());
+        assert_eq!(event.volume, Decimal::from(4000));
+
+        assert_eq!(groups[1].group_id, "solo");
+        assert_eq!(groups[1].volume, Decimal::from(300));
+
+        // Without the event-level entry the title comes from the outcome titles
+        let groups = group_markets_by_event(markets[1..3].to_vec());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].title, "Top grossing movie 2025");
+        assert_eq!(groups[0].volume, Decimal::from(3500));
+    }
+
+    #[tokio::test]
+    async fn test_overrides_apply_and_survive_refresh() {
+        let db_path = std::env::temp_dir().join(format!("market_cache_override_test_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&db_path);
+        let platform = Platform::Polymarket;
+        let ids = |page: MarketPage| page.markets.into_iter().map(|m| (m.id, m.title)).collect::<Vec<_>>();
+
+        {
+            let source: Arc<dyn MarketSource> = Arc::new(CountingSource {
+                fetches: Default::default(),
+                yes_price: Decimal::new(50, 2),
+            });
+            let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+            let cache = MarketCache::with_source(&db_path, service, Arc::clone(&source)).await.unwrap();
+
+            let market = |id: &str, volume: i64| {
+                let mut m = test_market(id, MarketStatus::Open, Decimal::new(50, 2));
+                m.volume = Decimal::from(volume);
+                m
+            };
+            let markets = vec![market("big", 900), market("small", 10), market("spam", 500)];
+            MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, platform, &markets, Utc::now());
+
+            cache
+                .set_override(
+                    platform,
+                    "small",
+                    MarketOverride {
+                        display_title: Some("Corrected title".to_string()),
+                        pinned: true,
+                        notes: Some("title fixed by hand".to_string()),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            cache
+                .set_override(
+                    platform,
+                    "spam",
+                    MarketOverride {
+                        hidden: true,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            let page = cache.get_markets(None, PageRequest::all());
+            assert_eq!(page.total_count, 2);
+            assert_eq!(
+                ids(page),
+                vec![
+                    ("small".to_string(), "Corrected title".to_string()),
+                    ("big".to_string(), "Will it rain tomorrow?".to_string())
+                ]
+            );
+            assert_eq!(
+                cache.get_override(platform, "small").unwrap().notes.as_deref(),
+                Some("title fixed by hand")
+            );
+
+            // Pinned markets lead across page boundaries too
+            let first = cache.get_markets(None, PageRequest::first(1));
+            let cursor = MarketCursor::parse(first.next_cursor.as_deref().unwrap()).unwrap();
+            let second = cache.get_markets(
+                None,
+                PageRequest {
+                    cursor: Some(cursor),
+                    limit: Some(1),
+                    unlimited: false,
+                },
+            );
+            assert_eq!(ids(first)[0].0, "small");
+            assert_eq!(ids(second)[0].0, "big");
+
+            // A refresh rewrites the market row but not the override
+            let renamed = MarketOverride {
+                display_title: Some("Renamed".to_string()),
+                ..Default::default()
+            };
+            cache.set_override(platform, "m1", renamed).unwrap();
+            MarketCache::fetch_and_store_platform(
+                &cache.cache,
+                &cache.db,
+                &source,
+                &cache.trade_storage,
+                &cache.events,
+                platform,
+            )
+            .await
+            .unwrap();
+            let listed = cache.get_markets(None, PageRequest::all()).markets;
+            assert_eq!(listed.iter().find(|m| m.id == "m1").unwrap().title, "Renamed");
+            assert!(cache.clear_override(platform, "m1").unwrap());
+            assert!(!cache.clear_override(platform, "m1").unwrap());
+            let stored_title: String = cache
+                .db
+                .lock()
+                .query_row(
+                    "SELECT title FROM markets WHERE platform = 'polymarket' AND market_id = 'm1'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(stored_title, "Will it rain tomorrow?");
+        }
+
+        // Overrides persist across restarts
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(&db_path, service).await.unwrap();
+        assert!(cache.get_override(platform, "spam").unwrap().hidden);
+        assert_eq!(
+            cache.get_override(platform, "small").unwrap().display_title.as_deref(),
+            Some("Corrected title")
+        );
+        assert!(cache.get_override(platform, "m1").is_none());
+
+        drop(cache);
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_closing_soon_and_recently_resolved() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let now = Utc::now();
+
+        let market = |id: &str, status: MarketStatus, close_in_hours: Option<i64>| {
+            let mut m = test_market(id, status, Decimal::new(50, 2));
+            m.close_time = close_in_hours.map(|h| now + chrono::Duration::hours(h));
+            m
+        };
+        let markets = vec![
+            market("open-12h", MarketStatus::Open, Some(12)),
+            market("open-2h", MarketStatus::Open, Some(2)),
+            market("open-30h", MarketStatus::Open, Some(30)),
+            market("open-undated", MarketStatus::Open, None),
+            market("settled-1h", MarketStatus::Settled, Some(-1)),
+            market("settled-20h", MarketStatus::Settled, Some(-20)),
+            market("settled-3d", MarketStatus::Settled, Some(-72)),
+            market("settled-undated", MarketStatus::Settled, None),
+            market("closed-5h", MarketStatus::Closed, Some(-5)),
+        ];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, now);
+
+        let ids = |markets: Vec<PredictionMarket>| markets.into_iter().map(|m| m.id).collect::<Vec<_>>();
+        let day = chrono::Duration::hours(24);
+
+        assert_eq!(ids(cache.get_closing_soon(day, None, None)), vec!["open-2h", "open-12h"]);
+        assert_eq!(ids(cache.get_closing_soon(day, None, Some(1))), vec!["open-2h"]);
+        assert_eq!(ids(cache.get_closing_soon(day * 2, None, None)), vec!["open-2h", "open-12h", "open-30h"]);
+        assert!(cache.get_closing_soon(day, Some(Platform::Kalshi), None).is_empty());
+
+        assert_eq!(ids(cache.get_recently_resolved(day, None, None)), vec!["settled-1h", "settled-20h"]);
+        assert_eq!(
+            ids(cache.get_recently_resolved(chrono::Duration::days(7), None, None)),
+            vec!["settled-1h", "settled-20h", "settled-3d"]
+        );
+    }
+
+    #[test]
+    fn test_market_cursor_round_trip() {
+        let mut market = test_market("0xabc", MarketStatus::Open, Decimal::new(50, 2));
+        market.volume = Decimal::new(12345, 2);
+        let cursor = MarketCursor::of(&market, true);
+        assert_eq!(MarketCursor::parse(&cursor.encode()), Some(cursor));
+        assert_eq!(MarketCursor::parse("zz"), None);
+        assert_eq!(MarketCursor::parse("abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pages_survive_refresh() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+        let now = Utc::now();
+
+        let market = |id: &str, volume: i64| {
+            let mut m = test_market(id, MarketStatus::Open, Decimal::new(50, 2));
+            m.volume = Decimal::from(volume);
+            m
+        };
+        // "b" and "c" tie on volume and order by id
+        let markets = vec![market("a", 500), market("b", 400), market("c", 400), market("d", 300), market("e", 200)];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &markets, now);
+
+        let first = cache.get_markets(None, PageRequest::first(2));
+        let ids = |page: &MarketPage| page.markets.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&first), vec!["a", "b"]);
+        assert_eq!(first.total_count, 5);
+
+        // A refresh adds a high-volume market and drops "c" before the next page
+        let refreshed = vec![market("new", 900)];
+        MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, Platform::Polymarket, &refreshed, now);
+        cache.cache.write().remove(&(Platform::Polymarket, "c".to_string()));
+
+        let cursor = MarketCursor::parse(first.next_cursor.as_deref().unwrap()).unwrap();
+        let second = cache.get_markets(
+            None,
+            PageRequest {
+                cursor: Some(cursor),
+                limit: Some(2),
+                unlimited: false,
+            },
+        );
+        assert_eq!(ids(&second), vec!["d", "e"]);
+        assert_eq!(second.next_cursor, None);
+
+        let all = cache.get_markets(None, PageRequest::all());
+        assert_eq!(all.markets.len(), 5);
+        assert_eq!(all.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_categories_with_multi_tag_markets() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let cache = MarketCache::new(":memory:", service).await.unwrap();
+
+        let mut m1 = test_market("m1", MarketStatus::Open, Decimal::new(50, 2));
+        m1.category = Some("Politics".to_string());
+        m1.tags = vec!["Elections".to_string(), " politics ".to_string(), "  ".to_string()];
+        let mut m2 = test_market("m2", MarketStatus::Open, Decimal::new(50, 2));
+        m2.category = Some("crypto".to_string());
+        m2.tags = vec!["Bitcoin".to_string(), "ELECTIONS".to_string()];
+        m2.volume = Decimal::from(5000);
+        let mut m3 = test_market("m3", MarketStatus::Open, Decimal::new(50, 2));
+        m3.platform = Platform::Kalshi;
+        m3.category = Some("Politics".to_string());
+
+        let now = Utc::now();
+        for (platform, markets) in [(Platform::Polymarket, vec![m1, m2]), (Platform::Kalshi, vec![m3])] {
+            MarketCache::update_memory_cache(&cache.cache, &cache.trade_storage, platform, &markets, now);
+            MarketCache::store_markets_to_db(&cache.db, platform, &markets, now).unwrap();
+        }
+
+        assert_eq!(
+            cache.get_categories().unwrap(),
+            vec![
+                ("elections".to_string(), 2),
+                ("politics".to_string(), 2),
+                ("bitcoin".to_string(), 1),
+                ("crypto".to_string(), 1),
+            ]
+        );
+
+        // Multi-tag markets appear under each tag; lookups are normalized
+        let elections: Vec<String> = cache
+            .get_markets_by_category(" Elections ", None, None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(elections, vec!["m2", "m1"]);
+
+        let kalshi_politics = cache
+            .get_markets_by_category("POLITICS", Some(Platform::Kalshi), None)
+            .unwrap();
+        assert_eq!(kalshi_politics.len(), 1);
+        assert_eq!(kalshi_politics[0].id, "m3");
+
+        // Re-storing a market with fewer tags drops the stale rows
+        let mut m2 = cache.get_markets_by_category("crypto", None, None).unwrap().remove(0);
+        m2.tags.clear();
+        MarketCache::store_markets_to_db(&cache.db, Platform::Polymarket, &[m2], now).unwrap();
+        assert!(cache.get_markets_by_category("bitcoin", None, None).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,237 @@
+//! Cross-Platform Market Matching
+//!
+//! Pairs Kalshi and Polymarket markets that describe the same event. Titles
+//! are compared by token overlap (Jaccard over normalized words), blended with
+//! embedding cosine similarity when both markets have a stored embedding.
+//! Pairing is one-to-one: the most confident candidate pairs are taken first.
+
+use std::collections::{HashMap, HashSet};
+
+use terminal_core::PredictionMarket;
+use terminal_embedding::cosine_similarity;
+
+/// Minimum confidence for an automatic match
+pub const MIN_MATCH_CONFIDENCE: f64 = 0.6;
+
+/// Share of the confidence taken from embedding similarity when available
+const EMBEDDING_WEIGHT: f64 = 0.5;
+
+/// Words that carry no signal about which event a title describes
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "at", "be", "by", "does", "for", "in", "is", "of", "on", "or", "the", "to",
+    "will", "win", "with",
+];
+
+/// A candidate pairing of a Kalshi and a Polymarket market
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketMatch {
+    pub kalshi_id: String,
+    pub polymarket_id: String,
+    /// 0.0 - 1.0
+    pub confidence: f64,
+}
+
+/// Normalized title words: lowercased alphanumerics without stopwords
+pub fn title_tokens(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|t| t.to_lowercase())
+        .filter(|t| !t.is_empty() && !STOPWORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// Jaccard similarity of two token sets
+pub fn token_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Pair markets across platforms, most confident first
+///
+/// `embeddings` maps market ids to stored vectors; pairs in `excluded` are
+/// never matched.
+pub fn find_matches(
+    kalshi: &[PredictionMarket],
+    polymarket: &[PredictionMarket],
+    embeddings: &HashMap<String, Vec<f32>>,
+    excluded: &HashSet<(String, String)>,
+    min_confidence: f64,
+) -> Vec<MarketMatch> {
+    let poly_tokens: Vec<HashSet<String>> = polymarket.iter().map(|m| title_tokens(&m.title)).collect();
+
+    // Only compare pairs that share at least one token
+    let mut by_token: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, tokens) in poly_tokens.iter().enumerate() {
+        for token in tokens {
+            by_token.entry(token.as_str()).or_default().push(i);
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for k in kalshi {
+        let k_tokens = title_tokens(&k.title);
+        let mut compared = HashSet::new();
+        for token in &k_tokens {
+            for &i in by_token.get(token.as_str()).into_iter().flatten() {
+                if !compared.insert(i) {
+                    continue;
+                }
+                let p = &polymarket[i];
+                if excluded.contains(&(k.id.clone(), p.id.clone())) {
+                    continue;
+                }
+
+                let overlap = token_similarity(&k_tokens, &poly_tokens[i]);
+                let confidence = match (embeddings.get(&k.id), embeddings.get(&p.id)) {
+                    (Some(ke), Some(pe)) => {
+                        (1.0 - EMBEDDING_WEIGHT) * overlap + EMBEDDING_WEIGHT * cosine_similarity(ke, pe)
+                    }
+                    _ => overlap,
+                };
+                if confidence >= min_confidence {
+                    candidates.push(MarketMatch {
+                        kalshi_id: k.id.clone(),
+                        polymarket_id: p.id.clone(),
+                        confidence,
+                    });
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| (&a.kalshi_id, &a.polymarket_id).cmp(&(&b.kalshi_id, &b.polymarket_id)))
+    });
+
+    let mut used_kalshi = HashSet::new();
+    let mut used_poly = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|m| {
+            if used_kalshi.contains(&m.kalshi_id) || used_poly.contains(&m.polymarket_id) {
+                return false;
+            }
+            used_kalshi.insert(m.kalshi_id.clone());
+            used_poly.insert(m.polymarket_id.clone());
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use terminal_core::{MarketStatus, Platform};
+
+    fn market(platform: Platform, id: &str, title: &str) -> PredictionMarket {
+        PredictionMarket {
+            id: id.to_string(),
+            platform,
+            ticker: None,
+            title: title.to_string(),
+            description: None,
+            category: None,
+            yes_price: Decimal::new(50, 2),
+            no_price: Decimal::new(50, 2),
+            volume: Decimal::ZERO,
+            volume_24hr: None,
+            liquidity: None,
+            open_interest: None,
+            close_time: None,
+            created_at: None,
+            status: MarketStatus::Open,
+            image_url: None,
+            url: None,
+            outcome_count: None,
+            leading_outcome: None,
+            is_multi_outcome: false,
+            event_id: None,
+            options_json: None,
+            resolution_source: None,
+            tags: vec![],
+            is_sports: false,
+            is_live: false,
+            score: None,
+            game_period: None,
+            home_team: None,
+            away_team: None,
+            home_odds: None,
+            away_odds: None,
+            spread_line: None,
+            total_line: None,
+            change_1h: None,
+            change_24h: None,
+        }
+    }
+
+    #[test]
+    fn test_matches_near_duplicate_titles() {
+        let kalshi = vec![
+            market(Platform::Kalshi, "KXFED-25DEC", "Will the Fed cut rates in December 2025?"),
+            market(Platform::Kalshi, "KXBTC-100K", "Bitcoin above $100k by end of 2025"),
+            market(Platform::Kalshi, "KXRAIN-NYC", "Rain in NYC tomorrow?"),
+        ];
+        let polymarket = vec![
+            market(Platform::Polymarket, "p-btc", "Will Bitcoin be above $100k by the end of 2025?"),
+            market(Platform::Polymarket, "p-fed", "Fed cuts rates in December 2025"),
+            market(Platform::Polymarket, "p-eth", "Ethereum above $10k by end of 2025"),
+        ];
+
+        let matches = find_matches(&kalshi, &polymarket, &HashMap::new(), &HashSet::new(), MIN_MATCH_CONFIDENCE);
+        let pairs: Vec<(&str, &str)> = matches
+            .iter()
+            .map(|m| (m.kalshi_id.as_str(), m.polymarket_id.as_str()))
+            .collect();
+
+        assert!(pairs.contains(&("KXBTC-100K", "p-btc")));
+        assert!(pairs.contains(&("KXFED-25DEC", "p-fed")));
+        assert!(!pairs.iter().any(|(_, p)| *p == "p-eth"));
+        assert!(!pairs.iter().any(|(k, _)| *k == "KXRAIN-NYC"));
+        assert!(matches.iter().all(|m| m.confidence >= MIN_MATCH_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_embeddings_and_exclusions() {
+        let kalshi = vec![market(Platform::Kalshi, "k1", "Fed cut in December")];
+        let polymarket = vec![market(
+            Platform::Polymarket,
+            "p1",
+            "Fed decision in December: cut, hold or hike?",
+        )];
+
+        // Token overlap alone (3 of 6 words) falls short of the threshold
+        assert!(find_matches(&kalshi, &polymarket, &HashMap::new(), &HashSet::new(), MIN_MATCH_CONFIDENCE).is_empty());
+
+        // Identical embeddings lift it over
+        let embeddings = HashMap::from([
+            ("k1".to_string(), vec![1.0, 0.0]),
+            ("p1".to_string(), vec![1.0, 0.0]),
+        ]);
+        let matches = find_matches(&kalshi, &polymarket, &embeddings, &HashSet::new(), MIN_MATCH_CONFIDENCE);
+        assert_eq!(matches.len(), 1);
+        assert!((matches[0].confidence - 0.75).abs() < 1e-9);
+
+        // An excluded pair is never matched
+        let excluded = HashSet::from([("k1".to_string(), "p1".to_string())]);
+        assert!(find_matches(&kalshi, &polymarket, &embeddings, &excluded, MIN_MATCH_CONFIDENCE).is_empty());
+    }
+
+    #[test]
+    fn test_pairing_is_one_to_one() {
+        let kalshi = vec![market(Platform::Kalshi, "k1", "Bitcoin above 100k in 2025")];
+        let polymarket = vec![
+            market(Platform::Polymarket, "p1", "Bitcoin above 100k in 2025"),
+            market(Platform::Polymarket, "p2", "Bitcoin above 100k in 2025?"),
+        ];
+
+        let matches = find_matches(&kalshi, &polymarket, &HashMap::new(), &HashSet::new(), MIN_MATCH_CONFIDENCE);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].polymarket_id, "p1");
+    }
+}
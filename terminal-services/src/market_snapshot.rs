@@ -0,0 +1,274 @@
+//! Current market state for new WebSocket subscribers
+//!
+//! A client subscribing to a market channel is sent one `snapshot` message
+//! with the market as cached, its current order book and its latest stored
+//! trades, so it can render straight away instead of waiting for the next
+//! update. The book is the aggregator's streamed copy when there is one,
+//! otherwise the last stored snapshot.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use terminal_core::{OrderBook, Platform, PredictionMarket, ServerMessage, Trade};
+use tracing::warn;
+
+use crate::{MarketCache, MarketDataAggregator, TradeStorage};
+
+/// Default trades included in a snapshot
+pub const DEFAULT_SNAPSHOT_TRADES: usize = 50;
+
+/// What a new subscriber to a market is sent before live updates
+#[derive(Debug, Clone, Default)]
+pub struct MarketSnapshot {
+    pub market: Option<PredictionMarket>,
+    pub orderbook: Option<OrderBook>,
+    /// Oldest first
+    pub trades: Vec<Trade>,
+}
+
+impl MarketSnapshot {
+    pub fn into_message(self, platform: Platform, market_id: String) -> ServerMessage {
+        ServerMessage::Snapshot {
+            platform,
+            market_id,
+            market: self.market,
+            orderbook: self.orderbook,
+            trades: self.trades,
+        }
+    }
+}
+
+/// Assembles market snapshots for `WebSocketState`
+#[async_trait]
+pub trait SnapshotSource: Send + Sync {
+    async fn snapshot(&self, platform: Platform, market_id: &str) -> MarketSnapshot;
+}
+
+/// Snapshots from the market cache, the aggregator's books and trade storage
+pub struct MarketSnapshotter {
+    market_cache: Arc<MarketCache>,
+    aggregator: Arc<MarketDataAggregator>,
+    trade_storage: Option<Arc<TradeStorage>>,
+    trade_limit: usize,
+}
+
+impl MarketSnapshotter {
+    pub fn new(market_cache: Arc<MarketCache>, aggregator: Arc<MarketDataAggregator>) -> Self {
+        Self {
+            market_cache,
+            aggregator,
+            trade_storage: None,
+            trade_limit: DEFAULT_SNAPSHOT_TRADES,
+        }
+    }
+
+    /// Read stored trades and fall back to stored books from `storage`
+    pub fn with_trade_storage(mut self, storage: Arc<TradeStorage>) -> Self {
+        self.trade_storage = Some(storage);
+        self
+    }
+
+    /// Include up to `limit` trades per snapshot
+    pub fn with_trade_limit(mut self, limit: usize) -> Self {
+        self.trade_limit = limit;
+        self
+    }
+}
+
+#[async_trait]
+impl SnapshotSource for MarketSnapshotter {
+    async fn snapshot(&self, platform: Platform, market_id: &str) -> MarketSnapshot {
+        // Cache only: a miss is queued for refresh rather than fetched inline
+        let market = self
+            .market_cache
+            .get_markets_by_ids(&[(platform, market_id.to_string())])
+            .pop()
+            .flatten();
+
+        let mut orderbook = self.aggregator.get_orderbook(platform, market_id).await;
+        let mut trades = Vec::new();
+        if let Some(storage) = &self.trade_storage {
+            if orderbook.is_none() {
+                match storage.get_latest_orderbook_snapshot(platform, market_id) {
+                    Ok(stored) => orderbook = stored.map(|s| s.to_orderbook(platform, market_id)),
+                    Err(e) => warn!("Failed to read stored book for {} snapshot: {}", market_id, e),
+                }
+            }
+            match storage.get_recent_trades(platform, market_id, self.trade_limit) {
+                Ok(recent) => trades = recent,
+                Err(e) => warn!("Failed to read trades for {} snapshot: {}", market_id, e),
+            }
+        }
+
+        MarketSnapshot {
+            market,
+            orderbook,
+            trades,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use rust_decimal::Decimal;
+    use terminal_core::{MarketStatus, TerminalError, TradeOutcome};
+    use terminal_kalshi::KalshiClient;
+    use terminal_polymarket::PolymarketClient;
+
+    use crate::{AggregatorConfig, MarketService, MarketSource, OrderbookMetrics, WebSocketState};
+
+    struct FixedSource;
+
+    fn market(id: &str) -> PredictionMarket {
+        PredictionMarket {
+            id: id.to_string(),
+            platform: Platform::Kalshi,
+            ticker: Some(id.to_string()),
+            title: "Will it snow in March?".to_string(),
+            description: None,
+            category: None,
+            yes_price: Decimal::new(42, 2),
+            no_price: Decimal::new(58, 2),
+            volume: Decimal::from(1000),
+            volume_24hr: None,
+            liquidity: None,
+            open_interest: None,
+            close_time: None,
+            created_at: None,
+            status: MarketStatus::Open,
+            image_url: None,
+            url: None,
+            outcome_count: None,
+            leading_outcome: None,
+            is_multi_outcome: false,
+            event_id: None,
+            options_json: None,
+            resolution_source: None,
+            tags: vec![],
+            is_sports: false,
+            is_live: false,
+            score: None,
+            game_period: None,
+            home_team: None,
+            away_team: None,
+            home_odds: None,
+            away_odds: None,
+            spread_line: None,
+            total_line: None,
+            change_1h: None,
+            change_24h: None,
+        }
+    }
+
+    #[async_trait]
+    impl MarketSource for FixedSource {
+        async fn fetch_market(&self, _platform: Platform, market_id: &str) -> Result<PredictionMarket, TerminalError> {
+            Ok(market(market_id))
+        }
+
+        async fn fetch_platform_markets(&self, _platform: Platform) -> Result<Vec<PredictionMarket>, TerminalError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn trade(id: &str, seconds_ago: i64) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: "KXSNOW-25".to_string(),
+            platform: Platform::Kalshi,
+            timestamp: Utc::now() - Duration::seconds(seconds_ago),
+            price: Decimal::new(42, 2),
+            quantity: Decimal::from(10),
+            outcome: TradeOutcome::Yes,
+            side: None,
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_sent_on_subscribe_before_streaming() {
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let market_cache = Arc::new(MarketCache::with_source(":memory:", service.clone(), Arc::new(FixedSource)).await.unwrap());
+        market_cache.refresh_market(Platform::Kalshi, "KXSNOW-25").await.unwrap();
+
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        for (id, seconds_ago) in [("t0", 40), ("t1", 30), ("t2", 20), ("t3", 10)] {
+            storage.store_trade(&trade(id, seconds_ago)).unwrap();
+        }
+        storage
+            .store_orderbook_snapshot(
+                Platform::Kalshi,
+                "KXSNOW-25",
+                r#"[{"price":"0.41","quantity":"100"}]"#,
+                r#"[{"price":"0.43","quantity":"50"}]"#,
+                "[]",
+                "[]",
+                &OrderbookMetrics::default(),
+            )
+            .unwrap();
+
+        // No streamed book yet: the stored one stands in
+        let ws_state = Arc::new(WebSocketState::new(service.clone()));
+        let aggregator = Arc::new(MarketDataAggregator::new(AggregatorConfig::default(), Arc::clone(&ws_state), service));
+        let source: Arc<dyn SnapshotSource> = Arc::new(
+            MarketSnapshotter::new(market_cache, aggregator)
+                .with_trade_storage(storage)
+                .with_trade_limit(3),
+        );
+
+        let client_id = ws_state.subscriptions.connect_client();
+        let (outgoing_tx, mut outgoing_rx) = tokio::sync::mpsc::channel(16);
+        let subscribe = r#"{"type":"subscribe","subscription":{"type":"order_book","platform":"kalshi","market_id":"KXSNOW-25"}}"#;
+        WebSocketState::handle_message(
+            client_id,
+            tokio_tungstenite::tungstenite::Message::Text(subscribe.into()),
+            &ws_state.subscriptions,
+            &outgoing_tx,
+            &None,
+            &None,
+            &None,
+            &None,
+            &Some(Arc::clone(&source)),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Subscribed { .. })));
+        match outgoing_rx.try_recv() {
+            Ok(ServerMessage::Snapshot { platform, market_id, market, orderbook, trades }) => {
+                assert_eq!((platform, market_id.as_str()), (Platform::Kalshi, "KXSNOW-25"));
+                assert_eq!(market.map(|m| m.yes_price), Some(Decimal::new(42, 2)));
+                let orderbook = orderbook.unwrap();
+                assert_eq!(orderbook.best_yes_bid(), Some(Decimal::new(41, 2)));
+                assert_eq!(orderbook.best_yes_ask(), Some(Decimal::new(43, 2)));
+                let ids: Vec<String> = trades.into_iter().map(|t| t.id).collect();
+                assert_eq!(ids, vec!["t1", "t2", "t3"]);
+            }
+            other => panic!("expected a snapshot, got {:?}", other),
+        }
+        assert!(outgoing_rx.try_recv().is_err());
+
+        // Channels without market state get none
+        let stats = r#"{"type":"subscribe","subscription":{"type":"stats","platform":"kalshi","market_id":"KXSNOW-25"}}"#;
+        WebSocketState::handle_message(
+            client_id,
+            tokio_tungstenite::tungstenite::Message::Text(stats.into()),
+            &ws_state.subscriptions,
+            &outgoing_tx,
+            &None,
+            &None,
+            &None,
+            &None,
+            &Some(Arc::clone(&source)),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Subscribed { .. })));
+        assert!(outgoing_rx.try_recv().is_err());
+    }
+}
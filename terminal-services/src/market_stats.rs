@@ -4,21 +4,58 @@
 //! volume, and transaction counts over configurable timeframes.
 
 use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use terminal_core::Platform;
-use tracing::{debug, warn};
+use std::time::Instant;
+use terminal_core::{
+    OrderBookLevel, Platform, PredictionMarket, PriceInterval, SubscriptionChannel, UnifiedMarket,
+};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
 
-use crate::trade_storage::TradeStorage;
+use crate::canary::is_canary_market;
+use crate::market_cache::{normalize_category, MarketCache};
+use crate::candle_service::{fill_stored_candle_gaps, rolling_volatility, CandleService};
+use crate::stats_cache::{StatsCache, StatsCacheConfig, StatsCacheKey, StatsCacheStats};
+use crate::trade_storage::{PriceExtremes, SpreadPoint, TradeBucket, TradeFlow, TradeStorage};
+use crate::websocket::WebSocketState;
+
+/// Order book snapshots older than this mark liquidity as stale (5 minutes)
+const LIQUIDITY_STALE_SECS: i64 = 300;
+
+/// Default cross-platform YES spread (in price units) that flags an
+/// arbitrage candidate
+const DEFAULT_ARBITRAGE_SPREAD: Decimal = Decimal::from_parts(5, 0, 0, false, 2);
+
+/// Price snapshots older than this keep coverage below full (15 minutes)
+const DEFAULT_STALE_AFTER_SECS: i64 = 900;
+
+/// How long a category's volume table serves percentile lookups
+const VOLUME_PERCENTILE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Default trade-size bin edges in USD notional: $0-10, 10-100, 100-1k, 1k-10k, 10k+
+pub const DEFAULT_TRADE_SIZE_BUCKETS: &[f64] = &[10.0, 100.0, 1_000.0, 10_000.0];
+
+/// How often the stats push loop looks for dirtied or due markets
+const STATS_PUSH_TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Pending "market dirtied" notices before senders start dropping them;
+/// dropped notices only leave stats stale until the cache TTL
+const DIRTY_CHANNEL_CAPACITY: usize = 1024;
 
 /// Timeframe for stats calculation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Timeframe {
+    #[serde(rename = "5m")]
+    FiveMin,
     #[serde(rename = "1h")]
     OneHour,
+    #[serde(rename = "4h")]
+    FourHours,
     #[serde(rename = "24h")]
     TwentyFourHours,
     #[serde(rename = "7d")]
@@ -28,10 +65,22 @@ pub enum Timeframe {
 }
 
 impl Timeframe {
+    /// Every timeframe, shortest first
+    pub const ALL: [Timeframe; 6] = [
+        Timeframe::FiveMin,
+        Timeframe::OneHour,
+        Timeframe::FourHours,
+        Timeframe::TwentyFourHours,
+        Timeframe::SevenDays,
+        Timeframe::ThirtyDays,
+    ];
+
     /// Get the duration for this timeframe
     pub fn duration(&self) -> Duration {
         match self {
+            Timeframe::FiveMin => Duration::minutes(5),
             Timeframe::OneHour => Duration::hours(1),
+            Timeframe::FourHours => Duration::hours(4),
             Timeframe::TwentyFourHours => Duration::hours(24),
             Timeframe::SevenDays => Duration::days(7),
             Timeframe::ThirtyDays => Duration::days(30),
@@ -46,7 +95,9 @@ impl Timeframe {
     /// Parse from string
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
+            "5m" => Some(Timeframe::FiveMin),
             "1h" => Some(Timeframe::OneHour),
+            "4h" => Some(Timeframe::FourHours),
             "24h" => Some(Timeframe::TwentyFourHours),
             "7d" => Some(Timeframe::SevenDays),
             "30d" => Some(Timeframe::ThirtyDays),
@@ -57,12 +108,26 @@ impl Timeframe {
     /// Convert to string representation
     pub fn as_str(&self) -> &'static str {
         match self {
+            Timeframe::FiveMin => "5m",
             Timeframe::OneHour => "1h",
+            Timeframe::FourHours => "4h",
             Timeframe::TwentyFourHours => "24h",
             Timeframe::SevenDays => "7d",
             Timeframe::ThirtyDays => "30d",
         }
     }
+
+    /// Short display label (e.g. "4H")
+    pub fn label(&self) -> &'static str {
+        match self {
+            Timeframe::FiveMin => "5M",
+            Timeframe::OneHour => "1H",
+            Timeframe::FourHours => "4H",
+            Timeframe::TwentyFourHours => "24H",
+            Timeframe::SevenDays => "7D",
+            Timeframe::ThirtyDays => "30D",
+        }
+    }
 }
 
 impl std::fmt::Display for Timeframe {
@@ -71,6 +136,394 @@ impl std::fmt::Display for Timeframe {
     }
 }
 
+/// What the stats leaderboard ranks markets by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardMetric {
+    /// Trading volume in the timeframe
+    Volume,
+    /// Size of the YES price move in the timeframe, either direction
+    AbsPriceChange,
+    /// YES plus NO trades in the timeframe
+    TxnCount,
+}
+
+impl LeaderboardMetric {
+    /// Every metric
+    pub const ALL: [LeaderboardMetric; 3] = [
+        LeaderboardMetric::Volume,
+        LeaderboardMetric::AbsPriceChange,
+        LeaderboardMetric::TxnCount,
+    ];
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "volume" => Some(LeaderboardMetric::Volume),
+            "abs_price_change" => Some(LeaderboardMetric::AbsPriceChange),
+            "txn_count" => Some(LeaderboardMetric::TxnCount),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardMetric::Volume => "volume",
+            LeaderboardMetric::AbsPriceChange => "abs_price_change",
+            LeaderboardMetric::TxnCount => "txn_count",
+        }
+    }
+
+    /// The value a market is ranked on
+    fn value(&self, stats: &MarketStats) -> Decimal {
+        match self {
+            LeaderboardMetric::Volume => stats.volume,
+            LeaderboardMetric::AbsPriceChange => stats.price_change.abs(),
+            LeaderboardMetric::TxnCount => Decimal::from(stats.yes_txn_count + stats.no_txn_count),
+        }
+    }
+}
+
+/// Top-of-book liquidity from a market's latest order book snapshot
+///
+/// All fields are None when no snapshot has been stored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookLiquidity {
+    /// YES ask minus YES bid
+    pub spread: Option<Decimal>,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    /// Notional resting on the YES bid side
+    pub bid_depth_usd: Option<Decimal>,
+    /// Notional resting on the YES ask side
+    pub ask_depth_usd: Option<Decimal>,
+    /// True when the snapshot is more than 5 minutes old
+    pub liquidity_stale: bool,
+}
+
+impl BookLiquidity {
+    /// Liquidity from a stored snapshot as seen at `now`
+    fn from_snapshot(point: Option<&SpreadPoint>, now: DateTime<Utc>) -> Self {
+        let Some(point) = point else {
+            return Self::default();
+        };
+        let decimal = |v: f64| Decimal::try_from(v).ok();
+        Self {
+            spread: point.metrics.spread.and_then(decimal),
+            best_bid: point.metrics.best_bid.and_then(decimal),
+            best_ask: point.metrics.best_ask.and_then(decimal),
+            bid_depth_usd: decimal(point.metrics.bid_depth_usd),
+            ask_depth_usd: decimal(point.metrics.ask_depth_usd),
+            liquidity_stale: now.timestamp() - point.timestamp > LIQUIDITY_STALE_SECS,
+        }
+    }
+}
+
+/// Streaks and acceleration over a market's recent hourly closes
+///
+/// All fields are None without enough stored 1h candles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Momentum {
+    /// Consecutive rising 1h closes, ending at the latest candle
+    pub consecutive_up_candles: Option<u32>,
+    /// Consecutive falling 1h closes, ending at the latest candle
+    pub consecutive_down_candles: Option<u32>,
+    /// Latest hourly percent change minus the one before it
+    pub pct_change_acceleration: Option<Decimal>,
+}
+
+impl Momentum {
+    /// Momentum from closes ordered oldest first
+    ///
+    /// A flat close ends both streaks.
+    pub fn from_closes(closes: &[f64]) -> Self {
+        if closes.len() < 2 {
+            return Self::default();
+        }
+
+        let streak = |rising: bool| -> u32 {
+            closes
+                .windows(2)
+                .rev()
+                .take_while(|w| if rising { w[1] > w[0] } else { w[1] < w[0] })
+                .count() as u32
+        };
+
+        let pct_change = |from: f64, to: f64| (from > 0.0).then(|| (to - from) / from * 100.0);
+        let pct_change_acceleration = match closes {
+            [.., a, b, c] => match (pct_change(*a, *b), pct_change(*b, *c)) {
+                (Some(before), Some(latest)) => Decimal::try_from(latest - before).ok(),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        Self {
+            consecutive_up_candles: Some(streak(true)),
+            consecutive_down_candles: Some(streak(false)),
+            pct_change_acceleration,
+        }
+    }
+}
+
+/// Buy/sell imbalance over the trades that report an aggressor side
+///
+/// The ratios and delta are None without sided trades, or when more than half
+/// the trades lack a side (`side_coverage` still says how many had one).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlowImbalance {
+    /// Buy trades / sided trades
+    pub buy_ratio: Option<Decimal>,
+    /// Buy notional / sided notional
+    pub notional_buy_ratio: Option<Decimal>,
+    /// Buy notional minus sell notional
+    pub net_delta: Option<Decimal>,
+    /// Fraction of trades with a side (None without trades)
+    pub side_coverage: Option<Decimal>,
+}
+
+impl FlowImbalance {
+    /// Imbalance from a flow summary and the total number of trades behind it
+    pub fn from_flow(flow: &TradeFlow, trade_count: u32) -> Self {
+        if trade_count == 0 {
+            return Self::default();
+        }
+        let sided = (flow.buy_count + flow.sell_count).min(trade_count);
+        let side_coverage = Some(Decimal::from(sided) / Decimal::from(trade_count));
+        if sided == 0 || (trade_count - sided) * 2 > trade_count {
+            return Self {
+                side_coverage,
+                ..Self::default()
+            };
+        }
+
+        let sided_volume = flow.buy_volume + flow.sell_volume;
+        Self {
+            buy_ratio: Some(Decimal::from(flow.buy_count) / Decimal::from(sided)),
+            notional_buy_ratio: (sided_volume > 0.0)
+                .then(|| Decimal::try_from(flow.buy_volume / sided_volume).ok())
+                .flatten(),
+            net_delta: Decimal::try_from(flow.net_flow).ok(),
+            side_coverage,
+        }
+    }
+}
+
+/// All-time YES price extremes and the current price's distance from the high
+///
+/// Read from the extremes kept in `TradeStorage` as snapshots and candles are
+/// written; all fields are None before the first one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Extremes {
+    pub ath_price: Option<Decimal>,
+    pub atl_price: Option<Decimal>,
+    pub ath_timestamp: Option<DateTime<Utc>>,
+    pub atl_timestamp: Option<DateTime<Utc>>,
+    /// Percent from the ATH to the current price (e.g. -25.0 at 0.45 after a 0.60 high)
+    pub pct_from_ath: Option<Decimal>,
+}
+
+impl Extremes {
+    fn from_stored(extremes: Option<PriceExtremes>) -> Self {
+        let Some(extremes) = extremes else {
+            return Self::default();
+        };
+        Self {
+            ath_price: Decimal::try_from(extremes.ath_price).ok(),
+            atl_price: Decimal::try_from(extremes.atl_price).ok(),
+            ath_timestamp: DateTime::from_timestamp(extremes.ath_timestamp, 0),
+            atl_timestamp: DateTime::from_timestamp(extremes.atl_timestamp, 0),
+            pct_from_ath: None,
+        }
+    }
+
+    /// Measure `pct_from_ath` from a current YES price (left None without one)
+    fn with_current_price(self, yes_price: Decimal) -> Self {
+        let pct_from_ath = match self.ath_price {
+            Some(ath) if ath > Decimal::ZERO && yes_price > Decimal::ZERO => {
+                Some((yes_price - ath) / ath * Decimal::from(100))
+            }
+            _ => None,
+        };
+        Self { pct_from_ath, ..self }
+    }
+}
+
+/// How much of a stats window is backed by stored data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Coverage {
+    /// Trades in the window and a fresh price snapshot
+    Full,
+    /// Some data, but no trades in the window or only a stale snapshot
+    Partial,
+    /// Nothing stored for the market; every figure is a zero default
+    None,
+}
+
+/// Where a market's stats come from and how fresh that data is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataQuality {
+    /// Latest stored trade, in or before the window
+    pub last_trade_at: Option<DateTime<Utc>>,
+    /// Latest stored price snapshot (the stored outcome price for outcome stats)
+    pub last_snapshot_at: Option<DateTime<Utc>>,
+    pub trade_count_in_window: u32,
+    pub coverage: Coverage,
+}
+
+impl DataQuality {
+    /// Grade the data behind a market's stats as seen at `now`
+    fn assess(
+        last_trade_at: Option<DateTime<Utc>>,
+        last_snapshot_at: Option<DateTime<Utc>>,
+        trade_count_in_window: u32,
+        stale_after: Duration,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let snapshot_fresh = last_snapshot_at.is_some_and(|at| now - at <= stale_after);
+        let coverage = if last_trade_at.is_none() && last_snapshot_at.is_none() {
+            Coverage::None
+        } else if trade_count_in_window > 0 && snapshot_fresh {
+            Coverage::Full
+        } else {
+            Coverage::Partial
+        };
+        Self {
+            last_trade_at,
+            last_snapshot_at,
+            trade_count_in_window,
+            coverage,
+        }
+    }
+}
+
+/// Where a market's windowed volume ranks among the markets of its category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumePercentile {
+    /// The market's normalized category
+    pub category: String,
+    /// 0 for the lowest volume to 100 for the highest; ties share their middle rank
+    pub percentile: Decimal,
+    /// Markets in the category, this one included
+    pub category_size: usize,
+}
+
+impl VolumePercentile {
+    /// Rank `volume` among `volumes`, which must include it
+    pub fn rank(category: &str, volume: f64, volumes: &[f64]) -> Option<Self> {
+        let below = volumes.iter().filter(|v| **v < volume).count();
+        let equal = volumes.iter().filter(|v| **v == volume).count();
+        if equal == 0 {
+            return None;
+        }
+        let n = volumes.len();
+        let percentile = if n == 1 {
+            Decimal::from(100)
+        } else {
+            Decimal::from(2 * below + equal - 1) * Decimal::from(100) / Decimal::from(2 * (n - 1))
+        };
+        Some(Self {
+            category: category.to_string(),
+            percentile,
+            category_size: n,
+        })
+    }
+}
+
+/// Cost of buying a USD notional of YES right now, walking the resting asks
+///
+/// The fill fields are None when the latest order book snapshot is missing or
+/// stale, or its asks can't absorb the whole notional.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketImpact {
+    /// USD notional to buy
+    pub notional: Decimal,
+    /// Contracts the notional buys
+    pub contracts: Option<Decimal>,
+    /// Notional / contracts
+    pub avg_fill_price: Option<Decimal>,
+    /// Highest ask level reached
+    pub worst_price: Option<Decimal>,
+    /// Average fill minus the best ask
+    pub slippage: Option<Decimal>,
+    /// Slippage as a percent of the best ask
+    pub slippage_percent: Option<Decimal>,
+}
+
+impl MarketImpact {
+    fn unavailable(notional: Decimal) -> Self {
+        Self {
+            notional,
+            contracts: None,
+            avg_fill_price: None,
+            worst_price: None,
+            slippage: None,
+            slippage_percent: None,
+        }
+    }
+
+    /// Fill `notional` against YES asks, cheapest first
+    pub fn from_asks(asks: &[OrderBookLevel], notional: Decimal) -> Self {
+        let mut asks: Vec<&OrderBookLevel> = asks
+            .iter()
+            .filter(|l| l.price > Decimal::ZERO && l.quantity > Decimal::ZERO)
+            .collect();
+        asks.sort_by(|a, b| a.price.cmp(&b.price));
+        let Some(best_ask) = asks.first().map(|l| l.price) else {
+            return Self::unavailable(notional);
+        };
+
+        let mut remaining = notional;
+        let mut contracts = Decimal::ZERO;
+        let mut worst_price = best_ask;
+        for level in asks {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            worst_price = level.price;
+            let level_notional = level.price * level.quantity;
+            if remaining <= level_notional {
+                contracts += remaining / level.price;
+                remaining = Decimal::ZERO;
+            } else {
+                contracts += level.quantity;
+                remaining -= level_notional;
+            }
+        }
+        if remaining > Decimal::ZERO || contracts <= Decimal::ZERO {
+            return Self::unavailable(notional);
+        }
+
+        let avg_fill_price = notional / contracts;
+        let slippage = avg_fill_price - best_ask;
+        Self {
+            notional,
+            contracts: Some(contracts),
+            avg_fill_price: Some(avg_fill_price),
+            worst_price: Some(worst_price),
+            slippage: Some(slippage),
+            slippage_percent: Some(slippage / best_ask * Decimal::from(100)),
+        }
+    }
+}
+
+/// Trades of one notional size bin, for the retail vs whale breakdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeSizeBucket {
+    /// Smallest trade notional in the bin (inclusive)
+    pub min_notional: Decimal,
+    /// Largest trade notional in the bin (exclusive); None for the top bin
+    pub max_notional: Option<Decimal>,
+    /// Trades in the bin
+    pub count: u32,
+    /// Summed notional (price * quantity) of those trades
+    pub total_notional: Decimal,
+}
+
 /// Market statistics for a specific timeframe
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketStats {
@@ -92,55 +545,559 @@ pub struct MarketStats {
     pub yes_txn_count: u32,
     /// Number of NO trades in the timeframe
     pub no_txn_count: u32,
+    /// Volume-weighted average price in the timeframe (None without trades)
+    pub vwap: Option<Decimal>,
+    /// Time-weighted average price (mean of per-minute closes) in the timeframe
+    pub twap: Option<Decimal>,
+    /// Buy/sell order flow in the timeframe
+    pub flow: TradeFlow,
+    /// Aggressor ratios and net delta for the flow gauge
+    #[serde(flatten)]
+    pub imbalance: FlowImbalance,
+    /// Distinct wallets trading in the timeframe (None when the platform has no addresses)
+    pub unique_traders: Option<u32>,
+    /// Realized volatility of hourly log-returns over the last 24 hours
+    /// (None without stored hourly candles)
+    pub volatility_24h: Option<Decimal>,
+    /// Current spread and top-of-book depth
+    #[serde(flatten)]
+    pub liquidity: BookLiquidity,
+    /// Hourly streaks and acceleration
+    #[serde(flatten)]
+    pub momentum: Momentum,
+    /// All-time high/low and distance from the high
+    #[serde(flatten)]
+    pub extremes: Extremes,
+    /// Windowed volume rank within the market's category (None without a
+    /// known category)
+    #[serde(default)]
+    pub volume_percentile: Option<VolumePercentile>,
+    /// Contracts open as of the latest stored snapshot (Kalshi only)
+    #[serde(default)]
+    pub open_interest: Option<Decimal>,
+    /// Open interest change since the latest snapshot at least 24 hours old
+    /// (Kalshi only)
+    #[serde(default)]
+    pub oi_change_24h: Option<Decimal>,
     /// The timeframe these stats cover
     pub timeframe: Timeframe,
+    /// Outcome these stats are scoped to (multi-outcome markets only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome_id: Option<String>,
+    /// Freshness and coverage of the data behind these stats (None on the
+    /// two-query bulk path)
+    #[serde(default)]
+    pub data_quality: Option<DataQuality>,
+}
+
+/// Stats for one outcome of a multi-outcome market
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutcomeStats {
+    /// Outcome name (e.g. a candidate), when known
+    pub outcome_label: Option<String>,
+    #[serde(flatten)]
+    pub stats: MarketStats,
+}
+
+/// Stats for both legs of a cross-platform market pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedStats {
+    pub unified_id: String,
+    pub timeframe: Timeframe,
+    /// Kalshi leg (None when the pair has no Kalshi market)
+    pub kalshi: Option<MarketStats>,
+    /// Polymarket leg (None when the pair has no Polymarket market)
+    pub polymarket: Option<MarketStats>,
+    /// Kalshi YES minus Polymarket YES (None unless both legs are present)
+    pub price_spread: Option<Decimal>,
+    /// True when the absolute spread exceeds the arbitrage threshold
+    pub arbitrage_candidate: bool,
+    /// True when a leg is missing or has no trades in the timeframe
+    pub partial: bool,
+}
+
+/// One bucket of a market's stats history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsHistoryEntry {
+    /// Bucket start (aligned to a multiple of the bucket size)
+    pub timestamp: DateTime<Utc>,
+    /// Trading volume in the bucket (price * quantity sum)
+    pub volume: Decimal,
+    /// Number of trades in the bucket
+    pub txn_count: u32,
+    /// Last YES price in the bucket: the latest trade, else the latest
+    /// snapshot, else carried over from the previous bucket
+    pub close: Option<Decimal>,
+}
+
+/// Single-market stats that don't depend on the caller's current price,
+/// plus the anchor price the change is measured from
+#[derive(Debug, Clone)]
+struct CachedMarketStats {
+    stats: MarketStats,
+    price_then: Option<f64>,
 }
 
 /// Service for computing market statistics
 pub struct MarketStatsService {
     trade_storage: Arc<TradeStorage>,
+    /// Source of the hourly candles behind momentum (None leaves it out)
+    candle_service: Option<Arc<CandleService>>,
+    cache: StatsCache<CachedMarketStats>,
+    /// Absolute cross-platform spread above which a pair is an arbitrage candidate
+    arbitrage_threshold: Decimal,
+    /// Snapshot age past which `data_quality.coverage` can't be full
+    stale_after: Duration,
+    /// Category membership for volume percentiles
+    market_cache: Option<Arc<MarketCache>>,
+    /// Windowed volume of every market in a category, and when it was read
+    category_volumes: Mutex<HashMap<(String, Timeframe), (Instant, Arc<HashMap<(Platform, String), f64>>)>>,
+    /// Markets with trades stored since the last stats push
+    dirty: Mutex<HashSet<(Platform, String)>>,
+    /// Last stats pushed per `stats` subscribed market, and when they were computed
+    published: Mutex<HashMap<(Platform, String), (Instant, serde_json::Value)>>,
 }
 
 impl MarketStatsService {
     /// Create a new MarketStatsService
-    pub fn new(trade_storage: Arc<TradeStorage>) -> Self {
-        Self { trade_storage }
+    pub fn new(trade_storage: Arc<TradeStorage>, candle_service: Option<Arc<CandleService>>) -> Self {
+        Self::with_cache_config(trade_storage, candle_service, StatsCacheConfig::default())
     }
 
-    /// Get stats for a single market
-    pub fn get_market_stats(
+    /// Create a new MarketStatsService with a custom stats cache configuration
+    pub fn with_cache_config(
+        trade_storage: Arc<TradeStorage>,
+        candle_service: Option<Arc<CandleService>>,
+        cache_config: StatsCacheConfig,
+    ) -> Self {
+        Self {
+            trade_storage,
+            candle_service,
+            cache: StatsCache::new(cache_config),
+            arbitrage_threshold: DEFAULT_ARBITRAGE_SPREAD,
+            stale_after: Duration::seconds(DEFAULT_STALE_AFTER_SECS),
+            market_cache: None,
+            category_volumes: Mutex::new(HashMap::new()),
+            dirty: Mutex::new(HashSet::new()),
+            published: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the cross-platform spread that flags an arbitrage candidate
+    pub fn set_arbitrage_threshold(&mut self, threshold: Decimal) {
+        self.arbitrage_threshold = threshold;
+    }
+
+    /// Set the price snapshot age past which stats coverage is only partial
+    pub fn set_stale_after(&mut self, stale_after: Duration) {
+        self.stale_after = stale_after;
+    }
+
+    /// Look up market categories in this cache for volume percentiles
+    pub fn set_market_cache(&mut self, market_cache: Arc<MarketCache>) {
+        self.market_cache = Some(market_cache);
+    }
+
+    /// Stats cache counters
+    pub fn cache_stats(&self) -> StatsCacheStats {
+        self.cache.stats()
+    }
+
+    /// Drop cached stats of a market after new trades were stored for it
+    pub fn invalidate_market(&self, platform: Platform, market_id: &str) {
+        self.cache.invalidate_market(platform, market_id);
+        self.dirty.lock().insert((platform, market_id.to_string()));
+    }
+
+    /// Markets whose stats are pushed over the WebSocket (those with a `stats` subscriber)
+    pub fn stats_compute_set(&self, ws_state: &WebSocketState) -> Vec<(Platform, String)> {
+        ws_state.subscriptions.subscribed_markets(SubscriptionChannel::Stats)
+    }
+
+    /// Push 24h stats to `stats` subscribers
+    ///
+    /// Recomputes markets that were dirtied by new trades, never pushed, or
+    /// last computed more than `refresh` ago, and broadcasts only the ones
+    /// whose stats changed. Returns the number of updates sent.
+    pub fn push_stats_updates(&self, ws_state: &WebSocketState, refresh: std::time::Duration) -> usize {
+        let watched = self.stats_compute_set(ws_state);
+        let dirty = std::mem::take(&mut *self.dirty.lock());
+
+        let mut published = self.published.lock();
+        published.retain(|key, _| watched.contains(key));
+
+        let mut sent = 0;
+        for (platform, market_id) in watched {
+            let key = (platform, market_id.clone());
+            let due = match published.get(&key) {
+                Some((computed_at, _)) => dirty.contains(&key) || computed_at.elapsed() >= refresh,
+                None => true,
+            };
+            if !due {
+                continue;
+            }
+
+            let (yes_price, no_price) = self.stored_prices(platform, &market_id);
+            let stats = self.get_market_stats(platform, &market_id, yes_price, no_price, Timeframe::TwentyFourHours);
+            let value = match serde_json::to_value(&stats) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("Failed to serialize stats for {:?}/{}: {}", platform, market_id, e);
+                    continue;
+                }
+            };
+
+            let changed = published.get(&key).is_none_or(|(_, last)| *last != value);
+            published.insert(key, (Instant::now(), value.clone()));
+            if changed {
+                ws_state.broadcast_stats_update(platform, market_id, value);
+                sent += 1;
+            }
+        }
+        sent
+    }
+
+    /// Run the stats push loop for `stats` subscribers
+    ///
+    /// Checks every second so dirtied markets go out promptly; unchanged
+    /// markets are recomputed every `refresh`.
+    pub async fn start_stats_updates(self: Arc<Self>, ws_state: Arc<WebSocketState>, refresh: std::time::Duration) {
+        info!("Starting stats updates (refresh every {:?})", refresh);
+        let mut ticker = tokio::time::interval(STATS_PUSH_TICK);
+        loop {
+            ticker.tick().await;
+            let service = Arc::clone(&self);
+            let ws_state = Arc::clone(&ws_state);
+            // Stats read SQLite; keep them off the async workers
+            match tokio::task::spawn_blocking(move || service.push_stats_updates(&ws_state, refresh)).await {
+                Ok(sent) if sent > 0 => debug!("Pushed {} stats updates", sent),
+                Ok(_) => {}
+                Err(e) => warn!("Stats push failed: {}", e),
+            }
+        }
+    }
+
+    /// Current YES/NO prices from the stored price, else the last trade
+    fn stored_prices(&self, platform: Platform, market_id: &str) -> (Decimal, Decimal) {
+        let yes_price = self
+            .trade_storage
+            .get_price(platform, market_id)
+            .ok()
+            .flatten()
+            .and_then(|price| price.yes_price)
+            .and_then(|price| Decimal::try_from(price).ok())
+            .or_else(|| {
+                self.trade_storage
+                    .get_latest_trade(platform, market_id)
+                    .ok()
+                    .flatten()
+                    .map(|trade| trade.price)
+            });
+        match yes_price {
+            Some(yes_price) => (yes_price, Decimal::ONE - yes_price),
+            None => (Decimal::ZERO, Decimal::ZERO),
+        }
+    }
+
+    /// Start draining "market dirtied" notices into cache invalidations
+    ///
+    /// Hand the returned sender to whatever stores trades (the
+    /// `TradeCollector`); the task ends once every sender is dropped.
+    pub fn spawn_invalidation_listener(self: &Arc<Self>) -> mpsc::Sender<(Platform, String)> {
+        let (tx, mut rx) = mpsc::channel::<(Platform, String)>(DIRTY_CHANNEL_CAPACITY);
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Some((platform, market_id)) = rx.recv().await {
+                service.invalidate_market(platform, &market_id);
+            }
+        });
+        tx
+    }
+
+    /// Momentum over the last 48 hours of stored 1h candles
+    fn momentum(&self, platform: Platform, market_id: &str, now: DateTime<Utc>) -> Momentum {
+        let Some(candle_service) = &self.candle_service else {
+            return Momentum::default();
+        };
+        let closes: Vec<f64> = candle_service
+            .get_stored_candles(platform, market_id, None, PriceInterval::OneHour, now - Duration::hours(48), now)
+            .map(|candles| candles.iter().map(|c| c.close).collect())
+            .unwrap_or_default();
+        Momentum::from_closes(&closes)
+    }
+
+    /// Rank a market's windowed volume against every market in its category
+    ///
+    /// The category is the market's own (not its tags), from the market
+    /// cache. The category's volumes come from one bulk trade scan per
+    /// platform and are reused for 5 minutes. None without a market cache,
+    /// a cached market or a category.
+    pub fn get_volume_percentile(
         &self,
         platform: Platform,
         market_id: &str,
-        current_yes_price: Decimal,
-        current_no_price: Decimal,
         timeframe: Timeframe,
-    ) -> MarketStats {
+    ) -> Option<VolumePercentile> {
+        let market_cache = self.market_cache.as_ref()?;
+        let market = market_cache
+            .get_markets_by_ids(&[(platform, market_id.to_string())])
+            .into_iter()
+            .next()
+            .flatten()?;
+        let category = market.category.as_deref().and_then(normalize_category)?;
+
+        let volumes = self.category_volumes(market_cache, &category, timeframe);
+        let volume = *volumes.get(&(platform, market_id.to_string()))?;
+        let all: Vec<f64> = volumes.values().copied().collect();
+        VolumePercentile::rank(&category, volume, &all)
+    }
+
+    /// Windowed volume per market of a category, rescanned once the last scan is stale
+    fn category_volumes(
+        &self,
+        market_cache: &MarketCache,
+        category: &str,
+        timeframe: Timeframe,
+    ) -> Arc<HashMap<(Platform, String), f64>> {
+        let key = (category.to_string(), timeframe);
+        // Held across the scan so concurrent lookups wait for one result
+        let mut tables = self.category_volumes.lock();
+        if let Some((read_at, volumes)) = tables.get(&key) {
+            if read_at.elapsed() < VOLUME_PERCENTILE_TTL {
+                return Arc::clone(volumes);
+            }
+        }
+
+        let mut by_platform: HashMap<Platform, Vec<String>> = HashMap::new();
+        for market in market_cache
+            .get_markets_by_category(category, None, None)
+            .unwrap_or_default()
+        {
+            by_platform.entry(market.platform).or_default().push(market.id);
+        }
+
         let now = Utc::now();
         let from = timeframe.start_time();
+        let mut volumes = HashMap::new();
+        for (platform, market_ids) in by_platform {
+            // Chunked to keep each query's bound parameters modest
+            let traded: HashMap<String, f64> = market_ids
+                .chunks(500)
+                .flat_map(|chunk| {
+                    self.trade_storage
+                        .get_bulk_stats_in_range(platform, chunk, from, now)
+                        .unwrap_or_default()
+                })
+                .map(|s| (s.market_id, s.volume))
+                .collect();
+            for market_id in market_ids {
+                let volume = traded.get(&market_id).copied().unwrap_or(0.0);
+                volumes.insert((platform, market_id), volume);
+            }
+        }
 
-        // Get volume and transaction counts from trade storage
-        let volume = self
+        let volumes = Arc::new(volumes);
+        tables.insert(key, (Instant::now(), Arc::clone(&volumes)));
+        volumes
+    }
+
+    /// Latest stored open interest and its change over 24 hours (Kalshi only)
+    fn open_interest(&self, platform: Platform, market_id: &str, now: DateTime<Utc>) -> (Option<Decimal>, Option<Decimal>) {
+        if platform != Platform::Kalshi {
+            return (None, None);
+        }
+        let at = |time: DateTime<Utc>| {
+            self.trade_storage
+                .get_open_interest_at(platform, market_id, time)
+                .ok()
+                .flatten()
+                .and_then(|snapshot| Decimal::try_from(snapshot.open_interest).ok())
+        };
+        let Some(current) = at(now) else {
+            return (None, None);
+        };
+        let change = at(now - Duration::hours(24)).map(|then| current - then);
+        (Some(current), change)
+    }
+
+    /// Latest stored trade and price snapshot of a market, graded against its window
+    fn data_quality(&self, platform: Platform, market_id: &str, trade_count_in_window: u32, now: DateTime<Utc>) -> DataQuality {
+        let last_trade_at = self
             .trade_storage
-            .get_volume_in_range(platform, market_id, from, now)
-            .unwrap_or(0.0);
+            .get_latest_trade(platform, market_id)
+            .ok()
+            .flatten()
+            .map(|trade| trade.timestamp);
+        let last_snapshot_at = self
+            .trade_storage
+            .get_price_at_time(platform, market_id, now)
+            .ok()
+            .flatten()
+            .and_then(|snapshot| DateTime::from_timestamp(snapshot.timestamp, 0));
+        DataQuality::assess(last_trade_at, last_snapshot_at, trade_count_in_window, self.stale_after, now)
+    }
 
-        let txn_counts = self
+    /// Stored all-time extremes, without `pct_from_ath`
+    fn extremes(&self, platform: Platform, market_id: &str) -> Extremes {
+        Extremes::from_stored(
+            self.trade_storage
+                .get_price_extremes(platform, market_id)
+                .ok()
+                .flatten(),
+        )
+    }
+
+    /// Latest 24h realized volatility from stored hourly candles
+    ///
+    /// Reads 48 hours so flat-filled gaps at the start of the window carry the
+    /// previous close.
+    fn volatility_24h(&self, platform: Platform, market_id: &str, outcome_id: &str) -> Option<Decimal> {
+        let now = Utc::now();
+        let candles = self
             .trade_storage
-            .get_txn_counts_in_range(platform, market_id, from, now)
-            .unwrap_or(crate::trade_storage::TxnCounts {
-                yes_count: 0,
-                no_count: 0,
-            });
+            .get_candles_for_outcome(platform, market_id, outcome_id, "1h", now - Duration::hours(48), now)
+            .ok()?;
+        let candles = fill_stored_candle_gaps(candles, 3600, now.timestamp());
+        rolling_volatility(&candles, 24)
+            .last()
+            .and_then(|(_, v)| Decimal::try_from(*v).ok())
+    }
 
-        // Get historical price for change calculation
-        let (price_change, price_change_percent) = self
+    /// YES price at the start of a timeframe
+    ///
+    /// Uses the first trade in the window when stored trades reach back to
+    /// its start, otherwise the latest price snapshot at or before it; long
+    /// windows usually predate the collected trades.
+    fn price_then(&self, platform: Platform, market_id: &str, from: DateTime<Utc>, now: DateTime<Utc>) -> Option<f64> {
+        let trades_cover = self
             .trade_storage
+            .get_earliest_trade_time(platform, market_id)
+            .ok()
+            .flatten()
+            .is_some_and(|earliest| earliest <= from);
+        if trades_cover {
+            if let Some(price) = self
+                .trade_storage
+                .get_earliest_price_in_range(platform, market_id, from, now)
+                .ok()
+                .flatten()
+            {
+                return Some(price);
+            }
+        }
+
+        self.trade_storage
             .get_price_at_time(platform, market_id, from)
             .ok()
             .flatten()
-            .map(|snapshot| {
-                let old_price = Decimal::try_from(snapshot.yes_price).unwrap_or(current_yes_price);
+            .map(|snapshot| snapshot.yes_price)
+    }
+
+    /// Latest order book liquidity, with the spread falling back to the stored price's
+    fn liquidity(&self, platform: Platform, market_id: &str, now: DateTime<Utc>) -> BookLiquidity {
+        let point = self
+            .trade_storage
+            .get_latest_spread_point(platform, market_id)
+            .ok()
+            .flatten();
+        let mut liquidity = BookLiquidity::from_snapshot(point.as_ref(), now);
+        if liquidity.spread.is_none() {
+            liquidity.spread = self
+                .trade_storage
+                .get_price(platform, market_id)
+                .ok()
+                .flatten()
+                .and_then(|price| price.spread)
+                .and_then(|spread| Decimal::try_from(spread).ok());
+        }
+        liquidity
+    }
+
+    /// Estimate the fill of buying each USD notional of YES against the
+    /// latest stored order book
+    ///
+    /// Every estimate is unavailable when the snapshot is more than 5 minutes
+    /// old; notionals deeper than the stored asks are unavailable on their own.
+    pub fn get_market_impact(&self, platform: Platform, market_id: &str, notional_amounts: &[f64]) -> Vec<MarketImpact> {
+        let now = Utc::now();
+        let asks = self
+            .trade_storage
+            .get_latest_orderbook_snapshot(platform, market_id)
+            .ok()
+            .flatten()
+            .filter(|snapshot| now.timestamp() - snapshot.timestamp <= LIQUIDITY_STALE_SECS)
+            .map(|snapshot| snapshot.yes_ask_levels());
+
+        notional_amounts
+            .iter()
+            .map(|&amount| {
+                let notional = Decimal::try_from(amount).unwrap_or(Decimal::ZERO);
+                match &asks {
+                    Some(asks) => MarketImpact::from_asks(asks, notional),
+                    None => MarketImpact::unavailable(notional),
+                }
+            })
+            .collect()
+    }
+
+    /// Bin a market's trades in the timeframe by notional
+    ///
+    /// `buckets` are the ascending lower bounds of every bin after the first
+    /// (see [`DEFAULT_TRADE_SIZE_BUCKETS`]). Every bin is returned, zeroed
+    /// when the window has no trades in it.
+    pub fn get_trade_size_histogram(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        timeframe: Timeframe,
+        buckets: &[f64],
+    ) -> Vec<TradeSizeBucket> {
+        let bins = self
+            .trade_storage
+            .get_trade_size_bins(platform, market_id, buckets, timeframe.start_time(), Utc::now())
+            .unwrap_or_else(|e| {
+                warn!("Failed to bin trade sizes for {}: {}", market_id, e);
+                vec![(0, 0.0); buckets.len() + 1]
+            });
+
+        let decimal = |value: f64| Decimal::try_from(value).unwrap_or(Decimal::ZERO);
+        bins.into_iter()
+            .enumerate()
+            .map(|(i, (count, notional))| TradeSizeBucket {
+                min_notional: i.checked_sub(1).map_or(Decimal::ZERO, |edge| decimal(buckets[edge])),
+                max_notional: buckets.get(i).map(|&edge| decimal(edge)),
+                count,
+                total_notional: decimal(notional),
+            })
+            .collect()
+    }
+
+    /// Get stats for a single market
+    ///
+    /// Served from the stats cache when fresh; only the current prices and
+    /// the change measured from them are applied per call.
+    pub fn get_market_stats(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        current_yes_price: Decimal,
+        current_no_price: Decimal,
+        timeframe: Timeframe,
+    ) -> MarketStats {
+        let key = StatsCacheKey {
+            platform,
+            market_id: market_id.to_string(),
+            timeframe,
+        };
+        let cached = self
+            .cache
+            .get_or_load(key, || self.compute_market_stats(platform, market_id, timeframe));
+
+        // Get historical price for change calculation
+        let (price_change, price_change_percent) = cached
+            .price_then
+            .map(|then| {
+                let old_price = Decimal::try_from(then).unwrap_or(current_yes_price);
                 let change = current_yes_price - old_price;
                 let percent = if old_price > Decimal::ZERO {
                     (change / old_price) * Decimal::from(100)
@@ -151,28 +1108,332 @@ impl MarketStatsService {
             })
             .unwrap_or((Decimal::ZERO, Decimal::ZERO));
 
+        let extremes = cached.stats.extremes.clone().with_current_price(current_yes_price);
         MarketStats {
-            market_id: market_id.to_string(),
-            platform,
             yes_price: current_yes_price,
             no_price: current_no_price,
             price_change,
             price_change_percent,
-            volume: Decimal::try_from(volume).unwrap_or(Decimal::ZERO),
-            yes_txn_count: txn_counts.yes_count,
-            no_txn_count: txn_counts.no_count,
-            timeframe,
+            extremes,
+            ..cached.stats
         }
     }
 
-    /// Get stats for multiple markets efficiently
-    pub fn get_bulk_market_stats(
-        &self,
-        markets: &[(Platform, String, Decimal, Decimal)], // (platform, market_id, yes_price, no_price)
-        timeframe: Timeframe,
-    ) -> Vec<MarketStats> {
-        if markets.is_empty() {
-            return Vec::new();
+    /// Compute a market's stats from storage, leaving prices for the caller
+    fn compute_market_stats(&self, platform: Platform, market_id: &str, timeframe: Timeframe) -> CachedMarketStats {
+        let now = Utc::now();
+        let from = timeframe.start_time();
+
+        // Get volume and transaction counts from trade storage
+        let volume = self
+            .trade_storage
+            .get_volume_in_range(platform, market_id, from, now)
+            .unwrap_or(0.0);
+
+        let txn_counts = self
+            .trade_storage
+            .get_txn_counts_in_range(platform, market_id, from, now)
+            .unwrap_or(crate::trade_storage::TxnCounts {
+                yes_count: 0,
+                no_count: 0,
+            });
+
+        let vwap = self
+            .trade_storage
+            .get_vwap_in_range(platform, market_id, from, now)
+            .ok()
+            .flatten()
+            .and_then(|v| Decimal::try_from(v).ok());
+
+        let twap = self
+            .trade_storage
+            .get_twap_in_range(platform, market_id, from, now)
+            .ok()
+            .flatten()
+            .and_then(|v| Decimal::try_from(v).ok());
+
+        let flow = self
+            .trade_storage
+            .get_flow_in_range(platform, market_id, from, now)
+            .unwrap_or_default();
+
+        let unique_traders = self
+            .trade_storage
+            .get_unique_trader_count(platform, market_id, from, now)
+            .ok()
+            .flatten();
+
+        let (open_interest, oi_change_24h) = self.open_interest(platform, market_id, now);
+
+        let stats = MarketStats {
+            market_id: market_id.to_string(),
+            platform,
+            yes_price: Decimal::ZERO,
+            no_price: Decimal::ZERO,
+            price_change: Decimal::ZERO,
+            price_change_percent: Decimal::ZERO,
+            volume: Decimal::try_from(volume).unwrap_or(Decimal::ZERO),
+            yes_txn_count: txn_counts.yes_count,
+            no_txn_count: txn_counts.no_count,
+            vwap,
+            twap,
+            flow,
+            imbalance: FlowImbalance::from_flow(&flow, txn_counts.yes_count + txn_counts.no_count),
+            unique_traders,
+            volatility_24h: self.volatility_24h(platform, market_id, ""),
+            liquidity: self.liquidity(platform, market_id, now),
+            momentum: self.momentum(platform, market_id, now),
+            extremes: self.extremes(platform, market_id),
+            volume_percentile: self.get_volume_percentile(platform, market_id, timeframe),
+            open_interest,
+            oi_change_24h,
+            timeframe,
+            outcome_id: None,
+            data_quality: Some(self.data_quality(
+                platform,
+                market_id,
+                txn_counts.yes_count + txn_counts.no_count,
+                now,
+            )),
+        };
+
+        CachedMarketStats {
+            stats,
+            price_then: self.price_then(platform, market_id, from, now),
+        }
+    }
+
+    /// Get stats for each platform leg of a unified market and their price spread
+    ///
+    /// Legs are priced from the unified market's cached markets. A missing leg
+    /// or one without trades still yields the rest (flagged `partial`); the
+    /// spread needs both legs.
+    pub fn get_unified_stats(&self, unified: &UnifiedMarket, timeframe: Timeframe) -> UnifiedStats {
+        let leg_stats = |market: &Option<PredictionMarket>| {
+            market.as_ref().map(|m| {
+                self.get_market_stats(m.platform, &m.id, m.yes_price, m.no_price, timeframe)
+            })
+        };
+        let kalshi = leg_stats(&unified.kalshi);
+        let polymarket = leg_stats(&unified.polymarket);
+
+        let price_spread = match (&unified.kalshi, &unified.polymarket) {
+            (Some(k), Some(p)) => Some(k.yes_price - p.yes_price),
+            _ => None,
+        };
+        let arbitrage_candidate = price_spread.is_some_and(|spread| spread.abs() > self.arbitrage_threshold);
+
+        let traded = |stats: &Option<MarketStats>| {
+            stats
+                .as_ref()
+                .is_some_and(|s| s.yes_txn_count + s.no_txn_count > 0)
+        };
+        let partial = !traded(&kalshi) || !traded(&polymarket);
+
+        UnifiedStats {
+            unified_id: unified.id.clone(),
+            timeframe,
+            kalshi,
+            polymarket,
+            price_spread,
+            arbitrage_candidate,
+            partial,
+        }
+    }
+
+    /// Get a market's volume, trade count and close per bucket over `[from, to]`
+    ///
+    /// Every bucket in the range is listed, oldest first, zeroed when nothing
+    /// traded. Reads one grouped trade query and one grouped snapshot query;
+    /// the close before the first bucket is seeded from the snapshot at or
+    /// before `from`.
+    pub fn get_stats_history(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        bucket: Duration,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Vec<StatsHistoryEntry> {
+        let bucket_secs = bucket.num_seconds().max(1);
+
+        let trade_buckets: HashMap<i64, TradeBucket> = self
+            .trade_storage
+            .get_trade_buckets(platform, market_id, bucket_secs, from, to)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|b| (b.bucket_start, b))
+            .collect();
+        let snapshot_closes: HashMap<i64, f64> = self
+            .trade_storage
+            .get_snapshot_closes(platform, market_id, bucket_secs, from, to)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        let mut close = self
+            .trade_storage
+            .get_price_at_time(platform, market_id, from)
+            .ok()
+            .flatten()
+            .map(|snapshot| snapshot.yes_price);
+
+        let mut entries = Vec::new();
+        let mut start = from.timestamp().div_euclid(bucket_secs) * bucket_secs;
+        while start <= to.timestamp() {
+            let trades = trade_buckets.get(&start);
+            if let Some(price) = trades
+                .map(|b| b.close)
+                .or_else(|| snapshot_closes.get(&start).copied())
+            {
+                close = Some(price);
+            }
+
+            entries.push(StatsHistoryEntry {
+                timestamp: DateTime::from_timestamp(start, 0).unwrap_or(from),
+                volume: trades
+                    .and_then(|b| Decimal::try_from(b.volume).ok())
+                    .unwrap_or(Decimal::ZERO),
+                txn_count: trades.map_or(0, |b| b.trade_count),
+                close: close.and_then(|p| Decimal::try_from(p).ok()),
+            });
+            start += bucket_secs;
+        }
+
+        entries
+    }
+
+    /// Get stats for every outcome of a multi-outcome market
+    ///
+    /// Lists each outcome with a stored price or any stored trade, so outcomes
+    /// without trades in the timeframe come back zeroed rather than missing.
+    /// Ordered by current price (highest first), then outcomes only known from
+    /// trades by id.
+    pub fn get_outcome_stats(&self, platform: Platform, market_id: &str, timeframe: Timeframe) -> Vec<OutcomeStats> {
+        let mut outcomes: Vec<(String, Option<String>, Option<Decimal>)> = self
+            .trade_storage
+            .get_prices_for_market(platform, market_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| (p.outcome_id, p.outcome_label, Decimal::try_from(p.price).ok()))
+            .collect();
+        for outcome_id in self.trade_storage.get_outcome_ids(platform, market_id).unwrap_or_default() {
+            if !outcomes.iter().any(|(id, _, _)| *id == outcome_id) {
+                outcomes.push((outcome_id, None, None));
+            }
+        }
+
+        outcomes
+            .into_iter()
+            .map(|(outcome_id, outcome_label, price)| OutcomeStats {
+                outcome_label,
+                stats: self.get_stats_for_outcome(platform, market_id, &outcome_id, price, timeframe),
+            })
+            .collect()
+    }
+
+    /// Get stats for a single outcome of a multi-outcome market
+    ///
+    /// Every trade on the outcome token counts towards `yes_txn_count`; the
+    /// price change is measured from the first trade in the timeframe. Without
+    /// a current price the last trade's is used.
+    pub fn get_stats_for_outcome(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        current_price: Option<Decimal>,
+        timeframe: Timeframe,
+    ) -> MarketStats {
+        let now = Utc::now();
+        let trades = self
+            .trade_storage
+            .get_trades_for_outcome(platform, market_id, outcome_id, timeframe.start_time(), now)
+            .unwrap_or_default();
+        // The window's trades stand in for the latest trade; outcomes have no
+        // snapshots, so the stored outcome price dates the price instead
+        let last_trade_at = trades.last().map(|t| t.timestamp);
+        let last_price_at = self
+            .trade_storage
+            .get_prices_for_market(platform, market_id)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|p| p.outcome_id == outcome_id)
+            .and_then(|p| DateTime::from_timestamp(p.updated_at, 0));
+        let current_price = current_price
+            .or_else(|| trades.last().map(|t| t.price))
+            .unwrap_or(Decimal::ZERO);
+
+        let volume: Decimal = trades.iter().map(|t| t.price * t.quantity).sum();
+        let quantity: Decimal = trades.iter().map(|t| t.quantity).sum();
+        let vwap = (quantity > Decimal::ZERO).then(|| volume / quantity);
+        let twap = twap_from_trades(&trades);
+        let flow = flow_from_trades(&trades);
+        let unique_traders = {
+            let wallets: std::collections::HashSet<String> = trades
+                .iter()
+                .flat_map(|t| [t.maker_address.as_ref(), t.taker_address.as_ref()])
+                .flatten()
+                .map(|a| a.to_lowercase())
+                .collect();
+            (!wallets.is_empty()).then_some(wallets.len() as u32)
+        };
+
+        let (price_change, price_change_percent) = trades
+            .first()
+            .map(|first| {
+                let change = current_price - first.price;
+                let percent = if first.price > Decimal::ZERO {
+                    (change / first.price) * Decimal::from(100)
+                } else {
+                    Decimal::ZERO
+                };
+                (change, percent)
+            })
+            .unwrap_or((Decimal::ZERO, Decimal::ZERO));
+
+        MarketStats {
+            market_id: market_id.to_string(),
+            platform,
+            yes_price: current_price,
+            no_price: Decimal::ONE - current_price,
+            price_change,
+            price_change_percent,
+            volume,
+            yes_txn_count: trades.len() as u32,
+            no_txn_count: 0,
+            vwap,
+            twap,
+            flow,
+            imbalance: FlowImbalance::from_flow(&flow, trades.len() as u32),
+            unique_traders,
+            volatility_24h: self.volatility_24h(platform, market_id, outcome_id),
+            liquidity: BookLiquidity::default(),
+            momentum: Momentum::default(),
+            extremes: Extremes::default(),
+            volume_percentile: None,
+            open_interest: None,
+            oi_change_24h: None,
+            timeframe,
+            outcome_id: Some(outcome_id.to_string()),
+            data_quality: Some(DataQuality::assess(
+                last_trade_at,
+                last_price_at,
+                trades.len() as u32,
+                self.stale_after,
+                now,
+            )),
+        }
+    }
+
+    /// Get stats for multiple markets efficiently
+    pub fn get_bulk_market_stats(
+        &self,
+        markets: &[(Platform, String, Decimal, Decimal)], // (platform, market_id, yes_price, no_price)
+        timeframe: Timeframe,
+    ) -> Vec<MarketStats> {
+        if markets.is_empty() {
+            return Vec::new();
         }
 
         let now = Utc::now();
@@ -181,6 +1442,10 @@ impl MarketStatsService {
         // Group markets by platform for efficient batch queries
         let mut by_platform: HashMap<Platform, Vec<(String, Decimal, Decimal)>> = HashMap::new();
         for (platform, market_id, yes_price, no_price) in markets {
+            // The synthetic canary market never appears in public stats
+            if is_canary_market(market_id) {
+                continue;
+            }
             by_platform
                 .entry(*platform)
                 .or_default()
@@ -204,6 +1469,20 @@ impl MarketStatsService {
                 .get_prices_at_time_batch(platform, &market_ids, from)
                 .unwrap_or_default();
 
+            let books: HashMap<String, SpreadPoint> = self
+                .trade_storage
+                .get_latest_spread_points_batch(platform, &market_ids)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+
+            let twaps: HashMap<String, f64> = self
+                .trade_storage
+                .get_twap_batch(platform, &market_ids, from, now)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+
             // Build lookup maps
             let trade_stats_map: HashMap<String, _> = trade_stats
                 .into_iter()
@@ -216,10 +1495,11 @@ impl MarketStatsService {
 
             // Build stats for each market
             for (market_id, yes_price, no_price) in market_data {
-                let (volume, yes_count, no_count) = trade_stats_map
+                let (volume, yes_count, no_count, vwap, flow, unique_traders) = trade_stats_map
                     .get(&market_id)
-                    .map(|s| (s.volume, s.yes_count, s.no_count))
-                    .unwrap_or((0.0, 0, 0));
+                    .map(|s| (s.volume, s.yes_count, s.no_count, s.vwap, s.flow, s.unique_traders))
+                    .unwrap_or((0.0, 0, 0, None, TradeFlow::default(), None));
+                let twap = twaps.get(&market_id).copied();
 
                 let (price_change, price_change_percent) = historical_prices_map
                     .get(&market_id)
@@ -235,6 +1515,10 @@ impl MarketStatsService {
                     })
                     .unwrap_or((Decimal::ZERO, Decimal::ZERO));
 
+                let volatility_24h = self.volatility_24h(platform, &market_id, "");
+                let momentum = self.momentum(platform, &market_id, now);
+                let (open_interest, oi_change_24h) = self.open_interest(platform, &market_id, now);
+
                 results.push(MarketStats {
                     market_id,
                     platform,
@@ -245,7 +1529,21 @@ impl MarketStatsService {
                     volume: Decimal::try_from(volume).unwrap_or(Decimal::ZERO),
                     yes_txn_count: yes_count,
                     no_txn_count: no_count,
+                    vwap: vwap.and_then(|v| Decimal::try_from(v).ok()),
+                    twap: twap.and_then(|v| Decimal::try_from(v).ok()),
+                    flow,
+                    imbalance: FlowImbalance::from_flow(&flow, yes_count + no_count),
+                    unique_traders,
+                    volatility_24h,
+                    liquidity: BookLiquidity::from_snapshot(books.get(&market_id), now),
+                    momentum,
+                    extremes: self.extremes(platform, &market_id).with_current_price(yes_price),
+                    volume_percentile: None,
+                    open_interest,
+                    oi_change_24h,
                     timeframe,
+                    outcome_id: None,
+                    data_quality: Some(self.data_quality(platform, &market_id, yes_count + no_count, now)),
                 });
             }
         }
@@ -253,6 +1551,152 @@ impl MarketStatsService {
         results
     }
 
+    /// Rank markets by a metric over a timeframe, highest first
+    ///
+    /// Runs the two-query bulk path once per platform among `markets`, so the
+    /// cost doesn't grow with per-market queries. Price changes are measured
+    /// from `current_prices` (YES); markets without one rank with no change.
+    /// Ties are broken by market id, then platform.
+    pub fn get_leaderboard(
+        &self,
+        markets: &[(Platform, String)],
+        current_prices: &HashMap<(Platform, String), Decimal>,
+        timeframe: Timeframe,
+        metric: LeaderboardMetric,
+        limit: usize,
+    ) -> Vec<MarketStats> {
+        let mut by_platform: HashMap<Platform, Vec<String>> = HashMap::new();
+        for (platform, market_id) in markets {
+            by_platform.entry(*platform).or_default().push(market_id.clone());
+        }
+
+        let mut ranked: Vec<MarketStats> = Vec::new();
+        for (platform, mut market_ids) in by_platform {
+            market_ids.sort();
+            market_ids.dedup();
+            let prices: HashMap<String, Decimal> = market_ids
+                .iter()
+                .filter_map(|id| {
+                    current_prices
+                        .get(&(platform, id.clone()))
+                        .map(|price| (id.clone(), *price))
+                })
+                .collect();
+            ranked.extend(
+                self.get_bulk_stats(platform, &market_ids, &prices, timeframe)
+                    .into_values(),
+            );
+        }
+
+        ranked.sort_by(|a, b| {
+            metric
+                .value(b)
+                .cmp(&metric.value(a))
+                .then_with(|| a.market_id.cmp(&b.market_id))
+                .then_with(|| a.platform.display_name().cmp(b.platform.display_name()))
+        });
+        ranked.truncate(limit);
+        ranked
+    }
+
+    /// Get stats for many markets of a platform in two queries
+    ///
+    /// Reads the batched trade aggregates and the batched price snapshots at
+    /// the start of the timeframe, nothing per market; `twap`,
+    /// `volatility_24h` and the extremes are left out. Price changes are measured from
+    /// `current_prices` (YES), anchored on the snapshot or, without one, the
+    /// first trade in the window. Markets without data come back zeroed.
+    pub fn get_bulk_stats(
+        &self,
+        platform: Platform,
+        market_ids: &[String],
+        current_prices: &HashMap<String, Decimal>,
+        timeframe: Timeframe,
+    ) -> HashMap<String, MarketStats> {
+        let now = Utc::now();
+        let from = timeframe.start_time();
+
+        // The synthetic canary market never appears in public stats
+        let market_ids: Vec<String> = market_ids
+            .iter()
+            .filter(|id| !is_canary_market(id))
+            .cloned()
+            .collect();
+
+        let trade_stats: HashMap<String, _> = self
+            .trade_storage
+            .get_bulk_stats_in_range(platform, &market_ids, from, now)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| (s.market_id.clone(), s))
+            .collect();
+
+        let snapshots: HashMap<String, _> = self
+            .trade_storage
+            .get_prices_at_time_batch(platform, &market_ids, from)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        market_ids
+            .into_iter()
+            .map(|market_id| {
+                let trades = trade_stats.get(&market_id);
+                let yes_price = current_prices.get(&market_id).copied().unwrap_or(Decimal::ZERO);
+
+                let price_then = snapshots
+                    .get(&market_id)
+                    .map(|snapshot| snapshot.yes_price)
+                    .or_else(|| trades.and_then(|t| t.earliest_price))
+                    .and_then(|p| Decimal::try_from(p).ok());
+                let (price_change, price_change_percent) = match price_then {
+                    Some(old_price) if current_prices.contains_key(&market_id) => {
+                        let change = yes_price - old_price;
+                        let percent = if old_price > Decimal::ZERO {
+                            (change / old_price) * Decimal::from(100)
+                        } else {
+                            Decimal::ZERO
+                        };
+                        (change, percent)
+                    }
+                    _ => (Decimal::ZERO, Decimal::ZERO),
+                };
+
+                let stats = MarketStats {
+                    market_id: market_id.clone(),
+                    platform,
+                    yes_price,
+                    no_price: if yes_price > Decimal::ZERO { Decimal::ONE - yes_price } else { Decimal::ZERO },
+                    price_change,
+                    price_change_percent,
+                    volume: trades
+                        .and_then(|t| Decimal::try_from(t.volume).ok())
+                        .unwrap_or(Decimal::ZERO),
+                    yes_txn_count: trades.map_or(0, |t| t.yes_count),
+                    no_txn_count: trades.map_or(0, |t| t.no_count),
+                    vwap: trades.and_then(|t| t.vwap).and_then(|v| Decimal::try_from(v).ok()),
+                    twap: None,
+                    flow: trades.map(|t| t.flow).unwrap_or_default(),
+                    imbalance: trades
+                        .map(|t| FlowImbalance::from_flow(&t.flow, t.yes_count + t.no_count))
+                        .unwrap_or_default(),
+                    unique_traders: trades.and_then(|t| t.unique_traders),
+                    volatility_24h: None,
+                    liquidity: BookLiquidity::default(),
+                    momentum: Momentum::default(),
+                    extremes: Extremes::default(),
+                    volume_percentile: None,
+                    open_interest: None,
+                    oi_change_24h: None,
+                    timeframe,
+                    outcome_id: None,
+                    data_quality: None,
+                };
+                (market_id, stats)
+            })
+            .collect()
+    }
+
     /// Snapshot current prices for all provided markets
     /// Call this periodically (e.g., every 5 minutes) to enable price change calculation
     pub fn snapshot_prices(
@@ -272,24 +1716,1148 @@ impl MarketStatsService {
     }
 }
 
+/// Mean of the last trade price in each minute bucket (trades sorted ascending)
+fn twap_from_trades(trades: &[terminal_core::Trade]) -> Option<Decimal> {
+    let mut closes: Vec<Decimal> = Vec::new();
+    let mut current_bucket = None;
+    for trade in trades {
+        let bucket = trade.timestamp.timestamp() / 60;
+        if current_bucket == Some(bucket) {
+            if let Some(last) = closes.last_mut() {
+                *last = trade.price;
+            }
+        } else {
+            closes.push(trade.price);
+            current_bucket = Some(bucket);
+        }
+    }
+
+    if closes.is_empty() {
+        None
+    } else {
+        Some(closes.iter().sum::<Decimal>() / Decimal::from(closes.len()))
+    }
+}
+
+/// Notional buy/sell flow from a set of trades (unknown side counts as neither)
+fn flow_from_trades(trades: &[terminal_core::Trade]) -> TradeFlow {
+    use rust_decimal::prelude::ToPrimitive;
+    use terminal_core::TradeSide;
+
+    let (mut buy_volume, mut sell_volume, mut buy_count, mut sell_count) = (0.0, 0.0, 0, 0);
+    for trade in trades {
+        let notional = (trade.price * trade.quantity).to_f64().unwrap_or(0.0);
+        match trade.side {
+            Some(TradeSide::Buy) => {
+                buy_volume += notional;
+                buy_count += 1;
+            }
+            Some(TradeSide::Sell) => {
+                sell_volume += notional;
+                sell_count += 1;
+            }
+            None => {}
+        }
+    }
+
+    TradeFlow::new(buy_volume, sell_volume, buy_count, sell_count)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use terminal_core::{Trade, TradeOutcome, TradeSide};
+
+    fn trade_at(id: &str, price: Decimal, timestamp: DateTime<Utc>) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: "m1".to_string(),
+            platform: Platform::Kalshi,
+            timestamp,
+            price,
+            quantity: Decimal::from(100),
+            outcome: TradeOutcome::Yes,
+            side: Some(TradeSide::Buy),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    fn assert_close(actual: Decimal, expected: Decimal) {
+        assert!(
+            (actual - expected).abs() < Decimal::new(1, 6),
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
     #[test]
     fn test_timeframe_parsing() {
+        assert_eq!(Timeframe::from_str("5m"), Some(Timeframe::FiveMin));
         assert_eq!(Timeframe::from_str("1h"), Some(Timeframe::OneHour));
+        assert_eq!(Timeframe::from_str("4H"), Some(Timeframe::FourHours));
         assert_eq!(Timeframe::from_str("24h"), Some(Timeframe::TwentyFourHours));
         assert_eq!(Timeframe::from_str("7d"), Some(Timeframe::SevenDays));
         assert_eq!(Timeframe::from_str("30d"), Some(Timeframe::ThirtyDays));
         assert_eq!(Timeframe::from_str("invalid"), None);
+
+        for timeframe in Timeframe::ALL {
+            assert_eq!(Timeframe::from_str(timeframe.as_str()), Some(timeframe));
+            assert_eq!(timeframe.label(), timeframe.as_str().to_uppercase());
+            let json = serde_json::to_string(&timeframe).unwrap();
+            assert_eq!(json, format!("\"{}\"", timeframe.as_str()));
+        }
     }
 
     #[test]
     fn test_timeframe_duration() {
+        assert_eq!(Timeframe::FiveMin.duration(), Duration::minutes(5));
+        assert_eq!(Timeframe::FourHours.duration(), Duration::hours(4));
         assert_eq!(Timeframe::OneHour.duration(), Duration::hours(1));
         assert_eq!(Timeframe::TwentyFourHours.duration(), Duration::hours(24));
         assert_eq!(Timeframe::SevenDays.duration(), Duration::days(7));
         assert_eq!(Timeframe::ThirtyDays.duration(), Duration::days(30));
     }
+
+    #[test]
+    fn test_bulk_stats_issue_two_queries() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        let market_ids: Vec<String> = (0..20).map(|i| format!("m{}", i)).collect();
+
+        // Half the markets traded and were snapshotted an hour ago
+        for (i, market_id) in market_ids.iter().enumerate().take(10) {
+            let mut trade = trade_at(&format!("t{}", i), Decimal::new(60, 2), now - Duration::minutes(30));
+            trade.market_id = market_id.clone();
+            storage.store_trade(&trade).unwrap();
+            storage
+                .store_price_snapshot_at(Platform::Kalshi, market_id, (now - Duration::hours(2)).timestamp(), 0.40, None)
+                .unwrap();
+        }
+        let prices: HashMap<String, Decimal> =
+            market_ids.iter().map(|id| (id.clone(), Decimal::new(50, 2))).collect();
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+
+        let before = storage.read_count();
+        let bulk = service.get_bulk_stats(Platform::Kalshi, &market_ids, &prices, Timeframe::OneHour);
+        let bulk_reads = storage.read_count() - before;
+
+        let before = storage.read_count();
+        for market_id in &market_ids {
+            service.get_market_stats(
+                Platform::Kalshi,
+                market_id,
+                Decimal::new(50, 2),
+                Decimal::new(50, 2),
+                Timeframe::OneHour,
+            );
+        }
+        let per_market_reads = storage.read_count() - before;
+
+        assert_eq!(bulk_reads, 2);
+        assert!(per_market_reads >= market_ids.len() * 8, "{} reads", per_market_reads);
+
+        // Every id is present; ones without data are zeroed
+        assert_eq!(bulk.len(), 20);
+        let traded = &bulk["m3"];
+        assert_eq!(traded.yes_txn_count, 1);
+        assert_close(traded.volume, Decimal::from(60));
+        assert_close(traded.price_change, Decimal::new(10, 2));
+        let quiet = &bulk["m15"];
+        assert_eq!((quiet.yes_txn_count, quiet.volume), (0, Decimal::ZERO));
+        assert_eq!(quiet.price_change, Decimal::ZERO);
+        assert_eq!(quiet.yes_price, Decimal::new(50, 2));
+
+        assert!(service.get_bulk_stats(Platform::Kalshi, &[], &prices, Timeframe::OneHour).is_empty());
+    }
+
+    #[test]
+    fn test_leaderboard_ranking() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+
+        // (market, trade prices) in the last hour; each trade is 100 contracts
+        let seeded: [(&str, &[i64]); 4] = [
+            ("a", &[50]),
+            ("b", &[30, 30, 30]),
+            ("c", &[20, 30]),
+            ("d", &[10, 40]),
+        ];
+        for (market_id, prices) in seeded {
+            for (i, price) in prices.iter().enumerate() {
+                let mut trade = trade_at(
+                    &format!("{}-{}", market_id, i),
+                    Decimal::new(*price, 2),
+                    now - Duration::minutes(30),
+                );
+                trade.market_id = market_id.to_string();
+                storage.store_trade(&trade).unwrap();
+            }
+            storage
+                .store_price_snapshot_at(Platform::Kalshi, market_id, (now - Duration::hours(2)).timestamp(), 0.50, None)
+                .unwrap();
+        }
+
+        let markets: Vec<(Platform, String)> = ["d", "c", "b", "a", "quiet"]
+            .iter()
+            .map(|id| (Platform::Kalshi, id.to_string()))
+            .collect();
+        let current_prices: HashMap<(Platform, String), Decimal> = [("a", 80), ("b", 20), ("c", 60), ("d", 40)]
+            .iter()
+            .map(|(id, price)| ((Platform::Kalshi, id.to_string()), Decimal::new(*price, 2)))
+            .collect();
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+        let ranked = |metric, limit| -> Vec<String> {
+            service
+                .get_leaderboard(&markets, &current_prices, Timeframe::OneHour, metric, limit)
+                .into_iter()
+                .map(|stats| stats.market_id)
+                .collect()
+        };
+
+        // Volumes: a 50, b 90, c 50, d 50; ties fall back to the id
+        assert_eq!(ranked(LeaderboardMetric::Volume, 10), ["b", "a", "c", "d", "quiet"]);
+        // Moves from 0.50: a +0.30, b -0.30, c +0.10, d -0.10
+        assert_eq!(ranked(LeaderboardMetric::AbsPriceChange, 10), ["a", "b", "c", "d", "quiet"]);
+        assert_eq!(ranked(LeaderboardMetric::TxnCount, 2), ["b", "c"]);
+
+        // Two bulk queries regardless of how many markets are ranked
+        let before = storage.read_count();
+        ranked(LeaderboardMetric::Volume, 10);
+        assert_eq!(storage.read_count() - before, 2);
+
+        for metric in LeaderboardMetric::ALL {
+            assert_eq!(LeaderboardMetric::from_str(metric.as_str()), Some(metric));
+        }
+        assert_eq!(LeaderboardMetric::from_str("spread"), None);
+    }
+
+    #[test]
+    fn test_stats_history_daily_buckets() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let day = 86_400;
+        let today = Utc::now().timestamp().div_euclid(day) * day;
+        let at = |days_ago: i64, secs: i64| DateTime::from_timestamp(today - days_ago * day + secs, 0).unwrap();
+
+        // Four days ago: two trades; two days ago: one; three days ago and
+        // yesterday nothing traded, but yesterday has a snapshot
+        storage
+            .store_trades(&[
+                trade_at("a", Decimal::new(40, 2), at(4, 3_600)),
+                trade_at("b", Decimal::new(45, 2), at(4, 7_200)),
+                trade_at("c", Decimal::new(60, 2), at(2, 600)),
+            ])
+            .unwrap();
+        storage
+            .store_price_snapshot_at(Platform::Kalshi, "m1", at(5, 0).timestamp(), 0.35, None)
+            .unwrap();
+        storage
+            .store_price_snapshot_at(Platform::Kalshi, "m1", at(1, 100).timestamp(), 0.70, None)
+            .unwrap();
+
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+        let before = storage.read_count();
+        let history = service.get_stats_history(Platform::Kalshi, "m1", Duration::days(1), at(5, 0), at(0, 60));
+        assert_eq!(storage.read_count() - before, 3);
+
+        let days: Vec<i64> = history.iter().map(|e| e.timestamp.timestamp()).collect();
+        assert_eq!(days, (0..=5).rev().map(|d| today - d * day).collect::<Vec<_>>());
+
+        let counts: Vec<u32> = history.iter().map(|e| e.txn_count).collect();
+        assert_eq!(counts, [0, 2, 0, 1, 0, 0]);
+        for (entry, volume) in history.iter().zip([0, 85, 0, 60, 0, 0]) {
+            assert_close(entry.volume, Decimal::from(volume));
+        }
+
+        // Closes follow the last trade, then the snapshot, carrying forward
+        for (entry, close) in history.iter().zip([35, 45, 45, 60, 70, 70]) {
+            assert_close(entry.close.unwrap(), Decimal::new(close, 2));
+        }
+    }
+
+    #[test]
+    fn test_stats_include_book_liquidity() {
+        use crate::trade_storage::OrderbookMetrics;
+
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now().timestamp();
+        let metrics = OrderbookMetrics {
+            best_bid: Some(0.45),
+            best_ask: Some(0.47),
+            mid: Some(0.46),
+            spread: Some(0.02),
+            bid_depth_usd: 133.0,
+            ask_depth_usd: 73.5,
+        };
+        storage
+            .store_orderbook_snapshot_at(Platform::Kalshi, "fresh", now - 60, "[]", "[]", "[]", "[]", &metrics)
+            .unwrap();
+        storage
+            .store_orderbook_snapshot_at(Platform::Kalshi, "stale", now - 600, "[]", "[]", "[]", "[]", &metrics)
+            .unwrap();
+        let service = MarketStatsService::new(storage, None);
+        let stats = |market_id| {
+            service.get_market_stats(
+                Platform::Kalshi,
+                market_id,
+                Decimal::new(46, 2),
+                Decimal::new(54, 2),
+                Timeframe::OneHour,
+            )
+        };
+
+        let fresh = stats("fresh").liquidity;
+        assert_close(fresh.spread.unwrap(), Decimal::new(2, 2));
+        assert_close(fresh.best_bid.unwrap(), Decimal::new(45, 2));
+        assert_close(fresh.best_ask.unwrap(), Decimal::new(47, 2));
+        assert_close(fresh.bid_depth_usd.unwrap(), Decimal::from(133));
+        assert_close(fresh.ask_depth_usd.unwrap(), Decimal::new(735, 1));
+        assert!(!fresh.liquidity_stale);
+
+        let stale = stats("stale").liquidity;
+        assert!(stale.best_bid.is_some());
+        assert!(stale.liquidity_stale);
+
+        assert_eq!(stats("none").liquidity, BookLiquidity::default());
+
+        // The table path reads the same snapshots in one batch
+        let markets = vec![
+            (Platform::Kalshi, "fresh".to_string(), Decimal::new(46, 2), Decimal::new(54, 2)),
+            (Platform::Kalshi, "none".to_string(), Decimal::new(46, 2), Decimal::new(54, 2)),
+        ];
+        let bulk = service.get_bulk_market_stats(&markets, Timeframe::OneHour);
+        let by_id: HashMap<&str, &MarketStats> = bulk.iter().map(|s| (s.market_id.as_str(), s)).collect();
+        assert_eq!(by_id["fresh"].liquidity, fresh);
+        assert_eq!(by_id["none"].liquidity, BookLiquidity::default());
+
+        // Liquidity fields sit alongside the existing ones
+        let json = serde_json::to_value(&by_id["fresh"]).unwrap();
+        assert!(json.get("bid_depth_usd").is_some());
+        assert_eq!(json["liquidity_stale"], false);
+        assert!(json.get("volume").is_some());
+    }
+
+    #[test]
+    fn test_flow_imbalance_with_mixed_sides() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        let trade = |id: &str, market_id: &str, price: i64, side: Option<TradeSide>| {
+            let mut trade = trade_at(id, Decimal::new(price, 2), now - Duration::minutes(10));
+            trade.market_id = market_id.to_string();
+            trade.side = side;
+            trade
+        };
+
+        // m1: three buys, one sell, one without a side
+        // m2: one buy, two without a side
+        storage
+            .store_trades(&[
+                trade("b1", "m1", 50, Some(TradeSide::Buy)),
+                trade("b2", "m1", 50, Some(TradeSide::Buy)),
+                trade("b3", "m1", 50, Some(TradeSide::Buy)),
+                trade("s1", "m1", 40, Some(TradeSide::Sell)),
+                trade("u1", "m1", 90, None),
+                trade("b4", "m2", 50, Some(TradeSide::Buy)),
+                trade("u2", "m2", 50, None),
+                trade("u3", "m2", 50, None),
+            ])
+            .unwrap();
+
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+        let single = |market_id| {
+            service
+                .get_market_stats(Platform::Kalshi, market_id, Decimal::new(50, 2), Decimal::new(50, 2), Timeframe::OneHour)
+                .imbalance
+        };
+        let market_ids = vec!["m1".to_string(), "m2".to_string(), "quiet".to_string()];
+        let bulk = service.get_bulk_stats(Platform::Kalshi, &market_ids, &HashMap::new(), Timeframe::OneHour);
+
+        for covered in [single("m1"), bulk["m1"].imbalance.clone()] {
+            assert_eq!(covered.buy_ratio, Some(Decimal::new(75, 2)));
+            assert_close(covered.notional_buy_ratio.unwrap(), Decimal::from(150) / Decimal::from(190));
+            assert_close(covered.net_delta.unwrap(), Decimal::from(110));
+            assert_eq!(covered.side_coverage, Some(Decimal::new(8, 1)));
+        }
+
+        for sparse in [single("m2"), bulk["m2"].imbalance.clone()] {
+            assert_eq!(sparse.buy_ratio, None);
+            assert_eq!(sparse.notional_buy_ratio, None);
+            assert_eq!(sparse.net_delta, None);
+            assert_close(sparse.side_coverage.unwrap(), Decimal::ONE / Decimal::from(3));
+        }
+
+        assert_eq!(bulk["quiet"].imbalance, FlowImbalance::default());
+
+        // Exactly half the trades sided still reports the ratios
+        let half = FlowImbalance::from_flow(&TradeFlow::new(0.0, 30.0, 0, 1), 2);
+        assert_eq!(half.buy_ratio, Some(Decimal::ZERO));
+        assert_eq!(half.side_coverage, Some(Decimal::new(5, 1)));
+    }
+
+    fn leg(platform: Platform, id: &str, yes_price: Decimal) -> PredictionMarket {
+        PredictionMarket {
+            id: id.to_string(),
+            platform,
+            ticker: None,
+            title: "Pair".to_string(),
+            description: None,
+            category: None,
+            yes_price,
+            no_price: Decimal::ONE - yes_price,
+            volume: Decimal::ZERO,
+            volume_24hr: None,
+            liquidity: None,
+            open_interest: None,
+            close_time: None,
+            created_at: None,
+            status: terminal_core::MarketStatus::Open,
+            image_url: None,
+            url: None,
+            outcome_count: None,
+            leading_outcome: None,
+            is_multi_outcome: false,
+            event_id: None,
+            options_json: None,
+            resolution_source: None,
+            tags: vec![],
+            is_sports: false,
+            is_live: false,
+            score: None,
+            game_period: None,
+            home_team: None,
+            away_team: None,
+            home_odds: None,
+            away_odds: None,
+            spread_line: None,
+            total_line: None,
+            change_1h: None,
+            change_24h: None,
+        }
+    }
+
+    #[test]
+    fn test_volume_percentile_ranks_with_ties() {
+        let volumes = [0.0, 20.0, 20.0, 40.0, 50.0];
+        let pct = |volume: f64| VolumePercentile::rank("sports", volume, &volumes).unwrap().percentile;
+        assert_eq!(pct(0.0), Decimal::ZERO);
+        // Tied at ranks 1 and 2 of 0..=4: both get 1.5 / 4
+        assert_eq!(pct(20.0), Decimal::new(375, 1));
+        assert_eq!(pct(40.0), Decimal::from(75));
+        assert_eq!(pct(50.0), Decimal::from(100));
+        assert_eq!(VolumePercentile::rank("sports", 50.0, &[50.0]).unwrap().percentile, Decimal::from(100));
+        assert!(VolumePercentile::rank("sports", 30.0, &volumes).is_none());
+    }
+
+    /// Market source serving a fixed set of markets
+    struct FixedSource(Vec<PredictionMarket>);
+
+    #[async_trait::async_trait]
+    impl crate::MarketSource for FixedSource {
+        async fn fetch_market(
+            &self,
+            _platform: Platform,
+            market_id: &str,
+        ) -> Result<PredictionMarket, terminal_core::TerminalError> {
+            self.0
+                .iter()
+                .find(|m| m.id == market_id)
+                .cloned()
+                .ok_or_else(|| terminal_core::TerminalError::not_found(market_id.to_string()))
+        }
+
+        async fn fetch_platform_markets(
+            &self,
+            _platform: Platform,
+        ) -> Result<Vec<PredictionMarket>, terminal_core::TerminalError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_volume_percentile_within_category() {
+        use crate::MarketService;
+        use terminal_kalshi::KalshiClient;
+        use terminal_polymarket::PolymarketClient;
+
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        // Five sports markets with volumes 0 (no trades), 20, 20, 40 and 50
+        let mut markets = Vec::new();
+        let mut trades = Vec::new();
+        for (id, volume) in [("s0", 0), ("s1", 20), ("s2", 20), ("s3", 40), ("s4", 50)] {
+            let mut market = leg(Platform::Polymarket, id, Decimal::new(50, 2));
+            market.category = Some("Sports".to_string());
+            markets.push(market);
+            if volume > 0 {
+                trades.push(Trade {
+                    platform: Platform::Polymarket,
+                    market_id: id.to_string(),
+                    quantity: Decimal::from(volume * 2),
+                    ..trade_at(&format!("t-{}", id), Decimal::new(50, 2), now - Duration::minutes(10))
+                });
+            }
+        }
+        let mut politics = leg(Platform::Polymarket, "p1", Decimal::new(50, 2));
+        politics.category = Some("politics".to_string());
+        markets.push(politics);
+        markets.push(leg(Platform::Polymarket, "uncategorized", Decimal::new(50, 2)));
+        storage.store_trades(&trades).unwrap();
+
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let market_cache = MarketCache::with_source(":memory:", service, Arc::new(FixedSource(markets)))
+            .await
+            .unwrap();
+        market_cache.refresh_platform_now(Platform::Polymarket).await.unwrap();
+
+        let mut stats_service = MarketStatsService::new(Arc::clone(&storage), None);
+        stats_service.set_market_cache(Arc::new(market_cache));
+        let percentile = |id: &str| stats_service.get_volume_percentile(Platform::Polymarket, id, Timeframe::TwentyFourHours);
+
+        let tied = percentile("s1").unwrap();
+        assert_eq!(tied.category, "sports");
+        assert_eq!(tied.category_size, 5);
+        assert_eq!(tied.percentile, Decimal::new(375, 1));
+        assert_eq!(percentile("s2").unwrap().percentile, Decimal::new(375, 1));
+        assert_eq!(percentile("s0").unwrap().percentile, Decimal::ZERO);
+        assert_eq!(percentile("s4").unwrap().percentile, Decimal::from(100));
+        assert_eq!(percentile("p1").unwrap().category_size, 1);
+        assert!(percentile("uncategorized").is_none());
+
+        // The category's volumes are cached: new trades don't move the rank yet
+        storage
+            .store_trades(&[Trade {
+                platform: Platform::Polymarket,
+                market_id: "s0".to_string(),
+                quantity: Decimal::from(1000),
+                ..trade_at("t-late", Decimal::new(50, 2), now - Duration::minutes(1))
+            }])
+            .unwrap();
+        assert_eq!(percentile("s0").unwrap().percentile, Decimal::ZERO);
+
+        let stats = stats_service.get_market_stats(
+            Platform::Polymarket,
+            "s3",
+            Decimal::new(50, 2),
+            Decimal::new(50, 2),
+            Timeframe::TwentyFourHours,
+        );
+        assert_eq!(stats.volume_percentile.map(|p| p.percentile), Some(Decimal::from(75)));
+    }
+
+    #[tokio::test]
+    async fn test_kalshi_open_interest_change_from_refresh() {
+        use crate::MarketService;
+        use terminal_kalshi::types::KalshiMarket;
+        use terminal_kalshi::KalshiClient;
+        use terminal_polymarket::PolymarketClient;
+
+        let fixture = r#"{
+            "ticker": "KXOI-26",
+            "event_ticker": "KXOI",
+            "title": "Open interest fixture",
+            "yes_bid": 40,
+            "yes_ask": 42,
+            "last_price": 41,
+            "volume": 1200,
+            "open_interest": 850,
+            "status": "active"
+        }"#;
+        let market = serde_json::from_str::<KalshiMarket>(fixture).unwrap().to_prediction_market();
+        assert_eq!(market.open_interest, Some(Decimal::from(850)));
+
+        // Open interest recorded with a refresh a day ago
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let day_ago = (Utc::now() - Duration::hours(25)).timestamp();
+        storage
+            .store_open_interest_snapshots_at(day_ago, &[(Platform::Kalshi, "KXOI-26".to_string(), 0.40, Some(0.60), 600.0)])
+            .unwrap();
+
+        let service = MarketService::new(KalshiClient::new(true), PolymarketClient::new());
+        let market_cache = MarketCache::with_source(":memory:", service, Arc::new(FixedSource(vec![market])))
+            .await
+            .unwrap();
+        market_cache.set_trade_storage(Arc::clone(&storage));
+        market_cache.refresh_platform_now(Platform::Kalshi).await.unwrap();
+
+        // The cached market carries it without a stats call
+        let cached = market_cache
+            .get_markets_by_ids(&[(Platform::Kalshi, "KXOI-26".to_string())])
+            .remove(0)
+            .unwrap();
+        assert_eq!(cached.open_interest, Some(Decimal::from(850)));
+
+        let stats_service = MarketStatsService::new(Arc::clone(&storage), None);
+        let stats = stats_service.get_market_stats(
+            Platform::Kalshi,
+            "KXOI-26",
+            cached.yes_price,
+            cached.no_price,
+            Timeframe::TwentyFourHours,
+        );
+        assert_eq!(stats.open_interest, Some(Decimal::from(850)));
+        assert_eq!(stats.oi_change_24h, Some(Decimal::from(250)));
+
+        let polymarket = stats_service.get_market_stats(
+            Platform::Polymarket,
+            "KXOI-26",
+            Decimal::new(50, 2),
+            Decimal::new(50, 2),
+            Timeframe::TwentyFourHours,
+        );
+        assert_eq!(polymarket.open_interest, None);
+    }
+
+    #[test]
+    fn test_trade_size_histogram_bins() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        // Notionals at a price of 0.50: $2, $5, $50, $500, $500 and $25,000
+        let trades: Vec<Trade> = [4, 10, 100, 1_000, 1_000, 50_000]
+            .into_iter()
+            .enumerate()
+            .map(|(i, quantity)| Trade {
+                quantity: Decimal::from(quantity),
+                ..trade_at(&format!("t{}", i), Decimal::new(50, 2), now - Duration::minutes(10))
+            })
+            .collect();
+        storage.store_trades(&trades).unwrap();
+
+        let service = MarketStatsService::new(storage, None);
+        let histogram =
+            service.get_trade_size_histogram(Platform::Kalshi, "m1", Timeframe::TwentyFourHours, DEFAULT_TRADE_SIZE_BUCKETS);
+        let counts: Vec<u32> = histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 1, 2, 0, 1]);
+        let notionals: Vec<Decimal> = histogram.iter().map(|b| b.total_notional).collect();
+        assert_eq!(
+            notionals,
+            vec![Decimal::from(7), Decimal::from(50), Decimal::from(1_000), Decimal::ZERO, Decimal::from(25_000)]
+        );
+        assert_eq!(histogram[0].min_notional, Decimal::ZERO);
+        assert_eq!(histogram[1].min_notional, Decimal::from(10));
+        assert_eq!(histogram[1].max_notional, Some(Decimal::from(100)));
+        assert_eq!(histogram[4].max_notional, None);
+
+        // An empty window still returns every bin
+        let empty =
+            service.get_trade_size_histogram(Platform::Kalshi, "none", Timeframe::TwentyFourHours, DEFAULT_TRADE_SIZE_BUCKETS);
+        assert_eq!(empty.len(), 5);
+        assert!(empty.iter().all(|b| b.count == 0 && b.total_notional.is_zero()));
+    }
+
+    #[test]
+    fn test_unified_stats_for_both_and_one_empty_leg() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        let mut kalshi_trade = trade_at("k1", Decimal::new(60, 2), now - Duration::minutes(20));
+        kalshi_trade.market_id = "K-PAIR".to_string();
+        let mut poly_trade = trade_at("p1", Decimal::new(52, 2), now - Duration::minutes(20));
+        poly_trade.market_id = "poly-pair".to_string();
+        poly_trade.platform = Platform::Polymarket;
+        storage.store_trades(&[kalshi_trade, poly_trade]).unwrap();
+
+        let mut service = MarketStatsService::new(Arc::clone(&storage), None);
+        service.set_arbitrage_threshold(Decimal::new(5, 2));
+
+        // Both legs traded; 0.61 vs 0.53 is past the 0.05 threshold
+        let both = UnifiedMarket::matched(
+            "pair".to_string(),
+            "Pair".to_string(),
+            leg(Platform::Kalshi, "K-PAIR", Decimal::new(61, 2)),
+            leg(Platform::Polymarket, "poly-pair", Decimal::new(53, 2)),
+        );
+        let stats = service.get_unified_stats(&both, Timeframe::OneHour);
+        assert_eq!(stats.kalshi.as_ref().unwrap().yes_txn_count, 1);
+        assert_eq!(stats.polymarket.as_ref().unwrap().yes_txn_count, 1);
+        assert_eq!(stats.price_spread, Some(Decimal::new(8, 2)));
+        assert!(stats.arbitrage_candidate);
+        assert!(!stats.partial);
+
+        // The Polymarket leg has no trades: its stats are zeroed, not an error
+        let one_empty = UnifiedMarket::matched(
+            "quiet".to_string(),
+            "Quiet".to_string(),
+            leg(Platform::Kalshi, "K-PAIR", Decimal::new(50, 2)),
+            leg(Platform::Polymarket, "poly-quiet", Decimal::new(48, 2)),
+        );
+        let stats = service.get_unified_stats(&one_empty, Timeframe::OneHour);
+        assert_eq!(stats.kalshi.as_ref().unwrap().yes_txn_count, 1);
+        assert_eq!(stats.polymarket.as_ref().unwrap().yes_txn_count, 0);
+        assert_eq!(stats.price_spread, Some(Decimal::new(2, 2)));
+        assert!(!stats.arbitrage_candidate);
+        assert!(stats.partial);
+
+        // A single-platform market has no spread
+        let kalshi_only = UnifiedMarket::from_kalshi(leg(Platform::Kalshi, "K-PAIR", Decimal::new(50, 2)));
+        let stats = service.get_unified_stats(&kalshi_only, Timeframe::OneHour);
+        assert!(stats.polymarket.is_none());
+        assert_eq!(stats.price_spread, None);
+        assert!(stats.partial);
+    }
+
+    #[tokio::test]
+    async fn test_stats_channel_pushes_watched_markets() {
+        use crate::MarketService;
+        use terminal_core::{ServerMessage, SubscriptionType};
+        use terminal_kalshi::KalshiClient;
+        use terminal_polymarket::PolymarketClient;
+
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        storage
+            .store_trades(&[trade_at("t1", Decimal::new(40, 2), now - Duration::minutes(30))])
+            .unwrap();
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+        let ws_state = WebSocketState::new(MarketService::new(KalshiClient::new(true), PolymarketClient::new()));
+        let mut rx = ws_state.subscriptions.subscribe_broadcast();
+        let refresh = std::time::Duration::from_secs(60);
+
+        // Nothing is computed until someone subscribes
+        assert!(service.stats_compute_set(&ws_state).is_empty());
+        assert_eq!(service.push_stats_updates(&ws_state, refresh), 0);
+
+        let client = ws_state.subscriptions.new_client_id();
+        let subscription = SubscriptionType::Stats {
+            platform: Platform::Kalshi,
+            market_id: "m1".to_string(),
+        };
+        ws_state.subscriptions.subscribe(client, &subscription);
+        assert_eq!(service.stats_compute_set(&ws_state), [(Platform::Kalshi, "m1".to_string())]);
+
+        assert_eq!(service.push_stats_updates(&ws_state, refresh), 1);
+        match rx.recv().await.unwrap().message {
+            ServerMessage::StatsUpdate { market_id, stats, .. } => {
+                assert_eq!(market_id, "m1");
+                assert_eq!(stats["yes_txn_count"], 1);
+            }
+            other => panic!("expected stats update, got {:?}", other),
+        }
+
+        // Not dirty and not due: nothing is recomputed or sent
+        assert_eq!(service.push_stats_updates(&ws_state, refresh), 0);
+
+        // A new trade dirties the market and the changed stats go out
+        storage
+            .store_trades(&[trade_at("t2", Decimal::new(45, 2), now - Duration::minutes(5))])
+            .unwrap();
+        service.invalidate_market(Platform::Kalshi, "m1");
+        assert_eq!(service.push_stats_updates(&ws_state, refresh), 1);
+        match rx.recv().await.unwrap().message {
+            ServerMessage::StatsUpdate { stats, .. } => assert_eq!(stats["yes_txn_count"], 2),
+            other => panic!("expected stats update, got {:?}", other),
+        }
+
+        // Dirty but unchanged stats aren't re-sent
+        service.invalidate_market(Platform::Kalshi, "m1");
+        assert_eq!(service.push_stats_updates(&ws_state, refresh), 0);
+
+        ws_state.subscriptions.unsubscribe(client, &subscription);
+        assert!(service.stats_compute_set(&ws_state).is_empty());
+        service.invalidate_market(Platform::Kalshi, "m1");
+        assert_eq!(service.push_stats_updates(&ws_state, refresh), 0);
+    }
+
+    #[test]
+    fn test_market_impact_walks_ask_levels() {
+        let asks = vec![
+            OrderBookLevel::new(Decimal::new(60, 2), Decimal::from(100)),
+            OrderBookLevel::new(Decimal::new(50, 2), Decimal::from(100)),
+            OrderBookLevel::new(Decimal::new(80, 2), Decimal::from(50)),
+        ];
+
+        // Inside the best level: no slippage
+        let small = MarketImpact::from_asks(&asks, Decimal::from(25));
+        assert_eq!(small.contracts, Some(Decimal::from(50)));
+        assert_eq!(small.avg_fill_price, Some(Decimal::new(50, 2)));
+        assert_eq!(small.slippage, Some(Decimal::ZERO));
+
+        // $50 clears 0.50, the other $30 buys 50 at 0.60
+        let across = MarketImpact::from_asks(&asks, Decimal::from(80));
+        assert_eq!(across.contracts, Some(Decimal::from(150)));
+        assert_eq!(across.worst_price, Some(Decimal::new(60, 2)));
+        assert_close(across.avg_fill_price.unwrap(), Decimal::from(80) / Decimal::from(150));
+        assert_close(across.slippage_percent.unwrap(), Decimal::from(20) / Decimal::from(3));
+
+        // Exactly two full levels
+        let boundary = MarketImpact::from_asks(&asks, Decimal::from(110));
+        assert_eq!(boundary.contracts, Some(Decimal::from(200)));
+        assert_eq!(boundary.avg_fill_price, Some(Decimal::new(55, 2)));
+        assert_eq!(boundary.worst_price, Some(Decimal::new(60, 2)));
+
+        // Deeper than the $150 of asks
+        assert_eq!(
+            MarketImpact::from_asks(&asks, Decimal::from(200)),
+            MarketImpact::unavailable(Decimal::from(200))
+        );
+        assert_eq!(MarketImpact::from_asks(&[], Decimal::from(10)).avg_fill_price, None);
+    }
+
+    #[test]
+    fn test_market_impact_needs_fresh_snapshot() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+        let asks = serde_json::to_string(&[
+            OrderBookLevel::new(Decimal::new(50, 2), Decimal::from(100)),
+            OrderBookLevel::new(Decimal::new(60, 2), Decimal::from(100)),
+        ])
+        .unwrap();
+        let metrics = crate::OrderbookMetrics::default();
+
+        assert!(service.get_market_impact(Platform::Kalshi, "m1", &[25.0])[0].contracts.is_none());
+
+        let stale_at = Utc::now().timestamp() - 600;
+        storage
+            .store_orderbook_snapshot_at(Platform::Kalshi, "m1", stale_at, "[]", &asks, "[]", "[]", &metrics)
+            .unwrap();
+        assert!(service.get_market_impact(Platform::Kalshi, "m1", &[25.0])[0].contracts.is_none());
+
+        storage
+            .store_orderbook_snapshot(Platform::Kalshi, "m1", "[]", &asks, "[]", "[]", &metrics)
+            .unwrap();
+        let impact = service.get_market_impact(Platform::Kalshi, "m1", &[25.0, 500.0]);
+        assert_eq!(impact[0].avg_fill_price, Some(Decimal::new(50, 2)));
+        assert_eq!(impact[1].notional, Decimal::from(500));
+        assert!(impact[1].avg_fill_price.is_none());
+    }
+
+    #[test]
+    fn test_data_quality_for_fresh_stale_and_empty_markets() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        let trade = |id: &str, market_id: &str, at: DateTime<Utc>| Trade {
+            market_id: market_id.to_string(),
+            ..trade_at(id, Decimal::new(50, 2), at)
+        };
+        storage
+            .store_trades(&[
+                trade("f1", "fresh", now - Duration::minutes(30)),
+                trade("s1", "stale", now - Duration::minutes(30)),
+                trade("q1", "quiet", now - Duration::days(3)),
+            ])
+            .unwrap();
+        let snapshot = |market_id: &str, at: DateTime<Utc>| {
+            storage
+                .store_price_snapshot_at(Platform::Kalshi, market_id, at.timestamp(), 0.50, None)
+                .unwrap()
+        };
+        snapshot("fresh", now - Duration::minutes(1));
+        snapshot("stale", now - Duration::hours(2));
+        snapshot("quiet", now - Duration::minutes(1));
+
+        let mut service = MarketStatsService::new(Arc::clone(&storage), None);
+        let quality = |service: &MarketStatsService, market_id: &str| {
+            let half = Decimal::new(50, 2);
+            service
+                .get_market_stats(Platform::Kalshi, market_id, half, half, Timeframe::TwentyFourHours)
+                .data_quality
+                .unwrap()
+        };
+
+        let fresh = quality(&service, "fresh");
+        assert_eq!(fresh.coverage, Coverage::Full);
+        assert_eq!(fresh.trade_count_in_window, 1);
+        assert_eq!(fresh.last_trade_at.map(|t| t.timestamp()), Some((now - Duration::minutes(30)).timestamp()));
+
+        // Trades in the window but the snapshot is past the 15 minute default
+        let stale = quality(&service, "stale");
+        assert_eq!(stale.coverage, Coverage::Partial);
+        assert_eq!(stale.last_snapshot_at.map(|t| t.timestamp()), Some((now - Duration::hours(2)).timestamp()));
+
+        // A fresh snapshot but the only trade predates the window
+        let quiet = quality(&service, "quiet");
+        assert_eq!((quiet.coverage, quiet.trade_count_in_window), (Coverage::Partial, 0));
+        assert!(quiet.last_trade_at.is_some());
+
+        let empty = quality(&service, "empty");
+        assert_eq!(
+            empty,
+            DataQuality {
+                last_trade_at: None,
+                last_snapshot_at: None,
+                trade_count_in_window: 0,
+                coverage: Coverage::None,
+            }
+        );
+
+        // A looser guard accepts the two hour old snapshot
+        service.set_stale_after(Duration::hours(3));
+        service.invalidate_market(Platform::Kalshi, "stale");
+        assert_eq!(quality(&service, "stale").coverage, Coverage::Full);
+
+        // The bulk path grades too; the two-query path leaves it out
+        let bulk = service.get_bulk_market_stats(
+            &[(Platform::Kalshi, "empty".to_string(), Decimal::ZERO, Decimal::ZERO)],
+            Timeframe::TwentyFourHours,
+        );
+        assert_eq!(bulk[0].data_quality.as_ref().map(|q| q.coverage), Some(Coverage::None));
+        let two_query =
+            service.get_bulk_stats(Platform::Kalshi, &["fresh".to_string()], &HashMap::new(), Timeframe::TwentyFourHours);
+        assert!(two_query["fresh"].data_quality.is_none());
+    }
+
+    #[test]
+    fn test_extremes_follow_new_highs() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let service = MarketStatsService::new(Arc::clone(&storage), None);
+        let now = Utc::now().timestamp();
+        let stats_at = |cents: i64| {
+            service.invalidate_market(Platform::Kalshi, "m1");
+            service.get_market_stats(
+                Platform::Kalshi,
+                "m1",
+                Decimal::new(cents, 2),
+                Decimal::new(100 - cents, 2),
+                Timeframe::TwentyFourHours,
+            )
+        };
+
+        let stats = stats_at(45);
+        assert_eq!(stats.extremes, Extremes::default());
+
+        storage.store_price_snapshot_at(Platform::Kalshi, "m1", now - 7200, 0.60, None).unwrap();
+        storage.store_price_snapshot_at(Platform::Kalshi, "m1", now - 3600, 0.30, None).unwrap();
+        let stats = stats_at(45);
+        assert_eq!(stats.extremes.ath_price, Some(Decimal::new(60, 2)));
+        assert_eq!(stats.extremes.atl_price, Some(Decimal::new(30, 2)));
+        assert_eq!(stats.extremes.ath_timestamp, DateTime::from_timestamp(now - 7200, 0));
+        assert_close(stats.extremes.pct_from_ath.unwrap(), Decimal::from(-25));
+
+        // A new high moves the ATH; the low stays
+        storage.store_price_snapshot_at(Platform::Kalshi, "m1", now - 60, 0.80, None).unwrap();
+        let stats = stats_at(80);
+        assert_eq!(stats.extremes.ath_price, Some(Decimal::new(80, 2)));
+        assert_eq!(stats.extremes.ath_timestamp, DateTime::from_timestamp(now - 60, 0));
+        assert_eq!(stats.extremes.atl_price, Some(Decimal::new(30, 2)));
+        assert_eq!(stats.extremes.pct_from_ath, Some(Decimal::ZERO));
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert!(json.get("ath_price").is_some() && json.get("pct_from_ath").is_some());
+    }
+
+    #[test]
+    fn test_momentum_streaks_and_acceleration() {
+        let up = Momentum::from_closes(&[0.50, 0.40, 0.42, 0.45, 0.50]);
+        assert_eq!(up.consecutive_up_candles, Some(3));
+        assert_eq!(up.consecutive_down_candles, Some(0));
+        // +11.11% after +7.14%
+        let accel = up.pct_change_acceleration.unwrap();
+        assert!((accel - Decimal::new(397, 2)).abs() < Decimal::new(1, 2), "{}", accel);
+
+        let down = Momentum::from_closes(&[0.30, 0.60, 0.50, 0.45]);
+        assert_eq!(down.consecutive_up_candles, Some(0));
+        assert_eq!(down.consecutive_down_candles, Some(2));
+        // -10% after -16.67%: falling, but decelerating
+        assert!(down.pct_change_acceleration.unwrap() > Decimal::ZERO);
+
+        // A flat close ends both streaks
+        let flat = Momentum::from_closes(&[0.40, 0.45, 0.45]);
+        assert_eq!(flat.consecutive_up_candles, Some(0));
+        assert_eq!(flat.consecutive_down_candles, Some(0));
+
+        // Two closes give a streak but no acceleration; a zero close has no rate
+        let two = Momentum::from_closes(&[0.40, 0.45]);
+        assert_eq!(two.consecutive_up_candles, Some(1));
+        assert_eq!(two.pct_change_acceleration, None);
+        assert_eq!(Momentum::from_closes(&[0.0, 0.10, 0.20]).pct_change_acceleration, None);
+
+        assert_eq!(Momentum::from_closes(&[0.50]), Momentum::default());
+        assert_eq!(Momentum::from_closes(&[]), Momentum::default());
+    }
+
+    #[test]
+    fn test_stats_momentum_from_stored_candles() {
+        use crate::trade_storage::StoredCandle;
+
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let candle_service = Arc::new(CandleService::new(Arc::clone(&storage)));
+        let hour = Utc::now().timestamp() / 3600 * 3600;
+        for (i, close) in [0.60, 0.55, 0.50, 0.52].into_iter().enumerate() {
+            let candle = StoredCandle {
+                timestamp: hour - 3600 * (3 - i as i64),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 10.0,
+                trade_count: 1,
+            };
+            candle_service
+                .store_candle(Platform::Kalshi, "m1", None, PriceInterval::OneHour, &candle)
+                .unwrap();
+        }
+
+        let stats = |service: &MarketStatsService, market_id| {
+            service
+                .get_market_stats(Platform::Kalshi, market_id, Decimal::new(52, 2), Decimal::new(48, 2), Timeframe::OneHour)
+                .momentum
+        };
+
+        let service = MarketStatsService::new(Arc::clone(&storage), Some(candle_service));
+        let momentum = stats(&service, "m1");
+        assert_eq!(momentum.consecutive_up_candles, Some(1));
+        assert_eq!(momentum.consecutive_down_candles, Some(0));
+        assert!(momentum.pct_change_acceleration.unwrap() > Decimal::ZERO);
+
+        // No candles, or no candle service: left out
+        assert_eq!(stats(&service, "none"), Momentum::default());
+        let without = MarketStatsService::new(storage, None);
+        assert_eq!(stats(&without, "m1"), Momentum::default());
+    }
+
+    #[test]
+    fn test_outcome_stats_list_every_outcome() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        for (outcome_id, label, price) in [("harris", "Harris", 0.55), ("newsom", "Newsom", 0.30), ("shapiro", "Shapiro", 0.15)] {
+            storage
+                .store_price_for_outcome(Platform::Polymarket, "nominee", outcome_id, Some(label), price)
+                .unwrap();
+        }
+
+        let trade = |id: &str, outcome_id: &str, price: i64, ago: Duration| {
+            let mut trade = trade_at(id, Decimal::new(price, 2), now - ago);
+            trade.platform = Platform::Polymarket;
+            trade.market_id = "nominee".to_string();
+            trade.outcome_id = Some(outcome_id.to_string());
+            trade
+        };
+        storage
+            .store_trades(&[
+                trade("h1", "harris", 50, Duration::hours(3)),
+                trade("h2", "harris", 54, Duration::hours(1)),
+                trade("n1", "newsom", 30, Duration::hours(2)),
+                // Outside the window, and an outcome with no stored price
+                trade("s1", "shapiro", 20, Duration::days(3)),
+                trade("w1", "whitmer", 5, Duration::hours(2)),
+            ])
+            .unwrap();
+
+        let service = MarketStatsService::new(storage, None);
+        let outcomes = service.get_outcome_stats(Platform::Polymarket, "nominee", Timeframe::TwentyFourHours);
+        let ids: Vec<&str> = outcomes
+            .iter()
+            .map(|o| o.stats.outcome_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["harris", "newsom", "shapiro", "whitmer"]);
+
+        let harris = &outcomes[0];
+        assert_eq!(harris.outcome_label.as_deref(), Some("Harris"));
+        assert_eq!(harris.stats.yes_txn_count, 2);
+        assert_close(harris.stats.volume, Decimal::from(104));
+        assert_close(harris.stats.yes_price, Decimal::new(55, 2));
+        assert_close(harris.stats.price_change, Decimal::new(5, 2));
+
+        // No trades in the window: listed with zeros
+        let shapiro = &outcomes[2];
+        assert_eq!(shapiro.stats.yes_txn_count, 0);
+        assert_eq!(shapiro.stats.volume, Decimal::ZERO);
+        assert_eq!(shapiro.stats.price_change, Decimal::ZERO);
+        assert_close(shapiro.stats.yes_price, Decimal::new(15, 2));
+
+        // Without a stored price the last trade's is used
+        let whitmer = &outcomes[3];
+        assert_eq!(whitmer.outcome_label, None);
+        assert_eq!(whitmer.stats.yes_price, Decimal::new(5, 2));
+        assert_eq!(whitmer.stats.yes_txn_count, 1);
+
+        assert!(service
+            .get_outcome_stats(Platform::Polymarket, "unknown", Timeframe::TwentyFourHours)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stats_over_seven_days() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+
+        // Trades every 6 hours over the last 3 days, plus one 3 hours ago
+        let mut trades: Vec<Trade> = (0..12)
+            .map(|i| {
+                let at = now - Duration::hours(72 - 6 * i) - Duration::minutes(1);
+                trade_at(&format!("t{}", i), Decimal::new(40 + i, 2), at)
+            })
+            .collect();
+        trades.push(trade_at("recent", Decimal::new(45, 2), now - Duration::hours(3)));
+        storage.store_trades(&trades).unwrap();
+
+        // Daily snapshots going back 10 days; 0.20 a week or more ago
+        for day in 1..=10 {
+            let price = if day >= 7 { 0.20 } else { 0.30 };
+            let at = (now - Duration::days(day)).timestamp();
+            storage
+                .store_price_snapshot_at(Platform::Kalshi, "m1", at, price, None)
+                .unwrap();
+        }
+
+        let service = MarketStatsService::new(storage, None);
+        let stats = |timeframe| {
+            service.get_market_stats(Platform::Kalshi, "m1", Decimal::new(50, 2), Decimal::new(50, 2), timeframe)
+        };
+
+        // Trades don't reach back a week: anchored on the snapshot
+        let week = stats(Timeframe::SevenDays);
+        assert_eq!(week.yes_txn_count, 13);
+        assert_close(week.volume, Decimal::new(591, 0));
+        assert_close(week.price_change, Decimal::new(30, 2));
+        assert_close(week.price_change_percent, Decimal::from(150));
+
+        // Trades cover 4 hours: anchored on the first trade in the window
+        let four_hours = stats(Timeframe::FourHours);
+        assert_eq!(four_hours.yes_txn_count, 1);
+        assert_close(four_hours.price_change, Decimal::new(5, 2));
+
+        // No trades in the last 5 minutes: falls back to the snapshot a day ago
+        let five_min = stats(Timeframe::FiveMin);
+        assert_eq!(five_min.yes_txn_count, 0);
+        assert_close(five_min.price_change, Decimal::new(20, 2));
+
+        // Nothing reaches back 30 days
+        let month = stats(Timeframe::ThirtyDays);
+        assert_eq!(month.yes_txn_count, 13);
+        assert_eq!(month.price_change, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_cached_stats_invalidated_when_market_dirtied() {
+        let storage = Arc::new(TradeStorage::new_in_memory().unwrap());
+        let now = Utc::now();
+        storage
+            .store_trades(&[trade_at("t1", Decimal::new(40, 2), now - Duration::minutes(30))])
+            .unwrap();
+        storage
+            .store_price_snapshot_at(Platform::Kalshi, "m1", (now - Duration::hours(2)).timestamp(), 0.40, None)
+            .unwrap();
+
+        let service = Arc::new(MarketStatsService::new(storage.clone(), None));
+        let dirtied = service.spawn_invalidation_listener();
+        let stats = |yes_price| {
+            service.get_market_stats(Platform::Kalshi, "m1", yes_price, Decimal::ONE - yes_price, Timeframe::OneHour)
+        };
+
+        assert_eq!(stats(Decimal::new(50, 2)).yes_txn_count, 1);
+
+        // A new trade alone is hidden by the cache, but current prices still apply
+        storage
+            .store_trades(&[trade_at("t2", Decimal::new(45, 2), now - Duration::minutes(10))])
+            .unwrap();
+        let cached = stats(Decimal::new(60, 2));
+        assert_eq!(cached.yes_txn_count, 1);
+        assert_eq!(cached.yes_price, Decimal::new(60, 2));
+        assert_close(cached.price_change, Decimal::new(20, 2));
+        assert_eq!(service.cache_stats().hits, 1);
+
+        dirtied.send((Platform::Kalshi, "m1".to_string())).await.unwrap();
+        for _ in 0..50 {
+            if service.cache_stats().invalidations > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(service.cache_stats().invalidations, 1);
+        assert_eq!(stats(Decimal::new(60, 2)).yes_txn_count, 2);
+    }
 }
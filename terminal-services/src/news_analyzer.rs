@@ -13,7 +13,7 @@ use terminal_core::{
 use terminal_research::OpenAIClient;
 use tracing::{debug, instrument};
 
-use crate::MarketCache;
+use crate::{MarketCache, PageRequest};
 
 /// Configuration for the news analyzer
 #[derive(Debug, Clone)]
@@ -68,7 +68,10 @@ impl NewsAnalyzer {
     #[instrument(skip(self, news_item), fields(news_title = %news_item.title))]
     pub async fn analyze_news(&self, mut news_item: NewsItem) -> Result<NewsItem, TerminalError> {
         // Get top markets by volume from cache
-        let markets = self.market_cache.get_markets(Some(Platform::Polymarket));
+        let markets = self
+            .market_cache
+            .get_markets(Some(Platform::Polymarket), PageRequest::all())
+            .markets;
 
         // Take only the top N markets
         let markets: Vec<_> = markets
@@ -146,6 +146,22 @@ impl RateLimiter {
         }
     }
 
+    /// Take the current slot if it is free, without waiting
+    ///
+    /// Returns `false` instead of queueing when the slot is taken; the check
+    /// and the reservation happen under one lock, so concurrent callers can't
+    /// both succeed.
+    pub async fn try_acquire(&self) -> bool {
+        let now_ms = self.instant_to_ms(Instant::now());
+        let mut next_available = self.next_available_ms.lock().await;
+        if now_ms < *next_available {
+            return false;
+        }
+        *next_available = now_ms + self.min_interval.as_millis() as u64;
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
     /// Check if a request can be made immediately without waiting
     ///
     /// Returns `true` if no waiting would be required,
@@ -249,6 +265,27 @@ mod tests {
         assert!(limiter.can_acquire_immediately().await);
     }
 
+    #[tokio::test]
+    async fn test_try_acquire_never_queues() {
+        let limiter = Arc::new(RateLimiter::new(1000, "test"));
+
+        // Concurrent callers: exactly one gets the slot, the rest fail fast
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let limiter = Arc::clone(&limiter);
+            handles.push(tokio::spawn(async move { limiter.try_acquire().await }));
+        }
+        let mut granted = 0;
+        for handle in handles {
+            if handle.await.unwrap() {
+                granted += 1;
+            }
+        }
+        assert_eq!(granted, 1);
+        assert!(!limiter.can_acquire_immediately().await);
+        assert_eq!(limiter.stats().total_requests, 1);
+    }
+
     /// Test that concurrent requests are properly serialized
     /// This is the key test - it verifies the race condition fix
     #[tokio::test]
@@ -0,0 +1,197 @@
+//! Recent history for clients that subscribe mid-stream
+//!
+//! A client subscribing to a slow market would otherwise see nothing until
+//! the next trade or book change. The aggregator keeps the last few trades,
+//! the last book and the last price tick of every market it streams, and the
+//! WebSocket handler sends them (tagged `replay`) to each new subscriber
+//! before any live update. Buffers only exist for streamed markets and are
+//! dropped when the aggregator unsubscribes from the exchange.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use terminal_core::{
+    OrderBook, OrderBookUpdateType, Platform, ServerMessage, SubscriptionChannel, SubscriptionKey, Trade,
+};
+
+use crate::aggregator::MidTick;
+
+/// Default trades kept per market
+pub const DEFAULT_REPLAY_TRADES: usize = 50;
+
+/// Replayable messages for one market
+#[derive(Debug, Default)]
+struct MarketReplay {
+    /// Oldest first
+    trades: VecDeque<ServerMessage>,
+    book: Option<ServerMessage>,
+    tick: Option<ServerMessage>,
+}
+
+/// Last trades, book and price tick per streamed market
+#[derive(Debug)]
+pub struct ReplayBuffer {
+    max_trades: usize,
+    markets: Mutex<HashMap<(Platform, String), MarketReplay>>,
+}
+
+impl Default for ReplayBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLAY_TRADES)
+    }
+}
+
+impl ReplayBuffer {
+    pub fn new(max_trades: usize) -> Self {
+        Self {
+            max_trades,
+            markets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record_trade(&self, trade: &Trade) {
+        if self.max_trades == 0 {
+            return;
+        }
+        let mut markets = self.markets.lock();
+        let trades = &mut markets
+            .entry((trade.platform, trade.market_id.clone()))
+            .or_default()
+            .trades;
+        if trades.len() == self.max_trades {
+            trades.pop_front();
+        }
+        trades.push_back(ServerMessage::TradeUpdate {
+            platform: trade.platform,
+            market_id: trade.market_id.clone(),
+            trade: trade.clone(),
+            replay: true,
+        });
+    }
+
+    pub fn record_book(&self, platform: Platform, market_id: &str, book: &OrderBook) {
+        let message = ServerMessage::OrderBookUpdate {
+            platform,
+            market_id: market_id.to_string(),
+            update_type: OrderBookUpdateType::Snapshot,
+            yes_bids: book.yes_bids.clone(),
+            yes_asks: book.yes_asks.clone(),
+            no_bids: book.no_bids.clone(),
+            no_asks: book.no_asks.clone(),
+            timestamp: book.timestamp,
+            replay: true,
+        };
+        self.markets
+            .lock()
+            .entry((platform, market_id.to_string()))
+            .or_default()
+            .book = Some(message);
+    }
+
+    pub fn record_tick(&self, platform: Platform, market_id: &str, tick: &MidTick) {
+        let message = ServerMessage::PriceTick {
+            platform,
+            market_id: market_id.to_string(),
+            best_bid: tick.best_bid,
+            best_ask: tick.best_ask,
+            mid: tick.mid,
+            timestamp: Utc::now(),
+            replay: true,
+        };
+        self.markets
+            .lock()
+            .entry((platform, market_id.to_string()))
+            .or_default()
+            .tick = Some(message);
+    }
+
+    /// What a new subscriber to `key` is sent before live updates
+    ///
+    /// Trades come oldest first; channels without history get nothing.
+    pub fn replay(&self, key: &SubscriptionKey) -> Vec<ServerMessage> {
+        let markets = self.markets.lock();
+        let Some(market) = markets.get(&(key.platform, key.market_id.clone())) else {
+            return Vec::new();
+        };
+        match key.channel {
+            SubscriptionChannel::Trades => market.trades.iter().cloned().collect(),
+            SubscriptionChannel::OrderBook => market.book.iter().cloned().collect(),
+            SubscriptionChannel::Prices => market.tick.iter().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Drop a market that stopped streaming
+    pub fn forget(&self, platform: Platform, market_id: &str) {
+        self.markets.lock().remove(&(platform, market_id.to_string()));
+    }
+
+    /// Markets with buffered history
+    pub fn len(&self) -> usize {
+        self.markets.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use terminal_core::TradeOutcome;
+
+    fn trade(id: usize) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: "m".to_string(),
+            platform: Platform::Kalshi,
+            timestamp: Utc::now(),
+            price: Decimal::new(50, 2),
+            quantity: Decimal::ONE,
+            outcome: TradeOutcome::Yes,
+            side: None,
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    fn key(channel: SubscriptionChannel) -> SubscriptionKey {
+        SubscriptionKey {
+            platform: Platform::Kalshi,
+            market_id: "m".to_string(),
+            channel,
+        }
+    }
+
+    #[test]
+    fn test_keeps_latest_trades_per_market_until_forgotten() {
+        let buffer = ReplayBuffer::new(3);
+        for id in 0..5 {
+            buffer.record_trade(&trade(id));
+        }
+
+        let ids: Vec<String> = buffer
+            .replay(&key(SubscriptionChannel::Trades))
+            .into_iter()
+            .map(|message| match message {
+                ServerMessage::TradeUpdate { trade, replay, .. } => {
+                    assert!(replay);
+                    trade.id
+                }
+                other => panic!("expected a trade, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(ids, vec!["2", "3", "4"]);
+        // Nothing recorded for the book yet
+        assert!(buffer.replay(&key(SubscriptionChannel::OrderBook)).is_empty());
+
+        buffer.forget(Platform::Kalshi, "m");
+        assert!(buffer.is_empty());
+        assert!(buffer.replay(&key(SubscriptionChannel::Trades)).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Stats Cache
+//!
+//! Short-lived memo of computed market stats keyed by platform, market and
+//! timeframe, built on [`TtlCache`]. Entries expire after a TTL and are
+//! dropped as soon as new trades for their market are stored, so a burst of
+//! requests for a busy market costs one round of SQLite queries.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use terminal_core::Platform;
+
+use crate::market_stats::Timeframe;
+use crate::ttl_cache::TtlCache;
+
+/// Configuration for the stats cache
+#[derive(Debug, Clone)]
+pub struct StatsCacheConfig {
+    /// How long computed stats stay valid without new trades
+    pub ttl: Duration,
+    /// Maximum number of cached entries before LRU eviction
+    pub max_entries: usize,
+}
+
+impl Default for StatsCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(5),
+            max_entries: 1024,
+        }
+    }
+}
+
+/// Stats cache counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StatsCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped because new trades were stored for their market
+    pub invalidations: u64,
+    pub entries: usize,
+}
+
+/// Cache key: one market's stats over one timeframe
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct StatsCacheKey {
+    pub platform: Platform,
+    pub market_id: String,
+    pub timeframe: Timeframe,
+}
+
+/// In-memory TTL/LRU cache for computed stats
+#[derive(Debug)]
+pub(crate) struct StatsCache<V> {
+    inner: TtlCache<StatsCacheKey, V>,
+    invalidations: AtomicU64,
+}
+
+impl<V: Clone> StatsCache<V> {
+    pub(crate) fn new(config: StatsCacheConfig) -> Self {
+        Self {
+            inner: TtlCache::new(config.ttl, config.max_entries),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    /// Return the cached value for `key`, computing it on a miss
+    pub(crate) fn get_or_load(&self, key: StatsCacheKey, load: impl FnOnce() -> V) -> V {
+        self.inner.get_or_compute(key, load)
+    }
+
+    /// Drop every cached timeframe of a market
+    pub(crate) fn invalidate_market(&self, platform: Platform, market_id: &str) {
+        let dropped = self
+            .inner
+            .retain(|key| !(key.platform == platform && key.market_id == market_id));
+        self.invalidations.fetch_add(dropped as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> StatsCacheStats {
+        StatsCacheStats {
+            hits: self.inner.hits(),
+            misses: self.inner.misses(),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            entries: self.inner.entries(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn key(market_id: &str, timeframe: Timeframe) -> StatsCacheKey {
+        StatsCacheKey {
+            platform: Platform::Kalshi,
+            market_id: market_id.to_string(),
+            timeframe,
+        }
+    }
+
+    #[test]
+    fn test_dirty_market_invalidates_every_timeframe() {
+        let cache = StatsCache::new(StatsCacheConfig::default());
+        let loads = AtomicUsize::new(0);
+        let load = || loads.fetch_add(1, Ordering::SeqCst);
+
+        cache.get_or_load(key("m", Timeframe::OneHour), load);
+        cache.get_or_load(key("m", Timeframe::TwentyFourHours), load);
+        cache.get_or_load(key("other", Timeframe::OneHour), load);
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+
+        cache.invalidate_market(Platform::Kalshi, "m");
+        assert_eq!(cache.stats().invalidations, 2);
+        assert_eq!(cache.stats().entries, 1);
+
+        // The dirtied market recomputes, the other is still served from cache
+        cache.get_or_load(key("m", Timeframe::OneHour), load);
+        cache.get_or_load(key("other", Timeframe::OneHour), load);
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+
+        // Same id on another platform is untouched
+        cache.invalidate_market(Platform::Polymarket, "other");
+        assert_eq!(
+            cache.stats(),
+            StatsCacheStats { hits: 1, misses: 4, invalidations: 2, entries: 2 }
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Ticker tape of every trade on tracked markets
+//!
+//! Every trade broadcast to a market's subscribers is mirrored here (once,
+//! however many paths deliver it) and published on the `global_trades`
+//! channel, so the homepage can scroll all activity without subscribing to
+//! each market. Trades are published in windows; when a window holds more
+//! than the rate budget allows, the smallest trades by notional are dropped
+//! first and the rest go out in time order.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use terminal_core::{Platform, Trade};
+
+/// Default tape rate limit
+pub const DEFAULT_TAPE_MAX_PER_SEC: usize = 50;
+
+/// Default publishing window
+pub const DEFAULT_TAPE_WINDOW: Duration = Duration::from_millis(250);
+
+/// Recently seen trade ids kept for de-duplication
+const RECENT_TRADE_IDS: usize = 2048;
+
+/// Tape rate protection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickerTapeConfig {
+    /// Trades per second published across all markets; beyond it the
+    /// smallest are dropped
+    pub max_per_sec: usize,
+    /// How often buffered trades are published
+    pub window: Duration,
+}
+
+impl Default for TickerTapeConfig {
+    fn default() -> Self {
+        Self {
+            max_per_sec: DEFAULT_TAPE_MAX_PER_SEC,
+            window: DEFAULT_TAPE_WINDOW,
+        }
+    }
+}
+
+impl TickerTapeConfig {
+    /// Trades one window may publish
+    fn window_budget(&self) -> usize {
+        ((self.max_per_sec as f64 * self.window.as_secs_f64()).ceil() as usize).max(1)
+    }
+}
+
+/// Tape counters, as reported in the health payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TickerTapeStats {
+    pub published: u64,
+    /// Dropped by rate protection
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct TapeBuffer {
+    pending: Vec<Trade>,
+    seen: HashSet<(Platform, String)>,
+    seen_order: VecDeque<(Platform, String)>,
+}
+
+/// Trades waiting for the next tape window
+#[derive(Debug)]
+pub struct TickerTape {
+    config: TickerTapeConfig,
+    buffer: Mutex<TapeBuffer>,
+    published: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Default for TickerTape {
+    fn default() -> Self {
+        Self::new(TickerTapeConfig::default())
+    }
+}
+
+impl TickerTape {
+    pub fn new(config: TickerTapeConfig) -> Self {
+        Self {
+            config,
+            buffer: Mutex::new(TapeBuffer::default()),
+            published: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> TickerTapeConfig {
+        self.config
+    }
+
+    /// Queue a trade for the tape; a trade already seen is ignored
+    pub fn push(&self, trade: &Trade) {
+        let mut buffer = self.buffer.lock();
+        let id = (trade.platform, trade.id.clone());
+        if !buffer.seen.insert(id.clone()) {
+            return;
+        }
+        buffer.seen_order.push_back(id);
+        if buffer.seen_order.len() > RECENT_TRADE_IDS {
+            if let Some(oldest) = buffer.seen_order.pop_front() {
+                buffer.seen.remove(&oldest);
+            }
+        }
+        buffer.pending.push(trade.clone());
+    }
+
+    /// The trades to publish for this window, oldest first
+    ///
+    /// Over the window's budget, only the largest trades by notional are
+    /// kept.
+    pub fn drain(&self) -> Vec<Trade> {
+        let mut trades = std::mem::take(&mut self.buffer.lock().pending);
+        let budget = self.config.window_budget();
+        if trades.len() > budget {
+            self.dropped.fetch_add((trades.len() - budget) as u64, Ordering::Relaxed);
+            // Largest first (earlier trade on ties), then back to arrival order
+            let mut ranked: Vec<(usize, Trade)> = trades.into_iter().enumerate().collect();
+            ranked.sort_by(|(a_index, a), (b_index, b)| notional(b).cmp(&notional(a)).then(a_index.cmp(b_index)));
+            ranked.truncate(budget);
+            ranked.sort_by_key(|(index, _)| *index);
+            trades = ranked.into_iter().map(|(_, trade)| trade).collect();
+        }
+        self.published.fetch_add(trades.len() as u64, Ordering::Relaxed);
+        trades
+    }
+
+    pub fn stats(&self) -> TickerTapeStats {
+        TickerTapeStats {
+            published: self.published.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Price times size
+pub fn notional(trade: &Trade) -> Decimal {
+    trade.price * trade.quantity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use terminal_core::TradeOutcome;
+
+    fn trade(id: &str, quantity: i64) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: "m".to_string(),
+            platform: Platform::Polymarket,
+            timestamp: Utc::now(),
+            price: Decimal::new(50, 2),
+            quantity: Decimal::from(quantity),
+            outcome: TradeOutcome::Yes,
+            side: None,
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    fn ids(trades: &[Trade]) -> Vec<&str> {
+        trades.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_downsamples_smallest_first_over_the_rate() {
+        // 8/s in 500ms windows: 4 trades a window
+        let tape = TickerTape::new(TickerTapeConfig {
+            max_per_sec: 8,
+            window: Duration::from_millis(500),
+        });
+
+        // Under the rate everything goes out, duplicates once
+        for (id, quantity) in [("a", 1), ("b", 2), ("a", 1)] {
+            tape.push(&trade(id, quantity));
+        }
+        assert_eq!(ids(&tape.drain()), vec!["a", "b"]);
+        assert_eq!(tape.stats(), TickerTapeStats { published: 2, dropped: 0 });
+
+        for (id, quantity) in [("c", 5), ("d", 1), ("e", 9), ("f", 2), ("g", 7), ("h", 3)] {
+            tape.push(&trade(id, quantity));
+        }
+        assert_eq!(ids(&tape.drain()), vec!["c", "e", "g", "h"]);
+        assert_eq!(tape.stats(), TickerTapeStats { published: 6, dropped: 2 });
+        assert!(tape.drain().is_empty());
+    }
+}
@@ -6,12 +6,14 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
-use terminal_core::Platform;
+use chrono::Utc;
+use terminal_core::{Platform, PriceInterval, Trade};
 
+use crate::candle_service::CandleService;
 use crate::market_service::MarketService;
 use crate::trade_storage::TradeStorage;
 use crate::websocket::WebSocketState;
@@ -27,6 +29,8 @@ pub struct TradeCollectorConfig {
     pub collect_kalshi: bool,
     /// Whether to collect from Polymarket
     pub collect_polymarket: bool,
+    /// Candle intervals rebuilt from stored trades after a backfill
+    pub backfill_candle_intervals: Vec<PriceInterval>,
 }
 
 impl Default for TradeCollectorConfig {
@@ -36,6 +40,7 @@ impl Default for TradeCollectorConfig {
             trades_per_request: 50,
             collect_kalshi: true,
             collect_polymarket: true,
+            backfill_candle_intervals: vec![PriceInterval::OneHour, PriceInterval::OneDay],
         }
     }
 }
@@ -45,9 +50,13 @@ pub struct TradeCollector {
     market_service: Arc<MarketService>,
     storage: Arc<TradeStorage>,
     ws_state: Option<Arc<WebSocketState>>,
+    /// Used to rebuild stored candles after a backfill
+    candle_service: Arc<CandleService>,
     config: TradeCollectorConfig,
     /// Markets currently being tracked
     tracked_markets: RwLock<HashSet<(Platform, String)>>,
+    /// Notified with each market that gets new trades stored
+    dirty_tx: Option<mpsc::Sender<(Platform, String)>>,
 }
 
 impl TradeCollector {
@@ -60,10 +69,29 @@ impl TradeCollector {
     ) -> Self {
         Self {
             market_service,
+            candle_service: Arc::new(CandleService::new(storage.clone())),
             storage,
             ws_state,
             config,
             tracked_markets: RwLock::new(HashSet::new()),
+            dirty_tx: None,
+        }
+    }
+
+    /// Set a shared candle service (so rebuilds invalidate its read cache)
+    pub fn set_candle_service(&mut self, candle_service: Arc<CandleService>) {
+        self.candle_service = candle_service;
+    }
+
+    /// Set the "market dirtied" notifier (so stored trades invalidate cached stats)
+    pub fn set_stats_invalidator(&mut self, dirty_tx: mpsc::Sender<(Platform, String)>) {
+        self.dirty_tx = Some(dirty_tx);
+    }
+
+    /// Tell the stats cache a market has new trades; a full channel drops the notice
+    fn mark_dirty(&self, platform: Platform, market_id: &str) {
+        if let Some(ref dirty_tx) = self.dirty_tx {
+            let _ = dirty_tx.try_send((platform, market_id.to_string()));
         }
     }
 
@@ -81,6 +109,12 @@ impl TradeCollector {
         debug!("Stopped tracking market: {:?}/{}", platform, market_id);
     }
 
+    /// Get all markets currently being tracked
+    pub async fn tracked_markets(&self) -> Vec<(Platform, String)> {
+        let markets = self.tracked_markets.read().await;
+        markets.iter().cloned().collect()
+    }
+
     /// Start the background collection loop
     ///
     /// This runs indefinitely, polling for new trades at the configured interval.
@@ -187,18 +221,33 @@ impl TradeCollector {
             return Ok(0);
         }
 
+        self.ingest_trades(platform, market_id, &new_trades)
+    }
+
+    /// Store freshly collected trades for a market and broadcast them
+    ///
+    /// Returns how many were stored; duplicates are skipped.
+    pub fn ingest_trades(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        new_trades: &[Trade],
+    ) -> Result<usize, TradeCollectorError> {
         let new_trade_count = new_trades.len();
 
         // Store the trades
-        let stored = self.storage.store_trades(&new_trades)?;
+        let stored = self.storage.store_trades(new_trades)?;
         info!(
             "Stored {}/{} new trades for {:?}/{}",
             stored, new_trade_count, platform, market_id
         );
+        if stored > 0 {
+            self.mark_dirty(platform, market_id);
+        }
 
         // Broadcast new trades via WebSocket if available
         if let Some(ref ws_state) = self.ws_state {
-            for trade in &new_trades {
+            for trade in new_trades {
                 ws_state.broadcast_trade(trade.clone());
             }
         }
@@ -255,6 +304,9 @@ impl TradeCollector {
             // Store trades (INSERT OR IGNORE to avoid duplicates)
             let stored = self.storage.store_trades(&trade_history.trades)?;
             total_stored += stored;
+            if stored > 0 {
+                self.mark_dirty(platform, market_id);
+            }
 
             debug!(
                 "Backfill page {}: stored {} trades for {:?}/{}",
@@ -277,8 +329,59 @@ impl TradeCollector {
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
 
+        if total_stored > 0 {
+            self.rebuild_candles_after_backfill(platform, market_id);
+        }
+
         Ok(total_stored)
     }
+
+    /// Rebuild stored candles over the full stored trade history of a market
+    ///
+    /// Multi-outcome markets also get one series per outcome token, partitioned
+    /// by the `outcome_id` each trade was stored with.
+    fn rebuild_candles_after_backfill(&self, platform: Platform, market_id: &str) {
+        let from = match self.storage.get_earliest_trade_time(platform, market_id) {
+            Ok(Some(from)) => from,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to read trade range for {:?}/{}: {}", platform, market_id, e);
+                return;
+            }
+        };
+
+        let outcome_ids = self
+            .storage
+            .get_outcome_ids(platform, market_id)
+            .unwrap_or_default();
+        let mut outcomes: Vec<Option<&str>> = vec![None];
+        if outcome_ids.len() > 1 {
+            outcomes.extend(outcome_ids.iter().map(|id| Some(id.as_str())));
+        }
+
+        for outcome in outcomes {
+            for interval in &self.config.backfill_candle_intervals {
+                match self.candle_service.rebuild_candles(platform, market_id, outcome, *interval, from, Utc::now()) {
+                    Ok(count) => debug!(
+                        "Rebuilt {} {} candles for {:?}/{} (outcome {:?})",
+                        count,
+                        interval.as_str(),
+                        platform,
+                        market_id,
+                        outcome
+                    ),
+                    Err(e) => warn!(
+                        "Failed to rebuild {} candles for {:?}/{} (outcome {:?}): {}",
+                        interval.as_str(),
+                        platform,
+                        market_id,
+                        outcome,
+                        e
+                    ),
+                }
+            }
+        }
+    }
 }
 
 /// Errors that can occur during trade collection
@@ -3,15 +3,512 @@
 //! SQLite-based storage for historical trades, enabling price history generation.
 
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use rust_decimal::Decimal;
 use std::path::Path;
-use std::sync::Mutex;
-use terminal_core::{Platform, Trade, TradeOutcome, TradeSide};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use terminal_core::{AlertKind, OrderBook, OrderBookLevel, Platform, Trade, TradeOutcome, TradeSide};
+
+use crate::anomaly::MarketAlert;
+
+/// Schema migrations, applied in order. Index `n` brings the database to
+/// `user_version = n + 1`. Never edit an existing entry; append a new one.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    r#"
+    -- Trades table (existing)
+    CREATE TABLE IF NOT EXISTS trades (
+        id TEXT PRIMARY KEY,
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        price REAL NOT NULL,
+        quantity REAL NOT NULL,
+        outcome TEXT NOT NULL,
+        side TEXT,
+        created_at INTEGER DEFAULT (strftime('%s', 'now'))
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_trades_market
+    ON trades(platform, market_id, timestamp);
+
+    CREATE INDEX IF NOT EXISTS idx_trades_timestamp
+    ON trades(timestamp);
+
+    -- Prices table (current prices for fast lookup)
+    CREATE TABLE IF NOT EXISTS prices (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        yes_price REAL,
+        no_price REAL,
+        spread REAL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (platform, market_id)
+    );
+
+    -- Orderbook snapshots table (for historical depth analysis)
+    CREATE TABLE IF NOT EXISTS orderbook_snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        yes_bids TEXT,
+        yes_asks TEXT,
+        no_bids TEXT,
+        no_asks TEXT
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_ob_market
+    ON orderbook_snapshots(platform, market_id, timestamp);
+
+    -- Pre-computed candles table
+    CREATE TABLE IF NOT EXISTS candles (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        interval TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        open REAL,
+        high REAL,
+        low REAL,
+        close REAL,
+        volume REAL,
+        trade_count INTEGER,
+        PRIMARY KEY (platform, market_id, interval, timestamp)
+    );
+
+    -- Price snapshots table (for historical price change calculation)
+    CREATE TABLE IF NOT EXISTS price_snapshots (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        yes_price REAL NOT NULL,
+        no_price REAL,
+        PRIMARY KEY (platform, market_id, timestamp)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_price_snapshots_lookup
+    ON price_snapshots(platform, market_id, timestamp DESC);
+    "#,
+    // 2: persist on-chain transaction hashes
+    r#"
+    ALTER TABLE trades ADD COLUMN transaction_hash TEXT;
+    "#,
+    // 3: lookup trades by transaction hash
+    r#"
+    CREATE INDEX IF NOT EXISTS idx_trades_txn_hash
+    ON trades(transaction_hash) WHERE transaction_hash IS NOT NULL;
+    "#,
+    // 4: multi-outcome markets (one token per outcome)
+    r#"
+    ALTER TABLE trades ADD COLUMN outcome_id TEXT;
+
+    CREATE INDEX IF NOT EXISTS idx_trades_outcome
+    ON trades(platform, market_id, outcome_id, timestamp);
+
+    CREATE TABLE IF NOT EXISTS outcome_prices (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        outcome_id TEXT NOT NULL,
+        outcome_label TEXT,
+        price REAL NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (platform, market_id, outcome_id)
+    );
+
+    -- Rebuild candles with outcome_id in the key ('' = whole market)
+    CREATE TABLE candles_v2 (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        outcome_id TEXT NOT NULL DEFAULT '',
+        interval TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        open REAL,
+        high REAL,
+        low REAL,
+        close REAL,
+        volume REAL,
+        trade_count INTEGER,
+        PRIMARY KEY (platform, market_id, outcome_id, interval, timestamp)
+    );
+
+    INSERT INTO candles_v2 (platform, market_id, outcome_id, interval, timestamp, open, high, low, close, volume, trade_count)
+    SELECT platform, market_id, '', interval, timestamp, open, high, low, close, volume, trade_count FROM candles;
+
+    DROP TABLE candles;
+    ALTER TABLE candles_v2 RENAME TO candles;
+    "#,
+    // 5: wallet addresses for distinct-trader counts
+    r#"
+    ALTER TABLE trades ADD COLUMN maker_address TEXT;
+    ALTER TABLE trades ADD COLUMN taker_address TEXT;
+    "#,
+    // 6: computed depth metrics on orderbook snapshots
+    r#"
+    ALTER TABLE orderbook_snapshots ADD COLUMN best_bid REAL;
+    ALTER TABLE orderbook_snapshots ADD COLUMN best_ask REAL;
+    ALTER TABLE orderbook_snapshots ADD COLUMN mid REAL;
+    ALTER TABLE orderbook_snapshots ADD COLUMN spread REAL;
+    ALTER TABLE orderbook_snapshots ADD COLUMN bid_depth_usd REAL;
+    ALTER TABLE orderbook_snapshots ADD COLUMN ask_depth_usd REAL;
+    "#,
+    // 7: per-row encoding of orderbook level arrays (0 = JSON text)
+    r#"
+    ALTER TABLE orderbook_snapshots ADD COLUMN encoding INTEGER NOT NULL DEFAULT 0;
+    "#,
+    // 8: final outcomes of resolved markets
+    r#"
+    CREATE TABLE IF NOT EXISTS resolutions (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        resolved_at INTEGER NOT NULL,
+        winning_outcome TEXT,
+        final_price REAL,
+        PRIMARY KEY (platform, market_id)
+    );
+    "#,
+    // 9: all-time YES price extremes, kept current as snapshots and candles are written
+    r#"
+    CREATE TABLE IF NOT EXISTS price_extremes (
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        ath_price REAL NOT NULL,
+        ath_timestamp INTEGER NOT NULL,
+        atl_price REAL NOT NULL,
+        atl_timestamp INTEGER NOT NULL,
+        PRIMARY KEY (platform, market_id)
+    );
+
+    -- Seed from existing history; the bare timestamp comes from the MAX/MIN row
+    INSERT INTO price_extremes (platform, market_id, ath_price, ath_timestamp, atl_price, atl_timestamp)
+    SELECT h.platform, h.market_id, h.price, h.timestamp, l.price, l.timestamp
+    FROM (
+        SELECT platform, market_id, MAX(price) AS price, timestamp FROM (
+            SELECT platform, market_id, yes_price AS price, timestamp FROM price_snapshots
+            UNION ALL
+            SELECT platform, market_id, high, timestamp FROM candles WHERE outcome_id = '' AND high IS NOT NULL
+        ) GROUP BY platform, market_id
+    ) h
+    JOIN (
+        SELECT platform, market_id, MIN(price) AS price, timestamp FROM (
+            SELECT platform, market_id, yes_price AS price, timestamp FROM price_snapshots
+            UNION ALL
+            SELECT platform, market_id, low, timestamp FROM candles WHERE outcome_id = '' AND low IS NOT NULL
+        ) GROUP BY platform, market_id
+    ) l ON h.platform = l.platform AND h.market_id = l.market_id;
+    "#,
+    // 10: Kalshi open interest recorded with each market refresh
+    r#"
+    ALTER TABLE price_snapshots ADD COLUMN open_interest REAL;
+    "#,
+    // 11: unusual-activity alerts raised on the exchange feeds
+    r#"
+    CREATE TABLE IF NOT EXISTS alerts (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        platform TEXT NOT NULL,
+        market_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        magnitude REAL NOT NULL,
+        timestamp INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_alerts_timestamp ON alerts(timestamp);
+    "#,
+    // 12: windows where feed data was lost (reconnects, book resyncs)
+    r#"
+    CREATE TABLE IF NOT EXISTS gaps (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        platform TEXT NOT NULL,
+        market_id TEXT,
+        started_at INTEGER NOT NULL,
+        ended_at INTEGER NOT NULL,
+        reason TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_gaps_ended_at ON gaps(ended_at);
+    "#,
+];
+
+/// Rows for the same fill whichever source printed it: `?1` platform, `?2`
+/// outcome token, `?3` second, `?4` price, `?5` size
+const SAME_FILL: &str =
+    "platform = ?1 AND outcome_id = ?2 AND timestamp = ?3 AND ABS(price - ?4) < 1e-9 AND ABS(quantity - ?5) < 1e-9";
+
+/// Trades read per page by `export_trades`
+const EXPORT_PAGE_SIZE: usize = 1000;
+
+/// Tables holding per-market rows (keyed by platform and market_id)
+const MARKET_TABLES: [&str; 10] = [
+    "trades",
+    "prices",
+    "orderbook_snapshots",
+    "candles",
+    "price_snapshots",
+    "outcome_prices",
+    "resolutions",
+    "price_extremes",
+    "alerts",
+    "gaps",
+];
+
+/// Price snapshots newer than this many days are kept at full resolution
+const SNAPSHOT_FULL_RESOLUTION_DAYS: i64 = 7;
+
+/// Price snapshots older than this many days are kept at one per day (hourly in between)
+const SNAPSHOT_HOURLY_RESOLUTION_DAYS: i64 = 30;
+
+/// Fold a (high, low) pair into a market's all-time extremes
+///
+/// `?3`/`?4` are the high and its timestamp, `?5`/`?6` the low and its
+/// timestamp. Ties keep the earlier extreme.
+const EXTREMES_UPSERT: &str = r#"
+    INSERT INTO price_extremes (platform, market_id, ath_price, ath_timestamp, atl_price, atl_timestamp)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+    ON CONFLICT (platform, market_id) DO UPDATE SET
+        ath_price = MAX(ath_price, excluded.ath_price),
+        ath_timestamp = CASE WHEN excluded.ath_price > ath_price THEN excluded.ath_timestamp ELSE ath_timestamp END,
+        atl_price = MIN(atl_price, excluded.atl_price),
+        atl_timestamp = CASE WHEN excluded.atl_price < atl_price THEN excluded.atl_timestamp ELSE atl_timestamp END
+"#;
+
+/// Highest high and lowest low of a candle batch, each with its bucket timestamp
+fn candle_extremes(candles: &[StoredCandle]) -> Option<((f64, i64), (f64, i64))> {
+    let high = candles
+        .iter()
+        .max_by(|a, b| a.high.total_cmp(&b.high))
+        .map(|c| (c.high, c.timestamp))?;
+    let low = candles
+        .iter()
+        .min_by(|a, b| a.low.total_cmp(&b.low))
+        .map(|c| (c.low, c.timestamp))?;
+    Some((high, low))
+}
+
+/// Column list matching [`trade_from_row`]
+const TRADE_COLUMNS: &str =
+    "id, platform, market_id, timestamp, price, quantity, outcome, side, transaction_hash, outcome_id, \
+     maker_address, taker_address";
+
+/// Map a row selected with [`TRADE_COLUMNS`] into a Trade
+fn trade_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Trade> {
+    let platform_str: String = row.get(1)?;
+    let timestamp: i64 = row.get(3)?;
+    let price: f64 = row.get(4)?;
+    let quantity: f64 = row.get(5)?;
+    let outcome_str: String = row.get(6)?;
+    let side_str: Option<String> = row.get(7)?;
+
+    Ok(Trade {
+        id: row.get(0)?,
+        market_id: row.get(2)?,
+        platform: if platform_str == "kalshi" {
+            Platform::Kalshi
+        } else {
+            Platform::Polymarket
+        },
+        timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
+        price: Decimal::try_from(price).unwrap_or_default(),
+        quantity: Decimal::try_from(quantity).unwrap_or_default(),
+        outcome: if outcome_str == "yes" {
+            TradeOutcome::Yes
+        } else {
+            TradeOutcome::No
+        },
+        side: side_str.map(|s| {
+            if s == "buy" {
+                TradeSide::Buy
+            } else {
+                TradeSide::Sell
+            }
+        }),
+        transaction_hash: row.get(8)?,
+        outcome_id: row.get(9)?,
+        maker_address: row.get(10)?,
+        taker_address: row.get(11)?,
+    })
+}
+
+/// Output format for trade exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeExportFormat {
+    /// CSV with header: id, timestamp, price, quantity, outcome, side
+    Csv,
+    /// One JSON-serialized Trade per line
+    JsonLines,
+}
+
+impl TradeExportFormat {
+    /// Parse from string ("csv", "json", "jsonl")
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Some(TradeExportFormat::Csv),
+            "json" | "jsonl" | "ndjson" => Some(TradeExportFormat::JsonLines),
+            _ => None,
+        }
+    }
+
+    /// MIME type for HTTP responses
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TradeExportFormat::Csv => "text/csv; charset=utf-8",
+            TradeExportFormat::JsonLines => "application/x-ndjson",
+        }
+    }
+
+    /// File extension (without the dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TradeExportFormat::Csv => "csv",
+            TradeExportFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// How orderbook snapshot level arrays are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotEncoding {
+    /// Plain JSON text
+    #[default]
+    Json,
+    /// JSON compressed with deflate, stored as a BLOB
+    JsonDeflate,
+}
+
+impl SnapshotEncoding {
+    /// Parse from string ("json", "deflate")
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(SnapshotEncoding::Json),
+            "deflate" | "json_deflate" => Some(SnapshotEncoding::JsonDeflate),
+            _ => None,
+        }
+    }
+
+    /// Value of the `encoding` column
+    fn as_i64(self) -> i64 {
+        match self {
+            SnapshotEncoding::Json => 0,
+            SnapshotEncoding::JsonDeflate => 1,
+        }
+    }
+
+    fn from_i64(value: i64) -> Option<Self> {
+        match value {
+            0 => Some(SnapshotEncoding::Json),
+            1 => Some(SnapshotEncoding::JsonDeflate),
+            _ => None,
+        }
+    }
+
+    /// Encode a JSON level array for storage
+    fn encode(self, json: &str) -> Result<rusqlite::types::Value, TradeStorageError> {
+        use std::io::Write;
+
+        match self {
+            SnapshotEncoding::Json => Ok(rusqlite::types::Value::Text(json.to_string())),
+            SnapshotEncoding::JsonDeflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(json.as_bytes())
+                    .and_then(|_| encoder.finish())
+                    .map(rusqlite::types::Value::Blob)
+                    .map_err(|e| TradeStorageError::Io(format!("Failed to compress snapshot: {}", e)))
+            }
+        }
+    }
+
+    /// Decode a stored level array back to JSON; `None` if missing or unreadable
+    fn decode(self, value: rusqlite::types::Value) -> Option<String> {
+        use rusqlite::types::Value;
+        use std::io::Read;
+
+        match (self, value) {
+            (SnapshotEncoding::Json, Value::Text(text)) => Some(text),
+            (SnapshotEncoding::JsonDeflate, Value::Blob(bytes)) => {
+                let mut json = String::new();
+                flate2::read::DeflateDecoder::new(bytes.as_slice())
+                    .read_to_string(&mut json)
+                    .ok()?;
+                Some(json)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Row count for a table: `(count, approximate)`
+///
+/// Uses the row estimate ANALYZE leaves in `sqlite_stat1` if present, since an
+/// exact COUNT(*) walks the whole table.
+fn table_row_count(conn: &Connection, table: &str) -> Result<(u64, bool), TradeStorageError> {
+    let estimate: Option<String> = conn
+        .query_row(
+            "SELECT stat FROM sqlite_stat1 WHERE tbl = ?1 LIMIT 1",
+            params![table],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap_or(None);
+
+    if let Some(rows) = estimate
+        .as_deref()
+        .and_then(|stat| stat.split_whitespace().next())
+        .and_then(|n| n.parse::<u64>().ok())
+    {
+        return Ok((rows, true));
+    }
+
+    let count: i64 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0))
+        .map_err(TradeStorageError::Database)?;
+    Ok((count as u64, false))
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or newline
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Configuration for the TradeStorage connection pool
+#[derive(Debug, Clone)]
+pub struct TradeStorageConfig {
+    /// Number of read-only connections (reads run concurrently with the writer)
+    pub read_pool_size: usize,
+    /// How long a connection waits on a locked database before failing
+    pub busy_timeout: Duration,
+    /// Encoding for newly stored orderbook snapshots (existing rows keep theirs)
+    pub snapshot_encoding: SnapshotEncoding,
+}
+
+impl Default for TradeStorageConfig {
+    fn default() -> Self {
+        Self {
+            read_pool_size: 4,
+            busy_timeout: Duration::from_secs(5),
+            snapshot_encoding: SnapshotEncoding::default(),
+        }
+    }
+}
 
 /// Trade storage service using SQLite
+///
+/// Uses a single writer connection plus a small pool of read-only connections.
+/// With WAL mode enabled, reads never block behind writes (and vice versa).
 pub struct TradeStorage {
-    conn: Mutex<Connection>,
+    /// Dedicated connection for all writes
+    writer: Mutex<Connection>,
+    /// Read-only connections (empty for in-memory databases, which use the writer)
+    readers: Vec<Mutex<Connection>>,
+    /// Round-robin cursor for picking a reader
+    next_reader: AtomicUsize,
+    /// Read connections checked out since startup (roughly one per query)
+    reads: AtomicUsize,
+    /// Encoding for newly stored orderbook snapshots
+    snapshot_encoding: SnapshotEncoding,
 }
 
 impl TradeStorage {
@@ -19,123 +516,154 @@ impl TradeStorage {
     ///
     /// Creates the database file and tables if they don't exist.
     pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, TradeStorageError> {
+        Self::with_config(db_path, TradeStorageConfig::default())
+    }
+
+    /// Create a new TradeStorage instance with a custom pool configuration
+    pub fn with_config<P: AsRef<Path>>(
+        db_path: P,
+        config: TradeStorageConfig,
+    ) -> Result<Self, TradeStorageError> {
+        let db_path = db_path.as_ref();
+
         // Ensure parent directory exists
-        if let Some(parent) = db_path.as_ref().parent() {
+        if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
                 TradeStorageError::Io(format!("Failed to create database directory: {}", e))
             })?;
         }
 
-        let conn = Connection::open(db_path).map_err(TradeStorageError::Database)?;
+        let writer = Connection::open(db_path).map_err(TradeStorageError::Database)?;
+        writer
+            .busy_timeout(config.busy_timeout)
+            .map_err(TradeStorageError::Database)?;
+        // WAL lets readers proceed while the writer holds a transaction
+        writer
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .map_err(TradeStorageError::Database)?;
 
-        let storage = Self {
-            conn: Mutex::new(conn),
+        let mut storage = Self {
+            writer: Mutex::new(writer),
+            readers: Vec::new(),
+            next_reader: AtomicUsize::new(0),
+            reads: AtomicUsize::new(0),
+            snapshot_encoding: config.snapshot_encoding,
         };
         storage.init_schema()?;
 
+        // Open readers after the schema exists
+        for _ in 0..config.read_pool_size {
+            let reader = Connection::open_with_flags(
+                db_path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )
+            .map_err(TradeStorageError::Database)?;
+            reader
+                .busy_timeout(config.busy_timeout)
+                .map_err(TradeStorageError::Database)?;
+            storage.readers.push(Mutex::new(reader));
+        }
+
         Ok(storage)
     }
 
     /// Create an in-memory TradeStorage (useful for testing)
+    ///
+    /// In-memory databases are private to a connection, so reads share the writer.
     pub fn new_in_memory() -> Result<Self, TradeStorageError> {
         let conn = Connection::open_in_memory().map_err(TradeStorageError::Database)?;
 
         let storage = Self {
-            conn: Mutex::new(conn),
+            writer: Mutex::new(conn),
+            readers: Vec::new(),
+            next_reader: AtomicUsize::new(0),
+            reads: AtomicUsize::new(0),
+            snapshot_encoding: SnapshotEncoding::default(),
         };
         storage.init_schema()?;
 
         Ok(storage)
     }
 
-    /// Initialize the database schema
-    fn init_schema(&self) -> Result<(), TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    /// Change the encoding used for newly stored orderbook snapshots
+    pub fn set_snapshot_encoding(&mut self, encoding: SnapshotEncoding) {
+        self.snapshot_encoding = encoding;
+    }
 
-        conn.execute_batch(
-            r#"
-            -- Trades table (existing)
-            CREATE TABLE IF NOT EXISTS trades (
-                id TEXT PRIMARY KEY,
-                platform TEXT NOT NULL,
-                market_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                price REAL NOT NULL,
-                quantity REAL NOT NULL,
-                outcome TEXT NOT NULL,
-                side TEXT,
-                created_at INTEGER DEFAULT (strftime('%s', 'now'))
-            );
+    /// Read connections checked out since startup
+    ///
+    /// Each read method checks out one connection, so this approximates the
+    /// number of read queries issued.
+    pub fn read_count(&self) -> usize {
+        self.reads.load(Ordering::Relaxed)
+    }
 
-            CREATE INDEX IF NOT EXISTS idx_trades_market
-            ON trades(platform, market_id, timestamp);
-
-            CREATE INDEX IF NOT EXISTS idx_trades_timestamp
-            ON trades(timestamp);
-
-            -- Prices table (current prices for fast lookup)
-            CREATE TABLE IF NOT EXISTS prices (
-                platform TEXT NOT NULL,
-                market_id TEXT NOT NULL,
-                yes_price REAL,
-                no_price REAL,
-                spread REAL,
-                updated_at INTEGER NOT NULL,
-                PRIMARY KEY (platform, market_id)
-            );
+    /// Acquire the writer connection
+    fn write_conn(&self) -> Result<MutexGuard<'_, Connection>, TradeStorageError> {
+        self.writer.lock().map_err(|_| TradeStorageError::LockError)
+    }
 
-            -- Orderbook snapshots table (for historical depth analysis)
-            CREATE TABLE IF NOT EXISTS orderbook_snapshots (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                platform TEXT NOT NULL,
-                market_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                yes_bids TEXT,
-                yes_asks TEXT,
-                no_bids TEXT,
-                no_asks TEXT
-            );
+    /// Acquire a read connection
+    ///
+    /// Prefers an idle reader; if all are busy, waits on the next one in rotation.
+    fn read_conn(&self) -> Result<MutexGuard<'_, Connection>, TradeStorageError> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        if self.readers.is_empty() {
+            return self.write_conn();
+        }
 
-            CREATE INDEX IF NOT EXISTS idx_ob_market
-            ON orderbook_snapshots(platform, market_id, timestamp);
-
-            -- Pre-computed candles table
-            CREATE TABLE IF NOT EXISTS candles (
-                platform TEXT NOT NULL,
-                market_id TEXT NOT NULL,
-                interval TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                open REAL,
-                high REAL,
-                low REAL,
-                close REAL,
-                volume REAL,
-                trade_count INTEGER,
-                PRIMARY KEY (platform, market_id, interval, timestamp)
-            );
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.readers.len() {
+            let reader = &self.readers[(start + offset) % self.readers.len()];
+            if let Ok(guard) = reader.try_lock() {
+                return Ok(guard);
+            }
+        }
 
-            -- Price snapshots table (for historical price change calculation)
-            CREATE TABLE IF NOT EXISTS price_snapshots (
-                platform TEXT NOT NULL,
-                market_id TEXT NOT NULL,
-                timestamp INTEGER NOT NULL,
-                yes_price REAL NOT NULL,
-                no_price REAL,
-                PRIMARY KEY (platform, market_id, timestamp)
-            );
+        self.readers[start % self.readers.len()]
+            .lock()
+            .map_err(|_| TradeStorageError::LockError)
+    }
 
-            CREATE INDEX IF NOT EXISTS idx_price_snapshots_lookup
-            ON price_snapshots(platform, market_id, timestamp DESC);
-            "#,
-        )
-        .map_err(TradeStorageError::Database)?;
+    /// Initialize the database schema by applying pending migrations
+    ///
+    /// The applied version is tracked in `PRAGMA user_version`; each migration
+    /// runs in its own transaction together with the version bump.
+    fn init_schema(&self) -> Result<(), TradeStorageError> {
+        let mut conn = self.write_conn()?;
+
+        let current: usize = conn
+            .pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))
+            .map_err(TradeStorageError::Database)? as usize;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+            let version = index + 1;
+            let tx = conn.transaction().map_err(TradeStorageError::Database)?;
+            tx.execute_batch(migration)
+                .map_err(TradeStorageError::Database)?;
+            tx.pragma_update(None, "user_version", version as i64)
+                .map_err(TradeStorageError::Database)?;
+            tx.commit().map_err(TradeStorageError::Database)?;
+            tracing::info!("Applied trade storage migration {}", version);
+        }
 
         Ok(())
     }
 
+    /// Get the schema version currently applied to the database
+    pub fn schema_version(&self) -> Result<usize, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(version as usize)
+    }
+
     /// Store a single trade
     pub fn store_trade(&self, trade: &Trade) -> Result<(), TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        let conn = self.write_conn()?;
 
         let platform_str = match trade.platform {
             Platform::Kalshi => "kalshi",
@@ -164,8 +692,8 @@ impl TradeStorage {
 
         conn.execute(
             r#"
-            INSERT OR REPLACE INTO trades (id, platform, market_id, timestamp, price, quantity, outcome, side)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            INSERT OR REPLACE INTO trades (id, platform, market_id, timestamp, price, quantity, outcome, side, transaction_hash, outcome_id, maker_address, taker_address)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
             "#,
             params![
                 trade.id,
@@ -176,6 +704,10 @@ impl TradeStorage {
                 quantity,
                 outcome_str,
                 side_str,
+                trade.transaction_hash,
+                trade.outcome_id,
+                trade.maker_address,
+                trade.taker_address,
             ],
         )
         .map_err(TradeStorageError::Database)?;
@@ -184,59 +716,121 @@ impl TradeStorage {
     }
 
     /// Store multiple trades in a batch
+    ///
+    /// All rows are inserted in a single transaction with a reused prepared
+    /// statement. Returns the number of trades actually inserted (duplicates
+    /// are ignored and not counted).
     pub fn store_trades(&self, trades: &[Trade]) -> Result<usize, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        let mut conn = self.write_conn()?;
+        let tx = conn.transaction().map_err(TradeStorageError::Database)?;
 
         let mut stored = 0;
-        for trade in trades {
-            let platform_str = match trade.platform {
-                Platform::Kalshi => "kalshi",
-                Platform::Polymarket => "polymarket",
-            };
-
-            let outcome_str = match trade.outcome {
-                TradeOutcome::Yes => "yes",
-                TradeOutcome::No => "no",
-            };
+        {
+            let mut stmt = tx
+                .prepare(
+                    r#"
+                    INSERT OR IGNORE INTO trades (id, platform, market_id, timestamp, price, quantity, outcome, side, transaction_hash, outcome_id, maker_address, taker_address)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                    "#,
+                )
+                .map_err(TradeStorageError::Database)?;
+            // A hashed (REST) print supersedes the feed print of the same fill
+            let mut superseded = tx
+                .prepare(&format!(
+                    "DELETE FROM trades WHERE rowid = (SELECT rowid FROM trades WHERE transaction_hash IS NULL AND {} LIMIT 1)",
+                    SAME_FILL
+                ))
+                .map_err(TradeStorageError::Database)?;
+
+            for trade in trades {
+                let platform_str = match trade.platform {
+                    Platform::Kalshi => "kalshi",
+                    Platform::Polymarket => "polymarket",
+                };
+
+                let outcome_str = match trade.outcome {
+                    TradeOutcome::Yes => "yes",
+                    TradeOutcome::No => "no",
+                };
+
+                let side_str = trade.side.as_ref().map(|s| match s {
+                    TradeSide::Buy => "buy",
+                    TradeSide::Sell => "sell",
+                });
+
+                let timestamp = trade.timestamp.timestamp();
+                let price: f64 = trade
+                    .price
+                    .try_into()
+                    .unwrap_or_else(|_| trade.price.to_string().parse().unwrap_or(0.0));
+                let quantity: f64 = trade
+                    .quantity
+                    .try_into()
+                    .unwrap_or_else(|_| trade.quantity.to_string().parse().unwrap_or(0.0));
+
+                // execute() returns changes(): 0 when the row was an ignored duplicate
+                let inserted = stmt
+                    .execute(params![
+                        trade.id,
+                        platform_str,
+                        trade.market_id,
+                        timestamp,
+                        price,
+                        quantity,
+                        outcome_str,
+                        side_str,
+                        trade.transaction_hash,
+                        trade.outcome_id,
+                        trade.maker_address,
+                        trade.taker_address,
+                    ])
+                    .map_err(TradeStorageError::Database)?;
+                stored += inserted;
+
+                if let (1, Some(_), Some(outcome_id)) = (inserted, &trade.transaction_hash, &trade.outcome_id) {
+                    superseded
+                        .execute(params![platform_str, outcome_id, timestamp, price, quantity])
+                        .map_err(TradeStorageError::Database)?;
+                }
+            }
+        }
 
-            let side_str = trade.side.as_ref().map(|s| match s {
-                TradeSide::Buy => "buy",
-                TradeSide::Sell => "sell",
-            });
+        tx.commit().map_err(TradeStorageError::Database)?;
 
-            let timestamp = trade.timestamp.timestamp();
-            let price: f64 = trade
-                .price
-                .try_into()
-                .unwrap_or_else(|_| trade.price.to_string().parse().unwrap_or(0.0));
-            let quantity: f64 = trade
-                .quantity
-                .try_into()
-                .unwrap_or_else(|_| trade.quantity.to_string().parse().unwrap_or(0.0));
-
-            let result = conn.execute(
-                r#"
-                INSERT OR IGNORE INTO trades (id, platform, market_id, timestamp, price, quantity, outcome, side)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-                "#,
-                params![
-                    trade.id,
-                    platform_str,
-                    trade.market_id,
-                    timestamp,
-                    price,
-                    quantity,
-                    outcome_str,
-                    side_str,
-                ],
-            );
+        Ok(stored)
+    }
 
-            if result.is_ok() {
-                stored += 1;
-            }
+    /// Whether a feed print is already stored, under its own id or as the
+    /// hashed (REST) print of the same fill
+    ///
+    /// Feed and REST prints of one fill get different ids and, on Polymarket,
+    /// different market keys; the outcome token, second, price and size are
+    /// what they share.
+    pub fn fill_stored(&self, trade: &Trade) -> Result<bool, TradeStorageError> {
+        if self.trade_exists(&trade.id)? {
+            return Ok(true);
         }
+        let Some(outcome_id) = &trade.outcome_id else {
+            return Ok(false);
+        };
 
-        Ok(stored)
+        let conn = self.read_conn()?;
+        let platform_str = match trade.platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+        let price: f64 = trade.price.try_into().unwrap_or(0.0);
+        let quantity: f64 = trade.quantity.try_into().unwrap_or(0.0);
+
+        conn.query_row(
+            &format!(
+                "SELECT EXISTS(SELECT 1 FROM trades WHERE transaction_hash IS NOT NULL AND {})",
+                SAME_FILL
+            ),
+            params![platform_str, outcome_id, trade.timestamp.timestamp(), price, quantity],
+            |row| row.get(0),
+        )
+        .map_err(TradeStorageError::Database)
     }
 
     /// Get trades for a market within a time range
@@ -247,7 +841,7 @@ impl TradeStorage {
         from: DateTime<Utc>,
         to: DateTime<Utc>,
     ) -> Result<Vec<Trade>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
@@ -258,81 +852,35 @@ impl TradeStorage {
         let to_ts = to.timestamp();
 
         let mut stmt = conn
-            .prepare(
+            .prepare(&format!(
                 r#"
-            SELECT id, platform, market_id, timestamp, price, quantity, outcome, side
+            SELECT {TRADE_COLUMNS}
             FROM trades
             WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
             ORDER BY timestamp ASC
-            "#,
-            )
+            "#
+            ))
             .map_err(TradeStorageError::Database)?;
 
         let trades = stmt
-            .query_map(params![platform_str, market_id, from_ts, to_ts], |row| {
-                let id: String = row.get(0)?;
-                let platform_str: String = row.get(1)?;
-                let market_id: String = row.get(2)?;
-                let timestamp: i64 = row.get(3)?;
-                let price: f64 = row.get(4)?;
-                let quantity: f64 = row.get(5)?;
-                let outcome_str: String = row.get(6)?;
-                let side_str: Option<String> = row.get(7)?;
-
-                Ok((
-                    id,
-                    platform_str,
-                    market_id,
-                    timestamp,
-                    price,
-                    quantity,
-                    outcome_str,
-                    side_str,
-                ))
-            })
+            .query_map(params![platform_str, market_id, from_ts, to_ts], trade_from_row)
             .map_err(TradeStorageError::Database)?
             .filter_map(|r| r.ok())
-            .map(
-                |(id, platform_str, market_id, timestamp, price, quantity, outcome_str, side_str)| {
-                    Trade {
-                        id,
-                        market_id,
-                        platform: if platform_str == "kalshi" {
-                            Platform::Kalshi
-                        } else {
-                            Platform::Polymarket
-                        },
-                        timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
-                        price: Decimal::try_from(price).unwrap_or_default(),
-                        quantity: Decimal::try_from(quantity).unwrap_or_default(),
-                        outcome: if outcome_str == "yes" {
-                            TradeOutcome::Yes
-                        } else {
-                            TradeOutcome::No
-                        },
-                        side: side_str.map(|s| {
-                            if s == "buy" {
-                                TradeSide::Buy
-                            } else {
-                                TradeSide::Sell
-                            }
-                        }),
-                        transaction_hash: None, // Not stored in local DB
-                    }
-                },
-            )
             .collect();
 
         Ok(trades)
     }
 
-    /// Get the latest trade for a market
-    pub fn get_latest_trade(
+    /// Get trades for a single outcome of a multi-outcome market within a time range
+    pub fn get_trades_for_outcome(
         &self,
         platform: Platform,
         market_id: &str,
-    ) -> Result<Option<Trade>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        outcome_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Trade>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
@@ -340,858 +888,4100 @@ impl TradeStorage {
         };
 
         let mut stmt = conn
-            .prepare(
+            .prepare(&format!(
                 r#"
-            SELECT id, platform, market_id, timestamp, price, quantity, outcome, side
+            SELECT {TRADE_COLUMNS}
             FROM trades
-            WHERE platform = ?1 AND market_id = ?2
-            ORDER BY timestamp DESC
-            LIMIT 1
-            "#,
-            )
+            WHERE platform = ?1 AND market_id = ?2 AND outcome_id = ?3
+              AND timestamp >= ?4 AND timestamp <= ?5
+            ORDER BY timestamp ASC
+            "#
+            ))
             .map_err(TradeStorageError::Database)?;
 
-        let result = stmt
-            .query_row(params![platform_str, market_id], |row| {
-                let id: String = row.get(0)?;
-                let platform_str: String = row.get(1)?;
-                let market_id: String = row.get(2)?;
-                let timestamp: i64 = row.get(3)?;
-                let price: f64 = row.get(4)?;
-                let quantity: f64 = row.get(5)?;
-                let outcome_str: String = row.get(6)?;
-                let side_str: Option<String> = row.get(7)?;
-
-                Ok((
-                    id,
-                    platform_str,
-                    market_id,
-                    timestamp,
-                    price,
-                    quantity,
-                    outcome_str,
-                    side_str,
-                ))
-            })
-            .optional()
-            .map_err(TradeStorageError::Database)?;
+        let trades = stmt
+            .query_map(
+                params![platform_str, market_id, outcome_id, from.timestamp(), to.timestamp()],
+                trade_from_row,
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
 
-        Ok(result.map(
-            |(id, platform_str, market_id, timestamp, price, quantity, outcome_str, side_str)| {
-                Trade {
-                    id,
-                    market_id,
-                    platform: if platform_str == "kalshi" {
-                        Platform::Kalshi
-                    } else {
-                        Platform::Polymarket
-                    },
-                    timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now),
-                    price: Decimal::try_from(price).unwrap_or_default(),
-                    quantity: Decimal::try_from(quantity).unwrap_or_default(),
-                    outcome: if outcome_str == "yes" {
-                        TradeOutcome::Yes
-                    } else {
-                        TradeOutcome::No
-                    },
-                    side: side_str.map(|s| {
-                        if s == "buy" {
-                            TradeSide::Buy
-                        } else {
-                            TradeSide::Sell
-                        }
-                    }),
-                    transaction_hash: None, // Not stored in local DB
-                }
-            },
-        ))
+        Ok(trades)
     }
 
-    /// Get the count of trades for a market
-    pub fn get_trade_count(
+    /// Get the distinct outcome IDs that have stored trades for a market
+    pub fn get_outcome_ids(
         &self,
         platform: Platform,
         market_id: &str,
-    ) -> Result<usize, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    ) -> Result<Vec<String>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let count: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM trades WHERE platform = ?1 AND market_id = ?2",
-                params![platform_str, market_id],
-                |row| row.get(0),
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT DISTINCT outcome_id FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND outcome_id IS NOT NULL AND outcome_id != ''
+                ORDER BY outcome_id
+                "#,
             )
             .map_err(TradeStorageError::Database)?;
 
-        Ok(count as usize)
-    }
-
-    /// Check if a trade exists
-    pub fn trade_exists(&self, trade_id: &str) -> Result<bool, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
-
-        let exists: bool = conn
-            .query_row(
-                "SELECT EXISTS(SELECT 1 FROM trades WHERE id = ?1)",
-                params![trade_id],
-                |row| row.get(0),
-            )
-            .map_err(TradeStorageError::Database)?;
+        let ids = stmt
+            .query_map(params![platform_str, market_id], |row| row.get(0))
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
 
-        Ok(exists)
+        Ok(ids)
     }
 
-    // =========================================================================
-    // Trade Aggregation Methods (for market stats)
-    // =========================================================================
-
-    /// Get total volume (sum of price * quantity) for a market in a time range
-    pub fn get_volume_in_range(
+    /// Get the latest trade for a market
+    pub fn get_latest_trade(
         &self,
         platform: Platform,
         market_id: &str,
-        from: DateTime<Utc>,
-        to: DateTime<Utc>,
-    ) -> Result<f64, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    ) -> Result<Option<Trade>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let from_ts = from.timestamp();
-        let to_ts = to.timestamp();
-
-        let volume: f64 = conn
-            .query_row(
+        let mut stmt = conn
+            .prepare(&format!(
                 r#"
-                SELECT COALESCE(SUM(price * quantity), 0.0)
-                FROM trades
-                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
-                "#,
-                params![platform_str, market_id, from_ts, to_ts],
-                |row| row.get(0),
-            )
+            SELECT {TRADE_COLUMNS}
+            FROM trades
+            WHERE platform = ?1 AND market_id = ?2
+            ORDER BY timestamp DESC
+            LIMIT 1
+            "#
+            ))
             .map_err(TradeStorageError::Database)?;
 
-        Ok(volume)
+        stmt.query_row(params![platform_str, market_id], trade_from_row)
+            .optional()
+            .map_err(TradeStorageError::Database)
     }
 
-    /// Get transaction counts by outcome (yes/no) for a market in a time range
-    pub fn get_txn_counts_in_range(
+    /// Get a market's `limit` most recent trades, oldest first
+    pub fn get_recent_trades(
         &self,
         platform: Platform,
         market_id: &str,
-        from: DateTime<Utc>,
-        to: DateTime<Utc>,
-    ) -> Result<TxnCounts, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        limit: usize,
+    ) -> Result<Vec<Trade>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let from_ts = from.timestamp();
-        let to_ts = to.timestamp();
-
-        let yes_count: i64 = conn
-            .query_row(
+        let mut stmt = conn
+            .prepare(&format!(
                 r#"
-                SELECT COUNT(*)
-                FROM trades
-                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4 AND outcome = 'yes'
-                "#,
-                params![platform_str, market_id, from_ts, to_ts],
-                |row| row.get(0),
-            )
+            SELECT {TRADE_COLUMNS}
+            FROM trades
+            WHERE platform = ?1 AND market_id = ?2
+            ORDER BY timestamp DESC
+            LIMIT ?3
+            "#
+            ))
             .map_err(TradeStorageError::Database)?;
 
-        let no_count: i64 = conn
-            .query_row(
-                r#"
-                SELECT COUNT(*)
-                FROM trades
-                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4 AND outcome = 'no'
-                "#,
-                params![platform_str, market_id, from_ts, to_ts],
-                |row| row.get(0),
-            )
-            .map_err(TradeStorageError::Database)?;
+        let mut trades: Vec<Trade> = stmt
+            .query_map(params![platform_str, market_id, limit as i64], trade_from_row)
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+        trades.reverse();
 
-        Ok(TxnCounts {
-            yes_count: yes_count as u32,
-            no_count: no_count as u32,
-        })
+        Ok(trades)
     }
 
-    /// Get the earliest trade price in a time range (for computing price change)
-    pub fn get_earliest_price_in_range(
+    /// Get the timestamp of a market's oldest stored trade
+    pub fn get_earliest_trade_time(
         &self,
         platform: Platform,
         market_id: &str,
-        from: DateTime<Utc>,
-        to: DateTime<Utc>,
-    ) -> Result<Option<f64>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    ) -> Result<Option<DateTime<Utc>>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let from_ts = from.timestamp();
-        let to_ts = to.timestamp();
-
-        let price: Option<f64> = conn
+        let earliest: Option<i64> = conn
             .query_row(
-                r#"
-                SELECT price
-                FROM trades
-                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
-                ORDER BY timestamp ASC
-                LIMIT 1
-                "#,
-                params![platform_str, market_id, from_ts, to_ts],
+                "SELECT MIN(timestamp) FROM trades WHERE platform = ?1 AND market_id = ?2",
+                params![platform_str, market_id],
                 |row| row.get(0),
             )
-            .optional()
             .map_err(TradeStorageError::Database)?;
 
-        Ok(price)
+        Ok(earliest.and_then(|t| DateTime::from_timestamp(t, 0)))
     }
 
-    /// Get aggregated stats for multiple markets efficiently
-    pub fn get_bulk_stats_in_range(
+    /// Get all trades contained in an on-chain transaction
+    pub fn get_trades_by_transaction_hash(
         &self,
-        platform: Platform,
-        market_ids: &[String],
-        from: DateTime<Utc>,
-        to: DateTime<Utc>,
-    ) -> Result<Vec<MarketTradeStats>, TradeStorageError> {
-        if market_ids.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
-
-        let platform_str = match platform {
-            Platform::Kalshi => "kalshi",
-            Platform::Polymarket => "polymarket",
-        };
-
-        let from_ts = from.timestamp();
-        let to_ts = to.timestamp();
-
-        // Build query with placeholders for all market IDs
-        let placeholders: String = market_ids.iter().enumerate()
-            .map(|(i, _)| format!("?{}", i + 5))
-            .collect::<Vec<_>>()
-            .join(", ");
+        transaction_hash: &str,
+    ) -> Result<Vec<Trade>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
-        let query = format!(
-            r#"
-            SELECT
-                market_id,
-                COALESCE(SUM(price * quantity), 0.0) as volume,
-                COUNT(CASE WHEN outcome = 'yes' THEN 1 END) as yes_count,
-                COUNT(CASE WHEN outcome = 'no' THEN 1 END) as no_count,
-                (SELECT price FROM trades t2
-                 WHERE t2.platform = ?1 AND t2.market_id = trades.market_id
-                 AND t2.timestamp >= ?3 AND t2.timestamp <= ?4
-                 ORDER BY t2.timestamp ASC LIMIT 1) as earliest_price
+        let mut stmt = conn
+            .prepare(&format!(
+                r#"
+            SELECT {TRADE_COLUMNS}
             FROM trades
-            WHERE platform = ?1 AND market_id IN ({}) AND timestamp >= ?3 AND timestamp <= ?4
-            GROUP BY market_id
-            "#,
-            placeholders
-        );
-
-        let mut stmt = conn.prepare(&query).map_err(TradeStorageError::Database)?;
-
-        // Build params: platform, unused, from, to, then all market_ids
-        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
-            Box::new(platform_str.to_string()),
-            Box::new(platform_str.to_string()), // Placeholder for subquery
-            Box::new(from_ts),
-            Box::new(to_ts),
-        ];
-        for id in market_ids {
-            params_vec.push(Box::new(id.clone()));
-        }
-
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+            WHERE transaction_hash = ?1
+            ORDER BY timestamp ASC, id ASC
+            "#
+            ))
+            .map_err(TradeStorageError::Database)?;
 
-        let stats = stmt
-            .query_map(params_refs.as_slice(), |row| {
-                Ok(MarketTradeStats {
-                    market_id: row.get(0)?,
-                    volume: row.get(1)?,
-                    yes_count: row.get::<_, i64>(2)? as u32,
-                    no_count: row.get::<_, i64>(3)? as u32,
-                    earliest_price: row.get(4)?,
-                })
-            })
+        let trades = stmt
+            .query_map(params![transaction_hash], trade_from_row)
             .map_err(TradeStorageError::Database)?
             .filter_map(|r| r.ok())
             .collect();
 
-        Ok(stats)
+        Ok(trades)
     }
 
-    // =========================================================================
-    // Price Storage Methods
-    // =========================================================================
-
-    /// Store or update current prices for a market
-    pub fn store_price(
+    /// Get a market's largest trades by notional (price * quantity), largest first
+    pub fn get_large_trades(
         &self,
         platform: Platform,
         market_id: &str,
-        yes_price: Option<f64>,
-        no_price: Option<f64>,
-    ) -> Result<(), TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        min_notional: f64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Trade>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let spread = match (yes_price, no_price) {
-            (Some(yes), Some(no)) => Some((yes - no).abs()),
-            _ => None,
-        };
+        let mut stmt = conn
+            .prepare(&format!(
+                r#"
+            SELECT {TRADE_COLUMNS}
+            FROM trades
+            WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+              AND price * quantity >= ?5
+            ORDER BY price * quantity DESC, timestamp DESC
+            LIMIT ?6
+            "#
+            ))
+            .map_err(TradeStorageError::Database)?;
 
-        let now = chrono::Utc::now().timestamp();
+        let trades = stmt
+            .query_map(
+                params![
+                    platform_str,
+                    market_id,
+                    from.timestamp(),
+                    to.timestamp(),
+                    min_notional,
+                    limit as i64
+                ],
+                trade_from_row,
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
 
-        conn.execute(
-            r#"
-            INSERT OR REPLACE INTO prices (platform, market_id, yes_price, no_price, spread, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-            "#,
-            params![platform_str, market_id, yes_price, no_price, spread, now],
-        )
-        .map_err(TradeStorageError::Database)?;
+        Ok(trades)
+    }
 
-        Ok(())
+    /// Get the largest trades across all markets since a point in time (whale feed)
+    ///
+    /// Each returned trade carries its own platform and market_id.
+    pub fn get_largest_trades_global(
+        &self,
+        min_notional: f64,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Trade>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                r#"
+            SELECT {TRADE_COLUMNS}
+            FROM trades
+            WHERE timestamp >= ?1 AND price * quantity >= ?2
+            ORDER BY price * quantity DESC, timestamp DESC
+            LIMIT ?3
+            "#
+            ))
+            .map_err(TradeStorageError::Database)?;
+
+        let trades = stmt
+            .query_map(
+                params![since.timestamp(), min_notional, limit as i64],
+                trade_from_row,
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(trades)
     }
 
-    /// Get current price for a market
-    pub fn get_price(
+    /// Get the count of trades for a market
+    pub fn get_trade_count(
         &self,
         platform: Platform,
         market_id: &str,
-    ) -> Result<Option<StoredPrice>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    ) -> Result<usize, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let result = conn
+        let count: i64 = conn
             .query_row(
-                "SELECT yes_price, no_price, spread, updated_at FROM prices WHERE platform = ?1 AND market_id = ?2",
+                "SELECT COUNT(*) FROM trades WHERE platform = ?1 AND market_id = ?2",
                 params![platform_str, market_id],
-                |row| {
-                    Ok(StoredPrice {
-                        yes_price: row.get(0)?,
-                        no_price: row.get(1)?,
-                        spread: row.get(2)?,
-                        updated_at: row.get(3)?,
-                    })
-                },
+                |row| row.get(0),
             )
-            .optional()
             .map_err(TradeStorageError::Database)?;
 
-        Ok(result)
+        Ok(count as usize)
     }
 
-    // =========================================================================
-    // Orderbook Snapshot Methods
-    // =========================================================================
+    /// Check if a trade exists
+    pub fn trade_exists(&self, trade_id: &str) -> Result<bool, TradeStorageError> {
+        let conn = self.read_conn()?;
 
-    /// Store an orderbook snapshot
-    pub fn store_orderbook_snapshot(
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM trades WHERE id = ?1)",
+                params![trade_id],
+                |row| row.get(0),
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(exists)
+    }
+
+    /// Delete everything stored for a market: trades, prices, books, candles,
+    /// snapshots, extremes, alerts and gaps
+    ///
+    /// Returns the number of rows deleted.
+    pub fn delete_market_data(
         &self,
         platform: Platform,
         market_id: &str,
-        yes_bids: &str,
-        yes_asks: &str,
-        no_bids: &str,
-        no_asks: &str,
-    ) -> Result<(), TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    ) -> Result<usize, TradeStorageError> {
+        let mut conn = self.write_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let now = chrono::Utc::now().timestamp();
-
-        conn.execute(
-            r#"
-            INSERT INTO orderbook_snapshots (platform, market_id, timestamp, yes_bids, yes_asks, no_bids, no_asks)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-            "#,
-            params![platform_str, market_id, now, yes_bids, yes_asks, no_bids, no_asks],
-        )
-        .map_err(TradeStorageError::Database)?;
+        let tx = conn.transaction().map_err(TradeStorageError::Database)?;
+        let mut deleted = 0;
+        for table in MARKET_TABLES {
+            deleted += tx
+                .execute(
+                    &format!("DELETE FROM {} WHERE platform = ?1 AND market_id = ?2", table),
+                    params![platform_str, market_id],
+                )
+                .map_err(TradeStorageError::Database)?;
+        }
+        tx.commit().map_err(TradeStorageError::Database)?;
 
-        Ok(())
+        Ok(deleted)
     }
 
-    /// Get orderbook snapshots for a market within a time range
-    pub fn get_orderbook_snapshots(
-        &self,
-        platform: Platform,
-        market_id: &str,
-        from: DateTime<Utc>,
-        to: DateTime<Utc>,
-        limit: Option<usize>,
-    ) -> Result<Vec<OrderbookSnapshot>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    /// Rows stored for a market across every table `delete_market_data` clears
+    pub fn count_market_data(&self, platform: Platform, market_id: &str) -> Result<u64, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let from_ts = from.timestamp();
-        let to_ts = to.timestamp();
-        let limit = limit.unwrap_or(1000) as i64;
-
-        let mut stmt = conn
-            .prepare(
-                r#"
-                SELECT timestamp, yes_bids, yes_asks, no_bids, no_asks
-                FROM orderbook_snapshots
-                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
-                ORDER BY timestamp DESC
-                LIMIT ?5
-                "#,
-            )
-            .map_err(TradeStorageError::Database)?;
-
-        let snapshots = stmt
-            .query_map(params![platform_str, market_id, from_ts, to_ts, limit], |row| {
-                Ok(OrderbookSnapshot {
-                    timestamp: row.get(0)?,
-                    yes_bids: row.get(1)?,
-                    yes_asks: row.get(2)?,
-                    no_bids: row.get(3)?,
-                    no_asks: row.get(4)?,
-                })
-            })
-            .map_err(TradeStorageError::Database)?
-            .filter_map(|r| r.ok())
-            .collect();
+        let mut rows = 0;
+        for table in MARKET_TABLES {
+            let count: i64 = conn
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {} WHERE platform = ?1 AND market_id = ?2", table),
+                    params![platform_str, market_id],
+                    |row| row.get(0),
+                )
+                .map_err(TradeStorageError::Database)?;
+            rows += count as u64;
+        }
 
-        Ok(snapshots)
+        Ok(rows)
     }
 
-    /// Prune old orderbook snapshots
-    pub fn prune_orderbook_snapshots(&self, older_than_days: u64) -> Result<usize, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    /// Delete trades older than the given number of days
+    ///
+    /// Trades for any (platform, market ID) in `keep_markets` are exempt (e.g.
+    /// markets still tracked by the TradeCollector). Returns the number of rows
+    /// deleted.
+    pub fn prune_trades(
+        &self,
+        older_than_days: u64,
+        keep_markets: Option<&[(Platform, String)]>,
+    ) -> Result<usize, TradeStorageError> {
+        let conn = self.write_conn()?;
 
         let cutoff = chrono::Utc::now().timestamp() - (older_than_days as i64 * 86400);
 
+        let keep_markets = keep_markets.unwrap_or(&[]);
+        let mut query = "DELETE FROM trades WHERE timestamp < ?1".to_string();
+        if !keep_markets.is_empty() {
+            let placeholders: String = (0..keep_markets.len())
+                .map(|i| format!("(?{}, ?{})", 2 * i + 2, 2 * i + 3))
+                .collect::<Vec<_>>()
+                .join(", ");
+            query.push_str(&format!(" AND (platform, market_id) NOT IN (VALUES {})", placeholders));
+        }
+
+        let platforms: Vec<&str> = keep_markets
+            .iter()
+            .map(|(platform, _)| match platform {
+                Platform::Kalshi => "kalshi",
+                Platform::Polymarket => "polymarket",
+            })
+            .collect();
+        let mut params_vec: Vec<&dyn rusqlite::ToSql> = vec![&cutoff];
+        for (platform, (_, market_id)) in platforms.iter().zip(keep_markets) {
+            params_vec.push(platform);
+            params_vec.push(market_id);
+        }
+
         let deleted = conn
-            .execute(
-                "DELETE FROM orderbook_snapshots WHERE timestamp < ?1",
-                params![cutoff],
-            )
+            .execute(&query, params_vec.as_slice())
             .map_err(TradeStorageError::Database)?;
 
         Ok(deleted)
     }
 
+    /// Rebuild the database file to reclaim space freed by pruning
+    ///
+    /// Also refreshes planner statistics (used for approximate row counts).
+    /// Blocks writes for the duration; call after large prunes only.
+    pub fn vacuum(&self) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        conn.execute_batch("VACUUM; ANALYZE;")
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
     // =========================================================================
-    // Candle Storage Methods
+    // Storage Statistics
     // =========================================================================
 
-    /// Store or update a candle
-    pub fn store_candle(
-        &self,
-        platform: Platform,
-        market_id: &str,
-        interval: &str,
-        timestamp: i64,
-        open: f64,
-        high: f64,
-        low: f64,
-        close: f64,
-        volume: f64,
-        trade_count: i64,
-    ) -> Result<(), TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    /// Summarize what is stored in the database
+    ///
+    /// Table totals come from `sqlite_stat1` when ANALYZE has run, falling back
+    /// to exact counts; per-platform figures are exact (covering index scans).
+    pub fn get_storage_stats(&self) -> Result<StorageStats, TradeStorageError> {
+        let conn = self.read_conn()?;
 
-        let platform_str = match platform {
-            Platform::Kalshi => "kalshi",
-            Platform::Polymarket => "polymarket",
-        };
+        let (total_trades, trades_approx) = table_row_count(&conn, "trades")?;
+        let (orderbook_snapshots, snapshots_approx) = table_row_count(&conn, "orderbook_snapshots")?;
+        let (price_snapshots, prices_approx) = table_row_count(&conn, "price_snapshots")?;
+        let (candles, candles_approx) = table_row_count(&conn, "candles")?;
 
-        conn.execute(
-            r#"
-            INSERT OR REPLACE INTO candles (platform, market_id, interval, timestamp, open, high, low, close, volume, trade_count)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-            "#,
-            params![platform_str, market_id, interval, timestamp, open, high, low, close, volume, trade_count],
-        )
-        .map_err(TradeStorageError::Database)?;
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT platform, COUNT(*), COUNT(DISTINCT market_id)
+                FROM trades
+                GROUP BY platform
+                ORDER BY platform
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
 
-        Ok(())
-    }
+        let platforms = stmt
+            .query_map([], |row| {
+                let platform_str: String = row.get(0)?;
+                Ok(PlatformStorageStats {
+                    platform: if platform_str == "kalshi" {
+                        Platform::Kalshi
+                    } else {
+                        Platform::Polymarket
+                    },
+                    trade_rows: row.get::<_, i64>(1)? as u64,
+                    distinct_markets: row.get::<_, i64>(2)? as u64,
+                })
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
 
-    /// Get candles for a market
-    pub fn get_candles(
-        &self,
-        platform: Platform,
-        market_id: &str,
-        interval: &str,
-        from: DateTime<Utc>,
-        to: DateTime<Utc>,
-    ) -> Result<Vec<StoredCandle>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        let (oldest_trade, newest_trade): (Option<i64>, Option<i64>) = conn
+            .query_row("SELECT MIN(timestamp), MAX(timestamp) FROM trades", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(TradeStorageError::Database)?;
 
-        let platform_str = match platform {
-            Platform::Kalshi => "kalshi",
-            Platform::Polymarket => "polymarket",
-        };
+        let page_count: i64 = conn
+            .pragma_query_value(None, "page_count", |row| row.get(0))
+            .map_err(TradeStorageError::Database)?;
+        let page_size: i64 = conn
+            .pragma_query_value(None, "page_size", |row| row.get(0))
+            .map_err(TradeStorageError::Database)?;
 
-        let from_ts = from.timestamp();
-        let to_ts = to.timestamp();
+        Ok(StorageStats {
+            total_trades,
+            platforms,
+            oldest_trade: oldest_trade.and_then(|t| DateTime::from_timestamp(t, 0)),
+            newest_trade: newest_trade.and_then(|t| DateTime::from_timestamp(t, 0)),
+            orderbook_snapshots,
+            price_snapshots,
+            candles,
+            db_size_bytes: (page_count * page_size) as u64,
+            approximate: trades_approx || snapshots_approx || prices_approx || candles_approx,
+        })
+    }
+
+    /// Markets with the most stored trade rows, largest first
+    pub fn get_top_markets_by_row_count(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<MarketRowCount>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let mut stmt = conn
             .prepare(
                 r#"
-                SELECT timestamp, open, high, low, close, volume, trade_count
-                FROM candles
-                WHERE platform = ?1 AND market_id = ?2 AND interval = ?3 AND timestamp >= ?4 AND timestamp <= ?5
-                ORDER BY timestamp ASC
+                SELECT platform, market_id, COUNT(*) as rows
+                FROM trades
+                GROUP BY platform, market_id
+                ORDER BY rows DESC, market_id ASC
+                LIMIT ?1
                 "#,
             )
             .map_err(TradeStorageError::Database)?;
 
-        let candles = stmt
-            .query_map(params![platform_str, market_id, interval, from_ts, to_ts], |row| {
-                Ok(StoredCandle {
-                    timestamp: row.get(0)?,
-                    open: row.get(1)?,
-                    high: row.get(2)?,
-                    low: row.get(3)?,
-                    close: row.get(4)?,
-                    volume: row.get(5)?,
-                    trade_count: row.get(6)?,
+        let markets = stmt
+            .query_map(params![limit as i64], |row| {
+                let platform_str: String = row.get(0)?;
+                Ok(MarketRowCount {
+                    platform: if platform_str == "kalshi" {
+                        Platform::Kalshi
+                    } else {
+                        Platform::Polymarket
+                    },
+                    market_id: row.get(1)?,
+                    trade_rows: row.get::<_, i64>(2)? as u64,
                 })
             })
             .map_err(TradeStorageError::Database)?
             .filter_map(|r| r.ok())
             .collect();
 
-        Ok(candles)
+        Ok(markets)
+    }
+
+    /// Export trades for a market within a time range to a writer
+    ///
+    /// Trades are read in keyset pages of `EXPORT_PAGE_SIZE`, and the read
+    /// connection goes back to the pool before each page is written, so a
+    /// slow writer (a slow download) never holds a reader. Memory use does
+    /// not grow with the number of trades. Returns the number of trades written.
+    pub fn export_trades<W: std::io::Write>(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        format: TradeExportFormat,
+        writer: &mut W,
+    ) -> Result<usize, TradeStorageError> {
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let io_err = |e: std::io::Error| TradeStorageError::Io(format!("Export write failed: {}", e));
+
+        if format == TradeExportFormat::Csv {
+            writeln!(writer, "id,timestamp,price,quantity,outcome,side").map_err(io_err)?;
+        }
+
+        // Position after the last exported trade: (timestamp, id)
+        let mut after = (from.timestamp(), String::new());
+        let mut written = 0;
+        loop {
+            let page = {
+                let conn = self.read_conn()?;
+                let mut stmt = conn
+                    .prepare(&format!(
+                        r#"
+                    SELECT {TRADE_COLUMNS}
+                    FROM trades
+                    WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                      AND (timestamp > ?5 OR (timestamp = ?5 AND id > ?6))
+                    ORDER BY timestamp ASC, id ASC
+                    LIMIT ?7
+                    "#
+                    ))
+                    .map_err(TradeStorageError::Database)?;
+                let page = stmt
+                    .query_map(
+                        params![
+                            platform_str,
+                            market_id,
+                            from.timestamp(),
+                            to.timestamp(),
+                            after.0,
+                            after.1,
+                            EXPORT_PAGE_SIZE as i64
+                        ],
+                        trade_from_row,
+                    )
+                    .map_err(TradeStorageError::Database)?
+                    .collect::<Result<Vec<Trade>, _>>()
+                    .map_err(TradeStorageError::Database)?;
+                page
+            };
+
+            for trade in &page {
+                match format {
+                    TradeExportFormat::Csv => {
+                        let outcome = match trade.outcome {
+                            TradeOutcome::Yes => "yes",
+                            TradeOutcome::No => "no",
+                        };
+                        let side = match trade.side {
+                            Some(TradeSide::Buy) => "buy",
+                            Some(TradeSide::Sell) => "sell",
+                            None => "",
+                        };
+                        writeln!(
+                            writer,
+                            "{},{},{},{},{},{}",
+                            csv_field(&trade.id),
+                            trade.timestamp.to_rfc3339(),
+                            trade.price,
+                            trade.quantity,
+                            outcome,
+                            side
+                        )
+                        .map_err(io_err)?;
+                    }
+                    TradeExportFormat::JsonLines => {
+                        serde_json::to_writer(&mut *writer, trade)
+                            .map_err(|e| TradeStorageError::Io(format!("Export write failed: {}", e)))?;
+                        writeln!(writer).map_err(io_err)?;
+                    }
+                }
+                written += 1;
+            }
+
+            match page.last() {
+                Some(last) if page.len() == EXPORT_PAGE_SIZE => {
+                    after = (last.timestamp.timestamp(), last.id.clone());
+                }
+                _ => break,
+            }
+        }
+
+        writer.flush().map_err(io_err)?;
+
+        Ok(written)
     }
 
     // =========================================================================
-    // Price Snapshot Methods (for historical price change calculation)
+    // Trade Aggregation Methods (for market stats)
     // =========================================================================
 
-    /// Store a price snapshot for a market
-    pub fn store_price_snapshot(
+    /// Get total volume (sum of price * quantity) for a market in a time range
+    pub fn get_volume_in_range(
         &self,
         platform: Platform,
         market_id: &str,
-        yes_price: f64,
-        no_price: Option<f64>,
-    ) -> Result<(), TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<f64, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let now = chrono::Utc::now().timestamp();
+        let from_ts = from.timestamp();
+        let to_ts = to.timestamp();
 
-        conn.execute(
-            r#"
-            INSERT OR REPLACE INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price)
-            VALUES (?1, ?2, ?3, ?4, ?5)
-            "#,
-            params![platform_str, market_id, now, yes_price, no_price],
-        )
-        .map_err(TradeStorageError::Database)?;
+        let volume: f64 = conn
+            .query_row(
+                r#"
+                SELECT COALESCE(SUM(price * quantity), 0.0)
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                "#,
+                params![platform_str, market_id, from_ts, to_ts],
+                |row| row.get(0),
+            )
+            .map_err(TradeStorageError::Database)?;
 
-        Ok(())
+        Ok(volume)
     }
 
-    /// Store multiple price snapshots in batch
-    pub fn store_price_snapshots_batch(
+    /// Get the volume-weighted average price for a market in a time range
+    ///
+    /// Returns `None` when there are no trades (or no quantity) in the range.
+    pub fn get_vwap_in_range(
         &self,
-        snapshots: &[(Platform, String, f64, Option<f64>)],
-    ) -> Result<usize, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
-
-        let now = chrono::Utc::now().timestamp();
-        let mut stored = 0;
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<f64>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
-        for (platform, market_id, yes_price, no_price) in snapshots {
-            let platform_str = match platform {
-                Platform::Kalshi => "kalshi",
-                Platform::Polymarket => "polymarket",
-            };
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
 
-            if conn
-                .execute(
-                    r#"
-                    INSERT OR REPLACE INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price)
-                    VALUES (?1, ?2, ?3, ?4, ?5)
-                    "#,
-                    params![platform_str, market_id, now, yes_price, no_price],
-                )
-                .is_ok()
-            {
-                stored += 1;
-            }
-        }
+        // Division by a zero SUM yields NULL in SQLite, never NaN
+        let vwap: Option<f64> = conn
+            .query_row(
+                r#"
+                SELECT SUM(price * quantity) / NULLIF(SUM(quantity), 0)
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                "#,
+                params![platform_str, market_id, from.timestamp(), to.timestamp()],
+                |row| row.get(0),
+            )
+            .map_err(TradeStorageError::Database)?;
 
-        Ok(stored)
+        Ok(vwap)
     }
 
-    /// Get the price snapshot closest to a specific timestamp (for historical comparison)
-    pub fn get_price_at_time(
+    /// Get the time-weighted average price for a market in a time range
+    ///
+    /// Trades are bucketed by minute; the average is taken over the last
+    /// price of each bucket that has trades. Returns `None` with no trades.
+    pub fn get_twap_in_range(
         &self,
         platform: Platform,
         market_id: &str,
-        target_time: DateTime<Utc>,
-    ) -> Result<Option<PriceSnapshot>, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<f64>, TradeStorageError> {
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let target_ts = target_time.timestamp();
-
-        // Get the snapshot closest to target time (prefer older if exactly between two)
-        let result = conn
+        let twap: Option<f64> = conn
             .query_row(
                 r#"
-                SELECT timestamp, yes_price, no_price
-                FROM price_snapshots
-                WHERE platform = ?1 AND market_id = ?2 AND timestamp <= ?3
-                ORDER BY timestamp DESC
-                LIMIT 1
+                SELECT AVG(price) FROM (
+                    SELECT
+                        price,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY timestamp / 60 ORDER BY timestamp DESC, rowid DESC
+                        ) as rn
+                    FROM trades
+                    WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                )
+                WHERE rn = 1
                 "#,
-                params![platform_str, market_id, target_ts],
-                |row| {
-                    Ok(PriceSnapshot {
-                        timestamp: row.get(0)?,
-                        yes_price: row.get(1)?,
-                        no_price: row.get(2)?,
-                    })
-                },
+                params![platform_str, market_id, from.timestamp(), to.timestamp()],
+                |row| row.get(0),
             )
-            .optional()
             .map_err(TradeStorageError::Database)?;
 
-        Ok(result)
+        Ok(twap)
     }
 
-    /// Get price snapshots for multiple markets at a specific time (batch operation)
-    pub fn get_prices_at_time_batch(
+    /// Get time-weighted average prices for multiple markets at once
+    ///
+    /// Markets without trades in the range are omitted.
+    pub fn get_twap_batch(
         &self,
         platform: Platform,
         market_ids: &[String],
-        target_time: DateTime<Utc>,
-    ) -> Result<Vec<(String, PriceSnapshot)>, TradeStorageError> {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(String, f64)>, TradeStorageError> {
         if market_ids.is_empty() {
             return Ok(Vec::new());
         }
 
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+        let conn = self.read_conn()?;
 
         let platform_str = match platform {
             Platform::Kalshi => "kalshi",
             Platform::Polymarket => "polymarket",
         };
 
-        let target_ts = target_time.timestamp();
-        let mut results = Vec::new();
+        // Positional params: ?1 platform, ?2 from, ?3 to, ?4.. market IDs
+        let placeholders: String = (0..market_ids.len())
+            .map(|i| format!("?{}", i + 4))
+            .collect::<Vec<_>>()
+            .join(", ");
 
-        // Query each market (SQLite doesn't support LATERAL JOIN easily)
-        for market_id in market_ids {
-            if let Ok(Some(snapshot)) = conn
-                .query_row(
-                    r#"
-                    SELECT timestamp, yes_price, no_price
-                    FROM price_snapshots
-                    WHERE platform = ?1 AND market_id = ?2 AND timestamp <= ?3
-                    ORDER BY timestamp DESC
-                    LIMIT 1
-                    "#,
-                    params![platform_str, market_id, target_ts],
-                    |row| {
-                        Ok(PriceSnapshot {
-                            timestamp: row.get(0)?,
-                            yes_price: row.get(1)?,
-                            no_price: row.get(2)?,
-                        })
-                    },
-                )
-                .optional()
-            {
-                results.push((market_id.clone(), snapshot));
+        let query = format!(
+            r#"
+            SELECT market_id, AVG(price) FROM (
+                SELECT
+                    market_id,
+                    price,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY market_id, timestamp / 60 ORDER BY timestamp DESC, rowid DESC
+                    ) as rn
+                FROM trades
+                WHERE platform = ?1 AND timestamp >= ?2 AND timestamp <= ?3 AND market_id IN ({})
+            )
+            WHERE rn = 1
+            GROUP BY market_id
+            "#,
+            placeholders
+        );
+
+        let mut stmt = conn.prepare(&query).map_err(TradeStorageError::Database)?;
+
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(platform_str.to_string()),
+            Box::new(from.timestamp()),
+            Box::new(to.timestamp()),
+        ];
+        for id in market_ids {
+            params_vec.push(Box::new(id.clone()));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let twaps = stmt
+            .query_map(params_refs.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(twaps)
+    }
+
+    /// Get notional volume and trade counts bucketed by a fixed interval
+    ///
+    /// Returns `(bucket_start, volume, trade_count)` with bucket starts aligned
+    /// to multiples of `interval_secs`. Buckets with no trades are omitted.
+    pub fn get_volume_buckets(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval_secs: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(i64, f64, u32)>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let interval_secs = interval_secs.max(1);
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT (timestamp / ?5) * ?5 as bucket, SUM(price * quantity), COUNT(*)
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                GROUP BY bucket
+                ORDER BY bucket ASC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let buckets = stmt
+            .query_map(
+                params![platform_str, market_id, from.timestamp(), to.timestamp(), interval_secs],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? as u32)),
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(buckets)
+    }
+
+    /// Get volume, trade count and closing trade price per fixed-size bucket
+    ///
+    /// One GROUP BY over the range; bucket starts are aligned to multiples of
+    /// `interval_secs` and buckets with no trades are omitted.
+    pub fn get_trade_buckets(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval_secs: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TradeBucket>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let interval_secs = interval_secs.max(1);
+
+        // With MAX(), SQLite takes the bare `price` column from the latest row
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT (timestamp / ?5) * ?5 as bucket, SUM(price * quantity), COUNT(*), price, MAX(timestamp)
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                GROUP BY bucket
+                ORDER BY bucket ASC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let buckets = stmt
+            .query_map(
+                params![platform_str, market_id, from.timestamp(), to.timestamp(), interval_secs],
+                |row| {
+                    Ok(TradeBucket {
+                        bucket_start: row.get(0)?,
+                        volume: row.get(1)?,
+                        trade_count: row.get::<_, i64>(2)? as u32,
+                        close: row.get(3)?,
+                    })
+                },
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(buckets)
+    }
+
+    /// Get buy/sell order flow for a market in a time range
+    ///
+    /// Volumes are notional (price * quantity). Trades without a side count
+    /// towards neither bucket.
+    pub fn get_flow_in_range(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<TradeFlow, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.query_row(
+            r#"
+            SELECT
+                COALESCE(SUM(CASE WHEN side = 'buy' THEN price * quantity END), 0.0),
+                COALESCE(SUM(CASE WHEN side = 'sell' THEN price * quantity END), 0.0),
+                COUNT(CASE WHEN side = 'buy' THEN 1 END),
+                COUNT(CASE WHEN side = 'sell' THEN 1 END)
+            FROM trades
+            WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+            "#,
+            params![platform_str, market_id, from.timestamp(), to.timestamp()],
+            |row| {
+                Ok(TradeFlow::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get::<_, i64>(2)? as u32,
+                    row.get::<_, i64>(3)? as u32,
+                ))
+            },
+        )
+        .map_err(TradeStorageError::Database)
+    }
+
+    /// Count and sum the notional of a market's trades per size bin in a time range
+    ///
+    /// `edges` are the ascending lower bounds of every bin after the first:
+    /// `[10.0, 100.0]` bins notionals (price * quantity) into [0, 10),
+    /// [10, 100) and [100, ∞). Returns one `(count, notional)` per bin, in
+    /// order, with empty bins zeroed.
+    pub fn get_trade_size_bins(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        edges: &[f64],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(u32, f64)>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        // Edges bind after the four range parameters
+        let cases: String = (0..edges.len())
+            .map(|i| format!("WHEN price * quantity < ?{} THEN {} ", i + 5, i))
+            .collect();
+        let query = format!(
+            r#"
+            SELECT CASE {}ELSE {} END AS bin, COUNT(*), COALESCE(SUM(price * quantity), 0.0)
+            FROM trades
+            WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+            GROUP BY bin
+            "#,
+            cases,
+            edges.len()
+        );
+
+        let mut stmt = conn.prepare(&query).map_err(TradeStorageError::Database)?;
+
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(platform_str.to_string()),
+            Box::new(market_id.to_string()),
+            Box::new(from.timestamp()),
+            Box::new(to.timestamp()),
+        ];
+        for edge in edges {
+            params_vec.push(Box::new(*edge));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let mut bins = vec![(0, 0.0); edges.len() + 1];
+        let rows = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as u32, row.get::<_, f64>(2)?))
+            })
+            .map_err(TradeStorageError::Database)?;
+        for row in rows {
+            let (bin, count, notional) = row.map_err(TradeStorageError::Database)?;
+            if let Some(slot) = bins.get_mut(bin) {
+                *slot = (count, notional);
+            }
+        }
+
+        Ok(bins)
+    }
+
+    /// Count distinct wallet addresses (maker or taker) trading a market in a time range
+    ///
+    /// Returns `None` when no trade in the range carries an address (e.g. Kalshi),
+    /// so callers can distinguish "unknown" from "zero".
+    pub fn get_unique_trader_count(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<u32>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let count: i64 = conn
+            .query_row(
+                r#"
+                SELECT COUNT(*) FROM (
+                    SELECT LOWER(maker_address) FROM trades
+                    WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                      AND maker_address IS NOT NULL
+                    UNION
+                    SELECT LOWER(taker_address) FROM trades
+                    WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                      AND taker_address IS NOT NULL
+                )
+                "#,
+                params![platform_str, market_id, from.timestamp(), to.timestamp()],
+                |row| row.get(0),
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        Ok((count > 0).then_some(count as u32))
+    }
+
+    /// Get transaction counts by outcome (yes/no) for a market in a time range
+    pub fn get_txn_counts_in_range(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<TxnCounts, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let from_ts = from.timestamp();
+        let to_ts = to.timestamp();
+
+        let yes_count: i64 = conn
+            .query_row(
+                r#"
+                SELECT COUNT(*)
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4 AND outcome = 'yes'
+                "#,
+                params![platform_str, market_id, from_ts, to_ts],
+                |row| row.get(0),
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let no_count: i64 = conn
+            .query_row(
+                r#"
+                SELECT COUNT(*)
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4 AND outcome = 'no'
+                "#,
+                params![platform_str, market_id, from_ts, to_ts],
+                |row| row.get(0),
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(TxnCounts {
+            yes_count: yes_count as u32,
+            no_count: no_count as u32,
+        })
+    }
+
+    /// Get the earliest trade price in a time range (for computing price change)
+    pub fn get_earliest_price_in_range(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<f64>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let from_ts = from.timestamp();
+        let to_ts = to.timestamp();
+
+        let price: Option<f64> = conn
+            .query_row(
+                r#"
+                SELECT price
+                FROM trades
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                ORDER BY timestamp ASC
+                LIMIT 1
+                "#,
+                params![platform_str, market_id, from_ts, to_ts],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(price)
+    }
+
+    /// Get aggregated stats for multiple markets efficiently
+    pub fn get_bulk_stats_in_range(
+        &self,
+        platform: Platform,
+        market_ids: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<MarketTradeStats>, TradeStorageError> {
+        if market_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let from_ts = from.timestamp();
+        let to_ts = to.timestamp();
+
+        // Positional params: ?1 platform, ?2 from, ?3 to, ?4.. market IDs
+        let placeholders: String = (0..market_ids.len())
+            .map(|i| format!("?{}", i + 4))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let query = format!(
+            r#"
+            SELECT
+                t.market_id,
+                COALESCE(SUM(t.price * t.quantity), 0.0) as volume,
+                COUNT(CASE WHEN t.outcome = 'yes' THEN 1 END) as yes_count,
+                COUNT(CASE WHEN t.outcome = 'no' THEN 1 END) as no_count,
+                SUM(t.price * t.quantity) / NULLIF(SUM(t.quantity), 0) as vwap,
+                COALESCE(SUM(CASE WHEN t.side = 'buy' THEN t.price * t.quantity END), 0.0) as buy_volume,
+                COALESCE(SUM(CASE WHEN t.side = 'sell' THEN t.price * t.quantity END), 0.0) as sell_volume,
+                COUNT(CASE WHEN t.side = 'buy' THEN 1 END) as buy_count,
+                COUNT(CASE WHEN t.side = 'sell' THEN 1 END) as sell_count,
+                (SELECT t2.price FROM trades t2
+                 WHERE t2.platform = t.platform AND t2.market_id = t.market_id
+                 AND t2.timestamp >= ?2 AND t2.timestamp <= ?3
+                 ORDER BY t2.timestamp ASC LIMIT 1) as earliest_price,
+                (SELECT NULLIF(COUNT(*), 0) FROM (
+                    SELECT LOWER(t3.maker_address) FROM trades t3
+                    WHERE t3.platform = t.platform AND t3.market_id = t.market_id
+                    AND t3.timestamp >= ?2 AND t3.timestamp <= ?3 AND t3.maker_address IS NOT NULL
+                    UNION
+                    SELECT LOWER(t3.taker_address) FROM trades t3
+                    WHERE t3.platform = t.platform AND t3.market_id = t.market_id
+                    AND t3.timestamp >= ?2 AND t3.timestamp <= ?3 AND t3.taker_address IS NOT NULL
+                 )) as unique_traders
+            FROM trades t
+            WHERE t.platform = ?1 AND t.market_id IN ({}) AND t.timestamp >= ?2 AND t.timestamp <= ?3
+            GROUP BY t.platform, t.market_id
+            "#,
+            placeholders
+        );
+
+        let mut stmt = conn.prepare(&query).map_err(TradeStorageError::Database)?;
+
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![
+            Box::new(platform_str.to_string()),
+            Box::new(from_ts),
+            Box::new(to_ts),
+        ];
+        for id in market_ids {
+            params_vec.push(Box::new(id.clone()));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let stats = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok(MarketTradeStats {
+                    market_id: row.get(0)?,
+                    volume: row.get(1)?,
+                    yes_count: row.get::<_, i64>(2)? as u32,
+                    no_count: row.get::<_, i64>(3)? as u32,
+                    vwap: row.get(4)?,
+                    flow: TradeFlow::new(
+                        row.get(5)?,
+                        row.get(6)?,
+                        row.get::<_, i64>(7)? as u32,
+                        row.get::<_, i64>(8)? as u32,
+                    ),
+                    earliest_price: row.get(9)?,
+                    unique_traders: row.get::<_, Option<i64>>(10)?.map(|c| c as u32),
+                })
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(stats)
+    }
+
+    // =========================================================================
+    // Resolution Storage Methods
+    // =========================================================================
+
+    /// Record a market's final outcome
+    ///
+    /// Idempotent: the first stored resolution wins. Returns `true` if a new row was written.
+    pub fn store_resolution(&self, resolution: &MarketResolution) -> Result<bool, TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match resolution.platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let inserted = conn
+            .execute(
+                r#"
+                INSERT OR IGNORE INTO resolutions (platform, market_id, resolved_at, winning_outcome, final_price)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                params![
+                    platform_str,
+                    resolution.market_id,
+                    resolution.resolved_at.timestamp(),
+                    resolution.winning_outcome,
+                    resolution.final_price,
+                ],
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Get the recorded resolution for a market, if any
+    pub fn get_resolution(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Option<MarketResolution>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.query_row(
+            r#"
+            SELECT resolved_at, winning_outcome, final_price
+            FROM resolutions
+            WHERE platform = ?1 AND market_id = ?2
+            "#,
+            params![platform_str, market_id],
+            |row| {
+                Ok(MarketResolution {
+                    platform,
+                    market_id: market_id.to_string(),
+                    resolved_at: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                    winning_outcome: row.get(1)?,
+                    final_price: row.get(2)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(TradeStorageError::Database)
+    }
+
+    // =========================================================================
+    // Alert Storage Methods
+    // =========================================================================
+
+    /// Record an unusual-activity alert
+    pub fn store_alert(&self, alert: &MarketAlert) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match alert.platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.execute(
+            r#"
+            INSERT INTO alerts (platform, market_id, kind, magnitude, timestamp)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![
+                platform_str,
+                alert.market_id,
+                alert.kind.as_str(),
+                alert.magnitude,
+                alert.timestamp.timestamp(),
+            ],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Most recent alerts, newest first, optionally for one platform
+    pub fn get_recent_alerts(
+        &self,
+        platform: Option<Platform>,
+        limit: usize,
+    ) -> Result<Vec<MarketAlert>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = platform.map(|p| match p {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        });
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT platform, market_id, kind, magnitude, timestamp
+                FROM alerts
+                WHERE ?1 IS NULL OR platform = ?1
+                ORDER BY timestamp DESC, id DESC
+                LIMIT ?2
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let alerts = stmt
+            .query_map(params![platform_str, limit as i64], |row| {
+                let platform_str: String = row.get(0)?;
+                let kind: String = row.get(2)?;
+                // Kinds this build doesn't know are skipped
+                let Some(kind) = AlertKind::parse(&kind) else {
+                    return Ok(None);
+                };
+                Ok(Some(MarketAlert {
+                    platform: if platform_str == "kalshi" {
+                        Platform::Kalshi
+                    } else {
+                        Platform::Polymarket
+                    },
+                    market_id: row.get(1)?,
+                    kind,
+                    magnitude: row.get(3)?,
+                    timestamp: DateTime::from_timestamp(row.get(4)?, 0).unwrap_or_default(),
+                }))
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok().flatten())
+            .collect();
+
+        Ok(alerts)
+    }
+
+    // =========================================================================
+    // Gap Log Methods
+    // =========================================================================
+
+    /// Record a window where feed data was lost
+    pub fn store_gap(&self, gap: &DataGap) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match gap.platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.execute(
+            r#"
+            INSERT INTO gaps (platform, market_id, started_at, ended_at, reason)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![
+                platform_str,
+                gap.market_id,
+                gap.started_at.timestamp(),
+                gap.ended_at.timestamp(),
+                gap.reason.as_str(),
+            ],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Gaps still open at or after `since`, oldest first
+    pub fn get_gaps_since(&self, since: DateTime<Utc>, limit: usize) -> Result<Vec<DataGap>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT platform, market_id, started_at, ended_at, reason
+                FROM gaps
+                WHERE ended_at >= ?1
+                ORDER BY started_at ASC, id ASC
+                LIMIT ?2
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let gaps = stmt
+            .query_map(params![since.timestamp(), limit as i64], |row| {
+                let platform_str: String = row.get(0)?;
+                let reason: String = row.get(4)?;
+                // Reasons this build doesn't know are skipped
+                let Some(reason) = GapReason::parse(&reason) else {
+                    return Ok(None);
+                };
+                Ok(Some(DataGap {
+                    platform: if platform_str == "kalshi" {
+                        Platform::Kalshi
+                    } else {
+                        Platform::Polymarket
+                    },
+                    market_id: row.get(1)?,
+                    started_at: DateTime::from_timestamp(row.get(2)?, 0).unwrap_or_default(),
+                    ended_at: DateTime::from_timestamp(row.get(3)?, 0).unwrap_or_default(),
+                    reason,
+                }))
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok().flatten())
+            .collect();
+
+        Ok(gaps)
+    }
+
+    // =========================================================================
+    // Price Storage Methods
+    // =========================================================================
+
+    /// Store or update current prices for a market
+    pub fn store_price(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        yes_price: Option<f64>,
+        no_price: Option<f64>,
+    ) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let spread = match (yes_price, no_price) {
+            (Some(yes), Some(no)) => Some((yes - no).abs()),
+            _ => None,
+        };
+
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO prices (platform, market_id, yes_price, no_price, spread, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![platform_str, market_id, yes_price, no_price, spread, now],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Get current price for a market
+    pub fn get_price(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Option<StoredPrice>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let result = conn
+            .query_row(
+                "SELECT yes_price, no_price, spread, updated_at FROM prices WHERE platform = ?1 AND market_id = ?2",
+                params![platform_str, market_id],
+                |row| {
+                    Ok(StoredPrice {
+                        yes_price: row.get(0)?,
+                        no_price: row.get(1)?,
+                        spread: row.get(2)?,
+                        updated_at: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(result)
+    }
+
+    /// Store or update the current price of one outcome in a multi-outcome market
+    pub fn store_price_for_outcome(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        outcome_label: Option<&str>,
+        price: f64,
+    ) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO outcome_prices (platform, market_id, outcome_id, outcome_label, price, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![platform_str, market_id, outcome_id, outcome_label, price, now],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Get the current price of every outcome in a market
+    pub fn get_prices_for_market(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Vec<OutcomePrice>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT outcome_id, outcome_label, price, updated_at
+                FROM outcome_prices
+                WHERE platform = ?1 AND market_id = ?2
+                ORDER BY price DESC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let prices = stmt
+            .query_map(params![platform_str, market_id], |row| {
+                Ok(OutcomePrice {
+                    outcome_id: row.get(0)?,
+                    outcome_label: row.get(1)?,
+                    price: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(prices)
+    }
+
+    // =========================================================================
+    // Orderbook Snapshot Methods
+    // =========================================================================
+
+    /// Store an orderbook snapshot along with its precomputed depth metrics
+    #[allow(clippy::too_many_arguments)]
+    pub fn store_orderbook_snapshot(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        yes_bids: &str,
+        yes_asks: &str,
+        no_bids: &str,
+        no_asks: &str,
+        metrics: &OrderbookMetrics,
+    ) -> Result<(), TradeStorageError> {
+        let now = chrono::Utc::now().timestamp();
+        self.store_orderbook_snapshot_at(platform, market_id, now, yes_bids, yes_asks, no_bids, no_asks, metrics)
+    }
+
+    /// Store an orderbook snapshot taken at a specific time
+    #[allow(clippy::too_many_arguments)]
+    pub fn store_orderbook_snapshot_at(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        timestamp: i64,
+        yes_bids: &str,
+        yes_asks: &str,
+        no_bids: &str,
+        no_asks: &str,
+        metrics: &OrderbookMetrics,
+    ) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let encoding = self.snapshot_encoding;
+
+        conn.execute(
+            r#"
+            INSERT INTO orderbook_snapshots (
+                platform, market_id, timestamp, yes_bids, yes_asks, no_bids, no_asks,
+                best_bid, best_ask, mid, spread, bid_depth_usd, ask_depth_usd, encoding
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            "#,
+            params![
+                platform_str,
+                market_id,
+                timestamp,
+                encoding.encode(yes_bids)?,
+                encoding.encode(yes_asks)?,
+                encoding.encode(no_bids)?,
+                encoding.encode(no_asks)?,
+                metrics.best_bid,
+                metrics.best_ask,
+                metrics.mid,
+                metrics.spread,
+                metrics.bid_depth_usd,
+                metrics.ask_depth_usd,
+                encoding.as_i64(),
+            ],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Get orderbook snapshots for a market within a time range
+    ///
+    /// Level arrays are decoded according to each row's stored encoding.
+    pub fn get_orderbook_snapshots(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<OrderbookSnapshot>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let from_ts = from.timestamp();
+        let to_ts = to.timestamp();
+        let limit = limit.unwrap_or(1000) as i64;
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT timestamp, yes_bids, yes_asks, no_bids, no_asks, encoding
+                FROM orderbook_snapshots
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                ORDER BY timestamp DESC
+                LIMIT ?5
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let snapshots = stmt
+            .query_map(params![platform_str, market_id, from_ts, to_ts, limit], |row| {
+                let encoding = SnapshotEncoding::from_i64(row.get(5)?).unwrap_or_default();
+                let levels = |idx: usize| -> rusqlite::Result<Option<String>> {
+                    Ok(encoding.decode(row.get(idx)?))
+                };
+                Ok(OrderbookSnapshot {
+                    timestamp: row.get(0)?,
+                    yes_bids: levels(1)?,
+                    yes_asks: levels(2)?,
+                    no_bids: levels(3)?,
+                    no_asks: levels(4)?,
+                })
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(snapshots)
+    }
+
+    /// Get a market's most recent orderbook snapshot, level arrays decoded
+    pub fn get_latest_orderbook_snapshot(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Option<OrderbookSnapshot>, TradeStorageError> {
+        let snapshots = self.get_orderbook_snapshots(
+            platform,
+            market_id,
+            DateTime::UNIX_EPOCH,
+            Utc::now() + chrono::Duration::minutes(1),
+            Some(1),
+        )?;
+        Ok(snapshots.into_iter().next())
+    }
+
+    /// Get the numeric spread/depth series for a market, oldest first
+    ///
+    /// Snapshots stored before depth metrics existed are skipped.
+    pub fn get_spread_history(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<SpreadPoint>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT timestamp, best_bid, best_ask, mid, spread, bid_depth_usd, ask_depth_usd
+                FROM orderbook_snapshots
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                  AND bid_depth_usd IS NOT NULL
+                ORDER BY timestamp ASC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let points = stmt
+            .query_map(
+                params![platform_str, market_id, from.timestamp(), to.timestamp()],
+                spread_point_from_row,
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(points)
+    }
+
+    /// Get the depth metrics of a market's most recent orderbook snapshot
+    pub fn get_latest_spread_point(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Option<SpreadPoint>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let point = conn
+            .query_row(
+                r#"
+                SELECT timestamp, best_bid, best_ask, mid, spread, bid_depth_usd, ask_depth_usd
+                FROM orderbook_snapshots
+                WHERE platform = ?1 AND market_id = ?2 AND bid_depth_usd IS NOT NULL
+                ORDER BY timestamp DESC
+                LIMIT 1
+                "#,
+                params![platform_str, market_id],
+                spread_point_from_row,
+            )
+            .optional()
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(point)
+    }
+
+    /// Get the latest orderbook depth metrics for multiple markets (batch operation)
+    pub fn get_latest_spread_points_batch(
+        &self,
+        platform: Platform,
+        market_ids: &[String],
+    ) -> Result<Vec<(String, SpreadPoint)>, TradeStorageError> {
+        if market_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        // Positional params: ?1 platform, ?2.. market IDs
+        let placeholders: String = (0..market_ids.len())
+            .map(|i| format!("?{}", i + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let query = format!(
+            r#"
+            SELECT market_id, timestamp, best_bid, best_ask, mid, spread, bid_depth_usd, ask_depth_usd
+            FROM (
+                SELECT
+                    market_id, timestamp, best_bid, best_ask, mid, spread, bid_depth_usd, ask_depth_usd,
+                    ROW_NUMBER() OVER (PARTITION BY market_id ORDER BY timestamp DESC) as rn
+                FROM orderbook_snapshots
+                WHERE platform = ?1 AND bid_depth_usd IS NOT NULL AND market_id IN ({})
+            )
+            WHERE rn = 1
+            "#,
+            placeholders
+        );
+
+        let mut stmt = conn.prepare(&query).map_err(TradeStorageError::Database)?;
+
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(platform_str.to_string())];
+        for id in market_ids {
+            params_vec.push(Box::new(id.clone()));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let points = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    SpreadPoint {
+                        timestamp: row.get(1)?,
+                        metrics: OrderbookMetrics {
+                            best_bid: row.get(2)?,
+                            best_ask: row.get(3)?,
+                            mid: row.get(4)?,
+                            spread: row.get(5)?,
+                            bid_depth_usd: row.get(6)?,
+                            ask_depth_usd: row.get(7)?,
+                        },
+                    },
+                ))
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(points)
+    }
+
+    /// Prune old orderbook snapshots
+    pub fn prune_orderbook_snapshots(&self, older_than_days: u64) -> Result<usize, TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let cutoff = chrono::Utc::now().timestamp() - (older_than_days as i64 * 86400);
+
+        let deleted = conn
+            .execute(
+                "DELETE FROM orderbook_snapshots WHERE timestamp < ?1",
+                params![cutoff],
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(deleted)
+    }
+
+    // =========================================================================
+    // Candle Storage Methods
+    // =========================================================================
+
+    /// Store or update a candle
+    pub fn store_candle(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: &str,
+        timestamp: i64,
+        open: f64,
+        high: f64,
+        low: f64,
+        close: f64,
+        volume: f64,
+        trade_count: i64,
+    ) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO candles (platform, market_id, interval, timestamp, open, high, low, close, volume, trade_count)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            params![platform_str, market_id, interval, timestamp, open, high, low, close, volume, trade_count],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        conn.execute(EXTREMES_UPSERT, params![platform_str, market_id, high, timestamp, low, timestamp])
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Store or update a candle for one outcome of a multi-outcome market
+    pub fn store_candle_for_outcome(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        interval: &str,
+        candle: &StoredCandle,
+    ) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO candles (platform, market_id, outcome_id, interval, timestamp, open, high, low, close, volume, trade_count)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+            params![
+                platform_str,
+                market_id,
+                outcome_id,
+                interval,
+                candle.timestamp,
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.volume,
+                candle.trade_count
+            ],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        if outcome_id.is_empty() {
+            conn.execute(
+                EXTREMES_UPSERT,
+                params![platform_str, market_id, candle.high, candle.timestamp, candle.low, candle.timestamp],
+            )
+            .map_err(TradeStorageError::Database)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace a market's candles in `[from, to]` with the given set, atomically
+    ///
+    /// Existing rows in the range are deleted first so stale buckets don't linger.
+    /// `outcome_id` of `None` targets the whole-market series.
+    /// Returns the number of candles written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn replace_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: Option<&str>,
+        interval: &str,
+        from: i64,
+        to: i64,
+        candles: &[StoredCandle],
+    ) -> Result<usize, TradeStorageError> {
+        let mut conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let outcome_id = outcome_id.unwrap_or("");
+
+        let tx = conn.transaction().map_err(TradeStorageError::Database)?;
+
+        tx.execute(
+            r#"
+            DELETE FROM candles
+            WHERE platform = ?1 AND market_id = ?2 AND outcome_id = ?3 AND interval = ?4
+              AND timestamp >= ?5 AND timestamp <= ?6
+            "#,
+            params![platform_str, market_id, outcome_id, interval, from, to],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        {
+            let mut stmt = tx
+                .prepare(
+                    r#"
+                    INSERT OR REPLACE INTO candles (platform, market_id, outcome_id, interval, timestamp, open, high, low, close, volume, trade_count)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                    "#,
+                )
+                .map_err(TradeStorageError::Database)?;
+
+            for candle in candles {
+                stmt.execute(params![
+                    platform_str,
+                    market_id,
+                    outcome_id,
+                    interval,
+                    candle.timestamp,
+                    candle.open,
+                    candle.high,
+                    candle.low,
+                    candle.close,
+                    candle.volume,
+                    candle.trade_count
+                ])
+                .map_err(TradeStorageError::Database)?;
+            }
+        }
+
+        if outcome_id.is_empty() {
+            if let Some(((high, high_ts), (low, low_ts))) = candle_extremes(candles) {
+                tx.execute(EXTREMES_UPSERT, params![platform_str, market_id, high, high_ts, low, low_ts])
+                    .map_err(TradeStorageError::Database)?;
+            }
+        }
+
+        tx.commit().map_err(TradeStorageError::Database)?;
+
+        Ok(candles.len())
+    }
+
+    /// Get candles for a market
+    pub fn get_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredCandle>, TradeStorageError> {
+        self.get_candles_for_outcome(platform, market_id, "", interval, from, to)
+    }
+
+    /// Get candles for one outcome of a market (`""` = the whole market)
+    pub fn get_candles_for_outcome(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        interval: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredCandle>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let from_ts = from.timestamp();
+        let to_ts = to.timestamp();
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT timestamp, open, high, low, close, volume, trade_count
+                FROM candles
+                WHERE platform = ?1 AND market_id = ?2 AND outcome_id = ?3 AND interval = ?4
+                  AND timestamp >= ?5 AND timestamp <= ?6
+                ORDER BY timestamp ASC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let candles = stmt
+            .query_map(params![platform_str, market_id, outcome_id, interval, from_ts, to_ts], |row| {
+                Ok(StoredCandle {
+                    timestamp: row.get(0)?,
+                    open: row.get(1)?,
+                    high: row.get(2)?,
+                    low: row.get(3)?,
+                    close: row.get(4)?,
+                    volume: row.get(5)?,
+                    trade_count: row.get(6)?,
+                })
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(candles)
+    }
+
+    /// Get the timestamp of the latest stored candle of a series (`""` = the whole market)
+    pub fn get_latest_candle_timestamp(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        interval: &str,
+    ) -> Result<Option<i64>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.query_row(
+            r#"
+            SELECT MAX(timestamp) FROM candles
+            WHERE platform = ?1 AND market_id = ?2 AND outcome_id = ?3 AND interval = ?4
+            "#,
+            params![platform_str, market_id, outcome_id, interval],
+            |row| row.get(0),
+        )
+        .map_err(TradeStorageError::Database)
+    }
+
+    /// Upsert price-only candles, keeping volume and trade counts of existing rows
+    ///
+    /// Used for candles built from platform price history, which carry no volume:
+    /// buckets already built from trades keep their volume while their prices are
+    /// refreshed. Returns the number of candles written.
+    pub fn upsert_price_candles(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        outcome_id: &str,
+        interval: &str,
+        candles: &[StoredCandle],
+    ) -> Result<usize, TradeStorageError> {
+        let mut conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let tx = conn.transaction().map_err(TradeStorageError::Database)?;
+
+        {
+            let mut stmt = tx
+                .prepare(
+                    r#"
+                    INSERT INTO candles (platform, market_id, outcome_id, interval, timestamp, open, high, low, close, volume, trade_count)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                    ON CONFLICT (platform, market_id, outcome_id, interval, timestamp) DO UPDATE SET
+                        open = excluded.open,
+                        high = excluded.high,
+                        low = excluded.low,
+                        close = excluded.close
+                    "#,
+                )
+                .map_err(TradeStorageError::Database)?;
+
+            for candle in candles {
+                stmt.execute(params![
+                    platform_str,
+                    market_id,
+                    outcome_id,
+                    interval,
+                    candle.timestamp,
+                    candle.open,
+                    candle.high,
+                    candle.low,
+                    candle.close,
+                    candle.volume,
+                    candle.trade_count
+                ])
+                .map_err(TradeStorageError::Database)?;
+            }
+        }
+
+        if outcome_id.is_empty() {
+            if let Some(((high, high_ts), (low, low_ts))) = candle_extremes(candles) {
+                tx.execute(EXTREMES_UPSERT, params![platform_str, market_id, high, high_ts, low, low_ts])
+                    .map_err(TradeStorageError::Database)?;
+            }
+        }
+
+        tx.commit().map_err(TradeStorageError::Database)?;
+
+        Ok(candles.len())
+    }
+
+    // =========================================================================
+    // Price Snapshot Methods (for historical price change calculation)
+    // =========================================================================
+
+    /// Store a price snapshot for a market
+    pub fn store_price_snapshot(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        yes_price: f64,
+        no_price: Option<f64>,
+    ) -> Result<(), TradeStorageError> {
+        self.store_price_snapshot_at(platform, market_id, Utc::now().timestamp(), yes_price, no_price)
+    }
+
+    /// Store a price snapshot for a market at a specific time
+    pub fn store_price_snapshot_at(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        timestamp: i64,
+        yes_price: f64,
+        no_price: Option<f64>,
+    ) -> Result<(), TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![platform_str, market_id, timestamp, yes_price, no_price],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        conn.execute(
+            EXTREMES_UPSERT,
+            params![platform_str, market_id, yes_price, timestamp, yes_price, timestamp],
+        )
+        .map_err(TradeStorageError::Database)?;
+
+        Ok(())
+    }
+
+    /// Store multiple price snapshots in batch (single transaction)
+    pub fn store_price_snapshots_batch(
+        &self,
+        snapshots: &[(Platform, String, f64, Option<f64>)],
+    ) -> Result<usize, TradeStorageError> {
+        self.store_snapshot_rows(
+            chrono::Utc::now().timestamp(),
+            snapshots
+                .iter()
+                .map(|(platform, market_id, yes_price, no_price)| (*platform, market_id.as_str(), *yes_price, *no_price, None)),
+        )
+    }
+
+    /// Store price snapshots carrying open interest at a specific time (single transaction)
+    pub fn store_open_interest_snapshots_at(
+        &self,
+        timestamp: i64,
+        snapshots: &[(Platform, String, f64, Option<f64>, f64)],
+    ) -> Result<usize, TradeStorageError> {
+        self.store_snapshot_rows(
+            timestamp,
+            snapshots.iter().map(|(platform, market_id, yes_price, no_price, open_interest)| {
+                (*platform, market_id.as_str(), *yes_price, *no_price, Some(*open_interest))
+            }),
+        )
+    }
+
+    /// Insert (platform, market_id, yes, no, open interest) snapshot rows and fold them into the extremes
+    fn store_snapshot_rows<'a>(
+        &self,
+        timestamp: i64,
+        rows: impl IntoIterator<Item = (Platform, &'a str, f64, Option<f64>, Option<f64>)>,
+    ) -> Result<usize, TradeStorageError> {
+        let mut conn = self.write_conn()?;
+        let tx = conn.transaction().map_err(TradeStorageError::Database)?;
+
+        let mut stored = 0;
+        {
+            let mut stmt = tx
+                .prepare(
+                    r#"
+                    INSERT OR REPLACE INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price, open_interest)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                    "#,
+                )
+                .map_err(TradeStorageError::Database)?;
+            let mut extremes_stmt = tx.prepare(EXTREMES_UPSERT).map_err(TradeStorageError::Database)?;
+
+            for (platform, market_id, yes_price, no_price, open_interest) in rows {
+                let platform_str = match platform {
+                    Platform::Kalshi => "kalshi",
+                    Platform::Polymarket => "polymarket",
+                };
+
+                stored += stmt
+                    .execute(params![platform_str, market_id, timestamp, yes_price, no_price, open_interest])
+                    .map_err(TradeStorageError::Database)?;
+                extremes_stmt
+                    .execute(params![platform_str, market_id, yes_price, timestamp, yes_price, timestamp])
+                    .map_err(TradeStorageError::Database)?;
+            }
+        }
+
+        tx.commit().map_err(TradeStorageError::Database)?;
+
+        Ok(stored)
+    }
+
+    /// Get the price snapshot closest to a specific timestamp (for historical comparison)
+    pub fn get_price_at_time(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        target_time: DateTime<Utc>,
+    ) -> Result<Option<PriceSnapshot>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let target_ts = target_time.timestamp();
+
+        // Get the snapshot closest to target time (prefer older if exactly between two)
+        let result = conn
+            .query_row(
+                r#"
+                SELECT timestamp, yes_price, no_price
+                FROM price_snapshots
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp <= ?3
+                ORDER BY timestamp DESC
+                LIMIT 1
+                "#,
+                params![platform_str, market_id, target_ts],
+                |row| {
+                    Ok(PriceSnapshot {
+                        timestamp: row.get(0)?,
+                        yes_price: row.get(1)?,
+                        no_price: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(result)
+    }
+
+    /// Get the latest open interest recorded at or before a specific time
+    pub fn get_open_interest_at(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        target_time: DateTime<Utc>,
+    ) -> Result<Option<OpenInterestSnapshot>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.query_row(
+            r#"
+            SELECT timestamp, open_interest
+            FROM price_snapshots
+            WHERE platform = ?1 AND market_id = ?2 AND timestamp <= ?3 AND open_interest IS NOT NULL
+            ORDER BY timestamp DESC
+            LIMIT 1
+            "#,
+            params![platform_str, market_id, target_time.timestamp()],
+            |row| {
+                Ok(OpenInterestSnapshot {
+                    timestamp: row.get(0)?,
+                    open_interest: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(TradeStorageError::Database)
+    }
+
+    /// Get a market's all-time YES price extremes from snapshots and whole-market candles
+    pub fn get_price_extremes(
+        &self,
+        platform: Platform,
+        market_id: &str,
+    ) -> Result<Option<PriceExtremes>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        conn.query_row(
+            r#"
+            SELECT ath_price, ath_timestamp, atl_price, atl_timestamp
+            FROM price_extremes
+            WHERE platform = ?1 AND market_id = ?2
+            "#,
+            params![platform_str, market_id],
+            |row| {
+                Ok(PriceExtremes {
+                    ath_price: row.get(0)?,
+                    ath_timestamp: row.get(1)?,
+                    atl_price: row.get(2)?,
+                    atl_timestamp: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(TradeStorageError::Database)
+    }
+
+    /// Get a market's price snapshots in a time range, oldest first
+    pub fn get_price_snapshots(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PriceSnapshot>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT timestamp, yes_price, no_price
+                FROM price_snapshots
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                ORDER BY timestamp ASC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let snapshots = stmt
+            .query_map(
+                params![platform_str, market_id, from.timestamp(), to.timestamp()],
+                |row| {
+                    Ok(PriceSnapshot {
+                        timestamp: row.get(0)?,
+                        yes_price: row.get(1)?,
+                        no_price: row.get(2)?,
+                    })
+                },
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(snapshots)
+    }
+
+    /// Get the last snapshot YES price per fixed-size bucket, oldest first
+    ///
+    /// Bucket starts are aligned to multiples of `interval_secs`; buckets
+    /// without a snapshot are omitted.
+    pub fn get_snapshot_closes(
+        &self,
+        platform: Platform,
+        market_id: &str,
+        interval_secs: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(i64, f64)>, TradeStorageError> {
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let interval_secs = interval_secs.max(1);
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT (timestamp / ?5) * ?5 as bucket, yes_price, MAX(timestamp)
+                FROM price_snapshots
+                WHERE platform = ?1 AND market_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4
+                GROUP BY bucket
+                ORDER BY bucket ASC
+                "#,
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        let closes = stmt
+            .query_map(
+                params![platform_str, market_id, from.timestamp(), to.timestamp(), interval_secs],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(closes)
+    }
+
+    /// Get price snapshots for multiple markets at a specific time (batch operation)
+    pub fn get_prices_at_time_batch(
+        &self,
+        platform: Platform,
+        market_ids: &[String],
+        target_time: DateTime<Utc>,
+    ) -> Result<Vec<(String, PriceSnapshot)>, TradeStorageError> {
+        if market_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.read_conn()?;
+
+        let platform_str = match platform {
+            Platform::Kalshi => "kalshi",
+            Platform::Polymarket => "polymarket",
+        };
+
+        let target_ts = target_time.timestamp();
+
+        // Positional params: ?1 platform, ?2 target time, ?3.. market IDs
+        let placeholders: String = (0..market_ids.len())
+            .map(|i| format!("?{}", i + 3))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Latest snapshot at-or-before the target time per market, in one pass
+        let query = format!(
+            r#"
+            SELECT market_id, timestamp, yes_price, no_price
+            FROM (
+                SELECT
+                    market_id, timestamp, yes_price, no_price,
+                    ROW_NUMBER() OVER (PARTITION BY market_id ORDER BY timestamp DESC) as rn
+                FROM price_snapshots
+                WHERE platform = ?1 AND timestamp <= ?2 AND market_id IN ({})
+            )
+            WHERE rn = 1
+            "#,
+            placeholders
+        );
+
+        let mut stmt = conn.prepare(&query).map_err(TradeStorageError::Database)?;
+
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> =
+            vec![Box::new(platform_str.to_string()), Box::new(target_ts)];
+        for id in market_ids {
+            params_vec.push(Box::new(id.clone()));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let results = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    PriceSnapshot {
+                        timestamp: row.get(1)?,
+                        yes_price: row.get(2)?,
+                        no_price: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(TradeStorageError::Database)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Prune old price snapshots (keep only last N days)
+    pub fn prune_price_snapshots(&self, older_than_days: u64) -> Result<usize, TradeStorageError> {
+        let conn = self.write_conn()?;
+
+        let cutoff = chrono::Utc::now().timestamp() - (older_than_days as i64 * 86400);
+
+        let deleted = conn
+            .execute(
+                "DELETE FROM price_snapshots WHERE timestamp < ?1",
+                params![cutoff],
+            )
+            .map_err(TradeStorageError::Database)?;
+
+        Ok(deleted)
+    }
+
+    /// Thin out old price snapshots so long-horizon history stays cheap
+    ///
+    /// Keeps every snapshot from the last 7 days, the last snapshot per hour up
+    /// to 30 days back, and the last snapshot per day beyond that. Returns the
+    /// number of rows deleted.
+    pub fn downsample_price_snapshots(&self) -> Result<usize, TradeStorageError> {
+        self.downsample_price_snapshots_at(Utc::now().timestamp())
+    }
+
+    fn downsample_price_snapshots_at(&self, now: i64) -> Result<usize, TradeStorageError> {
+        let mut conn = self.write_conn()?;
+        let tx = conn.transaction().map_err(TradeStorageError::Database)?;
+
+        // Keep only the latest snapshot in each bucket older than the cutoff,
+        // so closest-at-or-before lookups still see the bucket's closing price
+        let tiers = [
+            (now - SNAPSHOT_FULL_RESOLUTION_DAYS * 86400, 3600_i64),
+            (now - SNAPSHOT_HOURLY_RESOLUTION_DAYS * 86400, 86400_i64),
+        ];
+
+        let mut deleted = 0;
+        for (cutoff, bucket_secs) in tiers {
+            deleted += tx
+                .execute(
+                    r#"
+                    DELETE FROM price_snapshots
+                    WHERE timestamp < ?1
+                      AND timestamp < (
+                          SELECT MAX(q.timestamp) FROM price_snapshots q
+                          WHERE q.platform = price_snapshots.platform
+                            AND q.market_id = price_snapshots.market_id
+                            AND q.timestamp < ?1
+                            AND q.timestamp / ?2 = price_snapshots.timestamp / ?2
+                      )
+                    "#,
+                    params![cutoff, bucket_secs],
+                )
+                .map_err(TradeStorageError::Database)?;
+        }
+
+        tx.commit().map_err(TradeStorageError::Database)?;
+
+        Ok(deleted)
+    }
+}
+
+/// Price snapshot data
+#[derive(Debug, Clone)]
+pub struct PriceSnapshot {
+    pub timestamp: i64,
+    pub yes_price: f64,
+    pub no_price: Option<f64>,
+}
+
+/// Open interest stored with a price snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpenInterestSnapshot {
+    pub timestamp: i64,
+    pub open_interest: f64,
+}
+
+/// Trades aggregated into one fixed-size time bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeBucket {
+    /// Bucket start (unix seconds, aligned to the interval)
+    pub bucket_start: i64,
+    /// Notional volume (price * quantity)
+    pub volume: f64,
+    pub trade_count: u32,
+    /// Price of the last trade in the bucket
+    pub close: f64,
+}
+
+/// Stored price data
+#[derive(Debug, Clone)]
+pub struct StoredPrice {
+    pub yes_price: Option<f64>,
+    pub no_price: Option<f64>,
+    pub spread: Option<f64>,
+    pub updated_at: i64,
+}
+
+/// All-time YES price extremes of a market
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceExtremes {
+    pub ath_price: f64,
+    pub ath_timestamp: i64,
+    pub atl_price: f64,
+    pub atl_timestamp: i64,
+}
+
+/// Overview of database contents
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StorageStats {
+    pub total_trades: u64,
+    pub platforms: Vec<PlatformStorageStats>,
+    pub oldest_trade: Option<DateTime<Utc>>,
+    pub newest_trade: Option<DateTime<Utc>>,
+    pub orderbook_snapshots: u64,
+    pub price_snapshots: u64,
+    pub candles: u64,
+    /// Size of the main database file (excludes the WAL)
+    pub db_size_bytes: u64,
+    /// True if any table total is an ANALYZE estimate rather than an exact count
+    pub approximate: bool,
+}
+
+/// Stored trade rows for one platform
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlatformStorageStats {
+    pub platform: Platform,
+    pub trade_rows: u64,
+    pub distinct_markets: u64,
+}
+
+/// Stored trade rows for one market
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MarketRowCount {
+    pub platform: Platform,
+    pub market_id: String,
+    pub trade_rows: u64,
+}
+
+/// Why a feed lost data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GapReason {
+    /// The exchange connection dropped (or went silent) and was re-established
+    Reconnect,
+    /// A book fell out of sequence and was refetched over REST
+    Resync,
+}
+
+impl GapReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Reconnect => "reconnect",
+            Self::Resync => "resync",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "reconnect" => Some(Self::Reconnect),
+            "resync" => Some(Self::Resync),
+            _ => None,
+        }
+    }
+}
+
+/// A window where streamed data for a platform (or one market) is missing
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DataGap {
+    pub platform: Platform,
+    /// `None` when the whole feed was affected
+    pub market_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub reason: GapReason,
+}
+
+/// Final outcome of a resolved market
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MarketResolution {
+    pub platform: Platform,
+    pub market_id: String,
+    pub resolved_at: DateTime<Utc>,
+    /// Winning outcome label ("yes"/"no", or the option name for multi-outcome markets)
+    pub winning_outcome: Option<String>,
+    /// Last YES price observed at resolution
+    pub final_price: Option<f64>,
+}
+
+/// Stored orderbook snapshot
+#[derive(Debug, Clone)]
+pub struct OrderbookSnapshot {
+    pub timestamp: i64,
+    pub yes_bids: Option<String>,
+    pub yes_asks: Option<String>,
+    pub no_bids: Option<String>,
+    pub no_asks: Option<String>,
+}
+
+impl OrderbookSnapshot {
+    /// Parsed YES bids, best first
+    pub fn yes_bid_levels(&self) -> Vec<OrderBookLevel> {
+        self.yes_bids.as_deref().map(parse_levels).unwrap_or_default()
+    }
+
+    /// Parsed YES asks, best first
+    pub fn yes_ask_levels(&self) -> Vec<OrderBookLevel> {
+        self.yes_asks.as_deref().map(parse_levels).unwrap_or_default()
+    }
+
+    /// The stored book as an `OrderBook` for `market_id`
+    pub fn to_orderbook(&self, platform: Platform, market_id: &str) -> OrderBook {
+        let parse = |side: &Option<String>| side.as_deref().map(parse_levels).unwrap_or_default();
+        OrderBook {
+            market_id: market_id.to_string(),
+            platform,
+            timestamp: DateTime::from_timestamp(self.timestamp, 0).unwrap_or_default(),
+            yes_bids: parse(&self.yes_bids),
+            yes_asks: parse(&self.yes_asks),
+            no_bids: parse(&self.no_bids),
+            no_asks: parse(&self.no_asks),
+            sequence: None,
+        }
+    }
+}
+
+/// Parse a stored level array; malformed JSON reads as an empty side
+pub fn parse_levels(json: &str) -> Vec<OrderBookLevel> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+/// Depth metrics computed from the YES side of an orderbook at snapshot time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderbookMetrics {
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub mid: Option<f64>,
+    pub spread: Option<f64>,
+    /// Sum of price * quantity across all YES bids
+    pub bid_depth_usd: f64,
+    /// Sum of price * quantity across all YES asks
+    pub ask_depth_usd: f64,
+}
+
+impl OrderbookMetrics {
+    /// Compute metrics from a parsed orderbook
+    pub fn from_book(book: &OrderBook) -> Self {
+        Self::from_levels(&book.yes_bids, &book.yes_asks)
+    }
+
+    /// Compute metrics from a stored snapshot's level arrays
+    pub fn from_snapshot(snapshot: &OrderbookSnapshot) -> Self {
+        Self::from_levels(&snapshot.yes_bid_levels(), &snapshot.yes_ask_levels())
+    }
+
+    /// Compute metrics from YES bids and asks, each best first
+    fn from_levels(bids: &[OrderBookLevel], asks: &[OrderBookLevel]) -> Self {
+        let to_f64 =
+            |d: Decimal| -> f64 { d.try_into().unwrap_or_else(|_| d.to_string().parse().unwrap_or(0.0)) };
+        let depth = |levels: &[OrderBookLevel]| -> f64 {
+            levels.iter().map(|l| to_f64(l.price * l.quantity)).sum()
+        };
+        let (best_bid, best_ask) = (bids.first().map(|l| l.price), asks.first().map(|l| l.price));
+        let (mid, spread) = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => (Some((bid + ask) / Decimal::from(2)), Some(ask - bid)),
+            _ => (None, None),
+        };
+
+        Self {
+            best_bid: best_bid.map(to_f64),
+            best_ask: best_ask.map(to_f64),
+            mid: mid.map(to_f64),
+            spread: spread.map(to_f64),
+            bid_depth_usd: depth(bids),
+            ask_depth_usd: depth(asks),
+        }
+    }
+}
+
+/// A point in a market's spread/depth history
+#[derive(Debug, Clone)]
+pub struct SpreadPoint {
+    pub timestamp: i64,
+    pub metrics: OrderbookMetrics,
+}
+
+/// Map a `timestamp, best_bid, best_ask, mid, spread, bid_depth_usd, ask_depth_usd` row
+fn spread_point_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SpreadPoint> {
+    Ok(SpreadPoint {
+        timestamp: row.get(0)?,
+        metrics: OrderbookMetrics {
+            best_bid: row.get(1)?,
+            best_ask: row.get(2)?,
+            mid: row.get(3)?,
+            spread: row.get(4)?,
+            bid_depth_usd: row.get(5)?,
+            ask_depth_usd: row.get(6)?,
+        },
+    })
+}
+
+/// Stored candle data
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct StoredCandle {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub trade_count: i64,
+}
+
+/// Current price of a single outcome in a multi-outcome market
+#[derive(Debug, Clone)]
+pub struct OutcomePrice {
+    pub outcome_id: String,
+    pub outcome_label: Option<String>,
+    pub price: f64,
+    pub updated_at: i64,
+}
+
+/// Transaction counts by outcome
+#[derive(Debug, Clone)]
+pub struct TxnCounts {
+    pub yes_count: u32,
+    pub no_count: u32,
+}
+
+/// Aggregated trade stats for a market
+#[derive(Debug, Clone)]
+pub struct MarketTradeStats {
+    pub market_id: String,
+    pub volume: f64,
+    pub yes_count: u32,
+    pub no_count: u32,
+    pub vwap: Option<f64>,
+    pub flow: TradeFlow,
+    pub earliest_price: Option<f64>,
+    /// Distinct maker/taker addresses (None when the platform provides none)
+    pub unique_traders: Option<u32>,
+}
+
+/// Buy/sell order flow over a time range (notional volumes)
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TradeFlow {
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub buy_count: u32,
+    pub sell_count: u32,
+    /// buy_volume - sell_volume
+    pub net_flow: f64,
+}
+
+impl TradeFlow {
+    /// Create a flow summary, deriving net_flow
+    pub fn new(buy_volume: f64, sell_volume: f64, buy_count: u32, sell_count: u32) -> Self {
+        Self {
+            buy_volume,
+            sell_volume,
+            buy_count,
+            sell_count,
+            net_flow: buy_volume - sell_volume,
+        }
+    }
+}
+
+/// Errors that can occur during trade storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum TradeStorageError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("IO error: {0}")]
+    Io(String),
+
+    #[error("Failed to acquire lock")]
+    LockError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn create_test_trade(id: &str, market_id: &str, price: f64, timestamp_offset: i64) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: market_id.to_string(),
+            platform: Platform::Kalshi,
+            timestamp: Utc::now() + chrono::Duration::seconds(timestamp_offset),
+            price: Decimal::try_from(price).unwrap(),
+            quantity: dec!(100),
+            outcome: TradeOutcome::Yes,
+            side: Some(TradeSide::Buy),
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[test]
+    fn test_store_and_retrieve_trade() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let trade = create_test_trade("trade1", "market1", 0.55, 0);
+        storage.store_trade(&trade).unwrap();
+
+        let latest = storage
+            .get_latest_trade(Platform::Kalshi, "market1")
+            .unwrap();
+        assert!(latest.is_some());
+        assert_eq!(latest.unwrap().id, "trade1");
+    }
+
+    #[test]
+    fn test_store_batch_trades() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let trades = vec![
+            create_test_trade("trade1", "market1", 0.50, -100),
+            create_test_trade("trade2", "market1", 0.55, -50),
+            create_test_trade("trade3", "market1", 0.60, 0),
+        ];
+
+        let stored = storage.store_trades(&trades).unwrap();
+        assert_eq!(stored, 3);
+
+        let count = storage.get_trade_count(Platform::Kalshi, "market1").unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_store_trades_large_batch() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let trades: Vec<Trade> = (0..10_000)
+            .map(|i| create_test_trade(&format!("trade{}", i), "market1", 0.5, -(i as i64)))
+            .collect();
+
+        // One transaction for the whole batch; per-row autocommit took 10x+ longer
+        let started = std::time::Instant::now();
+        let stored = storage.store_trades(&trades).unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(stored, 10_000);
+
+        // Duplicates are ignored and not counted as inserted
+        assert_eq!(storage.store_trades(&trades[..100]).unwrap(), 0);
+        assert_eq!(
+            storage.get_trade_count(Platform::Kalshi, "market1").unwrap(),
+            10_000
+        );
+    }
+
+    #[test]
+    fn test_migrates_v0_database() {
+        let db_path = std::env::temp_dir().join(format!(
+            "trade_storage_migration_test_{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&db_path);
+
+        // A pre-migration database: original trades table, user_version = 0
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                r#"
+                CREATE TABLE trades (
+                    id TEXT PRIMARY KEY,
+                    platform TEXT NOT NULL,
+                    market_id TEXT NOT NULL,
+                    timestamp INTEGER NOT NULL,
+                    price REAL NOT NULL,
+                    quantity REAL NOT NULL,
+                    outcome TEXT NOT NULL,
+                    side TEXT,
+                    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+                );
+                INSERT INTO trades (id, platform, market_id, timestamp, price, quantity, outcome, side)
+                VALUES ('old', 'kalshi', 'market1', strftime('%s', 'now'), 0.4, 10, 'yes', 'buy');
+                "#,
+            )
+            .unwrap();
+        }
+
+        let storage = TradeStorage::new(&db_path).unwrap();
+        assert_eq!(storage.schema_version().unwrap(), MIGRATIONS.len());
+
+        let mut trade = create_test_trade("new", "market1", 0.6, 10);
+        trade.transaction_hash = Some("0xabc123".to_string());
+        storage.store_trade(&trade).unwrap();
+
+        let trades = storage
+            .get_trades(
+                Platform::Kalshi,
+                "market1",
+                Utc::now() - chrono::Duration::hours(1),
+                Utc::now() + chrono::Duration::hours(1),
+            )
+            .unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].id, "old");
+        assert_eq!(trades[0].transaction_hash, None);
+        assert_eq!(trades[1].transaction_hash.as_deref(), Some("0xabc123"));
+
+        // Reopening does not re-run migrations
+        drop(storage);
+        let storage = TradeStorage::new(&db_path).unwrap();
+        assert_eq!(storage.schema_version().unwrap(), MIGRATIONS.len());
+
+        drop(storage);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_transaction_hash_round_trip() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let mut with_hash_a = create_test_trade("trade1", "market1", 0.50, -10);
+        with_hash_a.transaction_hash = Some("0xfeed".to_string());
+        let mut with_hash_b = create_test_trade("trade2", "market2", 0.52, -5);
+        with_hash_b.transaction_hash = Some("0xfeed".to_string());
+        let without_hash = create_test_trade("trade3", "market1", 0.55, 0);
+
+        storage.store_trade(&with_hash_a).unwrap();
+        storage
+            .store_trades(&[with_hash_b, without_hash])
+            .unwrap();
+
+        let latest = storage
+            .get_latest_trade(Platform::Kalshi, "market1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.id, "trade3");
+        assert_eq!(latest.transaction_hash, None);
+
+        let by_hash = storage.get_trades_by_transaction_hash("0xfeed").unwrap();
+        let ids: Vec<&str> = by_hash.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["trade1", "trade2"]);
+        assert!(by_hash
+            .iter()
+            .all(|t| t.transaction_hash.as_deref() == Some("0xfeed")));
+
+        assert!(storage
+            .get_trades_by_transaction_hash("0xunknown")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_prune_trades() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let day = 86400;
+        storage
+            .store_trades(&[
+                create_test_trade("old", "market1", 0.50, -40 * day),
+                create_test_trade("recent", "market1", 0.55, -day),
+                create_test_trade("old_tracked", "market2", 0.60, -40 * day),
+            ])
+            .unwrap();
+
+        let keep = vec![(Platform::Kalshi, "market2".to_string())];
+        let deleted = storage.prune_trades(30, Some(&keep)).unwrap();
+        assert_eq!(deleted, 1);
+
+        assert!(!storage.trade_exists("old").unwrap());
+        assert!(storage.trade_exists("recent").unwrap());
+        assert!(storage.trade_exists("old_tracked").unwrap());
+
+        // Without exemptions the tracked market's old trade goes too
+        assert_eq!(storage.prune_trades(30, None).unwrap(), 1);
+        assert!(!storage.trade_exists("old_tracked").unwrap());
+
+        storage.vacuum().unwrap();
+    }
+
+    #[test]
+    fn test_prune_trades_keeps_only_the_tracked_platform() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let day = 86400;
+        let mut polymarket = create_test_trade("old_polymarket", "shared", 0.50, -40 * day);
+        polymarket.platform = Platform::Polymarket;
+        storage
+            .store_trades(&[create_test_trade("old_kalshi", "shared", 0.50, -40 * day), polymarket])
+            .unwrap();
+
+        // Only the Kalshi market is tracked; the same ID on Polymarket is not
+        let keep = vec![(Platform::Kalshi, "shared".to_string())];
+        assert_eq!(storage.prune_trades(30, Some(&keep)).unwrap(), 1);
+
+        assert!(storage.trade_exists("old_kalshi").unwrap());
+        assert!(!storage.trade_exists("old_polymarket").unwrap());
+    }
+
+    #[test]
+    fn test_export_trades_csv() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let mut trades: Vec<Trade> = (0..5)
+            .map(|i| create_test_trade(&format!("trade{}", i), "market1", 0.5, -(i as i64)))
+            .collect();
+        trades[0].id = "trade,with\"comma".to_string();
+        trades.push(create_test_trade("other", "market2", 0.5, 0));
+        storage.store_trades(&trades).unwrap();
+
+        let mut out = Vec::new();
+        let written = storage
+            .export_trades(
+                Platform::Kalshi,
+                "market1",
+                Utc::now() - chrono::Duration::hours(1),
+                Utc::now() + chrono::Duration::hours(1),
+                TradeExportFormat::Csv,
+                &mut out,
+            )
+            .unwrap();
+        assert_eq!(written, 5);
+
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,timestamp,price,quantity,outcome,side");
+        assert_eq!(lines.len(), 6);
+        assert!(csv.contains("\"trade,with\"\"comma\""));
+        assert!(lines[1..].iter().all(|l| l.ends_with(",yes,buy")));
+
+        let mut out = Vec::new();
+        storage
+            .export_trades(
+                Platform::Kalshi,
+                "market1",
+                Utc::now() - chrono::Duration::hours(1),
+                Utc::now() + chrono::Duration::hours(1),
+                TradeExportFormat::JsonLines,
+                &mut out,
+            )
+            .unwrap();
+        let jsonl = String::from_utf8(out).unwrap();
+        assert_eq!(jsonl.lines().count(), 5);
+        for line in jsonl.lines() {
+            let trade: Trade = serde_json::from_str(line).unwrap();
+            assert_eq!(trade.market_id, "market1");
+        }
+    }
+
+    /// A writer that stalls on its `stall_at`th write until released
+    struct StallingWriter {
+        writes: usize,
+        stall_at: usize,
+        stalled: std::sync::mpsc::Sender<()>,
+        release: std::sync::mpsc::Receiver<()>,
+    }
+
+    impl std::io::Write for StallingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.writes += 1;
+            if self.writes == self.stall_at {
+                let _ = self.stalled.send(());
+                let _ = self.release.recv();
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stalled_export_holds_no_reader() {
+        let db_path = std::env::temp_dir().join(format!(
+            "trade_storage_export_test_{}.db",
+            std::process::id()
+        ));
+        let storage = std::sync::Arc::new(
+            TradeStorage::with_config(
+                &db_path,
+                TradeStorageConfig {
+                    read_pool_size: 1,
+                    ..TradeStorageConfig::default()
+                },
+            )
+            .unwrap(),
+        );
+        let trades: Vec<Trade> = (0..(EXPORT_PAGE_SIZE + 10))
+            .map(|i| create_test_trade(&format!("t{:05}", i), "market1", 0.5, -((i % 50) as i64)))
+            .collect();
+        storage.store_trades(&trades).unwrap();
+
+        // The download stalls mid-export, after the first page was read
+        let (stalled_tx, stalled_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel();
+        let export = {
+            let storage = std::sync::Arc::clone(&storage);
+            std::thread::spawn(move || {
+                let mut writer = StallingWriter {
+                    writes: 0,
+                    stall_at: 10,
+                    stalled: stalled_tx,
+                    release: release_rx,
+                };
+                storage.export_trades(
+                    Platform::Kalshi,
+                    "market1",
+                    Utc::now() - chrono::Duration::hours(1),
+                    Utc::now() + chrono::Duration::hours(1),
+                    TradeExportFormat::JsonLines,
+                    &mut writer,
+                )
+            })
+        };
+        stalled_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // The only reader is free for everyone else meanwhile
+        let (read_tx, read_rx) = std::sync::mpsc::channel();
+        {
+            let storage = std::sync::Arc::clone(&storage);
+            std::thread::spawn(move || {
+                let _ = read_tx.send(storage.get_trade_count(Platform::Kalshi, "market1").unwrap());
+            });
+        }
+        let count = read_rx.recv_timeout(Duration::from_secs(5));
+        release_tx.send(()).unwrap();
+        assert_eq!(count, Ok(EXPORT_PAGE_SIZE + 10));
+
+        // Every trade exported once, across pages
+        assert_eq!(export.join().unwrap().unwrap(), EXPORT_PAGE_SIZE + 10);
+
+        drop(storage);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_bulk_stats_in_range() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let mut no_trade = create_test_trade("m2-b", "market2", 0.30, -10);
+        no_trade.outcome = TradeOutcome::No;
+        let mut polymarket_trade = create_test_trade("m1-poly", "market1", 0.99, -500);
+        polymarket_trade.platform = Platform::Polymarket;
+
+        storage
+            .store_trades(&[
+                create_test_trade("m1-a", "market1", 0.40, -300),
+                create_test_trade("m1-b", "market1", 0.60, -100),
+                create_test_trade("m2-a", "market2", 0.20, -200),
+                no_trade,
+                create_test_trade("m3-a", "market3", 0.75, -50),
+                // Same market ID on another platform must not leak into Kalshi stats
+                polymarket_trade,
+                // Outside the range
+                create_test_trade("m3-old", "market3", 0.10, -10_000),
+            ])
+            .unwrap();
+
+        let market_ids: Vec<String> = vec!["market1".into(), "market2".into(), "market3".into()];
+        let stats = storage
+            .get_bulk_stats_in_range(
+                Platform::Kalshi,
+                &market_ids,
+                Utc::now() - chrono::Duration::hours(1),
+                Utc::now(),
+            )
+            .unwrap();
+        let stats: std::collections::HashMap<_, _> =
+            stats.into_iter().map(|s| (s.market_id.clone(), s)).collect();
+        assert_eq!(stats.len(), 3);
+
+        // quantity is 100 for every fixture trade
+        let m1 = &stats["market1"];
+        assert!((m1.volume - 100.0).abs() < 1e-9);
+        assert_eq!((m1.yes_count, m1.no_count), (2, 0));
+        assert!((m1.earliest_price.unwrap() - 0.40).abs() < 1e-9);
+
+        let m2 = &stats["market2"];
+        assert!((m2.volume - 50.0).abs() < 1e-9);
+        assert_eq!((m2.yes_count, m2.no_count), (1, 1));
+        assert!((m2.earliest_price.unwrap() - 0.20).abs() < 1e-9);
+
+        let m3 = &stats["market3"];
+        assert!((m3.volume - 75.0).abs() < 1e-9);
+        assert_eq!((m3.yes_count, m3.no_count), (1, 0));
+        assert!((m3.earliest_price.unwrap() - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_prices_at_time_batch() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let target = 1_700_000_000i64;
+
+        // Markets 0..40 get snapshots before, at and after the target; 40..50 get none
+        {
+            let conn = storage.write_conn().unwrap();
+            for i in 0..40i64 {
+                let market_id = format!("market{}", i);
+                for (offset, price) in [(-3600 - i, 0.1), (-i, 0.2), (600, 0.9)] {
+                    conn.execute(
+                        "INSERT INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price) VALUES ('kalshi', ?1, ?2, ?3, NULL)",
+                        params![market_id, target + offset, price + i as f64 / 1000.0],
+                    )
+                    .unwrap();
+                }
             }
+            // Another platform's snapshot for an otherwise empty market is ignored
+            conn.execute(
+                "INSERT INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price) VALUES ('polymarket', 'market45', ?1, 0.5, NULL)",
+                params![target - 10],
+            )
+            .unwrap();
+        }
+
+        let market_ids: Vec<String> = (0..50).map(|i| format!("market{}", i)).collect();
+        let results = storage
+            .get_prices_at_time_batch(
+                Platform::Kalshi,
+                &market_ids,
+                DateTime::from_timestamp(target, 0).unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(results.len(), 40);
+        for (market_id, snapshot) in results {
+            let i: i64 = market_id.trim_start_matches("market").parse().unwrap();
+            assert!(i < 40);
+            assert_eq!(snapshot.timestamp, target - i);
+            assert!((snapshot.yes_price - (0.2 + i as f64 / 1000.0)).abs() < 1e-9);
         }
+    }
 
-        Ok(results)
+    #[test]
+    fn test_multi_outcome_market() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let outcomes = [("tok-a", "Chiefs", 0.45), ("tok-b", "Eagles", 0.35), ("tok-c", "Bills", 0.20)];
+
+        for (i, (outcome_id, label, price)) in outcomes.iter().enumerate() {
+            let trades: Vec<Trade> = (0..3)
+                .map(|j| {
+                    let mut trade = create_test_trade(
+                        &format!("{}-{}", outcome_id, j),
+                        "superbowl",
+                        *price,
+                        -((i * 10 + j) as i64),
+                    );
+                    trade.platform = Platform::Polymarket;
+                    trade.outcome_id = Some(outcome_id.to_string());
+                    trade
+                })
+                .collect();
+            storage.store_trades(&trades).unwrap();
+            storage
+                .store_price_for_outcome(Platform::Polymarket, "superbowl", outcome_id, Some(label), *price)
+                .unwrap();
+            storage
+                .store_candle_for_outcome(
+                    Platform::Polymarket,
+                    "superbowl",
+                    outcome_id,
+                    "1h",
+                    &StoredCandle {
+                        timestamp: 3600,
+                        open: *price,
+                        high: *price,
+                        low: *price,
+                        close: *price,
+                        volume: 300.0,
+                        trade_count: 3,
+                    },
+                )
+                .unwrap();
+        }
+
+        let from = Utc::now() - chrono::Duration::hours(1);
+        let to = Utc::now() + chrono::Duration::hours(1);
+        for (outcome_id, _, price) in &outcomes {
+            let trades = storage
+                .get_trades_for_outcome(Platform::Polymarket, "superbowl", outcome_id, from, to)
+                .unwrap();
+            assert_eq!(trades.len(), 3);
+            assert!(trades.iter().all(|t| t.outcome_id.as_deref() == Some(*outcome_id)));
+            assert!(trades.iter().all(|t| t.price == Decimal::try_from(*price).unwrap()));
+
+            let candles = storage
+                .get_candles_for_outcome(
+                    Platform::Polymarket,
+                    "superbowl",
+                    outcome_id,
+                    "1h",
+                    DateTime::from_timestamp(0, 0).unwrap(),
+                    to,
+                )
+                .unwrap();
+            assert_eq!(candles.len(), 1);
+            assert!((candles[0].close - price).abs() < 1e-9);
+        }
+
+        // Whole-market view still sees every trade but no outcome candles
+        assert_eq!(storage.get_trade_count(Platform::Polymarket, "superbowl").unwrap(), 9);
+        assert!(storage
+            .get_candles(Platform::Polymarket, "superbowl", "1h", DateTime::from_timestamp(0, 0).unwrap(), to)
+            .unwrap()
+            .is_empty());
+
+        let prices = storage.get_prices_for_market(Platform::Polymarket, "superbowl").unwrap();
+        let labels: Vec<_> = prices.iter().map(|p| p.outcome_label.as_deref().unwrap()).collect();
+        assert_eq!(labels, vec!["Chiefs", "Eagles", "Bills"]);
     }
 
-    /// Prune old price snapshots (keep only last N days)
-    pub fn prune_price_snapshots(&self, older_than_days: u64) -> Result<usize, TradeStorageError> {
-        let conn = self.conn.lock().map_err(|_| TradeStorageError::LockError)?;
+    #[test]
+    fn test_vwap_and_twap() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let from = Utc::now() - chrono::Duration::hours(1);
+        let to = Utc::now() + chrono::Duration::hours(1);
+
+        // No trades: None rather than NaN
+        assert_eq!(storage.get_vwap_in_range(Platform::Kalshi, "market1", from, to).unwrap(), None);
+        assert_eq!(storage.get_twap_in_range(Platform::Kalshi, "market1", from, to).unwrap(), None);
+
+        // Single trade: both equal its price
+        storage.store_trade(&create_test_trade("single", "single", 0.42, 0)).unwrap();
+        let vwap = storage.get_vwap_in_range(Platform::Kalshi, "single", from, to).unwrap().unwrap();
+        let twap = storage.get_twap_in_range(Platform::Kalshi, "single", from, to).unwrap().unwrap();
+        assert!((vwap - 0.42).abs() < 1e-9);
+        assert!((twap - 0.42).abs() < 1e-9);
+
+        // Bucket-aligned fixture so minute boundaries are deterministic
+        let base = (Utc::now().timestamp() / 60) * 60 - 600;
+        let at = |id: &str, price: f64, quantity: rust_decimal::Decimal, ts: i64| {
+            let mut trade = create_test_trade(id, "market1", price, 0);
+            trade.quantity = quantity;
+            trade.timestamp = DateTime::from_timestamp(ts, 0).unwrap();
+            trade
+        };
+        storage
+            .store_trades(&[
+                // Minute 1: last price 0.60
+                at("a", 0.40, dec!(100), base + 5),
+                at("b", 0.60, dec!(300), base + 30),
+                // Minute 2: last price 0.50
+                at("c", 0.50, dec!(100), base + 65),
+                // Minute 4: last price 0.70
+                at("d", 0.70, dec!(500), base + 200),
+            ])
+            .unwrap();
 
-        let cutoff = chrono::Utc::now().timestamp() - (older_than_days as i64 * 86400);
+        // VWAP = (40 + 180 + 50 + 350) / 1000 = 0.62
+        let vwap = storage.get_vwap_in_range(Platform::Kalshi, "market1", from, to).unwrap().unwrap();
+        assert!((vwap - 0.62).abs() < 1e-9);
 
-        let deleted = conn
-            .execute(
-                "DELETE FROM price_snapshots WHERE timestamp < ?1",
-                params![cutoff],
+        // TWAP = (0.60 + 0.50 + 0.70) / 3 = 0.60
+        let twap = storage.get_twap_in_range(Platform::Kalshi, "market1", from, to).unwrap().unwrap();
+        assert!((twap - 0.60).abs() < 1e-9);
+
+        let batch = storage
+            .get_twap_batch(Platform::Kalshi, &["market1".into(), "single".into(), "none".into()], from, to)
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+    }
+
+    #[test]
+    fn test_volume_buckets() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let trades: Vec<Trade> = (0..20)
+            .map(|i| create_test_trade(&format!("trade{}", i), "market1", 0.5, -(i as i64) * 700))
+            .collect();
+        storage.store_trades(&trades).unwrap();
+
+        let from = Utc::now() - chrono::Duration::hours(6);
+        let to = Utc::now();
+        let buckets = storage
+            .get_volume_buckets(Platform::Kalshi, "market1", 3600, from, to)
+            .unwrap();
+
+        assert!(!buckets.is_empty());
+        assert!(buckets.iter().all(|(start, _, _)| start % 3600 == 0));
+        assert!(buckets.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(buckets.iter().map(|(_, _, count)| count).sum::<u32>(), 20);
+
+        let total: f64 = buckets.iter().map(|(_, volume, _)| volume).sum();
+        let expected = storage.get_volume_in_range(Platform::Kalshi, "market1", from, to).unwrap();
+        assert!((total - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flow_in_range() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let mut sell = create_test_trade("sell", "market1", 0.40, -20);
+        sell.side = Some(TradeSide::Sell);
+        let mut unknown = create_test_trade("unknown", "market1", 0.90, -10);
+        unknown.side = None;
+        storage
+            .store_trades(&[
+                create_test_trade("buy1", "market1", 0.50, -40),
+                create_test_trade("buy2", "market1", 0.60, -30),
+                sell,
+                unknown,
+            ])
+            .unwrap();
+
+        let flow = storage
+            .get_flow_in_range(
+                Platform::Kalshi,
+                "market1",
+                Utc::now() - chrono::Duration::hours(1),
+                Utc::now(),
             )
-            .map_err(TradeStorageError::Database)?;
+            .unwrap();
 
-        Ok(deleted)
+        assert!((flow.buy_volume - 110.0).abs() < 1e-9);
+        assert!((flow.sell_volume - 40.0).abs() < 1e-9);
+        assert_eq!((flow.buy_count, flow.sell_count), (2, 1));
+        assert!((flow.net_flow - (flow.buy_volume - flow.sell_volume)).abs() < 1e-9);
+
+        let stats = storage
+            .get_bulk_stats_in_range(
+                Platform::Kalshi,
+                &["market1".to_string()],
+                Utc::now() - chrono::Duration::hours(1),
+                Utc::now(),
+            )
+            .unwrap();
+        assert_eq!(stats[0].flow, flow);
     }
-}
 
-/// Price snapshot data
-#[derive(Debug, Clone)]
-pub struct PriceSnapshot {
-    pub timestamp: i64,
-    pub yes_price: f64,
-    pub no_price: Option<f64>,
-}
+    #[test]
+    fn test_unique_trader_count() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let from = Utc::now() - chrono::Duration::hours(1);
+        let to = Utc::now();
+
+        let with_addresses = |id: &str, maker: Option<&str>, taker: Option<&str>| {
+            let mut trade = create_test_trade(id, "poly", 0.5, -10);
+            trade.platform = Platform::Polymarket;
+            trade.maker_address = maker.map(String::from);
+            trade.taker_address = taker.map(String::from);
+            trade
+        };
+        storage
+            .store_trades(&[
+                with_addresses("t1", Some("0xAAA"), Some("0xbbb")),
+                // Same wallets with roles swapped and different case
+                with_addresses("t2", Some("0xBBB"), Some("0xaaa")),
+                with_addresses("t3", None, Some("0xccc")),
+                with_addresses("t4", None, None),
+            ])
+            .unwrap();
 
-/// Stored price data
-#[derive(Debug, Clone)]
-pub struct StoredPrice {
-    pub yes_price: Option<f64>,
-    pub no_price: Option<f64>,
-    pub spread: Option<f64>,
-    pub updated_at: i64,
-}
+        assert_eq!(
+            storage.get_unique_trader_count(Platform::Polymarket, "poly", from, to).unwrap(),
+            Some(3)
+        );
 
-/// Stored orderbook snapshot
-#[derive(Debug, Clone)]
-pub struct OrderbookSnapshot {
-    pub timestamp: i64,
-    pub yes_bids: Option<String>,
-    pub yes_asks: Option<String>,
-    pub no_bids: Option<String>,
-    pub no_asks: Option<String>,
-}
+        // Kalshi trades carry no addresses: unknown, not zero
+        storage.store_trade(&create_test_trade("k1", "kalshi", 0.5, -10)).unwrap();
+        assert_eq!(
+            storage.get_unique_trader_count(Platform::Kalshi, "kalshi", from, to).unwrap(),
+            None
+        );
 
-/// Stored candle data
-#[derive(Debug, Clone)]
-pub struct StoredCandle {
-    pub timestamp: i64,
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub close: f64,
-    pub volume: f64,
-    pub trade_count: i64,
-}
+        let bulk = storage
+            .get_bulk_stats_in_range(Platform::Polymarket, &["poly".to_string()], from, to)
+            .unwrap();
+        assert_eq!(bulk[0].unique_traders, Some(3));
+    }
 
-/// Transaction counts by outcome
-#[derive(Debug, Clone)]
-pub struct TxnCounts {
-    pub yes_count: u32,
-    pub no_count: u32,
-}
+    #[test]
+    fn test_large_trades() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let from = Utc::now() - chrono::Duration::hours(1);
+        let to = Utc::now();
+
+        // Notional = price * 100
+        storage
+            .store_trades(&[
+                create_test_trade("small", "m1", 0.10, -50),
+                create_test_trade("mid", "m1", 0.50, -40),
+                create_test_trade("big", "m1", 0.90, -30),
+                create_test_trade("other", "m2", 0.70, -20),
+            ])
+            .unwrap();
 
-/// Aggregated trade stats for a market
-#[derive(Debug, Clone)]
-pub struct MarketTradeStats {
-    pub market_id: String,
-    pub volume: f64,
-    pub yes_count: u32,
-    pub no_count: u32,
-    pub earliest_price: Option<f64>,
-}
+        let large = storage
+            .get_large_trades(Platform::Kalshi, "m1", 40.0, from, to, 10)
+            .unwrap();
+        let ids: Vec<&str> = large.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["big", "mid"]);
 
-/// Errors that can occur during trade storage operations
-#[derive(Debug, thiserror::Error)]
-pub enum TradeStorageError {
-    #[error("Database error: {0}")]
-    Database(#[from] rusqlite::Error),
+        let limited = storage
+            .get_large_trades(Platform::Kalshi, "m1", 0.0, from, to, 1)
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+        assert_eq!(limited[0].id, "big");
+    }
 
-    #[error("IO error: {0}")]
-    Io(String),
+    #[test]
+    fn test_largest_trades_global() {
+        let storage = TradeStorage::new_in_memory().unwrap();
 
-    #[error("Failed to acquire lock")]
-    LockError,
-}
+        let mut poly = create_test_trade("p1", "poly-market", 0.80, -10);
+        poly.platform = Platform::Polymarket;
+        storage
+            .store_trades(&[
+                create_test_trade("k1", "kalshi-a", 0.60, -30),
+                create_test_trade("k2", "kalshi-b", 0.20, -20),
+                poly,
+                // Too old for the window
+                create_test_trade("old", "kalshi-a", 0.95, -7200),
+            ])
+            .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rust_decimal_macros::dec;
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let whales = storage.get_largest_trades_global(50.0, since, 10).unwrap();
 
-    fn create_test_trade(id: &str, market_id: &str, price: f64, timestamp_offset: i64) -> Trade {
-        Trade {
-            id: id.to_string(),
-            market_id: market_id.to_string(),
-            platform: Platform::Kalshi,
-            timestamp: Utc::now() + chrono::Duration::seconds(timestamp_offset),
-            price: Decimal::try_from(price).unwrap(),
-            quantity: dec!(100),
-            outcome: TradeOutcome::Yes,
-            side: Some(TradeSide::Buy),
-            transaction_hash: None,
+        let summary: Vec<(&str, Platform, &str)> = whales
+            .iter()
+            .map(|t| (t.id.as_str(), t.platform, t.market_id.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("p1", Platform::Polymarket, "poly-market"),
+                ("k1", Platform::Kalshi, "kalshi-a"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_orderbook_snapshot_metrics() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let mut book = OrderBook::new("ob-market".to_string(), Platform::Kalshi);
+        book.yes_bids = vec![
+            OrderBookLevel::new(dec!(0.45), dec!(100)),
+            OrderBookLevel::new(dec!(0.44), dec!(200)),
+        ];
+        book.yes_asks = vec![
+            OrderBookLevel::new(dec!(0.47), dec!(50)),
+            OrderBookLevel::new(dec!(0.50), dec!(100)),
+        ];
+
+        let metrics = OrderbookMetrics::from_book(&book);
+        assert!((metrics.best_bid.unwrap() - 0.45).abs() < 1e-9);
+        assert!((metrics.best_ask.unwrap() - 0.47).abs() < 1e-9);
+        assert!((metrics.mid.unwrap() - 0.46).abs() < 1e-9);
+        assert!((metrics.spread.unwrap() - 0.02).abs() < 1e-9);
+        // 0.45*100 + 0.44*200 = 133, 0.47*50 + 0.50*100 = 73.5
+        assert!((metrics.bid_depth_usd - 133.0).abs() < 1e-9);
+        assert!((metrics.ask_depth_usd - 73.5).abs() < 1e-9);
+
+        storage
+            .store_orderbook_snapshot(Platform::Kalshi, "ob-market", "[]", "[]", "[]", "[]", &metrics)
+            .unwrap();
+
+        let history = storage
+            .get_spread_history(
+                Platform::Kalshi,
+                "ob-market",
+                Utc::now() - chrono::Duration::minutes(1),
+                Utc::now() + chrono::Duration::minutes(1),
+            )
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].metrics, metrics);
+
+        // Metrics recomputed from stored level JSON match the live book's
+        let snapshot = OrderbookSnapshot {
+            timestamp: 0,
+            yes_bids: Some(serde_json::to_string(&book.yes_bids).unwrap()),
+            yes_asks: Some(serde_json::to_string(&book.yes_asks).unwrap()),
+            no_bids: None,
+            no_asks: Some("not json".to_string()),
+        };
+        assert_eq!(OrderbookMetrics::from_snapshot(&snapshot), metrics);
+
+        // An empty book has no prices and zero depth
+        let empty = OrderbookMetrics::from_book(&OrderBook::new("x".to_string(), Platform::Kalshi));
+        assert_eq!(empty.mid, None);
+        assert_eq!(empty.bid_depth_usd, 0.0);
+    }
+
+    /// JSON for a realistic 50-level book side
+    fn sample_levels(start: f64, step: f64) -> String {
+        let levels: Vec<OrderBookLevel> = (0..50)
+            .map(|i| {
+                OrderBookLevel::new(
+                    Decimal::try_from(start + step * i as f64).unwrap().round_dp(2),
+                    Decimal::from(100 + i * 37),
+                )
+            })
+            .collect();
+        serde_json::to_string(&levels).unwrap()
+    }
+
+    #[test]
+    fn test_compressed_snapshot_round_trip() {
+        let mut storage = TradeStorage::new_in_memory().unwrap();
+        let bids = sample_levels(0.49, -0.01);
+        let asks = sample_levels(0.51, 0.01);
+        let metrics = OrderbookMetrics::default();
+
+        // Existing plain-JSON row, then a compressed one
+        storage
+            .store_orderbook_snapshot(Platform::Kalshi, "m", &bids, &asks, "[]", "[]", &metrics)
+            .unwrap();
+        storage.set_snapshot_encoding(SnapshotEncoding::JsonDeflate);
+        storage
+            .store_orderbook_snapshot(Platform::Kalshi, "m", &bids, &asks, "[]", "[]", &metrics)
+            .unwrap();
+
+        let snapshots = storage
+            .get_orderbook_snapshots(
+                Platform::Kalshi,
+                "m",
+                Utc::now() - chrono::Duration::minutes(1),
+                Utc::now() + chrono::Duration::minutes(1),
+                None,
+            )
+            .unwrap();
+        assert_eq!(snapshots.len(), 2);
+        for snapshot in &snapshots {
+            assert_eq!(snapshot.yes_bids.as_deref(), Some(bids.as_str()));
+            assert_eq!(snapshot.yes_asks.as_deref(), Some(asks.as_str()));
+            assert_eq!(snapshot.no_bids.as_deref(), Some("[]"));
         }
     }
 
     #[test]
-    fn test_store_and_retrieve_trade() {
+    fn test_compressed_snapshot_is_smaller() {
+        let mut storage = TradeStorage::new_in_memory().unwrap();
+        let bids = sample_levels(0.49, -0.01);
+        let asks = sample_levels(0.51, 0.01);
+        let metrics = OrderbookMetrics::default();
+
+        storage
+            .store_orderbook_snapshot(Platform::Kalshi, "plain", &bids, &asks, &asks, &bids, &metrics)
+            .unwrap();
+        storage.set_snapshot_encoding(SnapshotEncoding::JsonDeflate);
+        storage
+            .store_orderbook_snapshot(Platform::Kalshi, "packed", &bids, &asks, &asks, &bids, &metrics)
+            .unwrap();
+
+        let conn = storage.write_conn().unwrap();
+        let stored_size = |market_id: &str| -> i64 {
+            conn.query_row(
+                "SELECT length(yes_bids) + length(yes_asks) + length(no_bids) + length(no_asks)
+                 FROM orderbook_snapshots WHERE market_id = ?1",
+                params![market_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        let plain = stored_size("plain");
+        let packed = stored_size("packed");
+        assert!(packed * 2 < plain, "compressed {} vs plain {} bytes", packed, plain);
+    }
+
+    #[test]
+    fn test_store_resolution_is_idempotent() {
         let storage = TradeStorage::new_in_memory().unwrap();
+        assert!(storage.get_resolution(Platform::Kalshi, "m").unwrap().is_none());
 
-        let trade = create_test_trade("trade1", "market1", 0.55, 0);
-        storage.store_trade(&trade).unwrap();
+        let resolution = MarketResolution {
+            platform: Platform::Kalshi,
+            market_id: "m".to_string(),
+            resolved_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            winning_outcome: Some("yes".to_string()),
+            final_price: Some(1.0),
+        };
+        assert!(storage.store_resolution(&resolution).unwrap());
+        assert_eq!(storage.get_resolution(Platform::Kalshi, "m").unwrap(), Some(resolution.clone()));
+
+        // Re-storing (even with different data) keeps the original record
+        let later = MarketResolution {
+            resolved_at: DateTime::from_timestamp(1_800_000_000, 0).unwrap(),
+            winning_outcome: Some("no".to_string()),
+            ..resolution.clone()
+        };
+        assert!(!storage.store_resolution(&later).unwrap());
+        assert_eq!(storage.get_resolution(Platform::Kalshi, "m").unwrap(), Some(resolution));
+    }
 
-        let latest = storage
-            .get_latest_trade(Platform::Kalshi, "market1")
+    #[test]
+    fn test_recent_alerts_newest_first() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let alert = |platform, seconds: i64, kind| MarketAlert {
+            platform,
+            market_id: "m".to_string(),
+            kind,
+            magnitude: 6.5,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap(),
+        };
+        storage.store_alert(&alert(Platform::Kalshi, 0, AlertKind::LargePrint)).unwrap();
+        storage.store_alert(&alert(Platform::Polymarket, 10, AlertKind::PriceJump)).unwrap();
+        storage.store_alert(&alert(Platform::Kalshi, 20, AlertKind::VolumeSpike)).unwrap();
+
+        let recent = storage.get_recent_alerts(None, 2).unwrap();
+        assert_eq!(recent, vec![
+            alert(Platform::Kalshi, 20, AlertKind::VolumeSpike),
+            alert(Platform::Polymarket, 10, AlertKind::PriceJump),
+        ]);
+
+        let kinds: Vec<AlertKind> = storage
+            .get_recent_alerts(Some(Platform::Kalshi), 10)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.kind)
+            .collect();
+        assert_eq!(kinds, vec![AlertKind::VolumeSpike, AlertKind::LargePrint]);
+    }
+
+    #[test]
+    fn test_gaps_since_include_overlapping_windows() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+        let gap = |market_id: Option<&str>, start: i64, end: i64, reason| DataGap {
+            platform: Platform::Polymarket,
+            market_id: market_id.map(String::from),
+            started_at: DateTime::from_timestamp(1_700_000_000 + start, 0).unwrap(),
+            ended_at: DateTime::from_timestamp(1_700_000_000 + end, 0).unwrap(),
+            reason,
+        };
+        storage.store_gap(&gap(None, 0, 30, GapReason::Reconnect)).unwrap();
+        storage.store_gap(&gap(Some("m"), 100, 102, GapReason::Resync)).unwrap();
+        storage.store_gap(&gap(None, 50, 120, GapReason::Reconnect)).unwrap();
+
+        // Anything still open at `since` counts, even if it started earlier
+        let since = DateTime::from_timestamp(1_700_000_060, 0).unwrap();
+        assert_eq!(storage.get_gaps_since(since, 10).unwrap(), vec![
+            gap(None, 50, 120, GapReason::Reconnect),
+            gap(Some("m"), 100, 102, GapReason::Resync),
+        ]);
+        assert_eq!(storage.get_gaps_since(DateTime::UNIX_EPOCH, 10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_price_extremes_persist_across_restart() {
+        let db_path = std::env::temp_dir().join(format!(
+            "trade_storage_extremes_test_{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&db_path);
+
+        {
+            let storage = TradeStorage::new(&db_path).unwrap();
+            assert!(storage.get_price_extremes(Platform::Kalshi, "m").unwrap().is_none());
+
+            storage.store_price_snapshot_at(Platform::Kalshi, "m", 1_000, 0.40, None).unwrap();
+            storage.store_price_snapshot_at(Platform::Kalshi, "m", 2_000, 0.60, None).unwrap();
+            storage.store_price_snapshot_at(Platform::Kalshi, "m", 3_000, 0.30, None).unwrap();
+            assert_eq!(
+                storage.get_price_extremes(Platform::Kalshi, "m").unwrap(),
+                Some(PriceExtremes { ath_price: 0.60, ath_timestamp: 2_000, atl_price: 0.30, atl_timestamp: 3_000 })
+            );
+
+            // A candle's high sets a new ATH; an equal low keeps the earlier ATL
+            let candle = StoredCandle {
+                timestamp: 3_600,
+                open: 0.5,
+                high: 0.75,
+                low: 0.30,
+                close: 0.7,
+                volume: 10.0,
+                trade_count: 2,
+            };
+            storage
+                .upsert_price_candles(Platform::Kalshi, "m", "", "1h", std::slice::from_ref(&candle))
+                .unwrap();
+            // Outcome series don't count towards the market's extremes
+            storage
+                .store_candle_for_outcome(Platform::Kalshi, "m", "token", "1h", &StoredCandle { high: 0.95, ..candle })
+                .unwrap();
+        }
+
+        let storage = TradeStorage::new(&db_path).unwrap();
+        assert_eq!(
+            storage.get_price_extremes(Platform::Kalshi, "m").unwrap(),
+            Some(PriceExtremes { ath_price: 0.75, ath_timestamp: 3_600, atl_price: 0.30, atl_timestamp: 3_000 })
+        );
+        assert!(storage.get_price_extremes(Platform::Polymarket, "m").unwrap().is_none());
+
+        drop(storage);
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_storage_stats() {
+        let storage = TradeStorage::new_in_memory().unwrap();
+
+        let mut poly = create_test_trade("p1", "poly-a", 0.5, -100);
+        poly.platform = Platform::Polymarket;
+        storage
+            .store_trades(&[
+                create_test_trade("k1", "kalshi-a", 0.5, -300),
+                create_test_trade("k2", "kalshi-a", 0.5, -200),
+                create_test_trade("k3", "kalshi-b", 0.5, -50),
+                poly,
+            ])
             .unwrap();
-        assert!(latest.is_some());
-        assert_eq!(latest.unwrap().id, "trade1");
+        storage
+            .store_orderbook_snapshot(
+                Platform::Kalshi,
+                "kalshi-a",
+                "[]",
+                "[]",
+                "[]",
+                "[]",
+                &OrderbookMetrics::default(),
+            )
+            .unwrap();
+
+        let stats = storage.get_storage_stats().unwrap();
+        assert_eq!(stats.total_trades, 4);
+        assert!(!stats.approximate);
+        assert_eq!(stats.orderbook_snapshots, 1);
+        assert_eq!(stats.price_snapshots, 0);
+        assert!(stats.db_size_bytes > 0);
+
+        let kalshi = stats.platforms.iter().find(|p| p.platform == Platform::Kalshi).unwrap();
+        assert_eq!((kalshi.trade_rows, kalshi.distinct_markets), (3, 2));
+        let poly = stats.platforms.iter().find(|p| p.platform == Platform::Polymarket).unwrap();
+        assert_eq!((poly.trade_rows, poly.distinct_markets), (1, 1));
+
+        let oldest = stats.oldest_trade.unwrap();
+        let newest = stats.newest_trade.unwrap();
+        assert_eq!((newest - oldest).num_seconds(), 250);
+
+        let top = storage.get_top_markets_by_row_count(2).unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].market_id.as_str(), top[0].trade_rows), ("kalshi-a", 2));
+        assert_eq!(top[1].trade_rows, 1);
+
+        // After ANALYZE the totals come from sqlite_stat1
+        storage.write_conn().unwrap().execute_batch("ANALYZE;").unwrap();
+        let stats = storage.get_storage_stats().unwrap();
+        assert!(stats.approximate);
+        assert_eq!(stats.total_trades, 4);
     }
 
     #[test]
-    fn test_store_batch_trades() {
+    fn test_downsample_price_snapshots() {
         let storage = TradeStorage::new_in_memory().unwrap();
 
-        let trades = vec![
-            create_test_trade("trade1", "market1", 0.50, -100),
-            create_test_trade("trade2", "market1", 0.55, -50),
-            create_test_trade("trade3", "market1", 0.60, 0),
-        ];
+        // Day-aligned "now" with a snapshot every 5 minutes for the last 40 days
+        let now = 1_700_000_000 / 86400 * 86400;
+        let start = now - 40 * 86400;
+        {
+            let mut conn = storage.write_conn().unwrap();
+            let tx = conn.transaction().unwrap();
+            for k in 0..(40 * 288) {
+                let ts = start + k * 300;
+                tx.execute(
+                    "INSERT INTO price_snapshots (platform, market_id, timestamp, yes_price, no_price)
+                     VALUES ('kalshi', 'm', ?1, ?2, NULL)",
+                    params![ts, k as f64],
+                )
+                .unwrap();
+            }
+            tx.commit().unwrap();
+        }
 
-        let stored = storage.store_trades(&trades).unwrap();
-        assert_eq!(stored, 3);
+        storage.downsample_price_snapshots_at(now).unwrap();
 
-        let count = storage.get_trade_count(Platform::Kalshi, "market1").unwrap();
-        assert_eq!(count, 3);
+        let count_between = |from: i64, to: i64| -> i64 {
+            storage
+                .write_conn()
+                .unwrap()
+                .query_row(
+                    "SELECT COUNT(*) FROM price_snapshots WHERE timestamp >= ?1 AND timestamp < ?2",
+                    params![from, to],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        assert_eq!(count_between(now - 7 * 86400, now), 7 * 288);
+        assert_eq!(count_between(now - 30 * 86400, now - 7 * 86400), 23 * 24);
+        assert_eq!(count_between(start, now - 30 * 86400), 10);
+
+        // Running again is a no-op
+        assert_eq!(storage.downsample_price_snapshots_at(now).unwrap(), 0);
+
+        // A lookup 35.5 days back resolves to the previous day's closing snapshot
+        let target = now - 35 * 86400 + 12 * 3600;
+        let snapshot = storage
+            .get_price_at_time(Platform::Kalshi, "m", DateTime::from_timestamp(target, 0).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.timestamp, now - 35 * 86400 - 300);
+        assert_eq!(snapshot.yes_price, ((snapshot.timestamp - start) / 300) as f64);
     }
 
     #[test]
@@ -1204,4 +4994,60 @@ mod tests {
         assert!(storage.trade_exists("trade1").unwrap());
         assert!(!storage.trade_exists("trade_nonexistent").unwrap());
     }
+
+    #[test]
+    fn test_concurrent_reads_during_writes() {
+        let db_path = std::env::temp_dir().join(format!(
+            "trade_storage_pool_test_{}.db",
+            std::process::id()
+        ));
+        let storage = std::sync::Arc::new(
+            TradeStorage::with_config(&db_path, TradeStorageConfig::default()).unwrap(),
+        );
+
+        let writer = {
+            let storage = std::sync::Arc::clone(&storage);
+            std::thread::spawn(move || {
+                for batch in 0..20 {
+                    let trades: Vec<Trade> = (0..50)
+                        .map(|i| {
+                            create_test_trade(&format!("t{}-{}", batch, i), "market1", 0.5, -(i as i64))
+                        })
+                        .collect();
+                    storage.store_trades(&trades).unwrap();
+                }
+            })
+        };
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let storage = std::sync::Arc::clone(&storage);
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        storage
+                            .get_trades(
+                                Platform::Kalshi,
+                                "market1",
+                                Utc::now() - chrono::Duration::hours(1),
+                                Utc::now(),
+                            )
+                            .unwrap();
+                        storage.get_trade_count(Platform::Kalshi, "market1").unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(storage.get_trade_count(Platform::Kalshi, "market1").unwrap(), 1000);
+
+        drop(storage);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+    }
 }
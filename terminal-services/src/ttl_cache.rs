@@ -0,0 +1,206 @@
+//! TTL Cache
+//!
+//! Short-lived in-memory cache shared by the candle read cache and the
+//! market stats memo. Entries expire after a TTL and the least recently used
+//! entry is evicted past capacity; callers drop stale entries with
+//! [`TtlCache::retain`].
+//!
+//! Concurrent misses for the same key are single-flight: the first caller
+//! computes while the others wait on the slot and then read its result.
+
+use std::convert::Infallible;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+
+#[derive(Debug)]
+struct Cached<V> {
+    value: V,
+    stored_at: Instant,
+}
+
+/// A key's cached value, locked while it is being computed
+#[derive(Debug)]
+struct CacheSlot<V> {
+    value: Mutex<Option<Cached<V>>>,
+    last_access: Mutex<Option<Instant>>,
+}
+
+impl<V> Default for CacheSlot<V> {
+    fn default() -> Self {
+        Self {
+            value: Mutex::new(None),
+            last_access: Mutex::new(None),
+        }
+    }
+}
+
+/// In-memory TTL/LRU cache with single-flight loads
+#[derive(Debug)]
+pub(crate) struct TtlCache<K: Eq + Hash, V> {
+    ttl: Duration,
+    max_entries: usize,
+    slots: DashMap<K, Arc<CacheSlot<V>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    pub(crate) fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            slots: DashMap::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Return the cached value for `key`, loading it on a miss
+    ///
+    /// A failed load caches nothing.
+    pub(crate) fn get_or_load<E>(&self, key: K, load: impl FnOnce() -> Result<V, E>) -> Result<V, E> {
+        // Clone the slot out so the map shard isn't held while loading
+        let slot = self.slots.entry(key).or_default().clone();
+        *slot.last_access.lock() = Some(Instant::now());
+
+        let mut value = slot.value.lock();
+        if let Some(cached) = value.as_ref() {
+            if cached.stored_at.elapsed() < self.ttl {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached.value.clone());
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let loaded = load()?;
+        *value = Some(Cached {
+            value: loaded.clone(),
+            stored_at: Instant::now(),
+        });
+        drop(value);
+
+        self.evict_lru();
+        Ok(loaded)
+    }
+
+    /// Return the cached value for `key`, computing it on a miss
+    pub(crate) fn get_or_compute(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        self.get_or_load(key, || Ok::<_, Infallible>(compute()))
+            .unwrap_or_else(|never| match never {})
+    }
+
+    /// Keep only the entries whose key passes `keep`, returning how many were dropped
+    pub(crate) fn retain(&self, mut keep: impl FnMut(&K) -> bool) -> usize {
+        let before = self.slots.len();
+        self.slots.retain(|key, _| keep(key));
+        before.saturating_sub(self.slots.len())
+    }
+
+    pub(crate) fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn entries(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Evict least recently used entries past capacity
+    fn evict_lru(&self) {
+        while self.slots.len() > self.max_entries {
+            let oldest = self
+                .slots
+                .iter()
+                .min_by_key(|entry| *entry.value().last_access.lock())
+                .map(|entry| entry.key().clone());
+            match oldest {
+                Some(key) => {
+                    self.slots.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_hits_misses_and_failed_loads() {
+        let cache: TtlCache<&str, usize> = TtlCache::new(Duration::from_secs(10), 16);
+        let loads = AtomicUsize::new(0);
+        let load = || Ok::<_, ()>(loads.fetch_add(1, Ordering::SeqCst));
+
+        assert_eq!(cache.get_or_load("a", load), Ok(0));
+        assert_eq!(cache.get_or_load("a", load), Ok(0));
+        assert_eq!((cache.hits(), cache.misses(), cache.entries()), (1, 1, 1));
+
+        // A failed load is retried by the next caller
+        assert_eq!(cache.get_or_load("b", || Err::<usize, _>(())), Err(()));
+        assert_eq!(cache.get_or_load("b", load), Ok(1));
+    }
+
+    #[test]
+    fn test_ttl_expiry_and_lru_eviction() {
+        let cache: TtlCache<&str, u32> = TtlCache::new(Duration::from_millis(20), 2);
+
+        cache.get_or_compute("a", || 1);
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(cache.get_or_compute("a", || 2), 2);
+        assert_eq!(cache.misses(), 2);
+
+        cache.get_or_compute("b", || 3);
+        cache.get_or_compute("c", || 4);
+        assert_eq!(cache.entries(), 2);
+        assert!(!cache.slots.contains_key("a"));
+    }
+
+    #[test]
+    fn test_retain_counts_dropped_entries() {
+        let cache: TtlCache<(u8, &str), u32> = TtlCache::new(Duration::from_secs(10), 16);
+        cache.get_or_compute((1, "x"), || 1);
+        cache.get_or_compute((1, "y"), || 2);
+        cache.get_or_compute((2, "x"), || 3);
+
+        assert_eq!(cache.retain(|(group, _)| *group != 1), 2);
+        assert_eq!(cache.entries(), 1);
+        assert_eq!(cache.retain(|_| true), 0);
+    }
+
+    #[test]
+    fn test_concurrent_misses_load_once() {
+        let cache: Arc<TtlCache<&str, u32>> = Arc::new(TtlCache::new(Duration::from_secs(10), 16));
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                let loads = loads.clone();
+                std::thread::spawn(move || {
+                    cache.get_or_compute("m", || {
+                        loads.fetch_add(1, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(20));
+                        42
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 42);
+        }
+
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.hits(), 7);
+    }
+}
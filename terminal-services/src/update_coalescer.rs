@@ -0,0 +1,280 @@
+//! Update coalescing for the aggregator's client fan-out
+//!
+//! Hot markets emit dozens of book deltas a second, and broadcasting each
+//! one to every subscriber saturates slow connections. The coalescer buffers
+//! per (market, channel) and flushes at a bounded rate: only the latest book
+//! (the aggregator's cache already has every delta applied, so it is the
+//! merge of all of them) and the latest price tick survive a window, while
+//! trades are delivered in arrival order and never dropped.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use terminal_core::{OrderBook, Platform, Trade};
+
+use crate::websocket::WebSocketState;
+
+/// Default order book and price flush interval (4 Hz per market)
+const DEFAULT_COALESCE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Flush rates per channel; `None` broadcasts every update as it arrives
+#[derive(Debug, Clone)]
+pub struct CoalesceConfig {
+    pub orderbook_interval: Option<Duration>,
+    pub price_interval: Option<Duration>,
+    /// Trades are never dropped; an interval only batches their delivery
+    pub trade_interval: Option<Duration>,
+}
+
+impl Default for CoalesceConfig {
+    fn default() -> Self {
+        Self {
+            orderbook_interval: Some(DEFAULT_COALESCE_INTERVAL),
+            price_interval: Some(DEFAULT_COALESCE_INTERVAL),
+            trade_interval: None,
+        }
+    }
+}
+
+impl CoalesceConfig {
+    /// Every update goes straight out
+    pub fn passthrough() -> Self {
+        Self {
+            orderbook_interval: None,
+            price_interval: None,
+            trade_interval: None,
+        }
+    }
+}
+
+type MarketKey = (Platform, String);
+
+/// Updates waiting for the next flush
+#[derive(Debug, Default)]
+struct CoalesceBuffer {
+    books: HashMap<MarketKey, OrderBook>,
+    prices: HashMap<MarketKey, (Decimal, Decimal)>,
+    trades: Vec<Trade>,
+    /// Updates replaced by a newer one before they were flushed
+    merged: u64,
+}
+
+impl CoalesceBuffer {
+    fn push_book(&mut self, platform: Platform, market_id: String, orderbook: OrderBook) {
+        if self.books.insert((platform, market_id), orderbook).is_some() {
+            self.merged += 1;
+        }
+    }
+
+    fn push_price(&mut self, platform: Platform, market_id: String, yes_price: Decimal, no_price: Decimal) {
+        if self.prices.insert((platform, market_id), (yes_price, no_price)).is_some() {
+            self.merged += 1;
+        }
+    }
+
+    fn push_trade(&mut self, trade: Trade) {
+        self.trades.push(trade);
+    }
+
+    fn take_books(&mut self) -> Vec<(MarketKey, OrderBook)> {
+        self.books.drain().collect()
+    }
+
+    fn take_prices(&mut self) -> Vec<(MarketKey, (Decimal, Decimal))> {
+        self.prices.drain().collect()
+    }
+
+    fn take_trades(&mut self) -> Vec<Trade> {
+        std::mem::take(&mut self.trades)
+    }
+}
+
+/// Rate-limits book and price broadcasts to frontend clients
+pub struct UpdateCoalescer {
+    ws_state: Arc<WebSocketState>,
+    config: CoalesceConfig,
+    buffer: Mutex<CoalesceBuffer>,
+}
+
+impl UpdateCoalescer {
+    pub fn new(ws_state: Arc<WebSocketState>, config: CoalesceConfig) -> Self {
+        Self {
+            ws_state,
+            config,
+            buffer: Mutex::new(CoalesceBuffer::default()),
+        }
+    }
+
+    /// Spawn a flush task for each channel that has an interval
+    pub fn start(self: &Arc<Self>) {
+        let flushes: [(Option<Duration>, fn(&Self)); 3] = [
+            (self.config.orderbook_interval, Self::flush_books),
+            (self.config.price_interval, Self::flush_prices),
+            (self.config.trade_interval, Self::flush_trades),
+        ];
+        for (interval, flush) in flushes {
+            let Some(interval) = interval else {
+                continue;
+            };
+            let coalescer = Arc::clone(self);
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    ticker.tick().await;
+                    flush(&coalescer);
+                }
+            });
+        }
+    }
+
+    /// The client fan-out, for messages that bypass coalescing
+    pub fn ws_state(&self) -> &Arc<WebSocketState> {
+        &self.ws_state
+    }
+
+    /// Updates merged away so far (a gauge of how much fan-out was saved)
+    pub fn merged_count(&self) -> u64 {
+        self.buffer.lock().merged
+    }
+
+    pub fn orderbook(&self, platform: Platform, market_id: String, orderbook: OrderBook) {
+        if self.config.orderbook_interval.is_some() {
+            self.buffer.lock().push_book(platform, market_id, orderbook);
+        } else {
+            self.ws_state.broadcast_orderbook_update(platform, market_id, orderbook);
+        }
+    }
+
+    pub fn price(&self, platform: Platform, market_id: String, yes_price: Decimal, no_price: Decimal) {
+        if self.config.price_interval.is_some() {
+            self.buffer.lock().push_price(platform, market_id, yes_price, no_price);
+        } else {
+            self.ws_state.broadcast_price_update(platform, market_id, yes_price, no_price);
+        }
+    }
+
+    pub fn trade(&self, trade: Trade) {
+        if self.config.trade_interval.is_some() {
+            self.buffer.lock().push_trade(trade);
+        } else {
+            self.ws_state.broadcast_trade(trade);
+        }
+    }
+
+    fn flush_books(&self) {
+        let books = self.buffer.lock().take_books();
+        for ((platform, market_id), orderbook) in books {
+            self.ws_state.broadcast_orderbook_update(platform, market_id, orderbook);
+        }
+    }
+
+    fn flush_prices(&self) {
+        let prices = self.buffer.lock().take_prices();
+        for ((platform, market_id), (yes_price, no_price)) in prices {
+            self.ws_state.broadcast_price_update(platform, market_id, yes_price, no_price);
+        }
+    }
+
+    fn flush_trades(&self) {
+        let trades = self.buffer.lock().take_trades();
+        for trade in trades {
+            self.ws_state.broadcast_trade(trade);
+        }
+    }
+}
+
+impl std::fmt::Debug for UpdateCoalescer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpdateCoalescer")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use terminal_core::{OrderBookLevel, TradeOutcome};
+
+    fn level(price: &str, size: &str) -> OrderBookLevel {
+        OrderBookLevel::new(price.parse().unwrap(), size.parse().unwrap())
+    }
+
+    fn levels(levels: &[OrderBookLevel]) -> Vec<(Decimal, Decimal)> {
+        levels.iter().map(|l| (l.price, l.quantity)).collect()
+    }
+
+    fn trade(id: &str) -> Trade {
+        Trade {
+            id: id.to_string(),
+            market_id: "m".to_string(),
+            platform: Platform::Polymarket,
+            timestamp: Utc::now(),
+            price: "0.5".parse().unwrap(),
+            quantity: Decimal::ONE,
+            outcome: TradeOutcome::Yes,
+            side: None,
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[test]
+    fn test_rapid_book_updates_merge_into_one() {
+        let mut buffer = CoalesceBuffer::default();
+        let mut book = OrderBook::new("m".to_string(), Platform::Polymarket);
+        book.yes_bids = vec![level("0.40", "100")];
+
+        // Each delta is applied to the cached book, which is then queued
+        let deltas = [("0.41", "10"), ("0.40", "60"), ("0.42", "5"), ("0.41", "0")];
+        for (price, size) in deltas {
+            let price: Decimal = price.parse().unwrap();
+            let size: Decimal = size.parse().unwrap();
+            book.yes_bids.retain(|l| l.price != price);
+            if size > Decimal::ZERO {
+                book.yes_bids.push(OrderBookLevel::new(price, size));
+            }
+            book.yes_bids.sort_by(|a, b| b.price.cmp(&a.price));
+            buffer.push_book(Platform::Polymarket, "m".to_string(), book.clone());
+        }
+
+        let flushed = buffer.take_books();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].0, (Platform::Polymarket, "m".to_string()));
+        assert_eq!(
+            levels(&flushed[0].1.yes_bids),
+            levels(&[level("0.42", "5"), level("0.40", "60")])
+        );
+        assert_eq!(buffer.merged, 3);
+        assert!(buffer.take_books().is_empty());
+    }
+
+    #[test]
+    fn test_prices_keep_latest_and_trades_keep_all() {
+        let mut buffer = CoalesceBuffer::default();
+        for cents in [40, 41, 43] {
+            let yes = Decimal::new(cents, 2);
+            buffer.push_price(Platform::Kalshi, "k".to_string(), yes, Decimal::ONE - yes);
+        }
+        buffer.push_price(Platform::Polymarket, "m".to_string(), Decimal::new(7, 1), Decimal::new(3, 1));
+
+        let mut prices = buffer.take_prices();
+        prices.sort_by(|a, b| a.0 .1.cmp(&b.0 .1));
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0].1, (Decimal::new(43, 2), Decimal::new(57, 2)));
+
+        for id in ["t1", "t2", "t3"] {
+            buffer.push_trade(trade(id));
+        }
+        let ids: Vec<String> = buffer.take_trades().into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["t1", "t2", "t3"]);
+        assert_eq!(buffer.merged, 2);
+    }
+}
@@ -0,0 +1,209 @@
+//! Token authentication for frontend WebSocket connections
+//!
+//! With tokens configured, a connection has to present one, either as the
+//! `token` query parameter of the upgrade request or in an `auth` message
+//! sent as its first frame within the timeout; otherwise it is closed with
+//! `ErrorCode::Unauthorized`. The matching token's label is attached to the
+//! connection for logging. Order updates only go to connections that
+//! authenticated (or to everyone when no tokens are configured).
+
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use terminal_core::{ClientMessage, ErrorCode, ServerMessage};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+/// Default wait for the `auth` message
+pub const DEFAULT_AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Accepted tokens for the /ws endpoint
+#[derive(Clone)]
+pub struct WsAuth {
+    /// (label, token)
+    tokens: Vec<(String, String)>,
+    /// How long a connection may take to send its `auth` message
+    pub timeout: Duration,
+}
+
+impl WsAuth {
+    /// Tokens from a comma-separated list of `label=token` entries; a bare
+    /// token is labelled `token-N` by position. `None` if there are none.
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        let tokens: Vec<(String, String)> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .enumerate()
+            .map(|(i, entry)| match entry.split_once('=') {
+                Some((label, token)) => (label.trim().to_string(), token.trim().to_string()),
+                None => (format!("token-{}", i + 1), entry.to_string()),
+            })
+            .filter(|(_, token)| !token.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(Self {
+            tokens,
+            timeout: DEFAULT_AUTH_TIMEOUT,
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Label of the token `presented` matches
+    pub fn check(&self, presented: &str) -> Option<&str> {
+        // Compare against every token so timing doesn't tell which matched
+        let mut found = None;
+        for (label, token) in &self.tokens {
+            if same_token(token.as_bytes(), presented.as_bytes()) && found.is_none() {
+                found = Some(label.as_str());
+            }
+        }
+        found
+    }
+}
+
+impl std::fmt::Debug for WsAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let labels: Vec<&str> = self.tokens.iter().map(|(label, _)| label.as_str()).collect();
+        f.debug_struct("WsAuth")
+            .field("labels", &labels)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// Why a connection was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// The first message wasn't `auth`, or the connection closed first
+    Missing,
+    /// No `auth` message within the timeout
+    TimedOut,
+    /// The token matches none configured
+    InvalidToken,
+}
+
+impl AuthError {
+    /// The error sent to the client before it is closed
+    pub fn to_message(&self) -> ServerMessage {
+        let message = match self {
+            Self::Missing => "Authentication required: send an auth message first",
+            Self::TimedOut => "Authentication timed out",
+            Self::InvalidToken => "Invalid token",
+        };
+        ServerMessage::Error {
+            code: ErrorCode::Unauthorized,
+            message: message.to_string(),
+            limit: None,
+            count: None,
+        }
+    }
+}
+
+/// Authenticate a new connection from its query token or its first message,
+/// returning the token's label
+pub(crate) async fn authenticate<R>(auth: &WsAuth, receiver: &mut R, query_token: Option<&str>) -> Result<String, AuthError>
+where
+    R: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    if let Some(token) = query_token {
+        return auth.check(token).map(str::to_string).ok_or(AuthError::InvalidToken);
+    }
+
+    let first = tokio::time::timeout(auth.timeout, async {
+        loop {
+            match receiver.next().await {
+                Some(Ok(Message::Text(text))) => return Some(text),
+                // Control frames can come before the auth message
+                Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                _ => return None,
+            }
+        }
+    })
+    .await
+    .map_err(|_| AuthError::TimedOut)?;
+
+    match first.map(|text| serde_json::from_str::<ClientMessage>(&text)) {
+        Some(Ok(ClientMessage::Auth { token })) => auth.check(&token).map(str::to_string).ok_or(AuthError::InvalidToken),
+        _ => Err(AuthError::Missing),
+    }
+}
+
+/// Constant-time for tokens of equal length
+fn same_token(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+
+    fn auth() -> WsAuth {
+        WsAuth::from_spec("desk=s3cret, backup-token")
+            .unwrap()
+            .with_timeout(Duration::from_millis(20))
+    }
+
+    fn frames(texts: &[&str]) -> impl Stream<Item = Result<Message, WsError>> + Unpin {
+        stream::iter(texts.iter().map(|text| Ok(Message::Text(text.to_string().into()))).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_tokens_parsed_with_labels() {
+        let auth = auth();
+        assert_eq!(auth.check("s3cret"), Some("desk"));
+        assert_eq!(auth.check("backup-token"), Some("token-2"));
+        assert_eq!(auth.check("s3cre"), None);
+        assert!(WsAuth::from_spec(" , ").is_none());
+        assert!(!format!("{:?}", auth).contains("s3cret"));
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_connections_rejected() {
+        let auth = auth();
+
+        // Subscribing before authenticating
+        let mut receiver = frames(&[r#"{"type":"ping","timestamp":1}"#]);
+        assert_eq!(authenticate(&auth, &mut receiver, None).await, Err(AuthError::Missing));
+
+        // Closing without a word
+        let mut receiver = frames(&[]);
+        assert_eq!(authenticate(&auth, &mut receiver, None).await, Err(AuthError::Missing));
+
+        // Saying nothing at all
+        let mut receiver = stream::pending::<Result<Message, WsError>>();
+        assert_eq!(authenticate(&auth, &mut receiver, None).await, Err(AuthError::TimedOut));
+    }
+
+    #[tokio::test]
+    async fn test_bad_token_rejected() {
+        let auth = auth();
+        let mut receiver = frames(&[r#"{"type":"auth","token":"guess"}"#]);
+        assert_eq!(authenticate(&auth, &mut receiver, None).await, Err(AuthError::InvalidToken));
+
+        let mut receiver = frames(&[]);
+        assert_eq!(authenticate(&auth, &mut receiver, Some("guess")).await, Err(AuthError::InvalidToken));
+        assert!(matches!(
+            AuthError::InvalidToken.to_message(),
+            ServerMessage::Error { code: ErrorCode::Unauthorized, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_token_accepted_from_query_or_first_message() {
+        let auth = auth();
+        let mut receiver = frames(&[]);
+        assert_eq!(authenticate(&auth, &mut receiver, Some("s3cret")).await, Ok("desk".to_string()));
+
+        let mut receiver = frames(&[r#"{"type":"auth","token":"backup-token"}"#, r#"{"type":"ping","timestamp":1}"#]);
+        assert_eq!(authenticate(&auth, &mut receiver, None).await, Ok("token-2".to_string()));
+        // Later frames are left for the connection
+        assert!(receiver.next().await.is_some());
+    }
+}
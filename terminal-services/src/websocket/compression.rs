@@ -0,0 +1,179 @@
+//! Compressed payloads for large client messages
+//!
+//! Deep order book snapshots run to tens of KB and go to many clients. A
+//! client that subscribes with `compress: true` gets that subscription's
+//! snapshot-class messages (the `snapshot` sent on subscribe and full order
+//! book snapshots) as a `compressed` message: the original JSON, zstd
+//! compressed and base64 encoded. Everything else stays plain JSON, as does
+//! any message that wouldn't get smaller.
+//!
+//! permessage-deflate would compress every frame without client changes,
+//! but neither axum's upgrade nor tungstenite negotiates it yet.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use base64::Engine;
+use serde::Serialize;
+use terminal_core::{PayloadEncoding, ServerMessage};
+use tracing::warn;
+
+/// Default zstd level: fast, most of the gain on repetitive book JSON
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Compressed-payload counters, as reported in the health payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompressionStats {
+    /// Messages sent compressed
+    pub compressed_messages: u64,
+    /// Their JSON size
+    pub raw_bytes: u64,
+    /// Their size as sent (base64 of the zstd frame)
+    pub compressed_bytes: u64,
+    pub bytes_saved: u64,
+}
+
+/// Compresses snapshot-class messages and counts what that saves
+#[derive(Debug)]
+pub struct PayloadCompressor {
+    level: i32,
+    compressed_messages: AtomicU64,
+    raw_bytes: AtomicU64,
+    compressed_bytes: AtomicU64,
+}
+
+impl Default for PayloadCompressor {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPRESSION_LEVEL)
+    }
+}
+
+impl PayloadCompressor {
+    pub fn new(level: i32) -> Self {
+        Self {
+            level,
+            compressed_messages: AtomicU64::new(0),
+            raw_bytes: AtomicU64::new(0),
+            compressed_bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// `message` as a `compressed` message, or `None` if it wouldn't be
+    /// smaller
+    pub fn compress(&self, message: &ServerMessage) -> Option<ServerMessage> {
+        let json = match serde_json::to_vec(message) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize message for compression: {}", e);
+                return None;
+            }
+        };
+        let data = match zstd::encode_all(json.as_slice(), self.level) {
+            Ok(frame) => base64::engine::general_purpose::STANDARD.encode(frame),
+            Err(e) => {
+                warn!("Failed to compress message: {}", e);
+                return None;
+            }
+        };
+        if data.len() >= json.len() {
+            return None;
+        }
+
+        self.compressed_messages.fetch_add(1, Ordering::Relaxed);
+        self.raw_bytes.fetch_add(json.len() as u64, Ordering::Relaxed);
+        self.compressed_bytes.fetch_add(data.len() as u64, Ordering::Relaxed);
+        Some(ServerMessage::Compressed {
+            encoding: PayloadEncoding::ZstdBase64,
+            data,
+        })
+    }
+
+    pub fn stats(&self) -> CompressionStats {
+        let raw_bytes = self.raw_bytes.load(Ordering::Relaxed);
+        let compressed_bytes = self.compressed_bytes.load(Ordering::Relaxed);
+        CompressionStats {
+            compressed_messages: self.compressed_messages.load(Ordering::Relaxed),
+            raw_bytes,
+            compressed_bytes,
+            bytes_saved: raw_bytes.saturating_sub(compressed_bytes),
+        }
+    }
+}
+
+/// The message inside a `compressed` message
+pub fn decompress(encoding: PayloadEncoding, data: &str) -> Result<ServerMessage, String> {
+    match encoding {
+        PayloadEncoding::ZstdBase64 => {
+            let frame = base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .map_err(|e| format!("Invalid base64: {}", e))?;
+            let json = zstd::decode_all(frame.as_slice()).map_err(|e| format!("Invalid zstd frame: {}", e))?;
+            serde_json::from_slice(&json).map_err(|e| format!("Invalid message: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use terminal_core::{OrderBook, OrderBookLevel, Platform};
+
+    fn deep_book() -> OrderBook {
+        let levels = |from: i64| {
+            (0..60)
+                .map(|i| OrderBookLevel {
+                    price: Decimal::new(from + i, 3),
+                    quantity: Decimal::from(100 + i),
+                    order_count: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        OrderBook {
+            market_id: "KXSNOW-25".to_string(),
+            platform: Platform::Kalshi,
+            yes_bids: levels(100),
+            yes_asks: levels(500),
+            no_bids: levels(200),
+            no_asks: levels(600),
+            timestamp: Utc::now(),
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_compressed_snapshot_round_trips() {
+        let snapshot = ServerMessage::Snapshot {
+            platform: Platform::Kalshi,
+            market_id: "KXSNOW-25".to_string(),
+            market: None,
+            orderbook: Some(deep_book()),
+            trades: vec![],
+        };
+
+        let compressor = PayloadCompressor::default();
+        let Some(ServerMessage::Compressed { encoding, data }) = compressor.compress(&snapshot) else {
+            panic!("a deep book should compress");
+        };
+        let restored = decompress(encoding, &data).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&snapshot).unwrap());
+
+        let raw = serde_json::to_vec(&snapshot).unwrap().len() as u64;
+        let stats = compressor.stats();
+        assert_eq!(stats.compressed_messages, 1);
+        assert_eq!(stats.raw_bytes, raw);
+        assert_eq!(stats.compressed_bytes, data.len() as u64);
+        assert_eq!(stats.bytes_saved, raw - data.len() as u64);
+    }
+
+    #[test]
+    fn test_small_messages_left_alone() {
+        let pong = ServerMessage::Pong {
+            client_timestamp: 1,
+            server_timestamp: 2,
+        };
+        let compressor = PayloadCompressor::default();
+        assert!(compressor.compress(&pong).is_none());
+        assert_eq!(compressor.stats(), CompressionStats::default());
+    }
+}
@@ -4,30 +4,76 @@
 //! and subscription management.
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
+use dashmap::DashMap;
 use terminal_core::{
-    ClientMessage, ErrorCode, Platform, ServerMessage, SubscriptionKey,
+    ClientMessage, ErrorCode, PingAction, PingScheduler, Platform, ServerMessage, SubscriptionChannel, SubscriptionKey,
 };
 use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
-use super::subscription::{BroadcastMessage, ClientId, SubscriptionManager};
-use crate::MarketService;
+use super::auth::{authenticate, WsAuth};
+use super::compression::{CompressionStats, PayloadCompressor};
+use super::outbound::{flush, OutboundBatching, OutboundQueue};
+use super::session::{DetachedSession, SessionConfig, SessionStats, SessionStore, SessionSubscription};
+use super::subscription::{BroadcastMessage, ClientId, SubscriptionLimits, SubscriptionManager};
+use crate::canary::is_canary_market;
+use crate::ticker_tape::{TickerTape, TickerTapeConfig, TickerTapeStats};
+use crate::{MarketAlert, MarketCacheEvent, MarketService, RefreshRequest, ReplayBuffer, SnapshotSource};
 
 /// Subscription event for notifying the aggregator
 #[derive(Debug, Clone)]
 pub enum SubscriptionEvent {
-    /// A client subscribed to a market
+    /// A market channel got its first subscriber
     Subscribe {
         platform: Platform,
         market_id: String,
     },
-    /// A client unsubscribed from a market (when no clients remain)
+    /// A market channel lost its last subscriber (unsubscribe or disconnect)
     Unsubscribe {
         platform: Platform,
         market_id: String,
     },
+    /// A multi-outcome event got its first subscriber
+    SubscribeEvent {
+        platform: Platform,
+        event_id: String,
+    },
+    /// A multi-outcome event lost its last subscriber
+    UnsubscribeEvent {
+        platform: Platform,
+        event_id: String,
+    },
+}
+
+impl SubscriptionEvent {
+    /// The aggregator event for the first subscriber to `key` arriving, or
+    /// its last one leaving; `None` for channels that don't stream from an
+    /// exchange
+    fn for_key(key: &SubscriptionKey, subscribed: bool) -> Option<Self> {
+        let (platform, id) = (key.platform, key.market_id.clone());
+        match (key.channel, subscribed) {
+            (
+                SubscriptionChannel::MarketListings | SubscriptionChannel::Alerts | SubscriptionChannel::GlobalTrades,
+                _,
+            ) => None,
+            (SubscriptionChannel::Event, true) => Some(Self::SubscribeEvent { platform, event_id: id }),
+            (SubscriptionChannel::Event, false) => Some(Self::UnsubscribeEvent { platform, event_id: id }),
+            (_, true) => Some(Self::Subscribe { platform, market_id: id }),
+            (_, false) => Some(Self::Unsubscribe { platform, market_id: id }),
+        }
+    }
+}
+
+/// Where an outcome market's updates are re-published for event subscribers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRoute {
+    pub event_id: String,
+    /// Outcome name the updates are tagged with
+    pub outcome: String,
 }
 
 /// Trade subscription event for notifying the trade collector
@@ -46,6 +92,47 @@ pub enum TradeSubscriptionEvent {
     },
 }
 
+/// The one key every `global_trades` subscriber shares
+fn global_trades_key() -> SubscriptionKey {
+    SubscriptionKey::from(&terminal_core::SubscriptionType::GlobalTrades { min_notional: None })
+}
+
+/// Whether subscribing to `channel` gets a market snapshot
+fn has_snapshot(channel: SubscriptionChannel) -> bool {
+    matches!(
+        channel,
+        SubscriptionChannel::Price | SubscriptionChannel::OrderBook | SubscriptionChannel::Trades | SubscriptionChannel::Prices
+    )
+}
+
+/// How often detached sessions are checked for expiry
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often client connections are pinged
+pub const DEFAULT_CLIENT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a ping may go unanswered before the client is dropped
+pub const DEFAULT_CLIENT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Server-initiated ping schedule for client connections
+///
+/// A client that misses a pong for `idle_timeout` (a sleeping tab, a
+/// vanished network) is closed and its subscriptions released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientHeartbeat {
+    pub ping_interval: Duration,
+    pub idle_timeout: Duration,
+}
+
+impl Default for ClientHeartbeat {
+    fn default() -> Self {
+        Self {
+            ping_interval: DEFAULT_CLIENT_PING_INTERVAL,
+            idle_timeout: DEFAULT_CLIENT_IDLE_TIMEOUT,
+        }
+    }
+}
+
 /// Shared state for WebSocket handlers
 #[derive(Clone)]
 pub struct WebSocketState {
@@ -57,6 +144,27 @@ pub struct WebSocketState {
     subscription_event_tx: Option<mpsc::Sender<SubscriptionEvent>>,
     /// Channel to notify trade collector of trade subscriptions
     trade_subscription_tx: Option<mpsc::Sender<TradeSubscriptionEvent>>,
+    /// Market cache refresh queue, fed on first subscription to a market
+    refresh_request_tx: Option<mpsc::Sender<RefreshRequest>>,
+    /// Outcome market -> subscribed events it belongs to
+    event_routes: Arc<DashMap<(Platform, String), Vec<EventRoute>>>,
+    /// Recent history sent to new subscribers, registered by the aggregator
+    replay_buffer: Arc<parking_lot::RwLock<Option<Arc<ReplayBuffer>>>>,
+    /// Current market state sent to new subscribers, registered once the
+    /// aggregator is up
+    snapshot_source: Arc<parking_lot::RwLock<Option<Arc<dyn SnapshotSource>>>>,
+    /// Ping schedule for client connections
+    heartbeat: ClientHeartbeat,
+    /// Flush interval and slow-consumer bound for client queues
+    batching: OutboundBatching,
+    /// Tokens connections must present; `None` accepts anyone
+    auth: Option<Arc<WsAuth>>,
+    /// Compresses snapshot-class messages for clients that ask
+    compressor: Arc<PayloadCompressor>,
+    /// Every broadcast trade, waiting for the next `global_trades` window
+    ticker_tape: Arc<TickerTape>,
+    /// Client sessions kept across reconnects
+    sessions: Arc<SessionStore>,
 }
 
 impl WebSocketState {
@@ -67,6 +175,16 @@ impl WebSocketState {
             market_service,
             subscription_event_tx: None,
             trade_subscription_tx: None,
+            refresh_request_tx: None,
+            event_routes: Arc::new(DashMap::new()),
+            replay_buffer: Arc::new(parking_lot::RwLock::new(None)),
+            snapshot_source: Arc::new(parking_lot::RwLock::new(None)),
+            heartbeat: ClientHeartbeat::default(),
+            batching: OutboundBatching::default(),
+            auth: None,
+            compressor: Arc::new(PayloadCompressor::default()),
+            ticker_tape: Arc::new(TickerTape::default()),
+            sessions: Arc::new(SessionStore::default()),
         }
     }
 
@@ -80,6 +198,69 @@ impl WebSocketState {
         self.trade_subscription_tx = Some(tx);
     }
 
+    /// Set the market cache refresh queue (see `MarketCache::refresh_sender`)
+    pub fn set_refresh_request_sender(&mut self, tx: mpsc::Sender<RefreshRequest>) {
+        self.refresh_request_tx = Some(tx);
+    }
+
+    /// Cap client subscriptions per connection and in total
+    ///
+    /// Replaces the subscription manager, so call it before any client
+    /// connects or anything holds `subscriptions`.
+    pub fn set_subscription_limits(&mut self, limits: SubscriptionLimits) {
+        self.subscriptions = Arc::new(SubscriptionManager::with_limits(limits));
+    }
+
+    /// Set the client ping interval and idle timeout
+    pub fn set_heartbeat(&mut self, heartbeat: ClientHeartbeat) {
+        self.heartbeat = heartbeat;
+    }
+
+    /// Set how client messages are batched and when a client is too slow
+    pub fn set_batching(&mut self, batching: OutboundBatching) {
+        self.batching = batching;
+    }
+
+    /// Require connections to present one of `auth`'s tokens
+    pub fn set_auth(&mut self, auth: WsAuth) {
+        self.auth = Some(Arc::new(auth));
+    }
+
+    /// Set the global trade tape's rate limit and window
+    pub fn set_ticker_tape(&mut self, config: TickerTapeConfig) {
+        self.ticker_tape = Arc::new(TickerTape::new(config));
+    }
+
+    /// Global trade tape counters for the health payload
+    pub fn ticker_tape_stats(&self) -> TickerTapeStats {
+        self.ticker_tape.stats()
+    }
+
+    /// Set how long and how much of a dropped connection's session is kept
+    pub fn set_sessions(&mut self, config: SessionConfig) {
+        self.sessions = Arc::new(SessionStore::new(config));
+    }
+
+    /// Resumable session counters for the health payload
+    pub fn session_stats(&self) -> SessionStats {
+        self.sessions.stats()
+    }
+
+    /// Compressed-payload counters for the health payload
+    pub fn compression_stats(&self) -> CompressionStats {
+        self.compressor.stats()
+    }
+
+    /// Replay `buffer` to each new subscriber before its live updates
+    pub fn set_replay_buffer(&self, buffer: Arc<ReplayBuffer>) {
+        *self.replay_buffer.write() = Some(buffer);
+    }
+
+    /// Send a snapshot from `source` to each new market subscriber
+    pub fn set_snapshot_source(&self, source: Arc<dyn SnapshotSource>) {
+        *self.snapshot_source.write() = Some(source);
+    }
+
     /// Get a subscription event receiver
     pub fn create_subscription_event_channel() -> (mpsc::Sender<SubscriptionEvent>, mpsc::Receiver<SubscriptionEvent>) {
         mpsc::channel(256)
@@ -94,83 +275,169 @@ impl WebSocketState {
     ///
     /// This is called when a WebSocket upgrade is successful.
     /// It spawns tasks to handle incoming messages and broadcast outgoing messages.
-    pub async fn handle_connection<S>(&self, socket: S)
+    /// `query_token` and `session_id` are the `token` and `session_id` query
+    /// parameters of the upgrade request, if it had them.
+    pub async fn handle_connection<S>(&self, socket: S, query_token: Option<String>, session_id: Option<String>)
     where
         S: futures_util::Stream<Item = Result<tokio_tungstenite::tungstenite::Message, tokio_tungstenite::tungstenite::Error>>
             + futures_util::Sink<tokio_tungstenite::tungstenite::Message, Error = tokio_tungstenite::tungstenite::Error>
             + Send
             + 'static,
     {
-        let client_id = self.subscriptions.new_client_id();
-        info!("New WebSocket connection: {}", client_id);
-
         let (mut ws_sender, mut ws_receiver) = socket.split();
 
-        // Subscribe to broadcast channel
-        let mut broadcast_rx = self.subscriptions.subscribe_broadcast();
+        // With tokens configured, nothing happens until the client presents one
+        let identity = match &self.auth {
+            Some(auth) => match authenticate(auth, &mut ws_receiver, query_token.as_deref()).await {
+                Ok(label) => {
+                    if let Ok(json) = serde_json::to_string(&ServerMessage::Authenticated { label: label.clone() }) {
+                        let _ = ws_sender.send(tokio_tungstenite::tungstenite::Message::Text(json.into())).await;
+                    }
+                    Some(label)
+                }
+                Err(e) => {
+                    warn!("Refusing WebSocket connection: {:?}", e);
+                    if let Ok(json) = serde_json::to_string(&e.to_message()) {
+                        let _ = ws_sender.send(tokio_tungstenite::tungstenite::Message::Text(json.into())).await;
+                    }
+                    let _ = ws_sender.send(tokio_tungstenite::tungstenite::Message::Close(None)).await;
+                    return;
+                }
+            },
+            None => None,
+        };
+        // Private updates go to authenticated connections, or anyone when auth is off
+        let trusted = self.auth.is_none() || identity.is_some();
+
+        let client_id = self.subscriptions.connect_client_as(identity.clone());
+        match &identity {
+            Some(label) => info!("New WebSocket connection: {} ({})", client_id, label),
+            None => info!("New WebSocket connection: {}", client_id),
+        }
 
         // Clone state for the message handler
         let subscriptions = Arc::clone(&self.subscriptions);
-        let subscriptions_for_broadcast = Arc::clone(&self.subscriptions);
 
-        // Spawn task to forward broadcast messages to this client
+        // Task: Forward broadcast messages to client (filtered by subscription)
         let (outgoing_tx, mut outgoing_rx) = tokio::sync::mpsc::channel::<ServerMessage>(100);
-        let outgoing_tx_clone = outgoing_tx.clone();
+        self.forward_to_client(client_id, trusted, outgoing_tx.clone());
 
-        // Task: Forward broadcast messages to client (filtered by subscription)
-        tokio::spawn(async move {
+        // Outgoing messages wait in a per-client queue between flushes
+        let batching = self.batching;
+        let queue = Arc::new(parking_lot::Mutex::new(OutboundQueue::new(batching.max_queued)));
+        let queued = Arc::new(tokio::sync::Notify::new());
+
+        // Task: Queue outgoing messages as they come, however slow the socket is
+        let queue_task = {
+            let queue = Arc::clone(&queue);
+            let queued = Arc::clone(&queued);
+            tokio::spawn(async move {
+                while let Some(message) = outgoing_rx.recv().await {
+                    let pushed = queue.lock().push(message);
+                    queued.notify_one();
+                    if pushed.is_err() {
+                        break;
+                    }
+                }
+            })
+        };
+
+        // Pings go out with the outgoing messages, pongs come back with the incoming ones
+        let heartbeat = Arc::new(parking_lot::Mutex::new(PingScheduler::new(
+            self.heartbeat.ping_interval,
+            self.heartbeat.idle_timeout,
+        )));
+        let pinger = Arc::clone(&heartbeat);
+        let idle_timeout = self.heartbeat.idle_timeout;
+        let compressor = Arc::clone(&self.compressor);
+        let subscriptions_for_compression = Arc::clone(&self.subscriptions);
+        let unsent = Arc::clone(&queue);
+
+        // Task: Flush queued messages and send pings to WebSocket; true if
+        // the client stopped answering pings or fell too far behind
+        let mut send_task = tokio::spawn(async move {
+            // Snapshot-class messages go out compressed where the client asked
+            let prepare = |message: ServerMessage| {
+                if subscriptions_for_compression.wants_compressed(client_id, &message) {
+                    compressor.compress(&message).unwrap_or(message)
+                } else {
+                    message
+                }
+            };
+            let mut flush_timer = batching.is_batched().then(|| {
+                let mut timer = tokio::time::interval(batching.flush_interval);
+                timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                timer
+            });
             loop {
-                match broadcast_rx.recv().await {
-                    Ok(BroadcastMessage { key, message }) => {
-                        // Global messages (like research updates) go to all clients
-                        let is_global = key.market_id == "__global__";
-                        // Check if this client is subscribed to this key
-                        if is_global || subscriptions_for_broadcast.is_subscribed(client_id, &key) {
-                            if outgoing_tx_clone.send(message).await.is_err() {
-                                break;
+                let next_ping = pinger.lock().next_deadline(Instant::now());
+                tokio::select! {
+                    _ = async {
+                        match flush_timer.as_mut() {
+                            Some(timer) => {
+                                timer.tick().await;
                             }
+                            None => queued.notified().await,
+                        }
+                    } => {
+                        let overflowed = queue.lock().overflowed();
+                        if let Some(slow) = overflowed {
+                            warn!("Client {} has {} messages queued, closing slow consumer", client_id, slow.queued);
+                            if let Ok(json) = serde_json::to_string(&slow.to_message()) {
+                                let _ = ws_sender.send(tokio_tungstenite::tungstenite::Message::Text(json.into())).await;
+                            }
+                            let _ = ws_sender.send(tokio_tungstenite::tungstenite::Message::Close(None)).await;
+                            return true;
+                        }
+                        if flush(&mut ws_sender, &queue, batching.is_batched(), &prepare).await.is_err() {
+                            return false;
                         }
                     }
-                    Err(broadcast::error::RecvError::Lagged(n)) => {
-                        warn!("Client {} lagged {} messages", client_id, n);
-                    }
-                    Err(broadcast::error::RecvError::Closed) => {
-                        break;
+                    _ = tokio::time::sleep_until(next_ping.into()) => {
+                        let action = pinger.lock().poll(Instant::now());
+                        match action {
+                            PingAction::Send => {
+                                if ws_sender
+                                    .send(tokio_tungstenite::tungstenite::Message::Ping(Vec::new().into()))
+                                    .await
+                                    .is_err()
+                                {
+                                    return false;
+                                }
+                            }
+                            PingAction::TimedOut => {
+                                warn!("Client {} sent no pong within {}s, closing", client_id, idle_timeout.as_secs());
+                                let _ = ws_sender.send(tokio_tungstenite::tungstenite::Message::Close(None)).await;
+                                return true;
+                            }
+                            PingAction::Wait => {}
+                        }
                     }
                 }
             }
         });
 
-        // Task: Send outgoing messages to WebSocket
-        let send_task = tokio::spawn(async move {
-            while let Some(message) = outgoing_rx.recv().await {
-                let json = match serde_json::to_string(&message) {
-                    Ok(j) => j,
-                    Err(e) => {
-                        error!("Failed to serialize message: {}", e);
-                        continue;
-                    }
-                };
-
-                if ws_sender
-                    .send(tokio_tungstenite::tungstenite::Message::Text(json.into()))
-                    .await
-                    .is_err()
-                {
-                    break;
-                }
-            }
-        });
+        // Pick up where an earlier connection with this session left off
+        let session_id = session_id.filter(|id| self.sessions.accepts(id));
+        if let Some(session_id) = &session_id {
+            self.resume_session(session_id, client_id, &outgoing_tx).await;
+        }
 
         // Task: Receive and process incoming messages
         let recv_task = {
             let outgoing_tx = outgoing_tx.clone();
             let subscription_event_tx = self.subscription_event_tx.clone();
             let trade_subscription_tx = self.trade_subscription_tx.clone();
+            let refresh_request_tx = self.refresh_request_tx.clone();
+            let replay_buffer = self.replay_buffer.read().clone();
+            let snapshot_source = self.snapshot_source.read().clone();
             async move {
                 while let Some(result) = ws_receiver.next().await {
                     match result {
                         Ok(msg) => {
+                            if let tokio_tungstenite::tungstenite::Message::Pong(_) = msg {
+                                heartbeat.lock().on_pong(Instant::now());
+                            }
                             if let Err(e) = Self::handle_message(
                                 client_id,
                                 msg,
@@ -178,6 +445,9 @@ impl WebSocketState {
                                 &outgoing_tx,
                                 &subscription_event_tx,
                                 &trade_subscription_tx,
+                                &refresh_request_tx,
+                                &replay_buffer,
+                                &snapshot_source,
                             )
                             .await
                             {
@@ -193,25 +463,127 @@ impl WebSocketState {
             }
         };
 
-        // Wait for either task to complete (connection closed)
-        tokio::select! {
-            _ = send_task => {}
-            _ = recv_task => {}
-        }
+        // Wait for either task to complete (connection closed, dead or slow)
+        let timed_out = tokio::select! {
+            result = &mut send_task => result.unwrap_or(false),
+            _ = recv_task => {
+                send_task.abort();
+                false
+            }
+        };
+        // Drops the outgoing receiver, which ends the broadcast forwarder
+        queue_task.abort();
 
-        // Clean up subscriptions
-        self.subscriptions.remove_client(client_id);
+        match &session_id {
+            Some(session_id) => {
+                let undelivered = unsent.lock().take();
+                self.detach_session(session_id, client_id, timed_out, undelivered).await;
+            }
+            None => self.disconnect_client(client_id, timed_out).await,
+        }
         info!("WebSocket connection closed: {}", client_id);
     }
 
+    /// Clean up after a connection, releasing the markets nobody else
+    /// watches; `timed_out` counts it as a dead or slow client
+    pub async fn disconnect_client(&self, client_id: ClientId, timed_out: bool) {
+        if timed_out {
+            self.subscriptions.record_cleaned_up();
+        }
+        let emptied = self.subscriptions.remove_client(client_id);
+        self.release_markets(&emptied).await;
+    }
+
+    /// Tell the aggregator `keys` lost their last subscriber
+    async fn release_markets(&self, keys: &[SubscriptionKey]) {
+        if let Some(ref tx) = self.subscription_event_tx {
+            for event in keys.iter().filter_map(|key| SubscriptionEvent::for_key(key, false)) {
+                let _ = tx.send(event).await;
+            }
+        }
+    }
+
+    /// Give `session_id` to a new connection, replaying what it missed and
+    /// restoring its subscriptions if it resumes one
+    async fn resume_session(&self, session_id: &str, client_id: ClientId, outgoing_tx: &mpsc::Sender<ServerMessage>) {
+        let identity = self.subscriptions.client_identity(client_id);
+        let resumed = self.sessions.attach(session_id, client_id, identity.as_deref());
+        let _ = outgoing_tx
+            .send(ServerMessage::Session {
+                session_id: session_id.to_string(),
+                resumed: resumed.is_some(),
+                replayed: resumed.as_ref().map_or(0, |r| r.missed.len()),
+                dropped: resumed.as_ref().map_or(0, |r| r.dropped),
+            })
+            .await;
+        let Some(resumed) = resumed else {
+            return;
+        };
+        info!(
+            "{} resumed session {}: replaying {} missed messages, restoring {} subscriptions",
+            client_id,
+            session_id,
+            resumed.missed.len(),
+            resumed.subscriptions.len()
+        );
+
+        for message in resumed.missed {
+            let _ = outgoing_tx.send(message).await;
+        }
+        // Restored like fresh subscribes, so each gets a current snapshot
+        let replay_buffer = self.replay_buffer.read().clone();
+        let snapshot_source = self.snapshot_source.read().clone();
+        for SessionSubscription { subscription, compress } in resumed.subscriptions {
+            let Ok(text) = serde_json::to_string(&ClientMessage::Subscribe { subscription, compress }) else {
+                continue;
+            };
+            if let Err(e) = Self::handle_message(
+                client_id,
+                tokio_tungstenite::tungstenite::Message::Text(text.into()),
+                &self.subscriptions,
+                outgoing_tx,
+                &self.subscription_event_tx,
+                &self.trade_subscription_tx,
+                &self.refresh_request_tx,
+                &replay_buffer,
+                &snapshot_source,
+            )
+            .await
+            {
+                warn!("Failed to restore a subscription for {}: {}", client_id, e);
+            }
+        }
+        // The restored subscriptions hold the session's markets now
+        self.release_markets(&resumed.held).await;
+    }
+
+    /// Close a connection that has a session, keeping its subscriptions,
+    /// unsent messages and markets for a reconnect
+    async fn detach_session(&self, session_id: &str, client_id: ClientId, timed_out: bool, undelivered: Vec<ServerMessage>) {
+        if timed_out {
+            self.subscriptions.record_cleaned_up();
+        }
+        let subscriptions = self.subscriptions.subscription_set(client_id);
+        let identity = self.subscriptions.client_identity(client_id);
+        let trusted = self.auth.is_none() || identity.is_some();
+        let held = self.subscriptions.remove_client(client_id);
+        let session = DetachedSession::new(identity, trusted, subscriptions, held);
+        let released = self.sessions.detach(session_id, client_id, session, undelivered);
+        self.release_markets(&released).await;
+    }
+
     /// Handle an incoming WebSocket message
-    async fn handle_message(
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn handle_message(
         client_id: ClientId,
         msg: tokio_tungstenite::tungstenite::Message,
         subscriptions: &Arc<SubscriptionManager>,
         outgoing_tx: &tokio::sync::mpsc::Sender<ServerMessage>,
         subscription_event_tx: &Option<mpsc::Sender<SubscriptionEvent>>,
         trade_subscription_tx: &Option<mpsc::Sender<TradeSubscriptionEvent>>,
+        refresh_request_tx: &Option<mpsc::Sender<RefreshRequest>>,
+        replay_buffer: &Option<Arc<ReplayBuffer>>,
+        snapshot_source: &Option<Arc<dyn SnapshotSource>>,
     ) -> Result<(), String> {
         use terminal_core::SubscriptionType;
         use tokio_tungstenite::tungstenite::Message;
@@ -222,26 +594,68 @@ impl WebSocketState {
                     .map_err(|e| format!("Invalid JSON: {}", e))?;
 
                 match client_msg {
-                    ClientMessage::Subscribe { subscription } => {
+                    ClientMessage::Subscribe { subscription, compress } => {
                         // Check if this is a new subscription for this market
                         let key = SubscriptionKey::from(&subscription);
                         let is_first = subscriptions.is_first_subscription(&key);
 
+                        // Over the limit: refuse this one, keep the connection
+                        if let Err(e) = subscriptions.check_limits(client_id, &key) {
+                            warn!("Refusing subscription from {}: {:?}", client_id, e);
+                            let _ = outgoing_tx.send(e.to_message()).await;
+                            return Ok(());
+                        }
+                        subscriptions.set_compressed(client_id, &key, compress);
+                        if let SubscriptionType::GlobalTrades { min_notional } = &subscription {
+                            subscriptions.set_tape_filter(client_id, *min_notional);
+                        }
+
+                        // Confirm, then send the market's current state and replay
+                        // recent history; live updates only reach the client once
+                        // it's subscribed, so they follow
+                        let _ = outgoing_tx
+                            .send(ServerMessage::Subscribed {
+                                subscription: subscription.clone(),
+                            })
+                            .await;
+                        if let (Some(source), true) = (snapshot_source, has_snapshot(key.channel)) {
+                            let snapshot = source.snapshot(key.platform, &key.market_id).await;
+                            let _ = outgoing_tx.send(snapshot.into_message(key.platform, key.market_id.clone())).await;
+                        }
+                        if let Some(buffer) = replay_buffer {
+                            for message in buffer.replay(&key) {
+                                let _ = outgoing_tx.send(message).await;
+                            }
+                        }
+
                         subscriptions.subscribe(client_id, &subscription);
 
                         // Notify aggregator if this is the first subscription for this market
-                        if is_first {
-                            if let Some(ref tx) = subscription_event_tx {
-                                let _ = tx.send(SubscriptionEvent::Subscribe {
+                        if is_first && subscription.is_market_scoped() {
+                            if let (Some(tx), Some(event)) =
+                                (subscription_event_tx, SubscriptionEvent::for_key(&key, true))
+                            {
+                                let _ = tx.send(event).await;
+                            }
+
+                            // Bring the cached market up to date for the new subscriber
+                            if let Some(ref tx) = refresh_request_tx {
+                                let _ = tx.try_send(RefreshRequest::Single {
                                     platform: subscription.platform(),
                                     market_id: subscription.market_id().to_string(),
-                                }).await;
+                                });
                             }
                         }
 
                         // Notify trade collector if this is a trades subscription
-                        // (always notify, even if not first - trade collector will dedupe)
-                        if matches!(subscription, SubscriptionType::Trades { .. }) {
+                        // (always notify, even if not first - trade collector will dedupe).
+                        // Live candles and stats are built from stored trades, so they count too.
+                        if matches!(
+                            subscription,
+                            SubscriptionType::Trades { .. }
+                                | SubscriptionType::Candles { .. }
+                                | SubscriptionType::Stats { .. }
+                        ) {
                             if let Some(ref tx) = trade_subscription_tx {
                                 let _ = tx.send(TradeSubscriptionEvent::Subscribe {
                                     platform: subscription.platform(),
@@ -249,29 +663,36 @@ impl WebSocketState {
                                 }).await;
                             }
                         }
-
-                        // Send confirmation
-                        let _ = outgoing_tx
-                            .send(ServerMessage::Subscribed {
-                                subscription,
-                            })
-                            .await;
                     }
                     ClientMessage::Unsubscribe { subscription } => {
+                        let key = SubscriptionKey::from(&subscription);
+                        let was_subscribed = subscriptions.is_subscribed(client_id, &key);
                         subscriptions.unsubscribe(client_id, &subscription);
 
                         // Check if any clients remain subscribed to this market
-                        let key = SubscriptionKey::from(&subscription);
-                        if !subscriptions.has_any_subscribers(&key) {
-                            if let Some(ref tx) = subscription_event_tx {
-                                let _ = tx.send(SubscriptionEvent::Unsubscribe {
-                                    platform: subscription.platform(),
-                                    market_id: subscription.market_id().to_string(),
-                                }).await;
+                        // (a repeated unsubscribe must not release it twice)
+                        if was_subscribed && subscription.is_market_scoped() && !subscriptions.has_any_subscribers(&key) {
+                            if let (Some(tx), Some(event)) =
+                                (subscription_event_tx, SubscriptionEvent::for_key(&key, false))
+                            {
+                                let _ = tx.send(event).await;
                             }
 
-                            // Notify trade collector if this was a trades subscription
-                            if matches!(subscription, SubscriptionType::Trades { .. }) {
+                            // Notify trade collector if this was the last trades/candles/stats
+                            // subscription for the market
+                            let trade_fed = |channel: &SubscriptionChannel| {
+                                matches!(
+                                    channel,
+                                    SubscriptionChannel::Trades
+                                        | SubscriptionChannel::Candles(_)
+                                        | SubscriptionChannel::Stats
+                                )
+                            };
+                            let still_needed = subscriptions
+                                .get_market_subscriptions(subscription.platform(), subscription.market_id())
+                                .iter()
+                                .any(|k| trade_fed(&k.channel));
+                            if trade_fed(&key.channel) && !still_needed {
                                 if let Some(ref tx) = trade_subscription_tx {
                                     let _ = tx.send(TradeSubscriptionEvent::Unsubscribe {
                                         platform: subscription.platform(),
@@ -296,6 +717,10 @@ impl WebSocketState {
                             })
                             .await;
                     }
+                    ClientMessage::Auth { .. } => {
+                        // Only meaningful as the first message of a connection
+                        debug!("Ignoring auth message from already connected {}", client_id);
+                    }
                 }
             }
             Message::Ping(_data) => {
@@ -315,6 +740,8 @@ impl WebSocketState {
                     .send(ServerMessage::Error {
                         code: ErrorCode::InvalidMessage,
                         message: "Binary messages not supported".to_string(),
+                        limit: None,
+                        count: None,
                     })
                     .await;
             }
@@ -340,14 +767,42 @@ impl WebSocketState {
             channel: terminal_core::SubscriptionChannel::Price,
         };
 
+        let message = ServerMessage::PriceUpdate {
+            platform,
+            market_id: market_id.clone(),
+            yes_price,
+            no_price,
+            timestamp: Utc::now(),
+        };
+        self.publish_to_events(platform, &market_id, &message);
+        self.subscriptions.broadcast(key, message);
+    }
+
+    /// Broadcast a top-of-book tick to clients on the market's prices channel
+    pub fn broadcast_price_tick(
+        &self,
+        platform: Platform,
+        market_id: String,
+        best_bid: rust_decimal::Decimal,
+        best_ask: rust_decimal::Decimal,
+        mid: rust_decimal::Decimal,
+    ) {
+        let key = SubscriptionKey {
+            platform,
+            market_id: market_id.clone(),
+            channel: SubscriptionChannel::Prices,
+        };
+
         self.subscriptions.broadcast(
             key,
-            ServerMessage::PriceUpdate {
+            ServerMessage::PriceTick {
                 platform,
                 market_id,
-                yes_price,
-                no_price,
+                best_bid,
+                best_ask,
+                mid,
                 timestamp: Utc::now(),
+                replay: false,
             },
         );
     }
@@ -365,39 +820,295 @@ impl WebSocketState {
             channel: terminal_core::SubscriptionChannel::OrderBook,
         };
 
+        let message = ServerMessage::OrderBookUpdate {
+            platform,
+            market_id: market_id.clone(),
+            update_type: terminal_core::OrderBookUpdateType::Snapshot,
+            yes_bids: orderbook.yes_bids,
+            yes_asks: orderbook.yes_asks,
+            no_bids: orderbook.no_bids,
+            no_asks: orderbook.no_asks,
+            timestamp: Utc::now(),
+            replay: false,
+        };
+        self.publish_to_events(platform, &market_id, &message);
+        self.subscriptions.broadcast(key, message);
+    }
+
+    /// Broadcast a trade update to all subscribed clients
+    pub fn broadcast_trade(&self, trade: terminal_core::Trade) {
+        let key = SubscriptionKey {
+            platform: trade.platform,
+            market_id: trade.market_id.clone(),
+            channel: terminal_core::SubscriptionChannel::Trades,
+        };
+
+        // Mirrored onto the global tape while anyone watches it
+        if !is_canary_market(&trade.market_id) && self.subscriptions.has_any_subscribers(&global_trades_key()) {
+            self.ticker_tape.push(&trade);
+        }
+
+        let (platform, market_id) = (trade.platform, trade.market_id.clone());
+        let message = ServerMessage::TradeUpdate {
+            platform,
+            market_id: market_id.clone(),
+            trade,
+            replay: false,
+        };
+        self.publish_to_events(platform, &market_id, &message);
+        self.subscriptions.broadcast(key, message);
+    }
+
+    /// Re-publish an outcome market's updates to the events it belongs to
+    ///
+    /// Replaces any earlier routes from `event_id`; the aggregator calls this
+    /// once it has resolved the event's outcomes.
+    pub fn route_event(&self, platform: Platform, event_id: &str, outcomes: Vec<(String, String)>) {
+        self.unroute_event(platform, event_id);
+        for (market_id, outcome) in outcomes {
+            self.event_routes.entry((platform, market_id)).or_default().push(EventRoute {
+                event_id: event_id.to_string(),
+                outcome,
+            });
+        }
+    }
+
+    /// Stop re-publishing outcome updates to an event
+    pub fn unroute_event(&self, platform: Platform, event_id: &str) {
+        self.event_routes.retain(|(route_platform, _), routes| {
+            if *route_platform == platform {
+                routes.retain(|route| route.event_id != event_id);
+            }
+            !routes.is_empty()
+        });
+    }
+
+    /// Events an outcome market's updates are re-published to
+    pub fn event_routes(&self, platform: Platform, market_id: &str) -> Vec<EventRoute> {
+        self.event_routes
+            .get(&(platform, market_id.to_string()))
+            .map(|routes| routes.value().clone())
+            .unwrap_or_default()
+    }
+
+    fn publish_to_events(&self, platform: Platform, market_id: &str, message: &ServerMessage) {
+        for route in self.event_routes(platform, market_id) {
+            let key = SubscriptionKey {
+                platform,
+                market_id: route.event_id.clone(),
+                channel: SubscriptionChannel::Event,
+            };
+            self.subscriptions.broadcast(
+                key,
+                ServerMessage::EventUpdate {
+                    platform,
+                    event_id: route.event_id,
+                    market_id: market_id.to_string(),
+                    outcome: route.outcome,
+                    update: Box::new(message.clone()),
+                },
+            );
+        }
+    }
+
+    /// Broadcast a live candle update to clients subscribed to that interval
+    pub fn broadcast_candle_update(
+        &self,
+        platform: Platform,
+        market_id: String,
+        interval: terminal_core::PriceInterval,
+        candle: terminal_core::PriceCandle,
+        closed: bool,
+    ) {
+        let key = SubscriptionKey {
+            platform,
+            market_id: market_id.clone(),
+            channel: terminal_core::SubscriptionChannel::Candles(interval),
+        };
+
         self.subscriptions.broadcast(
             key,
-            ServerMessage::OrderBookUpdate {
+            ServerMessage::CandleUpdate {
                 platform,
                 market_id,
-                update_type: terminal_core::OrderBookUpdateType::Snapshot,
-                yes_bids: orderbook.yes_bids,
-                yes_asks: orderbook.yes_asks,
-                no_bids: orderbook.no_bids,
-                no_asks: orderbook.no_asks,
-                timestamp: Utc::now(),
+                interval,
+                candle,
+                closed,
             },
         );
     }
 
-    /// Broadcast a trade update to all subscribed clients
-    pub fn broadcast_trade(&self, trade: terminal_core::Trade) {
+    /// Broadcast recomputed stats to clients subscribed to a market's stats
+    pub fn broadcast_stats_update(&self, platform: Platform, market_id: String, stats: serde_json::Value) {
         let key = SubscriptionKey {
-            platform: trade.platform,
-            market_id: trade.market_id.clone(),
-            channel: terminal_core::SubscriptionChannel::Trades,
+            platform,
+            market_id: market_id.clone(),
+            channel: terminal_core::SubscriptionChannel::Stats,
         };
 
         self.subscriptions.broadcast(
             key,
-            ServerMessage::TradeUpdate {
-                platform: trade.platform,
-                market_id: trade.market_id.clone(),
-                trade,
+            ServerMessage::StatsUpdate {
+                platform,
+                market_id,
+                stats,
             },
         );
     }
 
+    /// Announce a newly listed market to clients following its platform's listings
+    pub fn broadcast_market_listed(&self, market: terminal_core::PredictionMarket) {
+        let key = SubscriptionKey {
+            platform: market.platform,
+            market_id: String::new(),
+            channel: terminal_core::SubscriptionChannel::MarketListings,
+        };
+
+        self.subscriptions.broadcast(
+            key,
+            ServerMessage::MarketListed {
+                platform: market.platform,
+                market,
+            },
+        );
+    }
+
+    /// Send an unusual-activity alert to clients following its platform's alerts
+    pub fn broadcast_market_alert(&self, alert: MarketAlert) {
+        let key = SubscriptionKey {
+            platform: alert.platform,
+            market_id: String::new(),
+            channel: SubscriptionChannel::Alerts,
+        };
+
+        self.subscriptions.broadcast(
+            key,
+            ServerMessage::MarketAlert {
+                platform: alert.platform,
+                market_id: alert.market_id,
+                kind: alert.kind,
+                magnitude: alert.magnitude,
+                timestamp: alert.timestamp,
+            },
+        );
+    }
+
+    /// Forward broadcasts meant for `client_id` into its outgoing queue
+    ///
+    /// Global messages (like research updates) go to all clients, private
+    /// ones (order updates) to trusted clients, the rest to subscribers of
+    /// the key. Stops once the queue's receiver is dropped.
+    pub fn forward_to_client(
+        &self,
+        client_id: ClientId,
+        trusted: bool,
+        outgoing_tx: mpsc::Sender<ServerMessage>,
+    ) -> JoinHandle<()> {
+        // Subscribe before spawning so nothing sent after this call is missed
+        let mut broadcast_rx = self.subscriptions.subscribe_broadcast();
+        let subscriptions = Arc::clone(&self.subscriptions);
+        tokio::spawn(async move {
+            loop {
+                match broadcast_rx.recv().await {
+                    Ok(BroadcastMessage { key, message }) => {
+                        let deliver = match key.market_id.as_str() {
+                            "__global__" => true,
+                            "__authenticated__" => trusted,
+                            _ => {
+                                subscriptions.is_subscribed(client_id, &key)
+                                    && match &message {
+                                        ServerMessage::GlobalTrade { trade } => {
+                                            subscriptions.passes_tape_filter(client_id, trade)
+                                        }
+                                        _ => true,
+                                    }
+                            }
+                        };
+                        if deliver {
+                            if outgoing_tx.send(message).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Client {} lagged {} messages", client_id, n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Forward markets added to the market cache to `market_listings` subscribers
+    ///
+    /// Updates and removals are not forwarded; clients follow those through
+    /// the per-market channels.
+    pub fn forward_market_listings(
+        &self,
+        mut events: broadcast::Receiver<MarketCacheEvent>,
+    ) -> JoinHandle<()> {
+        let state = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(MarketCacheEvent::Added { market, .. }) => state.broadcast_market_listed(market),
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Market listing forwarder lagged {} cache events", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Buffer broadcasts for detached sessions and expire them, releasing
+    /// the markets they held
+    pub fn start_sessions(&self) -> JoinHandle<()> {
+        let state = self.clone();
+        let mut broadcast_rx = self.subscriptions.subscribe_broadcast();
+        tokio::spawn(async move {
+            let mut sweep = tokio::time::interval(SESSION_SWEEP_INTERVAL);
+            sweep.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    received = broadcast_rx.recv() => match received {
+                        Ok(broadcast) => state.sessions.record(&broadcast),
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("Session buffer lagged {} messages", n);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = sweep.tick() => {
+                        let released = state.sessions.sweep();
+                        state.release_markets(&released).await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Publish the global trade tape every window until the state is dropped
+    pub fn start_ticker_tape(&self) -> JoinHandle<()> {
+        let tape = Arc::clone(&self.ticker_tape);
+        let subscriptions = Arc::downgrade(&self.subscriptions);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tape.config().window);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let Some(subscriptions) = subscriptions.upgrade() else {
+                    break;
+                };
+                for trade in tape.drain() {
+                    subscriptions.broadcast(global_trades_key(), ServerMessage::GlobalTrade { trade });
+                }
+            }
+        })
+    }
+
     /// Broadcast a global news item to all subscribed clients
     pub fn broadcast_global_news(&self, news_item: terminal_core::NewsItem) {
         // Global news doesn't have a specific market/platform key
@@ -431,6 +1142,18 @@ impl WebSocketState {
         );
     }
 
+    /// Tell every connected client about an exchange connection change
+    ///
+    /// `resync` marks a feed restored after a drop, so clients refetch
+    /// snapshots for whatever they missed.
+    pub fn broadcast_connection_status(&self, platform: Platform, status: terminal_core::ConnectionState, resync: bool) {
+        self.subscriptions.broadcast_to_all(ServerMessage::ConnectionStatus {
+            platform,
+            status,
+            resync,
+        });
+    }
+
     /// Broadcast a research update to all connected clients
     ///
     /// Research updates are global messages that go to all clients,
@@ -438,6 +1161,11 @@ impl WebSocketState {
     pub fn broadcast_research_update(&self, update: serde_json::Value) {
         self.subscriptions.broadcast_to_all(ServerMessage::ResearchUpdate { update });
     }
+
+    /// Broadcast an order update to authenticated clients
+    pub fn broadcast_order_update(&self, update: serde_json::Value) {
+        self.subscriptions.broadcast_to_authenticated(ServerMessage::OrderUpdate { update });
+    }
 }
 
 impl std::fmt::Debug for WebSocketState {
@@ -447,3 +1175,253 @@ impl std::fmt::Debug for WebSocketState {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::websocket::ClientStats;
+    use terminal_core::SubscriptionType;
+
+    fn trades(market_id: &str) -> SubscriptionType {
+        SubscriptionType::Trades {
+            platform: Platform::Kalshi,
+            market_id: market_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dead_client_keys_removed_and_markets_released() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let mut state = WebSocketState::new(market_service);
+        let (tx, mut events) = WebSocketState::create_subscription_event_channel();
+        state.set_subscription_event_sender(tx);
+
+        // A sleeping tab watching two markets, one shared with a live tab
+        let dead = state.subscriptions.connect_client();
+        let live = state.subscriptions.connect_client();
+        state.subscriptions.subscribe(dead, &trades("shared"));
+        state.subscriptions.subscribe(dead, &trades("only-dead"));
+        state.subscriptions.subscribe(live, &trades("shared"));
+        assert_eq!(state.subscriptions.client_stats().connected_clients, 2);
+
+        state.disconnect_client(dead, true).await;
+
+        for market_id in ["shared", "only-dead"] {
+            let key = SubscriptionKey::from(&trades(market_id));
+            assert!(!state.subscriptions.is_subscribed(dead, &key));
+        }
+        assert_eq!(state.subscriptions.subscriber_count(&SubscriptionKey::from(&trades("shared"))), 1);
+        assert!(!state.subscriptions.has_any_subscribers(&SubscriptionKey::from(&trades("only-dead"))));
+
+        // Only the market nobody else watches is released
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SubscriptionEvent::Unsubscribe { platform: Platform::Kalshi, ref market_id }) if market_id == "only-dead"
+        ));
+        assert!(events.try_recv().is_err());
+
+        assert_eq!(state.subscriptions.client_stats(), ClientStats {
+            connected_clients: 1,
+            cleaned_up_clients: 1,
+        });
+
+        // A normal close isn't a cleanup
+        state.disconnect_client(live, false).await;
+        assert_eq!(state.subscriptions.client_stats().connected_clients, 0);
+        assert_eq!(state.subscriptions.client_stats().cleaned_up_clients, 1);
+    }
+
+    /// Handle a trades subscribe or unsubscribe for `market_id`
+    async fn trades_message(
+        state: &WebSocketState,
+        client_id: ClientId,
+        outgoing_tx: &mpsc::Sender<ServerMessage>,
+        kind: &str,
+        market_id: &str,
+    ) {
+        let text = format!(
+            r#"{{"type":"{}","subscription":{{"type":"trades","platform":"kalshi","market_id":"{}"}}}}"#,
+            kind, market_id
+        );
+        WebSocketState::handle_message(
+            client_id,
+            tokio_tungstenite::tungstenite::Message::Text(text.into()),
+            &state.subscriptions,
+            outgoing_tx,
+            &None,
+            &None,
+            &None,
+            &None,
+            &None,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscription_over_limit_refused_without_disconnecting() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let mut state = WebSocketState::new(market_service);
+        state.set_subscription_limits(SubscriptionLimits {
+            per_client: Some(1),
+            total: None,
+        });
+        let client_id = state.subscriptions.connect_client();
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel(16);
+
+        trades_message(&state, client_id, &outgoing_tx, "subscribe", "m1").await;
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Subscribed { .. })));
+        trades_message(&state, client_id, &outgoing_tx, "subscribe", "m2").await;
+        assert!(matches!(
+            outgoing_rx.try_recv(),
+            Ok(ServerMessage::Error { code: ErrorCode::SubscriptionLimitExceeded, limit: Some(1), count: Some(1), .. })
+        ));
+        assert!(!state.subscriptions.is_subscribed(client_id, &SubscriptionKey::from(&trades("m2"))));
+
+        // Still connected: freeing quota lets the next one through
+        trades_message(&state, client_id, &outgoing_tx, "unsubscribe", "m1").await;
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Unsubscribed { .. })));
+        trades_message(&state, client_id, &outgoing_tx, "subscribe", "m2").await;
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Subscribed { .. })));
+    }
+
+    fn trade(id: &str, market_id: &str, quantity: i64) -> terminal_core::Trade {
+        terminal_core::Trade {
+            id: id.to_string(),
+            market_id: market_id.to_string(),
+            platform: Platform::Kalshi,
+            timestamp: Utc::now(),
+            price: rust_decimal::Decimal::new(50, 2),
+            quantity: rust_decimal::Decimal::from(quantity),
+            outcome: terminal_core::TradeOutcome::Yes,
+            side: None,
+            transaction_hash: None,
+            outcome_id: None,
+            maker_address: None,
+            taker_address: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_global_trades_filtered_by_min_notional() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let state = WebSocketState::new(market_service);
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel(16);
+        let big_only = state.subscriptions.connect_client();
+        let everything = state.subscriptions.connect_client();
+
+        for (client_id, subscription) in [
+            (big_only, r#"{"type":"global_trades","min_notional":"100"}"#),
+            (everything, r#"{"type":"global_trades"}"#),
+        ] {
+            let text = format!(r#"{{"type":"subscribe","subscription":{}}}"#, subscription);
+            WebSocketState::handle_message(
+                client_id,
+                tokio_tungstenite::tungstenite::Message::Text(text.into()),
+                &state.subscriptions,
+                &outgoing_tx,
+                &None,
+                &None,
+                &None,
+                &None,
+                &None,
+            )
+            .await
+            .unwrap();
+            assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Subscribed { .. })));
+        }
+
+        // Trades on any market reach the tape, the canary's don't
+        state.broadcast_trade(trade("t1", "KXSNOW-25", 400)); // 200 notional
+        state.broadcast_trade(trade("t2", "KXRAIN-25", 10)); // 5 notional
+        state.broadcast_trade(trade("t3", crate::CANARY_MARKET_ID, 400));
+        let tape = state.ticker_tape.drain();
+        assert_eq!(tape.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["t1", "t2"]);
+
+        let subscriptions = &state.subscriptions;
+        assert!(subscriptions.is_subscribed(big_only, &global_trades_key()));
+        assert!(subscriptions.passes_tape_filter(big_only, &tape[0]));
+        assert!(!subscriptions.passes_tape_filter(big_only, &tape[1]));
+        assert!(subscriptions.passes_tape_filter(everything, &tape[1]));
+
+        // Dropping the subscription drops its filter
+        subscriptions.unsubscribe(big_only, &SubscriptionType::GlobalTrades { min_notional: None });
+        assert!(subscriptions.passes_tape_filter(big_only, &tape[1]));
+    }
+
+    #[tokio::test]
+    async fn test_session_resumed_with_missed_updates_and_subscriptions() {
+        let market_service = MarketService::new(
+            terminal_kalshi::KalshiClient::new(true),
+            terminal_polymarket::PolymarketClient::new(),
+        );
+        let mut state = WebSocketState::new(market_service);
+        state.set_sessions(SessionConfig {
+            ttl: Duration::from_millis(300),
+            ..SessionConfig::default()
+        });
+        let (subscription_tx, mut subscription_rx) = WebSocketState::create_subscription_event_channel();
+        state.set_subscription_event_sender(subscription_tx);
+        let sessions_task = state.start_sessions();
+        let key = SubscriptionKey::from(&trades("m1"));
+
+        // First connection: fresh session, one subscription, then gone
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel(16);
+        let first = state.subscriptions.connect_client();
+        state.resume_session("phone", first, &outgoing_tx).await;
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Session { resumed: false, .. })));
+        trades_message(&state, first, &outgoing_tx, "subscribe", "m1").await;
+        assert!(matches!(subscription_rx.try_recv(), Ok(SubscriptionEvent::Subscribe { .. })));
+        state.detach_session("phone", first, false, vec![]).await;
+        // The session keeps the market open
+        assert!(subscription_rx.try_recv().is_err());
+
+        state.broadcast_trade(trade("t1", "m1", 1));
+        state.broadcast_trade(trade("t2", "m1", 2));
+        for _ in 0..100 {
+            if state.session_stats().buffered_messages == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Reconnecting in time replays both, then restores the subscription
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel(16);
+        let second = state.subscriptions.connect_client();
+        state.resume_session("phone", second, &outgoing_tx).await;
+        assert!(matches!(
+            outgoing_rx.try_recv(),
+            Ok(ServerMessage::Session { resumed: true, replayed: 2, dropped: 0, .. })
+        ));
+        for id in ["t1", "t2"] {
+            match outgoing_rx.try_recv() {
+                Ok(ServerMessage::TradeUpdate { trade, .. }) => assert_eq!(trade.id, id),
+                other => panic!("expected trade {}, got {:?}", id, other),
+            }
+        }
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Subscribed { .. })));
+        assert!(state.subscriptions.is_subscribed(second, &key));
+        // Handed over: opened for the restored subscription, released for the session
+        assert!(matches!(subscription_rx.try_recv(), Ok(SubscriptionEvent::Subscribe { .. })));
+        assert!(matches!(subscription_rx.try_recv(), Ok(SubscriptionEvent::Unsubscribe { .. })));
+
+        // After the TTL the session is gone and the market released
+        state.detach_session("phone", second, false, vec![]).await;
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let third = state.subscriptions.connect_client();
+        state.resume_session("phone", third, &outgoing_tx).await;
+        assert!(matches!(outgoing_rx.try_recv(), Ok(ServerMessage::Session { resumed: false, .. })));
+        assert!(!state.subscriptions.is_subscribed(third, &key));
+        assert_eq!(state.session_stats().expired, 1);
+        sessions_task.abort();
+    }
+}
@@ -5,6 +5,20 @@
 
 mod subscription;
 mod handler;
+mod auth;
+mod compression;
+mod outbound;
+mod session;
 
-pub use subscription::SubscriptionManager;
-pub use handler::{SubscriptionEvent, TradeSubscriptionEvent, WebSocketState};
+pub use subscription::{
+    ClientId, ClientStats, SubscriptionLimitError, SubscriptionLimits, SubscriptionManager, DEFAULT_MAX_SUBSCRIPTIONS_PER_CLIENT,
+    DEFAULT_MAX_SUBSCRIPTIONS_TOTAL,
+};
+pub use outbound::{OutboundBatching, SlowConsumer, DEFAULT_FLUSH_INTERVAL, DEFAULT_MAX_QUEUED};
+pub use compression::{decompress, CompressionStats, PayloadCompressor, DEFAULT_COMPRESSION_LEVEL};
+pub use auth::{AuthError, WsAuth, DEFAULT_AUTH_TIMEOUT};
+pub use session::{
+    SessionConfig, SessionStats, SessionStore, SessionSubscription, DEFAULT_MAX_SESSIONS, DEFAULT_SESSION_BUFFER,
+    DEFAULT_SESSION_TTL,
+};
+pub use handler::{ClientHeartbeat, EventRoute, SubscriptionEvent, TradeSubscriptionEvent, WebSocketState};
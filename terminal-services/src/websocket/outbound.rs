@@ -0,0 +1,425 @@
+//! Per-client outbound message queue
+//!
+//! Broadcasts are queued per client and flushed on a short interval as one
+//! JSON array frame, rather than one frame per message. Trades always go
+//! out; a newer order book for a market replaces (or, for a delta, is merged
+//! into) the one still waiting, so a client only gets the latest book per
+//! flush. A client whose queue grows past its bound is too slow to keep up
+//! and is disconnected with `ErrorCode::SlowConsumer`.
+//!
+//! The aggregator's `UpdateCoalescer` already thins updates per market at
+//! the source; this queue is about delivery to each connection.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::{Sink, SinkExt};
+use rust_decimal::Decimal;
+use terminal_core::{ErrorCode, OrderBookLevel, OrderBookUpdateType, Platform, ServerMessage};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::error;
+
+/// Default delay between flushes of a client's queue
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Default messages a client may have queued before it counts as too slow
+pub const DEFAULT_MAX_QUEUED: usize = 2000;
+
+/// How client messages are batched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboundBatching {
+    /// Zero sends each message in its own frame as soon as it's queued
+    pub flush_interval: Duration,
+    /// Queued messages (after coalescing) that make a client a slow consumer
+    pub max_queued: usize,
+}
+
+impl Default for OutboundBatching {
+    fn default() -> Self {
+        Self {
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            max_queued: DEFAULT_MAX_QUEUED,
+        }
+    }
+}
+
+impl OutboundBatching {
+    pub fn is_batched(&self) -> bool {
+        !self.flush_interval.is_zero()
+    }
+}
+
+/// A client's queue grew past its bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlowConsumer {
+    pub queued: usize,
+}
+
+impl SlowConsumer {
+    /// The error sent to the client before it is closed
+    pub fn to_message(&self) -> ServerMessage {
+        ServerMessage::Error {
+            code: ErrorCode::SlowConsumer,
+            message: format!("{} messages waiting to be sent; closing slow connection", self.queued),
+            limit: None,
+            count: Some(self.queued),
+        }
+    }
+}
+
+/// Messages waiting to be flushed to one client
+#[derive(Debug)]
+pub(crate) struct OutboundQueue {
+    max_queued: usize,
+    /// In send order; `None` marks a book superseded by a later one
+    pending: Vec<Option<ServerMessage>>,
+    queued: usize,
+    /// Market -> index of its pending book in `pending`
+    books: HashMap<(Platform, String), usize>,
+    overflowed: Option<SlowConsumer>,
+}
+
+impl OutboundQueue {
+    pub(crate) fn new(max_queued: usize) -> Self {
+        Self {
+            max_queued,
+            pending: Vec::new(),
+            queued: 0,
+            books: HashMap::new(),
+            overflowed: None,
+        }
+    }
+
+    /// Queue a message, coalescing order books
+    ///
+    /// Once the queue has overflowed it stays overflowed and takes nothing
+    /// more.
+    pub(crate) fn push(&mut self, message: ServerMessage) -> Result<(), SlowConsumer> {
+        if let Some(slow) = self.overflowed {
+            return Err(slow);
+        }
+
+        let message = match message {
+            ServerMessage::OrderBookUpdate { platform, ref market_id, .. } => {
+                match self.books.remove(&(platform, market_id.clone())) {
+                    Some(index) => {
+                        let previous = self.pending[index].take().expect("indexed book is pending");
+                        self.queued -= 1;
+                        coalesce_books(previous, message)
+                    }
+                    None => message,
+                }
+            }
+            other => other,
+        };
+        if let ServerMessage::OrderBookUpdate { platform, market_id, .. } = &message {
+            self.books.insert((*platform, market_id.clone()), self.pending.len());
+        }
+        self.pending.push(Some(message));
+        self.queued += 1;
+
+        // Superseded books leave gaps; don't let them pile up while the
+        // client isn't draining
+        if self.pending.len() > 2 * self.queued.max(16) {
+            self.compact();
+        }
+
+        if self.queued > self.max_queued {
+            let slow = SlowConsumer { queued: self.queued };
+            self.overflowed = Some(slow);
+            return Err(slow);
+        }
+        Ok(())
+    }
+
+    /// Set once the client fell too far behind
+    pub(crate) fn overflowed(&self) -> Option<SlowConsumer> {
+        self.overflowed
+    }
+
+    /// Everything waiting, in send order
+    pub(crate) fn take(&mut self) -> Vec<ServerMessage> {
+        self.books.clear();
+        self.queued = 0;
+        self.pending.drain(..).flatten().collect()
+    }
+
+    fn compact(&mut self) {
+        self.pending.retain(Option::is_some);
+        self.books.clear();
+        for (index, message) in self.pending.iter().enumerate() {
+            if let Some(ServerMessage::OrderBookUpdate { platform, market_id, .. }) = message {
+                self.books.insert((*platform, market_id.clone()), index);
+            }
+        }
+    }
+}
+
+/// Send everything queued, through `prepare`, as one array frame when
+/// `batched` or one frame per message otherwise; returns the messages sent
+pub(crate) async fn flush<W>(
+    sink: &mut W,
+    queue: &parking_lot::Mutex<OutboundQueue>,
+    batched: bool,
+    prepare: impl Fn(ServerMessage) -> ServerMessage,
+) -> Result<usize, W::Error>
+where
+    W: Sink<Message> + Unpin,
+{
+    let batch: Vec<ServerMessage> = queue.lock().take().into_iter().map(prepare).collect();
+    if batch.is_empty() {
+        return Ok(0);
+    }
+    let sent = batch.len();
+
+    let frames = if batched {
+        serde_json::to_string(&batch).map(|json| vec![json])
+    } else {
+        batch.iter().map(serde_json::to_string).collect()
+    };
+    match frames {
+        Ok(frames) => {
+            for json in frames {
+                sink.feed(Message::Text(json.into())).await?;
+            }
+            sink.flush().await?;
+        }
+        Err(e) => error!("Failed to serialize messages: {}", e),
+    }
+    Ok(sent)
+}
+
+/// `next` replacing or updating the `previous` book for the same market
+///
+/// A snapshot replaces whatever was waiting. A delta is applied to a waiting
+/// snapshot, or folded into a waiting delta (keeping zero-quantity removals).
+fn coalesce_books(previous: ServerMessage, next: ServerMessage) -> ServerMessage {
+    match (previous, next) {
+        (
+            ServerMessage::OrderBookUpdate {
+                update_type: previous_type,
+                yes_bids: mut previous_yes_bids,
+                yes_asks: mut previous_yes_asks,
+                no_bids: mut previous_no_bids,
+                no_asks: mut previous_no_asks,
+                replay: previous_replay,
+                ..
+            },
+            ServerMessage::OrderBookUpdate {
+                platform,
+                market_id,
+                update_type: OrderBookUpdateType::Delta,
+                yes_bids,
+                yes_asks,
+                no_bids,
+                no_asks,
+                timestamp,
+                replay,
+            },
+        ) => {
+            let keep_removals = previous_type == OrderBookUpdateType::Delta;
+            apply_levels(&mut previous_yes_bids, yes_bids, true, keep_removals);
+            apply_levels(&mut previous_yes_asks, yes_asks, false, keep_removals);
+            apply_levels(&mut previous_no_bids, no_bids, true, keep_removals);
+            apply_levels(&mut previous_no_asks, no_asks, false, keep_removals);
+
+            ServerMessage::OrderBookUpdate {
+                platform,
+                market_id,
+                update_type: previous_type,
+                yes_bids: previous_yes_bids,
+                yes_asks: previous_yes_asks,
+                no_bids: previous_no_bids,
+                no_asks: previous_no_asks,
+                timestamp,
+                replay: previous_replay && replay,
+            }
+        }
+        (_, next) => next,
+    }
+}
+
+/// Apply changed levels to one side, best price first
+fn apply_levels(side: &mut Vec<OrderBookLevel>, changes: Vec<OrderBookLevel>, bids: bool, keep_removals: bool) {
+    for change in changes {
+        side.retain(|level| level.price != change.price);
+        if keep_removals || change.quantity > Decimal::ZERO {
+            side.push(change);
+        }
+    }
+    if bids {
+        side.sort_by_key(|level| std::cmp::Reverse(level.price));
+    } else {
+        side.sort_by_key(|level| level.price);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use terminal_core::{Trade, TradeOutcome};
+
+    fn level(price: i64, quantity: i64) -> OrderBookLevel {
+        OrderBookLevel {
+            price: Decimal::new(price, 2),
+            quantity: Decimal::from(quantity),
+            order_count: None,
+        }
+    }
+
+    fn book(market_id: &str, update_type: OrderBookUpdateType, yes_bids: Vec<OrderBookLevel>) -> ServerMessage {
+        ServerMessage::OrderBookUpdate {
+            platform: Platform::Kalshi,
+            market_id: market_id.to_string(),
+            update_type,
+            yes_bids,
+            yes_asks: vec![],
+            no_bids: vec![],
+            no_asks: vec![],
+            timestamp: Utc::now(),
+            replay: false,
+        }
+    }
+
+    fn trade(id: &str) -> ServerMessage {
+        ServerMessage::TradeUpdate {
+            platform: Platform::Kalshi,
+            market_id: "a".to_string(),
+            trade: Trade {
+                id: id.to_string(),
+                market_id: "a".to_string(),
+                platform: Platform::Kalshi,
+                timestamp: Utc::now(),
+                price: Decimal::new(50, 2),
+                quantity: Decimal::ONE,
+                outcome: TradeOutcome::Yes,
+                side: None,
+                transaction_hash: None,
+                outcome_id: None,
+                maker_address: None,
+                taker_address: None,
+            },
+            replay: false,
+        }
+    }
+
+    /// Collects sent frames
+    #[derive(Default)]
+    struct FakeSink {
+        frames: Vec<Message>,
+    }
+
+    impl Sink<Message> for FakeSink {
+        type Error = std::convert::Infallible;
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+            self.frames.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn decode(frame: &Message) -> serde_json::Value {
+        match frame {
+            Message::Text(text) => serde_json::from_str(text.as_str()).unwrap(),
+            other => panic!("expected a text frame, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_trades_and_coalesces_books() {
+        let queue = parking_lot::Mutex::new(OutboundQueue::new(100));
+        {
+            let mut queue = queue.lock();
+            queue.push(book("a", OrderBookUpdateType::Snapshot, vec![level(40, 10), level(39, 5)])).unwrap();
+            queue.push(trade("t1")).unwrap();
+            queue.push(book("b", OrderBookUpdateType::Snapshot, vec![level(20, 1)])).unwrap();
+            queue.push(trade("t2")).unwrap();
+            // Delta onto the waiting snapshot: 0.40 removed, 0.41 added
+            queue.push(book("a", OrderBookUpdateType::Delta, vec![level(40, 0), level(41, 7)])).unwrap();
+            queue.push(book("b", OrderBookUpdateType::Snapshot, vec![level(21, 2)])).unwrap();
+            assert_eq!(queue.queued, 4);
+        }
+
+        let mut sink = FakeSink::default();
+        assert_eq!(flush(&mut sink, &queue, true, std::convert::identity).await.unwrap(), 4);
+        assert_eq!(sink.frames.len(), 1);
+        let batch = decode(&sink.frames[0]).as_array().cloned().unwrap();
+        let kinds: Vec<(&str, &str)> = batch
+            .iter()
+            .map(|m| (m["type"].as_str().unwrap(), m["market_id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("trade_update", "a"), ("trade_update", "a"), ("order_book_update", "a"), ("order_book_update", "b")]
+        );
+        assert_eq!(batch[0]["trade"]["id"], "t1");
+        assert_eq!(batch[1]["trade"]["id"], "t2");
+        assert_eq!(batch[2]["update_type"], "snapshot");
+        let bids: Vec<&str> = batch[2]["yes_bids"].as_array().unwrap().iter().map(|l| l["price"].as_str().unwrap()).collect();
+        assert_eq!(bids, vec!["0.41", "0.39"]);
+        assert_eq!(batch[3]["yes_bids"][0]["price"], "0.21");
+
+        // Nothing left; unbatched sends a frame per message
+        assert_eq!(flush(&mut sink, &queue, true, std::convert::identity).await.unwrap(), 0);
+        queue.lock().push(trade("t3")).unwrap();
+        queue.lock().push(trade("t4")).unwrap();
+        assert_eq!(flush(&mut sink, &queue, false, std::convert::identity).await.unwrap(), 2);
+        assert_eq!(sink.frames.len(), 3);
+        assert_eq!(decode(&sink.frames[1])["trade"]["id"], "t3");
+        assert_eq!(decode(&sink.frames[2])["trade"]["id"], "t4");
+    }
+
+    #[test]
+    fn test_deltas_fold_together_keeping_removals() {
+        let mut queue = OutboundQueue::new(100);
+        queue.push(book("a", OrderBookUpdateType::Delta, vec![level(40, 0)])).unwrap();
+        queue.push(book("a", OrderBookUpdateType::Delta, vec![level(41, 3)])).unwrap();
+        match queue.take().as_slice() {
+            [ServerMessage::OrderBookUpdate { update_type, yes_bids, .. }] => {
+                assert_eq!(*update_type, OrderBookUpdateType::Delta);
+                let levels: Vec<(Decimal, Decimal)> = yes_bids.iter().map(|l| (l.price, l.quantity)).collect();
+                assert_eq!(levels, vec![(Decimal::new(41, 2), Decimal::from(3)), (Decimal::new(40, 2), Decimal::ZERO)]);
+            }
+            other => panic!("expected one book, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_overflow_marks_slow_consumer() {
+        let mut queue = OutboundQueue::new(3);
+        for id in ["t1", "t2", "t3"] {
+            queue.push(trade(id)).unwrap();
+        }
+        // Books for one market never use up more than one slot
+        for _ in 0..100 {
+            queue.push(book("a", OrderBookUpdateType::Snapshot, vec![level(40, 1)])).unwrap_err();
+        }
+        assert_eq!(queue.overflowed(), Some(SlowConsumer { queued: 4 }));
+
+        let mut queue = OutboundQueue::new(3);
+        queue.push(trade("t1")).unwrap();
+        for _ in 0..100 {
+            queue.push(book("a", OrderBookUpdateType::Snapshot, vec![level(40, 1)])).unwrap();
+        }
+        assert_eq!(queue.queued, 2);
+        assert!(queue.pending.len() < 40);
+        assert_eq!(queue.push(trade("t2")), Ok(()));
+        assert_eq!(queue.push(trade("t3")), Err(SlowConsumer { queued: 4 }));
+        assert!(queue.push(trade("t4")).is_err());
+    }
+}
@@ -0,0 +1,400 @@
+//! Resumable client sessions
+//!
+//! A client that connects with `?session_id=` keeps its session across
+//! reconnects. When the connection drops, its subscriptions and whatever was
+//! still queued for it are kept for the session TTL, and every message it
+//! would have been sent meanwhile is buffered, oldest dropped first past the
+//! bound. Reconnecting with the same id (and token) within the TTL replays
+//! the buffer and restores the subscriptions; after it the session starts
+//! fresh. Markets a detached session was the last subscriber to stay open
+//! until it resumes or expires.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use terminal_core::{ServerMessage, SubscriptionKey, SubscriptionType};
+
+use super::subscription::{BroadcastMessage, ClientId};
+use crate::ticker_tape::notional;
+
+/// Default time a dropped connection's session is kept
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(60);
+
+/// Default missed messages kept per session
+pub const DEFAULT_SESSION_BUFFER: usize = 100;
+
+/// Default detached sessions kept at once
+pub const DEFAULT_MAX_SESSIONS: usize = 500;
+
+/// Longest session id accepted
+const MAX_SESSION_ID_LEN: usize = 128;
+
+/// How long dropped connections' sessions are kept, and how much of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    /// Zero turns resuming off
+    pub ttl: Duration,
+    /// Missed messages kept per session
+    pub max_buffered: usize,
+    /// Detached sessions kept at once; past it the longest detached expires
+    pub max_sessions: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_SESSION_TTL,
+            max_buffered: DEFAULT_SESSION_BUFFER,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+        }
+    }
+}
+
+/// A subscription as the client made it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSubscription {
+    pub subscription: SubscriptionType,
+    pub compress: bool,
+}
+
+/// Session counters, as reported in the health payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SessionStats {
+    pub detached_sessions: usize,
+    /// Missed messages waiting in detached sessions
+    pub buffered_messages: usize,
+    pub resumed: u64,
+    /// Sessions that ran out their TTL or were evicted for room
+    pub expired: u64,
+}
+
+/// What a closed connection leaves for its session to resume
+#[derive(Debug)]
+pub(crate) struct DetachedSession {
+    identity: Option<String>,
+    /// Whether it got private (`__authenticated__`) messages
+    trusted: bool,
+    subscriptions: Vec<SessionSubscription>,
+    /// Markets it was the last subscriber to, released when it ends
+    held: Vec<SubscriptionKey>,
+    keys: HashSet<SubscriptionKey>,
+    min_notional: Option<Decimal>,
+    missed: VecDeque<ServerMessage>,
+    dropped: usize,
+    detached_at: Instant,
+}
+
+impl DetachedSession {
+    pub(crate) fn new(
+        identity: Option<String>,
+        trusted: bool,
+        subscriptions: Vec<SessionSubscription>,
+        held: Vec<SubscriptionKey>,
+    ) -> Self {
+        let keys = subscriptions.iter().map(|s| SubscriptionKey::from(&s.subscription)).collect();
+        let min_notional = subscriptions.iter().find_map(|s| match s.subscription {
+            SubscriptionType::GlobalTrades { min_notional } => min_notional,
+            _ => None,
+        });
+        Self {
+            identity,
+            trusted,
+            subscriptions,
+            held,
+            keys,
+            min_notional,
+            missed: VecDeque::new(),
+            dropped: 0,
+            detached_at: Instant::now(),
+        }
+    }
+
+    /// Whether the connection would have been sent `message` (the same rule
+    /// as its broadcast forwarder)
+    fn wants(&self, key: &SubscriptionKey, message: &ServerMessage) -> bool {
+        match key.market_id.as_str() {
+            "__global__" => true,
+            "__authenticated__" => self.trusted,
+            _ => {
+                self.keys.contains(key)
+                    && match message {
+                        ServerMessage::GlobalTrade { trade } => {
+                            self.min_notional.is_none_or(|min_notional| notional(trade) >= min_notional)
+                        }
+                        _ => true,
+                    }
+            }
+        }
+    }
+
+    fn buffer(&mut self, message: ServerMessage, max_buffered: usize) {
+        self.missed.push_back(message);
+        while self.missed.len() > max_buffered {
+            self.missed.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
+
+/// A detached session picked up by a new connection
+#[derive(Debug)]
+pub(crate) struct ResumedSession {
+    pub subscriptions: Vec<SessionSubscription>,
+    /// Oldest first
+    pub missed: Vec<ServerMessage>,
+    /// Missed messages that didn't fit the buffer
+    pub dropped: usize,
+    /// Markets to release once the subscriptions are restored
+    pub held: Vec<SubscriptionKey>,
+}
+
+#[derive(Debug, Default)]
+struct Sessions {
+    /// Session id -> the connection holding it
+    attached: HashMap<String, ClientId>,
+    detached: HashMap<String, DetachedSession>,
+    /// Markets held by sessions that ended, waiting for the sweep
+    released: Vec<SubscriptionKey>,
+}
+
+/// Sessions by client-supplied id
+#[derive(Debug)]
+pub struct SessionStore {
+    config: SessionConfig,
+    sessions: Mutex<Sessions>,
+    resumed: AtomicU64,
+    expired: AtomicU64,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::new(SessionConfig::default())
+    }
+}
+
+impl SessionStore {
+    pub fn new(config: SessionConfig) -> Self {
+        Self {
+            config,
+            sessions: Mutex::new(Sessions::default()),
+            resumed: AtomicU64::new(0),
+            expired: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether a connection may keep a session under `session_id`
+    pub fn accepts(&self, session_id: &str) -> bool {
+        !self.config.ttl.is_zero()
+            && self.config.max_sessions > 0
+            && !session_id.is_empty()
+            && session_id.len() <= MAX_SESSION_ID_LEN
+    }
+
+    /// Give `session_id` to `client_id`, resuming it if an earlier
+    /// connection with the same identity left it within the TTL
+    pub(crate) fn attach(&self, session_id: &str, client_id: ClientId, identity: Option<&str>) -> Option<ResumedSession> {
+        let mut sessions = self.sessions.lock();
+        self.expire(&mut sessions, Instant::now());
+        sessions.attached.insert(session_id.to_string(), client_id);
+
+        let session = sessions.detached.remove(session_id)?;
+        if session.identity.as_deref() != identity {
+            // Not this client's to resume; it ends here
+            sessions.released.extend(session.held);
+            return None;
+        }
+        self.resumed.fetch_add(1, Ordering::Relaxed);
+        Some(ResumedSession {
+            subscriptions: session.subscriptions,
+            missed: session.missed.into(),
+            dropped: session.dropped,
+            held: session.held,
+        })
+    }
+
+    /// Keep `client_id`'s session for resuming, starting with the messages
+    /// it never got sent
+    ///
+    /// Returns the markets to release now when the session isn't kept
+    /// (another connection took it over).
+    pub(crate) fn detach(
+        &self,
+        session_id: &str,
+        client_id: ClientId,
+        mut session: DetachedSession,
+        undelivered: Vec<ServerMessage>,
+    ) -> Vec<SubscriptionKey> {
+        let mut sessions = self.sessions.lock();
+        if sessions.attached.get(session_id) != Some(&client_id) {
+            return session.held;
+        }
+        sessions.attached.remove(session_id);
+
+        let now = Instant::now();
+        self.expire(&mut sessions, now);
+        while sessions.detached.len() >= self.config.max_sessions {
+            let Some(oldest) = sessions
+                .detached
+                .iter()
+                .min_by_key(|(_, session)| session.detached_at)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = sessions.detached.remove(&oldest) {
+                sessions.released.extend(evicted.held);
+                self.expired.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        session.detached_at = now;
+        for message in undelivered {
+            session.buffer(message, self.config.max_buffered);
+        }
+        sessions.detached.insert(session_id.to_string(), session);
+        Vec::new()
+    }
+
+    /// Buffer a broadcast for every detached session that would have got it
+    pub fn record(&self, broadcast: &BroadcastMessage) {
+        let mut sessions = self.sessions.lock();
+        for session in sessions.detached.values_mut() {
+            if session.wants(&broadcast.key, &broadcast.message) {
+                session.buffer(broadcast.message.clone(), self.config.max_buffered);
+            }
+        }
+    }
+
+    /// Expire sessions past their TTL, returning the markets that ended
+    /// sessions held
+    pub(crate) fn sweep(&self) -> Vec<SubscriptionKey> {
+        let mut sessions = self.sessions.lock();
+        self.expire(&mut sessions, Instant::now());
+        std::mem::take(&mut sessions.released)
+    }
+
+    pub fn stats(&self) -> SessionStats {
+        let sessions = self.sessions.lock();
+        SessionStats {
+            detached_sessions: sessions.detached.len(),
+            buffered_messages: sessions.detached.values().map(|s| s.missed.len()).sum(),
+            resumed: self.resumed.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+        }
+    }
+
+    fn expire(&self, sessions: &mut Sessions, now: Instant) {
+        let ttl = self.config.ttl;
+        let expired: Vec<String> = sessions
+            .detached
+            .iter()
+            .filter(|(_, session)| now.duration_since(session.detached_at) >= ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            if let Some(session) = sessions.detached.remove(&id) {
+                sessions.released.extend(session.held);
+                self.expired.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use terminal_core::Platform;
+
+    fn trades(market_id: &str) -> SubscriptionType {
+        SubscriptionType::Trades {
+            platform: Platform::Kalshi,
+            market_id: market_id.to_string(),
+        }
+    }
+
+    fn pong(n: i64) -> BroadcastMessage {
+        BroadcastMessage {
+            key: SubscriptionKey::from(&trades("m1")),
+            message: ServerMessage::Pong {
+                client_timestamp: n,
+                server_timestamp: Utc::now().timestamp_millis(),
+            },
+        }
+    }
+
+    fn detached(held: Vec<SubscriptionKey>) -> DetachedSession {
+        let subscriptions = vec![SessionSubscription {
+            subscription: trades("m1"),
+            compress: false,
+        }];
+        DetachedSession::new(None, true, subscriptions, held)
+    }
+
+    #[test]
+    fn test_missed_messages_bounded_and_replayed_once() {
+        let store = SessionStore::new(SessionConfig {
+            max_buffered: 2,
+            ..SessionConfig::default()
+        });
+        let (first, second) = (ClientId(1), ClientId(2));
+        assert!(store.attach("phone", first, None).is_none());
+        let held = vec![SubscriptionKey::from(&trades("m1"))];
+        assert!(store.detach("phone", first, detached(held.clone()), vec![pong(0).message]).is_empty());
+
+        // Only the session's own subscriptions are kept
+        store.record(&pong(1));
+        store.record(&BroadcastMessage {
+            key: SubscriptionKey::from(&trades("m2")),
+            ..pong(9)
+        });
+        store.record(&pong(2));
+        assert_eq!(store.stats().buffered_messages, 2);
+
+        let resumed = store.attach("phone", second, None).unwrap();
+        let replayed: Vec<i64> = resumed
+            .missed
+            .iter()
+            .map(|message| match message {
+                ServerMessage::Pong { client_timestamp, .. } => *client_timestamp,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(replayed, vec![1, 2]);
+        assert_eq!(resumed.dropped, 1);
+        assert_eq!(resumed.held, held);
+        assert_eq!(store.stats().resumed, 1);
+
+        // A stale connection closing doesn't take the session back
+        assert_eq!(store.detach("phone", first, detached(held.clone()), vec![]), held);
+        assert_eq!(store.stats().detached_sessions, 0);
+    }
+
+    #[test]
+    fn test_expired_or_foreign_sessions_start_fresh() {
+        let store = SessionStore::new(SessionConfig {
+            ttl: Duration::from_millis(20),
+            ..SessionConfig::default()
+        });
+        let held = vec![SubscriptionKey::from(&trades("m1"))];
+
+        store.attach("phone", ClientId(1), None);
+        store.detach("phone", ClientId(1), detached(held.clone()), vec![]);
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(store.sweep(), held);
+        assert!(store.attach("phone", ClientId(2), None).is_none());
+        assert_eq!(store.stats().expired, 1);
+
+        // Someone else's session id doesn't hand over its messages
+        store.detach("phone", ClientId(2), detached(held.clone()), vec![pong(1).message]);
+        assert!(store.attach("phone", ClientId(3), Some("desk")).is_none());
+        assert_eq!(store.sweep(), held);
+
+        assert!(!store.accepts(""));
+        assert!(!store.accepts(&"x".repeat(MAX_SESSION_ID_LEN + 1)));
+    }
+}
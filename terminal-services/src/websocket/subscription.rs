@@ -3,13 +3,19 @@
 //! Manages client subscriptions and broadcasts updates to interested clients.
 
 use dashmap::DashMap;
+use serde::Serialize;
 use std::collections::HashSet;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use terminal_core::{ServerMessage, SubscriptionKey, SubscriptionType};
+use rust_decimal::Decimal;
+use terminal_core::{
+    ErrorCode, Platform, ServerMessage, SubscriptionChannel, SubscriptionKey, SubscriptionType, Trade,
+};
 use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
+use super::session::SessionSubscription;
+
 /// Unique identifier for a WebSocket client connection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ClientId(pub u64);
@@ -20,6 +26,62 @@ impl std::fmt::Display for ClientId {
     }
 }
 
+/// Default subscriptions one connection may hold
+pub const DEFAULT_MAX_SUBSCRIPTIONS_PER_CLIENT: usize = 200;
+
+/// Default subscriptions all connections together may hold
+pub const DEFAULT_MAX_SUBSCRIPTIONS_TOTAL: usize = 20_000;
+
+/// Caps on client subscriptions; `None` for no cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionLimits {
+    pub per_client: Option<usize>,
+    /// Across all connections
+    pub total: Option<usize>,
+}
+
+impl Default for SubscriptionLimits {
+    fn default() -> Self {
+        Self {
+            per_client: Some(DEFAULT_MAX_SUBSCRIPTIONS_PER_CLIENT),
+            total: Some(DEFAULT_MAX_SUBSCRIPTIONS_TOTAL),
+        }
+    }
+}
+
+/// A subscription refused for hitting a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionLimitError {
+    PerClient { limit: usize, count: usize },
+    Total { limit: usize, count: usize },
+}
+
+impl SubscriptionLimitError {
+    /// The error sent back to the client
+    pub fn to_message(self) -> ServerMessage {
+        let (code, message, limit, count) = match self {
+            Self::PerClient { limit, count } => (
+                ErrorCode::SubscriptionLimitExceeded,
+                format!("Connection subscription limit of {} reached ({} active)", limit, count),
+                limit,
+                count,
+            ),
+            Self::Total { limit, count } => (
+                ErrorCode::GlobalSubscriptionLimitExceeded,
+                format!("Server subscription limit of {} reached ({} active)", limit, count),
+                limit,
+                count,
+            ),
+        };
+        ServerMessage::Error {
+            code,
+            message,
+            limit: Some(limit),
+            count: Some(count),
+        }
+    }
+}
+
 /// Manages subscriptions and message broadcasting
 pub struct SubscriptionManager {
     /// Next client ID to assign
@@ -28,11 +90,32 @@ pub struct SubscriptionManager {
     subscriptions: DashMap<SubscriptionKey, HashSet<ClientId>>,
     /// Map of client ID -> set of subscription keys
     client_subscriptions: DashMap<ClientId, HashSet<SubscriptionKey>>,
+    /// Open connections, subscribed or not, with the label of the token
+    /// they authenticated with
+    connected: DashMap<ClientId, Option<String>>,
+    /// Client -> subscriptions it wants snapshot-class messages compressed for
+    compressed: DashMap<ClientId, HashSet<SubscriptionKey>>,
+    /// Client -> smallest notional it wants on the global trade tape
+    tape_filters: DashMap<ClientId, Decimal>,
+    /// Connections closed for missing heartbeat pongs
+    cleaned_up: AtomicU64,
+    limits: SubscriptionLimits,
+    /// Client subscriptions summed over all clients
+    client_subscription_count: AtomicUsize,
     /// Broadcast channel for sending messages to all clients
     /// Each client receives messages and filters based on their subscriptions
     broadcast_tx: broadcast::Sender<BroadcastMessage>,
 }
 
+/// Client connection counts, as reported in the health payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ClientStats {
+    pub connected_clients: usize,
+    /// Connections closed for missing heartbeat pongs or falling too far
+    /// behind
+    pub cleaned_up_clients: u64,
+}
+
 /// A message with its target subscription info
 #[derive(Debug, Clone)]
 pub struct BroadcastMessage {
@@ -43,13 +126,24 @@ pub struct BroadcastMessage {
 }
 
 impl SubscriptionManager {
-    /// Create a new subscription manager
+    /// Create a new subscription manager with the default limits
     pub fn new() -> Self {
+        Self::with_limits(SubscriptionLimits::default())
+    }
+
+    /// Create a subscription manager enforcing `limits`
+    pub fn with_limits(limits: SubscriptionLimits) -> Self {
         let (broadcast_tx, _) = broadcast::channel(1024);
         Self {
             next_client_id: AtomicU64::new(1),
             subscriptions: DashMap::new(),
             client_subscriptions: DashMap::new(),
+            connected: DashMap::new(),
+            compressed: DashMap::new(),
+            tape_filters: DashMap::new(),
+            cleaned_up: AtomicU64::new(0),
+            limits,
+            client_subscription_count: AtomicUsize::new(0),
             broadcast_tx,
         }
     }
@@ -59,6 +153,77 @@ impl SubscriptionManager {
         ClientId(self.next_client_id.fetch_add(1, Ordering::SeqCst))
     }
 
+    /// Register a new connection, returning its ID
+    pub fn connect_client(&self) -> ClientId {
+        self.connect_client_as(None)
+    }
+
+    /// Register a connection that authenticated with the token `identity`
+    /// labels
+    pub fn connect_client_as(&self, identity: Option<String>) -> ClientId {
+        let client_id = self.new_client_id();
+        self.connected.insert(client_id, identity);
+        client_id
+    }
+
+    /// Label of the token a connection authenticated with
+    pub fn client_identity(&self, client_id: ClientId) -> Option<String> {
+        self.connected.get(&client_id).and_then(|identity| identity.clone())
+    }
+
+    /// Record whether `client_id` wants `key`'s snapshot-class messages
+    /// compressed
+    pub fn set_compressed(&self, client_id: ClientId, key: &SubscriptionKey, compress: bool) {
+        if compress {
+            self.compressed.entry(client_id).or_default().insert(key.clone());
+        } else if let Some(mut keys) = self.compressed.get_mut(&client_id) {
+            keys.remove(key);
+        }
+    }
+
+    /// Whether `message` goes to `client_id` compressed: a market snapshot
+    /// for a market it subscribed to with compression, or a full book for
+    /// such an order book subscription
+    pub fn wants_compressed(&self, client_id: ClientId, message: &ServerMessage) -> bool {
+        let (platform, market_id, books_only) = match message {
+            ServerMessage::Snapshot { platform, market_id, .. } => (*platform, market_id, false),
+            ServerMessage::OrderBookUpdate {
+                platform,
+                market_id,
+                update_type: terminal_core::OrderBookUpdateType::Snapshot,
+                ..
+            } => (*platform, market_id, true),
+            _ => return false,
+        };
+        self.compressed.get(&client_id).is_some_and(|keys| {
+            keys.iter().any(|key| {
+                key.platform == platform
+                    && key.market_id == *market_id
+                    && (!books_only || key.channel == SubscriptionChannel::OrderBook)
+            })
+        })
+    }
+
+    /// Set the smallest notional (price times size) of the tape trades
+    /// `client_id` gets; `None` lets every trade through
+    pub fn set_tape_filter(&self, client_id: ClientId, min_notional: Option<Decimal>) {
+        match min_notional {
+            Some(min_notional) => {
+                self.tape_filters.insert(client_id, min_notional);
+            }
+            None => {
+                self.tape_filters.remove(&client_id);
+            }
+        }
+    }
+
+    /// Whether a tape trade clears `client_id`'s notional filter
+    pub fn passes_tape_filter(&self, client_id: ClientId, trade: &Trade) -> bool {
+        self.tape_filters
+            .get(&client_id)
+            .is_none_or(|min_notional| crate::ticker_tape::notional(trade) >= *min_notional)
+    }
+
     /// Subscribe to a broadcast channel
     pub fn subscribe_broadcast(&self) -> broadcast::Receiver<BroadcastMessage> {
         self.broadcast_tx.subscribe()
@@ -75,10 +240,14 @@ impl SubscriptionManager {
             .insert(client_id);
 
         // Add to client -> subscriptions map
-        self.client_subscriptions
+        let added = self
+            .client_subscriptions
             .entry(client_id)
             .or_default()
             .insert(key.clone());
+        if added {
+            self.client_subscription_count.fetch_add(1, Ordering::Relaxed);
+        }
 
         debug!(
             "Client {} subscribed to {:?}",
@@ -101,7 +270,13 @@ impl SubscriptionManager {
 
         // Remove from client -> subscriptions map
         if let Some(mut subs) = self.client_subscriptions.get_mut(&client_id) {
-            subs.remove(&key);
+            if subs.remove(&key) {
+                self.client_subscription_count.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+        self.set_compressed(client_id, &key, false);
+        if key.channel == SubscriptionChannel::GlobalTrades {
+            self.set_tape_filter(client_id, None);
         }
 
         debug!(
@@ -110,10 +285,35 @@ impl SubscriptionManager {
         );
     }
 
+    /// `client_id`'s subscriptions as it made them, to restore elsewhere
+    pub fn subscription_set(&self, client_id: ClientId) -> Vec<SessionSubscription> {
+        let Some(keys) = self.client_subscriptions.get(&client_id) else {
+            return Vec::new();
+        };
+        keys.iter()
+            .filter_map(|key| {
+                let mut subscription = key.subscription()?;
+                if let SubscriptionType::GlobalTrades { min_notional } = &mut subscription {
+                    *min_notional = self.tape_filters.get(&client_id).map(|min| *min);
+                }
+                let compress = self.compressed.get(&client_id).is_some_and(|compressed| compressed.contains(key));
+                Some(SessionSubscription { subscription, compress })
+            })
+            .collect()
+    }
+
     /// Remove all subscriptions for a client (on disconnect)
-    pub fn remove_client(&self, client_id: ClientId) {
+    ///
+    /// Returns the keys this client was the last subscriber to.
+    pub fn remove_client(&self, client_id: ClientId) -> Vec<SubscriptionKey> {
+        let mut emptied = Vec::new();
+        self.connected.remove(&client_id);
+        self.compressed.remove(&client_id);
+        self.tape_filters.remove(&client_id);
+
         // Get all subscriptions for this client
         if let Some((_, subscriptions)) = self.client_subscriptions.remove(&client_id) {
+            self.client_subscription_count.fetch_sub(subscriptions.len(), Ordering::Relaxed);
             // Remove client from each subscription
             for key in subscriptions {
                 if let Some(mut clients) = self.subscriptions.get_mut(&key) {
@@ -121,12 +321,45 @@ impl SubscriptionManager {
                     if clients.is_empty() {
                         drop(clients);
                         self.subscriptions.remove(&key);
+                        emptied.push(key);
                     }
                 }
             }
         }
 
         info!("Client {} disconnected, removed all subscriptions", client_id);
+        emptied
+    }
+
+    /// Check `key` fits within the limits before subscribing `client_id`
+    ///
+    /// Repeating a subscription the client already holds takes no quota.
+    pub fn check_limits(&self, client_id: ClientId, key: &SubscriptionKey) -> Result<(), SubscriptionLimitError> {
+        if self.is_subscribed(client_id, key) {
+            return Ok(());
+        }
+        let count = self.client_subscriptions.get(&client_id).map_or(0, |subs| subs.len());
+        if let Some(limit) = self.limits.per_client.filter(|limit| count >= *limit) {
+            return Err(SubscriptionLimitError::PerClient { limit, count });
+        }
+        let count = self.client_subscription_count.load(Ordering::Relaxed);
+        if let Some(limit) = self.limits.total.filter(|limit| count >= *limit) {
+            return Err(SubscriptionLimitError::Total { limit, count });
+        }
+        Ok(())
+    }
+
+    /// Count a connection closed for missing heartbeat pongs
+    pub fn record_cleaned_up(&self) {
+        self.cleaned_up.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Connected and cleaned-up client counts
+    pub fn client_stats(&self) -> ClientStats {
+        ClientStats {
+            connected_clients: self.connected.len(),
+            cleaned_up_clients: self.cleaned_up.load(Ordering::Relaxed),
+        }
     }
 
     /// Check if a client is subscribed to a specific subscription
@@ -203,6 +436,23 @@ impl SubscriptionManager {
         }
     }
 
+    /// Broadcast a message to every client trusted with private updates:
+    /// those that authenticated, or all of them when auth is off
+    pub fn broadcast_to_authenticated(&self, message: ServerMessage) {
+        let broadcast_msg = BroadcastMessage {
+            key: SubscriptionKey {
+                platform: terminal_core::Platform::Kalshi, // placeholder
+                market_id: "__authenticated__".to_string(),
+                channel: terminal_core::SubscriptionChannel::Price, // placeholder
+            },
+            message,
+        };
+
+        if let Err(e) = self.broadcast_tx.send(broadcast_msg) {
+            warn!("Failed to broadcast private message: {} (no receivers)", e);
+        }
+    }
+
     /// Get all subscriptions for a specific platform/market
     pub fn get_market_subscriptions(
         &self,
@@ -218,6 +468,15 @@ impl SubscriptionManager {
             .collect()
     }
 
+    /// Markets with at least one subscriber on a channel
+    pub fn subscribed_markets(&self, channel: SubscriptionChannel) -> Vec<(Platform, String)> {
+        self.subscriptions
+            .iter()
+            .filter(|entry| entry.key().channel == channel && !entry.value().is_empty())
+            .map(|entry| (entry.key().platform, entry.key().market_id.clone()))
+            .collect()
+    }
+
     /// Check if there are any active subscriptions
     pub fn has_subscriptions(&self) -> bool {
         !self.subscriptions.is_empty()
@@ -254,3 +513,57 @@ impl std::fmt::Debug for SubscriptionManager {
 pub fn create_subscription_manager() -> Arc<SubscriptionManager> {
     Arc::new(SubscriptionManager::new())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trades(market_id: &str) -> SubscriptionType {
+        SubscriptionType::Trades {
+            platform: Platform::Kalshi,
+            market_id: market_id.to_string(),
+        }
+    }
+
+    /// Subscribe if the limits allow it
+    fn try_subscribe(manager: &SubscriptionManager, client_id: ClientId, market_id: &str) -> Result<(), SubscriptionLimitError> {
+        let subscription = trades(market_id);
+        manager.check_limits(client_id, &SubscriptionKey::from(&subscription))?;
+        manager.subscribe(client_id, &subscription);
+        Ok(())
+    }
+
+    #[test]
+    fn test_per_client_and_total_limits() {
+        let manager = SubscriptionManager::with_limits(SubscriptionLimits {
+            per_client: Some(2),
+            total: Some(3),
+        });
+        let (a, b) = (manager.connect_client(), manager.connect_client());
+
+        assert!(try_subscribe(&manager, a, "m1").is_ok());
+        assert!(try_subscribe(&manager, a, "m2").is_ok());
+        assert_eq!(try_subscribe(&manager, a, "m3"), Err(SubscriptionLimitError::PerClient { limit: 2, count: 2 }));
+        // Already held: no quota needed
+        assert!(try_subscribe(&manager, a, "m1").is_ok());
+
+        assert!(try_subscribe(&manager, b, "m1").is_ok());
+        assert_eq!(try_subscribe(&manager, b, "m2"), Err(SubscriptionLimitError::Total { limit: 3, count: 3 }));
+
+        // Unsubscribing and disconnecting free quota
+        manager.unsubscribe(a, &trades("m2"));
+        assert!(try_subscribe(&manager, a, "m3").is_ok());
+        manager.remove_client(a);
+        assert!(try_subscribe(&manager, b, "m2").is_ok());
+        assert!(try_subscribe(&manager, b, "m4").is_err());
+
+        let refused = SubscriptionLimitError::PerClient { limit: 2, count: 2 };
+        match refused.to_message() {
+            ServerMessage::Error { code, limit, count, .. } => {
+                assert_eq!(code, ErrorCode::SubscriptionLimitExceeded);
+                assert_eq!((limit, count), (Some(2), Some(2)));
+            }
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+}